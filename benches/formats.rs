@@ -0,0 +1,51 @@
+//! 内置格式类型的校验与每次调用都 `Regex::new` 编译模式的对比，
+//! 说明预编译的静态正则省下的开销：
+//!
+//! ```sh
+//! cargo bench --bench formats
+//! ```
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use regex::Regex;
+use std::hint::black_box;
+use zz_validator::{
+    ast::{FieldType, Value},
+    validator::{builtin_patterns, validate_type},
+};
+
+/// (类型, 对应的内置正则名称, 合法的样例值)
+const FORMATS: &[(&str, &str, &str)] = &[
+    ("email", "EMAIL_RE", "alice@example.com"),
+    ("uuid", "UUID_RE", "550e8400-e29b-41d4-a716-446655440000"),
+    ("ip", "IP_V4_RE", "192.168.10.254"),
+    ("mac", "MAC_RE", "ac:de:48:00:11:22"),
+    ("date", "DATE_RE", "2024-02-29"),
+    ("semver", "SEMVER_RE", "1.42.0-rc.1+build.7"),
+];
+
+fn pattern(name: &str) -> &'static str {
+    builtin_patterns()
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, p)| *p)
+        .expect("builtin pattern")
+}
+
+fn bench_formats(c: &mut Criterion) {
+    for &(keyword, name, sample) in FORMATS {
+        let t = FieldType::from_keyword(keyword).expect("builtin type");
+        let value = Value::String(sample.into());
+        let pattern = pattern(name);
+        let mut group = c.benchmark_group(keyword);
+        group.bench_function("validate_type", |b| {
+            b.iter(|| validate_type(black_box(&value), &t).unwrap())
+        });
+        group.bench_function("regex_new_per_call", |b| {
+            b.iter(|| assert!(Regex::new(pattern).unwrap().is_match(black_box(sample))))
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_formats);
+criterion_main!(benches);
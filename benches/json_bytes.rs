@@ -0,0 +1,67 @@
+//! `validate_json_bytes` 与先解析为 `Value` 再校验的对比：
+//!
+//! ```sh
+//! cargo bench --bench json_bytes                       # serde_json 流式校验
+//! cargo bench --bench json_bytes --features simd-json  # simd-json tape
+//! ```
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+use zz_validator::{json::from_json_str, schema::Schema};
+
+const SCHEMA: &str = r#"(
+    id:uuid, name:string[1,32], email:email, age:int[0,150]=18,
+    role:string enum("admin","user","guest")="user",
+    profile:object(first:string[1,50], last:string[1,50], site?:uri),
+    tags:array<string[1,16]>,
+    orders:array<object(id:int, amount:float[0,100000], currency:string[3,3])>
+)"#;
+
+fn document(orders: usize) -> String {
+    let orders: Vec<String> = (0..orders)
+        .map(|i| {
+            format!(
+                r#"{{"id": {}, "amount": {}.5, "currency": "EUR"}}"#,
+                i,
+                i * 10
+            )
+        })
+        .collect();
+    format!(
+        r#"{{
+            "id": "550e8400-e29b-41d4-a716-446655440000", "name": "alice",
+            "email": "alice@example.com", "role": "admin",
+            "profile": {{"first": "Alice", "last": "Liddell", "site": "https://example.com"}},
+            "tags": ["rust", "json", "validation"],
+            "trace": {{"span": "0af7651916cd43dd8448eb211c80319c", "sampled": true}},
+            "orders": [{}]
+        }}"#,
+        orders.join(", ")
+    )
+}
+
+fn bench_json_bytes(c: &mut Criterion) {
+    let schema = Schema::parse(SCHEMA).unwrap();
+    for orders in [1, 100] {
+        let doc = document(orders);
+        let mut group = c.benchmark_group(format!("orders_{}", orders));
+        group.throughput(Throughput::Bytes(doc.len() as u64));
+        group.bench_function("parse_then_validate", |b| {
+            b.iter(|| {
+                let mut value = from_json_str(black_box(&doc)).unwrap();
+                schema.validate(&mut value).unwrap();
+            })
+        });
+        group.bench_function("validate_json_bytes", |b| {
+            b.iter(|| {
+                schema
+                    .validate_json_bytes(black_box(doc.as_bytes()))
+                    .unwrap()
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_json_bytes);
+criterion_main!(benches);
@@ -0,0 +1,62 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{LitStr, parse_macro_input};
+use zz_validator::schema::Schema;
+
+/// -----------------------------
+/// schema!
+/// -----------------------------
+/// 编译期解析 DSL 并做与 [`Schema::parse`] 相同的合理性检查，语法错误与自相矛盾的规则
+/// （如 `int[10,1]`、无法编译的正则、不在 enum 中的默认值）直接导致编译失败，返回 `&'static Schema`。
+/// 运行时第一次使用时构造 `Schema`，之后复用同一份
+///
+/// ```
+/// use zz_validator_macros::schema;
+///
+/// let user = schema!("(name:string[1,20], age:int[0,150]=18)");
+/// assert_eq!(user.rules().len(), 2);
+/// ```
+///
+/// ```compile_fail
+/// use zz_validator_macros::schema;
+///
+/// let user = schema!("(name:string[1,20, age:int)");
+/// ```
+///
+/// ```compile_fail
+/// use zz_validator_macros::schema;
+///
+/// let range = schema!("(a:int[10,1])");
+/// ```
+///
+/// ```compile_fail
+/// use zz_validator_macros::schema;
+///
+/// let pattern = schema!(r#"(a:string regex("("))"#);
+/// ```
+///
+/// ```compile_fail
+/// use zz_validator_macros::schema;
+///
+/// let status = schema!(r#"(status:string enum("open", "closed") = "void")"#);
+/// ```
+#[proc_macro]
+pub fn schema(input: TokenStream) -> TokenStream {
+    let dsl = parse_macro_input!(input as LitStr);
+    if let Err(e) = Schema::parse(&dsl.value()) {
+        return syn::Error::new(dsl.span(), format!("invalid schema: {}", e))
+            .to_compile_error()
+            .into();
+    }
+    quote! {
+        {
+            static SCHEMA: ::std::sync::LazyLock<::zz_validator::schema::Schema> =
+                ::std::sync::LazyLock::new(|| {
+                    ::zz_validator::schema::Schema::parse(#dsl)
+                        .expect("schema! checked the DSL at compile time")
+                });
+            &*SCHEMA
+        }
+    }
+    .into()
+}
@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod schema_macro_tests {
+    use zz_validator::{json::from_json_str, schema::Schema};
+    use zz_validator_macros::schema;
+
+    fn user() -> &'static Schema {
+        schema!(
+            r#"meta(name="User", version=1)
+            (
+                name:string[1,20] regex("^[a-z]+$"),
+                age:int[0,150]=18
+            )"#
+        )
+    }
+
+    #[test]
+    fn test_embedded_schema() {
+        let schema = user();
+        assert_eq!(schema.metadata().name(), Some("User"));
+
+        let mut value = from_json_str(r#"{"name": "alice"}"#).unwrap();
+        assert!(schema.validate(&mut value).is_ok());
+        assert_eq!(
+            value.as_object().unwrap().get("age"),
+            Some(&zz_validator::ast::Value::Int(18))
+        );
+        let mut bad = from_json_str(r#"{"name": "Alice"}"#).unwrap();
+        assert!(schema.validate(&mut bad).is_err());
+    }
+
+    #[test]
+    fn test_constructed_once() {
+        assert!(std::ptr::eq(user(), user()));
+        // 每个调用处各有一份
+        let other = schema!("(a:int)");
+        assert!(!std::ptr::eq(user(), other));
+    }
+}
@@ -1,9 +1,13 @@
-use std::collections::HashMap;
+pub use crate::map::ObjectMap;
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::Arc;
 
 /// -----------------------------
 /// AST
 /// -----------------------------
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
 pub enum FieldType {
     String,
     Int,
@@ -43,9 +47,223 @@ pub enum FieldType {
     FilePath,
     Alpha,
     Alphanumeric,
+    Ulid,
+    Ksuid,
+    NanoId(usize),
+    /// 带参数的 uuid：`uuid(v4)`、`uuid(v7, compact)`
+    UuidOf(UuidSpec),
+    /// 带参数的 color：`color(hex, alpha)`、`color(rgb, hsl)`
+    ColorOf(ColorSpec),
+    /// 带选项的 hostname：`hostname(allow_single_label, allow_trailing_dot)`
+    HostnameOf(HostnameSpec),
+    /// `host:port`，host 为主机名、IPv4 或 `[IPv6]`
+    SocketAddr,
+    /// 与 socketaddr 相同，但 host 必须是 IP 地址
+    IpPort,
+    /// 金额对象 `{amount, currency}`：amount 为十进制数，currency 为 ISO 4217 代码；
+    /// 区间约束作用于 amount，`coerce` 时接受 `"12.50 USD"`
+    Money,
+}
+
+/// `uuid(...)` 的参数：限定版本号（同时要求 RFC 4122 变体位），以及是否接受 32 位无连字符形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
+pub struct UuidSpec {
+    pub version: Option<u8>,
+    pub compact: bool,
+}
+
+/// `hostname(...)` 的选项：允许 `localhost` 这样的单标签名，允许末尾的根域点
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
+pub struct HostnameSpec {
+    pub allow_single_label: bool,
+    pub allow_trailing_dot: bool,
+}
+
+impl fmt::Display for HostnameSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags = [
+            (self.allow_single_label, "allow_single_label"),
+            (self.allow_trailing_dot, "allow_trailing_dot"),
+        ];
+        let args: Vec<&str> = flags
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, n)| *n)
+            .collect();
+        write!(f, "hostname({})", args.join(", "))
+    }
+}
+
+/// `color(...)` 接受的写法；未列出 hex / rgb / hsl 时默认只接受十六进制，
+/// `alpha` 允许 4/8 位十六进制与带透明度的函数写法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorSpec {
+    pub hex: bool,
+    pub rgb: bool,
+    pub hsl: bool,
+    pub alpha: bool,
+}
+
+impl fmt::Display for ColorSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags = [
+            (self.hex, "hex"),
+            (self.rgb, "rgb"),
+            (self.hsl, "hsl"),
+            (self.alpha, "alpha"),
+        ];
+        let args: Vec<&str> = flags
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, n)| *n)
+            .collect();
+        write!(f, "color({})", args.join(", "))
+    }
+}
+
+impl fmt::Display for UuidSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut args = Vec::new();
+        if let Some(v) = self.version {
+            args.push(format!("v{}", v));
+        }
+        if self.compact {
+            args.push("compact".to_string());
+        }
+        write!(f, "uuid({})", args.join(", "))
+    }
+}
+
+/// `nanoid` 不带长度时的默认长度
+pub const NANOID_DEFAULT_LEN: usize = 21;
+
+impl FieldType {
+    /// 值以字符串表示的类型；`port` 同时接受整数与字符串
+    pub fn is_textual(&self) -> bool {
+        !matches!(
+            self,
+            Self::Int
+                | Self::Float
+                | Self::Bool
+                | Self::Timestamp
+                | Self::Object
+                | Self::Array
+                | Self::Money
+        )
+    }
+
+    /// DSL 关键字对应的类型（`regex(...)` 需要参数，不在此列；`nanoid` 取默认长度）
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        let t = match keyword {
+            "string" => Self::String,
+            "int" => Self::Int,
+            "float" => Self::Float,
+            "bool" => Self::Bool,
+            "object" => Self::Object,
+            "array" => Self::Array,
+            "email" => Self::Email,
+            "uri" => Self::Uri,
+            "uuid" => Self::Uuid,
+            "ip" => Self::Ip,
+            "mac" => Self::Mac,
+            "date" => Self::Date,
+            "datetime" => Self::DateTime,
+            "time" => Self::Time,
+            "timestamp" => Self::Timestamp,
+            "color" => Self::Color,
+            "hostname" => Self::Hostname,
+            "slug" => Self::Slug,
+            "hex" => Self::Hex,
+            "base64" => Self::Base64,
+            "password" => Self::Password,
+            "token" => Self::Token,
+            "phone" => Self::Phone,
+            "creditcard" => Self::CreditCard,
+            "isbn" => Self::ISBN,
+            "port" => Self::Port,
+            "json" => Self::Json,
+            "urlencoded" => Self::UrlEncoded,
+            "lat" => Self::Lat,
+            "lng" => Self::Lng,
+            "semver" => Self::SemVer,
+            "username" => Self::Username,
+            "countrycode" => Self::CountryCode,
+            "postalcode" => Self::PostalCode,
+            "filepath" => Self::FilePath,
+            "alpha" => Self::Alpha,
+            "alphanumeric" => Self::Alphanumeric,
+            "ulid" => Self::Ulid,
+            "ksuid" => Self::Ksuid,
+            "nanoid" => Self::NanoId(NANOID_DEFAULT_LEN),
+            "socketaddr" => Self::SocketAddr,
+            "ip_port" => Self::IpPort,
+            "money" => Self::Money,
+            _ => return None,
+        };
+        Some(t)
+    }
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::String => "string",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::Bool => "bool",
+            Self::Object => "object",
+            Self::Array => "array",
+            Self::Email => "email",
+            Self::Uri => "uri",
+            Self::Uuid => "uuid",
+            Self::Ip => "ip",
+            Self::Mac => "mac",
+            Self::Date => "date",
+            Self::DateTime => "datetime",
+            Self::Custom(pattern) => return write!(f, "regex({})", quote(pattern)),
+            Self::Time => "time",
+            Self::Timestamp => "timestamp",
+            Self::Color => "color",
+            Self::Hostname => "hostname",
+            Self::Slug => "slug",
+            Self::Hex => "hex",
+            Self::Base64 => "base64",
+            Self::Password => "password",
+            Self::Token => "token",
+            Self::Phone => "phone",
+            Self::CreditCard => "creditcard",
+            Self::ISBN => "isbn",
+            Self::Port => "port",
+            Self::Json => "json",
+            Self::UrlEncoded => "urlencoded",
+            Self::Lat => "lat",
+            Self::Lng => "lng",
+            Self::SemVer => "semver",
+            Self::Username => "username",
+            Self::CountryCode => "countrycode",
+            Self::PostalCode => "postalcode",
+            Self::FilePath => "filepath",
+            Self::Alpha => "alpha",
+            Self::Alphanumeric => "alphanumeric",
+            Self::Ulid => "ulid",
+            Self::Ksuid => "ksuid",
+            Self::SocketAddr => "socketaddr",
+            Self::IpPort => "ip_port",
+            Self::Money => "money",
+            Self::NanoId(len) => return write!(f, "nanoid({})", len),
+            Self::UuidOf(spec) => return write!(f, "{}", spec),
+            Self::ColorOf(spec) => return write!(f, "{}", spec),
+            Self::HostnameOf(spec) => return write!(f, "{}", spec),
+        };
+        f.write_str(name)
+    }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
 pub enum Constraint {
     Range {
         min: Value,
@@ -54,9 +272,494 @@ pub enum Constraint {
         max_inclusive: bool,
     },
     Regex(String),
+    /// 按名称调用注册表中的自定义校验器：`check("name")`
+    Check(String),
+    /// 内嵌脚本表达式，需启用 `script` feature：`script("value.len() % 2 == 0")`
+    Script(String),
+    /// date / datetime / timestamp 早于校验时刻
+    Past,
+    /// date / datetime / timestamp 晚于校验时刻
+    Future,
+    /// 与校验时刻相差不超过给定秒数：`within(30d)`
+    Within(i64),
+    /// 按校验时刻计算的周岁不小于给定值：`min_age(18)`
+    MinAge(u32),
+    /// 校验和：`luhn`、`checksum(mod97)`、`checksum(crc32="cbf43926")`
+    Checksum {
+        algorithm: String,
+        expected: Option<String>,
+    },
+    /// 签名校验：用 `key` 取得的密钥对同级字段 `over` 的规范化 JSON 重新计算签名，
+    /// 与本字段的值（十六进制）比较：`signed_with(hmac_sha256, key="ctx:webhook_secret", over="$.payload")`。
+    /// `key` 为 `ctx:name`（`ValidationContext::var`）或 `env:NAME`，`over` 中的 `$` 为本字段所在的对象
+    SignedWith {
+        algorithm: String,
+        key: String,
+        over: String,
+    },
+    /// 数组元素（数字、日期或字符串）有序：`sorted(asc)`、`sorted(desc)`、`strictly_increasing`、
+    /// `strictly_decreasing`，非日期字符串按 `collation` 比较：`sorted(asc, nocase)`
+    Sorted {
+        descending: bool,
+        strict: bool,
+        collation: Collation,
+    },
+    /// 数值数组的聚合约束：`sum<=100`、`avg[0,5]`、`count_where(>0)>=1`
+    ArrayAggregate {
+        aggregate: Aggregate,
+        bound: AggregateBound,
+    },
+    /// 对象数组中某个子字段（可用 `.` 分隔的路径）的值互不相同：`unique_by(id)`
+    UniqueBy(String),
+    /// 对象数组中每个元素内部的字段比较：`each(start < end)`
+    Each(FieldCompare),
+    /// 对象数组中相邻元素之间的字段比较：`adjacent(prev.end <= next.start)`
+    Adjacent(FieldCompare),
+    /// 值必须出现在同一文档中某条路径下：`ref(users[].id)`
+    Ref(String),
+    /// 值必须能被外部 `RefResolver` 按类别找到：`exists("user")`
+    Exists(String),
+    /// mac 地址的厂商前缀（前 3 个字节）必须在列表中：`oui("AC:DE:48")`
+    Oui(Vec<String>),
+    /// 数值必须是给定值的整数倍：`multiple_of(0.01)`，float 按 `float_epsilon` 容差判断
+    MultipleOf(Value),
+    /// money 的币种必须在列表中：`money[0,10000](currency=USD|EUR)`
+    Currency(Vec<String>),
+    /// 字段必须由调用方提供，默认值不算：`present`
+    Present,
+    /// 字段不得提供，例如创建时的 `id?:int absent`：`absent`
+    Absent,
+    /// 字段已废弃，出现时产生警告：`deprecated`、`deprecated("use handle")`
+    Deprecated(Option<String>),
+    /// 面向用户的字段名称，不参与校验，错误消息模板用 `{label}` 引用：`label("Email address")`
+    Label(String),
+    /// 字段的值是密文：用注册的同名 `Decryptor` 解密后按本字段的规则校验明文，
+    /// 载荷中仍保留密文：`card: object(number: creditcard, cvv: string[3, 4]) encrypted(kms)`
+    Encrypted(String),
+    /// 校验通过后把字段值的哈希写入 `ValidationReport::hashes`，不暴露原值：`hash_into_report(sha256)`
+    HashIntoReport(String),
+    /// 校验前改写字段的值：`slugify`
+    Transform(Transform),
+    /// 内层约束失败时只产生警告，不影响校验结论：`warn([0,160])`
+    Warn(Box<Constraint>),
+    /// 校验时才确定的默认值：`=env(DEFAULT_REGION)`、`=ctx(tenant_currency)`、`=now()`
+    DefaultFrom(DefaultExpr),
+}
+
+/// 默认值表达式，在每次校验时求值；取不到值时按字段缺失处理
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
+pub enum DefaultExpr {
+    /// 环境变量，字符串按字段类型转换（同 `coerce`）：`env(DEFAULT_REGION)`
+    Env(String),
+    /// `ValidationContext::var` 设置的值：`ctx(tenant_currency)`
+    Ctx(String),
+    /// 校验时钟的当前时间，按字段类型输出为 timestamp / datetime / date：`now()`
+    Now,
+    /// 当天零点（UTC）：`today()`
+    Today,
+    /// `ValidationContext::ids` 生成的 uuid：`uuid()`
+    Uuid,
+}
+
+impl DefaultExpr {
+    /// 可以出现在 `=` 之后的表达式名称
+    pub(crate) fn is_keyword(name: &str) -> bool {
+        matches!(name, "env" | "ctx" | "now" | "today" | "uuid")
+    }
+
+    /// 生成的值能否用于该类型的字段
+    pub fn applies_to(&self, t: &FieldType) -> bool {
+        match self {
+            Self::Env(_) | Self::Ctx(_) => true,
+            Self::Now | Self::Today => matches!(
+                t,
+                FieldType::Date | FieldType::DateTime | FieldType::Timestamp
+            ),
+            Self::Uuid => matches!(t, FieldType::Uuid | FieldType::String),
+        }
+    }
+}
+
+/// -----------------------------
+/// Transform
+/// -----------------------------
+/// 校验前就地改写字符串值的转换，改写记录为 `ChangeKind::Transform`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
+pub enum Transform {
+    /// 用 `ValidationContext::transliterator` 音译为 ASCII、转小写，
+    /// 其余字符连续出现时替换为一个 `-`：`"Crème Brûlée!"` -> `"creme-brulee"`
+    Slugify,
+    /// 按地区（大写的 ISO 3166 代码，默认 `US`）把号码改写为 E.164：
+    /// `"(415) 555-0123"` -> `"+14155550123"`，见 [`crate::phone::normalize_phone`]；
+    /// 无法改写或有多种解释时校验失败
+    NormalizePhone { region: String },
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Slugify => write!(f, "slugify"),
+            Self::NormalizePhone { region } => write!(f, "normalize_phone(region=\"{}\")", region),
+        }
+    }
+}
+
+impl fmt::Display for DefaultExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Env(name) => write!(f, "env({})", name),
+            Self::Ctx(name) => write!(f, "ctx({})", name),
+            Self::Now => write!(f, "now()"),
+            Self::Today => write!(f, "today()"),
+            Self::Uuid => write!(f, "uuid()"),
+        }
+    }
+}
+
+/// 约束失败的后果：`Error` 使校验失败，`Warning` 记入报告的 `warnings`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// 比较运算符
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
+pub enum CompareOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl CompareOp {
+    pub fn apply(self, left: f64, right: f64) -> bool {
+        match self {
+            Self::Lt => left < right,
+            Self::Le => left <= right,
+            Self::Gt => left > right,
+            Self::Ge => left >= right,
+            Self::Eq => left == right,
+            Self::Ne => left != right,
+        }
+    }
+
+    /// 与 `apply` 相同，但两者相差不超过 `epsilon` 时视为相等
+    pub fn apply_within(self, left: f64, right: f64, epsilon: f64) -> bool {
+        if (left - right).abs() <= epsilon {
+            self.holds(std::cmp::Ordering::Equal)
+        } else {
+            self.apply(left, right)
+        }
+    }
+
+    /// 按比较结果判断是否满足运算符
+    pub fn holds(self, ord: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering::*;
+        match self {
+            Self::Lt => ord == Less,
+            Self::Le => ord != Greater,
+            Self::Gt => ord == Greater,
+            Self::Ge => ord != Less,
+            Self::Eq => ord == Equal,
+            Self::Ne => ord != Equal,
+        }
+    }
+}
+
+impl fmt::Display for CompareOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+        })
+    }
+}
+
+/// 元素字段比较的操作数：字段路径（`start`、`prev.end`）或字面量
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
+pub enum Operand {
+    Field(String),
+    Literal(Value),
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Field(path) => f.write_str(path),
+            Self::Literal(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+/// `each(...)` / `adjacent(...)` 中的比较表达式，可在末尾指定字符串的比较方式：
+/// `each(first < last, nocase)`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldCompare {
+    pub left: Operand,
+    pub op: CompareOp,
+    pub right: Operand,
+    pub collation: Collation,
+}
+
+impl fmt::Display for FieldCompare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.left, self.op, self.right)?;
+        if self.collation != Collation::Binary {
+            write!(f, ", {}", self.collation)?;
+        }
+        Ok(())
+    }
+}
+
+/// 非日期字符串的比较方式
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
+pub enum Collation {
+    /// 按字节（即码点）比较，默认
+    #[default]
+    Binary,
+    /// 转为小写后按码点比较：`nocase`
+    CaseInsensitive,
+    /// Unicode 排序规则（需要 `icu` feature）：`unicode`、`unicode("sv")`
+    Unicode(Option<String>),
+}
+
+impl fmt::Display for Collation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Binary => f.write_str("binary"),
+            Self::CaseInsensitive => f.write_str("nocase"),
+            Self::Unicode(None) => f.write_str("unicode"),
+            Self::Unicode(Some(locale)) => write!(f, "unicode({})", quote(locale)),
+        }
+    }
+}
+
+/// 数组聚合函数
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
+pub enum Aggregate {
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Count,
+    /// 满足条件的元素个数
+    CountWhere(CompareOp, Value),
+}
+
+impl fmt::Display for Aggregate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sum => write!(f, "sum"),
+            Self::Avg => write!(f, "avg"),
+            Self::Min => write!(f, "min"),
+            Self::Max => write!(f, "max"),
+            Self::Count => write!(f, "count"),
+            Self::CountWhere(op, v) => write!(f, "count_where({}{})", op, v),
+        }
+    }
+}
+
+/// 聚合结果需满足的条件：比较或区间
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
+pub enum AggregateBound {
+    Compare(CompareOp, Value),
+    Range {
+        min: Value,
+        max: Value,
+        min_inclusive: bool,
+        max_inclusive: bool,
+    },
+}
+
+impl fmt::Display for AggregateBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Compare(op, v) => write!(f, "{}{}", op, v),
+            Self::Range {
+                min,
+                max,
+                min_inclusive,
+                max_inclusive,
+            } => write!(
+                f,
+                "{}{}, {}{}",
+                if *min_inclusive { '[' } else { '(' },
+                min,
+                max,
+                if *max_inclusive { ']' } else { ')' }
+            ),
+        }
+    }
+}
+
+impl Constraint {
+    /// 约束能否用于该类型的字段：区间只用于数值、日期、字符串长度与数组长度，
+    /// 正则只用于字符串类的值，时间约束只用于日期时间，数组约束只用于数组
+    pub fn applies_to(&self, t: &FieldType) -> bool {
+        match self {
+            Self::Range { .. } => matches!(
+                t,
+                FieldType::Int
+                    | FieldType::Float
+                    | FieldType::Timestamp
+                    | FieldType::Date
+                    | FieldType::DateTime
+                    | FieldType::String
+                    | FieldType::Array
+                    | FieldType::Money
+            ),
+            Self::Regex(_) => t.is_textual(),
+            Self::Past | Self::Future | Self::Within(_) | Self::MinAge(_) => matches!(
+                t,
+                FieldType::Date | FieldType::DateTime | FieldType::Timestamp
+            ),
+            Self::Checksum { .. } => t.is_textual() || *t == FieldType::Int,
+            Self::SignedWith { .. } => t.is_textual(),
+            Self::Sorted { .. }
+            | Self::ArrayAggregate { .. }
+            | Self::UniqueBy(_)
+            | Self::Each(_)
+            | Self::Adjacent(_) => *t == FieldType::Array,
+            Self::Oui(_) => *t == FieldType::Mac,
+            Self::MultipleOf(_) => matches!(t, FieldType::Int | FieldType::Float),
+            Self::Currency(_) => *t == FieldType::Money,
+            Self::Check(_)
+            | Self::Script(_)
+            | Self::Ref(_)
+            | Self::Exists(_)
+            | Self::Present
+            | Self::Absent
+            | Self::Deprecated(_)
+            | Self::Label(_)
+            | Self::Encrypted(_)
+            | Self::HashIntoReport(_) => true,
+            Self::Transform(_) => t.is_textual(),
+            Self::DefaultFrom(expr) => expr.applies_to(t),
+            Self::Warn(inner) => inner.applies_to(t),
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::Deprecated(_) | Self::Warn(_) => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Range {
+                min,
+                max,
+                min_inclusive,
+                max_inclusive,
+            } => write!(
+                f,
+                "{}{}, {}{}",
+                if *min_inclusive { '[' } else { '(' },
+                min,
+                max,
+                if *max_inclusive { ']' } else { ')' }
+            ),
+            Self::Regex(pattern) => write!(f, "regex({})", quote(pattern)),
+            Self::Check(name) => write!(f, "check({})", quote(name)),
+            Self::Script(source) => write!(f, "script({})", quote(source)),
+            Self::Past => write!(f, "past"),
+            Self::Future => write!(f, "future"),
+            Self::Present => write!(f, "present"),
+            Self::Absent => write!(f, "absent"),
+            Self::Within(seconds) => {
+                // 以能整除的最大单位输出
+                let (amount, unit) = [(7 * 86400, "w"), (86400, "d"), (3600, "h"), (60, "m")]
+                    .into_iter()
+                    .find(|(scale, _)| *seconds != 0 && seconds % scale == 0)
+                    .map(|(scale, unit)| (seconds / scale, unit))
+                    .unwrap_or((*seconds, "s"));
+                write!(f, "within({}{})", amount, unit)
+            }
+            Self::MinAge(years) => write!(f, "min_age({})", years),
+            Self::Checksum {
+                algorithm,
+                expected: None,
+            } if algorithm == "luhn" => write!(f, "luhn"),
+            Self::Checksum {
+                algorithm,
+                expected,
+            } => match expected {
+                Some(expected) => write!(f, "checksum({}={})", algorithm, quote(expected)),
+                None => write!(f, "checksum({})", algorithm),
+            },
+            Self::SignedWith {
+                algorithm,
+                key,
+                over,
+            } => write!(
+                f,
+                "signed_with({}, key={}, over={})",
+                algorithm,
+                quote(key),
+                quote(over)
+            ),
+            Self::Sorted {
+                descending,
+                strict,
+                collation,
+            } => {
+                let (direction, trend) = if *descending {
+                    ("desc", "decreasing")
+                } else {
+                    ("asc", "increasing")
+                };
+                match (strict, collation) {
+                    (false, Collation::Binary) => write!(f, "sorted({})", direction),
+                    (false, c) => write!(f, "sorted({}, {})", direction, c),
+                    (true, Collation::Binary) => write!(f, "strictly_{}", trend),
+                    (true, c) => write!(f, "strictly_{}({})", trend, c),
+                }
+            }
+            Self::ArrayAggregate { aggregate, bound } => write!(f, "{}{}", aggregate, bound),
+            Self::UniqueBy(path) => write!(f, "unique_by({})", ident_or_quote(path)),
+            Self::Each(cmp) => write!(f, "each({})", cmp),
+            Self::Adjacent(cmp) => write!(f, "adjacent({})", cmp),
+            Self::Ref(path) => write!(f, "ref({})", path),
+            Self::Exists(kind) => write!(f, "exists({})", quote(kind)),
+            Self::Oui(prefixes) => {
+                let quoted: Vec<String> = prefixes.iter().map(|p| quote(p)).collect();
+                write!(f, "oui({})", quoted.join(", "))
+            }
+            Self::MultipleOf(divisor) => write!(f, "multiple_of({})", divisor),
+            Self::Currency(codes) => write!(f, "(currency={})", codes.join("|")),
+            Self::DefaultFrom(expr) => write!(f, "= {}", expr),
+            Self::Deprecated(None) => write!(f, "deprecated"),
+            Self::Deprecated(Some(message)) => write!(f, "deprecated({})", quote(message)),
+            Self::Label(text) => write!(f, "label({})", quote(text)),
+            Self::Encrypted(name) => write!(f, "encrypted({})", ident_or_quote(name)),
+            Self::HashIntoReport(algorithm) => write!(f, "hash_into_report({})", algorithm),
+            Self::Transform(transform) => write!(f, "{}", transform),
+            Self::Warn(inner) => write!(f, "warn({})", inner),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
 pub struct Constraints {
     pub items: Vec<Constraint>,
 }
@@ -64,17 +767,237 @@ pub struct Constraints {
 /// -----------------------------
 /// Value
 /// -----------------------------
-#[derive(Debug, Clone, PartialEq)]
+/// 实现了 `Eq`、`Hash` 与 `Ord`，可作为 `HashSet` / `BTreeMap` 的键或直接排序。
+/// 浮点数按以下约定比较：NaN 与自身相等且大于所有数字，`0.0` 与 `-0.0` 相等；
+/// `Int(1)` 与 `Float(1.0)` 仍视为不同的值，排序时 int 在前。
+///
+/// 不同种类之间的顺序为 bool < 数字 < string < array < object；
+/// int 与 float 之间按数值精确比较，对象按排序后的键值对逐项比较
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
 pub enum Value {
     String(String),
     Int(i64),
+    /// 超出 i64 范围的整数（`bigint` feature），用 [`Value::integer`] 构造，
+    /// 不会保存 i64 范围内的值
+    #[cfg(feature = "bigint")]
+    BigInt(num_bigint::BigInt),
     Float(f64),
     Bool(bool),
-    Object(HashMap<String, Value>),
+    Object(ObjectMap),
     Array(Vec<Value>),
 }
 
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Int(a), Value::Int(b)) => a == b,
+            #[cfg(feature = "bigint")]
+            (Value::BigInt(a), Value::BigInt(b)) => a == b,
+            (Value::Float(a), Value::Float(b)) => a == b || (a.is_nan() && b.is_nan()),
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Object(a), Value::Object(b)) => a == b,
+            (Value::Array(a), Value::Array(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Value {}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            (Value::Array(a), Value::Array(b)) => a.cmp(b),
+            (Value::Object(a), Value::Object(b)) => a.cmp(b),
+            _ if self.is_number() && other.is_number() => {
+                let nan = |v: &Value| matches!(v, Value::Float(f) if f.is_nan());
+                compare_numbers(self, other)
+                    .unwrap_or_else(|| nan(self).cmp(&nan(other)))
+                    // 数值相等的 int 与 float 不相等，int 在前
+                    .then_with(|| self.number_rank().cmp(&other.number_rank()))
+            }
+            _ => self.kind_rank().cmp(&other.kind_rank()),
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// 数字之间按数值比较：int、bigint 与 float 之间精确比较，不经 f64 转换丢失精度；
+/// 含 NaN 或不是数字时为 None
+pub fn compare_numbers(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Int(x), Value::Int(y)) => Some(x.cmp(y)),
+        (Value::Float(x), Value::Float(y)) => x.partial_cmp(y),
+        (Value::Int(i), Value::Float(f)) => compare_int_float(*i, *f),
+        (Value::Float(f), Value::Int(i)) => compare_int_float(*i, *f).map(Ordering::reverse),
+        #[cfg(feature = "bigint")]
+        (Value::BigInt(_), _) | (_, Value::BigInt(_)) => compare_big(a, b),
+        _ => None,
+    }
+}
+
+/// 至少一方为 bigint：整数直接比较，与 float 比较时先比整数部分，再看小数部分
+#[cfg(feature = "bigint")]
+fn compare_big(a: &Value, b: &Value) -> Option<Ordering> {
+    use num_bigint::BigInt;
+    use num_traits::FromPrimitive;
+
+    let integer = |v: &Value| match v {
+        Value::Int(i) => Some(BigInt::from(*i)),
+        Value::BigInt(b) => Some(b.clone()),
+        _ => None,
+    };
+    match (integer(a), integer(b)) {
+        (Some(x), Some(y)) => Some(x.cmp(&y)),
+        (Some(x), None) => {
+            let f = b.as_float()?;
+            if f.is_nan() {
+                return None;
+            }
+            if f.is_infinite() {
+                return Some(if f > 0.0 {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                });
+            }
+            let whole = f.trunc();
+            Some(
+                x.cmp(&BigInt::from_f64(whole)?)
+                    .then(whole.partial_cmp(&f)?),
+            )
+        }
+        (None, Some(_)) => compare_big(b, a).map(Ordering::reverse),
+        (None, None) => None,
+    }
+}
+
+fn compare_int_float(i: i64, f: f64) -> Option<Ordering> {
+    // ±2^63 已超出 i64 的范围
+    const LIMIT: f64 = 9_223_372_036_854_775_808.0;
+    if f.is_nan() {
+        return None;
+    }
+    if f >= LIMIT {
+        return Some(Ordering::Less);
+    }
+    if f < -LIMIT {
+        return Some(Ordering::Greater);
+    }
+    let whole = f.trunc();
+    Some(
+        (i as i128)
+            .cmp(&(whole as i128))
+            .then(whole.partial_cmp(&f)?),
+    )
+}
+
+impl std::hash::Hash for Value {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Value::String(s) => s.hash(state),
+            Value::Int(i) => i.hash(state),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(b) => b.hash(state),
+            // 与 eq 一致：-0.0 归为 0.0，所有 NaN 归为同一个位模式
+            Value::Float(f) if *f == 0.0 => 0u64.hash(state),
+            Value::Float(f) if f.is_nan() => f64::NAN.to_bits().hash(state),
+            Value::Float(f) => f.to_bits().hash(state),
+            Value::Bool(b) => b.hash(state),
+            Value::Object(map) => map.hash(state),
+            Value::Array(items) => items.hash(state),
+        }
+    }
+}
+
 impl Value {
+    /// 不同种类之间的排序位置
+    fn kind_rank(&self) -> u8 {
+        match self {
+            Value::Bool(_) => 0,
+            Value::String(_) => 2,
+            Value::Array(_) => 3,
+            Value::Object(_) => 4,
+            _ => 1,
+        }
+    }
+
+    /// 数值相等的不同数字类型之间的顺序：int < bigint < float
+    fn number_rank(&self) -> u8 {
+        match self {
+            Value::Int(_) => 0,
+            Value::Float(_) => 2,
+            _ => 1,
+        }
+    }
+
+    /// 值的种类：`string`、`int`、`bigint`、`float`、`bool`、`object`、`array`
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::String(_) => "string",
+            Value::Int(_) => "int",
+            #[cfg(feature = "bigint")]
+            Value::BigInt(_) => "bigint",
+            Value::Float(_) => "float",
+            Value::Bool(_) => "bool",
+            Value::Object(_) => "object",
+            Value::Array(_) => "array",
+        }
+    }
+
+    /// int 或 bigint
+    pub fn is_integer(&self) -> bool {
+        match self {
+            Value::Int(_) => true,
+            #[cfg(feature = "bigint")]
+            Value::BigInt(_) => true,
+            _ => false,
+        }
+    }
+
+    /// int、bigint 或 float
+    pub fn is_number(&self) -> bool {
+        match self {
+            Value::Int(_) | Value::Float(_) => true,
+            #[cfg(feature = "bigint")]
+            Value::BigInt(_) => true,
+            _ => false,
+        }
+    }
+
+    /// 解析十进制整数字面量：i64 范围内为 `Int`，开启 `bigint` 时超出范围的为 `BigInt`
+    pub fn integer(literal: &str) -> Option<Value> {
+        if let Ok(i) = literal.parse::<i64>() {
+            return Some(Value::Int(i));
+        }
+        #[cfg(feature = "bigint")]
+        if let Ok(b) = literal.parse::<num_bigint::BigInt>() {
+            return Some(Value::BigInt(b));
+        }
+        None
+    }
+
+    /// 开启 `bigint` 时超出 i64 的 u64 保存为 `BigInt`，否则为 `Float`
+    pub fn from_u64(n: u64) -> Value {
+        match i64::try_from(n) {
+            Ok(i) => Value::Int(i),
+            #[cfg(feature = "bigint")]
+            Err(_) => Value::BigInt(n.into()),
+            #[cfg(not(feature = "bigint"))]
+            Err(_) => Value::Float(n as f64),
+        }
+    }
+
     pub fn as_str(&self) -> Option<&str> {
         if let Value::String(s) = self {
             Some(s)
@@ -103,14 +1026,14 @@ impl Value {
             None
         }
     }
-    pub fn as_object(&self) -> Option<&HashMap<String, Value>> {
+    pub fn as_object(&self) -> Option<&ObjectMap> {
         if let Value::Object(m) = self {
             Some(m)
         } else {
             None
         }
     }
-    pub fn as_object_mut(&mut self) -> Option<&mut HashMap<String, Value>> {
+    pub fn as_object_mut(&mut self) -> Option<&mut ObjectMap> {
         if let Value::Object(m) = self {
             Some(m)
         } else {
@@ -133,9 +1056,187 @@ impl Value {
     }
 }
 
+/// 以 DSL 字面量形式输出，对象按键排序
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String(s) => f.write_str(&quote(s)),
+            Self::Int(i) => write!(f, "{}", i),
+            #[cfg(feature = "bigint")]
+            Self::BigInt(b) => write!(f, "{}", b),
+            Self::Float(n) => write!(f, "{:?}", n),
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                f.write_str("{")?;
+                for (i, k) in keys.into_iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", quote(k), map[k])?;
+                }
+                f.write_str("}")
+            }
+            Self::Array(items) => {
+                f.write_str("[")?;
+                for (i, v) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", v)?;
+                }
+                f.write_str("]")
+            }
+        }
+    }
+}
+
+/// 合法标识符原样输出，否则加引号
+fn ident_or_quote(s: &str) -> String {
+    if !s.is_empty()
+        && !s.starts_with(|c: char| c.is_ascii_digit())
+        && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+    {
+        s.to_string()
+    } else {
+        quote(s)
+    }
+}
+
+/// 输出带引号的字符串，转义规则与 tokenizer 对应
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// -----------------------------
+/// FieldName
+/// -----------------------------
+/// 规则的字段名。内部为 `Arc<str>`：克隆规则（具名类型的每处引用、`pick` / `omit` 派生的 schema）
+/// 只增加引用计数，`Schema` 编译时再把同名字段驻留为同一份（见 `compile::intern_names`）。
+/// 可以像 `&str` 一样使用，并能直接与 `&str` / `String` 比较
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "artifact",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct FieldName(Arc<str>);
+
+impl FieldName {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// 与 `other` 共用同一份字符串
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl std::ops::Deref for FieldName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for FieldName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::borrow::Borrow<str> for FieldName {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for FieldName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for FieldName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&str> for FieldName {
+    fn from(name: &str) -> Self {
+        Self(name.into())
+    }
+}
+
+impl From<String> for FieldName {
+    fn from(name: String) -> Self {
+        Self(name.into())
+    }
+}
+
+impl From<Arc<str>> for FieldName {
+    fn from(name: Arc<str>) -> Self {
+        Self(name)
+    }
+}
+
+impl PartialEq<str> for FieldName {
+    fn eq(&self, other: &str) -> bool {
+        *self.0 == *other
+    }
+}
+
+impl PartialEq<&str> for FieldName {
+    fn eq(&self, other: &&str) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl PartialEq<String> for FieldName {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl PartialEq<FieldName> for str {
+    fn eq(&self, other: &FieldName) -> bool {
+        *self == *other.0
+    }
+}
+
+impl PartialEq<FieldName> for &str {
+    fn eq(&self, other: &FieldName) -> bool {
+        **self == *other.0
+    }
+}
+
+impl PartialEq<FieldName> for String {
+    fn eq(&self, other: &FieldName) -> bool {
+        **self == *other.0
+    }
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
 pub struct FieldRule {
-    pub field: String,
+    pub field: FieldName,
     pub field_type: FieldType,
     pub required: bool,
     pub default: Option<Value>,
@@ -146,3 +1247,245 @@ pub struct FieldRule {
     pub children: Option<Vec<FieldRule>>,
     pub is_array: bool,
 }
+
+/// -----------------------------
+/// Metadata
+/// -----------------------------
+/// DSL 开头 `meta(name="CreateUser", version=3, owner="payments")` 声明的 schema 标识信息，
+/// 保持声明顺序，导出时原样带上。键应为标识符，值为字符串、数字或 bool
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
+pub struct Metadata {
+    entries: Vec<(String, Value)>,
+}
+
+impl Metadata {
+    /// 随 schema 声明的 float 容差：`meta(float_epsilon=1e-9)`。
+    /// 通过 `Schema` 校验时取代 `ValidationOptions::float_epsilon`，使用同一 schema 的调用方比较结果一致
+    pub const FLOAT_EPSILON: &'static str = "float_epsilon";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 返回被替换的旧值，替换时保持原来的位置
+    pub fn insert(&mut self, key: impl Into<String>, value: Value) -> Option<Value> {
+        let key = key.into();
+        match self.entries.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, old)) => Some(std::mem::replace(old, value)),
+            None => {
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// `name` 项（字符串）
+    pub fn name(&self) -> Option<&str> {
+        self.get("name").and_then(Value::as_str)
+    }
+
+    /// `version` 项
+    pub fn version(&self) -> Option<&Value> {
+        self.get("version")
+    }
+
+    /// `float_epsilon` 项：该 schema 比较 float 时使用的绝对容差，见 [`Metadata::FLOAT_EPSILON`]
+    pub fn float_epsilon(&self) -> Option<f64> {
+        match self.get(Self::FLOAT_EPSILON)? {
+            Value::Int(i) => Some(*i as f64),
+            Value::Float(f) => Some(*f),
+            _ => None,
+        }
+        .filter(|e| e.is_finite() && *e >= 0.0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl fmt::Display for Metadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("meta(")?;
+        for (i, (k, v)) in self.entries.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}={}", k, v)?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// -----------------------------
+/// Projection
+/// -----------------------------
+/// 从一组规则派生出只含部分字段的规则，对应 DSL 末尾的 `pick(username, email)` /
+/// `omit(password)`，字段保持原来的声明顺序
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Projection {
+    /// 只保留列出的字段
+    Pick(Vec<String>),
+    /// 去掉列出的字段
+    Omit(Vec<String>),
+}
+
+impl Projection {
+    /// 列出的字段必须都已声明
+    pub fn apply(&self, rules: &[FieldRule]) -> Result<Vec<FieldRule>, String> {
+        let (Self::Pick(fields) | Self::Omit(fields)) = self;
+        if let Some(unknown) = fields
+            .iter()
+            .find(|f| !rules.iter().any(|r| *r.field == **f))
+        {
+            return Err(format!("'{}' is not declared in the schema", unknown));
+        }
+        let keep = matches!(self, Self::Pick(_));
+        Ok(rules
+            .iter()
+            .filter(|r| fields.iter().any(|f| *f == *r.field) == keep)
+            .cloned()
+            .collect())
+    }
+}
+
+impl fmt::Display for Projection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, fields) = match self {
+            Self::Pick(fields) => ("pick", fields),
+            Self::Omit(fields) => ("omit", fields),
+        };
+        write!(f, "{}({})", name, fields.join(", "))
+    }
+}
+
+/// -----------------------------
+/// SequenceRule
+/// -----------------------------
+/// 相邻两次提交之间的约束，由 [`crate::sequence::SequenceValidator`] 执行，
+/// 如 `monotonic(version)`、`delta(balance) <= 1000`。路径以 `.` 分隔
+#[derive(Debug, Clone, PartialEq)]
+pub enum SequenceRule {
+    /// 不得小于上一次的值；`strict` 时必须严格递增（`strictly_monotonic(...)`）
+    Monotonic { path: String, strict: bool },
+    /// 本次减上一次的差值满足比较；`absolute` 时取差值的绝对值（`abs_delta(...)`）
+    Delta {
+        path: String,
+        absolute: bool,
+        op: CompareOp,
+        bound: Value,
+    },
+}
+
+impl SequenceRule {
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Monotonic { path, .. } | Self::Delta { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for SequenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Monotonic { path, strict } => {
+                let name = if *strict {
+                    "strictly_monotonic"
+                } else {
+                    "monotonic"
+                };
+                write!(f, "{}({})", name, path)
+            }
+            Self::Delta {
+                path,
+                absolute,
+                op,
+                bound,
+            } => {
+                let name = if *absolute { "abs_delta" } else { "delta" };
+                write!(f, "{}({}) {} {}", name, path, op, bound)
+            }
+        }
+    }
+}
+
+impl FieldRule {
+    pub(crate) fn has_constraint(&self, pred: impl Fn(&Constraint) -> bool) -> bool {
+        self.constraints
+            .as_ref()
+            .is_some_and(|c| c.items.iter().any(pred))
+    }
+
+    /// 带有 `present` 约束：无论是否可选都必须出现在输入中
+    pub fn must_be_present(&self) -> bool {
+        self.has_constraint(|c| matches!(c, Constraint::Present))
+    }
+
+    /// 带有 `absent` 约束：出现在输入中即校验失败
+    pub fn must_be_absent(&self) -> bool {
+        self.has_constraint(|c| matches!(c, Constraint::Absent))
+    }
+
+    /// `encrypted(name)` 标记的解密器名称
+    pub fn encryption(&self) -> Option<&str> {
+        self.constraints
+            .as_ref()?
+            .items
+            .iter()
+            .find_map(|c| match c {
+                Constraint::Encrypted(name) => Some(name.as_str()),
+                _ => None,
+            })
+    }
+
+    /// `=env(...)` / `=ctx(...)` 等校验时求值的默认值
+    pub fn default_expr(&self) -> Option<&DefaultExpr> {
+        self.constraints
+            .as_ref()?
+            .items
+            .iter()
+            .find_map(|c| match c {
+                Constraint::DefaultFrom(expr) => Some(expr),
+                _ => None,
+            })
+    }
+
+    /// 声明了默认值（固定值或表达式），缺失时不一定报错
+    pub fn has_default(&self) -> bool {
+        self.default.is_some() || self.default_expr().is_some()
+    }
+}
+
+/// 按点号路径查找规则，例如 `profile.contact.email`。
+/// 数组字段会自动进入其元素规则，`tags[]` 与 `tags` 等价。
+pub fn find_rule<'a>(rules: &'a [FieldRule], path: &str) -> Option<&'a FieldRule> {
+    let mut current: Option<&FieldRule> = None;
+    for segment in path.split('.') {
+        let name = segment.split('[').next().unwrap_or(segment);
+        let candidates = match current {
+            None => rules,
+            Some(rule) => {
+                let mut parent = rule;
+                while let Some(sub) = &parent.rule {
+                    parent = sub;
+                }
+                parent.children.as_deref()?
+            }
+        };
+        current = Some(candidates.iter().find(|r| *r.field == *name)?);
+    }
+    current
+}
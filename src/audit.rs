@@ -0,0 +1,235 @@
+use std::{
+    fmt,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    sync::Mutex,
+};
+
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    ast::{FieldRule, Value},
+    export::to_dsl,
+    json::to_json_string,
+    validator::ValidationError,
+};
+
+/// -----------------------------
+/// AuditSink
+/// -----------------------------
+/// 接收每次校验的结论，作为"输入已经过检查"的留存证据。
+/// 通过 `ValidationContext::audit` 设置；写入失败只记录 `tracing::error!`，不影响校验结果
+pub trait AuditSink: Send + Sync {
+    fn record(&self, record: &AuditRecord) -> io::Result<()>;
+}
+
+/// 一次校验的结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditOutcome {
+    Passed,
+    Failed,
+}
+
+impl fmt::Display for AuditOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Passed => write!(f, "passed"),
+            Self::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// 一条审计记录。哈希均为 SHA-256 的小写十六进制
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditRecord {
+    /// 规则的指纹，见 [`fingerprint`]
+    pub schema_fingerprint: String,
+    /// 校验前（填充默认值、coerce 之前）载荷的哈希，见 [`payload_hash`]
+    pub payload_hash: String,
+    pub outcome: AuditOutcome,
+    /// 失败时的错误；`exhaustive` 模式下同一字段的多个失败逐条展开
+    pub errors: Vec<ValidationError>,
+    /// 校验时 `ValidationContext::clock` 的 Unix 秒
+    pub timestamp: i64,
+}
+
+impl AuditRecord {
+    /// 单行 JSON，错误以文本保存
+    pub fn to_json(&self) -> serde_json::Value {
+        let errors: Vec<String> = self.errors.iter().map(|e| e.to_string()).collect();
+        json!({
+            "timestamp": self.timestamp,
+            "schema": self.schema_fingerprint,
+            "payload": self.payload_hash,
+            "outcome": self.outcome.to_string(),
+            "errors": errors,
+        })
+    }
+}
+
+/// 规则的指纹：规则重新输出为 DSL 后的哈希。写法不同（空白、数字格式）但规则相同时指纹相同
+pub fn fingerprint(rules: &[FieldRule]) -> String {
+    sha256_hex(to_dsl(rules).as_bytes())
+}
+
+/// 载荷的哈希：按键排序输出的 JSON 的哈希，与对象键的顺序无关
+pub fn payload_hash(value: &Value) -> String {
+    sha256_hex(to_json_string(value).as_bytes())
+}
+
+/// 与转换为 `Value` 后的 [`payload_hash`] 相同，但直接输出 `serde_json::Value` 交给哈希，
+/// 不复制文档：对象中值为 null 的键略去，其余位置的 null 按空字符串输出
+#[cfg(feature = "json-value")]
+pub(crate) fn json_payload_hash(json: &serde_json::Value) -> String {
+    let mut hasher = Sha256::new();
+    // 写入哈希不会失败
+    let _ = serde_json::to_writer(&mut hasher, &AsValue(json));
+    hex(&hasher.finalize())
+}
+
+/// 按 `Value` 的语义输出的 `serde_json::Value`，对象键按字典序排列
+#[cfg(feature = "json-value")]
+struct AsValue<'a>(&'a serde_json::Value);
+
+#[cfg(feature = "json-value")]
+impl serde::Serialize for AsValue<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{SerializeMap, SerializeSeq};
+        match self.0 {
+            serde_json::Value::Null => serializer.serialize_str(""),
+            serde_json::Value::Number(n) => {
+                let value = Value::from(serde_json::Value::Number(n.clone()));
+                serde_json::Value::from(&value).serialize(serializer)
+            }
+            serde_json::Value::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(&AsValue(item))?;
+                }
+                seq.end()
+            }
+            serde_json::Value::Object(map) => {
+                let mut entries: Vec<_> = map.iter().filter(|(_, v)| !v.is_null()).collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                let mut out = serializer.serialize_map(Some(entries.len()))?;
+                for (k, v) in entries {
+                    out.serialize_entry(k, &AsValue(v))?;
+                }
+                out.end()
+            }
+            json => json.serialize(serializer),
+        }
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex(&Sha256::digest(bytes))
+}
+
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 生成记录并交给 sink
+pub(crate) fn record(
+    sink: &dyn AuditSink,
+    rules: &[FieldRule],
+    payload_hash: String,
+    result: Result<(), &ValidationError>,
+    timestamp: i64,
+) {
+    let errors = match result {
+        Ok(()) => Vec::new(),
+        Err(ValidationError::FieldErrors { errors, .. }) => errors.clone(),
+        Err(e) => vec![e.clone()],
+    };
+    let record = AuditRecord {
+        schema_fingerprint: fingerprint(rules),
+        payload_hash,
+        outcome: if errors.is_empty() {
+            AuditOutcome::Passed
+        } else {
+            AuditOutcome::Failed
+        },
+        errors,
+        timestamp,
+    };
+    if let Err(e) = sink.record(&record) {
+        tracing::error!("Failed to write audit record: {}", e);
+    }
+}
+
+/// -----------------------------
+/// MemoryAuditSink
+/// -----------------------------
+/// 保存在内存中，适合测试或由调用方定期取出转存
+#[derive(Debug, Default)]
+pub struct MemoryAuditSink {
+    records: Mutex<Vec<AuditRecord>>,
+}
+
+impl MemoryAuditSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 当前所有记录的副本
+    pub fn records(&self) -> Vec<AuditRecord> {
+        self.records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// 取出并清空
+    pub fn take(&self) -> Vec<AuditRecord> {
+        std::mem::take(&mut *self.records.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+impl AuditSink for MemoryAuditSink {
+    fn record(&self, record: &AuditRecord) -> io::Result<()> {
+        self.records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(record.clone());
+        Ok(())
+    }
+}
+
+/// -----------------------------
+/// FileAuditSink
+/// -----------------------------
+/// 以追加方式写入文件，每条记录一行 JSON（见 [`AuditRecord::to_json`]），写入后立即 flush
+#[derive(Debug)]
+pub struct FileAuditSink {
+    file: Mutex<File>,
+}
+
+impl FileAuditSink {
+    /// 打开（不存在时创建）文件，保留已有内容
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl AuditSink for FileAuditSink {
+    fn record(&self, record: &AuditRecord) -> io::Result<()> {
+        let mut line = record.to_json().to_string();
+        line.push('\n');
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        file.write_all(line.as_bytes())?;
+        file.flush()
+    }
+}
+
+impl<T: AuditSink + ?Sized> AuditSink for std::sync::Arc<T> {
+    fn record(&self, record: &AuditRecord) -> io::Result<()> {
+        (**self).record(record)
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+use std::io::{BufRead, IsTerminal, Write};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use zz_validator::{
+    diagnostics,
+    explain::explain,
+    generator::Generator,
+    json::to_json_string,
+    parser::Parser,
+    schema::Schema,
+    validator::{Budget, DEFAULT_MAX_DEPTH, ValidationContext, ValidationOptions},
+};
+
+const USAGE: &str = "Usage:
+  validator explain --schema <file> <field.path>
+  validator generate --schema <file> [--count <n>] [--invalid-rate <0..1>] [--seed <n>]
+  validator repl --schema <file> [--strict <true|false>] [--coerce <true|false>]
+                 [--canonicalize <true|false>] [--exhaustive <true|false>] [--float-epsilon <f>]
+                 [--max-errors <n>] [--frozen <true|false>] [--max-depth <n>]
+                 [--max-nodes <n>] [--max-time-ms <ms>] [--ordered <true|false>]
+  validator convert --from <vdsl|jsonschema> --to <vdsl|jsonschema|openapi|typescript> [--name <Name>] <file>";
+
+/// 命令行参数：`--name value` 形式的选项与位置参数
+struct Args {
+    options: HashMap<String, String>,
+    positional: Vec<String>,
+}
+
+impl Args {
+    fn parse(raw: &[String]) -> Result<Self, String> {
+        let mut options = HashMap::new();
+        let mut positional = Vec::new();
+        let mut iter = raw.iter();
+        while let Some(arg) = iter.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                let value = match name.split_once('=') {
+                    Some((n, v)) => {
+                        options.insert(n.to_string(), v.to_string());
+                        continue;
+                    }
+                    None => iter
+                        .next()
+                        .ok_or_else(|| format!("Missing value for --{}", name))?,
+                };
+                options.insert(name.to_string(), value.clone());
+            } else {
+                positional.push(arg.clone());
+            }
+        }
+        Ok(Self {
+            options,
+            positional,
+        })
+    }
+
+    fn parsed<T: std::str::FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        match self.options.get(name) {
+            Some(raw) => raw
+                .parse()
+                .map_err(|_| format!("Invalid value for --{}: {}", name, raw)),
+            None => Ok(default),
+        }
+    }
+
+    fn required(&self, name: &str) -> Result<&str, String> {
+        self.options
+            .get(name)
+            .map(String::as_str)
+            .ok_or_else(|| format!("Missing --{}", name))
+    }
+}
+
+/// 先按容错模式解析以一次报告全部语法错误，语法无误后再构建 `Schema`，
+/// 这样空范围等规则冲突会被拒绝，`meta(...)` 中的设置也会生效
+fn load_schema(path: &str) -> Result<Schema, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    Parser::parse_rules_recovering(&text).map_err(|errors| {
+        format!(
+            "{}: {} syntax error(s)\n\n{}",
+            path,
+            errors.len(),
+            diagnostics::render_all(&text, path, &errors).trim_end()
+        )
+    })?;
+    Schema::parse(&text).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn cmd_explain(args: &Args) -> Result<(), String> {
+    let schema = load_schema(args.required("schema")?)?;
+    let path = args
+        .positional
+        .first()
+        .ok_or("Missing field path to explain")?;
+    print!("{}", explain(schema.rules(), path)?);
+    Ok(())
+}
+
+fn cmd_generate(args: &Args) -> Result<(), String> {
+    let schema = load_schema(args.required("schema")?)?;
+    let count: usize = args.parsed("count", 10)?;
+    let invalid_rate: f64 = args.parsed("invalid-rate", 0.0)?;
+    if !(0.0..=1.0).contains(&invalid_rate) {
+        return Err("--invalid-rate must be between 0 and 1".into());
+    }
+    let default_seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default();
+    let seed: u64 = args.parsed("seed", default_seed)?;
+
+    // 按比例均匀分布非法数据：第 i 条数据在累计比例跨过整数时生成非法数据
+    let mut generator = Generator::new(seed);
+    for i in 0..count {
+        let invalid = ((i + 1) as f64 * invalid_rate).floor() > (i as f64 * invalid_rate).floor();
+        let value = if invalid {
+            generator.invalid(schema.rules())?
+        } else {
+            generator.valid(schema.rules())?
+        };
+        println!("{}", to_json_string(&value));
+    }
+    Ok(())
+}
+
+fn cmd_convert(args: &Args) -> Result<(), String> {
+    let path = args
+        .positional
+        .first()
+        .ok_or("Missing schema file to convert")?;
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let schema = match args.required("from")? {
+        "vdsl" => Schema::parse(&text),
+        "jsonschema" => serde_json::from_str(&text)
+            .map_err(|e| e.to_string())
+            .and_then(|schema| Schema::from_json_schema(&schema)),
+        other => return Err(format!("Unsupported source format '{}'", other)),
+    }
+    .map_err(|e| format!("{}: {}", path, e))?;
+
+    // --name 优先，其次是 meta(name=...)，最后按文件名生成
+    let name = match (args.options.get("name"), schema.metadata().name()) {
+        (Some(name), _) => name.clone(),
+        (None, Some(name)) => name.to_string(),
+        (None, None) => type_name(path),
+    };
+    let output = match args.required("to")? {
+        "vdsl" => schema.to_dsl(),
+        "jsonschema" => pretty(&schema.to_json_schema())?,
+        "openapi" => pretty(&schema.to_openapi(&name))?,
+        "typescript" => schema.to_typescript(&name),
+        other => return Err(format!("Unsupported target format '{}'", other)),
+    };
+    println!("{}", output.trim_end());
+    Ok(())
+}
+
+const REPL_HELP: &str = "Enter a JSON object (may span several lines) to validate it.
+Commands:
+  :schema <file>     load another schema
+  :strict [on|off]   reject fields not declared in the schema
+  :coerce [on|off]   convert strings to the declared int/float/bool types
+  :canonicalize [on|off]
+                     normalize uuid/mac/color values in the output
+  :exhaustive [on|off]
+                     report every failing constraint of a field
+  :frozen [on|off]   fail instead of changing the payload
+  :help              show this help
+  :quit              exit";
+
+/// 交互模式：逐个读取 JSON 片段并立即输出校验结果（每行一个 JSON）
+fn cmd_repl(args: &Args) -> Result<(), String> {
+    let mut schema_path = args.required("schema")?.to_string();
+    let mut schema = load_schema(&schema_path)?;
+    let mut options = ValidationOptions {
+        strict: args.parsed("strict", false)?,
+        coerce: args.parsed("coerce", false)?,
+        canonicalize: args.parsed("canonicalize", false)?,
+        exhaustive: args.parsed("exhaustive", false)?,
+        float_epsilon: args.parsed("float-epsilon", 0.0)?,
+        max_errors: args.parsed("max-errors", 0)?,
+        frozen: args.parsed("frozen", false)?,
+        max_depth: args.parsed("max-depth", DEFAULT_MAX_DEPTH)?,
+        budget: Budget {
+            max_nodes: args.parsed("max-nodes", 0)?,
+            max_time: match args.parsed("max-time-ms", 0u64)? {
+                0 => None,
+                ms => Some(Duration::from_millis(ms)),
+            },
+        },
+        #[cfg(feature = "ordered")]
+        ordered: args.parsed("ordered", false)?,
+    };
+
+    let stdin = std::io::stdin();
+    let interactive = stdin.is_terminal();
+    let mut stdout = std::io::stdout();
+    let mut buffer = String::new();
+    let mut lines = stdin.lock().lines();
+    loop {
+        if interactive {
+            print!("{}", if buffer.is_empty() { "> " } else { ". " });
+            stdout.flush().map_err(|e| e.to_string())?;
+        }
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = line.map_err(|e| e.to_string())?;
+
+        if buffer.is_empty() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            if let Some(command) = trimmed.strip_prefix(':') {
+                let mut parts = command.split_whitespace();
+                match (parts.next().unwrap_or(""), parts.next()) {
+                    ("quit" | "q", _) => break,
+                    ("help", _) => println!("{}", REPL_HELP),
+                    ("schema", Some(path)) => match load_schema(path) {
+                        Ok(loaded) => {
+                            schema = loaded;
+                            schema_path = path.to_string();
+                            println!("schema: {}", schema_path);
+                        }
+                        Err(e) => println!("error: {}", e),
+                    },
+                    ("schema", None) => println!("schema: {}", schema_path),
+                    ("strict", flag) => match toggle(options.strict, flag) {
+                        Ok(on) => {
+                            options.strict = on;
+                            println!("strict: {}", on_off(on));
+                        }
+                        Err(e) => println!("error: {}", e),
+                    },
+                    ("coerce", flag) => match toggle(options.coerce, flag) {
+                        Ok(on) => {
+                            options.coerce = on;
+                            println!("coerce: {}", on_off(on));
+                        }
+                        Err(e) => println!("error: {}", e),
+                    },
+                    ("canonicalize", flag) => match toggle(options.canonicalize, flag) {
+                        Ok(on) => {
+                            options.canonicalize = on;
+                            println!("canonicalize: {}", on_off(on));
+                        }
+                        Err(e) => println!("error: {}", e),
+                    },
+                    ("exhaustive", flag) => match toggle(options.exhaustive, flag) {
+                        Ok(on) => {
+                            options.exhaustive = on;
+                            println!("exhaustive: {}", on_off(on));
+                        }
+                        Err(e) => println!("error: {}", e),
+                    },
+                    ("frozen", flag) => match toggle(options.frozen, flag) {
+                        Ok(on) => {
+                            options.frozen = on;
+                            println!("frozen: {}", on_off(on));
+                        }
+                        Err(e) => println!("error: {}", e),
+                    },
+                    (other, _) => println!("error: unknown command ':{}' (try :help)", other),
+                }
+                continue;
+            }
+        }
+
+        buffer.push_str(&line);
+        buffer.push('\n');
+        let json = match serde_json::from_str::<serde_json::Value>(&buffer) {
+            Ok(json) => json,
+            // 片段尚未结束，继续读取下一行
+            Err(e) if e.is_eof() => continue,
+            Err(e) => {
+                println!(
+                    "{}",
+                    serde_json::json!({ "valid": false, "error": e.to_string() })
+                );
+                buffer.clear();
+                continue;
+            }
+        };
+        buffer.clear();
+
+        let mut value = json.into();
+        let ctx = ValidationContext::new().options(options.clone());
+        let result = match schema.validate_with_context(&mut value, &ctx) {
+            Ok(()) => serde_json::json!({
+                "valid": true,
+                "value": serde_json::Value::from(&value),
+            }),
+            Err(e) => serde_json::json!({ "valid": false, "error": e.to_string() }),
+        };
+        println!("{}", result);
+    }
+    if !buffer.trim().is_empty() {
+        println!(
+            "{}",
+            serde_json::json!({ "valid": false, "error": "unexpected end of input" })
+        );
+    }
+    Ok(())
+}
+
+fn toggle(current: bool, flag: Option<&str>) -> Result<bool, String> {
+    match flag {
+        None => Ok(!current),
+        Some("on") => Ok(true),
+        Some("off") => Ok(false),
+        Some(other) => Err(format!("expected 'on' or 'off', got '{}'", other)),
+    }
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on { "on" } else { "off" }
+}
+
+fn pretty(value: &serde_json::Value) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| e.to_string())
+}
+
+/// 由文件名推导类型名：`create_user.json` -> `CreateUser`
+fn type_name(path: &str) -> String {
+    let stem = std::path::Path::new(path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("Schema");
+    let name: String = stem
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("Schema{}", name)
+    } else {
+        name
+    }
+}
+
+fn main() -> ExitCode {
+    let raw: Vec<String> = std::env::args().skip(1).collect();
+    let Some((command, rest)) = raw.split_first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+
+    let result = Args::parse(rest).and_then(|args| match command.as_str() {
+        "explain" => cmd_explain(&args),
+        "generate" => cmd_generate(&args),
+        "convert" => cmd_convert(&args),
+        "repl" => cmd_repl(&args),
+        other => Err(format!("Unknown command '{}'\n{}", other, USAGE)),
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! `ValidationOptions::budget` 的计量：一次校验期间访问的节点数与截止时间记录在线程局部变量里，
+//! 嵌套的校验（如未填入的默认值、逐个字段的结论）共用同一份计量；不在计量期间时不限制
+
+use std::{
+    cell::RefCell,
+    time::{Duration, Instant},
+};
+
+use crate::validator::Budget;
+
+struct Meter {
+    nodes: usize,
+    max_nodes: usize,
+    max_time: Option<Duration>,
+    deadline: Option<Instant>,
+}
+
+thread_local! {
+    static METER: RefCell<Option<Meter>> = const { RefCell::new(None) };
+}
+
+/// 计量期间持有，离开作用域（包括 panic）时结束由它开始的计量
+pub(crate) struct Guard {
+    started: bool,
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        if self.started {
+            METER.with(|m| m.borrow_mut().take());
+        }
+    }
+}
+
+/// 没有进行中的计量时按 `budget` 开始计量；已经在计量（外层校验）时沿用外层的预算
+pub(crate) fn start(budget: &Budget) -> Guard {
+    if budget.max_nodes == 0 && budget.max_time.is_none() {
+        return Guard { started: false };
+    }
+    let started = METER.with(|m| {
+        let mut m = m.borrow_mut();
+        if m.is_some() {
+            return false;
+        }
+        *m = Some(Meter {
+            nodes: 0,
+            max_nodes: budget.max_nodes,
+            max_time: budget.max_time,
+            deadline: budget.max_time.map(|t| Instant::now() + t),
+        });
+        true
+    });
+    Guard { started }
+}
+
+/// 记一个节点；预算用完时返回超出的限制，如 `1000 nodes`、`50ms`
+pub(crate) fn charge() -> Option<String> {
+    METER.with(|m| {
+        let mut m = m.borrow_mut();
+        let meter = m.as_mut()?;
+        meter.nodes += 1;
+        if meter.max_nodes > 0 && meter.nodes > meter.max_nodes {
+            return Some(format!("{} nodes", meter.max_nodes));
+        }
+        match (meter.deadline, meter.max_time) {
+            (Some(deadline), Some(max_time)) if Instant::now() >= deadline => {
+                Some(format!("{:?}", max_time))
+            }
+            _ => None,
+        }
+    })
+}
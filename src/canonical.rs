@@ -0,0 +1,108 @@
+use crate::{
+    ast::{FieldRule, FieldType, Value},
+    report::Normalization,
+    validator::{Path, validate_type},
+};
+
+/// -----------------------------
+/// Canonicalization
+/// -----------------------------
+/// 标识符类型的规范形式：uuid 小写带连字符，mac 小写冒号分隔，
+/// 十六进制 color 展开为 6 位（带透明度为 8 位）小写形式。
+/// 只对已通过校验的值调用，不认识的类型返回 None
+pub fn canonical_form(value: &Value, t: &FieldType) -> Option<String> {
+    let s = value.as_str()?;
+    let hex: String = s
+        .chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    match t {
+        FieldType::Uuid | FieldType::UuidOf(_) if hex.len() == 32 => Some(format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )),
+        FieldType::Mac if hex.len().is_multiple_of(2) => {
+            let pairs: Vec<&str> = (0..hex.len()).step_by(2).map(|i| &hex[i..i + 2]).collect();
+            Some(pairs.join(":"))
+        }
+        // 函数写法（rgb() / hsl()）保持原样
+        FieldType::Color | FieldType::ColorOf(_) if !s.starts_with('#') => None,
+        FieldType::Color | FieldType::ColorOf(_) if hex.len() == 3 || hex.len() == 4 => {
+            let doubled: String = hex.chars().flat_map(|c| [c, c]).collect();
+            Some(format!("#{}", doubled))
+        }
+        FieldType::Color | FieldType::ColorOf(_) if hex.len() == 6 || hex.len() == 8 => {
+            Some(format!("#{}", hex))
+        }
+        _ => None,
+    }
+}
+
+/// 按规则就地规范化对象中的字段，记录发生变化的值
+pub(crate) fn canonicalize_object(
+    value: &mut Value,
+    rules: &[FieldRule],
+    prefix: Path,
+    out: &mut Vec<Normalization>,
+) {
+    let Value::Object(map) = value else {
+        return;
+    };
+    for rule in rules {
+        if let Some(v) = map.get_mut(rule.field.as_str()) {
+            canonicalize_value(v, rule, prefix.field(&rule.field), out);
+        }
+    }
+}
+
+pub(crate) fn canonicalize_value(
+    value: &mut Value,
+    rule: &FieldRule,
+    path: Path,
+    out: &mut Vec<Normalization>,
+) {
+    // 密文原样保留
+    if rule.encryption().is_some() {
+        return;
+    }
+    match value {
+        Value::Object(_) => {
+            if let Some(children) = &rule.children {
+                canonicalize_object(value, children, path, out);
+            }
+        }
+        Value::Array(items) => {
+            if let Some(sub) = &rule.rule {
+                for (i, item) in items.iter_mut().enumerate() {
+                    canonicalize_value(item, sub, path.index(i), out);
+                }
+            }
+        }
+        Value::String(_) => {
+            // 联合类型取第一个匹配的类型
+            let types = match &rule.union_types {
+                Some(types) => types.as_slice(),
+                None => std::slice::from_ref(&rule.field_type),
+            };
+            let Some(t) = types.iter().find(|t| validate_type(value, t).is_ok()) else {
+                return;
+            };
+            if let Some(after) = canonical_form(value, t)
+                && let Value::String(before) = value
+                && *before != after
+            {
+                out.push(Normalization {
+                    path: path.to_string(),
+                    before: std::mem::replace(before, after.clone()),
+                    after,
+                });
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,116 @@
+/// -----------------------------
+/// Checksum
+/// -----------------------------
+/// `checksum(name)` / `checksum(name="expected")` 按名称调用的校验和算法。
+/// 内置 `luhn`、`mod97`、`crc32`，自定义算法通过 `ValidatorRegistry::register_checksum` 注册
+pub trait Checksum: Send + Sync {
+    /// `expected` 为 DSL 中给出的期望值；为 None 时输入自带校验位
+    fn verify(&self, input: &str, expected: Option<&str>) -> bool;
+}
+
+impl<F> Checksum for F
+where
+    F: Fn(&str, Option<&str>) -> bool + Send + Sync,
+{
+    fn verify(&self, input: &str, expected: Option<&str>) -> bool {
+        self(input, expected)
+    }
+}
+
+/// Luhn (mod 10)，用于银行卡号、IMEI 等，允许空格与连字符分隔
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Luhn;
+
+impl Checksum for Luhn {
+    fn verify(&self, input: &str, expected: Option<&str>) -> bool {
+        if expected.is_some() {
+            return false;
+        }
+        let mut digits = Vec::with_capacity(input.len());
+        for c in input.chars() {
+            match c.to_digit(10) {
+                Some(d) => digits.push(d),
+                None if c == ' ' || c == '-' => {}
+                None => return false,
+            }
+        }
+        digits.len() >= 2 && luhn_valid(&digits)
+    }
+}
+
+pub(crate) fn luhn_valid(digits: &[u32]) -> bool {
+    let mut sum = 0;
+    let mut double = false;
+    for &d in digits.iter().rev() {
+        sum += if double {
+            if d > 4 { d * 2 - 9 } else { d * 2 }
+        } else {
+            d
+        };
+        double = !double;
+    }
+    sum % 10 == 0
+}
+
+/// ISO 7064 MOD 97-10（如 LEI），字母按 A=10 … Z=35 展开，余数为 1 时通过
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Mod97;
+
+impl Checksum for Mod97 {
+    fn verify(&self, input: &str, expected: Option<&str>) -> bool {
+        if expected.is_some() || input.len() < 2 {
+            return false;
+        }
+        let mut rem: u32 = 0;
+        for c in input.chars() {
+            let n = match c.to_digit(36) {
+                Some(n) => n,
+                None => return false,
+            };
+            rem = if n < 10 {
+                (rem * 10 + n) % 97
+            } else {
+                (rem * 100 + n) % 97
+            };
+        }
+        rem == 1
+    }
+}
+
+/// CRC-32 (IEEE)，与期望的十六进制摘要比较（不区分大小写）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Crc32;
+
+impl Checksum for Crc32 {
+    fn verify(&self, input: &str, expected: Option<&str>) -> bool {
+        match expected {
+            Some(hex) => format!("{:08x}", crc32(input.as_bytes())).eq_ignore_ascii_case(hex),
+            None => false,
+        }
+    }
+}
+
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in bytes {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// 按名称查找内置算法
+pub(crate) fn builtin(name: &str) -> Option<&'static dyn Checksum> {
+    match name {
+        "luhn" => Some(&Luhn),
+        "mod97" => Some(&Mod97),
+        "crc32" => Some(&Crc32),
+        _ => None,
+    }
+}
@@ -0,0 +1,144 @@
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::ast::Value;
+
+/// -----------------------------
+/// Clock
+/// -----------------------------
+/// `past` / `future` / `within(..)` 约束使用的当前时间（Unix 秒），测试时可替换为固定时钟
+pub trait Clock: fmt::Debug + Send + Sync {
+    fn now(&self) -> i64;
+}
+
+/// 系统时间
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs() as i64),
+        }
+    }
+}
+
+/// 固定在某一时刻的时钟
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub i64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> i64 {
+        self.0
+    }
+}
+
+/// -----------------------------
+/// Instant
+/// -----------------------------
+/// 时间值对应的 Unix 秒，以及是否只精确到天（date）
+pub(crate) fn instant_of(value: &Value) -> Option<(i64, bool)> {
+    match value {
+        // timestamp 为 Unix 秒
+        Value::Int(i) => Some((*i, false)),
+        Value::String(s) => parse_date_time(s),
+        _ => None,
+    }
+}
+
+/// 可解析的最大年份（绝对值），换算成 Unix 秒后远小于 `i64::MAX`
+const MAX_YEAR: i64 = 1_000_000_000;
+
+/// `YYYY-MM-DD` 或 `YYYY-MM-DDTHH:MM:SS[Z]`（按 UTC 处理）
+fn parse_date_time(s: &str) -> Option<(i64, bool)> {
+    let (date, time) = match s.split_once('T') {
+        Some((date, time)) => (date, Some(time.strip_suffix('Z').unwrap_or(time))),
+        None => (s, None),
+    };
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    // 超出范围的年份按无法解析处理，避免换算成秒时溢出
+    if !(-MAX_YEAR..=MAX_YEAR).contains(&year)
+        || !(1..=12).contains(&month)
+        || day == 0
+        || day > days_in_month(year, month)
+    {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+
+    let Some(time) = time else {
+        return Some((days * 86400, true));
+    };
+    let mut parts = time.splitn(3, ':');
+    let hour: i64 = parts.next()?.parse().ok()?;
+    let minute: i64 = parts.next()?.parse().ok()?;
+    let second: i64 = parts.next()?.parse().ok()?;
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    Some((days * 86400 + hour * 3600 + minute * 60 + second, false))
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// 在 `now` 时刻的周岁。2 月 29 日出生者在平年的 3 月 1 日满岁
+pub(crate) fn age_at(birth: i64, now: i64) -> i64 {
+    let (by, bm, bd) = civil_from_days(birth.div_euclid(86400));
+    let (ny, nm, nd) = civil_from_days(now.div_euclid(86400));
+    let age = ny - by;
+    if (nm, nd) < (bm, bd) { age - 1 } else { age }
+}
+
+/// Unix 秒格式化为 `YYYY-MM-DD` 或 `YYYY-MM-DDTHH:MM:SSZ`
+pub(crate) fn format_instant(secs: i64, date_only: bool) -> String {
+    let (year, month, day) = civil_from_days(secs.div_euclid(86400));
+    if date_only {
+        return format!("{:04}-{:02}-{:02}", year, month, day);
+    }
+    let rem = secs.rem_euclid(86400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/// 1970-01-01 之后第 `days` 天对应的公历日期
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// 公历日期距 1970-01-01 的天数
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
@@ -0,0 +1,62 @@
+use std::cmp::Ordering;
+
+use crate::ast::Collation;
+
+/// -----------------------------
+/// Collation
+/// -----------------------------
+/// 按指定方式比较两个字符串；排序规则不可用（未开启 `icu` feature 或 locale 无效）时返回原因
+pub(crate) fn compare(collation: &Collation, a: &str, b: &str) -> Result<Ordering, String> {
+    match collation {
+        Collation::Binary => Ok(a.cmp(b)),
+        Collation::CaseInsensitive => Ok(a
+            .chars()
+            .flat_map(char::to_lowercase)
+            .cmp(b.chars().flat_map(char::to_lowercase))),
+        Collation::Unicode(locale) => unicode::compare(locale.as_deref(), a, b),
+    }
+}
+
+#[cfg(feature = "icu")]
+mod unicode {
+    use std::{cell::RefCell, cmp::Ordering, collections::HashMap, rc::Rc};
+
+    use icu_collator::{Collator, CollatorOptions};
+    use icu_provider::DataLocale;
+
+    thread_local! {
+        /// 构造 Collator 需要加载排序数据，按 locale 缓存
+        static COLLATORS: RefCell<HashMap<String, Rc<Collator>>> = RefCell::new(HashMap::new());
+    }
+
+    pub(super) fn compare(locale: Option<&str>, a: &str, b: &str) -> Result<Ordering, String> {
+        let key = locale.unwrap_or_default();
+        let cached = COLLATORS.with(|c| c.borrow().get(key).cloned());
+        let collator = match cached {
+            Some(collator) => collator,
+            None => {
+                let data_locale = match locale {
+                    Some(tag) => tag
+                        .parse::<DataLocale>()
+                        .map_err(|_| format!("Invalid collation locale '{}'", tag))?,
+                    None => DataLocale::default(),
+                };
+                let collator = Collator::try_new(&data_locale, CollatorOptions::new())
+                    .map(Rc::new)
+                    .map_err(|e| format!("Unicode collation unavailable: {}", e))?;
+                COLLATORS.with(|c| c.borrow_mut().insert(key.to_string(), collator.clone()));
+                collator
+            }
+        };
+        Ok(collator.compare(a, b))
+    }
+}
+
+#[cfg(not(feature = "icu"))]
+mod unicode {
+    use std::cmp::Ordering;
+
+    pub(super) fn compare(_locale: Option<&str>, _a: &str, _b: &str) -> Result<Ordering, String> {
+        Err("unicode collation requires the `icu` feature".into())
+    }
+}
@@ -0,0 +1,97 @@
+use crate::ast::ColorSpec;
+
+/// -----------------------------
+/// Color
+/// -----------------------------
+/// 按 `color(...)` 的参数检查 CSS 颜色：十六进制、`rgb()` / `rgba()`、`hsl()` / `hsla()`。
+/// 函数写法支持逗号分隔与 CSS4 的空格分隔（`rgb(0 0 0 / 50%)`）
+pub(crate) fn is_valid_color(s: &str, spec: &ColorSpec) -> bool {
+    if let Some(hex) = s.strip_prefix('#') {
+        return spec.hex && is_hex_color(hex, spec.alpha);
+    }
+    let Some((name, rest)) = s.split_once('(') else {
+        return false;
+    };
+    let Some(args) = rest.strip_suffix(')') else {
+        return false;
+    };
+    let name = name.to_ascii_lowercase();
+    let (base, explicit_alpha) = match name.strip_suffix('a') {
+        Some(base) => (base, true),
+        None => (name.as_str(), false),
+    };
+    let enabled = match base {
+        "rgb" => spec.rgb,
+        "hsl" => spec.hsl,
+        _ => false,
+    };
+    if !enabled || (explicit_alpha && !spec.alpha) {
+        return false;
+    }
+
+    let Some((channels, alpha)) = split_args(args) else {
+        return false;
+    };
+    if explicit_alpha && alpha.is_none() {
+        return false;
+    }
+    if let Some(a) = alpha
+        && !(spec.alpha && is_alpha(a))
+    {
+        return false;
+    }
+    match base {
+        "rgb" => {
+            channels.len() == 3
+                && (channels.iter().all(|c| is_number_in(c, 255.0))
+                    || channels.iter().all(|c| is_percent(c)))
+        }
+        _ => {
+            channels.len() == 3
+                && is_hue(channels[0])
+                && is_percent(channels[1])
+                && is_percent(channels[2])
+        }
+    }
+}
+
+fn is_hex_color(hex: &str, alpha: bool) -> bool {
+    let len_ok = match hex.len() {
+        3 | 6 => true,
+        4 | 8 => alpha,
+        _ => false,
+    };
+    len_ok && hex.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// 拆出三个颜色通道与可选的 alpha：`r, g, b[, a]` 或 `r g b[ / a]`
+fn split_args(args: &str) -> Option<(Vec<&str>, Option<&str>)> {
+    if args.contains(',') {
+        let mut parts: Vec<&str> = args.split(',').map(str::trim).collect();
+        let alpha = if parts.len() == 4 { parts.pop() } else { None };
+        return Some((parts, alpha));
+    }
+    let (channels, alpha) = match args.split_once('/') {
+        Some((c, a)) => (c, Some(a.trim())),
+        None => (args, None),
+    };
+    Some((channels.split_whitespace().collect(), alpha))
+}
+
+fn is_number_in(s: &str, max: f64) -> bool {
+    s.parse::<f64>()
+        .is_ok_and(|n| n.is_finite() && (0.0..=max).contains(&n))
+}
+
+fn is_percent(s: &str) -> bool {
+    s.strip_suffix('%').is_some_and(|n| is_number_in(n, 100.0))
+}
+
+fn is_alpha(s: &str) -> bool {
+    is_number_in(s, 1.0) || is_percent(s)
+}
+
+fn is_hue(s: &str) -> bool {
+    let n = s.strip_suffix("deg").unwrap_or(s);
+    n.parse::<f64>().is_ok_and(f64::is_finite)
+}
@@ -0,0 +1,238 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+
+use crate::{
+    ast::{Constraint, FieldName, FieldRule, FieldType, Value},
+    validator::{Result, check_bounds, check_length},
+};
+
+/// -----------------------------
+/// CompiledRule
+/// -----------------------------
+/// `Schema::compile` 为每条规则预先计算的数据，结构与规则树一一对应，
+/// 校验时省去对每个值重复的边界解析、正则查找与线性查找
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct CompiledRule {
+    /// 与 `constraints` 一一对应，区间约束的边界已解析
+    pub(crate) ranges: Vec<Option<FoldedRange>>,
+    /// 与 `constraints` 一一对应，`regex(...)` 约束编译好的正则；
+    /// 不进入预编译产物，加载后由 [`CompiledRule::attach_regexes`] 重新编译
+    #[cfg_attr(feature = "artifact", serde(skip))]
+    pub(crate) regexes: Vec<Option<Regex>>,
+    /// 自定义正则类型 `regex("...")` 编译好的正则
+    #[cfg_attr(feature = "artifact", serde(skip))]
+    pub(crate) pattern: Option<Regex>,
+    /// enum 的哈希查找表
+    pub(crate) enum_set: Option<HashSet<Value>>,
+    /// 联合类型的尝试顺序（下标），判断成本低的类型在前
+    pub(crate) union_order: Vec<usize>,
+    pub(crate) sub: Option<Box<CompiledRule>>,
+    pub(crate) children: Vec<CompiledRule>,
+}
+
+impl CompiledRule {
+    pub(crate) fn compile(rule: &FieldRule) -> Self {
+        let ranges = rule
+            .constraints
+            .iter()
+            .flat_map(|c| &c.items)
+            .map(|con| match con {
+                Constraint::Range {
+                    min,
+                    max,
+                    min_inclusive,
+                    max_inclusive,
+                } => Some(FoldedRange::fold(min, max, *min_inclusive, *max_inclusive)),
+                _ => None,
+            })
+            .collect();
+
+        let enum_set = rule
+            .enum_values
+            .as_ref()
+            .map(|values| values.iter().cloned().collect());
+
+        let mut union_order: Vec<usize> =
+            (0..rule.union_types.as_ref().map_or(0, Vec::len)).collect();
+        if let Some(types) = &rule.union_types {
+            union_order.sort_by_key(|&i| type_cost(&types[i]));
+        }
+
+        let mut compiled = Self {
+            ranges,
+            enum_set,
+            union_order,
+            sub: rule.rule.as_deref().map(|sub| Box::new(Self::compile(sub))),
+            children: rule.children.iter().flatten().map(Self::compile).collect(),
+            ..Self::default()
+        };
+        compiled.compile_regexes(rule);
+        compiled
+    }
+
+    /// 编译本规则的正则；无法编译的正则留空，校验时退回原始路径报错
+    fn compile_regexes(&mut self, rule: &FieldRule) {
+        self.regexes = rule
+            .constraints
+            .iter()
+            .flat_map(|c| &c.items)
+            .map(|con| match con {
+                Constraint::Regex(pattern) => Regex::new(pattern).ok(),
+                _ => None,
+            })
+            .collect();
+        self.pattern = match &rule.field_type {
+            FieldType::Custom(pattern) => Regex::new(pattern).ok(),
+            _ => None,
+        };
+    }
+
+    /// 为加载的预编译产物重新编译整棵规则树的正则
+    #[cfg(feature = "artifact")]
+    pub(crate) fn attach_regexes(&mut self, rule: &FieldRule) {
+        self.compile_regexes(rule);
+        if let (Some(sub), Some(sub_rule)) = (&mut self.sub, &rule.rule) {
+            sub.attach_regexes(sub_rule);
+        }
+        for (child, child_rule) in self.children.iter_mut().zip(rule.children.iter().flatten()) {
+            child.attach_regexes(child_rule);
+        }
+    }
+
+    /// 加载的预编译数据与规则树形状一致：区间与约束一一对应、联合类型的顺序是其下标的排列、
+    /// enum 查找表与 enum 值相同、元素规则与子字段逐层对应。校验时按这些下标直接取值，
+    /// 不一致的产物必须在加载时拒绝
+    #[cfg(feature = "artifact")]
+    pub(crate) fn fits(&self, rule: &FieldRule) -> bool {
+        let constraints: Vec<&Constraint> =
+            rule.constraints.iter().flat_map(|c| &c.items).collect();
+        let ranges_fit = self.ranges.len() == constraints.len()
+            && self
+                .ranges
+                .iter()
+                .zip(&constraints)
+                .all(|(range, con)| range.is_some() == matches!(con, Constraint::Range { .. }));
+        let mut order = self.union_order.clone();
+        order.sort_unstable();
+        let union_fits = order
+            .into_iter()
+            .eq(0..rule.union_types.as_ref().map_or(0, Vec::len));
+        let enum_fits = match (&self.enum_set, &rule.enum_values) {
+            (None, None) => true,
+            (Some(set), Some(values)) => *set == values.iter().cloned().collect(),
+            _ => false,
+        };
+        let sub_fits = match (&self.sub, &rule.rule) {
+            (None, None) => true,
+            (Some(sub), Some(sub_rule)) => sub.fits(sub_rule),
+            _ => false,
+        };
+        let children = rule.children.as_deref().unwrap_or_default();
+        let children_fit = self.children.len() == children.len()
+            && self.children.iter().zip(children).all(|(c, r)| c.fits(r));
+        ranges_fit && union_fits && enum_fits && sub_fits && children_fit
+    }
+
+    pub(crate) fn regex(&self, i: usize) -> Option<&Regex> {
+        self.regexes.get(i)?.as_ref()
+    }
+
+    pub(crate) fn compile_all(rules: &[FieldRule]) -> Vec<Self> {
+        rules.iter().map(Self::compile).collect()
+    }
+
+    pub(crate) fn enum_contains(&self, values: &[Value], val: &Value) -> bool {
+        match &self.enum_set {
+            Some(set) => set.contains(val),
+            None => values.contains(val),
+        }
+    }
+}
+
+/// 把整棵规则树中同名的字段名换成同一份 [`FieldName`]：具名类型的各处引用、
+/// 各层对象中的 `id` / `name` 等在编译后的 schema 中只保存一次，克隆规则也不再复制字段名
+pub(crate) fn intern_names(rules: &mut [FieldRule]) {
+    fn walk(rules: &mut [FieldRule], names: &mut HashSet<FieldName>) {
+        for rule in rules {
+            match names.get(rule.field.as_str()) {
+                Some(name) => rule.field = name.clone(),
+                None => {
+                    names.insert(rule.field.clone());
+                }
+            }
+            if let Some(sub) = rule.rule.as_deref_mut() {
+                walk(std::slice::from_mut(sub), names);
+            }
+            if let Some(children) = &mut rule.children {
+                walk(children, names);
+            }
+        }
+    }
+    walk(rules, &mut HashSet::new());
+}
+
+/// 类型检查只需匹配枚举变体的排在前面，依赖正则或解析的排在后面
+fn type_cost(t: &FieldType) -> u8 {
+    match t {
+        FieldType::Int
+        | FieldType::Float
+        | FieldType::Bool
+        | FieldType::String
+        | FieldType::Timestamp
+        | FieldType::Object
+        | FieldType::Array => 0,
+        _ => 1,
+    }
+}
+
+/// -----------------------------
+/// FoldedRange
+/// -----------------------------
+/// 区间约束按值的种类预先解析好的边界；无法解析的部分为 None，校验时退回原始路径报错
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "artifact", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct FoldedRange {
+    /// int 值的边界，浮点边界向内取整
+    int: Option<(f64, f64)>,
+    float: Option<(f64, f64)>,
+    /// 字符串长度与数组元素个数
+    length: Option<(usize, usize)>,
+    min_inclusive: bool,
+    max_inclusive: bool,
+}
+
+impl FoldedRange {
+    fn fold(min: &Value, max: &Value, min_inclusive: bool, max_inclusive: bool) -> Self {
+        let number = |v: &Value, round: fn(f64) -> f64| match v {
+            Value::Int(i) => Some(*i as f64),
+            Value::Float(f) => Some(round(*f)),
+            _ => None,
+        };
+        let length = |v: &Value| match v {
+            Value::Int(i) => Some(*i as usize),
+            Value::String(s) => s.parse::<usize>().ok(),
+            _ => None,
+        };
+        Self {
+            int: number(min, f64::ceil).zip(number(max, f64::floor)),
+            float: number(min, |f| f).zip(number(max, |f| f)),
+            length: length(min).zip(length(max)),
+            min_inclusive,
+            max_inclusive,
+        }
+    }
+
+    /// 该种类的边界未能预先解析时返回 None
+    pub(crate) fn check(&self, val: &Value, field: &str) -> Option<Result<()>> {
+        let (min_inc, max_inc) = (self.min_inclusive, self.max_inclusive);
+        Some(match val {
+            Value::Int(i) => check_bounds(*i as f64, i, self.int?, min_inc, max_inc, field),
+            Value::Float(f) => check_bounds(*f, f, self.float?, min_inc, max_inc, field),
+            Value::String(s) => check_length(s.len(), self.length?, min_inc, max_inc, field),
+            Value::Array(items) => check_length(items.len(), self.length?, min_inc, max_inc, field),
+            _ => return None,
+        })
+    }
+}
@@ -0,0 +1,230 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::{
+    ast::{FieldRule, ObjectMap, Value, find_rule},
+    validator::{ValidationError, coerce_str, validate_object},
+};
+
+/// -----------------------------
+/// ConfigError
+/// -----------------------------
+#[derive(Debug)]
+pub enum ConfigError {
+    Io { path: PathBuf, message: String },
+    Parse { path: PathBuf, message: String },
+    UnsupportedFormat(PathBuf),
+    Validation(ValidationError),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, message } => {
+                write!(f, "Failed to read {}: {}", path.display(), message)
+            }
+            Self::Parse { path, message } => {
+                write!(f, "Failed to parse {}: {}", path.display(), message)
+            }
+            Self::UnsupportedFormat(path) => {
+                write!(f, "Unsupported config format: {}", path.display())
+            }
+            Self::Validation(err) => write!(f, "Invalid config: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<ValidationError> for ConfigError {
+    fn from(err: ValidationError) -> Self {
+        Self::Validation(err)
+    }
+}
+
+/// -----------------------------
+/// ConfigLoader
+/// -----------------------------
+/// 按层合并配置来源，后面的层覆盖前面的层：
+/// 配置文件（按添加顺序） < 环境变量 < 命令行参数。
+/// 合并结果最后按规则校验并注入默认值。
+pub struct ConfigLoader {
+    rules: Vec<FieldRule>,
+    files: Vec<PathBuf>,
+    env_prefix: Option<String>,
+    env_vars: Option<Vec<(String, String)>>,
+    args: Vec<String>,
+}
+
+impl ConfigLoader {
+    pub fn new(rules: Vec<FieldRule>) -> Self {
+        Self {
+            rules,
+            files: Vec::new(),
+            env_prefix: None,
+            env_vars: None,
+            args: Vec::new(),
+        }
+    }
+
+    /// 添加一个配置文件（目前支持 `.toml`）
+    pub fn file(mut self, path: impl AsRef<Path>) -> Self {
+        self.files.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// 读取以 `prefix` 开头的环境变量，`APP_DB__HOST` 对应 `db.host`
+    pub fn env_prefix(mut self, prefix: &str) -> Self {
+        self.env_prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// 使用给定的变量代替进程环境变量（主要用于测试）
+    pub fn env_vars<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.env_vars = Some(
+            vars.into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        );
+        self
+    }
+
+    /// 命令行参数：`--db.host=localhost` 或 `--db.host localhost`，
+    /// 单独的 `--flag` 视为 `true`，其他位置参数被忽略
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args = args.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn load(&self) -> Result<Value, ConfigError> {
+        let mut merged = Value::Object(ObjectMap::new());
+
+        for path in &self.files {
+            merge(&mut merged, load_file(path)?);
+        }
+
+        if let Some(prefix) = &self.env_prefix {
+            let vars = match &self.env_vars {
+                Some(vars) => vars.clone(),
+                None => std::env::vars().collect(),
+            };
+            for (key, raw) in vars {
+                let Some(rest) = key.strip_prefix(prefix.as_str()) else {
+                    continue;
+                };
+                let path: Vec<String> = rest.split("__").map(|s| s.to_lowercase()).collect();
+                self.set_raw(&mut merged, &path, &raw);
+            }
+        }
+
+        let mut args = self.args.iter().peekable();
+        while let Some(arg) = args.next() {
+            let Some(key) = arg.strip_prefix("--") else {
+                continue;
+            };
+            let (key, raw) = match key.split_once('=') {
+                Some((k, v)) => (k, v.to_string()),
+                None => match args.next_if(|next| !next.starts_with("--")) {
+                    Some(next) => (key, next.clone()),
+                    None => (key, "true".to_string()),
+                },
+            };
+            let path: Vec<String> = key.split('.').map(String::from).collect();
+            self.set_raw(&mut merged, &path, &raw);
+        }
+
+        validate_object(&mut merged, &self.rules)?;
+        Ok(merged)
+    }
+
+    /// 按规则声明的类型转换字符串值后写入
+    fn set_raw(&self, root: &mut Value, path: &[String], raw: &str) {
+        if path.iter().any(|s| s.is_empty()) {
+            return;
+        }
+        let value = match find_rule(&self.rules, &path.join(".")) {
+            Some(rule) => coerce_str(raw, rule),
+            None => Value::String(raw.to_string()),
+        };
+        insert_path(root, path, value);
+    }
+}
+
+fn insert_path(root: &mut Value, path: &[String], value: Value) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut current = root;
+    for key in parents {
+        let Value::Object(map) = current else {
+            return;
+        };
+        let entry = map.get_or_insert_with(key.clone(), || Value::Object(ObjectMap::new()));
+        if !matches!(entry, Value::Object(_)) {
+            *entry = Value::Object(ObjectMap::new());
+        }
+        current = entry;
+    }
+    if let Value::Object(map) = current {
+        map.insert(last.clone(), value);
+    }
+}
+
+/// 深度合并：对象逐键合并，其余值直接覆盖
+fn merge(base: &mut Value, layer: Value) {
+    match (base, layer) {
+        (Value::Object(base_map), Value::Object(layer_map)) => {
+            for (k, v) in layer_map {
+                match base_map.get_mut(&k) {
+                    Some(existing) => merge(existing, v),
+                    None => {
+                        base_map.insert(k, v);
+                    }
+                }
+            }
+        }
+        (base, layer) => *base = layer,
+    }
+}
+
+fn load_file(path: &Path) -> Result<Value, ConfigError> {
+    let text = std::fs::read_to_string(path).map_err(|e| ConfigError::Io {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("toml") => {
+            let table: toml::Table =
+                text.parse()
+                    .map_err(|e: toml::de::Error| ConfigError::Parse {
+                        path: path.to_path_buf(),
+                        message: e.message().to_string(),
+                    })?;
+            Ok(from_toml(toml::Value::Table(table)))
+        }
+        _ => Err(ConfigError::UnsupportedFormat(path.to_path_buf())),
+    }
+}
+
+fn from_toml(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::Int(i),
+        toml::Value::Float(f) => Value::Float(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(d) => Value::String(d.to_string()),
+        toml::Value::Array(a) => Value::Array(a.into_iter().map(from_toml).collect()),
+        toml::Value::Table(t) => {
+            Value::Object(t.into_iter().map(|(k, v)| (k, from_toml(v))).collect())
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{ast::Value, validator::ValidationError};
+
+/// -----------------------------
+/// ConvertError
+/// -----------------------------
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConvertError {
+    /// 转换前的校验失败
+    Invalid(ValidationError),
+    Missing(String),
+    WrongType {
+        path: String,
+        expected: &'static str,
+        actual: String,
+    },
+    OutOfRange {
+        path: String,
+        value: String,
+        target: &'static str,
+    },
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(e) => write!(f, "{}", e),
+            Self::Missing(path) => write!(f, "Missing field {}", path),
+            Self::WrongType {
+                path,
+                expected,
+                actual,
+            } => write!(f, "{} expected {}, found {}", path, expected, actual),
+            Self::OutOfRange {
+                path,
+                value,
+                target,
+            } => write!(f, "{} value {} does not fit in {}", path, value, target),
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+impl From<ValidationError> for ConvertError {
+    fn from(e: ValidationError) -> Self {
+        Self::Invalid(e)
+    }
+}
+
+/// -----------------------------
+/// FromValue
+/// -----------------------------
+/// 从（已校验的）`Value` 构造用户类型。基础类型、`Option` / `Vec` / `HashMap` 已实现，
+/// 结构体用 [`impl_from_value!`](crate::impl_from_value) 生成实现
+pub trait FromValue: Sized {
+    /// `path` 为当前值在文档中的位置，用于错误信息
+    fn from_value(value: &Value, path: &str) -> Result<Self, ConvertError>;
+
+    /// 对象中缺少该字段时的取值：`Option` 为 `None`，其余类型报错
+    fn absent(path: &str) -> Result<Self, ConvertError> {
+        Err(ConvertError::Missing(path.to_string()))
+    }
+}
+
+/// 读取对象字段，供 `impl_from_value!` 使用
+pub fn field<T: FromValue>(value: &Value, key: &str, path: &str) -> Result<T, ConvertError> {
+    let obj = value
+        .as_object()
+        .ok_or_else(|| wrong_type(value, path, "object"))?;
+    let path = if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    };
+    match obj.get(key) {
+        Some(v) => T::from_value(v, &path),
+        None => T::absent(&path),
+    }
+}
+
+fn wrong_type(value: &Value, path: &str, expected: &'static str) -> ConvertError {
+    ConvertError::WrongType {
+        path: path.to_string(),
+        expected,
+        actual: value.type_name().to_string(),
+    }
+}
+
+impl FromValue for Value {
+    fn from_value(value: &Value, _path: &str) -> Result<Self, ConvertError> {
+        Ok(value.clone())
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value, path: &str) -> Result<Self, ConvertError> {
+        value
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| wrong_type(value, path, "string"))
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: &Value, path: &str) -> Result<Self, ConvertError> {
+        value
+            .as_bool()
+            .ok_or_else(|| wrong_type(value, path, "bool"))
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: &Value, path: &str) -> Result<Self, ConvertError> {
+        match value {
+            Value::Float(f) => Ok(*f),
+            Value::Int(i) => Ok(*i as f64),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(b) => Ok(num_traits::ToPrimitive::to_f64(b).unwrap_or(f64::NAN)),
+            _ => Err(wrong_type(value, path, "float")),
+        }
+    }
+}
+
+impl FromValue for f32 {
+    fn from_value(value: &Value, path: &str) -> Result<Self, ConvertError> {
+        f64::from_value(value, path).map(|f| f as f32)
+    }
+}
+
+macro_rules! int_from_value {
+    ($($t:ty),*) => {
+        $(
+            impl FromValue for $t {
+                fn from_value(value: &Value, path: &str) -> Result<Self, ConvertError> {
+                    let converted = match value {
+                        Value::Int(i) => <$t>::try_from(*i).ok(),
+                        #[cfg(feature = "bigint")]
+                        Value::BigInt(b) => <$t>::try_from(b).ok(),
+                        _ => return Err(wrong_type(value, path, "int")),
+                    };
+                    converted.ok_or_else(|| ConvertError::OutOfRange {
+                        path: path.to_string(),
+                        value: value.to_string(),
+                        target: stringify!($t),
+                    })
+                }
+            }
+        )*
+    };
+}
+
+int_from_value!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, usize);
+
+#[cfg(feature = "bigint")]
+impl FromValue for num_bigint::BigInt {
+    fn from_value(value: &Value, path: &str) -> Result<Self, ConvertError> {
+        match value {
+            Value::Int(i) => Ok((*i).into()),
+            Value::BigInt(b) => Ok(b.clone()),
+            _ => Err(wrong_type(value, path, "int")),
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    /// 可选字段允许留空：`""` 无法转换为目标类型时视为 `None`
+    fn from_value(value: &Value, path: &str) -> Result<Self, ConvertError> {
+        match T::from_value(value, path) {
+            Ok(v) => Ok(Some(v)),
+            Err(_) if matches!(value, Value::String(s) if s.is_empty()) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn absent(_path: &str) -> Result<Self, ConvertError> {
+        Ok(None)
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: &Value, path: &str) -> Result<Self, ConvertError> {
+        let items = value
+            .as_array()
+            .ok_or_else(|| wrong_type(value, path, "array"))?;
+        items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| T::from_value(item, &format!("{}[{}]", path, i)))
+            .collect()
+    }
+}
+
+impl<T: FromValue> FromValue for HashMap<String, T> {
+    fn from_value(value: &Value, path: &str) -> Result<Self, ConvertError> {
+        let obj = value
+            .as_object()
+            .ok_or_else(|| wrong_type(value, path, "object"))?;
+        obj.keys()
+            .map(|k| Ok((k.clone(), field(value, k, path)?)))
+            .collect()
+    }
+}
+
+/// 为结构体实现 [`FromValue`] 与 `TryFrom<Value>`，字段按同名键读取，`字段 = "键"` 可改名：
+///
+/// ```
+/// use zz_validator::{ast::Value, impl_from_value};
+///
+/// struct User {
+///     name: String,
+///     age: u8,
+///     nickname: Option<String>,
+/// }
+/// impl_from_value!(User { name, age, nickname = "nick" });
+///
+/// let value = Value::Object(
+///     [
+///         ("name".to_string(), Value::String("alice".into())),
+///         ("age".to_string(), Value::Int(30)),
+///     ]
+///     .into(),
+/// );
+/// let user = User::try_from(value).unwrap();
+/// assert_eq!((user.name.as_str(), user.age, user.nickname), ("alice", 30, None));
+/// ```
+#[macro_export]
+macro_rules! impl_from_value {
+    ($ty:ident { $($field:ident $(= $key:literal)?),* $(,)? }) => {
+        impl $crate::convert::FromValue for $ty {
+            fn from_value(
+                value: &$crate::ast::Value,
+                path: &str,
+            ) -> ::std::result::Result<Self, $crate::convert::ConvertError> {
+                Ok(Self {
+                    $(
+                        $field: $crate::convert::field(
+                            value,
+                            { let _key = stringify!($field); $(let _key = $key;)? _key },
+                            path,
+                        )?,
+                    )*
+                })
+            }
+        }
+
+        impl ::std::convert::TryFrom<$crate::ast::Value> for $ty {
+            type Error = $crate::convert::ConvertError;
+
+            fn try_from(value: $crate::ast::Value) -> ::std::result::Result<Self, Self::Error> {
+                <Self as $crate::convert::FromValue>::from_value(&value, "")
+            }
+        }
+    };
+}
@@ -0,0 +1,18 @@
+/// -----------------------------
+/// Decryptor
+/// -----------------------------
+/// `encrypted(name)` 按名称调用的解密逻辑，例如调用 KMS 解开信封加密的数据密钥后再解密字段。
+/// 通过 `ValidatorRegistry::register_decryptor` 注册
+pub trait Decryptor: Send + Sync {
+    /// `ciphertext` 为字段中的密文字符串；成功时返回明文，否则返回失败原因
+    fn decrypt(&self, ciphertext: &str) -> Result<String, String>;
+}
+
+impl<F> Decryptor for F
+where
+    F: Fn(&str) -> Result<String, String> + Send + Sync,
+{
+    fn decrypt(&self, ciphertext: &str) -> Result<String, String> {
+        self(ciphertext)
+    }
+}
@@ -0,0 +1,60 @@
+use crate::parser::SyntaxError;
+
+/// -----------------------------
+/// Diagnostics
+/// -----------------------------
+/// 把 DSL 错误渲染为带源码片段的文本：出错的那一行，以及出错的词法单元下方的 `^`。
+/// `name` 为源码的来源（如文件路径），为空时只输出行列：
+///
+/// ```text
+/// error: Unknown type strin
+///  --> user.vdsl:3:5
+///   |
+/// 3 |   b:strin,
+///   |     ^^^^^
+/// ```
+pub fn render(source: &str, name: &str, error: &SyntaxError) -> String {
+    let (line, column, width) = match error.span {
+        Some(span) => {
+            let text = source.get(span.start..span.end).unwrap_or_default();
+            let width = text.lines().next().map_or(0, |l| l.chars().count());
+            (span.line, span.column, width.max(1))
+        }
+        // 输入末尾：指向最后一个非空白字符之后
+        None => {
+            let text = source.trim_end();
+            let line = text.matches('\n').count() + 1;
+            let last = text.rsplit('\n').next().unwrap_or_default();
+            (line, last.chars().count() + 1, 1)
+        }
+    };
+    let code = source.lines().nth(line - 1).unwrap_or_default();
+    // 保留行首的制表符，使 `^` 与源码对齐
+    let indent: String = code
+        .chars()
+        .take(column - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let gutter = " ".repeat(line.to_string().len());
+    let location = if name.is_empty() {
+        format!("{}:{}", line, column)
+    } else {
+        format!("{}:{}:{}", name, line, column)
+    };
+
+    let mut out = format!("error: {}\n", error.message);
+    out.push_str(&format!("{}--> {}\n", gutter, location));
+    out.push_str(&format!("{} |\n", gutter));
+    out.push_str(&format!("{} | {}\n", line, code));
+    out.push_str(&format!("{} | {}{}\n", gutter, indent, "^".repeat(width)));
+    out
+}
+
+/// 依次渲染多处错误，之间空一行，用于 `Parser::parse_rules_recovering` 的结果
+pub fn render_all(source: &str, name: &str, errors: &[SyntaxError]) -> String {
+    errors
+        .iter()
+        .map(|e| render(source, name, e))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,111 @@
+use once_cell::sync::Lazy;
+
+use crate::token::{Token, tokenize};
+
+/// -----------------------------
+/// DslMacro
+/// -----------------------------
+/// 在字段列表中展开为一组字段的宏，如 `(...timestamps(), name:string)`。
+/// 宏体是不带外层括号的字段列表，其中的 `{参数名}` 在展开时替换为实参，
+/// 未传入的参数使用默认值。DSL 中以 `macro name(p, q=1) = (...)` 定义
+#[derive(Debug, Clone, PartialEq)]
+pub struct DslMacro {
+    pub name: String,
+    /// 参数名与默认值，没有默认值的参数必须在调用时传入
+    pub params: Vec<(String, Option<Token>)>,
+    pub body: Vec<Token>,
+}
+
+impl DslMacro {
+    /// 宏体中出现了未声明的参数时返回错误
+    pub fn new(
+        name: &str,
+        params: Vec<(String, Option<Token>)>,
+        body: Vec<Token>,
+    ) -> Result<Self, String> {
+        for token in &body {
+            if let Token::Placeholder(p) = token
+                && !params.iter().any(|(name, _)| name == p)
+            {
+                return Err(format!("Unknown parameter '{}' in macro {}", p, name));
+            }
+        }
+        Ok(Self {
+            name: name.into(),
+            params,
+            body,
+        })
+    }
+
+    /// 用实参替换宏体中的参数，返回可以直接解析的字段列表 `( ... )`
+    pub fn expand(&self, args: &[(String, Token)]) -> Result<Vec<Token>, String> {
+        for (i, (key, _)) in args.iter().enumerate() {
+            if !self.params.iter().any(|(p, _)| p == key) {
+                return Err(format!(
+                    "Unknown parameter '{}' for macro {}",
+                    key, self.name
+                ));
+            }
+            if args[..i].iter().any(|(k, _)| k == key) {
+                return Err(format!(
+                    "Duplicate parameter '{}' for macro {}",
+                    key, self.name
+                ));
+            }
+        }
+        let mut values = Vec::with_capacity(self.params.len());
+        for (param, default) in &self.params {
+            let value = args
+                .iter()
+                .find(|(k, _)| k == param)
+                .map(|(_, v)| v)
+                .or(default.as_ref())
+                .ok_or_else(|| format!("Missing parameter '{}' for macro {}", param, self.name))?;
+            values.push((param, value));
+        }
+        let mut tokens = Vec::with_capacity(self.body.len() + 2);
+        tokens.push(Token::LParen);
+        for token in &self.body {
+            match token {
+                Token::Placeholder(p) => match values.iter().find(|(name, _)| *name == p) {
+                    Some((_, value)) => tokens.push((*value).clone()),
+                    None => {
+                        return Err(format!("Unknown parameter '{}' in macro {}", p, self.name));
+                    }
+                },
+                t => tokens.push(t.clone()),
+            }
+        }
+        tokens.push(Token::RParen);
+        Ok(tokens)
+    }
+}
+
+/// 排序参数：逗号分隔的字段名，可带 `-` 表示降序，如 `-created_at,name`
+const SORT_PATTERN: &str = r"^-?[A-Za-z_][A-Za-z0-9_.]*(,-?[A-Za-z_][A-Za-z0-9_.]*)*$";
+
+static BUILTINS: Lazy<Vec<DslMacro>> = Lazy::new(|| {
+    let number = |n: &str| Some(Token::Number(n.into()));
+    let body = format!(
+        "page: int[1, {{max_page}}] = 1, limit: int[1, {{max_limit}}] = {{default_limit}}, \
+         sort?: string[1, 200] regex({:?})",
+        SORT_PATTERN
+    );
+    vec![
+        DslMacro::new(
+            "pagination",
+            vec![
+                ("max_limit".into(), number("100")),
+                ("default_limit".into(), number("20")),
+                ("max_page".into(), number("1000000")),
+            ],
+            tokenize(&body).expect("invalid builtin macro"),
+        )
+        .expect("invalid builtin macro"),
+    ]
+});
+
+/// 按名称取出内置宏，目前只有 `pagination`
+pub fn builtin(name: &str) -> Option<&'static DslMacro> {
+    BUILTINS.iter().find(|m| m.name == name)
+}
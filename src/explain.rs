@@ -0,0 +1,67 @@
+use crate::ast::{Constraint, FieldRule, find_rule};
+
+/// -----------------------------
+/// Explain
+/// -----------------------------
+/// 输出路径对应规则的可读说明（类型、约束、默认值、是否必填）
+pub fn explain(rules: &[FieldRule], path: &str) -> Result<String, String> {
+    let rule = find_rule(rules, path).ok_or_else(|| format!("No rule found for '{}'", path))?;
+
+    let mut out = String::new();
+    out.push_str(&format!("field:       {}\n", path));
+    out.push_str(&format!("type:        {}\n", describe_type(rule)));
+    out.push_str(&format!("required:    {}\n", rule.required));
+    out.push_str(&format!(
+        "default:     {}\n",
+        rule.default
+            .as_ref()
+            .map(|d| d.to_string())
+            .or_else(|| rule.default_expr().map(|e| e.to_string()))
+            .unwrap_or_else(|| "-".into())
+    ));
+    if let Some(values) = &rule.enum_values {
+        let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        out.push_str(&format!("enum:        {}\n", values.join(", ")));
+    }
+    // 默认值表达式已在 default 一行列出
+    let items: Vec<String> = rule
+        .constraints
+        .iter()
+        .flat_map(|c| &c.items)
+        .filter(|c| !matches!(c, Constraint::DefaultFrom(_)))
+        .map(|c| c.to_string())
+        .collect();
+    if items.is_empty() {
+        out.push_str("constraints: -\n");
+    } else {
+        out.push_str(&format!("constraints: {}\n", items.join(" ")));
+    }
+    if let Some(children) = &rule.children {
+        let names: Vec<&str> = children.iter().map(|c| c.field.as_str()).collect();
+        out.push_str(&format!("fields:      {}\n", names.join(", ")));
+    }
+    Ok(out)
+}
+
+fn describe_type(rule: &FieldRule) -> String {
+    let base = match &rule.union_types {
+        Some(types) => types
+            .iter()
+            .map(|t| t.to_string())
+            .collect::<Vec<_>>()
+            .join("|"),
+        None => rule.field_type.to_string(),
+    };
+    match &rule.rule {
+        Some(sub) => {
+            let mut inner = describe_type(sub);
+            if let Some(c) = &sub.constraints {
+                for item in &c.items {
+                    inner.push_str(&format!(" {}", item));
+                }
+            }
+            format!("{}<{}>", base, inner)
+        }
+        None => base,
+    }
+}
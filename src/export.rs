@@ -0,0 +1,459 @@
+use serde_json::{Map, json};
+
+use crate::ast::{Constraint, FieldRule, FieldType, Metadata, Value};
+
+/// -----------------------------
+/// DSL
+/// -----------------------------
+/// 将规则重新输出为 DSL 文本，可被 `Parser::parse_rules` 解析回相同的规则
+pub fn to_dsl(rules: &[FieldRule]) -> String {
+    to_dsl_with(rules, &Metadata::new())
+}
+
+/// 非空的 metadata 输出为开头的 `meta(...)` 头部
+pub(crate) fn to_dsl_with(rules: &[FieldRule], metadata: &Metadata) -> String {
+    let mut out = String::new();
+    if !metadata.is_empty() {
+        out.push_str(&format!("{}\n", metadata));
+    }
+    out.push_str("(\n");
+    write_dsl_fields(&mut out, rules, 1);
+    out.push(')');
+    out
+}
+
+fn write_dsl_fields(out: &mut String, rules: &[FieldRule], depth: usize) {
+    for (i, rule) in rules.iter().enumerate() {
+        out.push_str(&"    ".repeat(depth));
+        out.push_str(&rule.field);
+        if !rule.required {
+            out.push('?');
+        }
+        out.push(':');
+        write_dsl_rule(out, rule, depth);
+        if i + 1 < rules.len() {
+            out.push(',');
+        }
+        out.push('\n');
+    }
+}
+
+/// 输出类型及其后缀（子规则、子字段、约束、枚举、默认值），不含字段名
+fn write_dsl_rule(out: &mut String, rule: &FieldRule, depth: usize) {
+    let types = match &rule.union_types {
+        Some(types) => types.clone(),
+        None => vec![rule.field_type.clone()],
+    };
+    let names: Vec<String> = types.iter().map(|t| t.to_string()).collect();
+    out.push_str(&names.join("|"));
+
+    if let Some(sub) = &rule.rule {
+        out.push('<');
+        write_dsl_rule(out, sub, depth);
+        out.push('>');
+    }
+    if let Some(children) = &rule.children {
+        out.push_str("(\n");
+        write_dsl_fields(out, children, depth + 1);
+        out.push_str(&"    ".repeat(depth));
+        out.push(')');
+    }
+    if let Some(c) = &rule.constraints {
+        for item in &c.items {
+            match item {
+                Constraint::Range { .. } | Constraint::Currency(_) => {
+                    out.push_str(&item.to_string())
+                }
+                _ => {
+                    out.push(' ');
+                    out.push_str(&item.to_string());
+                }
+            }
+        }
+    }
+    if let Some(values) = &rule.enum_values {
+        let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        out.push_str(&format!(" enum({})", values.join(", ")));
+    }
+    if let Some(default) = &rule.default {
+        out.push_str(&format!(" = {}", default));
+    }
+}
+
+/// -----------------------------
+/// JSON Schema
+/// -----------------------------
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// 没有标准 JSON Schema 表示的类型通过该扩展关键字保留原始 DSL 类型
+pub const VDSL_TYPE_KEYWORD: &str = "x-vdsl-type";
+
+/// 没有标准 JSON Schema 表示的约束以 DSL 文本列表保留，如 `["check(\"luhn\")", "past"]`
+pub const VDSL_CONSTRAINTS_KEYWORD: &str = "x-vdsl-constraints";
+
+/// schema 的 `meta(...)` 信息，如 `{"name": "CreateUser", "version": 3}`
+pub const VDSL_META_KEYWORD: &str = "x-vdsl-meta";
+
+/// 输出 JSON Schema (draft 2020-12)
+pub fn to_json_schema(rules: &[FieldRule]) -> serde_json::Value {
+    to_json_schema_with(rules, &Metadata::new())
+}
+
+pub(crate) fn to_json_schema_with(rules: &[FieldRule], metadata: &Metadata) -> serde_json::Value {
+    let mut schema = object_schema(rules, metadata);
+    if let serde_json::Value::Object(map) = &mut schema {
+        map.insert("$schema".into(), json!(JSON_SCHEMA_DIALECT));
+    }
+    schema
+}
+
+/// 对象的 schema；metadata 只用于顶层，`name` 作为 `title`，全部项放在扩展关键字中
+fn object_schema(rules: &[FieldRule], metadata: &Metadata) -> serde_json::Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for rule in rules {
+        properties.insert(rule.field.to_string(), rule_schema(rule));
+        if (rule.required && !rule.has_default()) || rule.must_be_present() {
+            required.push(json!(rule.field.as_str()));
+        }
+    }
+    let mut schema = Map::new();
+    if let Some(name) = metadata.name() {
+        schema.insert("title".into(), json!(name));
+    }
+    if !metadata.is_empty() {
+        let meta: Map<String, serde_json::Value> = metadata
+            .iter()
+            .map(|(k, v)| (k.to_string(), value_to_json(v)))
+            .collect();
+        schema.insert(VDSL_META_KEYWORD.into(), serde_json::Value::Object(meta));
+    }
+    schema.insert("type".into(), json!("object"));
+    schema.insert("properties".into(), serde_json::Value::Object(properties));
+    if !required.is_empty() {
+        schema.insert("required".into(), serde_json::Value::Array(required));
+    }
+    serde_json::Value::Object(schema)
+}
+
+fn rule_schema(rule: &FieldRule) -> serde_json::Value {
+    let mut schema = match &rule.union_types {
+        Some(types) => {
+            let branches: Vec<serde_json::Value> = types
+                .iter()
+                .map(|t| serde_json::Value::Object(type_schema(t, rule)))
+                .collect();
+            let mut map = Map::new();
+            map.insert("anyOf".into(), serde_json::Value::Array(branches));
+            map
+        }
+        None => type_schema(&rule.field_type, rule),
+    };
+
+    let mut patterns = Vec::new();
+    let mut extra = Vec::new();
+    if let Some(c) = &rule.constraints {
+        for item in &c.items {
+            match item {
+                Constraint::Range {
+                    min,
+                    max,
+                    min_inclusive,
+                    max_inclusive,
+                } => {
+                    if matches!(
+                        rule.field_type,
+                        FieldType::Date | FieldType::DateTime | FieldType::Money
+                    ) {
+                        // JSON Schema 没有日期区间关键字，money 的区间作用于 amount，保留为扩展约束
+                        extra.push(json!(item.to_string()));
+                    } else if is_length_type(&rule.field_type) {
+                        let lo = length_bound(min) + if *min_inclusive { 0 } else { 1 };
+                        let hi = length_bound(max) - if *max_inclusive { 0 } else { 1 };
+                        schema.insert("minLength".into(), json!(lo.max(0)));
+                        schema.insert("maxLength".into(), json!(hi.max(0)));
+                    } else {
+                        let (lo_key, hi_key) = (
+                            if *min_inclusive {
+                                "minimum"
+                            } else {
+                                "exclusiveMinimum"
+                            },
+                            if *max_inclusive {
+                                "maximum"
+                            } else {
+                                "exclusiveMaximum"
+                            },
+                        );
+                        schema.insert(lo_key.into(), value_to_json(min));
+                        schema.insert(hi_key.into(), value_to_json(max));
+                    }
+                }
+                Constraint::Regex(pattern) => patterns.push(pattern.clone()),
+                Constraint::MultipleOf(divisor) => {
+                    schema.insert("multipleOf".into(), value_to_json(divisor));
+                }
+                other => extra.push(json!(other.to_string())),
+            }
+        }
+    }
+    match patterns.len() {
+        0 => {}
+        1 => {
+            schema.insert("pattern".into(), json!(patterns[0]));
+        }
+        _ => {
+            let all: Vec<serde_json::Value> =
+                patterns.iter().map(|p| json!({ "pattern": p })).collect();
+            schema.insert("allOf".into(), serde_json::Value::Array(all));
+        }
+    }
+    if !extra.is_empty() {
+        schema.insert(
+            VDSL_CONSTRAINTS_KEYWORD.into(),
+            serde_json::Value::Array(extra),
+        );
+    }
+    if let Some(values) = &rule.enum_values {
+        schema.insert(
+            "enum".into(),
+            serde_json::Value::Array(values.iter().map(value_to_json).collect()),
+        );
+    }
+    if let Some(default) = &rule.default {
+        schema.insert("default".into(), value_to_json(default));
+    }
+    serde_json::Value::Object(schema)
+}
+
+fn type_schema(t: &FieldType, rule: &FieldRule) -> Map<String, serde_json::Value> {
+    let mut map = Map::new();
+    let (ty, format) = match t {
+        FieldType::String => ("string", None),
+        FieldType::Int => ("integer", None),
+        FieldType::Float => ("number", None),
+        FieldType::Bool => ("boolean", None),
+        FieldType::Object => {
+            return match &rule.children {
+                Some(children) => match object_schema(children, &Metadata::new()) {
+                    serde_json::Value::Object(m) => m,
+                    _ => Map::new(),
+                },
+                None => {
+                    map.insert("type".into(), json!("object"));
+                    map
+                }
+            };
+        }
+        FieldType::Array => {
+            map.insert("type".into(), json!("array"));
+            if let Some(sub) = &rule.rule {
+                map.insert("items".into(), rule_schema(sub));
+            }
+            return map;
+        }
+        FieldType::Custom(pattern) => {
+            map.insert("type".into(), json!("string"));
+            map.insert("pattern".into(), json!(pattern));
+            return map;
+        }
+        FieldType::Email => ("string", Some("email")),
+        FieldType::Uri => ("string", Some("uri")),
+        FieldType::Uuid => ("string", Some("uuid")),
+        FieldType::UuidOf(spec) => {
+            map.insert("type".into(), json!("string"));
+            if !spec.compact {
+                map.insert("format".into(), json!("uuid"));
+            }
+            map.insert(VDSL_TYPE_KEYWORD.into(), json!(t.to_string()));
+            return map;
+        }
+        FieldType::Date => ("string", Some("date")),
+        FieldType::DateTime => ("string", Some("date-time")),
+        FieldType::Time => ("string", Some("time")),
+        FieldType::Hostname => ("string", Some("hostname")),
+        FieldType::HostnameOf(_) => {
+            map.insert("type".into(), json!("string"));
+            map.insert(VDSL_TYPE_KEYWORD.into(), json!(t.to_string()));
+            return map;
+        }
+        FieldType::Money => {
+            map.insert("type".into(), json!("object"));
+            map.insert(
+                "properties".into(),
+                json!({
+                    "amount": { "type": ["number", "string"] },
+                    "currency": { "type": "string", "pattern": "^[A-Z]{3}$" },
+                }),
+            );
+            map.insert("required".into(), json!(["amount", "currency"]));
+            map.insert("additionalProperties".into(), json!(false));
+            map.insert(VDSL_TYPE_KEYWORD.into(), json!(t.to_string()));
+            return map;
+        }
+        FieldType::Timestamp => {
+            map.insert("type".into(), json!("integer"));
+            map.insert(VDSL_TYPE_KEYWORD.into(), json!(t.to_string()));
+            return map;
+        }
+        other => {
+            map.insert("type".into(), json!("string"));
+            map.insert(VDSL_TYPE_KEYWORD.into(), json!(other.to_string()));
+            return map;
+        }
+    };
+    map.insert("type".into(), json!(ty));
+    if let Some(format) = format {
+        map.insert("format".into(), json!(format));
+    }
+    map
+}
+
+/// 范围约束作用于长度的类型
+fn is_length_type(t: &FieldType) -> bool {
+    !matches!(
+        t,
+        FieldType::Int | FieldType::Float | FieldType::Timestamp | FieldType::Bool
+    )
+}
+
+fn length_bound(v: &Value) -> i64 {
+    match v {
+        Value::Int(i) => *i,
+        Value::Float(f) => *f as i64,
+        Value::String(s) => s.parse().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+fn value_to_json(v: &Value) -> serde_json::Value {
+    serde_json::Value::from(v)
+}
+
+/// -----------------------------
+/// OpenAPI
+/// -----------------------------
+/// 输出仅包含 `components.schemas.<name>` 的 OpenAPI 3.1 文档
+pub fn to_openapi(rules: &[FieldRule], name: &str) -> serde_json::Value {
+    to_openapi_with(rules, name, &Metadata::new())
+}
+
+/// metadata 的 `version` 作为文档版本
+pub(crate) fn to_openapi_with(
+    rules: &[FieldRule],
+    name: &str,
+    metadata: &Metadata,
+) -> serde_json::Value {
+    let version = metadata
+        .version()
+        .map(plain_text)
+        .unwrap_or_else(|| "1.0.0".into());
+    json!({
+        "openapi": "3.1.0",
+        "info": { "title": name, "version": version },
+        "paths": {},
+        "components": { "schemas": { name: object_schema(rules, metadata) } }
+    })
+}
+
+/// 字符串不加引号
+fn plain_text(v: &Value) -> String {
+    v.as_str().map_or_else(|| v.to_string(), str::to_string)
+}
+
+/// -----------------------------
+/// TypeScript
+/// -----------------------------
+/// 输出描述输入数据的 TypeScript interface，有默认值的字段视为可选
+pub fn to_typescript(rules: &[FieldRule], name: &str) -> String {
+    to_typescript_with(rules, name, &Metadata::new())
+}
+
+/// metadata 输出为 interface 前的文档注释
+pub(crate) fn to_typescript_with(rules: &[FieldRule], name: &str, metadata: &Metadata) -> String {
+    let mut out = String::new();
+    if !metadata.is_empty() {
+        out.push_str("/**\n");
+        for (k, v) in metadata.iter() {
+            out.push_str(&format!(" * {}: {}\n", k, plain_text(v)));
+        }
+        out.push_str(" */\n");
+    }
+    out.push_str(&format!("export interface {} ", name));
+    write_ts_object(&mut out, rules, 0);
+    out.push('\n');
+    out
+}
+
+fn write_ts_object(out: &mut String, rules: &[FieldRule], depth: usize) {
+    out.push_str("{\n");
+    for rule in rules {
+        out.push_str(&"  ".repeat(depth + 1));
+        out.push_str(&ts_key(&rule.field));
+        if (!rule.required || rule.has_default()) && !rule.must_be_present() {
+            out.push('?');
+        }
+        out.push_str(": ");
+        if rule.must_be_absent() {
+            out.push_str("never");
+        } else {
+            write_ts_type(out, rule, depth + 1);
+        }
+        out.push_str(";\n");
+    }
+    out.push_str(&"  ".repeat(depth));
+    out.push('}');
+}
+
+fn write_ts_type(out: &mut String, rule: &FieldRule, depth: usize) {
+    if let Some(values) = &rule.enum_values {
+        let literals: Vec<String> = values
+            .iter()
+            .map(|v| value_to_json(v).to_string())
+            .collect();
+        out.push_str(&literals.join(" | "));
+        return;
+    }
+    let types = match &rule.union_types {
+        Some(types) => types.clone(),
+        None => vec![rule.field_type.clone()],
+    };
+    for (i, t) in types.iter().enumerate() {
+        if i > 0 {
+            out.push_str(" | ");
+        }
+        match t {
+            FieldType::Int | FieldType::Float | FieldType::Timestamp => out.push_str("number"),
+            FieldType::Bool => out.push_str("boolean"),
+            FieldType::Money => out.push_str("{ amount: number | string; currency: string }"),
+            FieldType::Object => match &rule.children {
+                Some(children) => write_ts_object(out, children, depth),
+                None => out.push_str("Record<string, unknown>"),
+            },
+            FieldType::Array => match &rule.rule {
+                Some(sub) => {
+                    out.push_str("Array<");
+                    write_ts_type(out, sub, depth);
+                    out.push('>');
+                }
+                None => out.push_str("unknown[]"),
+            },
+            _ => out.push_str("string"),
+        }
+    }
+}
+
+fn ts_key(name: &str) -> String {
+    let plain = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if plain {
+        name.to_string()
+    } else {
+        json!(name).to_string()
+    }
+}
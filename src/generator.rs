@@ -0,0 +1,507 @@
+use crate::{
+    ast::{Constraint, FieldRule, FieldType, ObjectMap, Value},
+    clock::{format_instant, instant_of},
+    validator::{validate, validate_field},
+};
+
+/// 整条数据的最大重试次数
+const MAX_ATTEMPTS: usize = 64;
+/// 单个字段的最大重试次数，需覆盖正则穷举的 字符集 × 长度 组合
+const FIELD_ATTEMPTS: usize = 128;
+
+const LOWER: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const UPPER: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &[u8] = b"0123456789";
+const ALNUM: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+const HEX: &[u8] = b"0123456789abcdef";
+const CROCKFORD: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const NANOID: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_-";
+/// 自定义正则时依次尝试的字符集
+const CHARSETS: &[&[u8]] = &[LOWER, ALNUM, DIGITS, UPPER, b"abc_123", HEX];
+
+/// -----------------------------
+/// Generator
+/// -----------------------------
+/// 根据规则生成测试数据。相同的种子总是产生相同的数据序列。
+/// 生成结果都会经过校验器确认：`valid` 一定能通过校验，`invalid` 一定不能。
+pub struct Generator {
+    state: u64,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: seed ^ 0x9E37_79B9_7F4A_7C15,
+        }
+    }
+
+    /// 生成一条能通过校验的数据
+    pub fn valid(&mut self, rules: &[FieldRule]) -> Result<Value, String> {
+        for _ in 0..MAX_ATTEMPTS {
+            let value = self.object(rules)?;
+            if validate(&value, rules).is_some() {
+                return Ok(value);
+            }
+        }
+        Err("Unable to generate a valid payload".into())
+    }
+
+    /// 生成一条不能通过校验的数据：在合法数据上删除必填字段、替换类型或越界
+    pub fn invalid(&mut self, rules: &[FieldRule]) -> Result<Value, String> {
+        if rules.is_empty() {
+            return Err("Cannot generate an invalid payload for an empty schema".into());
+        }
+        for _ in 0..MAX_ATTEMPTS {
+            let mut value = self.valid(rules)?;
+            self.corrupt(&mut value, rules);
+            if validate(&value, rules).is_none() {
+                return Ok(value);
+            }
+        }
+        Err("Unable to generate an invalid payload".into())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        // xorshift64*
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// [lo, hi] 闭区间内的随机整数
+    fn between(&mut self, lo: i64, hi: i64) -> i64 {
+        if hi <= lo {
+            return lo;
+        }
+        let span = (hi as i128 - lo as i128 + 1) as u128;
+        (lo as i128 + (self.next_u64() as u128 % span) as i128) as i64
+    }
+
+    fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn chance(&mut self, p: f64) -> bool {
+        self.unit() < p
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.between(0, items.len() as i64 - 1) as usize]
+    }
+
+    fn chars(&mut self, charset: &[u8], len: usize) -> String {
+        (0..len).map(|_| *self.pick(charset) as char).collect()
+    }
+
+    fn digits(&mut self, len: usize) -> String {
+        self.chars(DIGITS, len)
+    }
+
+    fn object(&mut self, rules: &[FieldRule]) -> Result<Value, String> {
+        let mut map = ObjectMap::new();
+        for rule in rules {
+            if rule.must_be_absent()
+                || (!rule.required && !rule.must_be_present() && !self.chance(0.5))
+            {
+                continue;
+            }
+            map.insert(rule.field.to_string(), self.field(rule)?);
+        }
+        Ok(Value::Object(map))
+    }
+
+    /// 生成单个字段的值，并用该字段规则确认合法
+    fn field(&mut self, rule: &FieldRule) -> Result<Value, String> {
+        if let Some(values) = &rule.enum_values
+            && !values.is_empty()
+        {
+            return Ok(self.pick(values).clone());
+        }
+
+        for attempt in 0..FIELD_ATTEMPTS {
+            let ty = match &rule.union_types {
+                Some(types) => self.pick(types).clone(),
+                None => rule.field_type.clone(),
+            };
+            let value = self.typed(&ty, rule, attempt)?;
+            let mut wrapper = Value::Object(ObjectMap::from([(rule.field.to_string(), value)]));
+            if validate_field(&mut wrapper, rule).is_ok()
+                && let Value::Object(mut map) = wrapper
+                && let Some(value) = map.remove(rule.field.as_str())
+            {
+                return Ok(value);
+            }
+        }
+        Err(format!(
+            "Unable to generate a value for '{}' ({})",
+            rule.field, rule.field_type
+        ))
+    }
+
+    fn typed(&mut self, ty: &FieldType, rule: &FieldRule, attempt: usize) -> Result<Value, String> {
+        let range = rule.constraints.as_ref().and_then(|c| {
+            c.items.iter().find_map(|item| match item {
+                Constraint::Range {
+                    min,
+                    max,
+                    min_inclusive,
+                    max_inclusive,
+                } => Some((min, max, *min_inclusive, *max_inclusive)),
+                _ => None,
+            })
+        });
+        let has_pattern = matches!(ty, FieldType::Custom(_))
+            || rule.constraints.as_ref().is_some_and(|c| {
+                c.items
+                    .iter()
+                    .any(|item| matches!(item, Constraint::Regex(_)))
+            });
+
+        let value = match ty {
+            FieldType::String | FieldType::Password | FieldType::Token | FieldType::Custom(_) => {
+                if has_pattern {
+                    // 有正则时按 字符集 × 长度 依次穷举
+                    let (lo, hi) = int_bounds(range, 1, 16);
+                    let (lo, hi) = (lo.max(0), hi.max(0));
+                    let step = (attempt / CHARSETS.len()) as i64;
+                    let len = (lo + step % (hi - lo + 1).max(1)) as usize;
+                    Value::String(self.chars(CHARSETS[attempt % CHARSETS.len()], len))
+                } else {
+                    let (lo, hi) = int_bounds(range, 3, 12);
+                    let len = self.between(lo.max(0), hi.max(0)) as usize;
+                    Value::String(self.chars(ALNUM, len))
+                }
+            }
+            FieldType::Alpha => {
+                let (lo, hi) = int_bounds(range, 3, 12);
+                let len = self.between(lo.max(1), hi.max(1)) as usize;
+                Value::String(self.chars(LOWER, len))
+            }
+            FieldType::Alphanumeric => {
+                let (lo, hi) = int_bounds(range, 3, 12);
+                let len = self.between(lo.max(1), hi.max(1)) as usize;
+                Value::String(self.chars(ALNUM, len))
+            }
+            FieldType::Int => {
+                let (lo, hi) = int_bounds(range, 0, 1000);
+                Value::Int(self.between(lo, hi))
+            }
+            FieldType::Float => {
+                let (lo, hi) = match range {
+                    Some((min, max, _, _)) => {
+                        (as_f64(min).unwrap_or(0.0), as_f64(max).unwrap_or(1000.0))
+                    }
+                    None => (0.0, 1000.0),
+                };
+                Value::Float(lo + (hi - lo) * self.unit())
+            }
+            FieldType::Bool => Value::Bool(self.chance(0.5)),
+            FieldType::Object => match &rule.children {
+                Some(children) => self.object(children)?,
+                None => Value::Object(ObjectMap::new()),
+            },
+            FieldType::Array => match &rule.rule {
+                Some(sub) => {
+                    let len = self.between(1, 3);
+                    let mut items = Vec::new();
+                    for _ in 0..len {
+                        items.push(self.field(sub)?);
+                    }
+                    Value::Array(items)
+                }
+                None => Value::Array(Vec::new()),
+            },
+            FieldType::Email => {
+                let user = self.chars(LOWER, 6);
+                Value::String(format!("{}@example.com", user))
+            }
+            FieldType::Uri => {
+                let path = self.chars(LOWER, 8);
+                Value::String(format!("https://example.com/{}", path))
+            }
+            FieldType::Uuid => Value::String(format!(
+                "{}-{}-4{}-a{}-{}",
+                self.chars(HEX, 8),
+                self.chars(HEX, 4),
+                self.chars(HEX, 3),
+                self.chars(HEX, 3),
+                self.chars(HEX, 12)
+            )),
+            FieldType::Ip => Value::String(format!(
+                "{}.{}.{}.{}",
+                self.between(1, 254),
+                self.between(0, 255),
+                self.between(0, 255),
+                self.between(1, 254)
+            )),
+            FieldType::Mac => {
+                let parts: Vec<String> = (0..6).map(|_| self.chars(HEX, 2)).collect();
+                Value::String(parts.join(":"))
+            }
+            FieldType::Date | FieldType::DateTime if date_bounds(range).is_some() => {
+                let (lo, hi) = date_bounds(range).unwrap_or_default();
+                let date_only = *ty == FieldType::Date;
+                let secs = if date_only {
+                    // 取边界之间的某一天 00:00
+                    86400 * self.between((lo + 86399).div_euclid(86400), hi.div_euclid(86400))
+                } else {
+                    self.between(lo, hi)
+                };
+                Value::String(format_instant(secs, date_only))
+            }
+            FieldType::Date => Value::String(self.date()),
+            FieldType::DateTime => {
+                let date = self.date();
+                let time = self.time();
+                Value::String(format!("{}T{}Z", date, time))
+            }
+            FieldType::Time => Value::String(self.time()),
+            FieldType::Timestamp => Value::Int(self.between(1_600_000_000, 1_900_000_000)),
+            FieldType::Color => Value::String(format!("#{}", self.chars(HEX, 6))),
+            FieldType::ColorOf(spec) if spec.hex => {
+                let len = if spec.alpha { 8 } else { 6 };
+                Value::String(format!("#{}", self.chars(HEX, len)))
+            }
+            FieldType::ColorOf(spec) if spec.rgb => Value::String(format!(
+                "rgb({}, {}, {})",
+                self.between(0, 255),
+                self.between(0, 255),
+                self.between(0, 255)
+            )),
+            FieldType::ColorOf(_) => Value::String(format!(
+                "hsl({}, {}%, {}%)",
+                self.between(0, 359),
+                self.between(0, 100),
+                self.between(0, 100)
+            )),
+            FieldType::Hostname | FieldType::HostnameOf(_) => {
+                let label = self.chars(LOWER, 8);
+                Value::String(format!("{}.example.com", label))
+            }
+            FieldType::Slug => {
+                let a = self.chars(LOWER, 5);
+                let b = self.chars(LOWER, 5);
+                Value::String(format!("{}-{}", a, b))
+            }
+            FieldType::Hex => Value::String(self.chars(HEX, 16)),
+            FieldType::Base64 => Value::String(format!("{}==", self.chars(ALNUM, 10))),
+            FieldType::Phone => {
+                Value::String(format!("+{}{}", self.between(1, 9), self.digits(10)))
+            }
+            FieldType::CreditCard => Value::String(self.luhn_number(16)),
+            FieldType::ISBN => Value::String(format!("978{}", self.digits(10))),
+            FieldType::Port => Value::String(self.between(1, 65535).to_string()),
+            FieldType::SocketAddr => {
+                let host = self.chars(LOWER, 6);
+                Value::String(format!("{}.example.com:{}", host, self.between(1, 65535)))
+            }
+            FieldType::Money => {
+                let (lo, hi) = int_bounds(range, 0, 1000);
+                let allowed = rule.constraints.as_ref().and_then(|c| {
+                    c.items.iter().find_map(|item| match item {
+                        Constraint::Currency(codes) => Some(codes.clone()),
+                        _ => None,
+                    })
+                });
+                let currency = match allowed {
+                    Some(codes) if !codes.is_empty() => self.pick(&codes).clone(),
+                    _ => "USD".to_string(),
+                };
+                let mut money = ObjectMap::new();
+                money.insert("amount".into(), Value::Int(self.between(lo, hi)));
+                money.insert("currency".into(), Value::String(currency));
+                Value::Object(money)
+            }
+            FieldType::IpPort => Value::String(format!(
+                "10.{}.{}.{}:{}",
+                self.between(0, 255),
+                self.between(0, 255),
+                self.between(1, 254),
+                self.between(1, 65535)
+            )),
+            FieldType::Json => {
+                Value::String(format!("[{},{}]", self.between(0, 99), self.between(0, 99)))
+            }
+            FieldType::UrlEncoded => {
+                let a = self.chars(LOWER, 4);
+                let b = self.chars(LOWER, 4);
+                Value::String(format!("{}%20{}", a, b))
+            }
+            FieldType::Lat => Value::String(format!("{:.4}", -90.0 + 180.0 * self.unit())),
+            FieldType::Lng => Value::String(format!("{:.4}", -180.0 + 360.0 * self.unit())),
+            FieldType::SemVer => Value::String(format!(
+                "{}.{}.{}",
+                self.between(0, 9),
+                self.between(0, 20),
+                self.between(0, 50)
+            )),
+            FieldType::Username => {
+                let head = self.chars(LOWER, 1);
+                let tail = self.chars(ALNUM, 7);
+                Value::String(format!("{}{}", head, tail))
+            }
+            FieldType::CountryCode => Value::String(self.chars(UPPER, 2)),
+            FieldType::PostalCode => Value::String(self.digits(5)),
+            FieldType::FilePath => {
+                let name = self.chars(LOWER, 6);
+                Value::String(format!("/tmp/{}.txt", name))
+            }
+            FieldType::Ulid => {
+                let head = self.chars(b"01234567", 1);
+                let tail = self.chars(CROCKFORD, 25);
+                Value::String(format!("{}{}", head, tail))
+            }
+            FieldType::Ksuid => {
+                let head = self.chars(DIGITS, 1);
+                let tail = self.chars(ALNUM, 26);
+                Value::String(format!("{}{}", head, tail))
+            }
+            FieldType::NanoId(len) => Value::String(self.chars(NANOID, *len)),
+            FieldType::UuidOf(spec) => {
+                let uuid = format!(
+                    "{}-{}-{}{}-a{}-{}",
+                    self.chars(HEX, 8),
+                    self.chars(HEX, 4),
+                    spec.version.unwrap_or(4),
+                    self.chars(HEX, 3),
+                    self.chars(HEX, 3),
+                    self.chars(HEX, 12)
+                );
+                Value::String(if spec.compact && self.between(0, 1) == 1 {
+                    uuid.replace('-', "")
+                } else {
+                    uuid
+                })
+            }
+        };
+        Ok(value)
+    }
+
+    fn date(&mut self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}",
+            self.between(1970, 2037),
+            self.between(1, 12),
+            self.between(1, 28)
+        )
+    }
+
+    fn time(&mut self) -> String {
+        format!(
+            "{:02}:{:02}:{:02}",
+            self.between(0, 23),
+            self.between(0, 59),
+            self.between(0, 59)
+        )
+    }
+
+    /// 生成满足 Luhn 校验的卡号
+    fn luhn_number(&mut self, len: usize) -> String {
+        let mut digits: Vec<u32> = (0..len - 1).map(|_| self.between(0, 9) as u32).collect();
+        digits[0] = 4;
+        let mut sum = 0;
+        for (i, &d) in digits.iter().rev().enumerate() {
+            // 校验位在最右侧，因此从右往左数第一个已有数字需要翻倍
+            sum += if i % 2 == 0 {
+                if d > 4 { d * 2 - 9 } else { d * 2 }
+            } else {
+                d
+            };
+        }
+        digits.push((10 - sum % 10) % 10);
+        digits
+            .iter()
+            .map(|d| char::from_digit(*d, 10).unwrap_or('0'))
+            .collect()
+    }
+
+    /// 随机破坏一个字段：删除必填字段、替换为错误类型或超出范围
+    fn corrupt(&mut self, value: &mut Value, rules: &[FieldRule]) {
+        let Value::Object(map) = value else {
+            return;
+        };
+        let rule = self.pick(rules);
+
+        if let Some(children) = &rule.children
+            && let Some(child @ Value::Object(_)) = map.get_mut(rule.field.as_str())
+            && !children.is_empty()
+            && self.chance(0.5)
+        {
+            self.corrupt(child, children);
+            return;
+        }
+
+        match self.between(0, 2) {
+            0 if (rule.required && !rule.has_default()) || rule.must_be_present() => {
+                map.remove(rule.field.as_str());
+            }
+            1 => {
+                if let Some(out) = out_of_range(rule) {
+                    map.insert(rule.field.to_string(), out);
+                }
+            }
+            _ => {
+                let wrong = if rule.field_type == FieldType::Bool {
+                    Value::String("not-a-bool".into())
+                } else {
+                    Value::Bool(true)
+                };
+                map.insert(rule.field.to_string(), wrong);
+            }
+        }
+    }
+}
+
+/// 从范围约束得到整数闭区间（向内取整），没有约束时使用默认区间
+fn int_bounds(range: Option<(&Value, &Value, bool, bool)>, lo: i64, hi: i64) -> (i64, i64) {
+    let Some((min, max, min_inc, max_inc)) = range else {
+        return (lo, hi);
+    };
+    let mut lo = as_f64(min).map(|f| f.ceil() as i64).unwrap_or(lo);
+    let mut hi = as_f64(max).map(|f| f.floor() as i64).unwrap_or(hi);
+    if !min_inc {
+        lo = lo.saturating_add(1);
+    }
+    if !max_inc {
+        hi = hi.saturating_sub(1);
+    }
+    (lo, hi)
+}
+
+/// 日期区间两端的 Unix 秒
+fn date_bounds(range: Option<(&Value, &Value, bool, bool)>) -> Option<(i64, i64)> {
+    let (min, max, _, _) = range?;
+    let instant = |v: &Value| instant_of(v).map(|(t, _)| t);
+    instant(min).zip(instant(max))
+}
+
+fn as_f64(v: &Value) -> Option<f64> {
+    match v {
+        Value::Int(i) => Some(*i as f64),
+        Value::Float(f) => Some(*f),
+        _ => None,
+    }
+}
+
+/// 构造一个刚好超出范围约束上界的值
+fn out_of_range(rule: &FieldRule) -> Option<Value> {
+    let (max, max_inc) = rule
+        .constraints
+        .as_ref()?
+        .items
+        .iter()
+        .find_map(|c| match c {
+            Constraint::Range {
+                max, max_inclusive, ..
+            } => Some((max, *max_inclusive)),
+            _ => None,
+        })?;
+    let over = as_f64(max)? + if max_inc { 1.0 } else { 0.0 };
+    match rule.field_type {
+        FieldType::Int => Some(Value::Int(over.floor() as i64)),
+        FieldType::Float => Some(Value::Float(over)),
+        FieldType::String => Some(Value::String("x".repeat(over.max(0.0) as usize))),
+        _ => None,
+    }
+}
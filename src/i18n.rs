@@ -0,0 +1,340 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::ast::{Constraint, FieldRule};
+
+/// -----------------------------
+/// MessageResolver
+/// -----------------------------
+/// 把错误代码（[`ValidationError::code`](crate::validator::ValidationError::code)）与参数
+/// （[`ValidationError::params`](crate::validator::ValidationError::params)）翻译为面向用户的文本，
+/// 用于 [`ValidationError::localized`](crate::validator::ValidationError::localized)。
+/// 返回 `None` 时使用错误自身的英文文本
+pub trait MessageResolver: Send + Sync {
+    fn resolve(&self, code: &str, params: &[(&'static str, String)]) -> Option<String>;
+}
+
+/// -----------------------------
+/// Catalog
+/// -----------------------------
+/// 按错误代码保存消息模板，模板中的 `{field}`、`{value}` 等替换为同名参数，
+/// 没有对应参数的占位符原样保留：
+///
+/// ```toml
+/// missing_field = "缺少必填字段 {field}"
+/// out_of_range = "{field} 的值 {value} 不在 [{min}, {max}] 范围内"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Catalog {
+    templates: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从 TOML 读取，顶层每个键为错误代码，值为模板字符串
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let table: toml::Table = text.parse().map_err(|e: toml::de::Error| e.to_string())?;
+        let mut catalog = Self::new();
+        for (code, template) in table {
+            match template {
+                toml::Value::String(template) => catalog.insert(code, template),
+                other => {
+                    return Err(format!(
+                        "Message template for '{}' must be a string, got {}",
+                        code,
+                        other.type_str()
+                    ));
+                }
+            }
+        }
+        Ok(catalog)
+    }
+
+    pub fn with(mut self, code: impl Into<String>, template: impl Into<String>) -> Self {
+        self.insert(code, template);
+        self
+    }
+
+    pub fn insert(&mut self, code: impl Into<String>, template: impl Into<String>) {
+        self.templates.insert(code.into(), template.into());
+    }
+
+    pub fn get(&self, code: &str) -> Option<&str> {
+        self.templates.get(code).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.templates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+}
+
+impl MessageResolver for Catalog {
+    fn resolve(&self, code: &str, params: &[(&'static str, String)]) -> Option<String> {
+        self.get(code).map(|template| render(template, params))
+    }
+}
+
+/// -----------------------------
+/// Locales
+/// -----------------------------
+/// 按语言标签（如 `de`、`zh-CN`）保存的一组消息包，根据 HTTP `Accept-Language` 选出最合适的一个：
+/// 按 `q` 值从高到低尝试请求的语言，先找完全相同的标签，再逐段去掉子标签（`de-CH` -> `de`），
+/// 最后接受以请求语言为前缀的标签（请求 `zh` 时接受 `zh-CN`）；`*` 匹配回退语言，
+/// `q=0` 的语言不会被选中。都不匹配时使用回退语言，没有回退语言时使用英文原文
+#[derive(Clone, Default)]
+pub struct Locales {
+    bundles: Vec<(String, Arc<dyn MessageResolver>)>,
+    fallback: Option<String>,
+}
+
+impl std::fmt::Debug for Locales {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Locales")
+            .field("tags", &self.tags().collect::<Vec<_>>())
+            .field("fallback", &self.fallback)
+            .finish()
+    }
+}
+
+impl Locales {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加一个消息包，同一标签再次添加时替换原有的包；标签不区分大小写
+    pub fn with(mut self, tag: impl Into<String>, bundle: impl MessageResolver + 'static) -> Self {
+        self.insert(tag, bundle);
+        self
+    }
+
+    pub fn insert(&mut self, tag: impl Into<String>, bundle: impl MessageResolver + 'static) {
+        let tag = tag.into();
+        let bundle: Arc<dyn MessageResolver> = Arc::new(bundle);
+        match self
+            .bundles
+            .iter_mut()
+            .find(|(t, _)| t.eq_ignore_ascii_case(&tag))
+        {
+            Some(entry) => *entry = (tag, bundle),
+            None => self.bundles.push((tag, bundle)),
+        }
+    }
+
+    /// 没有请求的语言可用时使用的语言，必须是已添加的标签
+    pub fn fallback(mut self, tag: impl Into<String>) -> Self {
+        self.fallback = Some(tag.into());
+        self
+    }
+
+    /// 已添加的语言标签，按添加顺序排列
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.bundles.iter().map(|(tag, _)| tag.as_str())
+    }
+
+    pub fn get(&self, tag: &str) -> Option<&dyn MessageResolver> {
+        self.find(tag).map(|(_, bundle)| bundle.as_ref())
+    }
+
+    /// 为 `Accept-Language` 选出的语言标签（可用作响应的 `Content-Language`），
+    /// 没有可用的消息包时为 `None`
+    pub fn negotiate(&self, accept_language: &str) -> Option<&str> {
+        let matched = accept_language_ranges(accept_language)
+            .into_iter()
+            .find_map(|range| self.lookup(range));
+        matched
+            .or_else(|| self.find(self.fallback.as_deref()?))
+            .map(|(tag, _)| tag.as_str())
+    }
+
+    /// `negotiate` 选出的消息包
+    pub fn resolver(&self, accept_language: &str) -> Option<&dyn MessageResolver> {
+        self.get(self.negotiate(accept_language)?)
+    }
+
+    fn find(&self, tag: &str) -> Option<&(String, Arc<dyn MessageResolver>)> {
+        self.bundles
+            .iter()
+            .find(|(t, _)| t.eq_ignore_ascii_case(tag))
+    }
+
+    fn lookup(&self, range: &str) -> Option<&(String, Arc<dyn MessageResolver>)> {
+        if range == "*" {
+            let fallback = self.fallback.as_deref().and_then(|tag| self.find(tag));
+            return fallback.or(self.bundles.first());
+        }
+        let mut prefix = range;
+        loop {
+            if let Some(found) = self.find(prefix) {
+                return Some(found);
+            }
+            match prefix.rfind('-') {
+                Some(end) => prefix = &prefix[..end],
+                None => break,
+            }
+        }
+        self.bundles.iter().find(|(tag, _)| {
+            tag.len() > range.len()
+                && tag.as_bytes()[range.len()] == b'-'
+                && tag[..range.len()].eq_ignore_ascii_case(range)
+        })
+    }
+}
+
+/// 解析 `Accept-Language`，按 `q` 值从高到低排列（相同时保持原顺序），去掉 `q=0` 与无法解析的项
+fn accept_language_ranges(header: &str) -> Vec<&str> {
+    let mut ranges: Vec<(&str, f32)> = header
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let range = parts.next()?.trim();
+            let mut q = 1.0;
+            for param in parts {
+                if let Some(value) = param.trim().strip_prefix("q=") {
+                    q = value.trim().parse().ok()?;
+                }
+            }
+            (!range.is_empty() && q > 0.0).then_some((range, q))
+        })
+        .collect();
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranges.into_iter().map(|(range, _)| range).collect()
+}
+
+/// -----------------------------
+/// Labels
+/// -----------------------------
+/// 字段路径到 `label(...)` 的映射，翻译时作为参数 `{label}` 填入模板，
+/// 没有 label 的字段使用路径本身：
+///
+/// ```toml
+/// regex_mismatch = "{label} is invalid"
+/// ```
+///
+/// 数组元素不区分下标，`users[2].email` 与 `users[0].email` 取 `users[].email` 的 label
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Labels {
+    labels: HashMap<String, String>,
+}
+
+impl Labels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 收集规则（包括嵌套对象与数组元素）上的 `label(...)`
+    pub fn from_rules(rules: &[FieldRule]) -> Self {
+        let mut labels = Self::new();
+        labels.collect(rules, "");
+        labels
+    }
+
+    fn collect(&mut self, rules: &[FieldRule], prefix: &str) {
+        for rule in rules {
+            let path = match (prefix, rule.field.as_str()) {
+                ("", field) => field.to_string(),
+                (prefix, "") => format!("{}[]", prefix),
+                (prefix, field) => format!("{}.{}", prefix, field),
+            };
+            let label = rule
+                .constraints
+                .iter()
+                .flat_map(|c| &c.items)
+                .find_map(|c| match c {
+                    Constraint::Label(text) => Some(text),
+                    _ => None,
+                });
+            if let Some(label) = label {
+                self.insert(path.clone(), label.clone());
+            }
+            if let Some(sub) = &rule.rule {
+                self.collect(std::slice::from_ref(sub.as_ref()), &path);
+            }
+            if let Some(children) = &rule.children {
+                self.collect(children, &path);
+            }
+        }
+    }
+
+    /// `path` 中的数组元素写作 `[]`
+    pub fn insert(&mut self, path: impl Into<String>, label: impl Into<String>) {
+        self.labels.insert(path.into(), label.into());
+    }
+
+    /// 按错误中的路径查找，数组下标不参与比较
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.labels.get(&without_indices(path)).map(String::as_str)
+    }
+
+    /// 有 label 时返回 label，否则返回路径本身
+    pub fn label_for<'a>(&'a self, path: &'a str) -> &'a str {
+        self.get(path).unwrap_or(path)
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+}
+
+/// `users[2].tags[0]` -> `users[].tags[]`
+fn without_indices(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    let mut in_index = false;
+    for c in path.chars() {
+        match c {
+            '[' => {
+                in_index = true;
+                out.push(c);
+            }
+            ']' => {
+                in_index = false;
+                out.push(c);
+            }
+            _ if in_index => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// 替换模板中的 `{name}` 占位符
+pub fn render(template: &str, params: &[(&'static str, String)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let param = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            let (_, value) = params.iter().find(|(n, _)| *n == name)?;
+            Some((value, end))
+        });
+        match param {
+            Some((value, end)) => {
+                out.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+impl<T: MessageResolver + ?Sized> MessageResolver for std::sync::Arc<T> {
+    fn resolve(&self, code: &str, params: &[(&'static str, String)]) -> Option<String> {
+        (**self).resolve(code, params)
+    }
+}
@@ -0,0 +1,60 @@
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// -----------------------------
+/// IdGenerator
+/// -----------------------------
+/// `=uuid()` 默认值使用的标识生成器，测试时可替换为可预测的实现
+pub trait IdGenerator: fmt::Debug + Send + Sync {
+    /// 小写、带连字符的 uuid
+    fn uuid(&self) -> String;
+}
+
+/// 随机的 v4 uuid，熵取自标准库 `RandomState` 的随机种子，不适合用作安全令牌
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomIds;
+
+impl IdGenerator for RandomIds {
+    fn uuid(&self) -> String {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let state = RandomState::new();
+        let half = || {
+            let mut hasher = state.build_hasher();
+            hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+            hasher.finish()
+        };
+        format_v4(half(), half())
+    }
+}
+
+/// 依次生成 `00000000-0000-4000-8000-000000000001`、`...0002` 等
+#[derive(Debug, Default)]
+pub struct SequentialIds(AtomicU64);
+
+impl SequentialIds {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl IdGenerator for SequentialIds {
+    fn uuid(&self) -> String {
+        format_v4(0, self.0.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+}
+
+/// 128 位按 v4 设置版本与变体位后格式化
+fn format_v4(high: u64, low: u64) -> String {
+    let high = (high & !0xF000) | 0x4000;
+    let low = (low & !(0xC << 60)) | (0x8 << 60);
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        high >> 32,
+        (high >> 16) & 0xFFFF,
+        high & 0xFFFF,
+        low >> 48,
+        low & 0xFFFF_FFFF_FFFF
+    )
+}
@@ -0,0 +1,339 @@
+use serde_json::Map;
+
+use crate::{
+    ast::{Constraint, Constraints, FieldRule, FieldType, Metadata, Value},
+    export::{VDSL_CONSTRAINTS_KEYWORD, VDSL_META_KEYWORD, VDSL_TYPE_KEYWORD},
+    parser::Parser,
+};
+
+type JsonMap = Map<String, serde_json::Value>;
+
+/// -----------------------------
+/// JSON Schema 导入
+/// -----------------------------
+/// 将对象类型的 JSON Schema 转换为规则。
+/// 支持 type / format / pattern / enum / default / 长度与数值范围 / anyOf / items / properties，
+/// 以及 `export` 模块输出的 `x-vdsl-*` 扩展关键字。
+pub fn from_json_schema(schema: &serde_json::Value) -> Result<Vec<FieldRule>, String> {
+    let map = schema
+        .as_object()
+        .ok_or("JSON Schema root must be an object")?;
+    if !schema_types(map)?.iter().all(|t| t == "object") {
+        return Err("JSON Schema root must describe an object".into());
+    }
+    object_rules(map, "")
+}
+
+/// 读取 `export` 输出的 `x-vdsl-meta`，没有时为空
+pub fn metadata_from_json_schema(schema: &serde_json::Value) -> Result<Metadata, String> {
+    let mut metadata = Metadata::new();
+    let Some(meta) = schema.get(VDSL_META_KEYWORD) else {
+        return Ok(metadata);
+    };
+    let meta = meta
+        .as_object()
+        .ok_or_else(|| format!("{} must be an object", VDSL_META_KEYWORD))?;
+    for (key, value) in meta {
+        match Value::from(value.clone()) {
+            v @ (Value::Object(_) | Value::Array(_)) => {
+                return Err(format!(
+                    "{}: '{}' must be a string, number or bool, got {}",
+                    VDSL_META_KEYWORD, key, v
+                ));
+            }
+            v => {
+                metadata.insert(key.clone(), v);
+            }
+        }
+    }
+    Ok(metadata)
+}
+
+fn object_rules(map: &JsonMap, path: &str) -> Result<Vec<FieldRule>, String> {
+    let required: Vec<&str> = map
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+
+    let mut rules = Vec::new();
+    if let Some(properties) = map.get("properties") {
+        let properties = properties
+            .as_object()
+            .ok_or_else(|| format!("{}: 'properties' must be an object", display(path)))?;
+        for (name, prop) in properties {
+            let child_path = if path.is_empty() {
+                name.clone()
+            } else {
+                format!("{}.{}", path, name)
+            };
+            let mut rule = property_rule(prop, &child_path)?;
+            rule.field = name.as_str().into();
+            // 有默认值的字段缺失时会被填充，等价于必填
+            rule.required = required.contains(&name.as_str()) || rule.default.is_some();
+            rules.push(rule);
+        }
+    }
+    Ok(rules)
+}
+
+fn property_rule(schema: &serde_json::Value, path: &str) -> Result<FieldRule, String> {
+    let map = schema
+        .as_object()
+        .ok_or_else(|| format!("{}: schema must be an object", display(path)))?;
+    if map.contains_key("$ref") {
+        return Err(format!("{}: $ref is not supported", display(path)));
+    }
+
+    let mut rule = FieldRule {
+        field: "".into(),
+        field_type: FieldType::String,
+        required: true,
+        default: None,
+        enum_values: None,
+        union_types: None,
+        constraints: None,
+        rule: None,
+        children: None,
+        is_array: false,
+    };
+
+    let branches = map.get("anyOf").or_else(|| map.get("oneOf"));
+    let types = match branches {
+        Some(branches) => {
+            let branches = branches
+                .as_array()
+                .ok_or_else(|| format!("{}: anyOf must be an array", display(path)))?;
+            let mut types = Vec::new();
+            for branch in branches {
+                let branch = branch
+                    .as_object()
+                    .ok_or_else(|| format!("{}: anyOf entries must be objects", display(path)))?;
+                types.extend(field_types(branch, path)?);
+                merge_structure(&mut rule, branch, path)?;
+            }
+            types
+        }
+        None => field_types(map, path)?,
+    };
+    if types.is_empty() {
+        return Err(format!("{}: missing type", display(path)));
+    }
+
+    rule.field_type = types[0].clone();
+    rule.is_array = rule.field_type == FieldType::Array;
+    if types.len() > 1 {
+        rule.union_types = Some(types);
+    }
+    merge_structure(&mut rule, map, path)?;
+    if rule.field_type == FieldType::Money {
+        // money 导出的 properties 只用于说明结构
+        rule.children = None;
+    }
+
+    let mut constraints = Vec::new();
+    let length = map.contains_key("minLength") || map.contains_key("maxLength");
+    if length {
+        if !matches!(rule.field_type, FieldType::String) {
+            return Err(format!(
+                "{}: minLength/maxLength are only supported on plain strings",
+                display(path)
+            ));
+        }
+        constraints.push(Constraint::Range {
+            min: Value::Int(int_keyword(map, "minLength", path)?.unwrap_or(0)),
+            max: Value::Int(int_keyword(map, "maxLength", path)?.unwrap_or(i64::MAX)),
+            min_inclusive: true,
+            max_inclusive: true,
+        });
+    }
+    if let Some(range) = numeric_range(map, &rule.field_type, path)? {
+        constraints.push(range);
+    }
+    if let Some(pattern) = map.get("pattern").and_then(|p| p.as_str()) {
+        constraints.push(Constraint::Regex(pattern.to_string()));
+    }
+    if let Some(divisor) = map.get("multipleOf") {
+        match Value::from(divisor.clone()) {
+            d @ (Value::Int(_) | Value::Float(_)) => constraints.push(Constraint::MultipleOf(d)),
+            _ => return Err(format!("{}: multipleOf must be a number", display(path))),
+        }
+    }
+    if let Some(all) = map.get("allOf").and_then(|a| a.as_array()) {
+        for item in all {
+            if let Some(pattern) = item.get("pattern").and_then(|p| p.as_str()) {
+                constraints.push(Constraint::Regex(pattern.to_string()));
+            }
+        }
+    }
+    for text in string_list(map, VDSL_CONSTRAINTS_KEYWORD, path)? {
+        let parsed = Parser::parse_constraints(&text, &rule.field_type)
+            .map_err(|e| format!("{}: {}: {}", display(path), VDSL_CONSTRAINTS_KEYWORD, e))?;
+        constraints.extend(parsed);
+    }
+    if !constraints.is_empty() {
+        rule.constraints = Some(Constraints { items: constraints });
+    }
+
+    if let Some(values) = map.get("enum") {
+        let values = values
+            .as_array()
+            .ok_or_else(|| format!("{}: enum must be an array", display(path)))?;
+        rule.enum_values = Some(values.iter().cloned().map(Value::from).collect());
+    }
+    if let Some(default) = map.get("default") {
+        rule.default = Some(Value::from(default.clone()));
+    }
+    Ok(rule)
+}
+
+/// 子字段与数组元素规则
+fn merge_structure(rule: &mut FieldRule, map: &JsonMap, path: &str) -> Result<(), String> {
+    if map.contains_key("properties") {
+        rule.children = Some(object_rules(map, path)?);
+    }
+    if let Some(items) = map.get("items") {
+        let mut sub = property_rule(items, &format!("{}[]", path))?;
+        sub.required = true;
+        rule.rule = Some(Box::new(sub));
+    }
+    Ok(())
+}
+
+fn schema_types(map: &JsonMap) -> Result<Vec<String>, String> {
+    match map.get("type") {
+        None => Ok(Vec::new()),
+        Some(serde_json::Value::String(s)) => Ok(vec![s.clone()]),
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter(|t| t.as_str() != Some("null"))
+            .map(|t| {
+                t.as_str()
+                    .map(String::from)
+                    .ok_or_else(|| "type entries must be strings".to_string())
+            })
+            .collect(),
+        Some(other) => Err(format!("Invalid type {}", other)),
+    }
+}
+
+fn field_types(map: &JsonMap, path: &str) -> Result<Vec<FieldType>, String> {
+    if let Some(keyword) = map.get(VDSL_TYPE_KEYWORD).and_then(|v| v.as_str()) {
+        // 带参数的类型（如 `nanoid(10)`）交给 DSL 解析器
+        return FieldType::from_keyword(keyword)
+            .or_else(|| {
+                Parser::parse_rules(&format!("(x:{})", keyword))
+                    .ok()
+                    .and_then(|rules| rules.into_iter().next())
+                    .filter(|rule| rule.constraints.is_none())
+                    .map(|rule| rule.field_type)
+            })
+            .map(|t| vec![t])
+            .ok_or_else(|| {
+                format!(
+                    "{}: unknown {} '{}'",
+                    display(path),
+                    VDSL_TYPE_KEYWORD,
+                    keyword
+                )
+            });
+    }
+
+    let mut types = Vec::new();
+    for name in schema_types(map).map_err(|e| format!("{}: {}", display(path), e))? {
+        let t = match name.as_str() {
+            "string" => match map.get("format").and_then(|f| f.as_str()) {
+                Some("email") => FieldType::Email,
+                Some("uri") | Some("url") => FieldType::Uri,
+                Some("uuid") => FieldType::Uuid,
+                Some("date") => FieldType::Date,
+                Some("date-time") => FieldType::DateTime,
+                Some("time") => FieldType::Time,
+                Some("hostname") => FieldType::Hostname,
+                Some("ipv4") | Some("ipv6") => FieldType::Ip,
+                _ => FieldType::String,
+            },
+            "integer" => FieldType::Int,
+            "number" => FieldType::Float,
+            "boolean" => FieldType::Bool,
+            "object" => FieldType::Object,
+            "array" => FieldType::Array,
+            other => return Err(format!("{}: unsupported type '{}'", display(path), other)),
+        };
+        types.push(t);
+    }
+    Ok(types)
+}
+
+fn numeric_range(
+    map: &JsonMap,
+    field_type: &FieldType,
+    path: &str,
+) -> Result<Option<Constraint>, String> {
+    let bound = |inclusive: &str, exclusive: &str| -> Result<Option<(Value, bool)>, String> {
+        for (key, inclusive) in [(inclusive, true), (exclusive, false)] {
+            if let Some(v) = map.get(key) {
+                return match Value::from(v.clone()) {
+                    v @ (Value::Int(_) | Value::Float(_)) => Ok(Some((v, inclusive))),
+                    _ => Err(format!("{}: {} must be a number", display(path), key)),
+                };
+            }
+        }
+        Ok(None)
+    };
+    let min = bound("minimum", "exclusiveMinimum")?;
+    let max = bound("maximum", "exclusiveMaximum")?;
+    if min.is_none() && max.is_none() {
+        return Ok(None);
+    }
+    let (lo, hi) = match field_type {
+        FieldType::Int => (Value::Int(i64::MIN), Value::Int(i64::MAX)),
+        FieldType::Float => (Value::Float(f64::MIN), Value::Float(f64::MAX)),
+        _ => {
+            return Err(format!(
+                "{}: numeric bounds are only supported on numbers",
+                display(path)
+            ));
+        }
+    };
+    let (min, min_inclusive) = min.unwrap_or((lo, true));
+    let (max, max_inclusive) = max.unwrap_or((hi, true));
+    Ok(Some(Constraint::Range {
+        min,
+        max,
+        min_inclusive,
+        max_inclusive,
+    }))
+}
+
+/// 扩展关键字中的字符串数组
+fn string_list(map: &JsonMap, key: &str, path: &str) -> Result<Vec<String>, String> {
+    let Some(items) = map.get(key) else {
+        return Ok(Vec::new());
+    };
+    let items = items
+        .as_array()
+        .ok_or_else(|| format!("{}: {} must be an array", display(path), key))?;
+    items
+        .iter()
+        .map(|item| {
+            item.as_str()
+                .map(String::from)
+                .ok_or_else(|| format!("{}: {} entries must be strings", display(path), key))
+        })
+        .collect()
+}
+
+fn int_keyword(map: &JsonMap, key: &str, path: &str) -> Result<Option<i64>, String> {
+    match map.get(key) {
+        None => Ok(None),
+        Some(v) => v
+            .as_i64()
+            .map(Some)
+            .ok_or_else(|| format!("{}: {} must be an integer", display(path), key)),
+    }
+}
+
+fn display(path: &str) -> &str {
+    if path.is_empty() { "<root>" } else { path }
+}
@@ -0,0 +1,1134 @@
+use std::{cell::RefCell, fmt};
+
+use serde::{
+    Deserialize,
+    de::{
+        self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor,
+        value::SeqAccessDeserializer,
+    },
+};
+
+#[cfg(feature = "json-value")]
+use crate::{
+    ast::ObjectMap,
+    canonical::canonicalize_value,
+    refs::RefIndex,
+    report::{Change, ChangeKind, Normalization},
+    validator::Path,
+};
+use crate::{
+    ast::{Constraint, FieldRule, FieldType, Value},
+    compile::CompiledRule,
+    validator::{self, Budget, ValidationContext, ValidationError},
+};
+
+/// -----------------------------
+/// JSON 互转
+/// -----------------------------
+impl From<serde_json::Value> for Value {
+    fn from(json: serde_json::Value) -> Self {
+        match json {
+            // Value 没有空值类型：对象中的 null 视为字段缺失，其余位置视为空字符串
+            serde_json::Value::Null => Value::String(String::new()),
+            serde_json::Value::Bool(b) => Value::Bool(b),
+            serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => Value::Int(i),
+                (None, Some(u)) => Value::from_u64(u),
+                _ => number_literal(&n.to_string()),
+            },
+            serde_json::Value::String(s) => Value::String(s),
+            serde_json::Value::Array(a) => Value::Array(a.into_iter().map(Value::from).collect()),
+            serde_json::Value::Object(o) => Value::Object(
+                o.into_iter()
+                    .filter(|(_, v)| !v.is_null())
+                    .map(|(k, v)| (k, Value::from(v)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<&Value> for serde_json::Value {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::String(s) => serde_json::Value::String(s.clone()),
+            Value::Int(i) => serde_json::Value::from(*i),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(b) => b
+                .to_string()
+                .parse()
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null),
+            Value::Float(f) => serde_json::Number::from_f64(*f)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null),
+            Value::Bool(b) => serde_json::Value::Bool(*b),
+            Value::Object(map) => {
+                #[cfg_attr(not(feature = "ordered"), allow(unused_mut))]
+                let mut entries: Vec<_> = map.iter().collect();
+                // preserve_order 下 serde_json 的对象按插入顺序输出，先排好序保持字典序
+                #[cfg(feature = "ordered")]
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                serde_json::Value::Object(
+                    entries
+                        .into_iter()
+                        .map(|(k, v)| (k.clone(), serde_json::Value::from(v)))
+                        .collect(),
+                )
+            }
+            Value::Array(items) => {
+                serde_json::Value::Array(items.iter().map(serde_json::Value::from).collect())
+            }
+        }
+    }
+}
+
+/// 不在 i64 / u64 范围内的数字：开启 `bigint` 时整数保存为 `BigInt`，其余按 f64 解析
+fn number_literal(literal: &str) -> Value {
+    #[cfg(feature = "bigint")]
+    if let Some(integer) = Value::integer(literal) {
+        return integer;
+    }
+    Value::Float(literal.parse().unwrap_or(f64::NAN))
+}
+
+/// 解析 JSON 文本
+pub fn from_json_str(input: &str) -> Result<Value, String> {
+    serde_json::from_str::<serde_json::Value>(input)
+        .map(Value::from)
+        .map_err(|e| e.to_string())
+}
+
+/// 输出紧凑 JSON，对象键按字典序排列
+pub fn to_json_string(value: &Value) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+/// -----------------------------
+/// 规范化 JSON（RFC 8785 JCS）
+/// -----------------------------
+impl Value {
+    /// 按 RFC 8785 输出规范化的 JSON，相同的值总是得到相同的字节，可以直接签名或计算哈希：
+    /// 对象键按 UTF-16 码元排序，没有多余的空白，字符串只转义必须转义的字符，
+    /// 数字按 ECMAScript 的 `Number.prototype.toString` 输出。
+    ///
+    /// JCS 中的数字都是 IEEE 754 双精度数，超出 ±2^53 的整数会和 JavaScript 一样损失精度；
+    /// NaN、无穷大以及超出双精度范围的整数无法表示，返回错误
+    pub fn to_canonical_json(&self) -> Result<String, String> {
+        let mut out = String::new();
+        write_canonical(self, &mut out)?;
+        Ok(out)
+    }
+}
+
+fn write_canonical(value: &Value, out: &mut String) -> Result<(), String> {
+    match value {
+        Value::String(s) => write_canonical_str(s, out),
+        Value::Int(i) => write_canonical_number(*i as f64, out)?,
+        #[cfg(feature = "bigint")]
+        Value::BigInt(b) => write_canonical_number(b.to_string().parse().unwrap_or(f64::NAN), out)?,
+        Value::Float(f) => write_canonical_number(*f, out)?,
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.encode_utf16().cmp(b.0.encode_utf16()));
+            out.push('{');
+            for (i, (k, v)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_str(k, out);
+                out.push(':');
+                write_canonical(v, out)?;
+            }
+            out.push('}');
+        }
+    }
+    Ok(())
+}
+
+/// 与 `JSON.stringify` 相同：只转义引号、反斜杠与控制字符
+fn write_canonical_str(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// ECMAScript 的数字格式：取最短的可往返十进制数字，按小数点位置决定是否使用指数形式
+fn write_canonical_number(f: f64, out: &mut String) -> Result<(), String> {
+    if !f.is_finite() {
+        return Err(format!("{} cannot be represented in canonical JSON", f));
+    }
+    if f == 0.0 {
+        out.push('0');
+        return Ok(());
+    }
+    if f < 0.0 {
+        out.push('-');
+    }
+    // `{:e}` 给出最短的有效数字，如 `1.2345e-7`
+    let scientific = format!("{:e}", f.abs());
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("scientific notation has an exponent");
+    let digits = mantissa.replace('.', "");
+    let k = digits.len() as i32;
+    // 小数点在第 n 位数字之后
+    let n = exponent.parse::<i32>().expect("exponent is an integer") + 1;
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n('0', -n as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        out.push('e');
+        out.push(if n > 0 { '+' } else { '-' });
+        out.push_str(&(n - 1).abs().to_string());
+    }
+    Ok(())
+}
+
+/// -----------------------------
+/// 流式校验
+/// -----------------------------
+/// 边解析 JSON 边按规则校验，对象与数组逐个元素处理，未声明的字段直接跳过，
+/// 不构造整份文档的 `Value`。结果与 `validate_object` 一致，但默认值只做校验、不回填；
+/// 多处出错时报告的是文档中先出现的那一处，而且出错后不再检查其余的 JSON 语法。
+///
+/// 含 `ref(..)`、`script(..)` 或 `signed_with(..)` 约束的规则需要整份文档，
+/// 上下文中有流式校验无法满足的选项时也一样（见 [`streams_with`]），此时退回先解析再校验
+pub(crate) fn validate_json_bytes(
+    bytes: &[u8],
+    rules: &[FieldRule],
+    compiled: &[CompiledRule],
+    ctx: &ValidationContext,
+) -> validator::Result<()> {
+    if rules.iter().any(needs_document) || !streams_with(ctx) {
+        let json: serde_json::Value = serde_json::from_slice(bytes).map_err(invalid_json)?;
+        let mut value = Value::from(json);
+        return validator::validate_compiled(&mut value, rules, Some(compiled), ctx).map(|_| ());
+    }
+
+    let stream = Stream {
+        ctx,
+        error: RefCell::new(None),
+    };
+    let result = stream_document(
+        bytes,
+        RootVisitor {
+            stream: &stream,
+            rules,
+            compiled,
+        },
+    );
+    match (result, stream.error.into_inner()) {
+        (_, Some(e)) => Err(e),
+        (Err(e), None) => Err(invalid_json(e)),
+        (Ok(()), None) => Ok(()),
+    }
+}
+
+/// 开启 `bigint` 时同样使用 serde_json：simd-json 不接受超出 64 位的整数
+#[cfg(any(not(feature = "simd-json"), feature = "bigint"))]
+fn stream_document(bytes: &[u8], visitor: RootVisitor) -> Result<(), serde_json::Error> {
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    de::Deserializer::deserialize_any(&mut deserializer, visitor)?;
+    deserializer.end()
+}
+
+/// simd-json 先把整份输入解析为扁平的 tape，再在 tape 上逐项校验；
+/// 解析需要可写的缓冲区，输入会先复制一份
+#[cfg(all(feature = "simd-json", not(feature = "bigint")))]
+fn stream_document(bytes: &[u8], visitor: RootVisitor) -> Result<(), String> {
+    if exceeds_depth(bytes, MAX_JSON_DEPTH) {
+        return Err(format!("nesting deeper than {} levels", MAX_JSON_DEPTH));
+    }
+    let mut buffer = bytes.to_vec();
+    let mut deserializer =
+        simd_json::Deserializer::from_slice(&mut buffer).map_err(|e| e.to_string())?;
+    de::Deserializer::deserialize_any(&mut deserializer, visitor).map_err(|e| e.to_string())
+}
+
+/// 与 serde_json 的递归上限一致
+#[cfg(all(feature = "simd-json", not(feature = "bigint")))]
+const MAX_JSON_DEPTH: usize = 128;
+
+/// simd-json 在 tape 上反序列化时逐层递归且没有深度限制，解析前先扫描一遍括号
+#[cfg(all(feature = "simd-json", not(feature = "bigint")))]
+fn exceeds_depth(bytes: &[u8], limit: usize) -> bool {
+    let (mut depth, mut in_string, mut escaped) = (0usize, false, false);
+    for &b in bytes {
+        if in_string {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match b {
+            b'"' => in_string = true,
+            b'[' | b'{' => {
+                depth += 1;
+                if depth > limit {
+                    return true;
+                }
+            }
+            b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    false
+}
+
+/// 流式校验跳过未声明的字段、不改写也不记录，每个值单独校验：
+/// `strict`、`coerce`、`frozen`、`ordered`、审计、预算以及比解析上限更小的 `max_depth` 都需要整份文档
+fn streams_with(ctx: &ValidationContext) -> bool {
+    let options = &ctx.options;
+    #[cfg(feature = "ordered")]
+    if options.ordered {
+        return false;
+    }
+    ctx.audit.is_none()
+        && !options.strict
+        && !options.coerce
+        && !options.frozen
+        && options.budget == Budget::default()
+        && (options.max_depth == 0 || options.max_depth >= validator::DEFAULT_MAX_DEPTH)
+}
+
+fn invalid_json(e: impl fmt::Display) -> ValidationError {
+    ValidationError::Custom(format!("Invalid JSON: {}", e))
+}
+
+fn needs_document(rule: &FieldRule) -> bool {
+    let own = reads_siblings(rule) || rule.has_constraint(|c| matches!(c, Constraint::Ref(_)));
+    own || rule.rule.as_deref().is_some_and(needs_document)
+        || rule
+            .children
+            .as_ref()
+            .is_some_and(|children| children.iter().any(needs_document))
+}
+
+/// 规则自身带有读取同级字段的 `script(..)` 或 `signed_with(..)` 约束（含 `warn(..)` 中的）
+fn reads_siblings(rule: &FieldRule) -> bool {
+    rule.has_constraint(|c| {
+        let c = match c {
+            Constraint::Warn(inner) => inner.as_ref(),
+            c => c,
+        };
+        matches!(c, Constraint::Script(_) | Constraint::SignedWith { .. })
+    })
+}
+
+/// 只有类型检查与逐项递归的对象 / 数组规则才能边读边校验，其余规则先取出当前值
+fn streamable(rule: &FieldRule) -> bool {
+    let plain = rule.union_types.is_none()
+        && rule.enum_values.is_none()
+        && rule.constraints.as_ref().is_none_or(|c| c.items.is_empty());
+    plain
+        && match rule.field_type {
+            FieldType::Object => rule.children.is_some(),
+            FieldType::Array => rule.rule.is_some(),
+            _ => false,
+        }
+}
+
+/// 第一个校验错误保存在这里，再以 serde 错误中止解析
+struct Stream<'a> {
+    ctx: &'a ValidationContext,
+    error: RefCell<Option<ValidationError>>,
+}
+
+impl Stream<'_> {
+    fn fail<E: de::Error>(&self, e: ValidationError) -> E {
+        *self.error.borrow_mut() = Some(e);
+        E::custom("validation failed")
+    }
+
+    /// 已保存的错误从子字段或数组元素向上传递时补上所在位置，见 `ValidationError::within`
+    fn within<E>(&self, parent: &str) -> impl FnOnce(E) -> E {
+        move |e| {
+            let mut error = self.error.borrow_mut();
+            if let Some(inner) = error.take() {
+                *error = Some(inner.within(parent));
+            }
+            e
+        }
+    }
+
+    fn check<E: de::Error>(
+        &self,
+        value: &Value,
+        rule: &FieldRule,
+        compiled: &CompiledRule,
+    ) -> Result<(), E> {
+        validator::validate_value(value, rule, Some(compiled), self.ctx, None)
+            .map_err(|e| self.fail(e))
+    }
+
+    /// `first` 为已经读出的第一个键
+    fn object<'de, A: MapAccess<'de>>(
+        &self,
+        mut map: A,
+        first: Option<String>,
+        rules: &[FieldRule],
+        compiled: &[CompiledRule],
+    ) -> Result<(), A::Error> {
+        let mut seen = vec![false; rules.len()];
+        let mut next = first;
+        while let Some(key) = next {
+            let mut matched = rules.iter().enumerate().filter(|(_, r)| r.field == key);
+            match (matched.next(), matched.next()) {
+                (None, _) => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+                (Some((i, rule)), None) => {
+                    seen[i] |= map.next_value_seed(FieldSeed {
+                        stream: self,
+                        rule,
+                        compiled: &compiled[i],
+                    })?;
+                }
+                // 同名字段有多条规则时取出值逐条校验
+                _ => {
+                    let json: serde_json::Value = map.next_value()?;
+                    if !json.is_null() {
+                        let value = Value::from(json);
+                        for (i, rule) in rules.iter().enumerate().filter(|(_, r)| r.field == key) {
+                            self.check(&value, rule, &compiled[i])?;
+                            seen[i] = true;
+                        }
+                    }
+                }
+            }
+            next = map.next_key()?;
+        }
+
+        for ((rule, compiled), _) in rules
+            .iter()
+            .zip(compiled)
+            .zip(seen)
+            .filter(|(_, seen)| !seen)
+        {
+            match validator::default_value(rule, self.ctx) {
+                Some(d) => self.check(&d, rule, compiled)?,
+                None if rule.required || rule.must_be_present() => {
+                    return Err(self.fail(ValidationError::MissingField(rule.field.to_string())));
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 校验一个字段值，返回该值是否存在（对象中的 null 视为缺失）
+struct FieldSeed<'a> {
+    stream: &'a Stream<'a>,
+    rule: &'a FieldRule,
+    compiled: &'a CompiledRule,
+}
+
+impl<'de> DeserializeSeed<'de> for FieldSeed<'_> {
+    type Value = bool;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<bool, D::Error> {
+        if streamable(self.rule) {
+            return deserializer.deserialize_any(FieldVisitor(self));
+        }
+        let json = serde_json::Value::deserialize(deserializer)?;
+        if json.is_null() {
+            return Ok(false);
+        }
+        self.stream
+            .check(&Value::from(json), self.rule, self.compiled)?;
+        Ok(true)
+    }
+}
+
+struct FieldVisitor<'a>(FieldSeed<'a>);
+
+impl FieldVisitor<'_> {
+    fn scalar<E: de::Error>(self, value: Value) -> Result<bool, E> {
+        self.0.stream.check(&value, self.0.rule, self.0.compiled)?;
+        Ok(true)
+    }
+}
+
+impl<'de> Visitor<'de> for FieldVisitor<'_> {
+    type Value = bool;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a value of field '{}'", self.0.rule.field)
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<bool, E> {
+        self.scalar(Value::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<bool, E> {
+        self.scalar(Value::Int(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<bool, E> {
+        self.scalar(Value::from_u64(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<bool, E> {
+        self.scalar(Value::Float(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<bool, E> {
+        self.scalar(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<bool, E> {
+        self.scalar(Value::String(v))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<bool, E> {
+        Ok(false)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<bool, A::Error> {
+        let first = match start_map(&mut map)? {
+            MapStart::Number(n) => return self.scalar(n),
+            MapStart::Key(first) => first,
+        };
+        let FieldSeed {
+            stream,
+            rule,
+            compiled,
+        } = self.0;
+        match &rule.children {
+            Some(children) => stream
+                .object(map, first, children, &compiled.children)
+                .map_err(stream.within(&rule.field))?,
+            None => {
+                let mut object = serde_json::Map::new();
+                let mut next = first;
+                while let Some(key) = next {
+                    object.insert(key, map.next_value()?);
+                    next = map.next_key()?;
+                }
+                let json = serde_json::Value::Object(object);
+                stream.check(&Value::from(json), rule, compiled)?;
+            }
+        }
+        Ok(true)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<bool, A::Error> {
+        let FieldSeed {
+            stream,
+            rule,
+            compiled,
+        } = self.0;
+        match (rule.rule.as_deref(), compiled.sub.as_deref()) {
+            (Some(sub), Some(sub_compiled)) => {
+                let seed = || FieldSeed {
+                    stream,
+                    rule: sub,
+                    compiled: sub_compiled,
+                };
+                for i in 0.. {
+                    let element = format!("{}[{}]", rule.field, i);
+                    let Some(present) = seq
+                        .next_element_seed(seed())
+                        .map_err(stream.within(&element))?
+                    else {
+                        break;
+                    };
+                    // 数组中的 null 按空字符串校验
+                    if !present {
+                        stream
+                            .check(&Value::String(String::new()), sub, sub_compiled)
+                            .map_err(stream.within(&element))?;
+                    }
+                }
+            }
+            _ => {
+                let json = serde_json::Value::deserialize(SeqAccessDeserializer::new(seq))?;
+                stream.check(&Value::from(json), rule, compiled)?;
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// 开启 `bigint` 后 serde_json 使用 arbitrary_precision：不在 i64 / u64 范围内的数字
+/// （包括所有小数）以只含这个键的对象交给 `visit_map`，值为数字的原文
+#[cfg(feature = "bigint")]
+const NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
+enum MapStart {
+    #[cfg_attr(not(feature = "bigint"), allow(dead_code))]
+    Number(Value),
+    Key(Option<String>),
+}
+
+/// 读出对象的第一个键，区分真正的对象与 arbitrary_precision 下的数字
+fn start_map<'de, A: MapAccess<'de>>(map: &mut A) -> Result<MapStart, A::Error> {
+    let first = map.next_key::<String>()?;
+    #[cfg(feature = "bigint")]
+    if first.as_deref() == Some(NUMBER_TOKEN) {
+        let literal: String = map.next_value()?;
+        return Ok(MapStart::Number(number_literal(&literal)));
+    }
+    Ok(MapStart::Key(first))
+}
+
+struct RootVisitor<'a> {
+    stream: &'a Stream<'a>,
+    rules: &'a [FieldRule],
+    compiled: &'a [CompiledRule],
+}
+
+impl RootVisitor<'_> {
+    fn not_object<E: de::Error>(self) -> Result<(), E> {
+        Err(self
+            .stream
+            .fail(ValidationError::Custom("Value is not object".into())))
+    }
+}
+
+impl<'de> Visitor<'de> for RootVisitor<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON object")
+    }
+
+    fn visit_bool<E: de::Error>(self, _: bool) -> Result<(), E> {
+        self.not_object()
+    }
+
+    fn visit_i64<E: de::Error>(self, _: i64) -> Result<(), E> {
+        self.not_object()
+    }
+
+    fn visit_u64<E: de::Error>(self, _: u64) -> Result<(), E> {
+        self.not_object()
+    }
+
+    fn visit_f64<E: de::Error>(self, _: f64) -> Result<(), E> {
+        self.not_object()
+    }
+
+    fn visit_str<E: de::Error>(self, _: &str) -> Result<(), E> {
+        self.not_object()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        self.not_object()
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, _: A) -> Result<(), A::Error> {
+        self.not_object()
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        match start_map(&mut map)? {
+            MapStart::Number(_) => self.not_object(),
+            MapStart::Key(first) => self.stream.object(map, first, self.rules, self.compiled),
+        }
+    }
+}
+
+/// -----------------------------
+/// serde_json::Value 校验
+/// -----------------------------
+/// 直接在 `serde_json::Value` 上按规则校验，不把整份文档转换为 `Value`，结论与转换后校验相同。
+/// 按 `prepare` 的顺序沿规则分几遍遍历文档：
+///
+/// - `strict` / `ordered` 只读地检查键；
+/// - coerce、`slugify` 等转换与默认值就地写入，只有可能被改写的叶子临时转换为 `Value`；
+/// - 逐个字段交给校验引擎：只做类型检查的对象与数组逐层深入，其余的值转换为 `Value`，
+///   字符串移出而不复制，校验后移回，复制的只有数字、对象的键与容器本身。
+///   读取同级字段的 `script(..)` / `signed_with(..)` 所在的那一层对象整体这样转换；
+/// - 通过后按 `canonicalize` 就地规范化。
+///
+/// 审计的载荷哈希直接在文档上计算，`ref(..)` 的索引只转换取到的目标值。
+/// 对象中的 null 视为字段缺失，其余位置的 null 按空字符串校验，没有被改写时保持原样。
+/// `frozen` 下不写入，改由校验引擎在叶子的副本上 coerce 与转换
+#[cfg(feature = "json-value")]
+pub(crate) fn validate_json_value(
+    json: &mut serde_json::Value,
+    rules: &[FieldRule],
+    compiled: &[CompiledRule],
+    ctx: &ValidationContext,
+) -> validator::Result<()> {
+    let payload = ctx
+        .audit
+        .as_ref()
+        .map(|_| crate::audit::json_payload_hash(json));
+    let result = match json {
+        serde_json::Value::Object(map) => json_document(map, rules, compiled, ctx),
+        _ => Err(ValidationError::Custom("Value is not object".into())),
+    };
+    validator::record_audit(ctx, rules, payload, result.as_ref().copied());
+    result
+}
+
+#[cfg(feature = "json-value")]
+fn json_document(
+    map: &mut serde_json::Map<String, serde_json::Value>,
+    rules: &[FieldRule],
+    compiled: &[CompiledRule],
+    ctx: &ValidationContext,
+) -> validator::Result<()> {
+    let _budget = crate::budget::start(&ctx.options.budget);
+    if ctx.options.strict {
+        json_unknown_fields(map, rules, Path::Root)?;
+    }
+    #[cfg(feature = "ordered")]
+    if ctx.options.ordered {
+        json_field_order(map, rules, Path::Root)?;
+    }
+    let write = !ctx.options.frozen;
+    let mut changes = Vec::new();
+    json_prepare_object(map, rules, Path::Root, ctx, write, &mut changes);
+
+    let refs = RefIndex::build_json(map, rules);
+    let adjust = !write && !changes.is_empty();
+    let checking;
+    let ctx = if refs.is_some() || adjust {
+        let copy = ValidationContext {
+            adjust_leaves: adjust,
+            ..ctx.clone()
+        };
+        checking = match refs {
+            Some(index) => copy.with_refs(index),
+            None => copy,
+        };
+        &checking
+    } else {
+        ctx
+    };
+    json_object(map, rules, compiled, ctx, 1)?;
+
+    let mut normalized = Vec::new();
+    if ctx.options.canonicalize {
+        json_canonicalize(map, rules, Path::Root, write, &mut normalized);
+    }
+    // 与 `check_frozen` 报告同一处：`prepare` 依次 coerce、转换、填入默认值，规范化在最后
+    let first = [
+        ChangeKind::Coerce,
+        ChangeKind::Transform,
+        ChangeKind::Default,
+    ]
+    .into_iter()
+    .find_map(|kind| changes.iter().find(|c| c.kind == kind))
+    .map(|c| (&c.path, c.kind))
+    .or_else(|| normalized.first().map(|n| (&n.path, ChangeKind::Normalize)));
+    match first {
+        Some((field, kind)) if !write => Err(ValidationError::WouldMutate {
+            field: field.clone(),
+            change: kind.to_string(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// 与 `reject_unknown_fields` 相同的遍历，值为 null 的键视为缺失
+#[cfg(feature = "json-value")]
+fn json_unknown_fields(
+    map: &serde_json::Map<String, serde_json::Value>,
+    rules: &[FieldRule],
+    prefix: Path,
+) -> validator::Result<()> {
+    for (key, v) in map.iter().filter(|(_, v)| !v.is_null()) {
+        let path = prefix.field(key);
+        let Some(rule) = rules.iter().find(|r| &r.field == key) else {
+            return Err(ValidationError::UnknownField(path.to_string()));
+        };
+        if let (Some(children), serde_json::Value::Object(v)) = (&rule.children, v) {
+            json_unknown_fields(v, children, path)?;
+        }
+        if let (Some(sub), serde_json::Value::Array(items)) = (&rule.rule, v)
+            && let Some(children) = &sub.children
+        {
+            for (i, item) in items.iter().enumerate() {
+                if let serde_json::Value::Object(item) = item {
+                    json_unknown_fields(item, children, path.index(i))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 与 `check_field_order` 相同的遍历，值为 null 的键视为缺失
+#[cfg(all(feature = "json-value", feature = "ordered"))]
+fn json_field_order(
+    map: &serde_json::Map<String, serde_json::Value>,
+    rules: &[FieldRule],
+    prefix: Path,
+) -> validator::Result<()> {
+    let present = || map.iter().filter(|(_, v)| !v.is_null());
+    if let Some((key, after)) = validator::out_of_order(present().map(|(k, _)| k), rules) {
+        return Err(ValidationError::OutOfOrder {
+            field: prefix.field(key).to_string(),
+            after: after.to_string(),
+        });
+    }
+    for (key, v) in present() {
+        let Some(rule) = rules.iter().find(|r| &r.field == key) else {
+            continue;
+        };
+        let path = prefix.field(key);
+        if let (Some(children), serde_json::Value::Object(v)) = (&rule.children, v) {
+            json_field_order(v, children, path)?;
+        }
+        if let (Some(sub), serde_json::Value::Array(items)) = (&rule.rule, v)
+            && let Some(children) = &sub.children
+        {
+            for (i, item) in items.iter().enumerate() {
+                if let serde_json::Value::Object(item) = item {
+                    json_field_order(item, children, path.index(i))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `prepare` 中的 coerce、转换与填入默认值，修改记入 `changes`；
+/// `write` 为 false（`frozen`）时只记录，不写入文档
+#[cfg(feature = "json-value")]
+fn json_prepare_object(
+    map: &mut serde_json::Map<String, serde_json::Value>,
+    rules: &[FieldRule],
+    prefix: Path,
+    ctx: &ValidationContext,
+    write: bool,
+    changes: &mut Vec<Change>,
+) {
+    #[cfg(feature = "ordered")]
+    let len = map.len();
+    for rule in rules {
+        let path = prefix.field(&rule.field);
+        match map.get_mut(rule.field.as_str()) {
+            Some(json) if !json.is_null() => json_prepare(json, rule, path, ctx, write, changes),
+            _ => {
+                let Some(d) = validator::default_value(rule, ctx) else {
+                    continue;
+                };
+                let mut d = d.into_owned();
+                changes.push(Change {
+                    path: path.to_string(),
+                    kind: ChangeKind::Default,
+                    before: None,
+                    after: d.clone(),
+                });
+                validator::fill_value(&mut d, rule, path, ctx, changes);
+                if write {
+                    map.insert(rule.field.to_string(), into_json(d));
+                }
+            }
+        }
+    }
+    // 与 `fill_defaults` 相同，填入的默认值放到声明的位置上
+    #[cfg(feature = "ordered")]
+    if ctx.options.ordered && map.len() != len {
+        *map = validator::declared_order(std::mem::take(map).into_iter().collect(), rules)
+            .into_iter()
+            .collect();
+    }
+}
+
+#[cfg(feature = "json-value")]
+fn json_prepare(
+    json: &mut serde_json::Value,
+    rule: &FieldRule,
+    path: Path,
+    ctx: &ValidationContext,
+    write: bool,
+    changes: &mut Vec<Change>,
+) {
+    // 密文原样保留
+    if rule.encryption().is_some() {
+        return;
+    }
+    match json {
+        serde_json::Value::Object(map) => {
+            if let Some(children) = &rule.children {
+                json_prepare_object(map, children, path, ctx, write, changes);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            if let Some(sub) = &rule.rule {
+                for (i, item) in items.iter_mut().enumerate() {
+                    json_prepare(item, sub, path.index(i), ctx, write, changes);
+                }
+            }
+        }
+        json if validator::adjusts_leaf(rule, ctx) => with_leaf(json, write, |leaf| {
+            let before = changes.len();
+            validator::adjust_leaf(leaf, rule, path, ctx, changes);
+            changes.len() > before
+        }),
+        _ => {}
+    }
+}
+
+/// 把叶子交给 `f` 按 `Value` 处理，`f` 返回是否改写了它。字符串移出再移回，不复制；
+/// `write` 为 false 时只处理副本
+#[cfg(feature = "json-value")]
+fn with_leaf(json: &mut serde_json::Value, write: bool, f: impl FnOnce(&mut Value) -> bool) {
+    let mut leaf = match &mut *json {
+        serde_json::Value::String(s) if write => Value::String(std::mem::take(s)),
+        json => Value::from(json.clone()),
+    };
+    let changed = f(&mut leaf);
+    match (json, leaf) {
+        (serde_json::Value::String(s), Value::String(after)) if write => *s = after,
+        (json, leaf) if write && changed => *json = into_json(leaf),
+        _ => {}
+    }
+}
+
+/// 不需要转换的值：类型检查之外没有其他规则、可以直接进入的对象或数组
+#[cfg(feature = "json-value")]
+fn descends(json: &serde_json::Value, rule: &FieldRule) -> bool {
+    streamable(rule)
+        && match json {
+            serde_json::Value::Object(_) => {
+                rule.field_type == FieldType::Object && rule.rule.is_none()
+            }
+            serde_json::Value::Array(_) => rule.field_type == FieldType::Array,
+            _ => false,
+        }
+}
+
+/// 按规则顺序校验对象的字段，缺失的字段取默认值
+#[cfg(feature = "json-value")]
+fn json_object(
+    map: &mut serde_json::Map<String, serde_json::Value>,
+    rules: &[FieldRule],
+    compiled: &[CompiledRule],
+    ctx: &ValidationContext,
+    depth: usize,
+) -> validator::Result<()> {
+    // 脚本与签名读取同级字段，这一层整体转换
+    if rules.iter().any(reads_siblings) {
+        let obj = Value::Object(lend_object(map));
+        let result = validator::validate_fields(&obj, rules, Some(compiled), ctx, depth);
+        if let Value::Object(fields) = obj {
+            restore_object(map, fields);
+        }
+        return result;
+    }
+    for (rule, compiled) in rules.iter().zip(compiled) {
+        match map.get_mut(rule.field.as_str()) {
+            Some(json) if descends(json, rule) => json_nested(json, rule, compiled, ctx, depth)?,
+            Some(json) if !json.is_null() => json_value(json, rule, compiled, ctx, depth)?,
+            _ => match validator::default_value(rule, ctx) {
+                Some(d) => validator::validate_at(&d, rule, Some(compiled), ctx, None, depth)?,
+                None if rule.required || rule.must_be_present() => {
+                    return Err(ValidationError::MissingField(rule.field.to_string()));
+                }
+                None => {}
+            },
+        }
+    }
+    Ok(())
+}
+
+/// 进入对象的子字段或数组的元素，错误路径在这里补上
+#[cfg(feature = "json-value")]
+fn json_nested(
+    json: &mut serde_json::Value,
+    rule: &FieldRule,
+    compiled: &CompiledRule,
+    ctx: &ValidationContext,
+    depth: usize,
+) -> validator::Result<()> {
+    validator::enter(rule, ctx, depth)?;
+    match (
+        json,
+        &rule.children,
+        rule.rule.as_deref(),
+        compiled.sub.as_deref(),
+    ) {
+        (serde_json::Value::Object(map), Some(children), _, _) => {
+            json_object(map, children, &compiled.children, ctx, depth + 1)
+                .map_err(|e| e.within(&rule.field))
+        }
+        (serde_json::Value::Array(items), _, Some(sub), Some(sub_compiled)) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                if descends(item, sub) {
+                    json_nested(item, sub, sub_compiled, ctx, depth + 1)
+                } else {
+                    json_value(item, sub, sub_compiled, ctx, depth + 1)
+                }
+                .map_err(|e| e.within(&format!("{}[{}]", rule.field, i)))?;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 把一个字段值借给校验引擎，校验后原样归还
+#[cfg(feature = "json-value")]
+fn json_value(
+    json: &mut serde_json::Value,
+    rule: &FieldRule,
+    compiled: &CompiledRule,
+    ctx: &ValidationContext,
+    depth: usize,
+) -> validator::Result<()> {
+    let value = lend(json);
+    let result = validator::validate_at(
+        &validator::leaf(&value, rule, ctx),
+        rule,
+        Some(compiled),
+        ctx,
+        None,
+        depth,
+    );
+    restore(json, value);
+    result
+}
+
+/// 转换为 `Value`：字符串移出而不复制，`json` 中留下原来的结构（null、数字与键），
+/// 由 [`restore`] 移回。null 的处理与 `From<serde_json::Value>` 相同
+#[cfg(feature = "json-value")]
+fn lend(json: &mut serde_json::Value) -> Value {
+    match json {
+        serde_json::Value::String(s) => Value::String(std::mem::take(s)),
+        serde_json::Value::Array(items) => Value::Array(items.iter_mut().map(lend).collect()),
+        serde_json::Value::Object(map) => Value::Object(lend_object(map)),
+        json => Value::from(json.clone()),
+    }
+}
+
+#[cfg(feature = "json-value")]
+fn lend_object(map: &mut serde_json::Map<String, serde_json::Value>) -> ObjectMap {
+    map.iter_mut()
+        .filter(|(_, v)| !v.is_null())
+        .map(|(k, v)| (k.clone(), lend(v)))
+        .collect()
+}
+
+#[cfg(feature = "json-value")]
+fn restore(json: &mut serde_json::Value, value: Value) {
+    match (json, value) {
+        (serde_json::Value::String(s), Value::String(value)) => *s = value,
+        (serde_json::Value::Array(items), Value::Array(values)) => {
+            for (item, value) in items.iter_mut().zip(values) {
+                restore(item, value);
+            }
+        }
+        (serde_json::Value::Object(map), Value::Object(fields)) => restore_object(map, fields),
+        _ => {}
+    }
+}
+
+#[cfg(feature = "json-value")]
+fn restore_object(map: &mut serde_json::Map<String, serde_json::Value>, mut fields: ObjectMap) {
+    for (k, v) in map.iter_mut() {
+        if let Some(value) = fields.remove(k) {
+            restore(v, value);
+        }
+    }
+}
+
+/// 与 `canonicalize_object` 相同的遍历，就地改写字符串；`write` 为 false 时只记录
+#[cfg(feature = "json-value")]
+fn json_canonicalize(
+    map: &mut serde_json::Map<String, serde_json::Value>,
+    rules: &[FieldRule],
+    prefix: Path,
+    write: bool,
+    out: &mut Vec<Normalization>,
+) {
+    for rule in rules {
+        if let Some(json) = map.get_mut(rule.field.as_str()) {
+            json_canonical(json, rule, prefix.field(&rule.field), write, out);
+        }
+    }
+}
+
+#[cfg(feature = "json-value")]
+fn json_canonical(
+    json: &mut serde_json::Value,
+    rule: &FieldRule,
+    path: Path,
+    write: bool,
+    out: &mut Vec<Normalization>,
+) {
+    // 密文原样保留
+    if rule.encryption().is_some() {
+        return;
+    }
+    match json {
+        serde_json::Value::Object(map) => {
+            if let Some(children) = &rule.children {
+                json_canonicalize(map, children, path, write, out);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            if let Some(sub) = &rule.rule {
+                for (i, item) in items.iter_mut().enumerate() {
+                    json_canonical(item, sub, path.index(i), write, out);
+                }
+            }
+        }
+        json @ serde_json::Value::String(_) => with_leaf(json, write, |leaf| {
+            let before = out.len();
+            canonicalize_value(leaf, rule, path, out);
+            out.len() > before
+        }),
+        _ => {}
+    }
+}
+
+/// 与 `From<&Value>` 相同，但直接移动字符串、数组与对象，不再复制
+#[cfg(feature = "json-value")]
+fn into_json(value: Value) -> serde_json::Value {
+    match value {
+        Value::String(s) => serde_json::Value::String(s),
+        Value::Array(items) => serde_json::Value::Array(items.into_iter().map(into_json).collect()),
+        Value::Object(map) => {
+            serde_json::Value::Object(map.into_iter().map(|(k, v)| (k, into_json(v))).collect())
+        }
+        other => serde_json::Value::from(&other),
+    }
+}
@@ -1,4 +1,47 @@
 pub mod ast;
+pub mod audit;
+mod budget;
+pub mod canonical;
+pub mod checksum;
+pub mod clock;
+mod collation;
+mod color;
+mod compile;
+pub mod config;
+pub mod convert;
+pub mod decrypt;
+pub mod diagnostics;
+pub mod dsl_macro;
+pub mod explain;
+pub mod export;
+pub mod generator;
+pub mod i18n;
+pub mod ids;
+pub mod import;
+pub mod json;
+pub mod map;
+mod money;
+pub mod mutation;
+mod net;
 pub mod parser;
+pub mod phone;
+pub mod prelude;
+pub mod refs;
+pub mod registry;
+pub mod replay;
+pub mod report;
+mod sanity;
+pub mod schema;
+#[cfg(feature = "script")]
+pub mod script;
+pub mod sequence;
+pub mod signature;
+pub mod slug;
+pub mod stream;
+pub mod testing;
+mod timing;
 pub mod token;
 pub mod validator;
+mod warning;
+#[cfg(feature = "wasm")]
+pub mod wasm;
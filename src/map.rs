@@ -0,0 +1,405 @@
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, hash_map},
+    fmt,
+    hash::{Hash, Hasher},
+    slice,
+    sync::Arc,
+    vec,
+};
+
+use crate::ast::Value;
+
+/// 不超过该数量的对象以数组存储，按键逐个比较即可，省去哈希与桶的分配
+pub const SMALL_MAP_MAX: usize = 8;
+
+/// -----------------------------
+/// ObjectMap
+/// -----------------------------
+/// `Value::Object` 的存储。大多数接口载荷字段不多，少量字段时用 `Vec` 顺序查找，
+/// 超过 [`SMALL_MAP_MAX`] 个后转为 `HashMap`。接口与 `HashMap` 保持一致，
+/// 迭代顺序同样不作保证；开启 `ordered` feature 后改用 `IndexMap`，
+/// 迭代顺序即插入顺序，删除时其余键的顺序不变。
+///
+/// 存储放在 `Arc` 中写时复制：克隆只增加引用计数，同一份载荷交给日志、审计、处理函数等
+/// 多个使用方时不会深拷贝；修改时只复制被改动的这一层，嵌套对象仍然共享
+#[derive(Clone, Default)]
+pub struct ObjectMap {
+    repr: Arc<Repr>,
+}
+
+#[derive(Clone)]
+enum Repr {
+    Small(Vec<(String, Value)>),
+    Large(LargeMap),
+}
+
+#[cfg(not(feature = "ordered"))]
+type LargeMap = HashMap<String, Value>;
+#[cfg(feature = "ordered")]
+type LargeMap = indexmap::IndexMap<String, Value>;
+
+#[cfg(not(feature = "ordered"))]
+use hash_map::{IntoIter as LargeIntoIter, Iter as LargeIter, IterMut as LargeIterMut};
+#[cfg(feature = "ordered")]
+use indexmap::map::{IntoIter as LargeIntoIter, Iter as LargeIter, IterMut as LargeIterMut};
+
+impl Default for Repr {
+    fn default() -> Self {
+        Repr::Small(Vec::new())
+    }
+}
+
+impl ObjectMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let repr = if capacity > SMALL_MAP_MAX {
+            Repr::Large(LargeMap::with_capacity(capacity))
+        } else {
+            Repr::Small(Vec::with_capacity(capacity))
+        };
+        Self {
+            repr: Arc::new(repr),
+        }
+    }
+
+    /// 两者是否共享同一份存储（克隆后尚未修改）
+    pub fn ptr_eq(a: &Self, b: &Self) -> bool {
+        Arc::ptr_eq(&a.repr, &b.repr)
+    }
+
+    /// 取得可写的存储，与其他克隆共享时先复制这一层
+    fn repr_mut(&mut self) -> &mut Repr {
+        Arc::make_mut(&mut self.repr)
+    }
+
+    pub fn len(&self) -> usize {
+        match &*self.repr {
+            Repr::Small(entries) => entries.len(),
+            Repr::Large(map) => map.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&Value>
+    where
+        String: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        match &*self.repr {
+            Repr::Small(entries) => entries
+                .iter()
+                .find(|(k, _)| k.borrow() == key)
+                .map(|(_, v)| v),
+            Repr::Large(map) => map.get(key),
+        }
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut Value>
+    where
+        String: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        // 键不存在时不必复制
+        if !self.contains_key(key) {
+            return None;
+        }
+        match self.repr_mut() {
+            Repr::Small(entries) => entries
+                .iter_mut()
+                .find(|(k, _)| k.borrow() == key)
+                .map(|(_, v)| v),
+            Repr::Large(map) => map.get_mut(key),
+        }
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        String: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// 返回被替换的旧值
+    pub fn insert(&mut self, key: String, value: Value) -> Option<Value> {
+        let repr = self.repr_mut();
+        match repr {
+            Repr::Small(entries) => {
+                if let Some((_, old)) = entries.iter_mut().find(|(k, _)| *k == key) {
+                    return Some(std::mem::replace(old, value));
+                }
+                if entries.len() < SMALL_MAP_MAX {
+                    entries.push((key, value));
+                    return None;
+                }
+                let mut map: LargeMap = std::mem::take(entries).into_iter().collect();
+                map.insert(key, value);
+                *repr = Repr::Large(map);
+                None
+            }
+            Repr::Large(map) => map.insert(key, value),
+        }
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<Value>
+    where
+        String: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        if !self.contains_key(key) {
+            return None;
+        }
+        match self.repr_mut() {
+            Repr::Small(entries) => {
+                let at = entries.iter().position(|(k, _)| k.borrow() == key)?;
+                #[cfg(not(feature = "ordered"))]
+                return Some(entries.swap_remove(at).1);
+                #[cfg(feature = "ordered")]
+                return Some(entries.remove(at).1);
+            }
+            #[cfg(not(feature = "ordered"))]
+            Repr::Large(map) => map.remove(key),
+            #[cfg(feature = "ordered")]
+            Repr::Large(map) => map.shift_remove(key),
+        }
+    }
+
+    /// 相当于 `HashMap::entry(key).or_insert_with(default)`
+    pub fn get_or_insert_with(
+        &mut self,
+        key: String,
+        default: impl FnOnce() -> Value,
+    ) -> &mut Value {
+        if !self.contains_key(&key) {
+            self.insert(key.clone(), default());
+        }
+        self.get_mut(&key).expect("key was just inserted")
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&String, &mut Value) -> bool) {
+        match self.repr_mut() {
+            Repr::Small(entries) => entries.retain_mut(|(k, v)| keep(k, v)),
+            Repr::Large(map) => map.retain(|k, v| keep(k, v)),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        match &*self.repr {
+            Repr::Small(entries) => Iter::Small(entries.iter()),
+            Repr::Large(map) => Iter::Large(map.iter()),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        match self.repr_mut() {
+            Repr::Small(entries) => IterMut::Small(entries.iter_mut()),
+            Repr::Large(map) => IterMut::Large(map.iter_mut()),
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.iter().map(|(_, v)| v)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Value> {
+        self.iter_mut().map(|(_, v)| v)
+    }
+}
+
+impl fmt::Debug for ObjectMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// 与 `HashMap` 相同，不考虑键的顺序
+impl PartialEq for ObjectMap {
+    fn eq(&self, other: &Self) -> bool {
+        Self::ptr_eq(self, other)
+            || (self.len() == other.len() && self.iter().all(|(k, v)| other.get(k) == Some(v)))
+    }
+}
+
+impl Eq for ObjectMap {}
+
+/// 与顺序无关：逐项哈希后相加
+impl Hash for ObjectMap {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let sum = self.iter().fold(0u64, |sum, entry| {
+            let mut hasher = hash_map::DefaultHasher::new();
+            entry.hash(&mut hasher);
+            sum.wrapping_add(hasher.finish())
+        });
+        self.len().hash(state);
+        sum.hash(state);
+    }
+}
+
+/// 按键排序后逐项比较
+impl Ord for ObjectMap {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        fn sorted(map: &ObjectMap) -> Vec<(&String, &Value)> {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            entries
+        }
+        sorted(self).cmp(&sorted(other))
+    }
+}
+
+impl PartialOrd for ObjectMap {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<Q> std::ops::Index<&Q> for ObjectMap
+where
+    String: Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+{
+    type Output = Value;
+
+    fn index(&self, key: &Q) -> &Value {
+        self.get(key).expect("key not found in object")
+    }
+}
+
+impl FromIterator<(String, Value)> for ObjectMap {
+    fn from_iter<I: IntoIterator<Item = (String, Value)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl Extend<(String, Value)> for ObjectMap {
+    fn extend<I: IntoIterator<Item = (String, Value)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<const N: usize> From<[(String, Value); N]> for ObjectMap {
+    fn from(entries: [(String, Value); N]) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+impl From<HashMap<String, Value>> for ObjectMap {
+    fn from(map: HashMap<String, Value>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl From<ObjectMap> for HashMap<String, Value> {
+    fn from(map: ObjectMap) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+/// 按键值对的 map 序列化，键的顺序不作保证
+#[cfg(feature = "artifact")]
+impl serde::Serialize for ObjectMap {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+#[cfg(feature = "artifact")]
+impl<'de> serde::Deserialize<'de> for ObjectMap {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        HashMap::<String, Value>::deserialize(deserializer).map(Self::from)
+    }
+}
+
+pub enum Iter<'a> {
+    Small(slice::Iter<'a, (String, Value)>),
+    Large(LargeIter<'a, String, Value>),
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a String, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Iter::Small(it) => it.next().map(|(k, v)| (k, v)),
+            Iter::Large(it) => it.next(),
+        }
+    }
+}
+
+pub enum IterMut<'a> {
+    Small(slice::IterMut<'a, (String, Value)>),
+    Large(LargeIterMut<'a, String, Value>),
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = (&'a String, &'a mut Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            IterMut::Small(it) => it.next().map(|(k, v)| (&*k, v)),
+            IterMut::Large(it) => it.next(),
+        }
+    }
+}
+
+pub enum IntoIter {
+    Small(vec::IntoIter<(String, Value)>),
+    Large(LargeIntoIter<String, Value>),
+}
+
+impl Iterator for IntoIter {
+    type Item = (String, Value);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            IntoIter::Small(it) => it.next(),
+            IntoIter::Large(it) => it.next(),
+        }
+    }
+}
+
+impl IntoIterator for ObjectMap {
+    type Item = (String, Value);
+    type IntoIter = IntoIter;
+
+    /// 独占存储时直接移出，否则复制
+    fn into_iter(self) -> IntoIter {
+        match Arc::unwrap_or_clone(self.repr) {
+            Repr::Small(entries) => IntoIter::Small(entries.into_iter()),
+            Repr::Large(map) => IntoIter::Large(map.into_iter()),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a ObjectMap {
+    type Item = (&'a String, &'a Value);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut ObjectMap {
+    type Item = (&'a String, &'a mut Value);
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> IterMut<'a> {
+        self.iter_mut()
+    }
+}
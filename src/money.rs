@@ -0,0 +1,119 @@
+use crate::ast::{ObjectMap, Value};
+
+/// -----------------------------
+/// Money
+/// -----------------------------
+/// `money` 类型的取值：`{"amount": 12.5, "currency": "USD"}`。
+/// amount 为整数、浮点数或十进制字符串（`"12.50"`，可保留精确的小数位），
+/// 小数位不能超过币种的最小单位；currency 为 ISO 4217 代码。
+///
+/// 下表为现行的 ISO 4217 代码（不含贵金属与测试代码）
+const CURRENCIES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD",
+    "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUP", "CVE",
+    "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL",
+    "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR",
+    "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD",
+    "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK",
+    "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN", "NIO",
+    "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON",
+    "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD",
+    "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD",
+    "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES", "VND", "VUV",
+    "WST", "XAF", "XCD", "XOF", "XPF", "YER", "ZAR", "ZMW", "ZWG",
+];
+
+pub(crate) fn is_currency(code: &str) -> bool {
+    CURRENCIES.contains(&code)
+}
+
+/// 币种最小单位的小数位数，未列出的为 2
+fn minor_units(code: &str) -> usize {
+    match code {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX"
+        | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        "CLF" | "UYW" => 4,
+        _ => 2,
+    }
+}
+
+/// 十进制字符串的数值与小数位数：`"-12.50"` -> `(-12.5, 2)`
+fn parse_decimal(s: &str) -> Option<(f64, usize)> {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+    let all_digits = |p: &str| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit());
+    if !all_digits(int) || (digits.contains('.') && !all_digits(frac)) {
+        return None;
+    }
+    Some((s.parse().ok()?, frac.len()))
+}
+
+/// amount 的数值与小数位数
+fn amount(value: &Value) -> Option<(f64, usize)> {
+    match value {
+        Value::Int(i) => Some((*i as f64, 0)),
+        Value::Float(f) if f.is_finite() => parse_decimal(&f.to_string()),
+        Value::String(s) => parse_decimal(s),
+        _ => None,
+    }
+}
+
+/// money 对象的金额及其文本，用于区间约束；amount 不是十进制数时返回 None
+pub(crate) fn amount_of(obj: &ObjectMap) -> Option<(f64, String)> {
+    let raw = obj.get("amount")?;
+    let (n, _) = amount(raw)?;
+    Some((
+        n,
+        raw.as_str().map_or_else(|| raw.to_string(), str::to_string),
+    ))
+}
+
+pub(crate) fn currency_of(value: &Value) -> Option<&str> {
+    value.as_object()?.get("currency")?.as_str()
+}
+
+/// 检查 money 对象，失败时返回原因
+pub(crate) fn check(obj: &ObjectMap) -> Result<(), String> {
+    if let Some(key) = obj
+        .keys()
+        .find(|k| k.as_str() != "amount" && k.as_str() != "currency")
+    {
+        return Err(format!("unexpected key '{}'", key));
+    }
+    let (Some(raw), Some(currency)) = (obj.get("amount"), obj.get("currency")) else {
+        return Err("money requires 'amount' and 'currency'".into());
+    };
+    let currency = match currency.as_str() {
+        Some(c) if is_currency(c) => c,
+        _ => return Err(format!("unknown currency {}", currency)),
+    };
+    let (_, places) = amount(raw).ok_or("amount must be a decimal number")?;
+    let allowed = minor_units(currency);
+    if places > allowed {
+        return Err(format!(
+            "{} amounts allow at most {} decimal places",
+            currency, allowed
+        ));
+    }
+    Ok(())
+}
+
+/// `coerce` 时把 `"12.50 USD"` 转为 money 对象，amount 保留原始的十进制字符串
+pub(crate) fn parse_str(input: &str) -> Option<Value> {
+    let mut parts = input.split_whitespace();
+    let (amount, currency) = (parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+    parse_decimal(amount)?;
+    let currency = currency.to_ascii_uppercase();
+    if !is_currency(&currency) {
+        return None;
+    }
+    let mut obj = ObjectMap::new();
+    obj.insert("amount".into(), Value::String(amount.into()));
+    obj.insert("currency".into(), Value::String(currency));
+    Some(Value::Object(obj))
+}
@@ -0,0 +1,248 @@
+use std::fmt;
+
+use crate::{
+    ast::{Constraint, FieldRule, Value},
+    validator::{ValidationContext, validate_with_context},
+};
+
+/// -----------------------------
+/// Mutation
+/// -----------------------------
+/// 对 schema 做一处放宽：删除范围、去掉必填、删除正则等。
+/// 用一组本应失败的样本分别校验每个变体，仍然全部失败的变体说明被删掉的规则
+/// 没有拦住任何样本——要么规则多余，要么样本不够
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mutation {
+    /// 字段路径，数组元素以 `[]` 结尾：`tags[]`
+    pub path: String,
+    pub kind: MutationKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MutationKind {
+    /// 删除范围约束
+    WidenRange(String),
+    /// 删除正则约束
+    RemoveRegex(String),
+    /// 删除其他约束
+    RemoveConstraint(String),
+    /// 删除 enum
+    RemoveEnum,
+    /// 必填字段改为可选
+    DropRequired,
+}
+
+impl fmt::Display for Mutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            MutationKind::WidenRange(c) => write!(f, "{}: widen range {}", self.path, c),
+            MutationKind::RemoveRegex(c) => write!(f, "{}: remove {}", self.path, c),
+            MutationKind::RemoveConstraint(c) => write!(f, "{}: remove {}", self.path, c),
+            MutationKind::RemoveEnum => write!(f, "{}: remove enum", self.path),
+            MutationKind::DropRequired => write!(f, "{}: drop required", self.path),
+        }
+    }
+}
+
+/// 一个变体的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct MutantResult {
+    pub mutation: Mutation,
+    /// 变体下改为通过的样本序号
+    pub passed: Vec<usize>,
+}
+
+impl MutantResult {
+    /// 至少有一个样本依赖这条规则才会失败
+    pub fn is_load_bearing(&self) -> bool {
+        !self.passed.is_empty()
+    }
+}
+
+/// 一次变异测试的结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MutationReport {
+    /// 原 schema 下失败、参与测试的样本数
+    pub samples: usize,
+    /// 原 schema 下就通过的样本序号，不参与测试
+    pub ignored: Vec<usize>,
+    pub results: Vec<MutantResult>,
+}
+
+impl MutationReport {
+    pub fn load_bearing(&self) -> impl Iterator<Item = &Mutation> {
+        self.results
+            .iter()
+            .filter(|r| r.is_load_bearing())
+            .map(|r| &r.mutation)
+    }
+
+    /// 删掉后所有样本仍然失败的规则
+    pub fn dead(&self) -> impl Iterator<Item = &Mutation> {
+        self.results
+            .iter()
+            .filter(|r| !r.is_load_bearing())
+            .map(|r| &r.mutation)
+    }
+}
+
+impl fmt::Display for MutationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} invalid samples, {} mutants",
+            self.samples,
+            self.results.len()
+        )?;
+        for r in &self.results {
+            if r.is_load_bearing() {
+                writeln!(
+                    f,
+                    "  load-bearing  {} ({} passed)",
+                    r.mutation,
+                    r.passed.len()
+                )?;
+            } else {
+                writeln!(f, "  dead          {}", r.mutation)?;
+            }
+        }
+        if !self.ignored.is_empty() {
+            writeln!(
+                f,
+                "{} samples passed the original schema",
+                self.ignored.len()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 生成全部单处放宽的变体，按字段声明顺序排列
+pub fn mutants(rules: &[FieldRule]) -> Vec<(Mutation, Vec<FieldRule>)> {
+    let mut out = Vec::new();
+    mutate_rules(rules, "", &mut |mutation, mutate| {
+        let mut mutant = rules.to_vec();
+        mutate_at(&mut mutant, &mutation.path, &mut |rule| mutate(rule));
+        out.push((mutation, mutant));
+    });
+    out
+}
+
+/// 用 `samples` 分别校验每个变体，报告哪些规则实际拦住了样本。
+/// `ctx` 用于全部校验；如设置了 `audit`，每次校验都会被记录
+pub fn mutation_test(
+    rules: &[FieldRule],
+    samples: &[Value],
+    ctx: &ValidationContext,
+) -> MutationReport {
+    let mut report = MutationReport::default();
+    let mut invalid = Vec::new();
+    for (i, sample) in samples.iter().enumerate() {
+        if validate_with_context(&mut sample.clone(), rules, ctx).is_ok() {
+            report.ignored.push(i);
+        } else {
+            invalid.push(i);
+        }
+    }
+    report.samples = invalid.len();
+
+    for (mutation, mutant) in mutants(rules) {
+        let passed = invalid
+            .iter()
+            .copied()
+            .filter(|&i| validate_with_context(&mut samples[i].clone(), &mutant, ctx).is_ok())
+            .collect();
+        report.results.push(MutantResult { mutation, passed });
+    }
+    report
+}
+
+type Mutate = Box<dyn Fn(&mut FieldRule)>;
+
+fn mutate_rules(rules: &[FieldRule], prefix: &str, emit: &mut dyn FnMut(Mutation, Mutate)) {
+    for rule in rules {
+        let path = if prefix.is_empty() {
+            rule.field.to_string()
+        } else {
+            format!("{}.{}", prefix, rule.field)
+        };
+        mutate_rule(rule, &path, emit);
+    }
+}
+
+fn mutate_rule(rule: &FieldRule, path: &str, emit: &mut dyn FnMut(Mutation, Mutate)) {
+    let at = |kind| Mutation {
+        path: path.to_string(),
+        kind,
+    };
+    if rule.required && !rule.has_default() && !rule.field.is_empty() {
+        emit(
+            at(MutationKind::DropRequired),
+            Box::new(|r: &mut FieldRule| r.required = false),
+        );
+    }
+    if rule.enum_values.is_some() {
+        emit(
+            at(MutationKind::RemoveEnum),
+            Box::new(|r: &mut FieldRule| r.enum_values = None),
+        );
+    }
+    if let Some(c) = &rule.constraints {
+        for (i, item) in c.items.iter().enumerate() {
+            let kind = match item {
+                Constraint::Range { .. } => MutationKind::WidenRange(item.to_string()),
+                Constraint::Regex(_) => MutationKind::RemoveRegex(item.to_string()),
+                // 不影响校验结论，去掉后必然存活
+                Constraint::Label(_) | Constraint::HashIntoReport(_) => continue,
+                _ => MutationKind::RemoveConstraint(item.to_string()),
+            };
+            emit(
+                at(kind),
+                Box::new(move |r: &mut FieldRule| {
+                    if let Some(c) = &mut r.constraints {
+                        c.items.remove(i);
+                    }
+                }),
+            );
+        }
+    }
+    if let Some(sub) = &rule.rule {
+        mutate_rule(sub, &format!("{}[]", path), emit);
+    }
+    if let Some(children) = &rule.children {
+        mutate_rules(children, path, emit);
+    }
+}
+
+/// 找到路径对应的规则并修改；路径由 `mutate_rules` 生成，一定存在
+fn mutate_at(rules: &mut [FieldRule], path: &str, mutate: &mut dyn FnMut(&mut FieldRule)) {
+    let (head, rest) = match path.split_once('.') {
+        Some((head, rest)) => (head, Some(rest)),
+        None => (path, None),
+    };
+    let name = head.trim_end_matches("[]");
+    let Some(mut rule) = rules.iter_mut().find(|r| r.field == name) else {
+        return;
+    };
+    for _ in 0..(head.len() - name.len()) / 2 {
+        match rule.rule.as_deref_mut() {
+            Some(sub) => rule = sub,
+            None => return,
+        }
+    }
+    match rest {
+        None => mutate(rule),
+        Some(rest) => {
+            let mut parent = &mut *rule;
+            while parent.children.is_none() {
+                match parent.rule.as_deref_mut() {
+                    Some(sub) => parent = sub,
+                    None => return,
+                }
+            }
+            if let Some(children) = &mut parent.children {
+                mutate_at(children, rest, mutate);
+            }
+        }
+    }
+}
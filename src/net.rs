@@ -0,0 +1,72 @@
+use crate::ast::HostnameSpec;
+
+/// -----------------------------
+/// Network
+/// -----------------------------
+/// RFC 1123 主机名：总长不超过 253，每个标签 1-63 个字母数字或连字符，
+/// 标签首尾不能是连字符，顶级标签不能全是数字（避免与 IPv4 混淆）
+pub(crate) fn check_hostname(s: &str, spec: &HostnameSpec) -> Result<(), String> {
+    let name = match s.strip_suffix('.') {
+        Some(name) if spec.allow_trailing_dot => name,
+        Some(_) => return Err("trailing dot not allowed".into()),
+        None => s,
+    };
+    if name.is_empty() || name.len() > 253 {
+        return Err(format!("length out of range: {}", name.len()));
+    }
+    let labels: Vec<&str> = name.split('.').collect();
+    if labels.len() == 1 && !spec.allow_single_label {
+        return Err("single-label name not allowed".into());
+    }
+    for label in &labels {
+        let valid = !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-');
+        if !valid {
+            return Err(format!("invalid label '{}'", label));
+        }
+    }
+    if labels
+        .last()
+        .is_some_and(|tld| tld.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return Err("top-level label is numeric".into());
+    }
+    Ok(())
+}
+
+/// 1-65535 的纯数字字符串
+pub(crate) fn is_port(s: &str) -> bool {
+    !s.is_empty()
+        && s.len() <= 5
+        && s.bytes().all(|b| b.is_ascii_digit())
+        && s.parse::<u32>().is_ok_and(|p| (1..=65535).contains(&p))
+}
+
+/// `host:port` / `[ipv6]:port`；未加方括号的 IPv6 地址不接受，主机名允许单标签（如 `localhost`）
+pub(crate) fn is_socket_addr(s: &str, ip_only: bool) -> bool {
+    if let Some(rest) = s.strip_prefix('[') {
+        return match rest.split_once("]:") {
+            Some((host, port)) => host.parse::<std::net::Ipv6Addr>().is_ok() && is_port(port),
+            None => false,
+        };
+    }
+    let Some((host, port)) = s.rsplit_once(':') else {
+        return false;
+    };
+    if !is_port(port) || host.contains(':') {
+        return false;
+    }
+    if host.parse::<std::net::Ipv4Addr>().is_ok() {
+        return true;
+    }
+    let spec = HostnameSpec {
+        allow_single_label: true,
+        allow_trailing_dot: false,
+    };
+    !ip_only && check_hostname(host, &spec).is_ok()
+}
@@ -1,20 +1,218 @@
+use std::fmt;
+
 use crate::{
-    ast::{Constraint, Constraints, FieldRule, FieldType, Value},
-    token::{Token, tokenize},
+    ast::{
+        Aggregate, AggregateBound, Collation, ColorSpec, CompareOp, Constraint, Constraints,
+        DefaultExpr, FieldCompare, FieldName, FieldRule, FieldType, HostnameSpec, Metadata,
+        Operand, Projection, SequenceRule, Transform, UuidSpec, Value,
+    },
+    clock::instant_of,
+    dsl_macro::{self, DslMacro},
+    token::{Span, Token, tokenize_spanned, tokenize_with_spans},
+    validator::validate_type,
 };
 
+/// `array<...>` / `object(...)` 允许的最大嵌套层数，防止恶意输入耗尽栈空间
+pub const MAX_NESTING_DEPTH: usize = 64;
+
+/// 具名类型与宏展开后允许的最大规模：每条字段规则（包括具名类型复制进来的）计 1，
+/// 每次宏展开再计入宏体的词法单元数。互相引用的定义可以让很短的 DSL 展开成指数级的规则，
+/// 超出时解析失败，防止按租户下发的 DSL 耗尽内存与时间
+pub const MAX_EXPANDED_SIZE: usize = 100_000;
+
+/// 默认值与字段类型不符（如 int 字段写 `=1.5`、数值联合类型写字符串默认值）时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LossyDefaults {
+    /// 不检查
+    Allow,
+    /// 保留写下的值，记录 warning（`tracing::warn!` 并可通过 [`Parser::warnings`] 取得）
+    #[default]
+    Warn,
+    /// 解析失败
+    Deny,
+}
+
+/// 同一层字段列表中出现同名字段时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateFields {
+    /// 保留全部同名规则，记录 warning（兼容旧的 schema）
+    Warn,
+    /// 解析失败
+    #[default]
+    Deny,
+}
+
+/// 字段列表中已经出现的字段：所属的宏与字段名（或宏名）的位置
+struct Declared {
+    field: FieldName,
+    origin: Option<String>,
+    span: Option<Span>,
+}
+
+/// -----------------------------
+/// SyntaxError
+/// -----------------------------
+/// 一处 DSL 语法错误：不含位置的错误信息，以及出错的词法单元的位置（`None` 为输入末尾）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub message: String,
+    pub span: Option<Span>,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.span {
+            Some(span) => write!(f, "{} at {}", self.message, span),
+            None => write!(f, "{} at end of input", self.message),
+        }
+    }
+}
+
 /// -----------------------------
 /// Parser
 /// -----------------------------
 pub struct Parser {
     tokens: Vec<Token>,
+    /// 与 `tokens` 一一对应的源码位置，`Parser::new` 创建时为空
+    spans: Vec<Span>,
     pos: usize,
+    depth: usize,
+    lossy_defaults: LossyDefaults,
+    duplicate_fields: DuplicateFields,
+    warnings: Vec<String>,
+    metadata: Metadata,
+    /// 声明了 `import std`，可以使用 [`crate::prelude::std_type`] 中的类型名
+    import_std: bool,
+    /// 头部 `macro ...` 定义的宏
+    macros: Vec<DslMacro>,
+    /// 头部 `name = type` 定义的具名类型，按定义顺序保存
+    definitions: Vec<(String, FieldRule)>,
+    /// 正在解析的具名类型及其所在的嵌套层级，用于发现定义引用自己、找到定义的结尾
+    defining: Option<(String, usize)>,
+    /// 正在展开的宏，用于发现宏调用自己
+    expanding: Vec<String>,
+    /// 已经展开的规模，见 [`MAX_EXPANDED_SIZE`]
+    expanded: usize,
+    /// 出错后跳到下一个字段继续解析，错误收集在 `errors` 中
+    recovering: bool,
+    errors: Vec<SyntaxError>,
+    reported_eof: bool,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, pos: 0 }
+        Self {
+            tokens,
+            spans: Vec::new(),
+            pos: 0,
+            depth: 0,
+            lossy_defaults: LossyDefaults::default(),
+            duplicate_fields: DuplicateFields::default(),
+            warnings: Vec::new(),
+            metadata: Metadata::new(),
+            import_std: false,
+            macros: Vec::new(),
+            definitions: Vec::new(),
+            defining: None,
+            expanding: Vec::new(),
+            expanded: 0,
+            recovering: false,
+            errors: Vec::new(),
+            reported_eof: false,
+        }
+    }
+
+    /// 使用 `tokenize_with_spans` 的结果创建，解析错误会带上出错位置的行列
+    pub fn with_spans(spanned: Vec<(Token, Span)>) -> Self {
+        let (tokens, spans) = spanned.into_iter().unzip();
+        Self {
+            spans,
+            ..Self::new(tokens)
+        }
+    }
+
+    pub fn lossy_defaults(mut self, policy: LossyDefaults) -> Self {
+        self.lossy_defaults = policy;
+        self
+    }
+
+    pub fn duplicate_fields(mut self, policy: DuplicateFields) -> Self {
+        self.duplicate_fields = policy;
+        self
+    }
+
+    /// 解析过程中产生的 warning
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// `meta(...)` 头部声明的信息，没有时为空
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
     }
+
+    /// 最近读取的词法单元的位置；读到输入末尾之后为 `None`
+    pub fn span(&self) -> Option<Span> {
+        self.spans.get(self.pos.checked_sub(1)?).copied()
+    }
+
+    /// 为错误信息加上出错的位置：最近读取的词法单元，或输入末尾
+    fn locate(&self, message: String) -> String {
+        match self.span() {
+            Some(span) => format!("{} at {}", message, span),
+            None if !self.spans.is_empty() => format!("{} at end of input", message),
+            None => message,
+        }
+    }
+
+    /// 记录一处错误；读到输入末尾后只记录一次，避免一个未闭合的括号引出多条错误
+    fn report(&mut self, message: String) {
+        let at_eof = self.pos >= self.tokens.len();
+        if at_eof && std::mem::replace(&mut self.reported_eof, true) {
+            return;
+        }
+        self.errors.push(SyntaxError {
+            message,
+            span: self.span(),
+        });
+    }
+
+    /// 字段列表中从 `start` 开始的字段出错：恢复模式下记录错误，
+    /// 跳到同一层的下一个 `,` 或结束的 `)`，否则直接返回错误
+    fn recover(&mut self, start: usize, message: String) -> Result<(), String> {
+        if !self.recovering {
+            return Err(message);
+        }
+        self.report(message);
+        self.pos = start;
+        let mut depth = 0usize;
+        while let Some(t) = self.peek() {
+            match t {
+                Token::Comma | Token::RParen if depth == 0 => break,
+                Token::LParen | Token::LBracket => depth += 1,
+                Token::RParen | Token::RBracket => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            self.next();
+        }
+        Ok(())
+    }
+
+    /// 跳过分隔符错误之后停下的 `,`，从下一个字段继续
+    fn skip_comma(&mut self) {
+        if matches!(self.peek(), Some(Token::Comma)) {
+            self.next();
+        }
+    }
+
+    /// 恢复模式下出错并跳到输入末尾后不再继续字段列表
+    fn check_eof(&self) -> Result<(), String> {
+        if self.recovering && !self.errors.is_empty() && self.peek().is_none() {
+            return Err("Unexpected EOF".into());
+        }
+        Ok(())
+    }
+
     fn peek(&self) -> Option<&Token> {
         self.tokens.get(self.pos)
     }
@@ -33,15 +231,52 @@ impl Parser {
 
     // parse_program 修正版
     pub fn parse_program(&mut self) -> Result<Vec<FieldRule>, String> {
+        // 头部：任意顺序的 `import std`、`macro ...`、`name = type` 与 `meta(...)`
+        let mut has_meta = false;
+        loop {
+            match self.peek() {
+                Some(Token::Ident(s)) if s == "import" => {
+                    self.next();
+                    match self.next() {
+                        Some(Token::Ident(name)) if name == "std" => self.import_std = true,
+                        Some(Token::Ident(name)) => {
+                            return Err(format!("Unknown import '{}'", name));
+                        }
+                        t => return Err(format!("Expected import name, got {:?}", t)),
+                    }
+                }
+                Some(Token::Ident(s)) if s == "macro" => {
+                    self.next();
+                    self.parse_macro_definition()?;
+                }
+                Some(Token::Ident(_))
+                    if matches!(self.tokens.get(self.pos + 1), Some(Token::Equal)) =>
+                {
+                    self.parse_type_definition()?;
+                }
+                Some(Token::Ident(s)) if s == "meta" && !has_meta => {
+                    self.next();
+                    self.metadata = self.parse_metadata()?;
+                    has_meta = true;
+                }
+                _ => break,
+            }
+        }
         self.expect(&Token::LParen)?;
         let mut rules = Vec::new();
+        let mut declared = Vec::new();
         loop {
+            self.check_eof()?;
             if matches!(self.peek(), Some(Token::RParen)) {
                 self.next();
                 break;
             }
-            let field = self.parse_field(false)?;
-            rules.push(field);
+            let start = self.pos;
+            if let Err(e) = self.parse_field_or_macro().and_then(|(fields, origin)| {
+                self.add_fields(&mut rules, &mut declared, fields, origin, start)
+            }) {
+                self.recover(start, e)?;
+            }
 
             match self.peek() {
                 Some(Token::Comma) => {
@@ -49,14 +284,461 @@ impl Parser {
                 }
                 Some(Token::RParen) => {}
                 _ => {
-                    return Err("Expected ',' or ')'".into());
+                    let start = self.pos;
+                    let t = self.next();
+                    self.recover(start, format!("Expected ',' or ')', got {:?}", t))?;
+                    self.skip_comma();
                 }
             }
         }
+        while let Some(projection) = self.parse_projection()? {
+            rules = projection.apply(&rules)?;
+        }
         Ok(rules)
     }
 
+    /// `=` 之后是 `env(` / `ctx(` 这样的默认值表达式
+    fn default_expr_ahead(&self) -> bool {
+        matches!(self.tokens.get(self.pos + 1), Some(Token::Ident(name)) if DefaultExpr::is_keyword(name))
+            && matches!(self.tokens.get(self.pos + 2), Some(Token::LParen))
+    }
+
+    /// `=env(NAME)` / `=ctx(name)`，名称为标识符或字符串；`=now()` / `=today()` / `=uuid()` 无参数
+    fn parse_default_expr(&mut self) -> Result<DefaultExpr, String> {
+        self.expect(&Token::Equal)?;
+        let keyword = match self.next() {
+            Some(Token::Ident(k)) => k,
+            t => return Err(format!("Expected default expression, got {:?}", t)),
+        };
+        self.expect(&Token::LParen)?;
+        let generated = match keyword.as_str() {
+            "now" => Some(DefaultExpr::Now),
+            "today" => Some(DefaultExpr::Today),
+            "uuid" => Some(DefaultExpr::Uuid),
+            _ => None,
+        };
+        if let Some(expr) = generated {
+            self.expect(&Token::RParen)?;
+            return Ok(expr);
+        }
+        let name = match self.next() {
+            Some(Token::Ident(n) | Token::String(n)) => n,
+            t => return Err(format!("Expected name in {}(...), got {:?}", keyword, t)),
+        };
+        self.expect(&Token::RParen)?;
+        Ok(match keyword.as_str() {
+            "env" => DefaultExpr::Env(name),
+            _ => DefaultExpr::Ctx(name),
+        })
+    }
+
+    /// `pick(a, b)` / `omit(a, b)`，字段名为标识符或字符串
+    fn parse_projection(&mut self) -> Result<Option<Projection>, String> {
+        let pick = match self.peek() {
+            Some(Token::Ident(s)) if s == "pick" => true,
+            Some(Token::Ident(s)) if s == "omit" => false,
+            _ => return Ok(None),
+        };
+        self.next();
+        self.expect(&Token::LParen)?;
+        let mut fields = Vec::new();
+        loop {
+            match self.next() {
+                Some(Token::Ident(name) | Token::String(name)) => fields.push(name),
+                t => return Err(format!("Expected field name in projection, got {:?}", t)),
+            }
+            match self.next() {
+                Some(Token::Comma) => {}
+                Some(Token::RParen) => break,
+                t => return Err(format!("Expected ',' or ')' in projection, got {:?}", t)),
+            }
+        }
+        Ok(Some(if pick {
+            Projection::Pick(fields)
+        } else {
+            Projection::Omit(fields)
+        }))
+    }
+
+    /// `monotonic(path)` / `strictly_monotonic(path)` / `delta(path) <= 1000` / `abs_delta(path) < 5`
+    fn parse_sequence_rule(&mut self) -> Result<Option<SequenceRule>, String> {
+        let name = match self.peek() {
+            Some(Token::Ident(s))
+                if matches!(
+                    s.as_str(),
+                    "monotonic" | "strictly_monotonic" | "delta" | "abs_delta"
+                ) =>
+            {
+                s.clone()
+            }
+            _ => return Ok(None),
+        };
+        self.next();
+        self.expect(&Token::LParen)?;
+        let path = self.parse_ref_path()?;
+        if path.contains("[]") {
+            return Err(format!("{}() cannot spread arrays: '{}'", name, path));
+        }
+        self.expect(&Token::RParen)?;
+        Ok(Some(match name.as_str() {
+            "monotonic" | "strictly_monotonic" => SequenceRule::Monotonic {
+                path,
+                strict: name == "strictly_monotonic",
+            },
+            _ => {
+                let (op, bound) = self.parse_comparison()?;
+                SequenceRule::Delta {
+                    path,
+                    absolute: name == "abs_delta",
+                    op,
+                    bound,
+                }
+            }
+        }))
+    }
+
+    /// 头部定义的具名类型，或 `import std` 之后可用的标准类型（如 `Address`），
+    /// 未导入时提示添加 `import std`
+    fn named_type(&mut self, name: &str) -> Result<FieldRule, String> {
+        if self.defining.as_ref().is_some_and(|(n, _)| n == name) {
+            return Err(format!("Type {} cannot refer to itself", name));
+        }
+        if let Some(i) = self.definitions.iter().position(|(n, _)| n == name) {
+            // 引用处的字段本身已由 `parse_field` 计入，这里只计复制进来的嵌套规则
+            self.charge(rule_size(&self.definitions[i].1) - 1)?;
+            return Ok(self.definitions[i].1.clone());
+        }
+        match crate::prelude::std_type(name) {
+            Some(rule) if self.import_std => Ok(rule),
+            Some(_) => Err(format!(
+                "Unknown type {} (add 'import std' to use standard types)",
+                name
+            )),
+            None => Err(format!("Unknown type {}", name)),
+        }
+    }
+
+    /// 字段列表中的一项：普通字段，或展开为一组字段的宏调用（同时返回宏名）
+    fn parse_field_or_macro(&mut self) -> Result<(Vec<FieldRule>, Option<String>), String> {
+        let spread = matches!(self.peek(), Some(Token::Dot));
+        if spread {
+            for _ in 0..3 {
+                self.expect(&Token::Dot)?;
+            }
+        }
+        let m = match (self.peek(), spread) {
+            (Some(Token::Ident(name)), true) => Some(
+                self.find_macro(name)
+                    .ok_or_else(|| format!("Unknown macro '{}'", name))?,
+            ),
+            (t, true) => return Err(format!("Expected macro name, got {:?}", t)),
+            // 内置宏也可以不带 `...` 调用；宏名之后是 `:` / `?` 时仍是同名字段
+            (Some(Token::Ident(name)), false)
+                if !matches!(
+                    self.tokens.get(self.pos + 1),
+                    Some(Token::Colon) | Some(Token::Question)
+                ) =>
+            {
+                dsl_macro::builtin(name).cloned()
+            }
+            _ => None,
+        };
+        match m {
+            Some(m) => Ok((self.parse_macro(&m)?, Some(m.name))),
+            None => Ok((vec![self.parse_field(false)?], None)),
+        }
+    }
+
+    /// 把字段列表中从 `start` 开始的一项的字段加入 `list`。同名字段按 [`DuplicateFields`]
+    /// 报错或记录 warning，信息中带上第一次定义的位置；宏展开的字段与同一层的其他字段同名时
+    /// 总是报错，宏不会悄悄覆盖已有的字段
+    fn add_fields(
+        &mut self,
+        list: &mut Vec<FieldRule>,
+        declared: &mut Vec<Declared>,
+        fields: Vec<FieldRule>,
+        origin: Option<String>,
+        start: usize,
+    ) -> Result<(), String> {
+        // `...name()` 指向宏名
+        let at = match self.tokens.get(start) {
+            Some(Token::Dot) => start + 3,
+            _ => start,
+        };
+        let span = self.spans.get(at).copied();
+        for field in fields {
+            if let Some(first) = declared.iter().find(|d| d.field == field.field) {
+                let mut message = match (&origin, &first.origin) {
+                    (Some(m), _) => format!(
+                        "Field '{}' from macro {} is already defined",
+                        field.field, m
+                    ),
+                    (None, Some(m)) => {
+                        format!("Field '{}' is already defined by macro {}", field.field, m)
+                    }
+                    (None, None) => format!("Duplicate field '{}'", field.field),
+                };
+                if let Some(first) = first.span {
+                    message = format!("{} (first defined at {})", message, first);
+                }
+                if origin.is_some()
+                    || first.origin.is_some()
+                    || self.duplicate_fields == DuplicateFields::Deny
+                {
+                    // 错误位置指向重复的字段名
+                    self.pos = at + 1;
+                    return Err(message);
+                }
+                if let Some(span) = span {
+                    message = format!("{} at {}", message, span);
+                }
+                tracing::warn!("{}", message);
+                self.warnings.push(message);
+            }
+            declared.push(Declared {
+                field: field.field.clone(),
+                origin: origin.clone(),
+                span,
+            });
+            list.push(field);
+        }
+        Ok(())
+    }
+
+    /// `address = object(street:string, city:string)`：之后的字段可以用 `shipping: address`
+    /// 引用，解析时展开为定义的结构。定义只能引用在它之前定义的类型
+    fn parse_type_definition(&mut self) -> Result<(), String> {
+        let name = match self.next() {
+            Some(Token::Ident(name)) => name,
+            t => return Err(format!("Expected type name, got {:?}", t)),
+        };
+        if FieldType::from_keyword(&name).is_some() {
+            return Err(format!(
+                "{} is a built-in type and cannot be redefined",
+                name
+            ));
+        }
+        if self.definitions.iter().any(|(n, _)| *n == name) {
+            return Err(format!("Type {} is already defined", name));
+        }
+        self.expect(&Token::Equal)?;
+        self.defining = Some((name.clone(), self.depth + 1));
+        let rule = self.parse_field(true);
+        self.defining = None;
+        self.definitions.push((name, rule?));
+        Ok(())
+    }
+
+    /// 头部定义的最外层之后出现字段列表的 `(`（后面是 `字段名:`、`字段名?`、`)` 或 `...`），
+    /// 定义到此结束，不把它当作区间、约束或对象结构
+    fn definition_ends(&self) -> bool {
+        if self
+            .defining
+            .as_ref()
+            .is_none_or(|(_, depth)| *depth != self.depth)
+        {
+            return false;
+        }
+        match self.tokens.get(self.pos + 1) {
+            Some(Token::RParen | Token::Dot) => true,
+            // `money(currency=USD)` 这样的约束中名称后面不是 `:` 或 `?`
+            Some(Token::Ident(_)) => matches!(
+                self.tokens.get(self.pos + 2),
+                Some(Token::Colon | Token::Question)
+            ),
+            _ => false,
+        }
+    }
+
+    /// 计入展开的规模，超出 [`MAX_EXPANDED_SIZE`] 时返回错误
+    fn charge(&mut self, size: usize) -> Result<(), String> {
+        self.expanded = self.expanded.saturating_add(size);
+        if self.expanded > MAX_EXPANDED_SIZE {
+            return Err(format!(
+                "Schema expands to more than {} rules",
+                MAX_EXPANDED_SIZE
+            ));
+        }
+        Ok(())
+    }
+
+    /// DSL 中定义的宏优先，其次是内置宏
+    fn find_macro(&self, name: &str) -> Option<DslMacro> {
+        self.macros
+            .iter()
+            .find(|m| m.name == name)
+            .or_else(|| dsl_macro::builtin(name))
+            .cloned()
+    }
+
+    /// `macro name(p, q=1) = (fields)`：宏体在调用时才替换参数并解析
+    fn parse_macro_definition(&mut self) -> Result<(), String> {
+        let name = match self.next() {
+            Some(Token::Ident(name)) => name,
+            t => return Err(format!("Expected macro name, got {:?}", t)),
+        };
+        if self.find_macro(&name).is_some() {
+            return Err(format!("Macro {} is already defined", name));
+        }
+        self.expect(&Token::LParen)?;
+        let mut params: Vec<(String, Option<Token>)> = Vec::new();
+        loop {
+            let param = match self.next() {
+                Some(Token::RParen) if params.is_empty() => break,
+                Some(Token::Ident(param)) => param,
+                t => return Err(format!("Expected macro parameter, got {:?}", t)),
+            };
+            if params.iter().any(|(p, _)| *p == param) {
+                return Err(format!(
+                    "Duplicate parameter '{}' for macro {}",
+                    param, name
+                ));
+            }
+            let default = if matches!(self.peek(), Some(Token::Equal)) {
+                self.next();
+                Some(self.parse_macro_value(&param)?)
+            } else {
+                None
+            };
+            params.push((param, default));
+            match self.next() {
+                Some(Token::Comma) => {}
+                Some(Token::RParen) => break,
+                t => return Err(format!("Expected ',' or ')' in macro, got {:?}", t)),
+            }
+        }
+        self.expect(&Token::Equal)?;
+        self.expect(&Token::LParen)?;
+        let mut body = Vec::new();
+        let mut depth = 0usize;
+        loop {
+            match self.next().ok_or("Unexpected EOF")? {
+                Token::RParen if depth == 0 => break,
+                t => {
+                    match t {
+                        Token::LParen => depth += 1,
+                        Token::RParen => depth -= 1,
+                        _ => {}
+                    }
+                    body.push(t);
+                }
+            }
+        }
+        self.macros.push(DslMacro::new(&name, params, body)?);
+        Ok(())
+    }
+
+    /// 宏参数的值：数字、字符串或标识符（如类型名）
+    fn parse_macro_value(&mut self, param: &str) -> Result<Token, String> {
+        match self.next() {
+            Some(t @ (Token::Number(_) | Token::String(_) | Token::Ident(_))) => Ok(t),
+            t => Err(format!(
+                "Invalid value for macro parameter '{}': {:?}",
+                param, t
+            )),
+        }
+    }
+
+    /// `pagination(max_limit=50)` / `...timestamps()`：解析实参，展开宏体并解析其中的字段。
+    /// 宏体中可以调用其他宏，但不能直接或间接调用自己
+    fn parse_macro(&mut self, m: &DslMacro) -> Result<Vec<FieldRule>, String> {
+        self.next();
+        let mut args = Vec::new();
+        if matches!(self.peek(), Some(Token::LParen)) {
+            self.next();
+            loop {
+                let key = match self.next() {
+                    Some(Token::RParen) if args.is_empty() => break,
+                    Some(Token::Ident(key)) => key,
+                    t => return Err(format!("Expected macro parameter, got {:?}", t)),
+                };
+                self.expect(&Token::Equal)?;
+                let value = self.parse_macro_value(&key)?;
+                args.push((key, value));
+                match self.next() {
+                    Some(Token::Comma) => {}
+                    Some(Token::RParen) => break,
+                    t => return Err(format!("Expected ',' or ')' in macro call, got {:?}", t)),
+                }
+            }
+        }
+        if self.expanding.contains(&m.name) {
+            return Err(format!("Macro {} expands itself", m.name));
+        }
+        self.charge(m.body.len())?;
+        let mut parser = Parser::new(m.expand(&args)?)
+            .lossy_defaults(self.lossy_defaults)
+            .duplicate_fields(self.duplicate_fields);
+        parser.import_std = self.import_std;
+        parser.depth = self.depth;
+        parser.expanded = self.expanded;
+        // 宏与具名类型借给展开用的解析器，不在每次展开时复制；
+        // 宏体头部新增的定义只在宏内有效，归还前去掉
+        let (macros, definitions) = (self.macros.len(), self.definitions.len());
+        parser.macros = std::mem::take(&mut self.macros);
+        parser.definitions = std::mem::take(&mut self.definitions);
+        parser.expanding = self.expanding.clone();
+        parser.expanding.push(m.name.clone());
+        let fields = parser.parse_program();
+        parser.macros.truncate(macros);
+        parser.definitions.truncate(definitions);
+        self.macros = parser.macros;
+        self.definitions = parser.definitions;
+        self.expanded = parser.expanded;
+        let fields = fields.map_err(|e| format!("In macro {}: {}", m.name, e))?;
+        self.warnings.append(&mut parser.warnings);
+        Ok(fields)
+    }
+
+    /// `meta(key=value, ...)`，值为字符串、数字或 true / false
+    fn parse_metadata(&mut self) -> Result<Metadata, String> {
+        self.expect(&Token::LParen)?;
+        let mut metadata = Metadata::new();
+        loop {
+            let key = match self.next() {
+                Some(Token::RParen) if metadata.is_empty() => break,
+                Some(Token::Ident(key)) => key,
+                t => return Err(format!("Expected metadata key, got {:?}", t)),
+            };
+            self.expect(&Token::Equal)?;
+            let value = match self.peek() {
+                Some(Token::Number(_)) => self.parse_number()?,
+                Some(Token::String(_)) | Some(Token::Ident(_)) => match self.next() {
+                    Some(Token::String(s)) => Value::String(s),
+                    Some(Token::Ident(b)) if b == "true" || b == "false" => {
+                        Value::Bool(b == "true")
+                    }
+                    t => return Err(format!("Invalid metadata value {:?}", t)),
+                },
+                t => return Err(format!("Invalid metadata value {:?}", t)),
+            };
+            if metadata.insert(key.clone(), value).is_some() {
+                return Err(format!("Duplicate metadata key '{}'", key));
+            }
+            match self.next() {
+                Some(Token::Comma) => {}
+                Some(Token::RParen) => break,
+                t => return Err(format!("Expected ',' or ')' in meta, got {:?}", t)),
+            }
+        }
+        Ok(metadata)
+    }
+
     pub fn parse_field(&mut self, nameless: bool) -> Result<FieldRule, String> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(format!(
+                "Nesting deeper than {} levels is not allowed",
+                MAX_NESTING_DEPTH
+            ));
+        }
+        self.charge(1)?;
+        self.depth += 1;
+        let rule = self.parse_field_at_depth(nameless);
+        self.depth -= 1;
+        rule
+    }
+
+    fn parse_field_at_depth(&mut self, nameless: bool) -> Result<FieldRule, String> {
         // -----------------------------
         // 1️⃣ 字段名 + optional
         // -----------------------------
@@ -86,46 +768,10 @@ impl Parser {
         // 2️⃣ 解析 union 类型
         // -----------------------------
         let mut union_types = Vec::new();
+        let mut named = None;
         loop {
             let ty = match self.next() {
                 Some(Token::Ident(s)) => match s.as_str() {
-                    "string" => FieldType::String,
-                    "int" => FieldType::Int,
-                    "float" => FieldType::Float,
-                    "bool" => FieldType::Bool,
-                    "object" => FieldType::Object,
-                    "array" => FieldType::Array,
-                    "email" => FieldType::Email,
-                    "uri" => FieldType::Uri,
-                    "uuid" => FieldType::Uuid,
-                    "ip" => FieldType::Ip,
-                    "mac" => FieldType::Mac,
-                    "date" => FieldType::Date,
-                    "datetime" => FieldType::DateTime,
-                    "time" => FieldType::Time,
-                    "timestamp" => FieldType::Timestamp,
-                    "color" => FieldType::Color,
-                    "hostname" => FieldType::Hostname,
-                    "slug" => FieldType::Slug,
-                    "hex" => FieldType::Hex,
-                    "base64" => FieldType::Base64,
-                    "password" => FieldType::Password,
-                    "token" => FieldType::Token,
-                    "phone" => FieldType::Phone,
-                    "creditcard" => FieldType::CreditCard,
-                    "isbn" => FieldType::ISBN,
-                    "port" => FieldType::Port,
-                    "json" => FieldType::Json,
-                    "urlencoded" => FieldType::UrlEncoded,
-                    "lat" => FieldType::Lat,
-                    "lng" => FieldType::Lng,
-                    "semver" => FieldType::SemVer,
-                    "username" => FieldType::Username,
-                    "countrycode" => FieldType::CountryCode,
-                    "postalcode" => FieldType::PostalCode,
-                    "filepath" => FieldType::FilePath,
-                    "alpha" => FieldType::Alpha,
-                    "alphanumeric" => FieldType::Alphanumeric,
                     "regex" => {
                         self.expect(&Token::LParen)?;
                         let pattern = match self.next() {
@@ -135,9 +781,37 @@ impl Parser {
                         self.expect(&Token::RParen)?;
                         FieldType::Custom(pattern)
                     }
-                    t => {
-                        return Err(format!("Unknown type {}", t));
+                    "nanoid" if matches!(self.peek(), Some(Token::LParen)) => {
+                        self.next();
+                        let len = match self.next() {
+                            Some(Token::Number(n)) => n
+                                .parse::<usize>()
+                                .ok()
+                                .filter(|len| *len > 0)
+                                .ok_or_else(|| format!("Invalid nanoid length {}", n))?,
+                            t => return Err(format!("Expected nanoid length, got {:?}", t)),
+                        };
+                        self.expect(&Token::RParen)?;
+                        FieldType::NanoId(len)
+                    }
+                    "uuid" if matches!(self.peek(), Some(Token::LParen)) => {
+                        FieldType::UuidOf(self.parse_uuid_spec()?)
                     }
+                    "color" if matches!(self.peek(), Some(Token::LParen)) => {
+                        FieldType::ColorOf(self.parse_color_spec()?)
+                    }
+                    "hostname" if matches!(self.peek(), Some(Token::LParen)) => {
+                        FieldType::HostnameOf(self.parse_hostname_spec()?)
+                    }
+                    t => match FieldType::from_keyword(t) {
+                        Some(ty) => ty,
+                        None => {
+                            let template = self.named_type(t)?;
+                            let ty = template.field_type.clone();
+                            named = Some((s.clone(), template));
+                            ty
+                        }
+                    },
                 },
                 t => {
                     return Err(format!("Expected type, got {:?}", t));
@@ -153,7 +827,10 @@ impl Parser {
             }
         }
 
-        let field_type = union_types[0].clone();
+        let field_type = union_types
+            .first()
+            .cloned()
+            .ok_or("Expected at least one type")?;
 
         let mut sub_rule = None;
         let mut children = None;
@@ -162,17 +839,36 @@ impl Parser {
         let mut default = None;
         let is_array = field_type == FieldType::Array;
 
+        // 具名类型展开为定义中的结构，之后仍可追加约束
+        if let Some((type_name, template)) = named.take() {
+            if union_types.len() > 1 {
+                return Err(format!(
+                    "Named type {} cannot be used in a union",
+                    type_name
+                ));
+            }
+            if let Some(types) = template.union_types {
+                union_types = types;
+            }
+            sub_rule = template.rule;
+            children = template.children;
+            constraints = template.constraints.map(|c| c.items).unwrap_or_default();
+            enum_values = template.enum_values;
+            default = template.default;
+        }
+        let expanded = children.is_some() || sub_rule.is_some();
+
         //
         // 3️⃣ array<sub_rule>
         //
         // 修正版 array 解析，确保 sub_rule 不被丢弃
-        if is_array && matches!(self.peek(), Some(Token::Lt)) {
+        if is_array && !expanded && matches!(self.peek(), Some(Token::Lt)) {
             self.next(); // consume '<'
             // 使用 nameless=true 避免重复解析字段名，但保留 FieldType、constraints 等
             let sub = self.parse_field(true)?;
             // 父级 array 的 rule 指向这个子规则
             sub_rule = Some(Box::new(FieldRule {
-                field: String::new(), // nameless
+                field: "".into(), // nameless
                 field_type: sub.field_type,
                 required: sub.required,
                 default: sub.default,
@@ -189,17 +885,27 @@ impl Parser {
         //
         // 4️⃣ object(...)
         //
-        if field_type == FieldType::Object && matches!(self.peek(), Some(Token::LParen)) {
+        if field_type == FieldType::Object
+            && !expanded
+            && matches!(self.peek(), Some(Token::LParen))
+        {
             self.next(); // consume '('
             let mut inner = Vec::new();
+            let mut declared = Vec::new();
 
             loop {
+                self.check_eof()?;
                 if matches!(self.peek(), Some(Token::RParen)) {
                     self.next(); // consume ')'
                     break;
                 }
 
-                inner.push(self.parse_field(false)?);
+                let start = self.pos;
+                if let Err(e) = self.parse_field_or_macro().and_then(|(fields, origin)| {
+                    self.add_fields(&mut inner, &mut declared, fields, origin, start)
+                }) {
+                    self.recover(start, e)?;
+                }
 
                 match self.peek() {
                     Some(Token::Comma) => {
@@ -207,7 +913,9 @@ impl Parser {
                     }
                     Some(Token::RParen) => {}
                     _ => {
-                        return Err("Expected ',' or ')' in object".into());
+                        let start = self.pos;
+                        self.recover(start, "Expected ',' or ')' in object".into())?;
+                        self.skip_comma();
                     }
                 }
             }
@@ -220,31 +928,9 @@ impl Parser {
         //
         loop {
             match self.peek() {
-                // range
-                Some(Token::LBracket) => {
-                    constraints.push(self.parse_range(&field_type)?);
-                }
-
-                Some(Token::LParen) => {
-                    if field_type == FieldType::Object {
-                        return Err("Unexpected '(' after object definition".into());
-                    }
-                    constraints.push(self.parse_range(&field_type)?);
-                }
-
-                // regex
-                Some(Token::Ident(s)) if s == "regex" => {
-                    self.next();
-                    self.expect(&Token::LParen)?;
-                    let pattern = match self.next() {
-                        Some(Token::Ident(p)) => p,
-                        Some(Token::String(p)) => p,
-                        t => {
-                            return Err(format!("Expected pattern, got {:?}", t));
-                        }
-                    };
-                    self.expect(&Token::RParen)?;
-                    constraints.push(Constraint::Regex(pattern));
+                Some(Token::LParen) if self.definition_ends() => break,
+                Some(Token::LParen) if field_type == FieldType::Object => {
+                    return Err("Unexpected '(' after object definition".into());
                 }
 
                 // enum
@@ -294,6 +980,11 @@ impl Parser {
                     enum_values = Some(vals);
                 }
 
+                // =env(...) / =ctx(...)
+                Some(Token::Equal) if self.default_expr_ahead() => {
+                    constraints.push(Constraint::DefaultFrom(self.parse_default_expr()?));
+                }
+
                 // default
                 Some(Token::Equal) => {
                     self.next();
@@ -330,14 +1021,60 @@ impl Parser {
                     default = Some(val);
                 }
 
-                _ => {
-                    break;
-                }
+                _ => match self.parse_constraint(&field_type)? {
+                    Some(c) => constraints.push(c),
+                    None => break,
+                },
             }
         }
 
+        for c in &constraints {
+            if !union_types.iter().any(|t| c.applies_to(t)) {
+                let declared: Vec<String> = union_types.iter().map(|t| t.to_string()).collect();
+                let target = if nameless {
+                    "array elements".to_string()
+                } else {
+                    format!("field '{}'", name)
+                };
+                return Err(format!(
+                    "{} cannot be applied to {} of type {}",
+                    c,
+                    target,
+                    declared.join("|")
+                ));
+            }
+        }
+        let dynamic = constraints
+            .iter()
+            .filter(|c| matches!(c, Constraint::DefaultFrom(_)))
+            .count();
+        if dynamic + default.is_some() as usize > 1 {
+            return Err(format!("Field '{}' has more than one default", name));
+        }
+        check_presence(
+            &name,
+            nameless,
+            optional,
+            dynamic > 0 || default.is_some(),
+            &constraints,
+        )?;
+        // 默认值、coerce 与转换不能改写密文
+        if (dynamic > 0 || default.is_some())
+            && constraints
+                .iter()
+                .any(|c| matches!(c, Constraint::Encrypted(_)))
+        {
+            return Err(format!(
+                "Field '{}' is encrypted and cannot have a default",
+                name
+            ));
+        }
+        if let Some(d) = &default {
+            self.check_default(&name, d, &union_types)?;
+        }
+
         Ok(FieldRule {
-            field: name,
+            field: name.into(),
             field_type,
             required: if nameless { true } else { !optional },
             default,
@@ -358,6 +1095,623 @@ impl Parser {
         })
     }
 
+    /// 解析单个约束（range / regex / check / script / 时间 / 校验和 / 数组），
+    /// 当前位置不是约束时返回 None
+    fn parse_constraint(&mut self, field_type: &FieldType) -> Result<Option<Constraint>, String> {
+        let keyword = match self.peek() {
+            Some(Token::LParen)
+                if *field_type == FieldType::Money
+                    && matches!(self.tokens.get(self.pos + 1), Some(Token::Ident(k)) if k == "currency") =>
+            {
+                return self.parse_currencies().map(Some);
+            }
+            Some(Token::LBracket) | Some(Token::LParen) => {
+                return self.parse_range(field_type).map(Some);
+            }
+            // 导出为 JSON Schema 时与其他约束一起以文本保留
+            Some(Token::Equal) if self.default_expr_ahead() => {
+                return Ok(Some(Constraint::DefaultFrom(self.parse_default_expr()?)));
+            }
+            Some(Token::Ident(s)) => s.clone(),
+            _ => return Ok(None),
+        };
+        let constraint = match keyword.as_str() {
+            "regex" => Constraint::Regex(self.parse_string_arg("pattern", true)?),
+            "check" => Constraint::Check(self.parse_string_arg("validator name", true)?),
+            "script" => Constraint::Script(self.parse_string_arg("script string", false)?),
+            "past" => {
+                self.next();
+                Constraint::Past
+            }
+            "future" => {
+                self.next();
+                Constraint::Future
+            }
+            "present" => {
+                self.next();
+                Constraint::Present
+            }
+            "slugify" => {
+                self.next();
+                Constraint::Transform(Transform::Slugify)
+            }
+            "normalize_phone" => {
+                self.next();
+                let mut region = "US".to_string();
+                if matches!(self.peek(), Some(Token::LParen)) {
+                    self.next();
+                    match self.next() {
+                        Some(Token::Ident(key)) if key == "region" => {}
+                        t => return Err(format!("Expected 'region', got {:?}", t)),
+                    }
+                    self.expect(&Token::Equal)?;
+                    region = match self.next() {
+                        Some(Token::String(r)) | Some(Token::Ident(r)) => r.to_ascii_uppercase(),
+                        t => return Err(format!("Expected phone region, got {:?}", t)),
+                    };
+                    self.expect(&Token::RParen)?;
+                }
+                if !crate::phone::is_supported_region(&region) {
+                    return Err(format!("Unknown phone region '{}'", region));
+                }
+                Constraint::Transform(Transform::NormalizePhone { region })
+            }
+            "absent" => {
+                self.next();
+                Constraint::Absent
+            }
+            "deprecated" => {
+                self.next();
+                let message = if matches!(self.peek(), Some(Token::LParen)) {
+                    self.next();
+                    let message = match self.next() {
+                        Some(Token::String(m)) => m,
+                        t => return Err(format!("Expected deprecation message, got {:?}", t)),
+                    };
+                    self.expect(&Token::RParen)?;
+                    Some(message)
+                } else {
+                    None
+                };
+                Constraint::Deprecated(message)
+            }
+            "label" => {
+                self.next();
+                self.expect(&Token::LParen)?;
+                let text = match self.next() {
+                    Some(Token::String(text)) => text,
+                    t => return Err(format!("Expected label text, got {:?}", t)),
+                };
+                self.expect(&Token::RParen)?;
+                Constraint::Label(text)
+            }
+            "encrypted" => Constraint::Encrypted(self.parse_string_arg("decryptor name", true)?),
+            "hash_into_report" => {
+                let algorithm = self.parse_string_arg("hash algorithm", true)?;
+                if !crate::report::HASH_ALGORITHMS.contains(&algorithm.as_str()) {
+                    return Err(format!("Unknown hash algorithm '{}'", algorithm));
+                }
+                Constraint::HashIntoReport(algorithm)
+            }
+            "warn" => {
+                self.next();
+                self.expect(&Token::LParen)?;
+                let inner = match self.parse_constraint(field_type)? {
+                    Some(
+                        c @ (Constraint::Present
+                        | Constraint::Absent
+                        | Constraint::Deprecated(_)
+                        | Constraint::Label(_)
+                        | Constraint::Encrypted(_)
+                        | Constraint::HashIntoReport(_)
+                        | Constraint::Transform(_)
+                        | Constraint::DefaultFrom(_)
+                        | Constraint::Warn(_)
+                        | Constraint::Ref(_)
+                        | Constraint::Exists(_)),
+                    ) => return Err(format!("warn(...) cannot wrap {}", c)),
+                    Some(c) => c,
+                    None => {
+                        return Err(format!(
+                            "Expected constraint in warn(...), got {:?}",
+                            self.next()
+                        ));
+                    }
+                };
+                self.expect(&Token::RParen)?;
+                Constraint::Warn(Box::new(inner))
+            }
+            "within" => {
+                self.next();
+                self.expect(&Token::LParen)?;
+                let seconds = self.parse_duration()?;
+                self.expect(&Token::RParen)?;
+                Constraint::Within(seconds)
+            }
+            "luhn" => {
+                self.next();
+                Constraint::Checksum {
+                    algorithm: "luhn".into(),
+                    expected: None,
+                }
+            }
+            "checksum" => {
+                self.next();
+                self.expect(&Token::LParen)?;
+                let algorithm = match self.next() {
+                    Some(Token::Ident(n)) | Some(Token::String(n)) => n,
+                    t => return Err(format!("Expected checksum algorithm, got {:?}", t)),
+                };
+                let expected = if matches!(self.peek(), Some(Token::Equal)) {
+                    self.next();
+                    match self.next() {
+                        Some(Token::String(v)) | Some(Token::Ident(v)) | Some(Token::Number(v)) => {
+                            Some(v)
+                        }
+                        t => return Err(format!("Expected checksum value, got {:?}", t)),
+                    }
+                } else {
+                    None
+                };
+                self.expect(&Token::RParen)?;
+                Constraint::Checksum {
+                    algorithm,
+                    expected,
+                }
+            }
+            "signed_with" => {
+                self.next();
+                self.expect(&Token::LParen)?;
+                let algorithm = match self.next() {
+                    Some(Token::Ident(n)) | Some(Token::String(n))
+                        if crate::signature::ALGORITHMS.contains(&n.as_str()) =>
+                    {
+                        n
+                    }
+                    t => return Err(format!("Expected signature algorithm, got {:?}", t)),
+                };
+                let (mut key, mut over) = (None, None);
+                while matches!(self.peek(), Some(Token::Comma)) {
+                    self.next();
+                    let slot = match self.next() {
+                        Some(Token::Ident(k)) if k == "key" => &mut key,
+                        Some(Token::Ident(k)) if k == "over" => &mut over,
+                        t => return Err(format!("Expected 'key' or 'over', got {:?}", t)),
+                    };
+                    self.expect(&Token::Equal)?;
+                    *slot = match self.next() {
+                        Some(Token::String(v)) => Some(v),
+                        t => return Err(format!("Expected quoted string, got {:?}", t)),
+                    };
+                }
+                self.expect(&Token::RParen)?;
+                let key = key.ok_or("signed_with(...) requires key=\"ctx:name\"")?;
+                if !crate::signature::is_key_source(&key) {
+                    return Err(format!(
+                        "Invalid signing key '{}', expected \"ctx:name\" or \"env:NAME\"",
+                        key
+                    ));
+                }
+                let over = over.ok_or("signed_with(...) requires over=\"$.field\"")?;
+                let valid_path = over
+                    .strip_prefix("$.")
+                    .is_some_and(|path| path.split('.').all(|s| !s.is_empty()));
+                if !valid_path {
+                    return Err(format!(
+                        "Invalid signed path '{}', expected \"$.field\"",
+                        over
+                    ));
+                }
+                Constraint::SignedWith {
+                    algorithm,
+                    key,
+                    over,
+                }
+            }
+            "sorted" => {
+                self.next();
+                let (mut descending, mut collation) = (false, Collation::Binary);
+                if matches!(self.peek(), Some(Token::LParen)) {
+                    self.next();
+                    descending = match self.next() {
+                        Some(Token::Ident(o)) if o == "asc" => false,
+                        Some(Token::Ident(o)) if o == "desc" => true,
+                        t => return Err(format!("Expected asc or desc, got {:?}", t)),
+                    };
+                    if matches!(self.peek(), Some(Token::Comma)) {
+                        self.next();
+                        collation = self.parse_collation()?;
+                    }
+                    self.expect(&Token::RParen)?;
+                }
+                Constraint::Sorted {
+                    descending,
+                    strict: false,
+                    collation,
+                }
+            }
+            "strictly_increasing" | "strictly_decreasing" => {
+                self.next();
+                let mut collation = Collation::Binary;
+                if matches!(self.peek(), Some(Token::LParen)) {
+                    self.next();
+                    collation = self.parse_collation()?;
+                    self.expect(&Token::RParen)?;
+                }
+                Constraint::Sorted {
+                    descending: keyword == "strictly_decreasing",
+                    strict: true,
+                    collation,
+                }
+            }
+            "sum" | "avg" | "min" | "max" | "count" | "count_where" => {
+                self.next();
+                let aggregate = match keyword.as_str() {
+                    "sum" => Aggregate::Sum,
+                    "avg" => Aggregate::Avg,
+                    "min" => Aggregate::Min,
+                    "max" => Aggregate::Max,
+                    "count" => Aggregate::Count,
+                    _ => {
+                        self.expect(&Token::LParen)?;
+                        let (op, value) = self.parse_comparison()?;
+                        self.expect(&Token::RParen)?;
+                        Aggregate::CountWhere(op, value)
+                    }
+                };
+                let bound = if matches!(self.peek(), Some(Token::LBracket) | Some(Token::LParen)) {
+                    self.parse_bound_range()?
+                } else {
+                    let (op, value) = self.parse_comparison()?;
+                    AggregateBound::Compare(op, value)
+                };
+                Constraint::ArrayAggregate { aggregate, bound }
+            }
+            "unique_by" => Constraint::UniqueBy(self.parse_string_arg("key path", true)?),
+            "each" => {
+                self.next();
+                Constraint::Each(self.parse_field_compare(false)?)
+            }
+            "adjacent" => {
+                self.next();
+                Constraint::Adjacent(self.parse_field_compare(true)?)
+            }
+            "exists" => Constraint::Exists(self.parse_string_arg("reference kind", true)?),
+            "multiple_of" => {
+                self.next();
+                self.expect(&Token::LParen)?;
+                let divisor = self.parse_number()?;
+                self.expect(&Token::RParen)?;
+                if matches!(divisor, Value::Int(0)) || divisor == Value::Float(0.0) {
+                    return Err("multiple_of() divisor must not be zero".into());
+                }
+                Constraint::MultipleOf(divisor)
+            }
+            "oui" => {
+                self.next();
+                self.expect(&Token::LParen)?;
+                let mut prefixes = Vec::new();
+                loop {
+                    match self.next() {
+                        Some(Token::String(p)) if oui_hex(&p).is_some() => prefixes.push(p),
+                        t => return Err(format!("Expected OUI like \"AC:DE:48\", got {:?}", t)),
+                    }
+                    match self.next() {
+                        Some(Token::Comma) => {}
+                        Some(Token::RParen) => break,
+                        t => return Err(format!("Expected ',' or ')' in oui(...), got {:?}", t)),
+                    }
+                }
+                Constraint::Oui(prefixes)
+            }
+            "ref" => {
+                self.next();
+                self.expect(&Token::LParen)?;
+                let path = self.parse_ref_path()?;
+                self.expect(&Token::RParen)?;
+                Constraint::Ref(path)
+            }
+            "min_age" => {
+                self.next();
+                self.expect(&Token::LParen)?;
+                let years = match self.next() {
+                    Some(Token::Number(n)) => n
+                        .parse::<u32>()
+                        .map_err(|_| format!("Invalid age '{}'", n))?,
+                    t => return Err(format!("Expected age, got {:?}", t)),
+                };
+                self.expect(&Token::RParen)?;
+                Constraint::MinAge(years)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(constraint))
+    }
+
+    /// `keyword("arg")`，`allow_ident` 时参数也可以不加引号
+    fn parse_string_arg(&mut self, what: &str, allow_ident: bool) -> Result<String, String> {
+        self.next(); // 关键字
+        self.expect(&Token::LParen)?;
+        let arg = match self.next() {
+            Some(Token::String(s)) => s,
+            Some(Token::Ident(s)) if allow_ident => s,
+            t => {
+                return Err(format!("Expected {}, got {:?}", what, t));
+            }
+        };
+        self.expect(&Token::RParen)?;
+        Ok(arg)
+    }
+
+    /// 比较运算符及右侧数字：`<=100`、`>0`、`==1`、`!=0`
+    fn parse_comparison(&mut self) -> Result<(CompareOp, Value), String> {
+        let op = self.parse_compare_op()?;
+        Ok((op, self.parse_number()?))
+    }
+
+    fn parse_compare_op(&mut self) -> Result<CompareOp, String> {
+        Ok(match self.next() {
+            Some(Token::Lt) if matches!(self.peek(), Some(Token::Equal)) => {
+                self.next();
+                CompareOp::Le
+            }
+            Some(Token::Lt) => CompareOp::Lt,
+            Some(Token::Gt) if matches!(self.peek(), Some(Token::Equal)) => {
+                self.next();
+                CompareOp::Ge
+            }
+            Some(Token::Gt) => CompareOp::Gt,
+            Some(Token::Equal) => {
+                self.expect(&Token::Equal)?;
+                CompareOp::Eq
+            }
+            Some(Token::Bang) => {
+                self.expect(&Token::Equal)?;
+                CompareOp::Ne
+            }
+            t => return Err(format!("Expected comparison operator, got {:?}", t)),
+        })
+    }
+
+    /// `uuid(v4)` / `uuid(compact)` / `uuid(v7, compact)`，版本号为 1-8
+    fn parse_uuid_spec(&mut self) -> Result<UuidSpec, String> {
+        self.expect(&Token::LParen)?;
+        let mut spec = UuidSpec::default();
+        loop {
+            match self.next() {
+                Some(Token::Ident(arg)) if arg == "compact" && !spec.compact => spec.compact = true,
+                Some(Token::Ident(arg)) if spec.version.is_none() => {
+                    let version = arg
+                        .strip_prefix('v')
+                        .and_then(|v| v.parse::<u8>().ok())
+                        .filter(|v| (1..=8).contains(v))
+                        .ok_or_else(|| format!("Invalid uuid parameter '{}'", arg))?;
+                    spec.version = Some(version);
+                }
+                t => return Err(format!("Unexpected uuid parameter {:?}", t)),
+            }
+            match self.next() {
+                Some(Token::Comma) => {}
+                Some(Token::RParen) => return Ok(spec),
+                t => return Err(format!("Expected ',' or ')' in uuid(...), got {:?}", t)),
+            }
+        }
+    }
+
+    /// `color(hex, rgb, hsl, alpha)` 的任意组合，只写 `alpha` 时即十六进制带透明度
+    fn parse_color_spec(&mut self) -> Result<ColorSpec, String> {
+        self.expect(&Token::LParen)?;
+        let mut spec = ColorSpec::default();
+        loop {
+            let flag = match self.next() {
+                Some(Token::Ident(arg)) => match arg.as_str() {
+                    "hex" => &mut spec.hex,
+                    "rgb" => &mut spec.rgb,
+                    "hsl" => &mut spec.hsl,
+                    "alpha" => &mut spec.alpha,
+                    _ => return Err(format!("Invalid color parameter '{}'", arg)),
+                },
+                t => return Err(format!("Unexpected color parameter {:?}", t)),
+            };
+            if *flag {
+                return Err("Duplicate color parameter".into());
+            }
+            *flag = true;
+            match self.next() {
+                Some(Token::Comma) => {}
+                Some(Token::RParen) => break,
+                t => return Err(format!("Expected ',' or ')' in color(...), got {:?}", t)),
+            }
+        }
+        if !(spec.hex || spec.rgb || spec.hsl) {
+            spec.hex = true;
+        }
+        Ok(spec)
+    }
+
+    /// `hostname(allow_single_label, allow_trailing_dot)`，至少一个选项
+    fn parse_hostname_spec(&mut self) -> Result<HostnameSpec, String> {
+        self.expect(&Token::LParen)?;
+        let mut spec = HostnameSpec::default();
+        loop {
+            let flag = match self.next() {
+                Some(Token::Ident(arg)) => match arg.as_str() {
+                    "allow_single_label" => &mut spec.allow_single_label,
+                    "allow_trailing_dot" => &mut spec.allow_trailing_dot,
+                    _ => return Err(format!("Invalid hostname option '{}'", arg)),
+                },
+                t => return Err(format!("Unexpected hostname option {:?}", t)),
+            };
+            if *flag {
+                return Err("Duplicate hostname option".into());
+            }
+            *flag = true;
+            match self.next() {
+                Some(Token::Comma) => {}
+                Some(Token::RParen) => return Ok(spec),
+                t => return Err(format!("Expected ',' or ')' in hostname(...), got {:?}", t)),
+            }
+        }
+    }
+
+    /// `each(...)` / `adjacent(...)` 的括号内比较；adjacent 的字段路径必须以 `prev.` 或 `next.` 开头
+    fn parse_field_compare(&mut self, adjacent: bool) -> Result<FieldCompare, String> {
+        self.expect(&Token::LParen)?;
+        let left = self.parse_operand(adjacent)?;
+        let op = self.parse_compare_op()?;
+        let right = self.parse_operand(adjacent)?;
+        let mut collation = Collation::Binary;
+        if matches!(self.peek(), Some(Token::Comma)) {
+            self.next();
+            collation = self.parse_collation()?;
+        }
+        self.expect(&Token::RParen)?;
+        if !matches!(left, Operand::Field(_)) && !matches!(right, Operand::Field(_)) {
+            return Err("Expected at least one field path in element comparison".into());
+        }
+        Ok(FieldCompare {
+            left,
+            op,
+            right,
+            collation,
+        })
+    }
+
+    /// 字符串比较方式：`binary`、`nocase`、`unicode`、`unicode("sv")`
+    fn parse_collation(&mut self) -> Result<Collation, String> {
+        let name = match self.next() {
+            Some(Token::Ident(name)) => name,
+            t => return Err(format!("Expected collation, got {:?}", t)),
+        };
+        match name.as_str() {
+            "binary" => Ok(Collation::Binary),
+            "nocase" => Ok(Collation::CaseInsensitive),
+            "unicode" if matches!(self.peek(), Some(Token::LParen)) => {
+                self.next();
+                let locale = match self.next() {
+                    Some(Token::String(s)) => s,
+                    t => return Err(format!("Expected locale string, got {:?}", t)),
+                };
+                self.expect(&Token::RParen)?;
+                Ok(Collation::Unicode(Some(locale)))
+            }
+            "unicode" => Ok(Collation::Unicode(None)),
+            other => Err(format!("Unknown collation '{}'", other)),
+        }
+    }
+
+    /// 文档根起的路径，`[]` 展开数组：`users[].id`、`teams[].members[].id`
+    fn parse_ref_path(&mut self) -> Result<String, String> {
+        let mut path = String::new();
+        loop {
+            match self.next() {
+                Some(Token::Ident(name)) => path.push_str(&name),
+                t => return Err(format!("Expected field name in ref path, got {:?}", t)),
+            }
+            if matches!(self.peek(), Some(Token::LBracket)) {
+                self.next();
+                self.expect(&Token::RBracket)?;
+                path.push_str("[]");
+            }
+            if !matches!(self.peek(), Some(Token::Dot)) {
+                return Ok(path);
+            }
+            self.next();
+            path.push('.');
+        }
+    }
+
+    fn parse_operand(&mut self, adjacent: bool) -> Result<Operand, String> {
+        if !matches!(self.peek(), Some(Token::Ident(_) | Token::String(_))) {
+            return Ok(Operand::Literal(self.parse_number()?));
+        }
+        match self.next() {
+            Some(Token::String(s)) => Ok(Operand::Literal(Value::String(s))),
+            Some(Token::Ident(mut path)) => {
+                while matches!(self.peek(), Some(Token::Dot)) {
+                    self.next();
+                    match self.next() {
+                        Some(Token::Ident(name)) => {
+                            path.push('.');
+                            path.push_str(&name);
+                        }
+                        t => return Err(format!("Expected field name after '.', got {:?}", t)),
+                    }
+                }
+                if adjacent && !(path.starts_with("prev.") || path.starts_with("next.")) {
+                    return Err(format!(
+                        "adjacent() fields must start with 'prev.' or 'next.', got '{}'",
+                        path
+                    ));
+                }
+                Ok(Operand::Field(path))
+            }
+            t => Err(format!("Expected operand, got {:?}", t)),
+        }
+    }
+
+    /// 聚合结果的区间，整数保持为 int
+    fn parse_bound_range(&mut self) -> Result<AggregateBound, String> {
+        let min_inclusive = matches!(self.next(), Some(Token::LBracket));
+        let min = self.parse_number()?;
+        self.expect(&Token::Comma)?;
+        let max = self.parse_number()?;
+        let max_inclusive = match self.next() {
+            Some(Token::RBracket) => true,
+            Some(Token::RParen) => false,
+            t => {
+                return Err(format!("Expected closing bracket or paren, got {:?}", t));
+            }
+        };
+        Ok(AggregateBound::Range {
+            min,
+            max,
+            min_inclusive,
+            max_inclusive,
+        })
+    }
+
+    fn parse_number(&mut self) -> Result<Value, String> {
+        match self.next() {
+            Some(Token::Number(n)) => match Value::integer(&n) {
+                Some(i) => Ok(i),
+                None => n
+                    .parse::<f64>()
+                    .map(Value::Float)
+                    .map_err(|_| format!("Invalid number '{}'", n)),
+            },
+            t => Err(format!("Expected number, got {:?}", t)),
+        }
+    }
+
+    /// 时长：`30d`、`12h`、`90m`、`45s`、`2w`，返回秒数
+    fn parse_duration(&mut self) -> Result<i64, String> {
+        let amount = match self.next() {
+            Some(Token::Number(n)) => n
+                .parse::<i64>()
+                .map_err(|_| format!("Invalid duration '{}'", n))?,
+            t => return Err(format!("Expected duration, got {:?}", t)),
+        };
+        let unit = match self.next() {
+            Some(Token::Ident(u)) => u,
+            t => return Err(format!("Expected duration unit, got {:?}", t)),
+        };
+        let scale = match unit.as_str() {
+            "s" => 1,
+            "m" => 60,
+            "h" => 3600,
+            "d" => 86400,
+            "w" => 7 * 86400,
+            other => return Err(format!("Unknown duration unit '{}'", other)),
+        };
+        if amount < 0 {
+            return Err(format!("Duration must not be negative: {}{}", amount, unit));
+        }
+        amount
+            .checked_mul(scale)
+            .ok_or_else(|| format!("Duration too large: {}{}", amount, unit))
+    }
+
     /// 根据 FieldType 解析 Token::Number 为 Value
     fn parse_token_number_as_type(
         &self,
@@ -368,9 +1722,9 @@ impl Parser {
             Token::Number(s) => {
                 match field_type {
                     // 如果目标是 Int，允许输入浮点字符串，先解析为 f64
-                    FieldType::Int => {
-                        if let Ok(i) = s.parse::<i64>() {
-                            Ok(Value::Int(i))
+                    FieldType::Int | FieldType::Timestamp => {
+                        if let Some(i) = Value::integer(s) {
+                            Ok(i)
                         } else if let Ok(f) = s.parse::<f64>() {
                             // 暂时返回 Float，由 parse_range 进行后续的 ceil/floor 处理
                             Ok(Value::Float(f))
@@ -382,33 +1736,104 @@ impl Parser {
                         .parse::<f64>()
                         .map(Value::Float)
                         .map_err(|_| format!("Invalid float '{}'", s)),
-                    // 如果是 String 类型，Range 通常代表长度，所以也支持数字解析
-                    FieldType::String => s
+                    // 如果是 String / Array 类型，Range 代表长度，所以也支持数字解析
+                    FieldType::String | FieldType::Array => s
                         .parse::<i64>()
                         .map(Value::Int)
                         .map_err(|_| format!("Invalid length number '{}'", s)),
-                    _ => Err(format!(
-                        "Range only supports int/float/string, got {:?}",
-                        field_type
-                    )),
+                    // 其余类型按数值解析，区间是否适用由 parse_field 统一检查
+                    _ => s
+                        .parse::<i64>()
+                        .map(Value::Int)
+                        .or_else(|_| s.parse::<f64>().map(Value::Float))
+                        .map_err(|_| format!("Invalid number '{}'", s)),
                 }
             }
             _ => Err("Expected a number token".into()),
         }
     }
 
-    /// Range 解析，支持 int/float 并对 int 类型进行向内取整
+    /// 默认值必须能通过声明的类型（联合类型满足其一即可），否则按 `lossy_defaults` 处理
+    fn check_default(
+        &mut self,
+        field: &str,
+        default: &Value,
+        types: &[FieldType],
+    ) -> Result<(), String> {
+        if self.lossy_defaults == LossyDefaults::Allow
+            || types.iter().any(|t| validate_type(default, t).is_ok())
+        {
+            return Ok(());
+        }
+        let declared: Vec<String> = types.iter().map(|t| t.to_string()).collect();
+        let message = format!(
+            "Default {} of field '{}' does not match type {}",
+            default,
+            field,
+            declared.join("|")
+        );
+        if self.lossy_defaults == LossyDefaults::Deny {
+            return Err(message);
+        }
+        tracing::warn!("{}", message);
+        self.warnings.push(message);
+        Ok(())
+    }
+
+    /// money 允许的币种：`(currency=USD|EUR)`
+    fn parse_currencies(&mut self) -> Result<Constraint, String> {
+        self.expect(&Token::LParen)?;
+        self.next(); // currency
+        self.expect(&Token::Equal)?;
+        let mut codes = Vec::new();
+        loop {
+            let code = match self.next() {
+                Some(Token::Ident(c)) | Some(Token::String(c)) => c,
+                t => return Err(format!("Expected currency code, got {:?}", t)),
+            };
+            if !crate::money::is_currency(&code) {
+                return Err(format!("Unknown currency '{}'", code));
+            }
+            codes.push(code);
+            match self.next() {
+                Some(Token::Pipe) => {}
+                Some(Token::RParen) => break,
+                t => return Err(format!("Expected '|' or ')' in currency list, got {:?}", t)),
+            }
+        }
+        Ok(Constraint::Currency(codes))
+    }
+
+    /// 区间的一个边界：date / datetime 为带引号的日期，其余为数字
+    fn parse_range_bound(&mut self, field_type: &FieldType, label: &str) -> Result<Value, String> {
+        let token = self
+            .next()
+            .ok_or_else(|| format!("Expected {} bound", label))?;
+        match (field_type, token) {
+            (FieldType::Date | FieldType::DateTime, Token::String(s)) => {
+                let bound = Value::String(s);
+                match instant_of(&bound) {
+                    Some(_) => Ok(bound),
+                    None => Err(format!("Invalid date in range: {}", bound)),
+                }
+            }
+            (FieldType::Date | FieldType::DateTime, t) => Err(format!(
+                "Expected quoted date as {} bound, got {:?}",
+                label, t
+            )),
+            (_, token) => self.parse_token_number_as_type(&token, field_type),
+        }
+    }
+
+    /// Range 解析：`[` / `(` 与 `]` / `)` 分别决定两端是否包含，可任意组合。
+    /// 支持 int/float 并对 int 类型进行向内取整，date / datetime 的边界为日期字符串
     fn parse_range(&mut self, field_type: &FieldType) -> Result<Constraint, String> {
         let min_inclusive = matches!(self.peek(), Some(Token::LBracket));
         self.next(); // 消耗 [ 或 (
 
-        let min_token = self.next().ok_or("Expected min number")?;
-        let mut min = self.parse_token_number_as_type(&min_token, field_type)?;
-
+        let mut min = self.parse_range_bound(field_type, "min")?;
         self.expect(&Token::Comma)?;
-
-        let max_token = self.next().ok_or("Expected max number")?;
-        let mut max = self.parse_token_number_as_type(&max_token, field_type)?;
+        let mut max = self.parse_range_bound(field_type, "max")?;
 
         // --- 逻辑核心：如果字段是 Int，将边界向内取整 ---
         if let FieldType::Int = field_type {
@@ -437,9 +1862,139 @@ impl Parser {
         })
     }
 
+    /// 解析完整的 DSL。任何输入都只会返回 `Err`，不会 panic
     pub fn parse_rules(input: &str) -> Result<Vec<FieldRule>, String> {
-        let tokens = tokenize(input)?;
-        let mut parser = Parser::new(tokens);
-        parser.parse_program()
+        let mut parser = Parser::with_spans(tokenize_with_spans(input)?);
+        parser.parse_program().map_err(|e| parser.locate(e))
     }
+
+    /// 同 `parse_rules`，但出错后跳到下一个字段（同一层的下一个 `,` 或结束的 `)`）继续解析，
+    /// 一次返回全部语法错误，按出现顺序排列
+    pub fn parse_rules_recovering(input: &str) -> Result<Vec<FieldRule>, Vec<SyntaxError>> {
+        let spanned = tokenize_spanned(input).map_err(|(message, span)| {
+            vec![SyntaxError {
+                message,
+                span: Some(span),
+            }]
+        })?;
+        let mut parser = Parser::with_spans(spanned);
+        parser.recovering = true;
+        let result = parser.parse_program();
+        if let Err(message) = &result {
+            parser.report(message.clone());
+        }
+        match result {
+            Ok(rules) if parser.errors.is_empty() => Ok(rules),
+            _ => Err(parser.errors),
+        }
+    }
+
+    /// 同 `parse_rules`，同时返回 `meta(...)` 头部
+    pub fn parse_with_metadata(input: &str) -> Result<(Vec<FieldRule>, Metadata), String> {
+        let mut parser = Parser::with_spans(tokenize_with_spans(input)?);
+        let rules = parser.parse_program().map_err(|e| parser.locate(e))?;
+        Ok((rules, parser.metadata))
+    }
+
+    /// 解析一串投影，如 `omit(password) pick(username, email)`
+    pub fn parse_projections(input: &str) -> Result<Vec<Projection>, String> {
+        let mut parser = Parser::with_spans(tokenize_with_spans(input)?);
+        let mut projections = Vec::new();
+        while let Some(p) = parser.parse_projection().map_err(|e| parser.locate(e))? {
+            projections.push(p);
+        }
+        match parser.next() {
+            None => Ok(projections),
+            Some(t) => Err(parser.locate(format!("Unexpected {:?} in projections", t))),
+        }
+    }
+
+    /// 解析一串序列规则，如 `monotonic(version), delta(balance) <= 1000`，逗号可省略
+    pub fn parse_sequence_rules(input: &str) -> Result<Vec<SequenceRule>, String> {
+        let mut parser = Parser::with_spans(tokenize_with_spans(input)?);
+        let mut rules = Vec::new();
+        while let Some(r) = parser.parse_sequence_rule().map_err(|e| parser.locate(e))? {
+            rules.push(r);
+            if matches!(parser.peek(), Some(Token::Comma)) {
+                parser.next();
+            }
+        }
+        match parser.next() {
+            None => Ok(rules),
+            Some(t) => Err(parser.locate(format!("Unexpected {:?} in sequence rules", t))),
+        }
+    }
+
+    /// 解析一组约束（不含 enum / 默认值），如 `regex("^a") within(30d)`
+    pub fn parse_constraints(
+        input: &str,
+        field_type: &FieldType,
+    ) -> Result<Vec<Constraint>, String> {
+        let mut parser = Parser::with_spans(tokenize_with_spans(input)?);
+        let mut constraints = Vec::new();
+        while let Some(c) = parser
+            .parse_constraint(field_type)
+            .map_err(|e| parser.locate(e))?
+        {
+            constraints.push(c);
+        }
+        match parser.next() {
+            None => Ok(constraints),
+            Some(t) => Err(parser.locate(format!("Unexpected {:?} in constraints", t))),
+        }
+    }
+}
+
+/// 规则本身及其中嵌套的字段与元素规则的条数
+fn rule_size(rule: &FieldRule) -> usize {
+    1 + rule.rule.as_deref().map_or(0, rule_size)
+        + rule.children.iter().flatten().map(rule_size).sum::<usize>()
+}
+
+/// `present` / `absent` 只用于具名字段，且不能与默认值或必填冲突
+fn check_presence(
+    name: &str,
+    nameless: bool,
+    optional: bool,
+    has_default: bool,
+    constraints: &[Constraint],
+) -> Result<(), String> {
+    let present = constraints.iter().any(|c| matches!(c, Constraint::Present));
+    let absent = constraints.iter().any(|c| matches!(c, Constraint::Absent));
+    if !present && !absent {
+        return Ok(());
+    }
+    let keyword = if present { "present" } else { "absent" };
+    if nameless {
+        return Err(format!("{} cannot be applied to array elements", keyword));
+    }
+    if present && absent {
+        return Err(format!(
+            "Field '{}' cannot be both present and absent",
+            name
+        ));
+    }
+    if has_default {
+        return Err(format!(
+            "Field '{}' is marked {} and cannot have a default",
+            name, keyword
+        ));
+    }
+    if absent && !optional {
+        return Err(format!(
+            "Field '{}' is marked absent and must be optional ('{}?')",
+            name, name
+        ));
+    }
+    Ok(())
+}
+
+/// OUI 前缀去掉分隔符后的 6 位大写十六进制
+pub(crate) fn oui_hex(prefix: &str) -> Option<String> {
+    let hex: String = prefix
+        .chars()
+        .filter(|c| !matches!(c, ':' | '-' | '.'))
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    (hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit())).then_some(hex)
 }
@@ -0,0 +1,188 @@
+/// -----------------------------
+/// Phone regions
+/// -----------------------------
+/// `normalize_phone` 使用的地区表：国家代码、国内长途前缀、国际冠码与
+/// 国内有效号码（不含长途前缀）的位数范围。只覆盖常见地区，规则也比
+/// libphonenumber 简单：不区分号段，只按前缀与位数判断
+struct Region {
+    code: &'static str,
+    country_code: &'static str,
+    trunk_prefix: &'static str,
+    /// 手机号等号码书写时通常不带长途前缀
+    trunk_optional: bool,
+    idd_prefix: &'static str,
+    national_len: (usize, usize),
+}
+
+const REGIONS: &[Region] = &[
+    Region {
+        code: "US",
+        country_code: "1",
+        trunk_prefix: "1",
+        trunk_optional: true,
+        idd_prefix: "011",
+        national_len: (10, 10),
+    },
+    Region {
+        code: "CA",
+        country_code: "1",
+        trunk_prefix: "1",
+        trunk_optional: true,
+        idd_prefix: "011",
+        national_len: (10, 10),
+    },
+    Region {
+        code: "GB",
+        country_code: "44",
+        trunk_prefix: "0",
+        trunk_optional: false,
+        idd_prefix: "00",
+        national_len: (9, 10),
+    },
+    Region {
+        code: "DE",
+        country_code: "49",
+        trunk_prefix: "0",
+        trunk_optional: false,
+        idd_prefix: "00",
+        national_len: (6, 11),
+    },
+    Region {
+        code: "FR",
+        country_code: "33",
+        trunk_prefix: "0",
+        trunk_optional: false,
+        idd_prefix: "00",
+        national_len: (9, 9),
+    },
+    Region {
+        code: "CN",
+        country_code: "86",
+        trunk_prefix: "0",
+        trunk_optional: true,
+        idd_prefix: "00",
+        national_len: (10, 11),
+    },
+    Region {
+        code: "JP",
+        country_code: "81",
+        trunk_prefix: "0",
+        trunk_optional: false,
+        idd_prefix: "010",
+        national_len: (9, 10),
+    },
+    Region {
+        code: "IN",
+        country_code: "91",
+        trunk_prefix: "0",
+        trunk_optional: true,
+        idd_prefix: "00",
+        national_len: (10, 10),
+    },
+    Region {
+        code: "AU",
+        country_code: "61",
+        trunk_prefix: "0",
+        trunk_optional: false,
+        idd_prefix: "0011",
+        national_len: (9, 9),
+    },
+    Region {
+        code: "BR",
+        country_code: "55",
+        trunk_prefix: "0",
+        trunk_optional: false,
+        idd_prefix: "00",
+        national_len: (10, 11),
+    },
+];
+
+/// E.164 号码（不含 `+`）的位数范围
+const E164_LEN: (usize, usize) = (7, 15);
+
+fn region(code: &str) -> Option<&'static Region> {
+    REGIONS.iter().find(|r| r.code.eq_ignore_ascii_case(code))
+}
+
+/// `normalize_phone(region=...)` 支持的地区代码
+pub fn is_supported_region(code: &str) -> bool {
+    region(code).is_some()
+}
+
+/// 把号码改写为 E.164（`+` 加国家代码与国内有效号码）。允许空格、`-`、`.`、`/` 与括号
+/// 作为分隔；以 `+` 开头的号码只去掉分隔符，其余按 `region` 的国际冠码或国内格式解释：
+/// `normalize_phone("(415) 555-0123", "US")` 为 `"+14155550123"`。
+///
+/// 无法按该地区解释，或者有多种解释（如日本的 `010` 既是国际冠码，也可以是
+/// 长途前缀 `0` 加上以 `10` 开头的号码）时返回错误
+pub fn normalize_phone(input: &str, region_code: &str) -> Result<String, String> {
+    let region =
+        region(region_code).ok_or_else(|| format!("Unknown phone region '{}'", region_code))?;
+    let trimmed = input.trim();
+    let (international, rest) = match trimmed.strip_prefix('+') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed),
+    };
+    let mut digits = String::with_capacity(rest.len());
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            ' ' | '-' | '.' | '/' | '(' | ')' => {}
+            _ => return Err(format!("'{}' is not a phone number", input)),
+        }
+    }
+    if digits.is_empty() {
+        return Err(format!("'{}' is not a phone number", input));
+    }
+    if international {
+        return e164(&digits).ok_or_else(|| format!("'{}' is not a valid E.164 number", input));
+    }
+
+    let mut candidates: Vec<String> = Vec::new();
+    let mut push = |candidate: Option<String>| {
+        if let Some(c) = candidate
+            && !candidates.contains(&c)
+        {
+            candidates.push(c);
+        }
+    };
+    if let Some(rest) = digits.strip_prefix(region.idd_prefix) {
+        push(e164(rest));
+    }
+    if let Some(national) = digits.strip_prefix(region.trunk_prefix) {
+        push(national_e164(region, national));
+    }
+    if region.trunk_optional && !digits.starts_with(region.trunk_prefix) {
+        push(national_e164(region, &digits));
+    }
+    match candidates.len() {
+        0 => Err(format!(
+            "'{}' is not a valid phone number for region {}",
+            input, region.code
+        )),
+        1 => Ok(candidates.remove(0)),
+        _ => Err(format!(
+            "'{}' is ambiguous for region {}: {}",
+            input,
+            region.code,
+            candidates.join(" or ")
+        )),
+    }
+}
+
+/// 国内有效号码不以 `0` 开头，位数在地区的范围内
+fn national_e164(region: &Region, national: &str) -> Option<String> {
+    let (min, max) = region.national_len;
+    if national.starts_with('0') || !(min..=max).contains(&national.len()) {
+        return None;
+    }
+    e164(&format!("{}{}", region.country_code, national))
+}
+
+fn e164(digits: &str) -> Option<String> {
+    let (min, max) = E164_LEN;
+    if digits.starts_with('0') || !(min..=max).contains(&digits.len()) {
+        return None;
+    }
+    Some(format!("+{}", digits))
+}
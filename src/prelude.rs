@@ -0,0 +1,84 @@
+use once_cell::sync::Lazy;
+
+use crate::{ast::FieldRule, parser::Parser};
+
+/// -----------------------------
+/// Prelude
+/// -----------------------------
+/// 随 crate 提供的常用 API 信封字段：幂等键、请求 ID、客户端版本与限流元数据。
+/// 可整体并入业务 schema，也可按名称取出单个类型
+pub const ENVELOPE_DSL: &str = r#"(
+    idempotency_key?: uuid | ulid,
+    request_id?: string[1, 128] regex("^[A-Za-z0-9._:-]+$"),
+    client_version?: semver,
+    rate_limit?: object(
+        limit: int[1, 1000000],
+        remaining: int[0, 1000000],
+        reset: timestamp
+    )
+)"#;
+
+static ENVELOPE: Lazy<Vec<FieldRule>> =
+    Lazy::new(|| Parser::parse_rules(ENVELOPE_DSL).expect("invalid prelude schema"));
+
+/// 信封字段的全部规则（均为可选字段）
+pub fn envelope() -> Vec<FieldRule> {
+    ENVELOPE.clone()
+}
+
+/// 按名称取出单个信封类型，如 `idempotency_key`、`client_version`
+pub fn named(name: &str) -> Option<FieldRule> {
+    ENVELOPE.iter().find(|r| r.field == name).cloned()
+}
+
+/// -----------------------------
+/// Standard types
+/// -----------------------------
+/// DSL 以 `import std` 开头时可以按名称使用的类型，如 `shipping:Address`、`area?:array<GeoPoint>`。
+/// 定义本身也是 DSL，第一次用到时才解析
+pub const STD_DSL: &str = r#"(
+    Address: object(
+        line1: string[1, 200],
+        line2?: string[1, 200],
+        city: string[1, 100],
+        region?: string[1, 100],
+        postal_code: postalcode,
+        country: countrycode
+    ),
+    GeoPoint: object(
+        lat: float[-90, 90],
+        lng: float[-180, 180]
+    ),
+    Pagination: object(
+        page: int[1, 1000000] = 1,
+        per_page: int[1, 100] = 20
+    ),
+    DateRange: object(
+        start: date,
+        end: date
+    ),
+    Money: money
+)"#;
+
+static STD: Lazy<Vec<FieldRule>> =
+    Lazy::new(|| Parser::parse_rules(STD_DSL).expect("invalid std schema"));
+
+/// 按名称取出 `import std` 中的类型，字段名即类型名
+pub fn std_type(name: &str) -> Option<FieldRule> {
+    STD.iter().find(|r| r.field == name).cloned()
+}
+
+/// `import std` 提供的全部类型名
+pub fn std_type_names() -> Vec<&'static str> {
+    STD.iter().map(|r| r.field.as_str()).collect()
+}
+
+/// 把信封字段追加到业务规则之后；业务 schema 中已有的同名字段保持不变
+pub fn with_envelope(mut rules: Vec<FieldRule>) -> Vec<FieldRule> {
+    for rule in ENVELOPE.iter() {
+        if !rules.iter().any(|r| r.field == rule.field) {
+            rules.push(rule.clone());
+        }
+    }
+    rules
+}
@@ -0,0 +1,260 @@
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+
+use crate::ast::{Constraint, FieldRule, Value};
+
+/// -----------------------------
+/// RefResolver
+/// -----------------------------
+/// `exists("user")` 使用的外部查找，例如按 ID 查询数据库
+pub trait RefResolver: Send + Sync {
+    fn exists(&self, kind: &str, id: &Value) -> bool;
+}
+
+impl<F> RefResolver for F
+where
+    F: Fn(&str, &Value) -> bool + Send + Sync,
+{
+    fn exists(&self, kind: &str, id: &Value) -> bool {
+        self(kind, id)
+    }
+}
+
+/// [`RefResolver`] 的异步版本，配合 `validate_async` 使用；实现时可直接写 `async fn`
+pub trait AsyncRefResolver: Send + Sync {
+    fn exists(&self, kind: &str, id: &Value) -> impl Future<Output = bool> + Send;
+}
+
+/// 异步查找的结果，供同步校验阶段使用
+#[derive(Debug, Default)]
+pub(crate) struct ResolvedRefs {
+    found: HashSet<(String, String)>,
+}
+
+impl ResolvedRefs {
+    /// 逐个等待去重后的 `(kind, id)`，只记录存在的
+    pub(crate) async fn resolve(
+        pending: Vec<(String, Value)>,
+        resolver: &impl AsyncRefResolver,
+    ) -> Self {
+        let mut found = HashSet::new();
+        for (kind, id) in pending {
+            if resolver.exists(&kind, &id).await {
+                found.insert((kind, id.to_string()));
+            }
+        }
+        Self { found }
+    }
+}
+
+impl RefResolver for ResolvedRefs {
+    fn exists(&self, kind: &str, id: &Value) -> bool {
+        self.found.contains(&(kind.to_string(), id.to_string()))
+    }
+}
+
+/// 按规则收集文档中所有 `exists(kind)` 需要查找的 `(kind, id)`，已去重
+pub(crate) fn collect_exists(value: &Value, rules: &[FieldRule]) -> Vec<(String, Value)> {
+    let mut out = Vec::new();
+    let mut seen = HashSet::new();
+    for rule in rules {
+        collect_exists_field(value, rule, &mut out, &mut seen);
+    }
+    out
+}
+
+fn collect_exists_field(
+    value: &Value,
+    rule: &FieldRule,
+    out: &mut Vec<(String, Value)>,
+    seen: &mut HashSet<(String, String)>,
+) {
+    let val = match value {
+        Value::Object(map) => match map.get(rule.field.as_str()) {
+            Some(v) => v,
+            None => return,
+        },
+        _ => value,
+    };
+    collect_exists_value(val, rule, out, seen);
+}
+
+/// 与校验器的递归方式保持一致：子规则作用于数组元素，children 作用于对象字段
+fn collect_exists_value(
+    val: &Value,
+    rule: &FieldRule,
+    out: &mut Vec<(String, Value)>,
+    seen: &mut HashSet<(String, String)>,
+) {
+    if let Some(c) = &rule.constraints {
+        for item in &c.items {
+            if let Constraint::Exists(kind) = item
+                && seen.insert((kind.clone(), val.to_string()))
+            {
+                out.push((kind.clone(), val.clone()));
+            }
+        }
+    }
+    if let Some(sub) = &rule.rule {
+        match val {
+            Value::Object(_) => collect_exists_field(val, sub, out, seen),
+            Value::Array(items) => {
+                for item in items {
+                    collect_exists_value(item, sub, out, seen);
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(children) = &rule.children
+        && let Value::Object(_) = val
+    {
+        for child in children {
+            collect_exists_field(val, child, out, seen);
+        }
+    }
+}
+
+/// -----------------------------
+/// RefIndex
+/// -----------------------------
+/// `ref(users[].id)` 使用的文档内索引：在对象校验开始时，
+/// 按规则中出现的每条引用路径收集一次目标值
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RefIndex {
+    keys: HashMap<String, HashSet<String>>,
+}
+
+impl RefIndex {
+    /// 规则中没有 ref 约束时返回 None，避免无谓的遍历
+    pub(crate) fn build(root: &Value, rules: &[FieldRule]) -> Option<Self> {
+        let mut paths = Vec::new();
+        collect_ref_paths(rules, &mut paths);
+        if paths.is_empty() {
+            return None;
+        }
+        let keys = paths
+            .into_iter()
+            .map(|path| {
+                let values = resolve_path(root, &path)
+                    .into_iter()
+                    .map(|v| v.to_string())
+                    .collect();
+                (path, values)
+            })
+            .collect();
+        Some(Self { keys })
+    }
+
+    /// 与 [`RefIndex::build`] 相同，直接在 `serde_json` 的文档上按路径取值，只转换取到的目标值
+    #[cfg(feature = "json-value")]
+    pub(crate) fn build_json(
+        root: &serde_json::Map<String, serde_json::Value>,
+        rules: &[FieldRule],
+    ) -> Option<Self> {
+        let mut paths = Vec::new();
+        collect_ref_paths(rules, &mut paths);
+        if paths.is_empty() {
+            return None;
+        }
+        let keys = paths
+            .into_iter()
+            .map(|path| {
+                let values = resolve_json_path(root, &path)
+                    .into_iter()
+                    .map(|v| Value::from(v.clone()).to_string())
+                    .collect();
+                (path, values)
+            })
+            .collect();
+        Some(Self { keys })
+    }
+
+    /// 规则中是否有 ref 约束
+    pub(crate) fn wanted(rules: &[FieldRule]) -> bool {
+        let mut paths = Vec::new();
+        collect_ref_paths(rules, &mut paths);
+        !paths.is_empty()
+    }
+
+    pub(crate) fn contains(&self, path: &str, value: &Value) -> bool {
+        self.keys
+            .get(path)
+            .is_some_and(|values| values.contains(&value.to_string()))
+    }
+}
+
+fn collect_ref_paths(rules: &[FieldRule], out: &mut Vec<String>) {
+    for rule in rules {
+        if let Some(c) = &rule.constraints {
+            for item in &c.items {
+                if let Constraint::Ref(path) = item
+                    && !out.contains(path)
+                {
+                    out.push(path.clone());
+                }
+            }
+        }
+        if let Some(sub) = &rule.rule {
+            collect_ref_paths(std::slice::from_ref(sub.as_ref()), out);
+        }
+        if let Some(children) = &rule.children {
+            collect_ref_paths(children, out);
+        }
+    }
+}
+
+/// 从文档根按路径取值，`name[]` 表示展开该数组的每个元素
+pub(crate) fn resolve_path<'a>(root: &'a Value, path: &str) -> Vec<&'a Value> {
+    let mut current = vec![root];
+    for segment in path.split('.') {
+        let (key, spread) = match segment.strip_suffix("[]") {
+            Some(key) => (key, true),
+            None => (segment, false),
+        };
+        let mut next = Vec::new();
+        for v in current {
+            let Some(child) = v.as_object().and_then(|map| map.get(key)) else {
+                continue;
+            };
+            match child {
+                Value::Array(items) if spread => next.extend(items.iter()),
+                _ if spread => {}
+                _ => next.push(child),
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+/// 与 [`resolve_path`] 相同，对象中的 null 视为缺失（与转换为 `Value` 时一致）
+#[cfg(feature = "json-value")]
+fn resolve_json_path<'a>(
+    root: &'a serde_json::Map<String, serde_json::Value>,
+    path: &str,
+) -> Vec<&'a serde_json::Value> {
+    let mut objects = vec![root];
+    let mut found = Vec::new();
+    for segment in path.split('.') {
+        let (key, spread) = match segment.strip_suffix("[]") {
+            Some(key) => (key, true),
+            None => (segment, false),
+        };
+        found.clear();
+        for map in objects {
+            match map.get(key) {
+                None | Some(serde_json::Value::Null) => {}
+                Some(serde_json::Value::Array(items)) if spread => found.extend(items.iter()),
+                Some(_) if spread => {}
+                Some(child) => found.push(child),
+            }
+        }
+        objects = found
+            .iter()
+            .copied()
+            .filter_map(serde_json::Value::as_object)
+            .collect();
+    }
+    found
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{
+    ast::Value,
+    checksum::{self, Checksum},
+    decrypt::Decryptor,
+};
+
+/// -----------------------------
+/// CustomValidator
+/// -----------------------------
+/// DSL 中 `check("name")` 按名称调用的自定义校验逻辑
+pub trait CustomValidator: Send + Sync {
+    /// 校验通过返回 `Ok(())`，否则返回失败原因
+    fn check(&self, value: &Value) -> Result<(), String>;
+}
+
+impl<F> CustomValidator for F
+where
+    F: Fn(&Value) -> Result<(), String> + Send + Sync,
+{
+    fn check(&self, value: &Value) -> Result<(), String> {
+        self(value)
+    }
+}
+
+/// -----------------------------
+/// ValidatorRegistry
+/// -----------------------------
+/// 名称到自定义校验器、校验和算法与解密器的映射，克隆时共享已注册的实现
+#[derive(Clone, Default)]
+pub struct ValidatorRegistry {
+    validators: HashMap<String, Arc<dyn CustomValidator>>,
+    checksums: HashMap<String, Arc<dyn Checksum>>,
+    decryptors: HashMap<String, Arc<dyn Decryptor>>,
+}
+
+impl ValidatorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册（或替换）名为 `name` 的校验器
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        validator: impl CustomValidator + 'static,
+    ) -> &mut Self {
+        self.validators.insert(name.into(), Arc::new(validator));
+        self
+    }
+
+    /// 编译 WASM 模块（二进制或 WAT 文本）并以 `name` 注册，模块约定见 [`crate::wasm`]
+    #[cfg(feature = "wasm")]
+    pub fn register_wasm(
+        &mut self,
+        name: impl Into<String>,
+        module: impl AsRef<[u8]>,
+    ) -> Result<&mut Self, String> {
+        let validator = crate::wasm::WasmValidator::new(module)?;
+        Ok(self.register(name, validator))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn CustomValidator> {
+        self.validators.get(name).map(|v| v.as_ref())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.validators.contains_key(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.validators.remove(name).is_some()
+    }
+
+    /// 注册（或覆盖同名内置的）校验和算法
+    pub fn register_checksum(
+        &mut self,
+        name: impl Into<String>,
+        algorithm: impl Checksum + 'static,
+    ) -> &mut Self {
+        self.checksums.insert(name.into(), Arc::new(algorithm));
+        self
+    }
+
+    /// 查找校验和算法，先查已注册的，再查内置的
+    pub fn checksum(&self, name: &str) -> Option<&dyn Checksum> {
+        match self.checksums.get(name) {
+            Some(c) => Some(c.as_ref()),
+            None => checksum::builtin(name),
+        }
+    }
+
+    /// 注册（或替换）`encrypted(name)` 使用的解密器
+    pub fn register_decryptor(
+        &mut self,
+        name: impl Into<String>,
+        decryptor: impl Decryptor + 'static,
+    ) -> &mut Self {
+        self.decryptors.insert(name.into(), Arc::new(decryptor));
+        self
+    }
+
+    pub fn decryptor(&self, name: &str) -> Option<&dyn Decryptor> {
+        self.decryptors.get(name).map(|d| d.as_ref())
+    }
+}
+
+impl fmt::Debug for ValidatorRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut validators: Vec<&String> = self.validators.keys().collect();
+        validators.sort();
+        let mut checksums: Vec<&String> = self.checksums.keys().collect();
+        checksums.sort();
+        let mut decryptors: Vec<&String> = self.decryptors.keys().collect();
+        decryptors.sort();
+        f.debug_struct("ValidatorRegistry")
+            .field("validators", &validators)
+            .field("checksums", &checksums)
+            .field("decryptors", &decryptors)
+            .finish()
+    }
+}
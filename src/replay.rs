@@ -0,0 +1,315 @@
+use std::{
+    collections::HashSet,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde_json::json;
+
+use crate::{
+    ast::{FieldRule, FieldType, Value},
+    audit::{fingerprint, payload_hash},
+    schema::Schema,
+    validator::ValidationContext,
+};
+
+/// -----------------------------
+/// Corpus
+/// -----------------------------
+/// 回放用的载荷库：目录下每个 `*.json` 文件是一条用例，保存脱敏后的载荷及其校验结论。
+/// 修改 schema 后用 [`Schema::replay`] 重新校验全部用例，找出结论发生变化的载荷。
+///
+/// 脱敏：`password` / `token` 字段以及 [`Corpus::redact`] 指定路径上的值整个替换为同类型的占位值：
+/// 字符串换成字节数相同的 `*`，长度约束（按字节计）的结果不变；数字换成 0、bool 换成 false，
+/// 数组与对象逐项替换，保留结构与键名。用例中的结论是脱敏后载荷的结论
+#[derive(Debug, Clone)]
+pub struct Corpus {
+    dir: PathBuf,
+    redact: HashSet<String>,
+}
+
+/// 一条用例的结论，错误以文本保存以便逐字比较
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayOutcome {
+    pub passed: bool,
+    pub errors: Vec<String>,
+}
+
+impl ReplayOutcome {
+    fn of(result: crate::validator::Result<()>) -> Self {
+        match result {
+            Ok(()) => Self {
+                passed: true,
+                errors: Vec::new(),
+            },
+            Err(e) => Self {
+                passed: false,
+                errors: vec![e.to_string()],
+            },
+        }
+    }
+}
+
+impl fmt::Display for ReplayOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.passed {
+            write!(f, "passed")
+        } else {
+            write!(f, "failed: {}", self.errors.join("; "))
+        }
+    }
+}
+
+/// 结论发生变化的用例
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayChange {
+    pub path: PathBuf,
+    pub expected: ReplayOutcome,
+    pub actual: ReplayOutcome,
+}
+
+/// 一次回放的结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayReport {
+    /// 回放的用例数
+    pub cases: usize,
+    pub changes: Vec<ReplayChange>,
+}
+
+impl ReplayReport {
+    pub fn is_unchanged(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// 有用例结论变化时 panic 并列出全部变化，用于测试
+    pub fn assert_unchanged(&self) {
+        if self.is_unchanged() {
+            return;
+        }
+        let lines: Vec<String> = self
+            .changes
+            .iter()
+            .map(|c| {
+                format!(
+                    "  {}: expected {}, got {}",
+                    c.path.display(),
+                    c.expected,
+                    c.actual
+                )
+            })
+            .collect();
+        panic!(
+            "{} of {} replayed payloads changed outcome:\n{}",
+            self.changes.len(),
+            self.cases,
+            lines.join("\n")
+        );
+    }
+}
+
+impl Corpus {
+    /// 使用（不存在时创建）目录
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+            redact: HashSet::new(),
+        })
+    }
+
+    /// 额外需要脱敏的字段路径，点号分隔，数组元素直接写子字段：`users.card`
+    pub fn redact<I, S>(mut self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.redact.extend(paths.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 同 [`Corpus::record_with_context`]，使用默认的校验环境
+    pub fn record(&self, schema: &Schema, payload: &Value) -> io::Result<Option<PathBuf>> {
+        self.record_with_context(schema, payload, ValidationContext::shared())
+    }
+
+    /// 脱敏后校验，失败时写入用例并返回文件路径；通过时不记录。
+    /// 文件名取自脱敏载荷的哈希，相同载荷只保存一份
+    pub fn record_with_context(
+        &self,
+        schema: &Schema,
+        payload: &Value,
+        ctx: &ValidationContext,
+    ) -> io::Result<Option<PathBuf>> {
+        let mut redacted = payload.clone();
+        self.redact_object(&mut redacted, schema.rules(), "");
+        let outcome = ReplayOutcome::of(schema.validate_with_context(&mut redacted.clone(), ctx));
+        if outcome.passed {
+            return Ok(None);
+        }
+        let hash = payload_hash(&redacted);
+        let path = self.dir.join(format!("{}.json", &hash[..16]));
+        write_case(&path, schema.rules(), &redacted, &outcome)?;
+        Ok(Some(path))
+    }
+
+    /// 接受一处有意的变化：把用例的结论更新为新的结论
+    pub fn accept(&self, change: &ReplayChange, schema: &Schema) -> io::Result<()> {
+        let (payload, _) = read_case(&change.path)?;
+        write_case(&change.path, schema.rules(), &payload, &change.actual)
+    }
+
+    /// 全部用例中保存的（脱敏后的）载荷，可作为变异测试的样本
+    pub fn payloads(&self) -> io::Result<Vec<Value>> {
+        self.cases()?
+            .iter()
+            .map(|path| read_case(path).map(|(payload, _)| payload))
+            .collect()
+    }
+
+    /// 按文件名排序的全部用例
+    fn cases(&self) -> io::Result<Vec<PathBuf>> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(&self.dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<_>>()?;
+        paths.retain(|p| p.extension().is_some_and(|ext| ext == "json"));
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn redact_object(&self, value: &mut Value, rules: &[FieldRule], prefix: &str) {
+        let Value::Object(map) = value else {
+            return;
+        };
+        for rule in rules {
+            if let Some(v) = map.get_mut(rule.field.as_str()) {
+                let path = if prefix.is_empty() {
+                    rule.field.to_string()
+                } else {
+                    format!("{}.{}", prefix, rule.field)
+                };
+                self.redact_value(v, rule, &path);
+            }
+        }
+    }
+
+    fn redact_value(&self, value: &mut Value, rule: &FieldRule, path: &str) {
+        if is_sensitive(rule) || self.redact.contains(path) {
+            mask(value);
+            return;
+        }
+        match value {
+            Value::Object(_) => {
+                if let Some(children) = &rule.children {
+                    self.redact_object(value, children, path);
+                }
+            }
+            Value::Array(items) => {
+                if let Some(sub) = &rule.rule {
+                    for item in items {
+                        self.redact_value(item, sub, path);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 把值换成同类型的占位值，数组与对象逐项替换
+fn mask(value: &mut Value) {
+    match value {
+        // 与字符串长度区间相同按字节计数，多字节字符替换后长度不变
+        Value::String(s) => *s = "*".repeat(s.len()),
+        Value::Int(i) => *i = 0,
+        #[cfg(feature = "bigint")]
+        Value::BigInt(_) => *value = Value::Int(0),
+        Value::Float(f) => *f = 0.0,
+        Value::Bool(b) => *b = false,
+        Value::Array(items) => items.iter_mut().for_each(mask),
+        Value::Object(map) => map.values_mut().for_each(mask),
+    }
+}
+
+fn is_sensitive(rule: &FieldRule) -> bool {
+    let types = match &rule.union_types {
+        Some(types) => types.as_slice(),
+        None => std::slice::from_ref(&rule.field_type),
+    };
+    types
+        .iter()
+        .any(|t| matches!(t, FieldType::Password | FieldType::Token))
+}
+
+fn invalid(path: &Path, message: impl fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {}", path.display(), message),
+    )
+}
+
+fn write_case(
+    path: &Path,
+    rules: &[FieldRule],
+    payload: &Value,
+    outcome: &ReplayOutcome,
+) -> io::Result<()> {
+    let case = json!({
+        "schema": fingerprint(rules),
+        "payload": serde_json::Value::from(payload),
+        "passed": outcome.passed,
+        "errors": outcome.errors,
+    });
+    let text = serde_json::to_string_pretty(&case).map_err(|e| invalid(path, e))?;
+    fs::write(path, text + "\n")
+}
+
+fn read_case(path: &Path) -> io::Result<(Value, ReplayOutcome)> {
+    let text = fs::read_to_string(path)?;
+    let case: serde_json::Value = serde_json::from_str(&text).map_err(|e| invalid(path, e))?;
+    let payload = case
+        .get("payload")
+        .cloned()
+        .map(Value::from)
+        .ok_or_else(|| invalid(path, "missing payload"))?;
+    let passed = case
+        .get("passed")
+        .and_then(|p| p.as_bool())
+        .ok_or_else(|| invalid(path, "missing passed"))?;
+    let errors = case
+        .get("errors")
+        .and_then(|e| e.as_array())
+        .map(|errors| {
+            errors
+                .iter()
+                .map(|e| e.as_str().map_or_else(|| e.to_string(), str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok((payload, ReplayOutcome { passed, errors }))
+}
+
+/// 用 `schema` 重新校验全部用例，与保存的结论逐条比较
+pub(crate) fn replay(
+    schema: &Schema,
+    corpus: &Corpus,
+    ctx: &ValidationContext,
+) -> io::Result<ReplayReport> {
+    let mut report = ReplayReport::default();
+    for path in corpus.cases()? {
+        let (mut payload, expected) = read_case(&path)?;
+        let actual = ReplayOutcome::of(schema.validate_with_context(&mut payload, ctx));
+        report.cases += 1;
+        if actual != expected {
+            report.changes.push(ReplayChange {
+                path,
+                expected,
+                actual,
+            });
+        }
+    }
+    Ok(report)
+}
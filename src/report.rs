@@ -0,0 +1,541 @@
+use std::fmt;
+
+use serde::ser::SerializeStruct;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    ast::{Constraint, FieldRule, Value},
+    i18n::{Labels, Locales, MessageResolver},
+    validator::{Path, ValidationError},
+};
+
+/// -----------------------------
+/// ValidationReport
+/// -----------------------------
+/// 一次校验成功后附带的信息，由 `validate_with_report` 返回
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    /// 开启 `canonicalize` 时被规范化的值，按遍历顺序排列
+    pub normalized: Vec<Normalization>,
+    /// 校验对载荷做的全部修改：coerce 转换、填充的默认值、规范化，按发生顺序排列
+    pub changes: Vec<Change>,
+    /// `warn(...)` 与 `deprecated` 产生的警告，不影响校验结论，按校验顺序排列
+    pub warnings: Vec<ValidationError>,
+    /// 警告数达到 `max_errors` 后其余警告被丢弃
+    pub warnings_truncated: bool,
+    /// 带 `hash_into_report(..)` 标记的字段的哈希，按遍历顺序排列，缺失的字段没有记录
+    pub hashes: Vec<FieldHash>,
+    /// 开启 `timing` feature 时每个字段的 enum 与各个约束的耗时，按校验顺序排列
+    #[cfg(feature = "timing")]
+    pub timings: Vec<ConstraintTiming>,
+}
+
+#[cfg(feature = "timing")]
+impl ValidationReport {
+    /// 耗时最长的 `n` 项
+    pub fn slowest(&self, n: usize) -> Vec<&ConstraintTiming> {
+        let mut timings: Vec<&ConstraintTiming> = self.timings.iter().collect();
+        timings.sort_by_key(|t| std::cmp::Reverse(t.elapsed));
+        timings.truncate(n);
+        timings
+    }
+}
+
+/// 一次就地规范化：字段路径（如 `devices[0].mac`）及前后的值
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Normalization {
+    pub path: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// 字段值的哈希（小写十六进制），用于在不暴露原值的情况下去重或统计邮箱等敏感数据。
+/// 按校验后的值计算：字符串为其 UTF-8 字节，其余值为 [`Value::to_canonical_json`] 的输出
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldHash {
+    pub path: String,
+    pub algorithm: String,
+    pub hash: String,
+}
+
+/// `hash_into_report(..)` 支持的算法
+pub(crate) const HASH_ALGORITHMS: &[&str] = &["sha256"];
+
+/// 按规则遍历对象，为带 `hash_into_report(..)` 的字段计算哈希
+pub(crate) fn hash_fields(
+    value: &Value,
+    rules: &[FieldRule],
+    prefix: Path,
+    out: &mut Vec<FieldHash>,
+) {
+    let Value::Object(map) = value else {
+        return;
+    };
+    for rule in rules {
+        if let Some(v) = map.get(rule.field.as_str()) {
+            hash_value(v, rule, prefix.field(&rule.field), out);
+        }
+    }
+}
+
+fn hash_value(value: &Value, rule: &FieldRule, path: Path, out: &mut Vec<FieldHash>) {
+    let algorithms = rule
+        .constraints
+        .iter()
+        .flat_map(|c| &c.items)
+        .filter_map(|c| match c {
+            Constraint::HashIntoReport(algorithm) => Some(algorithm),
+            _ => None,
+        });
+    for algorithm in algorithms {
+        let digest = match value {
+            Value::String(s) => Sha256::digest(s),
+            other => match other.to_canonical_json() {
+                Ok(json) => Sha256::digest(json),
+                Err(_) => continue,
+            },
+        };
+        out.push(FieldHash {
+            path: path.to_string(),
+            algorithm: algorithm.clone(),
+            hash: digest.iter().map(|b| format!("{:02x}", b)).collect(),
+        });
+    }
+    match value {
+        Value::Object(_) => {
+            if let Some(children) = &rule.children {
+                hash_fields(value, children, path, out);
+            }
+        }
+        Value::Array(items) => {
+            if let Some(sub) = &rule.rule {
+                for (i, item) in items.iter().enumerate() {
+                    hash_value(item, sub, path.index(i), out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// -----------------------------
+/// ErrorReport
+/// -----------------------------
+/// 校验失败时的结构化报告，按字段路径分组，可以直接序列化为 HTTP 422 响应体：
+///
+/// ```json
+/// {
+///   "errors": {
+///     "age": [{"code": "out_of_range", "message": "...", "params": {"value": "200", "min": "0", "max": "150"}}],
+///     "profile.email": [{"code": "regex_mismatch", "message": "...", "params": {...}}]
+///   },
+///   "truncated": false
+/// }
+/// ```
+///
+/// `exhaustive` 模式下同一字段的多个失败逐条展开；没有字段的错误（`Custom`）路径为空字符串
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorReport {
+    /// 按路径首次出现的顺序排列
+    pub fields: Vec<FieldFailures>,
+    /// 达到 `max_errors` 后还有失败被丢弃
+    pub truncated: bool,
+}
+
+/// 同一路径下的全部失败
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldFailures {
+    pub path: String,
+    pub failures: Vec<Failure>,
+}
+
+/// 一个失败：错误代码、面向用户的文本与消息参数（不含 `field`，路径已作为分组的键）
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    pub code: &'static str,
+    pub message: String,
+    pub params: Vec<(&'static str, String)>,
+}
+
+impl ErrorReport {
+    /// 消息使用错误自身的英文文本
+    pub fn from_error(error: &ValidationError) -> Self {
+        Self::build(error, |e| e.to_string())
+    }
+
+    /// 消息由 `resolver` 翻译，见 [`ValidationError::localized`]
+    pub fn localized(error: &ValidationError, resolver: &dyn MessageResolver) -> Self {
+        Self::build(error, |e| e.localized(resolver))
+    }
+
+    /// 消息由 `resolver` 翻译，模板中的 `{label}` 取 `labels`，见 [`ValidationError::localized_with_labels`]
+    pub fn localized_with_labels(
+        error: &ValidationError,
+        resolver: &dyn MessageResolver,
+        labels: &Labels,
+    ) -> Self {
+        Self::build(error, |e| e.localized_with_labels(resolver, labels))
+    }
+
+    /// 消息使用按 HTTP `Accept-Language` 从 `locales` 中选出的消息包（见 [`Locales::negotiate`]），
+    /// 没有可用的消息包时使用英文文本
+    pub fn negotiated(error: &ValidationError, locales: &Locales, accept_language: &str) -> Self {
+        match locales.resolver(accept_language) {
+            Some(resolver) => Self::localized(error, resolver),
+            None => Self::from_error(error),
+        }
+    }
+
+    fn build(error: &ValidationError, message: impl Fn(&ValidationError) -> String) -> Self {
+        let mut report = Self::default();
+        let errors = match error {
+            ValidationError::FieldErrors {
+                errors, truncated, ..
+            } => {
+                report.truncated = *truncated;
+                errors.as_slice()
+            }
+            e => std::slice::from_ref(e),
+        };
+        for e in errors {
+            let path = e.field().unwrap_or_default();
+            let failure = Failure {
+                code: e.code(),
+                message: message(e),
+                params: e
+                    .params()
+                    .into_iter()
+                    .filter(|(k, _)| *k != "field")
+                    .collect(),
+            };
+            match report.fields.iter_mut().find(|f| f.path == path) {
+                Some(field) => field.failures.push(failure),
+                None => report.fields.push(FieldFailures {
+                    path: path.to_string(),
+                    failures: vec![failure],
+                }),
+            }
+        }
+        report
+    }
+
+    /// 某个路径下的失败
+    pub fn get(&self, path: &str) -> Option<&[Failure]> {
+        self.fields
+            .iter()
+            .find(|f| f.path == path)
+            .map(|f| f.failures.as_slice())
+    }
+
+    /// 失败总数
+    pub fn len(&self) -> usize {
+        self.fields.iter().map(|f| f.failures.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+}
+
+impl From<&ValidationError> for ErrorReport {
+    fn from(error: &ValidationError) -> Self {
+        Self::from_error(error)
+    }
+}
+
+impl serde::Serialize for ErrorReport {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Fields<'a>(&'a [FieldFailures]);
+
+        impl serde::Serialize for Fields<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_map(self.0.iter().map(|f| (&f.path, &f.failures)))
+            }
+        }
+
+        let mut report = serializer.serialize_struct("ErrorReport", 2)?;
+        report.serialize_field("errors", &Fields(&self.fields))?;
+        report.serialize_field("truncated", &self.truncated)?;
+        report.end()
+    }
+}
+
+impl serde::Serialize for Failure {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Params<'a>(&'a [(&'static str, String)]);
+
+        impl serde::Serialize for Params<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_map(self.0.iter().map(|(k, v)| (k, v)))
+            }
+        }
+
+        let mut failure = serializer.serialize_struct("Failure", 3)?;
+        failure.serialize_field("code", self.code)?;
+        failure.serialize_field("message", &self.message)?;
+        failure.serialize_field("params", &Params(&self.params))?;
+        failure.end()
+    }
+}
+
+/// 对载荷的一处修改：字段路径（与错误中的路径相同，如 `users[0].age`）、
+/// 修改方式及前后的值；填充默认值时 `before` 为 `None`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub path: String,
+    pub kind: ChangeKind,
+    pub before: Option<Value>,
+    pub after: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// 缺失的字段填入默认值
+    Default,
+    /// `coerce` 选项按声明类型转换
+    Coerce,
+    /// `canonicalize` 选项规范化
+    Normalize,
+    /// `slugify` 等转换改写
+    Transform,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Default => "default",
+            Self::Coerce => "coerce",
+            Self::Normalize => "normalize",
+            Self::Transform => "transform",
+        })
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.before {
+            Some(before) => write!(
+                f,
+                "{}: {} {} -> {}",
+                self.path, self.kind, before, self.after
+            ),
+            None => write!(f, "{}: {} {}", self.path, self.kind, self.after),
+        }
+    }
+}
+
+/// -----------------------------
+/// OutcomeReport
+/// -----------------------------
+/// 逐个字段的校验结论，由 `validate_outcomes` 返回。与 `validate` 不同，
+/// 不在第一个失败处停止，每个声明的字段都有结论；对象字段的子字段、
+/// 对象数组的各个元素（路径如 `items[0]`）放在 `children` 中
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutcomeReport {
+    pub fields: Vec<FieldOutcome>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldOutcome {
+    /// 完整路径，与错误中的路径相同
+    pub path: String,
+    pub outcome: Outcome,
+    pub children: Vec<FieldOutcome>,
+}
+
+/// 一个字段的结论；子字段失败时所在的对象字段同样为 `Failed`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// 字段存在且通过校验
+    Passed,
+    /// 未通过校验，包括缺失的必填字段
+    Failed(ValidationError),
+    /// 字段缺失，填入的默认值通过校验
+    Defaulted(Value),
+    /// 可选字段缺失且没有默认值
+    SkippedOptional,
+}
+
+impl Outcome {
+    /// `passed`、`failed`、`defaulted`、`skipped_optional`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Passed => "passed",
+            Self::Failed(_) => "failed",
+            Self::Defaulted(_) => "defaulted",
+            Self::SkippedOptional => "skipped_optional",
+        }
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed(_))
+    }
+}
+
+impl OutcomeReport {
+    /// 所有字段都没有失败
+    pub fn is_valid(&self) -> bool {
+        !self.fields.iter().any(|f| f.outcome.is_failed())
+    }
+
+    /// 按路径查找，如 `profile.email`、`items[0].qty`
+    pub fn get(&self, path: &str) -> Option<&FieldOutcome> {
+        self.iter().find(|f| f.path == path)
+    }
+
+    /// 深度优先遍历全部字段
+    pub fn iter(&self) -> impl Iterator<Item = &FieldOutcome> {
+        let mut stack: Vec<&FieldOutcome> = self.fields.iter().rev().collect();
+        std::iter::from_fn(move || {
+            let next = stack.pop()?;
+            stack.extend(next.children.iter().rev());
+            Some(next)
+        })
+    }
+
+    /// 失败的字段中最深的那些（子字段都没有失败），即表单中需要标出的字段
+    pub fn failed(&self) -> impl Iterator<Item = (&str, &ValidationError)> {
+        self.iter().filter_map(|f| match &f.outcome {
+            Outcome::Failed(e) if !f.children.iter().any(|c| c.outcome.is_failed()) => {
+                Some((f.path.as_str(), e))
+            }
+            _ => None,
+        })
+    }
+
+    /// 填入了默认值的字段及填入的值
+    pub fn defaulted(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.iter().filter_map(|f| match &f.outcome {
+            Outcome::Defaulted(v) => Some((f.path.as_str(), v)),
+            _ => None,
+        })
+    }
+}
+
+/// -----------------------------
+/// ChangeSet
+/// -----------------------------
+/// `validate_changes` 计算出、尚未写入载荷的修改，按发生顺序排列。
+/// 调用方可以先审阅（或记入审计日志），再用 `apply` 写入
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChangeSet {
+    changes: Vec<Change>,
+}
+
+impl ChangeSet {
+    pub fn new(changes: Vec<Change>) -> Self {
+        Self { changes }
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Change> {
+        self.changes.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// 依次写入全部修改。填充默认值要求字段仍然缺失，其余修改要求当前值等于 `before`；
+    /// 任何一处不满足（载荷在校验之后被改动过）时返回错误，`value` 保持不变
+    pub fn apply(&self, value: &mut Value) -> Result<(), String> {
+        let mut updated = value.clone();
+        for change in &self.changes {
+            apply_change(&mut updated, change)
+                .map_err(|reason| format!("Cannot apply change at {}: {}", change.path, reason))?;
+        }
+        *value = updated;
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a ChangeSet {
+    type Item = &'a Change;
+    type IntoIter = std::slice::Iter<'a, Change>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.iter()
+    }
+}
+
+/// 路径中的一段：对象字段或数组下标
+enum Step<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+/// `users[0].name` 拆为 `users`、`0`、`name`
+fn steps(path: &str) -> Option<Vec<Step<'_>>> {
+    let mut steps = Vec::new();
+    for segment in path.split('.') {
+        let (name, indices) = match segment.find('[') {
+            Some(at) => segment.split_at(at),
+            None => (segment, ""),
+        };
+        if !name.is_empty() {
+            steps.push(Step::Key(name));
+        }
+        if !indices.is_empty() {
+            let inner = indices.strip_prefix('[')?.strip_suffix(']')?;
+            for index in inner.split("][") {
+                steps.push(Step::Index(index.parse().ok()?));
+            }
+        }
+    }
+    Some(steps)
+}
+
+fn apply_change(value: &mut Value, change: &Change) -> Result<(), &'static str> {
+    let steps = steps(&change.path).ok_or("invalid path")?;
+    let (last, parents) = steps.split_last().ok_or("invalid path")?;
+    let mut cur = value;
+    for step in parents {
+        cur = match (step, cur) {
+            (Step::Key(key), Value::Object(map)) => map.get_mut(*key),
+            (Step::Index(i), Value::Array(items)) => items.get_mut(*i),
+            _ => None,
+        }
+        .ok_or("path not found")?;
+    }
+    let slot = match (last, cur) {
+        (Step::Key(key), Value::Object(map)) => {
+            if change.before.is_none() {
+                if map.contains_key(*key) {
+                    return Err("field is already present");
+                }
+                map.insert(key.to_string(), change.after.clone());
+                return Ok(());
+            }
+            map.get_mut(*key)
+        }
+        (Step::Index(i), Value::Array(items)) => items.get_mut(*i),
+        _ => None,
+    }
+    .ok_or("path not found")?;
+    if change.before.as_ref() != Some(slot) {
+        return Err("value has changed since validation");
+    }
+    *slot = change.after.clone();
+    Ok(())
+}
+
+/// 一项检查的耗时：字段路径（与错误中的路径相同，如 `users[0].name`）、
+/// 约束的 DSL 写法（enum 记为 `enum`）及耗时
+#[cfg(feature = "timing")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintTiming {
+    pub path: String,
+    pub constraint: String,
+    pub elapsed: std::time::Duration,
+}
@@ -0,0 +1,202 @@
+//! `Schema::compile` 的合理性检查：在校验之前拒绝自相矛盾的规则，
+//! 而不是等到校验时报错或悄悄放过所有值
+
+use std::cmp::Ordering;
+
+use regex::Regex;
+
+use crate::{
+    ast::{Constraint, FieldRule, FieldType, Metadata, Value, compare_numbers},
+    clock::instant_of,
+    validator::{ValidationContext, validate_constraint, validate_type},
+};
+
+/// 依次检查每条规则（包括嵌套对象与数组元素），返回第一个问题
+pub(crate) fn check(rules: &[FieldRule]) -> Result<(), String> {
+    check_unique(rules, "")?;
+    rules.iter().try_for_each(|rule| check_rule(rule, ""))
+}
+
+/// metadata 中影响校验的项必须可用：`float_epsilon` 为非负的有限数
+pub(crate) fn check_metadata(metadata: &Metadata) -> Result<(), String> {
+    match metadata.get(Metadata::FLOAT_EPSILON) {
+        Some(v) if metadata.float_epsilon().is_none() => Err(format!(
+            "{} must be a non-negative number, got {}",
+            Metadata::FLOAT_EPSILON,
+            v
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn rule_path(prefix: &str, field: &str) -> String {
+    match (prefix, field) {
+        ("", field) => field.to_string(),
+        (prefix, "") => format!("{}[]", prefix),
+        (prefix, field) => format!("{}.{}", prefix, field),
+    }
+}
+
+/// 同一层的字段不能同名。DSL 在解析时已经检查，这里覆盖直接构造的规则
+/// 与以 `DuplicateFields::Warn` 解析的旧 schema
+fn check_unique(rules: &[FieldRule], prefix: &str) -> Result<(), String> {
+    for (i, rule) in rules.iter().enumerate() {
+        if rules[..i].iter().any(|r| r.field == rule.field) {
+            return Err(format!(
+                "Duplicate field '{}'",
+                rule_path(prefix, &rule.field)
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_rule(rule: &FieldRule, prefix: &str) -> Result<(), String> {
+    let path = rule_path(prefix, &rule.field);
+    let types = match &rule.union_types {
+        Some(types) => types.as_slice(),
+        None => std::slice::from_ref(&rule.field_type),
+    };
+
+    for t in types {
+        if let FieldType::Custom(pattern) = t {
+            check_regex(&path, pattern)?;
+        }
+    }
+    if let Some(constraints) = &rule.constraints {
+        for con in &constraints.items {
+            check_constraint(&path, rule, con)?;
+        }
+    }
+    if let Some(values) = &rule.enum_values {
+        for v in values {
+            if !types.iter().any(|t| validate_type(v, t).is_ok()) {
+                return Err(format!(
+                    "Enum value {} of field '{}' does not match type {}",
+                    v,
+                    path,
+                    type_names(types)
+                ));
+            }
+        }
+    }
+    if let Some(default) = &rule.default {
+        check_default(&path, rule, types, default)?;
+    }
+
+    if let Some(sub) = &rule.rule {
+        check_rule(sub, &path)?;
+    }
+    if let Some(children) = &rule.children {
+        check_unique(children, &path)?;
+        for child in children {
+            check_rule(child, &path)?;
+        }
+    }
+    Ok(())
+}
+
+fn check_constraint(path: &str, rule: &FieldRule, con: &Constraint) -> Result<(), String> {
+    match con {
+        Constraint::Range {
+            min,
+            max,
+            min_inclusive,
+            max_inclusive,
+        } => {
+            let empty = match bound_order(min, max) {
+                Some(Ordering::Greater) => true,
+                Some(Ordering::Equal) => !(*min_inclusive && *max_inclusive),
+                _ => false,
+            };
+            if empty {
+                return Err(format!("Range {} of field '{}' is empty", con, path));
+            }
+            let measures_length = rule.union_types.is_none()
+                && (rule.field_type.is_textual() || rule.field_type == FieldType::Array);
+            if measures_length && compare_numbers(min, &Value::Int(0)) == Some(Ordering::Less) {
+                return Err(format!(
+                    "Length range {} of field '{}' has a negative bound",
+                    con, path
+                ));
+            }
+            Ok(())
+        }
+        Constraint::Regex(pattern) => check_regex(path, pattern),
+        Constraint::Warn(inner) => check_constraint(path, rule, inner),
+        _ => Ok(()),
+    }
+}
+
+/// 数字按数值比较，日期按时刻比较
+fn bound_order(min: &Value, max: &Value) -> Option<Ordering> {
+    compare_numbers(min, max).or_else(|| match (min, max) {
+        (Value::String(_), Value::String(_)) => Some(instant_of(min)?.0.cmp(&instant_of(max)?.0)),
+        _ => None,
+    })
+}
+
+fn check_regex(path: &str, pattern: &str) -> Result<(), String> {
+    Regex::new(pattern)
+        .map(|_| ())
+        .map_err(|e| format!("Invalid regex for field '{}': {}", path, e))
+}
+
+/// 默认值必须满足字段自身的 enum 与约束。类型不符由解析时的 `LossyDefaults` 处理，
+/// 依赖校验时刻、同级字段或外部查找的约束（`past`、`script`、`exists` 等）在这里无法判断
+fn check_default(
+    path: &str,
+    rule: &FieldRule,
+    types: &[FieldType],
+    default: &Value,
+) -> Result<(), String> {
+    if !types.iter().any(|t| validate_type(default, t).is_ok()) {
+        return Ok(());
+    }
+    let violation = |reason: String| {
+        format!(
+            "Default {} of field '{}' violates its own constraints: {}",
+            default, path, reason
+        )
+    };
+    if let Some(values) = &rule.enum_values
+        && !values.contains(default)
+    {
+        return Err(violation("not in enum".into()));
+    }
+    let ctx = ValidationContext::default();
+    for con in rule.constraints.iter().flat_map(|c| &c.items) {
+        if is_static(con)
+            && let Err(e) = validate_constraint(default, con, &rule.field, &ctx, None)
+        {
+            return Err(violation(e.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// 只依赖值本身的约束
+fn is_static(con: &Constraint) -> bool {
+    matches!(
+        con,
+        Constraint::Range { .. }
+            | Constraint::Regex(_)
+            | Constraint::Checksum { .. }
+            | Constraint::Sorted { .. }
+            | Constraint::ArrayAggregate { .. }
+            | Constraint::UniqueBy(_)
+            | Constraint::Each(_)
+            | Constraint::Adjacent(_)
+            | Constraint::Oui(_)
+            | Constraint::MultipleOf(_)
+            | Constraint::Currency(_)
+    )
+}
+
+fn type_names(types: &[FieldType]) -> String {
+    types
+        .iter()
+        .map(|t| t.to_string())
+        .collect::<Vec<_>>()
+        .join("|")
+}
@@ -0,0 +1,859 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    ast::{Constraint, FieldRule, FieldType, Metadata, Projection, Value},
+    compile::{self, CompiledRule},
+    convert::{ConvertError, FromValue},
+    export,
+    i18n::Labels,
+    import, json,
+    mutation::{self, MutationReport},
+    parser::Parser,
+    replay::{self, Corpus, ReplayReport},
+    report::{ChangeSet, ErrorReport, OutcomeReport, ValidationReport},
+    sanity,
+    validator::{self, ValidationContext},
+};
+
+/// -----------------------------
+/// Schema
+/// -----------------------------
+/// 解析好的一组规则，作为校验及其衍生功能的入口。
+/// 编译后不再修改，内部数据以 `Arc` 共享：克隆只增加引用计数，
+/// 实例是 `Send + Sync`，可以直接放进 axum / actix 的应用状态，由多个线程同时校验
+#[derive(Debug, Clone)]
+pub struct Schema {
+    rules: Arc<[FieldRule]>,
+    compiled: Arc<[CompiledRule]>,
+    metadata: Arc<Metadata>,
+}
+
+/// 编译后的 schema。[`Schema`] 本身就是编译后的形式（见 [`Schema::compile`]）：
+/// `regex(...)` 约束与自定义正则类型在编译时一次编译完成，同一个实例（或其克隆）可以在多次校验、多个线程间复用
+pub type CompiledSchema = Schema;
+
+impl Schema {
+    /// 与 [`Schema::compile`] 相同的预计算，但不做合理性检查，用于已知无误的规则
+    pub fn new(mut rules: Vec<FieldRule>) -> Self {
+        compile::intern_names(&mut rules);
+        let compiled = CompiledRule::compile_all(&rules);
+        Self {
+            rules: rules.into(),
+            compiled: compiled.into(),
+            metadata: Arc::default(),
+        }
+    }
+
+    /// 先检查规则是否自相矛盾：同一层的字段同名、区间的 min 大于 max（或开区间为空）、长度区间为负、
+    /// 正则无法编译、enum 值与字段类型不符、默认值不满足字段自身的 enum 与约束，
+    /// 有问题时返回第一个问题的说明。
+    /// 再预先计算每条规则在校验时要反复用到的数据：区间边界（字符串长度区间不再对每个值解析）、
+    /// 字符串 enum 的哈希查找表、联合类型的尝试顺序（判断成本低的类型在前）。
+    /// 结果与直接用 `validator::validate_object` 校验相同
+    pub fn compile(rules: Vec<FieldRule>) -> Result<Self, String> {
+        sanity::check(&rules)?;
+        Ok(Self::new(rules))
+    }
+
+    /// 解析 DSL 并编译（见 [`Schema::compile`]），保留 `meta(...)` 头部
+    pub fn parse(dsl: &str) -> Result<Self, String> {
+        let (rules, metadata) = Parser::parse_with_metadata(dsl)?;
+        sanity::check_metadata(&metadata)?;
+        Ok(Self::compile(rules)?.with_metadata(metadata))
+    }
+
+    /// 从 JSON Schema 导入并编译，`x-vdsl-meta` 作为 metadata
+    pub fn from_json_schema(schema: &serde_json::Value) -> Result<Self, String> {
+        let metadata = import::metadata_from_json_schema(schema)?;
+        sanity::check_metadata(&metadata)?;
+        let rules = import::from_json_schema(schema)?;
+        Ok(Self::compile(rules)?.with_metadata(metadata))
+    }
+
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = Arc::new(metadata);
+        self
+    }
+
+    pub fn rules(&self) -> &[FieldRule] {
+        &self.rules
+    }
+
+    /// 以 `path` 处的对象字段为根的 schema，供子表单或只处理大契约中一部分的服务复用原定义。
+    /// 路径以点号分隔，`[]` 进入数组元素：`profile.contact`、`users[].address`；
+    /// 目标必须声明为 `object(...)`，metadata 沿用原 schema
+    pub fn subschema(&self, path: &str) -> Result<Self, String> {
+        let mut rules = &self.rules[..];
+        let mut target: Option<&FieldRule> = None;
+        for segment in path.split('.') {
+            if let Some(parent) = target {
+                rules = parent
+                    .children
+                    .as_deref()
+                    .ok_or_else(|| format!("'{}' does not go through object fields", path))?;
+            }
+            let name = segment.trim_end_matches("[]");
+            let mut rule = rules
+                .iter()
+                .find(|r| r.field == name)
+                .ok_or_else(|| format!("'{}' is not declared in the schema", path))?;
+            for _ in 0..(segment.len() - name.len()) / 2 {
+                rule = rule
+                    .rule
+                    .as_deref()
+                    .ok_or_else(|| format!("'{}' is not an array in '{}'", name, path))?;
+            }
+            target = Some(rule);
+        }
+        let children = target
+            .and_then(|rule| rule.children.clone())
+            .ok_or_else(|| format!("'{}' is not declared as object(...)", path))?;
+        Ok(Self::new(children).with_metadata(Metadata::clone(&self.metadata)))
+    }
+
+    /// 只保留列出的顶层字段的 schema（同 TypeScript 的 `Pick`），用于从完整模型派生 DTO。
+    /// 字段保持原来的顺序，列出未声明的字段时报错
+    pub fn pick<S: AsRef<str>>(&self, fields: impl IntoIterator<Item = S>) -> Result<Self, String> {
+        self.apply(&Projection::Pick(names(fields)))
+    }
+
+    /// 去掉列出的顶层字段的 schema（同 TypeScript 的 `Omit`）
+    pub fn omit<S: AsRef<str>>(&self, fields: impl IntoIterator<Item = S>) -> Result<Self, String> {
+        self.apply(&Projection::Omit(names(fields)))
+    }
+
+    /// 按 DSL 写法依次应用投影：`schema.project("omit(password, token)")`
+    pub fn project(&self, projections: &str) -> Result<Self, String> {
+        let mut schema = self.clone();
+        for projection in Parser::parse_projections(projections)? {
+            schema = schema.apply(&projection)?;
+        }
+        Ok(schema)
+    }
+
+    fn apply(&self, projection: &Projection) -> Result<Self, String> {
+        let rules = projection.apply(&self.rules)?;
+        Ok(Self::new(rules).with_metadata(Metadata::clone(&self.metadata)))
+    }
+
+    /// 所有字段（含嵌套对象与数组元素对象中的字段）都变为可选，用于 PATCH 这类只提交部分字段的请求。
+    /// 默认值与 `present` 一并去掉，缺失的字段不会被填入默认值；出现的字段仍按原约束校验
+    pub fn as_partial(&self) -> Self {
+        let mut rules = self.rules.to_vec();
+        for_each_field(&mut rules, &mut |rule| {
+            rule.required = false;
+            rule.default = None;
+            if let Some(c) = &mut rule.constraints {
+                c.items.retain(|c| !matches!(c, Constraint::Present));
+            }
+        });
+        Self::new(rules).with_metadata(Metadata::clone(&self.metadata))
+    }
+
+    /// 所有字段（含嵌套字段）都变为必填，带 `absent` 的字段保持可选；默认值保留
+    pub fn as_required(&self) -> Self {
+        let mut rules = self.rules.to_vec();
+        for_each_field(&mut rules, &mut |rule| {
+            if !rule.must_be_absent() {
+                rule.required = true;
+            }
+        });
+        Self::new(rules).with_metadata(Metadata::clone(&self.metadata))
+    }
+
+    /// schema 的标识信息（名称、版本、负责人等），供注册中心与报告使用
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// 字段的 `label(...)`，用于 [`ValidationError::localized_with_labels`](validator::ValidationError::localized_with_labels)
+    pub fn labels(&self) -> Labels {
+        Labels::from_rules(&self.rules)
+    }
+
+    /// 导出 DSL，开头带 `meta(...)` 头部
+    pub fn to_dsl(&self) -> String {
+        export::to_dsl_with(&self.rules, &self.metadata)
+    }
+
+    /// 导出 JSON Schema，`name` 作为 `title`，全部 metadata 放在 `x-vdsl-meta` 中
+    pub fn to_json_schema(&self) -> serde_json::Value {
+        export::to_json_schema_with(&self.rules, &self.metadata)
+    }
+
+    /// 导出 OpenAPI 文档，metadata 的 `version` 作为文档版本
+    pub fn to_openapi(&self, name: &str) -> serde_json::Value {
+        export::to_openapi_with(&self.rules, name, &self.metadata)
+    }
+
+    /// 导出 TypeScript interface，metadata 写在文档注释中
+    pub fn to_typescript(&self, name: &str) -> String {
+        export::to_typescript_with(&self.rules, name, &self.metadata)
+    }
+
+    /// 校验使用的上下文：metadata 声明了 `float_epsilon` 时以它取代调用方的设置
+    fn context<'a>(&self, ctx: &'a ValidationContext) -> Cow<'a, ValidationContext> {
+        match self.metadata.float_epsilon() {
+            Some(epsilon) if epsilon != ctx.options.float_epsilon => {
+                let mut ctx = ctx.clone();
+                ctx.options.float_epsilon = epsilon;
+                Cow::Owned(ctx)
+            }
+            _ => Cow::Borrowed(ctx),
+        }
+    }
+
+    /// 就地校验（填充默认值）
+    pub fn validate(&self, value: &mut Value) -> validator::Result<()> {
+        self.validate_with_context(value, ValidationContext::shared())
+    }
+
+    pub fn validate_with_context(
+        &self,
+        value: &mut Value,
+        ctx: &ValidationContext,
+    ) -> validator::Result<()> {
+        validator::validate_compiled(value, &self.rules, Some(&self.compiled), &self.context(ctx))
+            .map(|_| ())
+    }
+
+    /// 逐条校验一批记录（如批量导入的文件），结果与输入一一对应，通过的记录得到
+    /// 与 `validate_with_report` 相同的报告（含警告），默认值与转换照常写回。记录之间复用工作栈
+    pub fn validate_batch(
+        &self,
+        values: &mut [Value],
+    ) -> Vec<Result<ValidationReport, ErrorReport>> {
+        self.validate_batch_with_context(values, ValidationContext::shared())
+    }
+
+    pub fn validate_batch_with_context(
+        &self,
+        values: &mut [Value],
+        ctx: &ValidationContext,
+    ) -> Vec<Result<ValidationReport, ErrorReport>> {
+        validator::validate_batch_compiled(
+            values,
+            &self.rules,
+            Some(&self.compiled),
+            &self.context(ctx),
+            1,
+        )
+    }
+
+    /// 与 `validate_batch_with_context` 相同，但把记录切成连续的几段在多个线程中校验，
+    /// 结果仍按输入顺序排列。`threads` 为 0 时使用 [`std::thread::available_parallelism`]
+    pub fn validate_batch_parallel(
+        &self,
+        values: &mut [Value],
+        ctx: &ValidationContext,
+        threads: usize,
+    ) -> Vec<Result<ValidationReport, ErrorReport>> {
+        let threads = match threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        validator::validate_batch_compiled(
+            values,
+            &self.rules,
+            Some(&self.compiled),
+            &self.context(ctx),
+            threads,
+        )
+    }
+
+    /// 不修改载荷，返回校验会做的修改，见 [`validator::validate_changes`]
+    pub fn validate_changes(&self, value: &Value) -> validator::Result<ChangeSet> {
+        self.validate_changes_with_context(value, ValidationContext::shared())
+    }
+
+    pub fn validate_changes_with_context(
+        &self,
+        value: &Value,
+        ctx: &ValidationContext,
+    ) -> validator::Result<ChangeSet> {
+        validator::validate_changes_compiled(
+            value,
+            &self.rules,
+            Some(&self.compiled),
+            &self.context(ctx),
+        )
+    }
+
+    /// 逐个字段的结论，不在第一个失败处停止，见 [`validator::validate_outcomes`]
+    pub fn validate_outcomes(&self, value: &Value) -> OutcomeReport {
+        self.validate_outcomes_with_context(value, ValidationContext::shared())
+    }
+
+    pub fn validate_outcomes_with_context(
+        &self,
+        value: &Value,
+        ctx: &ValidationContext,
+    ) -> OutcomeReport {
+        validator::validate_outcomes_compiled(
+            value,
+            &self.rules,
+            Some(&self.compiled),
+            &self.context(ctx),
+        )
+    }
+
+    /// 只读校验，不填充默认值，见 [`validator::validate_shared`]。
+    /// 共享的 `Arc<Value>` 可以直接传入：`schema.validate_shared(&shared)`
+    pub fn validate_shared(&self, value: &Value) -> validator::Result<()> {
+        self.validate_shared_with_context(value, ValidationContext::shared())
+    }
+
+    pub fn validate_shared_with_context(
+        &self,
+        value: &Value,
+        ctx: &ValidationContext,
+    ) -> validator::Result<()> {
+        validator::validate_shared_compiled(
+            value,
+            &self.rules,
+            Some(&self.compiled),
+            &self.context(ctx),
+        )
+    }
+
+    /// 直接校验 JSON 字节，边解析边校验而不构造 `Value`，适合只需判断是否通过的场景，
+    /// 语义见 `validate`（默认值只校验不回填）
+    pub fn validate_json_bytes(&self, bytes: &[u8]) -> validator::Result<()> {
+        self.validate_json_bytes_with_context(bytes, ValidationContext::shared())
+    }
+
+    /// 与 `validate_json_bytes` 相同，但使用调用方的上下文（时钟、变量、自定义校验器、选项等）；
+    /// 设置了流式校验无法满足的选项（如 `strict`、`coerce`、审计）时先解析再校验
+    pub fn validate_json_bytes_with_context(
+        &self,
+        bytes: &[u8],
+        ctx: &ValidationContext,
+    ) -> validator::Result<()> {
+        json::validate_json_bytes(bytes, &self.rules, &self.compiled, &self.context(ctx))
+    }
+
+    /// 直接校验并就地修改 `serde_json::Value`（如 web 框架已经解析好的请求体），
+    /// 默认值与转换照常写回。不复制整份文档；带约束的对象 / 数组仍会临时转换为 `Value`，
+    /// 开销见 `json::validate_json_value`
+    #[cfg(feature = "json-value")]
+    pub fn validate_json(&self, json: &mut serde_json::Value) -> validator::Result<()> {
+        self.validate_json_with_context(json, ValidationContext::shared())
+    }
+
+    #[cfg(feature = "json-value")]
+    pub fn validate_json_with_context(
+        &self,
+        json: &mut serde_json::Value,
+        ctx: &ValidationContext,
+    ) -> validator::Result<()> {
+        json::validate_json_value(json, &self.rules, &self.compiled, &self.context(ctx))
+    }
+
+    /// 用当前规则重新校验 `corpus` 中的全部载荷，报告结论与记录时不同的用例，
+    /// 用于发现修改 schema 带来的意外行为变化
+    pub fn replay(&self, corpus: &Corpus) -> std::io::Result<ReplayReport> {
+        self.replay_with_context(corpus, ValidationContext::shared())
+    }
+
+    pub fn replay_with_context(
+        &self,
+        corpus: &Corpus,
+        ctx: &ValidationContext,
+    ) -> std::io::Result<ReplayReport> {
+        replay::replay(self, corpus, ctx)
+    }
+
+    /// 变异测试：逐一放宽规则并用本应失败的 `samples` 校验，找出没有拦住任何样本的规则
+    pub fn mutation_test(&self, samples: &[Value]) -> MutationReport {
+        mutation::mutation_test(
+            &self.rules,
+            samples,
+            &self.context(ValidationContext::shared()),
+        )
+    }
+
+    /// 校验（填充默认值）后转换为用户类型，见 [`impl_from_value!`](crate::impl_from_value)
+    pub fn decode<T: FromValue>(&self, mut value: Value) -> Result<T, ConvertError> {
+        self.validate(&mut value)?;
+        T::from_value(&value, "")
+    }
+
+    /// 校验通过后返回按规则取值的视图：必填字段的取值不再需要处理缺失或类型不符
+    pub fn typed_view<'a>(&'a self, value: &'a mut Value) -> validator::Result<TypedView<'a>> {
+        self.validate(value)?;
+        Ok(TypedView {
+            rules: &self.rules,
+            value,
+        })
+    }
+}
+
+/// 只读校验 `&Value`，从不写入载荷：适用于共享或不可变的数据，以及只能拿到 `&Value`
+/// 的请求守卫。缺失字段的默认值按填入后的样子校验，结论与 [`Schema::validate`] 相同，
+/// 见 [`validator::validate_shared`]
+pub fn validate_ref(value: &Value, schema: &CompiledSchema) -> validator::Result<()> {
+    schema.validate_shared(value)
+}
+
+pub fn validate_ref_with_context(
+    value: &Value,
+    schema: &CompiledSchema,
+    ctx: &ValidationContext,
+) -> validator::Result<()> {
+    schema.validate_shared_with_context(value, ctx)
+}
+
+/// 预编译产物的魔数，其后是生成它的 crate 版本与 postcard 编码的规则、metadata 和预计算数据
+#[cfg(feature = "artifact")]
+const ARTIFACT_MAGIC: &[u8; 4] = b"VDSL";
+
+#[cfg(feature = "artifact")]
+#[derive(serde::Serialize)]
+struct ArtifactRef<'a> {
+    metadata: &'a Metadata,
+    rules: &'a [FieldRule],
+    compiled: &'a [CompiledRule],
+}
+
+#[cfg(feature = "artifact")]
+#[derive(serde::Deserialize)]
+struct Artifact {
+    metadata: Metadata,
+    rules: Vec<FieldRule>,
+    compiled: Vec<CompiledRule>,
+}
+
+#[cfg(feature = "artifact")]
+impl Schema {
+    /// 输出二进制的预编译产物，包含规则、metadata 与 [`Schema::compile`] 的预计算数据。
+    /// 冷启动时用 [`Schema::deserialize_compiled`] 加载，省去解析 DSL 与预计算。
+    /// 产物只能由同一版本的 crate 加载
+    pub fn serialize_compiled(&self) -> Result<Vec<u8>, String> {
+        let mut out = ARTIFACT_MAGIC.to_vec();
+        out = postcard::to_extend(env!("CARGO_PKG_VERSION"), out).map_err(|e| e.to_string())?;
+        postcard::to_extend(
+            &ArtifactRef {
+                metadata: &self.metadata,
+                rules: &self.rules,
+                compiled: &self.compiled,
+            },
+            out,
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    /// 加载 [`Schema::serialize_compiled`] 的输出；格式不符、版本不同、预计算数据与规则不对应
+    /// 或规则通不过 [`Schema::compile`] 的检查时返回错误
+    pub fn deserialize_compiled(bytes: &[u8]) -> Result<Self, String> {
+        let rest = bytes
+            .strip_prefix(ARTIFACT_MAGIC)
+            .ok_or("Not a compiled schema artifact")?;
+        let (version, rest) = postcard::take_from_bytes::<&str>(rest)
+            .map_err(|e| format!("Invalid compiled schema artifact: {}", e))?;
+        if version != env!("CARGO_PKG_VERSION") {
+            return Err(format!(
+                "Compiled schema artifact was built by version {}, expected {}",
+                version,
+                env!("CARGO_PKG_VERSION")
+            ));
+        }
+        let artifact: Artifact = postcard::from_bytes(rest)
+            .map_err(|e| format!("Invalid compiled schema artifact: {}", e))?;
+        // 预计算数据在校验时按下标直接使用，加载时逐层确认与规则对应；
+        // 规则本身也要通过与 `Schema::compile` 相同的检查
+        if artifact.compiled.len() != artifact.rules.len()
+            || !artifact
+                .compiled
+                .iter()
+                .zip(&artifact.rules)
+                .all(|(c, rule)| c.fits(rule))
+        {
+            return Err(
+                "Invalid compiled schema artifact: compiled data does not match rules".into(),
+            );
+        }
+        sanity::check(&artifact.rules)
+            .and_then(|_| sanity::check_metadata(&artifact.metadata))
+            .map_err(|e| format!("Invalid compiled schema artifact: {}", e))?;
+        let mut rules = artifact.rules;
+        compile::intern_names(&mut rules);
+        let mut compiled = artifact.compiled;
+        for (c, rule) in compiled.iter_mut().zip(&rules) {
+            c.attach_regexes(rule);
+        }
+        Ok(Self {
+            rules: rules.into(),
+            compiled: compiled.into(),
+            metadata: Arc::new(artifact.metadata),
+        })
+    }
+}
+
+/// 依次访问每个具名字段，数组只进入元素对象的字段，元素规则本身不访问
+fn for_each_field(rules: &mut [FieldRule], f: &mut impl FnMut(&mut FieldRule)) {
+    for rule in rules {
+        f(rule);
+        let mut inner = rule;
+        while let Some(next) = inner.rule.as_deref_mut() {
+            inner = next;
+        }
+        if let Some(children) = &mut inner.children {
+            for_each_field(children, f);
+        }
+    }
+}
+
+fn names<S: AsRef<str>>(fields: impl IntoIterator<Item = S>) -> Vec<String> {
+    fields.into_iter().map(|f| f.as_ref().to_string()).collect()
+}
+
+impl From<Vec<FieldRule>> for Schema {
+    fn from(rules: Vec<FieldRule>) -> Self {
+        Self::new(rules)
+    }
+}
+
+/// -----------------------------
+/// SchemaRegistry
+/// -----------------------------
+/// 按 DSL 文本缓存编译好的 schema，供运行时才拿到 schema 的服务（如按租户下发）使用：
+/// 同一段 DSL 只解析、编译一次，之后按文本的 SHA-256 直接取出。
+/// 以 `&self` 调用，可以放在 `static` 或 `Arc` 中由多个线程共享；
+/// 解析失败的 DSL 不缓存，每次都返回错误
+#[derive(Debug, Default)]
+pub struct SchemaRegistry {
+    entries: RwLock<HashMap<[u8; 32], Entry>>,
+    /// 最多保留的 schema 数，0 为不限
+    capacity: usize,
+    /// 单调递增的使用计数，用于淘汰最久未用的 schema
+    tick: AtomicU64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    schema: Arc<CompiledSchema>,
+    used: AtomicU64,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 最多保留 `capacity` 个 schema，已满时淘汰最久未用的一个
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            ..Self::default()
+        }
+    }
+
+    /// 取出 `dsl` 编译后的 schema，未缓存时用 [`Schema::parse`] 编译并缓存。
+    /// 多个线程同时编译同一段 DSL 时只保留先放入的结果
+    pub fn get_or_compile(&self, dsl: &str) -> Result<Arc<CompiledSchema>, String> {
+        let key: [u8; 32] = Sha256::digest(dsl).into();
+        if let Some(schema) = self.lookup(&key) {
+            return Ok(schema);
+        }
+        let schema = Arc::new(Schema::parse(dsl)?);
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        if self.capacity > 0
+            && entries.len() >= self.capacity
+            && !entries.contains_key(&key)
+            && let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, e)| e.used.load(Ordering::Relaxed))
+                .map(|(k, _)| *k)
+        {
+            entries.remove(&oldest);
+        }
+        let entry = entries.entry(key).or_insert_with(|| Entry {
+            schema,
+            used: AtomicU64::new(0),
+        });
+        entry.used.store(self.next_tick(), Ordering::Relaxed);
+        Ok(entry.schema.clone())
+    }
+
+    /// 已缓存的 schema，不编译
+    pub fn get(&self, dsl: &str) -> Option<Arc<CompiledSchema>> {
+        self.lookup(&Sha256::digest(dsl).into())
+    }
+
+    /// 移出 `dsl` 的缓存，返回是否存在
+    pub fn remove(&self, dsl: &str) -> bool {
+        let key: [u8; 32] = Sha256::digest(dsl).into();
+        self.entries
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&key)
+            .is_some()
+    }
+
+    pub fn clear(&self) {
+        self.entries
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lookup(&self, key: &[u8; 32]) -> Option<Arc<CompiledSchema>> {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        let entry = entries.get(key)?;
+        entry.used.store(self.next_tick(), Ordering::Relaxed);
+        Some(entry.schema.clone())
+    }
+
+    fn next_tick(&self) -> u64 {
+        self.tick.fetch_add(1, Ordering::Relaxed) + 1
+    }
+}
+
+/// -----------------------------
+/// TypedView
+/// -----------------------------
+/// 已通过校验的对象上的类型化访问器，路径用点号分隔（`profile.contact.email`），
+/// 只能穿过对象字段，数组元素用 [`TypedView::items`] 访问。
+///
+/// `get_*` 用于路径上每一段都必填（或带默认值）的字段，`get_opt_*` 用于可能缺失的字段。
+/// 路径未在规则中声明、类型不符或对可选字段调用 `get_*` 属于编程错误，会 panic 并给出路径
+#[derive(Debug, Clone, Copy)]
+pub struct TypedView<'a> {
+    rules: &'a [FieldRule],
+    value: &'a Value,
+}
+
+/// 访问器期望的值类别
+#[derive(Clone, Copy)]
+enum Kind {
+    Int,
+    Float,
+    Bool,
+    Str,
+    Array,
+    Object,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Int => "int",
+            Kind::Float => "float",
+            Kind::Bool => "bool",
+            Kind::Str => "str",
+            Kind::Array => "array",
+            Kind::Object => "object",
+        }
+    }
+
+    /// 校验通过后该类型的值一定属于此类别
+    fn holds(self, t: &FieldType) -> bool {
+        match self {
+            Kind::Int => matches!(t, FieldType::Int | FieldType::Timestamp),
+            Kind::Float => *t == FieldType::Float,
+            Kind::Bool => *t == FieldType::Bool,
+            // port 也可以是整数
+            Kind::Str => t.is_textual() && *t != FieldType::Port,
+            Kind::Array => *t == FieldType::Array,
+            Kind::Object => matches!(t, FieldType::Object | FieldType::Money),
+        }
+    }
+}
+
+/// i64 范围内的整数；超出范围的 `BigInt` 同样通过了 `int` 的校验，取不出来属于编程错误
+#[cfg_attr(not(feature = "bigint"), allow(unused_variables))]
+fn small_int(path: &str, value: &Value) -> Option<i64> {
+    #[cfg(feature = "bigint")]
+    if let Value::BigInt(b) = value {
+        panic!("{} = {} does not fit in i64, use get_big_int", path, b);
+    }
+    value.as_int()
+}
+
+#[cfg(feature = "bigint")]
+fn big_int(value: &Value) -> Option<num_bigint::BigInt> {
+    match value {
+        Value::Int(i) => Some((*i).into()),
+        Value::BigInt(b) => Some(b.clone()),
+        _ => None,
+    }
+}
+
+impl<'a> TypedView<'a> {
+    /// 按路径取原始值，不做类型检查
+    pub fn get(&self, path: &str) -> Option<&'a Value> {
+        path.split('.')
+            .try_fold(self.value, |cur, key| cur.as_object()?.get(key))
+    }
+
+    pub fn value(&self) -> &'a Value {
+        self.value
+    }
+
+    /// 开启 `bigint` 时 `int` 字段可能超出 i64，此时 panic，改用 [`TypedView::get_big_int`]
+    pub fn get_int(&self, path: &str) -> i64 {
+        self.required(path, Kind::Int, |v| small_int(path, v))
+    }
+
+    pub fn get_opt_int(&self, path: &str) -> Option<i64> {
+        self.optional(path, Kind::Int, |v| small_int(path, v))
+    }
+
+    /// 任意大小的 `int` 字段
+    #[cfg(feature = "bigint")]
+    pub fn get_big_int(&self, path: &str) -> num_bigint::BigInt {
+        self.required(path, Kind::Int, big_int)
+    }
+
+    #[cfg(feature = "bigint")]
+    pub fn get_opt_big_int(&self, path: &str) -> Option<num_bigint::BigInt> {
+        self.optional(path, Kind::Int, big_int)
+    }
+
+    pub fn get_float(&self, path: &str) -> f64 {
+        self.required(path, Kind::Float, Value::as_float)
+    }
+
+    pub fn get_opt_float(&self, path: &str) -> Option<f64> {
+        self.optional(path, Kind::Float, Value::as_float)
+    }
+
+    pub fn get_bool(&self, path: &str) -> bool {
+        self.required(path, Kind::Bool, Value::as_bool)
+    }
+
+    pub fn get_opt_bool(&self, path: &str) -> Option<bool> {
+        self.optional(path, Kind::Bool, Value::as_bool)
+    }
+
+    pub fn get_str(&self, path: &str) -> &'a str {
+        self.required(path, Kind::Str, Value::as_str)
+    }
+
+    pub fn get_opt_str(&self, path: &str) -> Option<&'a str> {
+        self.optional(path, Kind::Str, Value::as_str)
+    }
+
+    pub fn get_array(&self, path: &str) -> &'a [Value] {
+        self.required(path, Kind::Array, |v| v.as_array().map(Vec::as_slice))
+    }
+
+    pub fn get_opt_array(&self, path: &str) -> Option<&'a [Value]> {
+        self.optional(path, Kind::Array, |v| v.as_array().map(Vec::as_slice))
+    }
+
+    /// 嵌套对象的视图
+    pub fn get_object(&self, path: &str) -> TypedView<'a> {
+        let (rule, _) = self.lookup(path, Kind::Object);
+        let value = self.required(path, Kind::Object, |v| v.as_object().map(|_| v));
+        self.nested(rule, value)
+    }
+
+    pub fn get_opt_object(&self, path: &str) -> Option<TypedView<'a>> {
+        let (rule, _) = self.lookup(path, Kind::Object);
+        let value = self.optional(path, Kind::Object, |v| v.as_object().map(|_| v))?;
+        Some(self.nested(rule, value))
+    }
+
+    /// `array<object(...)>` 字段中每个元素的视图；字段缺失时为空
+    pub fn items(&self, path: &str) -> Vec<TypedView<'a>> {
+        let (rule, _) = self.lookup(path, Kind::Array);
+        let children = match rule.rule.as_deref() {
+            Some(sub) if sub.field_type == FieldType::Object => {
+                sub.children.as_deref().unwrap_or_default()
+            }
+            _ => panic!("{} is not declared as array<object(...)>", path),
+        };
+        self.optional(path, Kind::Array, Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|value| TypedView {
+                        rules: children,
+                        value,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn nested(&self, rule: &'a FieldRule, value: &'a Value) -> TypedView<'a> {
+        TypedView {
+            rules: rule.children.as_deref().unwrap_or_default(),
+            value,
+        }
+    }
+
+    /// 找到路径对应的规则并检查类型；返回路径上是否每一段都一定存在
+    fn lookup(&self, path: &str, kind: Kind) -> (&'a FieldRule, bool) {
+        let mut rules = self.rules;
+        let mut present = true;
+        let mut found: Option<&'a FieldRule> = None;
+        for segment in path.split('.') {
+            if let Some(parent) = found {
+                rules = match (&parent.field_type, parent.children.as_deref()) {
+                    (FieldType::Object, Some(children)) => children,
+                    _ => panic!("{} does not go through object fields", path),
+                };
+            }
+            let rule = rules
+                .iter()
+                .find(|r| r.field == segment)
+                .unwrap_or_else(|| panic!("{} is not declared in the schema", path));
+            present &= rule.required || rule.default.is_some() || rule.must_be_present();
+            found = Some(rule);
+        }
+        let rule = found.unwrap_or_else(|| panic!("empty path"));
+        let types = match &rule.union_types {
+            Some(types) => types.as_slice(),
+            None => std::slice::from_ref(&rule.field_type),
+        };
+        if !types.iter().all(|t| kind.holds(t)) {
+            panic!(
+                "{} is declared as {}, not {}",
+                path,
+                rule.field_type,
+                kind.name()
+            );
+        }
+        (rule, present)
+    }
+
+    fn required<T>(&self, path: &str, kind: Kind, extract: impl Fn(&'a Value) -> Option<T>) -> T {
+        let (_, present) = self.lookup(path, kind);
+        if !present {
+            panic!("{} may be absent, use get_opt_{}", path, kind.name());
+        }
+        self.get(path)
+            .and_then(extract)
+            .unwrap_or_else(|| panic!("{} is not a validated {}", path, kind.name()))
+    }
+
+    /// 字段缺失时为 None；可选字段允许留空，留空的 `""` 对非字符串访问器同样视为缺失
+    fn optional<T>(
+        &self,
+        path: &str,
+        kind: Kind,
+        extract: impl Fn(&'a Value) -> Option<T>,
+    ) -> Option<T> {
+        self.lookup(path, kind);
+        self.get(path).and_then(extract)
+    }
+}
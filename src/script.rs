@@ -0,0 +1,71 @@
+//! `script("...")` 约束的脚本引擎（`script` feature，基于 rhai）。
+//!
+//! 脚本中 `value` 为当前字段值，`parent` 为字段所在的对象（可读取同级字段，数组元素为 `()`），
+//! 表达式结果必须为 bool。引擎限制了运算次数与嵌套深度，`print` / `debug` 输出被丢弃。
+
+use once_cell::sync::Lazy;
+use rhai::{AST, Array, Dynamic, Engine, Map, Scope};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::ast::Value;
+
+static ENGINE: Lazy<Engine> = Lazy::new(|| {
+    let mut engine = Engine::new();
+    engine.set_max_operations(100_000);
+    engine.set_max_expr_depths(64, 32);
+    engine.set_max_call_levels(32);
+    engine.set_max_string_size(1 << 20);
+    engine.set_max_array_size(10_000);
+    engine.set_max_map_size(10_000);
+    engine.on_print(|_| {});
+    engine.on_debug(|_, _, _| {});
+    engine
+});
+
+static AST_CACHE: Lazy<Mutex<HashMap<String, Arc<AST>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 执行脚本，返回表达式的 bool 结果；编译或运行出错时返回错误信息
+pub fn eval(source: &str, value: &Value, parent: Option<&Value>) -> Result<bool, String> {
+    let ast = compile(source)?;
+    let mut scope = Scope::new();
+    scope.push_constant("value", to_dynamic(value));
+    scope.push_constant("parent", parent.map(to_dynamic).unwrap_or(Dynamic::UNIT));
+    ENGINE
+        .eval_ast_with_scope::<bool>(&mut scope, &ast)
+        .map_err(|e| e.to_string())
+}
+
+fn compile(source: &str) -> Result<Arc<AST>, String> {
+    let mut cache = AST_CACHE
+        .lock()
+        .map_err(|_| "script cache lock poisoned".to_string())?;
+    if let Some(ast) = cache.get(source) {
+        return Ok(ast.clone());
+    }
+    let ast = Arc::new(
+        ENGINE
+            .compile_expression(source)
+            .map_err(|e| e.to_string())?,
+    );
+    cache.insert(source.to_string(), ast.clone());
+    Ok(ast)
+}
+
+fn to_dynamic(value: &Value) -> Dynamic {
+    match value {
+        Value::String(s) => Dynamic::from(s.clone()),
+        Value::Int(i) => Dynamic::from(*i),
+        // rhai 没有任意精度整数，以十进制字符串传入
+        #[cfg(feature = "bigint")]
+        Value::BigInt(b) => Dynamic::from(b.to_string()),
+        Value::Float(f) => Dynamic::from(*f),
+        Value::Bool(b) => Dynamic::from(*b),
+        Value::Array(items) => Dynamic::from(items.iter().map(to_dynamic).collect::<Array>()),
+        Value::Object(map) => Dynamic::from(
+            map.iter()
+                .map(|(k, v)| (k.as_str().into(), to_dynamic(v)))
+                .collect::<Map>(),
+        ),
+    }
+}
@@ -0,0 +1,241 @@
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    ast::{FieldType, SequenceRule, Value, find_rule},
+    clock::instant_of,
+    parser::Parser,
+    refs::resolve_path,
+    schema::Schema,
+    validator::{self, ValidationContext, ValidationError, number_of},
+};
+
+/// 并发提交导致 `compare_and_set` 失败时最多重试的次数，
+/// 用完后返回 `ValidationError::SequenceConflict`
+pub const MAX_RETRIES: usize = 16;
+
+/// -----------------------------
+/// SequenceStore
+/// -----------------------------
+/// 保存每个键上一次通过校验的载荷。多实例部署时可接到 Redis / 数据库，
+/// `compare_and_set` 需要是原子的，否则并发提交可能绕过规则
+pub trait SequenceStore: Send + Sync {
+    fn get(&self, key: &str) -> Option<Value>;
+
+    /// 仅当当前保存的值等于 `previous`（`None` 表示尚无记录）时写入 `value`，返回是否写入
+    fn compare_and_set(&self, key: &str, previous: Option<&Value>, value: Value) -> bool;
+}
+
+/// 进程内的默认存储
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    values: Mutex<HashMap<String, Value>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SequenceStore for MemoryStore {
+    fn get(&self, key: &str) -> Option<Value> {
+        let values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+        values.get(key).cloned()
+    }
+
+    fn compare_and_set(&self, key: &str, previous: Option<&Value>, value: Value) -> bool {
+        let mut values = self.values.lock().unwrap_or_else(|e| e.into_inner());
+        if values.get(key) != previous {
+            return false;
+        }
+        values.insert(key.to_string(), value);
+        true
+    }
+}
+
+/// -----------------------------
+/// SequenceValidator
+/// -----------------------------
+/// 有状态的校验：先按 schema 校验，再把载荷与同一键上一次通过校验的载荷比较，
+/// 适用于事件溯源等要求版本号递增、余额变化受限的接口：
+///
+/// ```
+/// use zz_validator::{json::from_json_str, schema::Schema, sequence::SequenceValidator};
+///
+/// let schema = Schema::parse("(account:string, version:int, balance:int)").unwrap();
+/// let validator = SequenceValidator::new(
+///     schema,
+///     "account",
+///     "strictly_monotonic(version), abs_delta(balance) <= 1000",
+/// )
+/// .unwrap();
+///
+/// let mut first = from_json_str(r#"{"account":"a","version":1,"balance":100}"#).unwrap();
+/// assert!(validator.validate(&mut first).is_ok());
+/// let mut replay = from_json_str(r#"{"account":"a","version":1,"balance":150}"#).unwrap();
+/// assert!(validator.validate(&mut replay).is_err());
+/// ```
+///
+/// 某条规则的字段在本次或上一次载荷中缺失时跳过该规则；未通过的载荷不会记录
+pub struct SequenceValidator {
+    schema: Schema,
+    key: String,
+    rules: Vec<SequenceRule>,
+    store: Arc<dyn SequenceStore>,
+}
+
+impl fmt::Debug for SequenceValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SequenceValidator")
+            .field("key", &self.key)
+            .field("rules", &self.rules)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SequenceValidator {
+    /// `key` 为区分序列的字段路径（如 `account_id`），`rules` 为序列规则 DSL
+    pub fn new(schema: Schema, key: &str, rules: &str) -> Result<Self, String> {
+        let rules = Parser::parse_sequence_rules(rules)?;
+        for path in std::iter::once(key).chain(rules.iter().map(SequenceRule::path)) {
+            if find_rule(schema.rules(), path).is_none() {
+                return Err(format!("'{}' is not declared in the schema", path));
+            }
+        }
+        if let Some(rule) = rules
+            .iter()
+            .find(|r| matches!(r, SequenceRule::Delta { .. }) && !measurable(&schema, r.path()))
+        {
+            return Err(format!("{} requires a numeric or time field", rule));
+        }
+        Ok(Self {
+            schema,
+            key: key.to_string(),
+            rules,
+            store: Arc::new(MemoryStore::new()),
+        })
+    }
+
+    /// 替换默认的进程内存储
+    pub fn store(mut self, store: impl SequenceStore + 'static) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    pub fn rules(&self) -> &[SequenceRule] {
+        &self.rules
+    }
+
+    pub fn validate(&self, value: &mut Value) -> validator::Result<()> {
+        self.validate_with_context(value, ValidationContext::shared())
+    }
+
+    pub fn validate_with_context(
+        &self,
+        value: &mut Value,
+        ctx: &ValidationContext,
+    ) -> validator::Result<()> {
+        self.schema.validate_with_context(value, ctx)?;
+        let key = match resolve_path(value, &self.key).first() {
+            Some(Value::String(s)) => s.clone(),
+            Some(v) => v.to_string(),
+            None => return Err(ValidationError::MissingField(self.key.clone())),
+        };
+        // 写入失败说明期间有其他提交，基于新的记录重新检查
+        for _ in 0..=MAX_RETRIES {
+            let previous = self.store.get(&key);
+            if let Some(previous) = &previous {
+                for rule in &self.rules {
+                    self.check(rule, previous, value)?;
+                }
+            }
+            if self
+                .store
+                .compare_and_set(&key, previous.as_ref(), value.clone())
+            {
+                return Ok(());
+            }
+        }
+        Err(ValidationError::SequenceConflict {
+            field: self.key.clone(),
+            attempts: MAX_RETRIES,
+        })
+    }
+
+    fn check(
+        &self,
+        rule: &SequenceRule,
+        previous: &Value,
+        current: &Value,
+    ) -> validator::Result<()> {
+        let (Some(before), Some(after)) = (
+            resolve_path(previous, rule.path()).first().copied(),
+            resolve_path(current, rule.path()).first().copied(),
+        ) else {
+            return Ok(());
+        };
+        let temporal = is_temporal(&self.schema, rule.path());
+        let holds = match rule {
+            SequenceRule::Monotonic { strict, .. } => {
+                let ord = match (temporal, instant_of(before), instant_of(after)) {
+                    (true, Some((b, _)), Some((a, _))) => a.cmp(&b),
+                    _ => after.cmp(before),
+                };
+                if *strict {
+                    ord == Ordering::Greater
+                } else {
+                    ord != Ordering::Less
+                }
+            }
+            SequenceRule::Delta {
+                absolute,
+                op,
+                bound,
+                ..
+            } => {
+                let measure = |v: &Value| {
+                    if temporal {
+                        instant_of(v).map(|(secs, _)| secs as f64)
+                    } else {
+                        number_of(v)
+                    }
+                };
+                let (Some(b), Some(a)) = (measure(before), measure(after)) else {
+                    return Ok(());
+                };
+                let delta = if *absolute { (a - b).abs() } else { a - b };
+                number_of(bound).is_some_and(|bound| op.apply(delta, bound))
+            }
+        };
+        if holds {
+            Ok(())
+        } else {
+            Err(ValidationError::SequenceViolation {
+                field: rule.path().to_string(),
+                rule: rule.to_string(),
+                previous: before.to_string(),
+                value: after.to_string(),
+            })
+        }
+    }
+}
+
+fn is_temporal(schema: &Schema, path: &str) -> bool {
+    find_rule(schema.rules(), path).is_some_and(|r| {
+        matches!(
+            r.field_type,
+            FieldType::Date | FieldType::DateTime | FieldType::Timestamp
+        )
+    })
+}
+
+fn measurable(schema: &Schema, path: &str) -> bool {
+    is_temporal(schema, path)
+        || find_rule(schema.rules(), path)
+            .is_some_and(|r| matches!(r.field_type, FieldType::Int | FieldType::Float))
+}
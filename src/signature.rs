@@ -0,0 +1,60 @@
+use sha2::{Digest, Sha256};
+
+/// -----------------------------
+/// Signature
+/// -----------------------------
+/// `signed_with(algorithm, key="...", over="$.path")` 使用的签名算法与密钥来源。
+/// 签名按 [`Value::to_canonical_json`](crate::ast::Value::to_canonical_json) 的输出计算，
+/// 与键的顺序、空白和数字写法无关
+pub(crate) const ALGORITHMS: &[&str] = &["hmac_sha256"];
+
+/// 密钥来源的前缀：`ctx:name` 读取 `ValidationContext::var`，`env:NAME` 读取环境变量
+pub(crate) fn is_key_source(key: &str) -> bool {
+    matches!(key.split_once(':'), Some(("ctx" | "env", name)) if !name.is_empty())
+}
+
+/// HMAC-SHA256（RFC 2104）
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let pad = |byte: u8| block.map(|b| b ^ byte);
+    let inner = Sha256::new()
+        .chain_update(pad(0x36))
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+/// 按 `algorithm` 计算签名并与十六进制的 `signature` 比较：大小写不限，
+/// 可以带 `sha256=` 这样的前缀；比较的耗时与签名内容无关
+pub(crate) fn verify(algorithm: &str, key: &[u8], message: &[u8], signature: &str) -> bool {
+    let expected = match algorithm {
+        "hmac_sha256" => hmac_sha256(key, message),
+        _ => return false,
+    };
+    let hex = signature.strip_prefix("sha256=").unwrap_or(signature);
+    if hex.len() != expected.len() * 2 {
+        return false;
+    }
+    let mut diff = 0u8;
+    for (pair, byte) in hex.as_bytes().chunks(2).zip(expected) {
+        match (hex_digit(pair[0]), hex_digit(pair[1])) {
+            (Some(high), Some(low)) => diff |= (high << 4 | low) ^ byte,
+            _ => return false,
+        }
+    }
+    diff == 0
+}
+
+fn hex_digit(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|d| d as u8)
+}
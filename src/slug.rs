@@ -0,0 +1,140 @@
+use std::fmt;
+
+/// -----------------------------
+/// Transliterator
+/// -----------------------------
+/// `slugify` 转换使用的音译：把任意 Unicode 文本转为 ASCII，
+/// 需要支持更多文字（如中文拼音）时可替换为自己的实现
+pub trait Transliterator: fmt::Debug + Send + Sync {
+    /// 无法音译的字符可以原样保留，`slugify` 会把它们当作分隔符
+    fn transliterate(&self, input: &str) -> String;
+}
+
+/// 内置的音译表：拉丁字母的变音符号与连字、希腊字母、西里尔字母
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BasicTransliterator;
+
+impl Transliterator for BasicTransliterator {
+    fn transliterate(&self, input: &str) -> String {
+        let mut out = String::with_capacity(input.len());
+        for c in input.chars() {
+            match transliterate_char(c) {
+                Some(ascii) => out.push_str(ascii),
+                None => out.push(c),
+            }
+        }
+        out
+    }
+}
+
+/// 音译后转小写，字母与数字之外的字符连续出现时替换为一个 `-`，去掉首尾的 `-`：
+/// `"Crème Brûlée: 2 Ways!"` -> `"creme-brulee-2-ways"`
+pub fn slugify(input: &str, transliterator: &dyn Transliterator) -> String {
+    let mut slug = String::with_capacity(input.len());
+    let mut dash = false;
+    for c in transliterator.transliterate(input).chars() {
+        if c.is_ascii_alphanumeric() {
+            if dash && !slug.is_empty() {
+                slug.push('-');
+            }
+            dash = false;
+            slug.push(c.to_ascii_lowercase());
+        } else {
+            dash = true;
+        }
+    }
+    slug
+}
+
+/// 按字符所在的组查表，大写字母的结果在 `slugify` 中统一转小写
+fn transliterate_char(c: char) -> Option<&'static str> {
+    if c.is_ascii() {
+        return None;
+    }
+    const TABLE: &[(&str, &str)] = &[
+        ("ÀÁÂÃÄÅĀĂĄàáâãäåāăą", "a"),
+        ("ÇĆĈĊČçćĉċč", "c"),
+        ("ĎĐďđ", "d"),
+        ("ÈÉÊËĒĔĖĘĚèéêëēĕėęě", "e"),
+        ("ĜĞĠĢĝğġģ", "g"),
+        ("ĤĦĥħ", "h"),
+        ("ÌÍÎÏĨĪĬĮİìíîïĩīĭįı", "i"),
+        ("Ĵĵ", "j"),
+        ("Ķķ", "k"),
+        ("ĹĻĽĿŁĺļľŀł", "l"),
+        ("ÑŃŅŇñńņň", "n"),
+        ("ÒÓÔÕÖØŌŎŐòóôõöøōŏő", "o"),
+        ("ŔŖŘŕŗř", "r"),
+        ("ŚŜŞŠśŝşšſ", "s"),
+        ("ŢŤŦţťŧ", "t"),
+        ("ÙÚÛÜŨŪŬŮŰŲùúûüũūŭůűų", "u"),
+        ("Ŵŵ", "w"),
+        ("ÝŸŶýÿŷ", "y"),
+        ("ŹŻŽźżž", "z"),
+        ("Ææ", "ae"),
+        ("Œœ", "oe"),
+        ("ß", "ss"),
+        ("Þþ", "th"),
+        ("Ðð", "d"),
+        // 希腊字母
+        ("ΑαΆά", "a"),
+        ("Ββ", "v"),
+        ("Γγ", "g"),
+        ("Δδ", "d"),
+        ("ΕεΈέ", "e"),
+        ("Ζζ", "z"),
+        ("ΗηΉή", "i"),
+        ("Θθ", "th"),
+        ("ΙιΊίΪϊΐ", "i"),
+        ("Κκ", "k"),
+        ("Λλ", "l"),
+        ("Μμ", "m"),
+        ("Νν", "n"),
+        ("Ξξ", "x"),
+        ("ΟοΌό", "o"),
+        ("Ππ", "p"),
+        ("Ρρ", "r"),
+        ("Σσς", "s"),
+        ("Ττ", "t"),
+        ("ΥυΎύΫϋΰ", "y"),
+        ("Φφ", "f"),
+        ("Χχ", "ch"),
+        ("Ψψ", "ps"),
+        ("ΩωΏώ", "o"),
+        // 西里尔字母
+        ("Аа", "a"),
+        ("Бб", "b"),
+        ("Вв", "v"),
+        ("ГгҐґ", "g"),
+        ("Дд", "d"),
+        ("ЕеЁёЭэЄє", "e"),
+        ("Жж", "zh"),
+        ("Зз", "z"),
+        ("ИиІіЇї", "i"),
+        ("Йй", "y"),
+        ("Кк", "k"),
+        ("Лл", "l"),
+        ("Мм", "m"),
+        ("Нн", "n"),
+        ("Оо", "o"),
+        ("Пп", "p"),
+        ("Рр", "r"),
+        ("Сс", "s"),
+        ("Тт", "t"),
+        ("Уу", "u"),
+        ("Фф", "f"),
+        ("Хх", "kh"),
+        ("Цц", "ts"),
+        ("Чч", "ch"),
+        ("Шш", "sh"),
+        ("Щщ", "shch"),
+        ("ЪъЬь", ""),
+        ("Ыы", "y"),
+        ("Юю", "yu"),
+        ("Яя", "ya"),
+    ];
+    TABLE
+        .iter()
+        .find(|(chars, _)| chars.contains(c))
+        .map(|(_, ascii)| *ascii)
+}
@@ -0,0 +1,146 @@
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, Read},
+};
+
+use serde::de::{Deserializer, SeqAccess, Visitor};
+
+use crate::{
+    ast::Value,
+    report::ErrorReport,
+    schema::Schema,
+    validator::{ValidationContext, ValidationError},
+};
+
+/// -----------------------------
+/// StreamValidator
+/// -----------------------------
+/// 流式校验大型导出文件：顶层 JSON 数组或 NDJSON 每解析出一条记录就立即校验，
+/// 结果逐条交给回调，内存中同时只保留一条记录，适合数 GB 的文件。
+/// 每条记录的校验与 [`Schema::validate_with_context`] 相同，默认值与转换写回回调收到的值
+pub struct StreamValidator<'a> {
+    schema: &'a Schema,
+    ctx: ValidationContext,
+}
+
+/// 一条记录的校验结果
+#[derive(Debug, Clone)]
+pub struct StreamItem {
+    /// JSON 数组中为元素下标；NDJSON 中为行号（从 0 开始，空行也计数）
+    pub index: usize,
+    /// 校验后的记录；NDJSON 中无法解析的行为该行原文
+    pub value: Value,
+    pub result: Result<(), ErrorReport>,
+}
+
+/// 一次流式校验的统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamReport {
+    /// 校验的记录数
+    pub items: usize,
+    /// 未通过的记录数
+    pub failed: usize,
+}
+
+impl StreamReport {
+    pub fn is_valid(&self) -> bool {
+        self.failed == 0
+    }
+
+    fn record(&mut self, item: &StreamItem) {
+        self.items += 1;
+        self.failed += usize::from(item.result.is_err());
+    }
+}
+
+impl<'a> StreamValidator<'a> {
+    pub fn new(schema: &'a Schema) -> Self {
+        Self::with_context(schema, ValidationContext::default())
+    }
+
+    pub fn with_context(schema: &'a Schema, ctx: ValidationContext) -> Self {
+        Self { schema, ctx }
+    }
+
+    fn check(&self, index: usize, mut value: Value) -> StreamItem {
+        let result = self
+            .schema
+            .validate_with_context(&mut value, &self.ctx)
+            .map_err(|e| ErrorReport::from_error(&e));
+        StreamItem {
+            index,
+            value,
+            result,
+        }
+    }
+
+    /// 校验顶层为 JSON 数组的输入，每个元素作为一条记录。读取时已加缓冲。
+    /// 读取失败、JSON 语法错误或顶层不是数组时中止并返回错误（`InvalidData`），
+    /// 此前的记录已经交给 `on_item`
+    pub fn validate_array<R: Read>(
+        &self,
+        reader: R,
+        mut on_item: impl FnMut(StreamItem),
+    ) -> io::Result<StreamReport> {
+        let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(reader));
+        let report = deserializer.deserialize_seq(ArrayVisitor {
+            stream: self,
+            on_item: &mut on_item,
+        })?;
+        deserializer.end()?;
+        Ok(report)
+    }
+
+    /// 校验 NDJSON：每个非空行是一条记录。某一行不是合法 JSON 时该行记为失败并继续，
+    /// 只有读取失败时中止
+    pub fn validate_ndjson<R: BufRead>(
+        &self,
+        reader: R,
+        mut on_item: impl FnMut(StreamItem),
+    ) -> io::Result<StreamReport> {
+        let mut report = StreamReport::default();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let item = match serde_json::from_str::<serde_json::Value>(&line) {
+                Ok(json) => self.check(index, Value::from(json)),
+                Err(e) => StreamItem {
+                    index,
+                    value: Value::String(line),
+                    result: Err(ErrorReport::from_error(&ValidationError::Custom(format!(
+                        "Invalid JSON: {}",
+                        e
+                    )))),
+                },
+            };
+            report.record(&item);
+            on_item(item);
+        }
+        Ok(report)
+    }
+}
+
+struct ArrayVisitor<'a, 'b, F> {
+    stream: &'a StreamValidator<'b>,
+    on_item: &'a mut F,
+}
+
+impl<'de, F: FnMut(StreamItem)> Visitor<'de> for ArrayVisitor<'_, '_, F> {
+    type Value = StreamReport;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON array")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<StreamReport, A::Error> {
+        let mut report = StreamReport::default();
+        while let Some(json) = seq.next_element::<serde_json::Value>()? {
+            let item = self.stream.check(report.items, Value::from(json));
+            report.record(&item);
+            (self.on_item)(item);
+        }
+        Ok(report)
+    }
+}
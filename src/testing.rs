@@ -0,0 +1,134 @@
+use std::{fs, path::Path};
+
+use crate::{
+    ast::Value,
+    json::from_json_str,
+    schema::Schema,
+    validator::{self, ValidationError},
+};
+
+/// -----------------------------
+/// Testing
+/// -----------------------------
+/// 供下游测试自己的 schema 使用，配合 [`assert_valid!`](crate::assert_valid) 与
+/// [`assert_errors_snapshot!`](crate::assert_errors_snapshot)。
+///
+/// 快照保存在调用方 crate 的 `tests/snapshots/<name>.snap`，应提交到仓库：
+/// 不存在时写入并通过；内容不一致时 panic 并给出两边的文本。
+/// 设置环境变量 `VDSL_UPDATE_SNAPSHOTS=1` 时直接用新结果覆盖快照
+pub const UPDATE_SNAPSHOTS_ENV: &str = "VDSL_UPDATE_SNAPSHOTS";
+
+/// 校验结论的文本形式：通过时输出填充默认值后的载荷，失败时每行一条错误
+/// （`exhaustive` 模式下同一字段的多个失败逐条展开）
+pub fn render(result: &validator::Result<()>, value: &Value) -> String {
+    match result {
+        Ok(()) => {
+            let json =
+                serde_json::to_string_pretty(&serde_json::Value::from(value)).unwrap_or_default();
+            format!("passed\n{}\n", json)
+        }
+        Err(e) => {
+            let errors = match e {
+                ValidationError::FieldErrors { errors, .. } => errors.as_slice(),
+                e => std::slice::from_ref(e),
+            };
+            let mut out = String::from("failed\n");
+            for error in errors {
+                out.push_str(&format!("- {}\n", error));
+            }
+            out
+        }
+    }
+}
+
+fn run(schema: &Schema, json: &str) -> (validator::Result<()>, Value) {
+    let mut value = match from_json_str(json) {
+        Ok(value) => value,
+        Err(e) => panic!("invalid JSON payload: {}\n{}", e, json),
+    };
+    let result = schema.validate(&mut value);
+    (result, value)
+}
+
+#[doc(hidden)]
+pub fn assert_valid(schema: &Schema, json: &str) {
+    let (result, value) = run(schema, json);
+    if result.is_err() {
+        panic!(
+            "expected payload to be valid, got:\n{}\npayload: {}",
+            render(&result, &value),
+            json
+        );
+    }
+}
+
+#[doc(hidden)]
+pub fn assert_errors_snapshot(schema: &Schema, json: &str, snapshot: &Path) {
+    let (result, value) = run(schema, json);
+    if result.is_ok() {
+        panic!(
+            "expected payload to be invalid, got:\n{}",
+            render(&result, &value)
+        );
+    }
+    check_snapshot(snapshot, &render(&result, &value));
+}
+
+/// 与快照文件比较，规则见 [`UPDATE_SNAPSHOTS_ENV`]
+pub fn check_snapshot(path: &Path, actual: &str) {
+    let update = std::env::var_os(UPDATE_SNAPSHOTS_ENV).is_some_and(|v| !v.is_empty() && v != "0");
+    match fs::read_to_string(path) {
+        Ok(expected) if expected == actual => {}
+        Ok(expected) if !update => panic!(
+            "snapshot {} does not match (set {}=1 to update)\n--- expected\n{}--- actual\n{}",
+            path.display(),
+            UPDATE_SNAPSHOTS_ENV,
+            expected,
+            actual
+        ),
+        _ => {
+            if let Some(dir) = path.parent()
+                && let Err(e) = fs::create_dir_all(dir)
+            {
+                panic!("failed to create {}: {}", dir.display(), e);
+            }
+            if let Err(e) = fs::write(path, actual) {
+                panic!("failed to write snapshot {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// 断言载荷通过校验，失败时 panic 并逐行列出错误
+///
+/// ```
+/// use zz_validator::{assert_valid, schema::Schema};
+///
+/// let schema = Schema::parse("(name:string[1,10], age:int=18)").unwrap();
+/// assert_valid!(schema, r#"{"name": "alice"}"#);
+/// ```
+#[macro_export]
+macro_rules! assert_valid {
+    ($schema:expr, $json:expr $(,)?) => {
+        $crate::testing::assert_valid(&$schema, ::core::convert::AsRef::<str>::as_ref(&$json))
+    };
+}
+
+/// 断言载荷校验失败，并把错误与 `tests/snapshots/<name>.snap` 比较
+///
+/// ```ignore
+/// assert_errors_snapshot!(schema, r#"{"name": ""}"#, "empty_name");
+/// ```
+#[macro_export]
+macro_rules! assert_errors_snapshot {
+    ($schema:expr, $json:expr, $name:expr $(,)?) => {
+        $crate::testing::assert_errors_snapshot(
+            &$schema,
+            ::core::convert::AsRef::<str>::as_ref(&$json),
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("snapshots")
+                .join(format!("{}.snap", $name)),
+        )
+    };
+}
@@ -0,0 +1,90 @@
+/// -----------------------------
+/// Timing
+/// -----------------------------
+/// 开启 `timing` feature 后，`validate_with_report` 记录每个字段的 enum 与各个约束的耗时，
+/// 放在报告的 `timings` 中。记录保存在线程局部变量里，只在一次校验期间有效；
+/// 未开启时以下函数都是空操作，不影响校验路径
+#[cfg(feature = "timing")]
+mod imp {
+    use std::{cell::RefCell, time::Instant};
+
+    use crate::{report::ConstraintTiming, validator::join_path};
+
+    thread_local! {
+        static TIMINGS: RefCell<Option<Vec<ConstraintTiming>>> = const { RefCell::new(None) };
+    }
+
+    /// 开始记录，返回外层（嵌套校验时）已有的记录，交给 `finish` 恢复
+    pub(crate) fn start() -> Option<Vec<ConstraintTiming>> {
+        TIMINGS.with(|t| t.borrow_mut().replace(Vec::new()))
+    }
+
+    pub(crate) fn finish(outer: Option<Vec<ConstraintTiming>>) -> Vec<ConstraintTiming> {
+        TIMINGS
+            .with(|t| std::mem::replace(&mut *t.borrow_mut(), outer))
+            .unwrap_or_default()
+    }
+
+    /// 执行 `check` 并记录耗时；`constraint` 只在记录时才生成
+    pub(crate) fn measure<T>(
+        field: &str,
+        constraint: impl FnOnce() -> String,
+        check: impl FnOnce() -> T,
+    ) -> T {
+        let started = Instant::now();
+        let result = check();
+        let elapsed = started.elapsed();
+        TIMINGS.with(|t| {
+            if let Some(timings) = t.borrow_mut().as_mut() {
+                timings.push(ConstraintTiming {
+                    path: field.to_string(),
+                    constraint: constraint(),
+                    elapsed,
+                });
+            }
+        });
+        result
+    }
+
+    /// 已记录的约束耗时条数，不在记录期间时为 0。进入对象或数组前取得，
+    /// 子字段的耗时都排在它之后
+    pub(crate) fn mark() -> usize {
+        TIMINGS.with(|t| t.borrow().as_ref().map_or(0, Vec::len))
+    }
+
+    /// 与 `ValidationError::within` 相同的规则，为 `mark` 之后的记录补上所在位置
+    pub(crate) fn within(mark: usize, parent: &str) {
+        if parent.is_empty() {
+            return;
+        }
+        TIMINGS.with(|t| {
+            if let Some(timings) = t.borrow_mut().as_mut() {
+                for timing in timings.iter_mut().skip(mark) {
+                    timing.path = join_path(parent, &timing.path);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(not(feature = "timing"))]
+mod imp {
+    #[inline(always)]
+    pub(crate) fn measure<T>(
+        _field: &str,
+        _constraint: impl FnOnce() -> String,
+        check: impl FnOnce() -> T,
+    ) -> T {
+        check()
+    }
+
+    #[inline(always)]
+    pub(crate) fn mark() -> usize {
+        0
+    }
+
+    #[inline(always)]
+    pub(crate) fn within(_mark: usize, _parent: &str) {}
+}
+
+pub(crate) use imp::*;
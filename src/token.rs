@@ -1,3 +1,5 @@
+use std::{fmt, iter::Peekable, str::Chars};
+
 /// -----------------------------
 /// Tokenizer
 /// -----------------------------
@@ -16,58 +18,148 @@ pub enum Token {
     Lt,
     Gt,
     Equal,
+    Bang,
     Pipe,
+    Dot,
+    /// 宏体中的参数 `{name}`，展开时替换为实参
+    Placeholder(String),
+}
+
+/// 词法单元在 DSL 源码中的位置：`start..end` 为字节偏移，`line` / `column` 从 1 开始，
+/// column 按字符计数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// 逐字符读取输入，同时记录当前的字节偏移与行列
+#[derive(Clone)]
+struct Cursor<'a> {
+    chars: Peekable<Chars<'a>>,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Cursor<'_> {
+    fn peek(&mut self) -> Option<&char> {
+        self.chars.peek()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        self.offset += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn span_from(&self, start: &Cursor) -> Span {
+        Span {
+            start: start.offset,
+            end: self.offset,
+            line: start.line,
+            column: start.column,
+        }
+    }
 }
 
 pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
-    let mut tokens = Vec::new();
-    let mut chars = input.chars().peekable();
+    Ok(tokenize_with_spans(input)?
+        .into_iter()
+        .map(|(token, _)| token)
+        .collect())
+}
+
+/// 同 `tokenize`，每个词法单元附带它在源码中的位置，错误信息也带上行列
+pub fn tokenize_with_spans(input: &str) -> Result<Vec<(Token, Span)>, String> {
+    tokenize_spanned(input).map_err(|(message, span)| format!("{} at {}", message, span))
+}
+
+/// 出错时分别返回不含位置的错误信息与出错的位置
+pub(crate) fn tokenize_spanned(input: &str) -> Result<Vec<(Token, Span)>, (String, Span)> {
+    let mut spanned = Vec::new();
+    let mut chars = Cursor {
+        chars: input.chars().peekable(),
+        offset: 0,
+        line: 1,
+        column: 1,
+    };
 
     while let Some(&ch) = chars.peek() {
-        match ch {
+        let start = chars.clone();
+        let token = match ch {
             '(' => {
-                tokens.push(Token::LParen);
                 chars.next();
+                Token::LParen
             }
             ')' => {
-                tokens.push(Token::RParen);
                 chars.next();
+                Token::RParen
             }
             '[' => {
-                tokens.push(Token::LBracket);
                 chars.next();
+                Token::LBracket
             }
             ']' => {
-                tokens.push(Token::RBracket);
                 chars.next();
+                Token::RBracket
             }
             '<' => {
-                tokens.push(Token::Lt);
                 chars.next();
+                Token::Lt
             }
             '>' => {
-                tokens.push(Token::Gt);
                 chars.next();
+                Token::Gt
             }
             ',' => {
-                tokens.push(Token::Comma);
                 chars.next();
+                Token::Comma
             }
             '?' => {
-                tokens.push(Token::Question);
                 chars.next();
+                Token::Question
             }
             ':' => {
-                tokens.push(Token::Colon);
                 chars.next();
+                Token::Colon
             }
             '=' => {
-                tokens.push(Token::Equal);
                 chars.next();
+                Token::Equal
+            }
+            '!' => {
+                chars.next();
+                Token::Bang
             }
             '|' => {
-                tokens.push(Token::Pipe);
                 chars.next();
+                Token::Pipe
+            }
+
+            // 不接数字的 `.` 是路径分隔符，如 `prev.end`
+            '.' if !chars
+                .chars
+                .clone()
+                .nth(1)
+                .is_some_and(|c| c.is_ascii_digit()) =>
+            {
+                chars.next();
+                Token::Dot
             }
 
             // 新逻辑：支持 + / - 开头
@@ -99,10 +191,13 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
 
                 // 尝试解析为 f64 验证格式是否正确
                 if num_str.parse::<f64>().is_err() {
-                    return Err(format!("Invalid number '{}'", num_str));
+                    return Err((
+                        format!("Invalid number '{}'", num_str),
+                        chars.span_from(&start),
+                    ));
                 }
 
-                tokens.push(Token::Number(num_str));
+                Token::Number(num_str)
             }
             '"' => {
                 chars.next(); // skip opening quote
@@ -132,7 +227,7 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
                         chars.next();
                     }
                 }
-                tokens.push(Token::String(s)); // 字符串作为 String 保存
+                Token::String(s) // 字符串作为 String 保存
             }
             c if c.is_alphanumeric() || c == '_' => {
                 let mut ident = String::new();
@@ -144,16 +239,36 @@ pub fn tokenize(input: &str) -> Result<Vec<Token>, String> {
                         break;
                     }
                 }
-                tokens.push(Token::Ident(ident));
+                Token::Ident(ident)
+            }
+            '{' => {
+                chars.next();
+                let mut name = String::new();
+                while let Some(&c) = chars.peek()
+                    && (c.is_alphanumeric() || c == '_')
+                {
+                    name.push(c);
+                    chars.next();
+                }
+                if name.is_empty() || chars.next() != Some('}') {
+                    return Err((
+                        "Expected macro parameter '{name}'".into(),
+                        chars.span_from(&start),
+                    ));
+                }
+                Token::Placeholder(name)
             }
             c if c.is_whitespace() => {
                 chars.next();
+                continue;
             }
             _ => {
-                return Err(format!("Unexpected char '{}'", ch));
+                chars.next();
+                return Err((format!("Unexpected char '{}'", ch), chars.span_from(&start)));
             }
-        }
+        };
+        spanned.push((token, chars.span_from(&start)));
     }
 
-    Ok(tokens)
+    Ok(spanned)
 }
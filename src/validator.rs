@@ -1,15 +1,44 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 static REGEX_CACHE: Lazy<Mutex<HashMap<String, Regex>>> = Lazy::new(|| Mutex::new(HashMap::new()));
 
 use crate::{
-    ast::{Constraint, FieldRule, FieldType, Value},
-    parser::Parser,
+    ast::{
+        Aggregate, AggregateBound, Collation, CompareOp, Constraint, DefaultExpr, FieldCompare,
+        FieldRule, FieldType, HostnameSpec, ObjectMap, Operand, Transform, UuidSpec, Value,
+        compare_numbers,
+    },
+    audit::{self, AuditSink},
+    budget,
+    canonical::canonicalize_object,
+    checksum::luhn_valid,
+    clock::{Clock, SystemClock, age_at, format_instant, instant_of},
+    collation,
+    color::is_valid_color,
+    compile::CompiledRule,
+    i18n::{Labels, MessageResolver},
+    ids::{IdGenerator, RandomIds},
+    money,
+    net::{check_hostname, is_port, is_socket_addr},
+    parser::{Parser, oui_hex},
+    phone::normalize_phone,
+    refs::{AsyncRefResolver, RefIndex, RefResolver, ResolvedRefs, collect_exists},
+    registry::ValidatorRegistry,
+    report::{
+        Change, ChangeKind, ChangeSet, ErrorReport, FieldOutcome, Outcome, OutcomeReport,
+        ValidationReport, hash_fields,
+    },
+    signature,
+    slug::{BasicTransliterator, Transliterator, slugify},
+    timing,
     token::tokenize,
+    warning,
 };
 
 /// -----------------------------
@@ -18,11 +47,14 @@ use crate::{
 #[derive(Debug, PartialEq, Clone)]
 pub enum ValidationError {
     MissingField(String),
+    /// 值不符合声明的类型：`actual` 为值的种类（见 `Value::type_name`）；
+    /// 种类相符但格式不对（如不合法的 email）时 `reason` 说明原因
     TypeMismatch {
         field: String,
         value: String,
-        expected: String,
-        actual: String,
+        expected: FieldType,
+        actual: &'static str,
+        reason: Option<String>,
     },
     UnionTypeMismatch {
         field: String,
@@ -39,6 +71,11 @@ pub enum ValidationError {
         value: String,
         min: String,
         max: String,
+        /// 区间两端是否包含边界，即规则中写的是 `[` / `]` 还是 `(` / `)`
+        min_inclusive: bool,
+        max_inclusive: bool,
+        /// 区间限制的是字符串长度或数组元素个数，`value` 为长度
+        length: bool,
     },
     RegexMismatch {
         field: String,
@@ -46,6 +83,118 @@ pub enum ValidationError {
     },
     InvalidRegex(String),
     NotAnObject(String),
+    UnknownField(String),
+    CheckFailed {
+        field: String,
+        check: String,
+        message: String,
+    },
+    ScriptFailed {
+        field: String,
+        script: String,
+        message: String,
+    },
+    TimeError {
+        field: String,
+        value: String,
+        constraint: String,
+    },
+    ChecksumMismatch {
+        field: String,
+        algorithm: String,
+    },
+    /// `signed_with(...)` 重新计算的签名与字段的值不符
+    SignatureMismatch {
+        field: String,
+        algorithm: String,
+    },
+    /// `encrypted(name)` 的解密器无法解密字段的值，或明文不是 JSON
+    DecryptionFailed {
+        field: String,
+        decryptor: String,
+        message: String,
+    },
+    /// `SequenceValidator` 的规则在相邻两次提交之间不成立
+    SequenceViolation {
+        field: String,
+        rule: String,
+        previous: String,
+        value: String,
+    },
+    /// 同一序列的并发提交不断抢先写入，重试 `attempts` 次后仍未能记录本次载荷
+    SequenceConflict {
+        field: String,
+        attempts: usize,
+    },
+    NotSorted {
+        field: String,
+        index: usize,
+        order: String,
+    },
+    AggregateError {
+        field: String,
+        constraint: String,
+        actual: String,
+    },
+    DuplicateKey {
+        field: String,
+        key: String,
+        value: String,
+        indices: Vec<usize>,
+    },
+    ElementConstraint {
+        field: String,
+        index: usize,
+        constraint: String,
+    },
+    DanglingRef {
+        field: String,
+        value: String,
+        target: String,
+    },
+    OuiMismatch {
+        field: String,
+        oui: String,
+    },
+    NotMultipleOf {
+        field: String,
+        value: String,
+        divisor: String,
+    },
+    /// 带有 `absent` 约束的字段出现在输入中
+    ForbiddenField(String),
+    /// 带有 `deprecated` 约束的字段出现在输入中（警告）
+    Deprecated {
+        field: String,
+        message: Option<String>,
+    },
+    /// `exhaustive` 模式下同一字段的多个失败；达到 `max_errors` 后不再收集，
+    /// `truncated` 表示还有失败被丢弃
+    FieldErrors {
+        field: String,
+        errors: Vec<ValidationError>,
+        truncated: bool,
+    },
+    /// `frozen` 模式下校验还会改写载荷：`change` 为修改的类别（`default` / `coerce` / `normalize` / `transform`）
+    WouldMutate {
+        field: String,
+        change: String,
+    },
+    /// 嵌套层数超过 `ValidationOptions::max_depth`
+    MaxDepthExceeded {
+        field: String,
+        limit: usize,
+    },
+    /// 访问到 `field` 时 `ValidationOptions::budget` 已经用完，`limit` 为超出的限制
+    BudgetExceeded {
+        field: String,
+        limit: String,
+    },
+    /// `ordered` 模式下 `field` 出现在了同级的 `after` 之后，而规则中它声明在前
+    OutOfOrder {
+        field: String,
+        after: String,
+    },
     Custom(String),
 }
 
@@ -53,16 +202,27 @@ impl fmt::Display for ValidationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::MissingField(field) => write!(f, "Missing required field {}", field),
+            Self::TypeMismatch {
+                field,
+                value,
+                reason: Some(reason),
+                ..
+            } => match field.as_str() {
+                "" => write!(f, "{}", reason),
+                _ => write!(f, "{} value {}: {}", field, value, reason),
+            },
             Self::TypeMismatch {
                 field,
                 value,
                 expected,
                 actual,
-            } => write!(
-                f,
-                "{} value {}: expected {}, found {}",
-                field, value, expected, actual
-            ),
+                reason: None,
+            } => {
+                if !field.is_empty() {
+                    write!(f, "{}: ", field)?;
+                }
+                write!(f, "expected {}, got {} {}", expected, actual, value)
+            }
             Self::UnionTypeMismatch {
                 field,
                 value,
@@ -82,16 +242,145 @@ impl fmt::Display for ValidationError {
                 value,
                 min,
                 max,
+                min_inclusive,
+                max_inclusive,
+                length,
             } => write!(
                 f,
-                "{} value {} out of range [{}, {}]",
-                field, value, min, max
+                "{} {} {} out of range {}{}, {}{}",
+                field,
+                if *length { "length" } else { "value" },
+                value,
+                if *min_inclusive { '[' } else { '(' },
+                min,
+                max,
+                if *max_inclusive { ']' } else { ')' },
             ),
             Self::RegexMismatch { field, pattern } => {
                 write!(f, "{} regex mismatch: {}", field, pattern)
             }
             Self::InvalidRegex(err) => write!(f, "Invalid regex: {}", err),
             Self::NotAnObject(field) => write!(f, "{} is not object but has children", field),
+            Self::UnknownField(field) => write!(f, "Unknown field {}", field),
+            Self::ForbiddenField(field) => write!(f, "Field {} must not be provided", field),
+            Self::CheckFailed {
+                field,
+                check,
+                message,
+            } => write!(f, "{} failed check '{}': {}", field, check, message),
+            Self::ScriptFailed {
+                field,
+                script,
+                message,
+            } => write!(f, "{} failed script `{}`: {}", field, script, message),
+            Self::TimeError {
+                field,
+                value,
+                constraint,
+            } => write!(f, "{} = {} does not satisfy {}", field, value, constraint),
+            Self::ChecksumMismatch { field, algorithm } => {
+                write!(f, "{} failed {} checksum", field, algorithm)
+            }
+            Self::SignatureMismatch { field, algorithm } => {
+                write!(f, "{} is not a valid {} signature", field, algorithm)
+            }
+            Self::DecryptionFailed {
+                field,
+                decryptor,
+                message,
+            } => write!(
+                f,
+                "{} could not be decrypted with {}: {}",
+                field, decryptor, message
+            ),
+            Self::SequenceViolation {
+                field,
+                rule,
+                previous,
+                value,
+            } => write!(
+                f,
+                "{} changed from {} to {}, violating {}",
+                field, previous, value, rule
+            ),
+            Self::SequenceConflict { field, attempts } => write!(
+                f,
+                "{} could not be recorded after {} retries on conflicting writes",
+                field, attempts
+            ),
+            Self::NotSorted {
+                field,
+                index,
+                order,
+            } => write!(f, "{}[{}] breaks {} order", field, index, order),
+            Self::AggregateError {
+                field,
+                constraint,
+                actual,
+            } => write!(f, "{} fails {} (actual {})", field, constraint, actual),
+            Self::DuplicateKey {
+                field,
+                key,
+                value,
+                indices,
+            } => write!(
+                f,
+                "{} has duplicate {} = {} at indices {:?}",
+                field, key, value, indices
+            ),
+            Self::ElementConstraint {
+                field,
+                index,
+                constraint,
+            } => write!(f, "{}[{}] fails {}", field, index, constraint),
+            Self::DanglingRef {
+                field,
+                value,
+                target,
+            } => write!(f, "{} = {} does not match any {}", field, value, target),
+            Self::OuiMismatch { field, oui } => {
+                write!(f, "{} vendor prefix {} is not allowed", field, oui)
+            }
+            Self::NotMultipleOf {
+                field,
+                value,
+                divisor,
+            } => write!(f, "{} = {} is not a multiple of {}", field, value, divisor),
+            Self::Deprecated {
+                field,
+                message: None,
+            } => write!(f, "{} is deprecated", field),
+            Self::Deprecated {
+                field,
+                message: Some(message),
+            } => write!(f, "{} is deprecated: {}", field, message),
+            Self::FieldErrors {
+                field,
+                errors,
+                truncated,
+            } => {
+                let all: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                write!(
+                    f,
+                    "{} has {} errors{}: {}",
+                    field,
+                    errors.len(),
+                    if *truncated { " (truncated)" } else { "" },
+                    all.join("; ")
+                )
+            }
+            Self::WouldMutate { field, change } => {
+                write!(f, "{} would be changed by validation ({})", field, change)
+            }
+            Self::MaxDepthExceeded { field, limit } => {
+                write!(f, "{} is nested deeper than {} levels", field, limit)
+            }
+            Self::BudgetExceeded { field, limit } => {
+                write!(f, "Validation budget of {} exceeded at {}", limit, field)
+            }
+            Self::OutOfOrder { field, after } => {
+                write!(f, "{} must come before '{}'", field, after)
+            }
             Self::Custom(err) => write!(f, "{}", err),
         }
     }
@@ -99,577 +388,3420 @@ impl fmt::Display for ValidationError {
 
 impl std::error::Error for ValidationError {}
 
-pub type Result<T> = std::result::Result<T, ValidationError>;
-
-/// -----------------------------
-/// Pre-compiled Regexes
-/// -----------------------------
-static EMAIL_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^[^@\s]+@[^@\s]+\.[^@\s]+$").expect("invalid regex"));
-static UUID_RE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"^[0-9a-fA-F]{8}-?[0-9a-fA-F]{4}-?[0-9a-fA-F]{4}-?[0-9a-fA-F]{4}-?[0-9a-fA-F]{12}$")
-        .expect("invalid regex")
-});
-static IP_V4_RE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"^((25[0-5]|2[0-4]\d|[01]?\d\d?)\.){3}(25[0-5]|2[0-4]\d|[01]?\d\d?)$")
-        .expect("invalid regex")
-});
-static IP_V6_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^([0-9a-fA-F]{1,4}:){7}[0-9a-fA-F]{1,4}$").expect("invalid regex"));
-static MAC_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^([0-9A-Fa-f]{2}[:-]){5}([0-9A-Fa-f]{2})$").expect("invalid regex"));
-static DATE_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^\d{4}-\d{2}-\d{2}$").expect("invalid regex"));
-static DATETIME_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z?$").expect("invalid regex"));
-static TIME_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^\d{2}:\d{2}:\d{2}$").expect("invalid regex"));
-static COLOR_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^#([0-9a-fA-F]{6}|[0-9a-fA-F]{3})$").expect("invalid regex"));
-static HOSTNAME_RE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"^(?:[a-zA-Z0-9_](?:[a-zA-Z0-9_-]{0,61}[a-zA-Z0-9])?\.)+[a-zA-Z]{2,63}$")
-        .expect("invalid regex")
-});
-static SLUG_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^[a-z0-9]+(?:-[a-z0-9]+)*$").expect("invalid regex"));
-static HEX_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[0-9a-fA-F]+$").expect("invalid regex"));
-static BASE64_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^[A-Za-z0-9+/]+={0,2}$").expect("invalid regex"));
-static PHONE_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^\+?[1-9]\d{1,14}$").expect("invalid regex"));
-static CREDITCARD_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^[0-9]{13,19}$").expect("invalid regex"));
-static ISBN_RE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"^(?:ISBN-?1[03]:? )?(?:97[89]-?)?[0-9]{9}[0-9X]$").expect("invalid regex")
-});
-static PORT_RE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(
-        r"^(?:[0-9]{1,4}|[1-5][0-9]{4}|6[0-4][0-9]{3}|65[0-4][0-9]{2}|655[0-2][0-9]|6553[0-5])$",
-    )
-    .expect("invalid regex")
-});
-static JSON_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r#"^[\[\]{}:,0-9"'\s-]+$"#).expect("invalid regex"));
-static URLENCODED_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^[a-zA-Z0-9._~-%]+$").expect("invalid regex"));
-static SEMVER_RE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"^(0|[1-9]\d*)\.(0|[1-9]\d*)\.(0|[1-9]\d*)(-[0-9a-zA-Z-]+(\.[0-9a-zA-Z-]+)*)?(\+[0-9a-zA-Z-]+(\.[0-9a-zA-Z-]+)*)?$").expect("invalid regex")
-});
-static USERNAME_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^[a-zA-Z][a-zA-Z0-9_-]{2,19}$").expect("invalid regex"));
-static COUNTRYCODE_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^[A-Z]{2}$").expect("invalid regex"));
-static POSTALCODE_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^[A-Z0-9]{3,10}$").expect("invalid regex"));
-static FILEPATH_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^(?:[a-zA-Z]:)?[/\w.-]+$").expect("invalid regex"));
-static ALPHA_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-zA-Z]+$").expect("invalid regex"));
-static ALPHANUMERIC_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^[a-zA-Z0-9]+$").expect("invalid regex"));
-
-/// -----------------------------
-/// Validator
-/// -----------------------------
-pub fn validate_field(value: &mut Value, rule: &FieldRule) -> Result<()> {
-    // 对对象，先填充默认值
-    if let Value::Object(obj) = value
-        && !obj.contains_key(&rule.field)
-        && let Some(d) = &rule.default
-    {
-        obj.insert(rule.field.clone(), d.clone());
+impl ValidationError {
+    /// 出错字段的完整路径：嵌套对象以 `.` 连接，数组元素带下标，如 `profile.contact.email`、
+    /// `tags[3]`、`users[0].name`；`InvalidRegex` 与 `Custom` 没有字段
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            Self::MissingField(field)
+            | Self::NotAnObject(field)
+            | Self::UnknownField(field)
+            | Self::ForbiddenField(field)
+            | Self::TypeMismatch { field, .. }
+            | Self::UnionTypeMismatch { field, .. }
+            | Self::EnumMismatch { field, .. }
+            | Self::RangeError { field, .. }
+            | Self::RegexMismatch { field, .. }
+            | Self::CheckFailed { field, .. }
+            | Self::ScriptFailed { field, .. }
+            | Self::TimeError { field, .. }
+            | Self::ChecksumMismatch { field, .. }
+            | Self::SignatureMismatch { field, .. }
+            | Self::DecryptionFailed { field, .. }
+            | Self::SequenceViolation { field, .. }
+            | Self::SequenceConflict { field, .. }
+            | Self::NotSorted { field, .. }
+            | Self::AggregateError { field, .. }
+            | Self::DuplicateKey { field, .. }
+            | Self::ElementConstraint { field, .. }
+            | Self::DanglingRef { field, .. }
+            | Self::OuiMismatch { field, .. }
+            | Self::NotMultipleOf { field, .. }
+            | Self::Deprecated { field, .. }
+            | Self::FieldErrors { field, .. }
+            | Self::WouldMutate { field, .. }
+            | Self::MaxDepthExceeded { field, .. }
+            | Self::BudgetExceeded { field, .. }
+            | Self::OutOfOrder { field, .. } => Some(field),
+            Self::InvalidRegex(_) | Self::Custom(_) => None,
+        }
     }
 
-    // 获取值
-    let val_opt = match value {
-        Value::Object(obj) => obj.get_mut(&rule.field),
-        _ => Some(value),
-    };
-
-    let val = match val_opt {
-        Some(v) => v,
-        None => {
-            if rule.required {
-                return Err(ValidationError::MissingField(rule.field.clone()));
-            } else {
-                return Ok(());
+    /// 以 JSON Pointer（RFC 6901）表示的字段路径：`profile.contact.email` 为
+    /// `/profile/contact/email`，`tags[3]` 为 `/tags/3`
+    pub fn pointer(&self) -> Option<String> {
+        let field = self.field()?;
+        let mut pointer = String::new();
+        for segment in field.split('.') {
+            let (name, indices) = match segment.find('[') {
+                Some(at) => segment.split_at(at),
+                None => (segment, ""),
+            };
+            if !name.is_empty() {
+                pointer.push('/');
+                pointer.push_str(&name.replace('~', "~0").replace('/', "~1"));
+            }
+            for index in indices.split(['[', ']']).filter(|i| !i.is_empty()) {
+                pointer.push('/');
+                pointer.push_str(index);
             }
         }
-    };
+        Some(pointer)
+    }
 
-    if !rule.required
-        && let Value::String(s) = val
-        && s.is_empty()
-    {
-        return Ok(());
+    fn field_mut(&mut self) -> Option<&mut String> {
+        match self {
+            Self::MissingField(field)
+            | Self::NotAnObject(field)
+            | Self::UnknownField(field)
+            | Self::ForbiddenField(field)
+            | Self::TypeMismatch { field, .. }
+            | Self::UnionTypeMismatch { field, .. }
+            | Self::EnumMismatch { field, .. }
+            | Self::RangeError { field, .. }
+            | Self::RegexMismatch { field, .. }
+            | Self::CheckFailed { field, .. }
+            | Self::ScriptFailed { field, .. }
+            | Self::TimeError { field, .. }
+            | Self::ChecksumMismatch { field, .. }
+            | Self::SignatureMismatch { field, .. }
+            | Self::DecryptionFailed { field, .. }
+            | Self::SequenceViolation { field, .. }
+            | Self::SequenceConflict { field, .. }
+            | Self::NotSorted { field, .. }
+            | Self::AggregateError { field, .. }
+            | Self::DuplicateKey { field, .. }
+            | Self::ElementConstraint { field, .. }
+            | Self::DanglingRef { field, .. }
+            | Self::OuiMismatch { field, .. }
+            | Self::NotMultipleOf { field, .. }
+            | Self::Deprecated { field, .. }
+            | Self::FieldErrors { field, .. }
+            | Self::WouldMutate { field, .. }
+            | Self::MaxDepthExceeded { field, .. }
+            | Self::BudgetExceeded { field, .. }
+            | Self::OutOfOrder { field, .. } => Some(field),
+            Self::InvalidRegex(_) | Self::Custom(_) => None,
+        }
     }
 
-    // union types 验证
-    if let Some(types) = &rule.union_types {
-        let mut ok = false;
-        for t in types {
-            if validate_type(val, t).is_ok() {
-                ok = true;
-                break;
-            }
+    /// 错误从子字段或数组元素向上传递时，在路径前加上所在位置：
+    /// `parent` 为对象字段名或带下标的 `tags[3]`，为空（无名的数组元素规则）时不变
+    pub(crate) fn within(mut self, parent: &str) -> Self {
+        if parent.is_empty() {
+            return self;
         }
-        if !ok {
-            return Err(ValidationError::UnionTypeMismatch {
-                field: rule.field.clone(),
-                value: format!("{:?}", val),
-                types: types.clone(),
-            });
+        if let Some(field) = self.field_mut() {
+            *field = join_path(parent, field);
         }
-    } else {
-        validate_type(val, &rule.field_type).map_err(|e| {
-            if let ValidationError::Custom(msg) = e {
-                ValidationError::TypeMismatch {
-                    field: rule.field.clone(),
-                    value: format!("{:?}", val),
-                    expected: format!("{:?}", rule.field_type),
-                    actual: msg,
-                }
-            } else {
-                e
-            }
-        })?;
+        if let Self::FieldErrors { errors, .. } = &mut self {
+            *errors = std::mem::take(errors)
+                .into_iter()
+                .map(|e| e.within(parent))
+                .collect();
+        }
+        self
     }
 
-    // enum 验证
-    if let Some(enum_vals) = &rule.enum_values
-        && !enum_vals.contains(val)
-    {
-        return Err(ValidationError::EnumMismatch {
-            field: rule.field.clone(),
-            value: format!("{:?}", val),
-            expected: enum_vals.clone(),
-        });
+    /// 出错的值（文本形式），只有携带值的错误才有
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            Self::TypeMismatch { value, .. }
+            | Self::UnionTypeMismatch { value, .. }
+            | Self::EnumMismatch { value, .. }
+            | Self::RangeError { value, .. }
+            | Self::TimeError { value, .. }
+            | Self::DuplicateKey { value, .. }
+            | Self::DanglingRef { value, .. }
+            | Self::NotMultipleOf { value, .. } => Some(value),
+            Self::OuiMismatch { oui, .. } => Some(oui),
+            _ => None,
+        }
     }
 
-    // constraints 验证
-    if let Some(c) = &rule.constraints {
-        for con in &c.items {
-            validate_constraint(val, con, &rule.field)?;
+    /// 稳定的错误代码（snake_case），用于构造 API 错误响应或按类别处理，
+    /// 不随错误文本的措辞变化
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MissingField(_) => "missing_field",
+            Self::TypeMismatch { .. } => "type_mismatch",
+            Self::UnionTypeMismatch { .. } => "union_type_mismatch",
+            Self::EnumMismatch { .. } => "enum_mismatch",
+            Self::RangeError { .. } => "out_of_range",
+            Self::RegexMismatch { .. } => "regex_mismatch",
+            Self::InvalidRegex(_) => "invalid_regex",
+            Self::NotAnObject(_) => "not_an_object",
+            Self::UnknownField(_) => "unknown_field",
+            Self::CheckFailed { .. } => "check_failed",
+            Self::ScriptFailed { .. } => "script_failed",
+            Self::TimeError { .. } => "time_constraint",
+            Self::ChecksumMismatch { .. } => "checksum_mismatch",
+            Self::SignatureMismatch { .. } => "signature_mismatch",
+            Self::DecryptionFailed { .. } => "decryption_failed",
+            Self::SequenceViolation { .. } => "sequence_violation",
+            Self::SequenceConflict { .. } => "sequence_conflict",
+            Self::NotSorted { .. } => "not_sorted",
+            Self::AggregateError { .. } => "aggregate",
+            Self::DuplicateKey { .. } => "duplicate_key",
+            Self::ElementConstraint { .. } => "element_constraint",
+            Self::DanglingRef { .. } => "dangling_ref",
+            Self::OuiMismatch { .. } => "oui_mismatch",
+            Self::NotMultipleOf { .. } => "not_multiple_of",
+            Self::ForbiddenField(_) => "forbidden_field",
+            Self::Deprecated { .. } => "deprecated",
+            Self::FieldErrors { .. } => "field_errors",
+            Self::WouldMutate { .. } => "would_mutate",
+            Self::MaxDepthExceeded { .. } => "max_depth_exceeded",
+            Self::BudgetExceeded { .. } => "budget_exceeded",
+            Self::OutOfOrder { .. } => "out_of_order",
+            Self::Custom(_) => "custom",
         }
     }
 
-    // sub_rule / array / object 递归验证
-    if let Some(sub_rule) = &rule.rule {
-        match val {
-            Value::Object(_) => validate_field(val, sub_rule)?,
-            Value::Array(arr) => {
-                for v in arr.iter_mut() {
-                    validate_field(v, sub_rule)?;
-                }
+    /// 与 `code()` 对应的消息参数，供 [`MessageResolver`] 填入翻译模板；
+    /// 参数名与字段名一致，如 `out_of_range` 有 `field`、`value`、`min`、`max`
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::MissingField(field)
+            | Self::NotAnObject(field)
+            | Self::UnknownField(field)
+            | Self::ForbiddenField(field) => vec![("field", field.clone())],
+            Self::TypeMismatch {
+                field,
+                value,
+                expected,
+                actual,
+                reason,
+            } => vec![
+                ("field", field.clone()),
+                ("value", value.clone()),
+                ("expected", expected.to_string()),
+                ("actual", actual.to_string()),
+                ("reason", reason.clone().unwrap_or_default()),
+            ],
+            Self::UnionTypeMismatch {
+                field,
+                value,
+                types,
+            } => vec![
+                ("field", field.clone()),
+                ("value", value.clone()),
+                (
+                    "types",
+                    types
+                        .iter()
+                        .map(|t| t.to_string())
+                        .collect::<Vec<_>>()
+                        .join("|"),
+                ),
+            ],
+            Self::EnumMismatch {
+                field,
+                value,
+                expected,
+            } => vec![
+                ("field", field.clone()),
+                ("value", value.clone()),
+                (
+                    "expected",
+                    expected
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+            ],
+            Self::RangeError {
+                field,
+                value,
+                min,
+                max,
+                ..
+            } => vec![
+                ("field", field.clone()),
+                ("value", value.clone()),
+                ("min", min.clone()),
+                ("max", max.clone()),
+            ],
+            Self::RegexMismatch { field, pattern } => {
+                vec![("field", field.clone()), ("pattern", pattern.clone())]
             }
-            _ => {}
+            Self::InvalidRegex(error) => vec![("error", error.clone())],
+            Self::CheckFailed {
+                field,
+                check,
+                message,
+            } => vec![
+                ("field", field.clone()),
+                ("check", check.clone()),
+                ("message", message.clone()),
+            ],
+            Self::ScriptFailed {
+                field,
+                script,
+                message,
+            } => vec![
+                ("field", field.clone()),
+                ("script", script.clone()),
+                ("message", message.clone()),
+            ],
+            Self::TimeError {
+                field,
+                value,
+                constraint,
+            } => vec![
+                ("field", field.clone()),
+                ("value", value.clone()),
+                ("constraint", constraint.clone()),
+            ],
+            Self::ChecksumMismatch { field, algorithm }
+            | Self::SignatureMismatch { field, algorithm } => {
+                vec![("field", field.clone()), ("algorithm", algorithm.clone())]
+            }
+            Self::DecryptionFailed {
+                field,
+                decryptor,
+                message,
+            } => vec![
+                ("field", field.clone()),
+                ("decryptor", decryptor.clone()),
+                ("message", message.clone()),
+            ],
+            Self::SequenceViolation {
+                field,
+                rule,
+                previous,
+                value,
+            } => vec![
+                ("field", field.clone()),
+                ("rule", rule.clone()),
+                ("previous", previous.clone()),
+                ("value", value.clone()),
+            ],
+            Self::SequenceConflict { field, attempts } => {
+                vec![("field", field.clone()), ("attempts", attempts.to_string())]
+            }
+            Self::NotSorted {
+                field,
+                index,
+                order,
+            } => vec![
+                ("field", field.clone()),
+                ("index", index.to_string()),
+                ("order", order.clone()),
+            ],
+            Self::AggregateError {
+                field,
+                constraint,
+                actual,
+            } => vec![
+                ("field", field.clone()),
+                ("constraint", constraint.clone()),
+                ("actual", actual.clone()),
+            ],
+            Self::DuplicateKey {
+                field,
+                key,
+                value,
+                indices,
+            } => vec![
+                ("field", field.clone()),
+                ("key", key.clone()),
+                ("value", value.clone()),
+                (
+                    "indices",
+                    indices
+                        .iter()
+                        .map(|i| i.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+            ],
+            Self::ElementConstraint {
+                field,
+                index,
+                constraint,
+            } => vec![
+                ("field", field.clone()),
+                ("index", index.to_string()),
+                ("constraint", constraint.clone()),
+            ],
+            Self::DanglingRef {
+                field,
+                value,
+                target,
+            } => vec![
+                ("field", field.clone()),
+                ("value", value.clone()),
+                ("target", target.clone()),
+            ],
+            Self::OuiMismatch { field, oui } => {
+                vec![("field", field.clone()), ("oui", oui.clone())]
+            }
+            Self::NotMultipleOf {
+                field,
+                value,
+                divisor,
+            } => vec![
+                ("field", field.clone()),
+                ("value", value.clone()),
+                ("divisor", divisor.clone()),
+            ],
+            Self::Deprecated { field, message } => vec![
+                ("field", field.clone()),
+                ("message", message.clone().unwrap_or_default()),
+            ],
+            Self::FieldErrors {
+                field,
+                errors,
+                truncated,
+            } => vec![
+                ("field", field.clone()),
+                ("count", errors.len().to_string()),
+                ("truncated", truncated.to_string()),
+            ],
+            Self::WouldMutate { field, change } => {
+                vec![("field", field.clone()), ("change", change.clone())]
+            }
+            Self::MaxDepthExceeded { field, limit } => {
+                vec![("field", field.clone()), ("limit", limit.to_string())]
+            }
+            Self::BudgetExceeded { field, limit } => {
+                vec![("field", field.clone()), ("limit", limit.clone())]
+            }
+            Self::OutOfOrder { field, after } => {
+                vec![("field", field.clone()), ("after", after.clone())]
+            }
+            Self::Custom(message) => vec![("message", message.clone())],
         }
     }
 
-    if let Some(children) = &rule.children {
-        if let Value::Object(_) = val {
-            for child_rule in children {
-                validate_field(val, child_rule)?;
-            }
-        } else {
-            return Err(ValidationError::NotAnObject(rule.field.clone()));
+    /// 用 `resolver` 翻译错误文本，找不到对应代码的模板时使用英文的 `to_string()`。
+    /// `field_errors` 另有参数 `errors`：逐个翻译后以 `; ` 连接的内层错误；
+    /// 有字段的错误另有参数 `label`，这里即为字段路径，见 [`ValidationError::localized_with_labels`]
+    pub fn localized(&self, resolver: &dyn MessageResolver) -> String {
+        self.localized_with_labels(resolver, &Labels::new())
+    }
+
+    /// 同 [`ValidationError::localized`]，参数 `label` 取字段的 `label(...)`，没有时为字段路径，
+    /// 使模板可以写成 `"{label} is invalid"` 而不是直接显示 `profile.contact.email`
+    pub fn localized_with_labels(&self, resolver: &dyn MessageResolver, labels: &Labels) -> String {
+        let mut params = self.params();
+        if let Self::FieldErrors { errors, .. } = self {
+            let inner = errors
+                .iter()
+                .map(|e| e.localized_with_labels(resolver, labels))
+                .collect::<Vec<_>>()
+                .join("; ");
+            params.push(("errors", inner));
+        }
+        if let Some(field) = self.field() {
+            params.push(("label", labels.label_for(field).to_string()));
+        }
+        if let Some(message) = resolver.resolve(self.code(), &params) {
+            return message;
+        }
+        match self {
+            Self::FieldErrors {
+                field,
+                errors,
+                truncated,
+            } => format!(
+                "{} has {} errors{}: {}",
+                field,
+                errors.len(),
+                if *truncated { " (truncated)" } else { "" },
+                params[3].1
+            ),
+            _ => self.to_string(),
         }
     }
+}
 
-    Ok(())
+pub type Result<T> = std::result::Result<T, ValidationError>;
+
+/// 把子路径接到 `parent` 之后：`profile` + `email` 为 `profile.email`，
+/// `tags` + `[3]` 为 `tags[3]`，子路径为空时就是 `parent`
+pub(crate) fn join_path(parent: &str, child: &str) -> String {
+    if child.is_empty() {
+        parent.to_string()
+    } else if child.starts_with('[') {
+        format!("{}{}", parent, child)
+    } else {
+        format!("{}.{}", parent, child)
+    }
 }
 
-fn validate_constraint(val: &Value, con: &Constraint, field_name: &str) -> Result<()> {
-    match con {
-        Constraint::Range {
-            min,
-            max,
-            min_inclusive,
-            max_inclusive,
-        } => validate_range(val, min, max, *min_inclusive, *max_inclusive, field_name),
-        Constraint::Regex(pattern) => {
-            let s = val.as_str().ok_or_else(|| {
-                ValidationError::Custom(format!("{} not string for regex", field_name))
-            })?;
-            let re = {
-                let mut cache = match REGEX_CACHE.lock() {
-                    Ok(c) => c,
-                    Err(e) => {
-                        tracing::error!("REGEX_CACHE lock poisoned: {}", e);
-                        return Err(ValidationError::Custom("regex cache lock poisoned".into()));
-                    }
-                };
-                if let Some(r) = cache.get(pattern) {
-                    r.clone()
-                } else {
-                    match Regex::new(pattern) {
-                        Ok(regex) => {
-                            cache.insert(pattern.clone(), regex.clone());
-                            regex
-                        }
-                        Err(e) => return Err(ValidationError::InvalidRegex(e.to_string())),
-                    }
-                }
-            };
-            if !re.is_match(s) {
-                return Err(ValidationError::RegexMismatch {
-                    field: field_name.to_string(),
-                    pattern: pattern.clone(),
-                });
+/// 遍历载荷时的当前位置，由父位置加字段名或下标串成链，
+/// 只在记录修改或报错时才拼成 `profile.tags[3]` 这样的字符串。
+/// 字段名借用规则中编译时驻留的 `FieldName`，通过的校验从不复制字段名；
+/// `ValidationError` 等公开类型中的路径仍为 `String`，只在出错或记录修改时分配一次
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Path<'a> {
+    Root,
+    /// 字段名为空（数组元素等无名规则）时与父位置相同
+    Field(&'a Path<'a>, &'a str),
+    Index(&'a Path<'a>, usize),
+}
+
+impl Path<'_> {
+    pub(crate) fn field<'b>(&'b self, name: &'b str) -> Path<'b> {
+        Path::Field(self, name)
+    }
+
+    pub(crate) fn index(&self, i: usize) -> Path<'_> {
+        Path::Index(self, i)
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            Path::Root => true,
+            Path::Field(parent, name) => name.is_empty() && parent.is_empty(),
+            Path::Index(..) => false,
+        }
+    }
+}
+
+impl fmt::Display for Path<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Path::Root => Ok(()),
+            Path::Field(parent, name) if name.is_empty() || parent.is_empty() => {
+                write!(f, "{}{}", parent, name)
             }
-            Ok(())
+            Path::Field(parent, name) => write!(f, "{}.{}", parent, name),
+            Path::Index(parent, i) => write!(f, "{}[{}]", parent, i),
         }
     }
 }
 
-fn validate_range(
-    val: &Value,
-    min: &Value,
-    max: &Value,
-    min_inc: bool,
-    max_inc: bool,
-    field: &str,
-) -> Result<()> {
-    match val {
-        Value::Int(i) => {
-            let n = *i as f64;
-            let min_v = match min {
-                Value::Int(mi) => *mi as f64,
-                Value::Float(mf) => mf.ceil(),
-                _ => {
-                    return Err(ValidationError::Custom(format!(
-                        "Invalid min value type for {}",
-                        field
-                    )));
+/// -----------------------------
+/// Pre-compiled Regexes
+/// -----------------------------
+/// 内置类型的正则：每个模式生成一个惰性编译的静态量，同时登记到 [`builtin_patterns`]，
+/// 由测试逐一编译，保证运行时的 `expect` 不会被触发
+macro_rules! builtin_regexes {
+    ($($(#[doc = $doc:expr])* $name:ident = $pattern:expr;)*) => {
+        $(
+            $(#[doc = $doc])*
+            static $name: Lazy<Regex> =
+                Lazy::new(|| Regex::new($pattern).expect("builtin pattern must compile"));
+        )*
+
+        /// 所有内置正则 `(名称, 模式)`
+        pub fn builtin_patterns() -> &'static [(&'static str, &'static str)] {
+            &[$((stringify!($name), $pattern)),*]
+        }
+    };
+}
+
+builtin_regexes! {
+    EMAIL_RE = r"^[^@\s]+@[^@\s]+\.[^@\s]+$";
+    UUID_RE = r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$";
+    UUID_COMPACT_RE = r"^[0-9a-fA-F]{32}$";
+    IP_V4_RE = r"^((25[0-5]|2[0-4]\d|[01]?\d\d?)\.){3}(25[0-5]|2[0-4]\d|[01]?\d\d?)$";
+    IP_V6_RE = r"^([0-9a-fA-F]{1,4}:){7}[0-9a-fA-F]{1,4}$";
+    /// 冒号/连字符分隔的 6 字节 (EUI-48) 或 8 字节 (EUI-64)，以及 Cisco 点分格式
+    MAC_RE = r"^(?:(?:[0-9A-Fa-f]{2}[:-]){5}(?:(?:[0-9A-Fa-f]{2}[:-]){2})?[0-9A-Fa-f]{2}|(?:[0-9A-Fa-f]{4}\.){2}(?:[0-9A-Fa-f]{4}\.)?[0-9A-Fa-f]{4})$";
+    DATE_RE = r"^\d{4}-\d{2}-\d{2}$";
+    DATETIME_RE = r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}Z?$";
+    TIME_RE = r"^\d{2}:\d{2}:\d{2}$";
+    COLOR_RE = r"^#([0-9a-fA-F]{6}|[0-9a-fA-F]{3})$";
+    SLUG_RE = r"^[a-z0-9]+(?:-[a-z0-9]+)*$";
+    HEX_RE = r"^[0-9a-fA-F]+$";
+    BASE64_RE = r"^[A-Za-z0-9+/]+={0,2}$";
+    PHONE_RE = r"^\+?[1-9]\d{1,14}$";
+    CREDITCARD_RE = r"^[0-9]{13,19}$";
+    ISBN_RE = r"^(?:ISBN-?1[03]:? )?(?:97[89]-?)?[0-9]{9}[0-9X]$";
+    JSON_RE = r#"^[\[\]{}:,0-9"'\s-]+$"#;
+    URLENCODED_RE = r"^[a-zA-Z0-9._~%-]+$";
+    SEMVER_RE = r"^(0|[1-9]\d*)\.(0|[1-9]\d*)\.(0|[1-9]\d*)(-[0-9a-zA-Z-]+(\.[0-9a-zA-Z-]+)*)?(\+[0-9a-zA-Z-]+(\.[0-9a-zA-Z-]+)*)?$";
+    USERNAME_RE = r"^[a-zA-Z][a-zA-Z0-9_-]{2,19}$";
+    COUNTRYCODE_RE = r"^[A-Z]{2}$";
+    POSTALCODE_RE = r"^[A-Z0-9]{3,10}$";
+    FILEPATH_RE = r"^(?:[a-zA-Z]:)?[/\w.-]+$";
+    /// Crockford Base32，首字符不超过 7，保证 128 位不溢出
+    ULID_RE = r"^[0-7][0-9A-HJKMNP-TV-Za-hjkmnp-tv-z]{25}$";
+    KSUID_RE = r"^[0-9A-Za-z]{27}$";
+    ALPHA_RE = r"^[a-zA-Z]+$";
+    ALPHANUMERIC_RE = r"^[a-zA-Z0-9]+$";
+}
+
+/// 编译用户在 DSL 中写的模式（带缓存）；非法模式返回 `InvalidRegex` 而不是 panic
+fn cached_regex(pattern: &str) -> Result<Regex> {
+    let mut cache = match REGEX_CACHE.lock() {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!("REGEX_CACHE lock poisoned: {}", e);
+            return Err(ValidationError::Custom("regex cache lock poisoned".into()));
+        }
+    };
+    if let Some(r) = cache.get(pattern) {
+        return Ok(r.clone());
+    }
+    let regex = Regex::new(pattern).map_err(|e| ValidationError::InvalidRegex(e.to_string()))?;
+    cache.insert(pattern.to_string(), regex.clone());
+    Ok(regex)
+}
+
+/// 160 位 KSUID 的最大值；Base62 字母表按 ASCII 排序，可直接比较字符串
+const KSUID_MAX: &str = "aWgEPTl1tmebfsQzFP4bxwgy80V";
+
+/// -----------------------------
+/// Validator
+/// -----------------------------
+pub fn validate_field(value: &mut Value, rule: &FieldRule) -> Result<()> {
+    let ctx = ValidationContext::shared();
+    fill_field(value, rule, Path::Root, ctx, &mut Vec::new());
+    let root = Task::Fields {
+        obj: value,
+        rules: std::slice::from_ref(rule),
+        compiled: None,
+        next: 0,
+        scope: None,
+        depth: 1,
+    };
+    match RefIndex::build(value, std::slice::from_ref(rule)) {
+        Some(index) => run(root, &ctx.clone().with_refs(index)),
+        None => run(root, ctx),
+    }
+}
+
+/// `validate_field` / `validate_object` 等的默认最大嵌套层数，与 JSON 解析的限制相同
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// -----------------------------
+/// Task
+/// -----------------------------
+/// 校验引擎的一项待办。嵌套的对象与数组不递归调用，而是压入显式的工作栈，
+/// 恶意构造的深层输入只会返回 `MaxDepthExceeded`，不会耗尽调用栈
+enum Task<'a> {
+    /// 依次在对象 `obj` 中定位 `rules` 的字段并校验（缺失时取默认值）；
+    /// `scope` 为这组字段所在的位置
+    Fields {
+        obj: &'a Value,
+        rules: &'a [FieldRule],
+        compiled: Option<&'a [CompiledRule]>,
+        next: usize,
+        scope: Option<&'a str>,
+        depth: usize,
+    },
+    /// 依次校验 `field` 数组的元素
+    Elements {
+        items: &'a [Value],
+        rule: &'a FieldRule,
+        compiled: Option<&'a CompiledRule>,
+        next: usize,
+        field: &'a str,
+        depth: usize,
+    },
+    /// 校验已经定位到的值
+    Value {
+        val: &'a Value,
+        rule: &'a FieldRule,
+        compiled: Option<&'a CompiledRule>,
+        parent: Option<&'a Value>,
+        depth: usize,
+    },
+    /// 子字段或数组元素校验完毕：为其间的计时与警告补上位置；
+    /// 出错时沿栈向上经过的每个 `Exit` 为错误补上位置
+    Exit {
+        scope: Scope<'a>,
+        marks: (usize, usize),
+    },
+}
+
+/// 子字段或数组元素所在的位置，需要补上位置时才拼成字符串
+#[derive(Clone, Copy)]
+enum Scope<'a> {
+    Field(&'a str),
+    Element(&'a str, usize),
+}
+
+impl<'a> Scope<'a> {
+    fn path(self) -> Cow<'a, str> {
+        match self {
+            Scope::Field(field) => Cow::Borrowed(field),
+            Scope::Element(field, i) => Cow::Owned(format!("{}[{}]", field, i)),
+        }
+    }
+}
+
+/// 处理 `root` 及其展开的全部待办，第一个错误即返回
+fn run(root: Task<'_>, ctx: &ValidationContext) -> Result<()> {
+    run_in(root, &mut Vec::new(), ctx)
+}
+
+/// 与 [`run`] 相同，使用调用方提供的空工作栈，返回时栈仍为空
+fn run_in<'a>(root: Task<'a>, stack: &mut Vec<Task<'a>>, ctx: &ValidationContext) -> Result<()> {
+    let _budget = budget::start(&ctx.options.budget);
+    stack.push(root);
+    drain(stack, ctx)
+}
+
+/// 依次处理栈中的任务，返回时栈为空
+fn drain<'a>(stack: &mut Vec<Task<'a>>, ctx: &ValidationContext) -> Result<()> {
+    while let Some(task) = stack.pop() {
+        if let Err(mut e) = step(task, stack, ctx) {
+            while let Some(task) = stack.pop() {
+                if let Task::Exit { scope, .. } = task {
+                    e = e.within(&scope.path());
+                }
+            }
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// 输入中的值；只读校验时换成 coerce 与转换后的副本
+pub(crate) fn leaf<'v>(
+    val: &'v Value,
+    rule: &FieldRule,
+    ctx: &ValidationContext,
+) -> Cow<'v, Value> {
+    match adjusted_leaf(val, rule, ctx) {
+        Some(adjusted) => Cow::Owned(adjusted),
+        None => Cow::Borrowed(val),
+    }
+}
+
+/// 按 `prepare` 的顺序（先 coerce，再 `slugify` 等转换）处理字符串与整数叶子的副本，
+/// 使只读校验与就地校验的结论相同；没有变化时为 None。对象与数组在访问到其中的叶子时再处理
+fn adjusted_leaf(val: &Value, rule: &FieldRule, ctx: &ValidationContext) -> Option<Value> {
+    if !ctx.adjust_leaves || !matches!(val, Value::String(_) | Value::Int(_)) {
+        return None;
+    }
+    if !adjusts_leaf(rule, ctx) {
+        return None;
+    }
+    let mut leaf = val.clone();
+    let mut changes = Vec::new();
+    adjust_leaf(&mut leaf, rule, Path::Root, ctx, &mut changes);
+    (!changes.is_empty()).then_some(leaf)
+}
+
+/// 这一字段的叶子在 `prepare` 中可能被 coerce 或转换
+pub(crate) fn adjusts_leaf(rule: &FieldRule, ctx: &ValidationContext) -> bool {
+    ctx.options.coerce || transforms(rule)
+}
+
+/// 对一个字符串或整数叶子做 `prepare` 中的 coerce 与转换，修改记入 `changes`
+pub(crate) fn adjust_leaf(
+    leaf: &mut Value,
+    rule: &FieldRule,
+    path: Path,
+    ctx: &ValidationContext,
+    changes: &mut Vec<Change>,
+) {
+    if ctx.options.coerce {
+        coerce_value(leaf, rule, path, changes);
+    }
+    if transforms(rule) {
+        transform_value(leaf, rule, path, ctx, changes);
+    }
+}
+
+/// 规则自身带有转换（不看嵌套规则）；密文不改写
+fn transforms(rule: &FieldRule) -> bool {
+    rule.encryption().is_none() && rule.has_constraint(|c| matches!(c, Constraint::Transform(_)))
+}
+
+/// 每个值在校验前计入预算，并检查嵌套深度
+pub(crate) fn enter(rule: &FieldRule, ctx: &ValidationContext, depth: usize) -> Result<()> {
+    if let Some(limit) = budget::charge() {
+        return Err(ValidationError::BudgetExceeded {
+            field: rule.field.to_string(),
+            limit,
+        });
+    }
+    let limit = ctx.options.max_depth;
+    if limit > 0 && depth > limit {
+        return Err(ValidationError::MaxDepthExceeded {
+            field: rule.field.to_string(),
+            limit,
+        });
+    }
+    Ok(())
+}
+
+fn step<'a>(task: Task<'a>, stack: &mut Vec<Task<'a>>, ctx: &ValidationContext) -> Result<()> {
+    match task {
+        Task::Fields {
+            obj,
+            rules,
+            compiled,
+            next,
+            scope,
+            depth,
+        } => {
+            let Some(rule) = rules.get(next) else {
+                return Ok(());
+            };
+            stack.push(Task::Fields {
+                obj,
+                rules,
+                compiled,
+                next: next + 1,
+                scope,
+                depth,
+            });
+            if let Some(path) = scope {
+                stack.push(Task::Exit {
+                    scope: Scope::Field(path),
+                    marks: (timing::mark(), warning::mark()),
+                });
+            }
+            let compiled = compiled.and_then(|c| c.get(next));
+            // script 约束可以访问同级字段
+            let (val, parent) = match obj {
+                Value::Object(map) => (
+                    map.get(rule.field.as_str())
+                        .map(|val| leaf(val, rule, ctx))
+                        .or_else(|| default_value(rule, ctx)),
+                    Some(obj),
+                ),
+                _ => (Some(leaf(obj, rule, ctx)), None),
+            };
+            match val {
+                Some(Cow::Borrowed(val)) => stack.push(Task::Value {
+                    val,
+                    rule,
+                    compiled,
+                    parent,
+                    depth,
+                }),
+                // 未填入的默认值与只读校验中转换后的值只在这里存在，就地校验完
+                Some(Cow::Owned(val)) => run(
+                    Task::Value {
+                        val: &val,
+                        rule,
+                        compiled,
+                        parent,
+                        depth,
+                    },
+                    ctx,
+                )?,
+                None if rule.required || rule.must_be_present() => {
+                    return Err(ValidationError::MissingField(rule.field.to_string()));
                 }
+                None => {}
+            }
+        }
+        Task::Elements {
+            items,
+            rule,
+            compiled,
+            next,
+            field,
+            depth,
+        } => {
+            let Some(val) = items.get(next) else {
+                return Ok(());
             };
-            let max_v = match max {
-                Value::Int(mi) => *mi as f64,
-                Value::Float(mf) => mf.floor(),
+            stack.push(Task::Elements {
+                items,
+                rule,
+                compiled,
+                next: next + 1,
+                field,
+                depth,
+            });
+            stack.push(Task::Exit {
+                scope: Scope::Element(field, next),
+                marks: (timing::mark(), warning::mark()),
+            });
+            match leaf(val, rule, ctx) {
+                Cow::Borrowed(val) => stack.push(Task::Value {
+                    val,
+                    rule,
+                    compiled,
+                    parent: None,
+                    depth,
+                }),
+                Cow::Owned(val) => run(
+                    Task::Value {
+                        val: &val,
+                        rule,
+                        compiled,
+                        parent: None,
+                        depth,
+                    },
+                    ctx,
+                )?,
+            }
+        }
+        Task::Value {
+            val,
+            rule,
+            compiled,
+            parent,
+            depth,
+        } => {
+            enter(rule, ctx, depth)?;
+            // 明文只在这里存在，就地校验完
+            if let Some(name) = rule.encryption() {
+                let Some(plain) = decrypt(val, name, rule, ctx)? else {
+                    return Ok(());
+                };
+                if ctx.options.strict
+                    && let Some(children) = &rule.children
+                {
+                    reject_unknown_fields(&plain, children, Path::Root)
+                        .map_err(|e| e.within(&rule.field))?;
+                }
+                // 错误与警告不能带出明文：值换成占位符，含有明文片段的说明整段换掉
+                let secrets = secrets_of(&plain);
+                let mark = warning::mark();
+                let mut local = Vec::new();
+                let result = expand(&plain, rule, compiled, parent, depth, &mut local, ctx)
+                    .and_then(|_| drain(&mut local, ctx));
+                warning::map_since(mark, |w| w.redact(&secrets));
+                return result.map_err(|mut e| {
+                    e.redact(&secrets);
+                    e
+                });
+            }
+            expand(val, rule, compiled, parent, depth, stack, ctx)?;
+        }
+        // 其间没有新的计时或警告时不必拼出路径
+        Task::Exit { scope, marks } => {
+            if marks != (timing::mark(), warning::mark()) {
+                let path = scope.path();
+                timing::within(marks.0, &path);
+                warning::within(marks.1, &path);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 校验值本身，再把 sub_rule / children 压栈，sub_rule 先于 children 校验
+fn expand<'a>(
+    val: &'a Value,
+    rule: &'a FieldRule,
+    compiled: Option<&'a CompiledRule>,
+    parent: Option<&'a Value>,
+    depth: usize,
+    stack: &mut Vec<Task<'a>>,
+    ctx: &ValidationContext,
+) -> Result<()> {
+    check_value(val, rule, compiled, ctx, parent)?;
+    if let Some(children) = &rule.children {
+        let Value::Object(_) = val else {
+            return Err(ValidationError::NotAnObject(rule.field.to_string()));
+        };
+        stack.push(Task::Fields {
+            obj: val,
+            rules: children,
+            compiled: compiled.map(|c| c.children.as_slice()),
+            next: 0,
+            scope: Some(&rule.field),
+            depth: depth + 1,
+        });
+    }
+    if let Some(sub_rule) = &rule.rule {
+        let sub_compiled = compiled.and_then(|c| c.sub.as_deref());
+        match val {
+            Value::Object(_) => stack.push(Task::Fields {
+                obj: val,
+                rules: std::slice::from_ref(sub_rule.as_ref()),
+                compiled: sub_compiled.map(std::slice::from_ref),
+                next: 0,
+                scope: None,
+                depth: depth + 1,
+            }),
+            Value::Array(items) => stack.push(Task::Elements {
+                items,
+                rule: sub_rule,
+                compiled: sub_compiled,
+                next: 0,
+                field: &rule.field,
+                depth: depth + 1,
+            }),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// 把 `encrypted(name)` 字段的密文交给同名的解密器：字符串类的类型直接校验明文，
+/// 其余类型（对象、数组、数字等）的明文按 JSON 解析。可选字段的空字符串与未加密时一样跳过
+fn decrypt(
+    val: &Value,
+    name: &str,
+    rule: &FieldRule,
+    ctx: &ValidationContext,
+) -> Result<Option<Value>> {
+    let Value::String(ciphertext) = val else {
+        return Err(mismatch(val, &FieldType::String).within(&rule.field));
+    };
+    if !rule.required && ciphertext.is_empty() {
+        return Ok(None);
+    }
+    let decryptor = ctx
+        .registry
+        .decryptor(name)
+        .ok_or_else(|| ValidationError::Custom(format!("Unknown decryptor {}", name)))?;
+    let failed = |message: String| ValidationError::DecryptionFailed {
+        field: rule.field.to_string(),
+        decryptor: name.to_string(),
+        message,
+    };
+    let plain = decryptor.decrypt(ciphertext).map_err(failed)?;
+    if rule.field_type.is_textual() && rule.union_types.is_none() {
+        return Ok(Some(Value::String(plain)));
+    }
+    match serde_json::from_str::<serde_json::Value>(&plain) {
+        Ok(json) => Ok(Some(Value::from(json))),
+        Err(e) => Err(failed(format!("plaintext is not valid JSON: {}", e))),
+    }
+}
+
+/// 加密字段的错误中替代明文内容的占位符
+const REDACTED: &str = "<redacted>";
+
+/// 明文中的全部标量（字符串的内容与数字、bool 的文本），用于在错误说明中找出明文片段
+fn secrets_of(plain: &Value) -> Vec<String> {
+    let mut secrets = Vec::new();
+    let mut pending = vec![plain];
+    while let Some(v) = pending.pop() {
+        match v {
+            Value::Object(map) => pending.extend(map.values()),
+            Value::Array(items) => pending.extend(items),
+            Value::String(s) if s.is_empty() => {}
+            Value::String(s) => secrets.push(s.clone()),
+            other => secrets.push(other.to_string()),
+        }
+    }
+    secrets
+}
+
+impl ValidationError {
+    /// 去掉来自加密字段明文的内容：携带值的部分换成占位符，
+    /// 说明文本中出现明文片段时整段换成占位符；路径、约束与错误代码保持不变
+    fn redact(&mut self, secrets: &[String]) {
+        let scrub = |text: &mut String| {
+            if secrets.iter().any(|s| text.contains(s.as_str())) {
+                *text = REDACTED.to_string();
+            }
+        };
+        match self {
+            Self::TypeMismatch { value, reason, .. } => {
+                *value = REDACTED.to_string();
+                if let Some(reason) = reason {
+                    scrub(reason);
+                }
+            }
+            Self::UnionTypeMismatch { value, .. }
+            | Self::EnumMismatch { value, .. }
+            | Self::RangeError { value, .. }
+            | Self::TimeError { value, .. }
+            | Self::DuplicateKey { value, .. }
+            | Self::DanglingRef { value, .. }
+            | Self::NotMultipleOf { value, .. }
+            | Self::OuiMismatch { oui: value, .. }
+            | Self::AggregateError { actual: value, .. } => *value = REDACTED.to_string(),
+            Self::SequenceViolation {
+                previous, value, ..
+            } => {
+                *previous = REDACTED.to_string();
+                *value = REDACTED.to_string();
+            }
+            Self::CheckFailed { message, .. }
+            | Self::ScriptFailed { message, .. }
+            | Self::DecryptionFailed { message, .. }
+            | Self::Deprecated {
+                message: Some(message),
+                ..
+            }
+            | Self::InvalidRegex(message)
+            | Self::Custom(message) => scrub(message),
+            Self::FieldErrors { errors, .. } => {
+                for e in errors {
+                    e.redact(secrets);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 校验已经定位到的字段值（含嵌套的字段与数组元素）；数组元素等无名规则直接走这里
+pub(crate) fn validate_value(
+    val: &Value,
+    rule: &FieldRule,
+    compiled: Option<&CompiledRule>,
+    ctx: &ValidationContext,
+    parent: Option<&Value>,
+) -> Result<()> {
+    validate_at(val, rule, compiled, ctx, parent, 1)
+}
+
+/// 与 [`validate_value`] 相同，嵌套深度从第 `depth` 层计起
+pub(crate) fn validate_at(
+    val: &Value,
+    rule: &FieldRule,
+    compiled: Option<&CompiledRule>,
+    ctx: &ValidationContext,
+    parent: Option<&Value>,
+    depth: usize,
+) -> Result<()> {
+    run(
+        Task::Value {
+            val,
+            rule,
+            compiled,
+            parent,
+            depth,
+        },
+        ctx,
+    )
+}
+
+/// 校验已经定位到的对象 `obj` 中 `rules` 的字段，嵌套深度从第 `depth` 层计起
+#[cfg(feature = "json-value")]
+pub(crate) fn validate_fields(
+    obj: &Value,
+    rules: &[FieldRule],
+    compiled: Option<&[CompiledRule]>,
+    ctx: &ValidationContext,
+    depth: usize,
+) -> Result<()> {
+    run(
+        Task::Fields {
+            obj,
+            rules,
+            compiled,
+            next: 0,
+            scope: None,
+            depth,
+        },
+        ctx,
+    )
+}
+
+/// 值本身的类型、enum 与约束，不含嵌套的字段与数组元素
+fn check_value(
+    val: &Value,
+    rule: &FieldRule,
+    compiled: Option<&CompiledRule>,
+    ctx: &ValidationContext,
+    parent: Option<&Value>,
+) -> Result<()> {
+    if rule.must_be_absent() {
+        return Err(ValidationError::ForbiddenField(rule.field.to_string()));
+    }
+    if !rule.required
+        && let Value::String(s) = val
+        && s.is_empty()
+    {
+        return Ok(());
+    }
+
+    // union types 验证
+    if let Some(types) = &rule.union_types {
+        let ok = match compiled {
+            Some(c) => c
+                .union_order
+                .iter()
+                .any(|&i| validate_type(val, &types[i]).is_ok()),
+            None => types.iter().any(|t| validate_type(val, t).is_ok()),
+        };
+        if !ok {
+            return Err(ValidationError::UnionTypeMismatch {
+                field: rule.field.to_string(),
+                value: format!("{:?}", val),
+                types: types.clone(),
+            });
+        }
+    } else {
+        let pattern = compiled.and_then(|c| c.pattern.as_ref());
+        validate_type_with(val, &rule.field_type, pattern).map_err(|e| e.within(&rule.field))?;
+    }
+
+    // enum 与 constraints 验证：默认遇到第一个失败即返回，exhaustive 模式下收集全部失败
+    let mut failures = Failures::new(&ctx.options);
+    let epsilon = ctx.options.float_epsilon;
+    if let Some(enum_vals) = &rule.enum_values
+        && !timing::measure(
+            &rule.field,
+            || "enum".into(),
+            || match val {
+                // 有容差时 float 无法哈希查找，逐个比较
+                Value::Float(f) if epsilon > 0.0 => enum_vals
+                    .iter()
+                    .any(|v| v.as_float().is_some_and(|e| (e - *f).abs() <= epsilon)),
+                _ => compiled.map_or_else(
+                    || enum_vals.contains(val),
+                    |c| c.enum_contains(enum_vals, val),
+                ),
+            },
+        )
+    {
+        failures.push(ValidationError::EnumMismatch {
+            field: rule.field.to_string(),
+            value: format!("{:?}", val),
+            expected: enum_vals.clone(),
+        })?;
+    }
+    if let Some(c) = &rule.constraints {
+        for (i, con) in c.items.iter().enumerate() {
+            if failures.truncated {
+                break;
+            }
+            let result = timing::measure(
+                &rule.field,
+                || con.to_string(),
+                || {
+                    if let (Constraint::Regex(pattern), Some(re)) =
+                        (con, compiled.and_then(|c| c.regex(i)))
+                    {
+                        return match_regex(val, re, pattern, &rule.field);
+                    }
+                    let folded = compiled
+                        .and_then(|c| c.ranges.get(i)?.as_ref())
+                        .and_then(|range| range.check(val, &rule.field));
+                    folded
+                        .unwrap_or_else(|| validate_constraint(val, con, &rule.field, ctx, parent))
+                },
+            );
+            if let Err(e) = result {
+                failures.push(e)?;
+            }
+        }
+    }
+    failures.finish(&rule.field)?;
+    Ok(())
+}
+
+pub(crate) fn validate_constraint(
+    val: &Value,
+    con: &Constraint,
+    field_name: &str,
+    ctx: &ValidationContext,
+    parent: Option<&Value>,
+) -> Result<()> {
+    match con {
+        Constraint::Range {
+            min,
+            max,
+            min_inclusive,
+            max_inclusive,
+        } => validate_range(val, min, max, *min_inclusive, *max_inclusive, field_name),
+        Constraint::Regex(pattern) => {
+            match_regex(val, &cached_regex(pattern)?, pattern, field_name)
+        }
+        Constraint::Check(name) => {
+            let validator = ctx
+                .registry
+                .get(name)
+                .ok_or_else(|| ValidationError::CheckFailed {
+                    field: field_name.to_string(),
+                    check: name.clone(),
+                    message: "validator not registered".into(),
+                })?;
+            validator
+                .check(val)
+                .map_err(|message| ValidationError::CheckFailed {
+                    field: field_name.to_string(),
+                    check: name.clone(),
+                    message,
+                })
+        }
+        Constraint::Checksum {
+            algorithm,
+            expected,
+        } => {
+            let input = match val {
+                Value::String(s) => s.clone(),
+                Value::Int(i) => i.to_string(),
+                #[cfg(feature = "bigint")]
+                Value::BigInt(b) => b.to_string(),
                 _ => {
                     return Err(ValidationError::Custom(format!(
-                        "Invalid max value type for {}",
-                        field
+                        "{} not string or int for checksum",
+                        field_name
                     )));
                 }
             };
+            let checksum = ctx.registry.checksum(algorithm).ok_or_else(|| {
+                ValidationError::Custom(format!("Unknown checksum algorithm {}", algorithm))
+            })?;
+            if checksum.verify(&input, expected.as_deref()) {
+                Ok(())
+            } else {
+                Err(ValidationError::ChecksumMismatch {
+                    field: field_name.to_string(),
+                    algorithm: algorithm.clone(),
+                })
+            }
+        }
+        Constraint::SignedWith {
+            algorithm,
+            key,
+            over,
+        } => validate_signature(val, algorithm, key, over, field_name, ctx, parent),
+        Constraint::Sorted {
+            descending,
+            strict,
+            collation,
+        } => validate_sorted(val, *descending, *strict, collation, con, field_name),
+        Constraint::ArrayAggregate { aggregate, bound } => validate_aggregate(
+            val,
+            aggregate,
+            bound,
+            con,
+            field_name,
+            ctx.options.float_epsilon,
+        ),
+        Constraint::UniqueBy(path) => validate_unique_by(val, path, field_name),
+        Constraint::Ref(path) => {
+            if ctx.refs.contains(path, val) {
+                Ok(())
+            } else {
+                Err(ValidationError::DanglingRef {
+                    field: field_name.to_string(),
+                    value: val.to_string(),
+                    target: path.clone(),
+                })
+            }
+        }
+        Constraint::Exists(kind) => {
+            let resolver = ctx.resolver.as_ref().ok_or_else(|| {
+                ValidationError::Custom(format!("{} requires a RefResolver", con))
+            })?;
+            if resolver.exists(kind, val) {
+                Ok(())
+            } else {
+                Err(ValidationError::DanglingRef {
+                    field: field_name.to_string(),
+                    value: val.to_string(),
+                    target: kind.clone(),
+                })
+            }
+        }
+        Constraint::Oui(prefixes) => {
+            let s = val.as_str().ok_or_else(|| {
+                ValidationError::Custom(format!("{} not string for {}", field_name, con))
+            })?;
+            let hex: String = s
+                .chars()
+                .filter(|c| c.is_ascii_hexdigit())
+                .map(|c| c.to_ascii_uppercase())
+                .collect();
+            let oui = hex.get(..6).unwrap_or(&hex);
+            if prefixes.iter().any(|p| oui_hex(p).as_deref() == Some(oui)) {
+                Ok(())
+            } else {
+                Err(ValidationError::OuiMismatch {
+                    field: field_name.to_string(),
+                    oui: oui.to_string(),
+                })
+            }
+        }
+        Constraint::MultipleOf(divisor) => {
+            validate_multiple_of(val, divisor, con, field_name, ctx.options.float_epsilon)
+        }
+        Constraint::Currency(codes) => match money::currency_of(val) {
+            Some(currency) if !codes.iter().any(|c| c == currency) => {
+                Err(ValidationError::EnumMismatch {
+                    field: join_path(field_name, "currency"),
+                    value: format!("{:?}", Value::String(currency.to_string())),
+                    expected: codes.iter().cloned().map(Value::String).collect(),
+                })
+            }
+            _ => Ok(()),
+        },
+        // 不在这里检查：定位字段时缺少 `present` 字段报 MissingField；`check_value` 一开始
+        // 就拒绝出现的 `absent` 字段，可选字段的空字符串也不例外；`=env(...)` 等默认值在填充默认值时求值
+        Constraint::Present | Constraint::Absent | Constraint::DefaultFrom(_) => Ok(()),
+        // 只用于错误消息
+        Constraint::Label(_) => Ok(()),
+        // 校验引擎定位到值后先行解密，见 `decrypt`
+        Constraint::Encrypted(_) => Ok(()),
+        // 校验通过后在 `finish` 中计算
+        Constraint::HashIntoReport(_) => Ok(()),
+        // 在校验前的预处理中改写；号码无法改写时保持原样，在这里报告原因
+        Constraint::Transform(Transform::NormalizePhone { region }) => match val {
+            Value::String(s) => match normalize_phone(s, region) {
+                Ok(normalized) if normalized == *s => Ok(()),
+                Ok(normalized) => Err(phone_error(
+                    s,
+                    field_name,
+                    format!("not in E.164 form (expected {})", normalized),
+                )),
+                Err(reason) => Err(phone_error(s, field_name, reason)),
+            },
+            _ => Ok(()),
+        },
+        Constraint::Transform(Transform::Slugify) => Ok(()),
+        // 只产生警告，校验结论不变
+        Constraint::Deprecated(message) => {
+            warning::record(ValidationError::Deprecated {
+                field: field_name.to_string(),
+                message: message.clone(),
+            });
+            Ok(())
+        }
+        Constraint::Warn(inner) => {
+            if let Err(e) = validate_constraint(val, inner, field_name, ctx, parent) {
+                warning::record(e);
+            }
+            Ok(())
+        }
+        Constraint::Each(cmp) | Constraint::Adjacent(cmp) => {
+            validate_element_compare(val, cmp, con, field_name, ctx.options.float_epsilon)
+        }
+        Constraint::Past | Constraint::Future | Constraint::Within(_) | Constraint::MinAge(_) => {
+            validate_time(val, con, field_name, ctx.clock.now())
+        }
+        #[cfg(feature = "script")]
+        Constraint::Script(source) => match crate::script::eval(source, val, parent) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(ValidationError::ScriptFailed {
+                field: field_name.to_string(),
+                script: source.clone(),
+                message: "returned false".into(),
+            }),
+            Err(message) => Err(ValidationError::ScriptFailed {
+                field: field_name.to_string(),
+                script: source.clone(),
+                message,
+            }),
+        },
+        #[cfg(not(feature = "script"))]
+        Constraint::Script(source) => {
+            let _ = parent;
+            Err(ValidationError::ScriptFailed {
+                field: field_name.to_string(),
+                script: source.clone(),
+                message: "script constraints require the `script` feature".into(),
+            })
+        }
+    }
+}
+
+/// 单次遍历检查相邻元素的顺序
+fn validate_sorted(
+    val: &Value,
+    descending: bool,
+    strict: bool,
+    collation: &Collation,
+    con: &Constraint,
+    field_name: &str,
+) -> Result<()> {
+    let items = val
+        .as_array()
+        .ok_or_else(|| ValidationError::Custom(format!("{} not array for {}", field_name, con)))?;
+    for (i, pair) in items.windows(2).enumerate() {
+        let ord = compare_ordered(&pair[0], &pair[1], collation)?.ok_or_else(|| {
+            ValidationError::Custom(format!(
+                "{}[{}] is not comparable for {}",
+                field_name,
+                i + 1,
+                con
+            ))
+        })?;
+        let ord = if descending { ord.reverse() } else { ord };
+        let ok = match ord {
+            std::cmp::Ordering::Less => true,
+            std::cmp::Ordering::Equal => !strict,
+            std::cmp::Ordering::Greater => false,
+        };
+        if !ok {
+            return Err(ValidationError::NotSorted {
+                field: field_name.to_string(),
+                index: i + 1,
+                order: con.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// `normalize_phone` 无法把值改写为 E.164
+fn phone_error(value: &str, field: &str, reason: String) -> ValidationError {
+    ValidationError::TypeMismatch {
+        field: field.to_string(),
+        value: value.to_string(),
+        expected: FieldType::Phone,
+        actual: "string",
+        reason: Some(reason),
+    }
+}
+
+/// int 之间精确取余；涉及 float 时取最接近的整数倍，误差不超过 `epsilon` 即可
+fn validate_multiple_of(
+    val: &Value,
+    divisor: &Value,
+    con: &Constraint,
+    field_name: &str,
+    epsilon: f64,
+) -> Result<()> {
+    let ok = match (val, divisor) {
+        // 除数非零时取余只会在 i64::MIN % -1 溢出，此时显然是整数倍
+        (Value::Int(v), Value::Int(d)) => *d != 0 && v.checked_rem(*d).is_none_or(|r| r == 0),
+        _ => {
+            let (v, d) = number_of(val).zip(number_of(divisor)).ok_or_else(|| {
+                ValidationError::Custom(format!("{} not number for {}", field_name, con))
+            })?;
+            (v - (v / d).round() * d).abs() <= epsilon
+        }
+    };
+    if ok {
+        Ok(())
+    } else {
+        Err(ValidationError::NotMultipleOf {
+            field: field_name.to_string(),
+            value: val.to_string(),
+            divisor: divisor.to_string(),
+        })
+    }
+}
+
+/// 数值数组的聚合约束；空数组的 avg / min / max 不校验
+fn validate_aggregate(
+    val: &Value,
+    aggregate: &Aggregate,
+    bound: &AggregateBound,
+    con: &Constraint,
+    field_name: &str,
+    epsilon: f64,
+) -> Result<()> {
+    let items = val
+        .as_array()
+        .ok_or_else(|| ValidationError::Custom(format!("{} not array for {}", field_name, con)))?;
+    let mut numbers = Vec::with_capacity(items.len());
+    for (i, item) in items.iter().enumerate() {
+        match number_of(item) {
+            Some(n) => numbers.push(n),
+            None => {
+                return Err(ValidationError::Custom(format!(
+                    "{}[{}] is not a number for {}",
+                    field_name, i, con
+                )));
+            }
+        }
+    }
+
+    let actual = match aggregate {
+        Aggregate::Sum => numbers.iter().sum(),
+        Aggregate::Count => numbers.len() as f64,
+        Aggregate::CountWhere(op, v) => {
+            let rhs = number_of(v).unwrap_or(f64::NAN);
+            numbers
+                .iter()
+                .filter(|n| op.apply_within(**n, rhs, epsilon))
+                .count() as f64
+        }
+        _ if numbers.is_empty() => return Ok(()),
+        Aggregate::Avg => numbers.iter().sum::<f64>() / numbers.len() as f64,
+        Aggregate::Min => numbers.iter().copied().fold(f64::INFINITY, f64::min),
+        Aggregate::Max => numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+    };
+
+    let ok = match bound {
+        AggregateBound::Compare(op, v) => {
+            op.apply_within(actual, number_of(v).unwrap_or(f64::NAN), epsilon)
+        }
+        AggregateBound::Range {
+            min,
+            max,
+            min_inclusive,
+            max_inclusive,
+        } => {
+            let (lo, hi) = (
+                number_of(min).unwrap_or(f64::NAN),
+                number_of(max).unwrap_or(f64::NAN),
+            );
+            let lower = if *min_inclusive {
+                CompareOp::Ge
+            } else {
+                CompareOp::Gt
+            };
+            let upper = if *max_inclusive {
+                CompareOp::Le
+            } else {
+                CompareOp::Lt
+            };
+            lower.apply_within(actual, lo, epsilon) && upper.apply_within(actual, hi, epsilon)
+        }
+    };
+    if ok {
+        Ok(())
+    } else {
+        Err(ValidationError::AggregateError {
+            field: field_name.to_string(),
+            constraint: con.to_string(),
+            actual: actual.to_string(),
+        })
+    }
+}
+
+pub(crate) fn number_of(v: &Value) -> Option<f64> {
+    match v {
+        Value::Int(i) => Some(*i as f64),
+        #[cfg(feature = "bigint")]
+        Value::BigInt(b) => num_traits::ToPrimitive::to_f64(b),
+        Value::Float(f) => Some(*f),
+        _ => None,
+    }
+}
+
+/// 在本字段所在的对象中取出 `over` 指向的子树，按 `key` 取得密钥后重新计算签名
+fn validate_signature(
+    val: &Value,
+    algorithm: &str,
+    key: &str,
+    over: &str,
+    field_name: &str,
+    ctx: &ValidationContext,
+    parent: Option<&Value>,
+) -> Result<()> {
+    let signature = val.as_str().ok_or_else(|| {
+        ValidationError::Custom(format!("{} not string for signed_with", field_name))
+    })?;
+    let secret = match key.split_once(':') {
+        Some(("ctx", name)) => ctx
+            .vars
+            .get(name)
+            .and_then(|v| v.as_str().map(str::to_string)),
+        Some(("env", name)) => std::env::var(name).ok(),
+        _ => None,
+    }
+    .ok_or_else(|| ValidationError::Custom(format!("Signing key {} is not set", key)))?;
+    let mut signed = parent;
+    for segment in over.trim_start_matches("$.").split('.') {
+        signed = signed
+            .and_then(Value::as_object)
+            .and_then(|obj| obj.get(segment));
+    }
+    // 被签名的子树不存在时同样视为签名不符
+    let verified = match signed.map(Value::to_canonical_json).transpose() {
+        Ok(Some(message)) => {
+            signature::verify(algorithm, secret.as_bytes(), message.as_bytes(), signature)
+        }
+        Ok(None) => false,
+        Err(e) => return Err(ValidationError::Custom(e)),
+    };
+    if verified {
+        Ok(())
+    } else {
+        Err(ValidationError::SignatureMismatch {
+            field: field_name.to_string(),
+            algorithm: algorithm.to_string(),
+        })
+    }
+}
+
+/// 对象数组中子字段的值互不相同；缺少该子字段的元素不参与比较
+fn validate_unique_by(val: &Value, path: &str, field_name: &str) -> Result<()> {
+    let items = val.as_array().ok_or_else(|| {
+        ValidationError::Custom(format!("{} not array for unique_by({})", field_name, path))
+    })?;
+    let keys: Vec<Option<String>> = items
+        .iter()
+        .map(|item| {
+            path.split('.')
+                .try_fold(item, |cur, key| cur.as_object()?.get(key))
+                .map(|v| v.to_string())
+        })
+        .collect();
+
+    // 按首次出现的顺序分组，报告第一个出现重复的值及其全部下标
+    let mut groups: Vec<(&str, Vec<usize>)> = Vec::new();
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+    for (i, key) in keys.iter().enumerate() {
+        let Some(key) = key else { continue };
+        match positions.get(key.as_str()) {
+            Some(&g) => groups[g].1.push(i),
+            None => {
+                positions.insert(key, groups.len());
+                groups.push((key, vec![i]));
+            }
+        }
+    }
+    match groups.into_iter().find(|(_, indices)| indices.len() > 1) {
+        Some((value, indices)) => Err(ValidationError::DuplicateKey {
+            field: field_name.to_string(),
+            key: path.to_string(),
+            value: value.to_string(),
+            indices,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// each / adjacent 约束；缺少的字段视为不参与比较。adjacent 报告的下标为 `next` 元素
+fn validate_element_compare(
+    val: &Value,
+    cmp: &FieldCompare,
+    con: &Constraint,
+    field_name: &str,
+    epsilon: f64,
+) -> Result<()> {
+    let items = val
+        .as_array()
+        .ok_or_else(|| ValidationError::Custom(format!("{} not array for {}", field_name, con)))?;
+    let adjacent = matches!(con, Constraint::Adjacent(_));
+    let start = if adjacent { 1 } else { 0 };
+
+    for index in start..items.len() {
+        let resolve = |operand: &'_ Operand| -> Option<Value> {
+            match operand {
+                Operand::Literal(v) => Some(v.clone()),
+                Operand::Field(path) => {
+                    let (item, path) = match path.split_once('.') {
+                        Some(("prev", rest)) if adjacent => (&items[index - 1], rest),
+                        Some(("next", rest)) if adjacent => (&items[index], rest),
+                        _ => (&items[index], path.as_str()),
+                    };
+                    path.split('.')
+                        .try_fold(item, |cur, key| cur.as_object()?.get(key))
+                        .cloned()
+                }
+            }
+        };
+        let (Some(left), Some(right)) = (resolve(&cmp.left), resolve(&cmp.right)) else {
+            continue;
+        };
+        let within = match (&left, &right) {
+            (Value::Float(_), _) | (_, Value::Float(_)) => number_of(&left)
+                .zip(number_of(&right))
+                .is_some_and(|(l, r)| (l - r).abs() <= epsilon),
+            _ => false,
+        };
+        let ok = match compare_ordered(&left, &right, &cmp.collation)? {
+            // 相差不超过 float_epsilon 的数值视为相等
+            Some(_) if within => cmp.op.holds(std::cmp::Ordering::Equal),
+            Some(ord) => cmp.op.holds(ord),
+            None if cmp.op == CompareOp::Eq => left == right,
+            None if cmp.op == CompareOp::Ne => left != right,
+            None => {
+                return Err(ValidationError::Custom(format!(
+                    "{}[{}] is not comparable for {}",
+                    field_name, index, con
+                )));
+            }
+        };
+        if !ok {
+            return Err(ValidationError::ElementConstraint {
+                field: field_name.to_string(),
+                index,
+                constraint: con.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// 数字之间按数值比较（与 `Value` 的排序一致），date / datetime 字符串按时刻比较，
+/// 其余字符串按 `collation` 比较
+fn compare_ordered(
+    a: &Value,
+    b: &Value,
+    collation: &Collation,
+) -> Result<Option<std::cmp::Ordering>> {
+    Ok(match (a, b) {
+        _ if a.is_number() && b.is_number() => compare_numbers(a, b),
+        (Value::String(x), Value::String(y)) => match (instant_of(a), instant_of(b)) {
+            (Some((x, _)), Some((y, _))) => Some(x.cmp(&y)),
+            _ => Some(collation::compare(collation, x, y).map_err(ValidationError::Custom)?),
+        },
+        _ => None,
+    })
+}
+
+/// past / future / within / min_age 约束；date 只精确到天，与当天零点比较
+fn validate_time(val: &Value, con: &Constraint, field_name: &str, now: i64) -> Result<()> {
+    let (instant, date_only) = instant_of(val).ok_or_else(|| {
+        ValidationError::Custom(format!(
+            "{} is not a date, datetime or timestamp",
+            field_name
+        ))
+    })?;
+    let now = if date_only {
+        now.div_euclid(86400) * 86400
+    } else {
+        now
+    };
+    let ok = match con {
+        Constraint::Past => instant < now,
+        Constraint::Future => instant > now,
+        Constraint::Within(seconds) => instant.abs_diff(now) <= seconds.unsigned_abs(),
+        Constraint::MinAge(years) => age_at(instant, now) >= *years as i64,
+        _ => true,
+    };
+    if ok {
+        Ok(())
+    } else {
+        Err(ValidationError::TimeError {
+            field: field_name.to_string(),
+            value: format!("{:?}", val),
+            constraint: con.to_string(),
+        })
+    }
+}
+
+fn validate_range(
+    val: &Value,
+    min: &Value,
+    max: &Value,
+    min_inc: bool,
+    max_inc: bool,
+    field: &str,
+) -> Result<()> {
+    // 字符串边界也可能是长度，能解析为日期时才按时刻比较
+    if matches!(min, Value::String(_)) && instant_of(min).is_some() {
+        return validate_date_range(val, min, max, min_inc, max_inc, field);
+    }
+    #[cfg(feature = "bigint")]
+    if val.is_number()
+        && [val, min, max]
+            .iter()
+            .any(|v| matches!(v, Value::BigInt(_)))
+    {
+        return check_exact_bounds(val, min, max, min_inc, max_inc, field);
+    }
+    // money 的区间作用于 amount
+    if let Some((n, text)) = val.as_object().and_then(money::amount_of) {
+        let bound = |v: &Value| match v {
+            Value::Int(i) => Some(*i as f64),
+            Value::Float(f) => Some(*f),
+            _ => None,
+        };
+        let (Some(lo), Some(hi)) = (bound(min), bound(max)) else {
+            return Err(ValidationError::Custom(format!(
+                "Invalid money range for {}",
+                field
+            )));
+        };
+        return check_bounds(n, &text, (lo, hi), min_inc, max_inc, field);
+    }
+    match val {
+        Value::Int(i) => {
+            let n = *i as f64;
+            let min_v = match min {
+                Value::Int(mi) => *mi as f64,
+                Value::Float(mf) => mf.ceil(),
+                _ => {
+                    return Err(ValidationError::Custom(format!(
+                        "Invalid min value type for {}",
+                        field
+                    )));
+                }
+            };
+            let max_v = match max {
+                Value::Int(mi) => *mi as f64,
+                Value::Float(mf) => mf.floor(),
+                _ => {
+                    return Err(ValidationError::Custom(format!(
+                        "Invalid max value type for {}",
+                        field
+                    )));
+                }
+            };
+
+            check_bounds(n, i, (min_v, max_v), min_inc, max_inc, field)?;
+        }
+        Value::Float(f) => {
+            let n = *f;
+            let min_v = match min {
+                Value::Int(mi) => *mi as f64,
+                Value::Float(mf) => *mf,
+                _ => {
+                    return Err(ValidationError::Custom(format!(
+                        "Invalid min value type in range for {}",
+                        field
+                    )));
+                }
+            };
+            let max_v = match max {
+                Value::Int(mi) => *mi as f64,
+                Value::Float(mf) => *mf,
+                _ => {
+                    return Err(ValidationError::Custom(format!(
+                        "Invalid max value type in range for {}",
+                        field
+                    )));
+                }
+            };
+            check_bounds(n, f, (min_v, max_v), min_inc, max_inc, field)?;
+        }
+        Value::String(s) => validate_length(s.len(), min, max, min_inc, max_inc, field)?,
+        Value::Array(items) => validate_length(items.len(), min, max, min_inc, max_inc, field)?,
+        _ => {
+            return Err(ValidationError::Custom(format!(
+                "{} cannot apply range constraint to {:?}",
+                field, val
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// date / datetime 的区间按时刻比较，只精确到天的边界取当天 00:00
+fn validate_date_range(
+    val: &Value,
+    min: &Value,
+    max: &Value,
+    min_inc: bool,
+    max_inc: bool,
+    field: &str,
+) -> Result<()> {
+    let instant = |v: &Value| instant_of(v).map(|(t, _)| t);
+    let (Some(n), Some(lo), Some(hi)) = (instant(val), instant(min), instant(max)) else {
+        return Err(ValidationError::Custom(format!(
+            "{} cannot apply date range to {}",
+            field, val
+        )));
+    };
+    let text = |v: &Value| v.as_str().map_or_else(|| v.to_string(), str::to_string);
+    check_bounds(n, &n, (lo, hi), min_inc, max_inc, field).map_err(|_| {
+        ValidationError::RangeError {
+            field: field.to_string(),
+            value: text(val),
+            min: text(min),
+            max: text(max),
+            min_inclusive: min_inc,
+            max_inclusive: max_inc,
+            length: false,
+        }
+    })
+}
+
+/// 值或边界为 bigint 时按精确的数值比较检查区间
+#[cfg(feature = "bigint")]
+fn check_exact_bounds(
+    val: &Value,
+    min: &Value,
+    max: &Value,
+    min_inc: bool,
+    max_inc: bool,
+    field: &str,
+) -> Result<()> {
+    for (bound, label) in [(min, "min"), (max, "max")] {
+        if !bound.is_number() {
+            return Err(ValidationError::Custom(format!(
+                "Invalid {} value type in range for {}",
+                label, field
+            )));
+        }
+    }
+    let min_ok = compare_numbers(val, min).is_some_and(|o| o.is_gt() || (min_inc && o.is_eq()));
+    let max_ok = compare_numbers(val, max).is_some_and(|o| o.is_lt() || (max_inc && o.is_eq()));
+    if min_ok && max_ok {
+        Ok(())
+    } else {
+        Err(ValidationError::RangeError {
+            field: field.to_string(),
+            value: val.to_string(),
+            min: min.to_string(),
+            max: max.to_string(),
+            min_inclusive: min_inc,
+            max_inclusive: max_inc,
+            length: false,
+        })
+    }
+}
+
+/// 字符串长度与数组元素个数的区间
+fn validate_length(
+    n: usize,
+    min: &Value,
+    max: &Value,
+    min_inc: bool,
+    max_inc: bool,
+    field: &str,
+) -> Result<()> {
+    let min_v = parse_usize(min, field, "min")?;
+    let max_v = parse_usize(max, field, "max")?;
+    check_length(n, (min_v, max_v), min_inc, max_inc, field)
+}
+
+/// 长度的区间比较，报错为 `tags length 0 out of range [1, 10]`，括号与规则中的写法相同
+pub(crate) fn check_length(
+    n: usize,
+    bounds: (usize, usize),
+    min_inc: bool,
+    max_inc: bool,
+    field: &str,
+) -> Result<()> {
+    check_bounds(n, &n, bounds, min_inc, max_inc, field).map_err(|mut e| {
+        if let ValidationError::RangeError { length, .. } = &mut e {
+            *length = true;
+        }
+        e
+    })
+}
+
+/// 区间比较，`value` 为报错时展示的原值
+pub(crate) fn check_bounds<T: PartialOrd + fmt::Display>(
+    n: T,
+    value: &dyn fmt::Display,
+    (min, max): (T, T),
+    min_inc: bool,
+    max_inc: bool,
+    field: &str,
+) -> Result<()> {
+    let min_ok = if min_inc { n >= min } else { n > min };
+    let max_ok = if max_inc { n <= max } else { n < max };
+    if min_ok && max_ok {
+        Ok(())
+    } else {
+        Err(ValidationError::RangeError {
+            field: field.to_string(),
+            value: value.to_string(),
+            min: min.to_string(),
+            max: max.to_string(),
+            min_inclusive: min_inc,
+            max_inclusive: max_inc,
+            length: false,
+        })
+    }
+}
+
+fn parse_usize(val: &Value, field: &str, label: &str) -> Result<usize> {
+    match val {
+        Value::Int(i) => Ok(*i as usize),
+        Value::String(s) => s.parse::<usize>().map_err(|_| {
+            ValidationError::Custom(format!(
+                "Failed to parse '{}' as usize for {} {}",
+                s, field, label
+            ))
+        }),
+        _ => Err(ValidationError::Custom(format!(
+            "Invalid {} value type in range for {}",
+            label, field
+        ))),
+    }
+}
+
+fn validate_hostname(value: &Value, spec: &HostnameSpec, t: &FieldType) -> Result<()> {
+    let s = value.as_str().ok_or_else(|| mismatch(value, t))?;
+    check_hostname(s, spec)
+        .map_err(|reason| ValidationError::Custom(format!("Invalid hostname: {} ({})", s, reason)))
+}
+
+/// 默认只接受带连字符的标准形式；指定版本时同时检查 RFC 4122 变体位 (10xx)
+fn validate_uuid(value: &Value, spec: &UuidSpec, t: &FieldType) -> Result<()> {
+    let s = value.as_str().ok_or_else(|| mismatch(value, t))?;
+    if !(UUID_RE.is_match(s) || spec.compact && UUID_COMPACT_RE.is_match(s)) {
+        return Err(ValidationError::Custom(format!("Invalid uuid: {}", s)));
+    }
+    if let Some(version) = spec.version {
+        let hex: Vec<u8> = s.bytes().filter(|b| *b != b'-').collect();
+        let nibble = |i: usize| (hex[i] as char).to_digit(16).unwrap_or(0);
+        if nibble(12) != version as u32 {
+            return Err(ValidationError::Custom(format!(
+                "uuid {} is not version {}",
+                s, version
+            )));
+        }
+        if nibble(16) & 0b1100 != 0b1000 {
+            return Err(ValidationError::Custom(format!(
+                "uuid {} does not use the RFC 4122 variant",
+                s
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn validate_string_type(value: &Value, re: &Regex, t: &FieldType) -> Result<()> {
+    let s = value.as_str().ok_or_else(|| mismatch(value, t))?;
+    if re.is_match(s) {
+        Ok(())
+    } else {
+        Err(ValidationError::Custom(format!("Invalid {}: {}", t, s)))
+    }
+}
+
+/// 值的种类与声明的类型不符
+fn mismatch(value: &Value, t: &FieldType) -> ValidationError {
+    ValidationError::TypeMismatch {
+        field: "".into(),
+        value: value.to_string(),
+        expected: t.clone(),
+        actual: value.type_name(),
+        reason: None,
+    }
+}
+
+/// 检查值是否符合类型，失败时返回字段为空的 `TypeMismatch`：
+/// 种类不符时带上实际种类，种类相符但格式不对时在 `reason` 中说明
+pub fn validate_type(value: &Value, t: &FieldType) -> Result<()> {
+    validate_type_with(value, t, None)
+}
+
+/// 同 [`validate_type`]，`pattern` 为自定义正则类型预先编译好的正则
+fn validate_type_with(value: &Value, t: &FieldType, pattern: Option<&Regex>) -> Result<()> {
+    let checked = match pattern {
+        Some(re) => match_pattern(value, re, t),
+        None => check_type(value, t),
+    };
+    checked.map_err(|e| match e {
+        ValidationError::Custom(reason) => ValidationError::TypeMismatch {
+            field: "".into(),
+            value: value.to_string(),
+            expected: t.clone(),
+            actual: value.type_name(),
+            reason: Some(reason),
+        },
+        e => e,
+    })
+}
+
+fn check_type(value: &Value, t: &FieldType) -> Result<()> {
+    match t {
+        FieldType::String => value.as_str().map(|_| ()).ok_or_else(|| mismatch(value, t)),
+        FieldType::Int => value
+            .is_integer()
+            .then_some(())
+            .ok_or_else(|| mismatch(value, t)),
+        FieldType::Float => value
+            .as_float()
+            .map(|_| ())
+            .ok_or_else(|| mismatch(value, t)),
+        FieldType::Bool => value
+            .as_bool()
+            .map(|_| ())
+            .ok_or_else(|| mismatch(value, t)),
+        FieldType::Object => value
+            .as_object()
+            .map(|_| ())
+            .ok_or_else(|| mismatch(value, t)),
+        FieldType::Array => value
+            .as_array()
+            .map(|_| ())
+            .ok_or_else(|| mismatch(value, t)),
+        FieldType::Email => {
+            let s = value.as_str().ok_or_else(|| mismatch(value, t))?;
+            if !EMAIL_RE.is_match(s) {
+                return Err(ValidationError::Custom(format!("Invalid email: {}", s)));
+            }
+            Ok(())
+        }
+        FieldType::Uri => {
+            let s = value.as_str().ok_or_else(|| mismatch(value, t))?;
+            url::Url::parse(s)
+                .map(|_| ())
+                .map_err(|_| ValidationError::Custom(format!("{} is not a valid URI", s)))
+        }
+        FieldType::Uuid => validate_uuid(value, &UuidSpec::default(), t),
+        FieldType::UuidOf(spec) => validate_uuid(value, spec, t),
+        FieldType::Ip => {
+            let s = value.as_str().ok_or_else(|| mismatch(value, t))?;
+            if IP_V4_RE.is_match(s) || IP_V6_RE.is_match(s) {
+                Ok(())
+            } else {
+                Err(ValidationError::Custom(format!("Invalid ip: {}", s)))
+            }
+        }
+        FieldType::Mac => validate_string_type(value, &MAC_RE, t),
+        FieldType::Date => validate_string_type(value, &DATE_RE, t),
+        FieldType::DateTime => validate_string_type(value, &DATETIME_RE, t),
+        FieldType::Time => validate_string_type(value, &TIME_RE, t),
+        FieldType::Timestamp => value.as_int().map(|_| ()).ok_or_else(|| mismatch(value, t)),
+        FieldType::Color => validate_string_type(value, &COLOR_RE, t),
+        FieldType::ColorOf(spec) => {
+            let s = value.as_str().ok_or_else(|| mismatch(value, t))?;
+            if is_valid_color(s.trim(), spec) {
+                Ok(())
+            } else {
+                Err(ValidationError::Custom(format!("Invalid {}: {}", spec, s)))
+            }
+        }
+        FieldType::Hostname => validate_hostname(value, &HostnameSpec::default(), t),
+        FieldType::HostnameOf(spec) => validate_hostname(value, spec, t),
+        FieldType::Slug => validate_string_type(value, &SLUG_RE, t),
+        FieldType::Hex => validate_string_type(value, &HEX_RE, t),
+        FieldType::Base64 => validate_string_type(value, &BASE64_RE, t),
+        FieldType::Password | FieldType::Token => {
+            value.as_str().map(|_| ()).ok_or_else(|| mismatch(value, t))
+        }
+        FieldType::Phone => validate_string_type(value, &PHONE_RE, t),
+        FieldType::CreditCard => {
+            let s = value.as_str().ok_or_else(|| mismatch(value, t))?;
+            if !CREDITCARD_RE.is_match(s) {
+                return Err(ValidationError::Custom(format!(
+                    "Invalid creditcard: {}",
+                    s
+                )));
+            }
+            let digits: Vec<u32> = s.chars().filter_map(|c| c.to_digit(10)).collect();
+            if digits.len() < 13 || digits.len() > 19 {
+                return Err(ValidationError::Custom(format!(
+                    "Invalid creditcard length: {}",
+                    digits.len()
+                )));
+            }
+            if !luhn_valid(&digits) {
+                return Err(ValidationError::Custom(
+                    "Invalid creditcard checksum".to_string(),
+                ));
+            }
+            Ok(())
+        }
+        FieldType::ISBN => validate_string_type(value, &ISBN_RE, t),
+        FieldType::Port => {
+            let ok = match value {
+                Value::Int(i) => (1..=65535).contains(i),
+                Value::String(s) => is_port(s),
+                _ => return Err(mismatch(value, t)),
+            };
+            if ok {
+                Ok(())
+            } else {
+                Err(ValidationError::Custom(format!("Invalid port: {}", value)))
+            }
+        }
+        FieldType::SocketAddr | FieldType::IpPort => {
+            let s = value.as_str().ok_or_else(|| mismatch(value, t))?;
+            if is_socket_addr(s, *t == FieldType::IpPort) {
+                Ok(())
+            } else {
+                Err(ValidationError::Custom(format!("Invalid {}: {}", t, s)))
+            }
+        }
+        FieldType::Money => {
+            let obj = value.as_object().ok_or_else(|| mismatch(value, t))?;
+            money::check(obj).map_err(|reason| ValidationError::TypeMismatch {
+                field: "".into(),
+                value: value.to_string(),
+                expected: t.clone(),
+                actual: value.type_name(),
+                reason: Some(reason),
+            })
+        }
+        FieldType::Json => validate_string_type(value, &JSON_RE, t),
+        FieldType::UrlEncoded => validate_string_type(value, &URLENCODED_RE, t),
+        FieldType::Lat => {
+            let s = value.as_str().ok_or_else(|| mismatch(value, t))?;
+            if let Ok(f) = s.parse::<f64>() {
+                if (-90.0..=90.0).contains(&f) {
+                    Ok(())
+                } else {
+                    Err(ValidationError::Custom(format!(
+                        "Latitude out of range: {}",
+                        f
+                    )))
+                }
+            } else {
+                Err(ValidationError::Custom(format!("Invalid latitude: {}", s)))
+            }
+        }
+        FieldType::Lng => {
+            let s = value.as_str().ok_or_else(|| mismatch(value, t))?;
+            if let Ok(f) = s.parse::<f64>() {
+                if (-180.0..=180.0).contains(&f) {
+                    Ok(())
+                } else {
+                    Err(ValidationError::Custom(format!(
+                        "Longitude out of range: {}",
+                        f
+                    )))
+                }
+            } else {
+                Err(ValidationError::Custom(format!("Invalid longitude: {}", s)))
+            }
+        }
+        FieldType::SemVer => validate_string_type(value, &SEMVER_RE, t),
+        FieldType::Username => validate_string_type(value, &USERNAME_RE, t),
+        FieldType::CountryCode => validate_string_type(value, &COUNTRYCODE_RE, t),
+        FieldType::PostalCode => validate_string_type(value, &POSTALCODE_RE, t),
+        FieldType::FilePath => validate_string_type(value, &FILEPATH_RE, t),
+        FieldType::Alpha => validate_string_type(value, &ALPHA_RE, t),
+        FieldType::Alphanumeric => validate_string_type(value, &ALPHANUMERIC_RE, t),
+        FieldType::Ulid => validate_string_type(value, &ULID_RE, t),
+        FieldType::Ksuid => {
+            validate_string_type(value, &KSUID_RE, t)?;
+            match value.as_str() {
+                Some(s) if s > KSUID_MAX => Err(ValidationError::Custom(format!(
+                    "KSUID out of range: {}",
+                    s
+                ))),
+                _ => Ok(()),
+            }
+        }
+        FieldType::NanoId(len) => {
+            let s = value.as_str().ok_or_else(|| mismatch(value, t))?;
+            let alphabet_ok = s
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-');
+            if alphabet_ok && s.len() == *len {
+                Ok(())
+            } else {
+                Err(ValidationError::Custom(format!(
+                    "Invalid nanoid({}): {}",
+                    len, s
+                )))
+            }
+        }
+        FieldType::Custom(pattern) => match_pattern(value, &cached_regex(pattern)?, t),
+    }
+}
+
+/// `regex(...)` 约束
+fn match_regex(val: &Value, re: &Regex, pattern: &str, field: &str) -> Result<()> {
+    let s = val
+        .as_str()
+        .ok_or_else(|| ValidationError::Custom(format!("{} not string for regex", field)))?;
+    if re.is_match(s) {
+        Ok(())
+    } else {
+        Err(ValidationError::RegexMismatch {
+            field: field.to_string(),
+            pattern: pattern.to_string(),
+        })
+    }
+}
+
+/// 自定义正则类型，`t` 为该类型
+fn match_pattern(value: &Value, re: &Regex, t: &FieldType) -> Result<()> {
+    let s = value.as_str().ok_or_else(|| mismatch(value, t))?;
+    if re.is_match(s) {
+        Ok(())
+    } else {
+        Err(ValidationError::Custom(format!("Pattern mismatch: {}", s)))
+    }
+}
+
+pub fn validate_object(value: &mut Value, rules: &[FieldRule]) -> Result<()> {
+    let ctx = ValidationContext::shared();
+    fill_defaults(value, rules, Path::Root, ctx, &mut Vec::new());
+    validate_object_in(value, rules, None, true, ctx, &mut Vec::new())
+}
+
+/// `filled` 表示默认值已写入 `value`；`stack` 为空的工作栈
+fn validate_object_in<'a>(
+    value: &'a Value,
+    rules: &'a [FieldRule],
+    compiled: Option<&'a [CompiledRule]>,
+    filled: bool,
+    ctx: &ValidationContext,
+    stack: &mut Vec<Task<'a>>,
+) -> Result<()> {
+    if let Value::Object(_) = value {
+        // 引用目标在开始校验前统一收集，字段顺序不影响结果
+        let indexed;
+        let ctx = match ref_index(value, rules, filled, ctx) {
+            Some(index) => {
+                indexed = ctx.clone().with_refs(index);
+                &indexed
+            }
+            None => ctx,
+        };
+        run_in(
+            Task::Fields {
+                obj: value,
+                rules,
+                compiled,
+                next: 0,
+                scope: None,
+                depth: 1,
+            },
+            stack,
+            ctx,
+        )
+    } else {
+        Err(ValidationError::Custom("Value is not object".into()))
+    }
+}
+
+/// 收集 ref 约束的目标。只读校验与 `validate_outcomes` 不把默认值写入载荷（`filled` 为 false），
+/// 此时在副本上填入默认值后再收集，只由默认值满足的引用目标同样能找到
+fn ref_index(
+    value: &Value,
+    rules: &[FieldRule],
+    filled: bool,
+    ctx: &ValidationContext,
+) -> Option<RefIndex> {
+    if !RefIndex::wanted(rules) {
+        return None;
+    }
+    if filled || !rules.iter().any(|r| r.has_default() || fills_nested(r)) {
+        return RefIndex::build(value, rules);
+    }
+    let mut filled = value.clone();
+    fill_defaults(&mut filled, rules, Path::Root, ctx, &mut Vec::new());
+    RefIndex::build(&filled, rules)
+}
+
+/// 一个字段的 enum 与约束失败：默认第一个失败即返回，
+/// `exhaustive` 模式下收集，超过 `max_errors` 的部分只记为 `truncated`
+struct Failures {
+    exhaustive: bool,
+    max_errors: usize,
+    errors: Vec<ValidationError>,
+    truncated: bool,
+}
+
+impl Failures {
+    fn new(options: &ValidationOptions) -> Self {
+        Self {
+            exhaustive: options.exhaustive,
+            max_errors: options.max_errors,
+            errors: Vec::new(),
+            truncated: false,
+        }
+    }
+
+    fn push(&mut self, e: ValidationError) -> Result<()> {
+        if !self.exhaustive {
+            return Err(e);
+        }
+        if self.max_errors > 0 && self.errors.len() >= self.max_errors {
+            self.truncated = true;
+        } else {
+            self.errors.push(e);
+        }
+        Ok(())
+    }
+
+    fn finish(mut self, field: &str) -> Result<()> {
+        match self.errors.len() {
+            0 => Ok(()),
+            1 if !self.truncated => Err(self.errors.remove(0)),
+            _ => Err(ValidationError::FieldErrors {
+                field: field.to_string(),
+                errors: self.errors,
+                truncated: self.truncated,
+            }),
+        }
+    }
+}
+
+/// -----------------------------
+/// ValidationOptions
+/// -----------------------------
+#[derive(Debug, Clone)]
+pub struct ValidationOptions {
+    /// 拒绝规则中未声明的字段
+    pub strict: bool,
+    /// 校验前按声明类型转换值：字符串转为 int/float/bool，int 转为 float
+    pub coerce: bool,
+    /// 校验通过后就地规范化 uuid / mac / color，并记录在报告中
+    pub canonicalize: bool,
+    /// 同一字段的 enum 与全部约束都检查一遍，多个失败合并为 `FieldErrors`，
+    /// 便于表单一次展示完整提示；类型不匹配时仍然立即返回
+    pub exhaustive: bool,
+    /// float 相等判断的绝对容差，用于 enum、数组聚合与元素比较、`multiple_of`，
+    /// 默认 0（精确比较）。例如设为 `1e-9` 后 `0.1 + 0.2` 满足 `sum==0.3`。
+    /// 通过 `Schema` 校验且其 metadata 声明了 `float_epsilon` 时，以 schema 的声明为准
+    pub float_epsilon: f64,
+    /// 一次校验最多收集的失败数：`exhaustive` 模式下同一字段的失败、报告中的警告，
+    /// 超出的部分丢弃并标记 `truncated`。默认 0（不限制）
+    pub max_errors: usize,
+    /// 载荷必须已是校验后的形态：填充默认值、coerce 或规范化会改写任何值时返回
+    /// `WouldMutate`。校验在副本上进行，载荷本身保持不变，用于多个环节重复校验的流水线
+    pub frozen: bool,
+    /// 校验深入嵌套对象与数组的最大层数（顶层字段为第 1 层），超过时返回
+    /// `MaxDepthExceeded`。默认 [`DEFAULT_MAX_DEPTH`]，0 表示不限制
+    pub max_depth: usize,
+    /// 一次校验最多访问的节点数与最长耗时，用完时返回 `BudgetExceeded`，
+    /// 避免病态输入长时间占用服务线程。默认不限制
+    pub budget: Budget,
+    /// 对象中的键必须按规则的声明顺序出现（未声明的键不参与比较），否则返回 `OutOfOrder`，
+    /// 用于对载荷原文计算签名的场景。在填充默认值之前检查
+    #[cfg(feature = "ordered")]
+    pub ordered: bool,
+}
+
+/// -----------------------------
+/// Budget
+/// -----------------------------
+/// 一次校验的预算。节点为每个字段值与数组元素；耗时只在访问节点之间检查，
+/// 单个节点的约束（如很慢的正则）不会被中途打断
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Budget {
+    /// 最多访问的节点数，0 表示不限制
+    pub max_nodes: usize,
+    /// 最长耗时，`None` 表示不限制
+    pub max_time: Option<Duration>,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            strict: false,
+            coerce: false,
+            canonicalize: false,
+            exhaustive: false,
+            float_epsilon: 0.0,
+            max_errors: 0,
+            frozen: false,
+            max_depth: DEFAULT_MAX_DEPTH,
+            budget: Budget::default(),
+            #[cfg(feature = "ordered")]
+            ordered: false,
+        }
+    }
+}
+
+pub fn validate_object_with(
+    value: &mut Value,
+    rules: &[FieldRule],
+    options: &ValidationOptions,
+) -> Result<()> {
+    validate_with_context(
+        value,
+        rules,
+        &ValidationContext {
+            options: options.clone(),
+            ..Default::default()
+        },
+    )
+}
+
+/// -----------------------------
+/// ValidationContext
+/// -----------------------------
+/// 校验时的运行环境：选项、`check("name")` 使用的自定义校验器，
+/// `past` / `future` / `within(..)` 使用的时钟，`exists(..)` 使用的外部查找，
+/// 以及接收每次校验结论的审计记录
+#[derive(Clone)]
+pub struct ValidationContext {
+    pub options: ValidationOptions,
+    pub registry: ValidatorRegistry,
+    pub clock: Arc<dyn Clock>,
+    pub resolver: Option<Arc<dyn RefResolver>>,
+    pub audit: Option<Arc<dyn AuditSink>>,
+    /// `=ctx(name)` 默认值读取的变量，如按租户设置的默认币种
+    pub vars: HashMap<String, Value>,
+    /// `=uuid()` 默认值使用的生成器
+    pub ids: Arc<dyn IdGenerator>,
+    /// `slugify` 转换使用的音译
+    pub transliterator: Arc<dyn Transliterator>,
+    pub(crate) refs: Arc<RefIndex>,
+    /// 只读校验：coerce 与转换作用在叶子值的副本上，见 [`adjusted_leaf`]
+    pub(crate) adjust_leaves: bool,
+}
+
+impl fmt::Debug for ValidationContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidationContext")
+            .field("options", &self.options)
+            .field("registry", &self.registry)
+            .field("clock", &self.clock)
+            .field("resolver", &self.resolver.is_some())
+            .field("audit", &self.audit.is_some())
+            .field("vars", &self.vars)
+            .field("ids", &self.ids)
+            .field("transliterator", &self.transliterator)
+            .finish()
+    }
+}
+
+impl Default for ValidationContext {
+    fn default() -> Self {
+        Self {
+            options: ValidationOptions::default(),
+            registry: ValidatorRegistry::default(),
+            clock: Arc::new(SystemClock),
+            resolver: None,
+            audit: None,
+            vars: HashMap::new(),
+            ids: Arc::new(RandomIds),
+            transliterator: Arc::new(BasicTransliterator),
+            refs: Arc::default(),
+            adjust_leaves: false,
+        }
+    }
+}
+
+/// 不带上下文的入口共用的默认上下文，不必每次校验都重新构造
+static DEFAULT_CONTEXT: Lazy<ValidationContext> = Lazy::new(ValidationContext::default);
+
+impl ValidationContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn shared() -> &'static Self {
+        &DEFAULT_CONTEXT
+    }
+
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn options(mut self, options: ValidationOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn registry(mut self, registry: ValidatorRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn resolver(mut self, resolver: impl RefResolver + 'static) -> Self {
+        self.resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// 每次校验后把结论交给 `sink`，见 [`AuditSink`]
+    pub fn audit(mut self, sink: impl AuditSink + 'static) -> Self {
+        self.audit = Some(Arc::new(sink));
+        self
+    }
+
+    /// 设置 `=ctx(name)` 默认值读取的变量
+    pub fn var(mut self, name: impl Into<String>, value: Value) -> Self {
+        self.vars.insert(name.into(), value);
+        self
+    }
+
+    /// 替换 `=uuid()` 默认值使用的生成器，测试时可用 [`crate::ids::SequentialIds`]
+    pub fn ids(mut self, ids: impl IdGenerator + 'static) -> Self {
+        self.ids = Arc::new(ids);
+        self
+    }
+
+    /// 替换 `slugify` 转换使用的音译，默认为 [`BasicTransliterator`]
+    pub fn transliterator(mut self, transliterator: impl Transliterator + 'static) -> Self {
+        self.transliterator = Arc::new(transliterator);
+        self
+    }
+
+    pub(crate) fn with_refs(mut self, refs: RefIndex) -> Self {
+        self.refs = Arc::new(refs);
+        self
+    }
+}
+
+pub fn validate_with_context(
+    value: &mut Value,
+    rules: &[FieldRule],
+    ctx: &ValidationContext,
+) -> Result<()> {
+    validate_with_report(value, rules, ctx).map(|_| ())
+}
+
+/// 与 [`validate_with_context`] 相同，成功时返回附带规范化记录等信息的报告
+pub fn validate_with_report(
+    value: &mut Value,
+    rules: &[FieldRule],
+    ctx: &ValidationContext,
+) -> Result<ValidationReport> {
+    validate_compiled(value, rules, None, ctx)
+}
+
+/// `Schema` 的校验入口，带上预先计算的数据
+pub(crate) fn validate_compiled(
+    value: &mut Value,
+    rules: &[FieldRule],
+    compiled: Option<&[CompiledRule]>,
+    ctx: &ValidationContext,
+) -> Result<ValidationReport> {
+    validate_reusing(value, rules, compiled, ctx, &mut Vec::new())
+}
+
+/// 一条记录的完整校验：frozen 时的副本、审计用的哈希、警告与耗时的收集、收尾、
+/// frozen 检查与审计记录。`stack` 为跨记录复用的工作栈，返回时为空
+fn validate_reusing(
+    value: &mut Value,
+    rules: &[FieldRule],
+    compiled: Option<&[CompiledRule]>,
+    ctx: &ValidationContext,
+    stack: &mut Vec<Task<'static>>,
+) -> Result<ValidationReport> {
+    let mut copy;
+    let value = if ctx.options.frozen {
+        copy = value.clone();
+        &mut copy
+    } else {
+        value
+    };
+    let payload = ctx.audit.as_ref().map(|_| audit::payload_hash(value));
+    #[cfg(feature = "timing")]
+    let outer = timing::start();
+    let outer_warnings = warning::start(ctx.options.max_errors);
+    // 工作栈借用着记录，校验完交还后才能收尾
+    let mut tasks = recycle(std::mem::take(stack));
+    let result = check_prepared(value, rules, compiled, ctx, Vec::new(), &mut tasks);
+    *stack = recycle(tasks);
+    let result = result.map(|changes| finish(value, rules, ctx, changes));
+    let warnings = warning::finish(outer_warnings);
+    let result = result.map(|report| ValidationReport {
+        warnings: warnings.warnings,
+        warnings_truncated: warnings.truncated,
+        ..report
+    });
+    #[cfg(feature = "timing")]
+    let result = result.map(|report| ValidationReport {
+        timings: timing::finish(outer),
+        ..report
+    });
+    let result = result.and_then(|report| check_frozen(ctx, report));
+    record_audit(ctx, rules, payload, result.as_ref().map(|_| ()));
+    result
+}
+
+/// 清空工作栈并改为借用另一条记录，保留已分配的容量
+fn recycle<'b>(mut stack: Vec<Task<'_>>) -> Vec<Task<'b>> {
+    stack.clear();
+    stack.into_iter().map(|_| unreachable!()).collect()
+}
+
+/// 逐条就地校验一批记录，结果与输入一一对应。`threads` 大于 1 时把记录切成连续的几段，
+/// 每段在一个线程中校验，共享同一份规则与上下文；结果仍按输入顺序排列
+pub(crate) fn validate_batch_compiled(
+    values: &mut [Value],
+    rules: &[FieldRule],
+    compiled: Option<&[CompiledRule]>,
+    ctx: &ValidationContext,
+    threads: usize,
+) -> Vec<std::result::Result<ValidationReport, ErrorReport>> {
+    // 同一段中的记录依次复用同一个工作栈
+    let run = |chunk: &mut [Value]| -> Vec<std::result::Result<ValidationReport, ErrorReport>> {
+        let mut stack = Vec::new();
+        chunk
+            .iter_mut()
+            .map(|value| {
+                validate_reusing(value, rules, compiled, ctx, &mut stack)
+                    .map_err(|e| ErrorReport::from_error(&e))
+            })
+            .collect()
+    };
+    let threads = threads.clamp(1, values.len().max(1));
+    if threads == 1 {
+        return run(values);
+    }
+    let chunk_len = values.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = values
+            .chunks_mut(chunk_len)
+            .map(|chunk| scope.spawn(move || run(chunk)))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect()
+    })
+}
+
+/// `frozen` 模式下第一处修改即为错误
+fn check_frozen(ctx: &ValidationContext, report: ValidationReport) -> Result<ValidationReport> {
+    match report.changes.first() {
+        Some(change) if ctx.options.frozen => Err(ValidationError::WouldMutate {
+            field: change.path.clone(),
+            change: change.kind.to_string(),
+        }),
+        _ => Ok(report),
+    }
+}
+
+/// 不修改载荷的校验：在副本上完成与 [`validate_with_report`] 相同的校验，
+/// 返回本次校验会做的修改（填充默认值、coerce、规范化），由调用方审阅后用
+/// `ChangeSet::apply` 写入。载荷只以共享引用传入，可以在多个线程间同时校验
+pub fn validate_changes(
+    value: &Value,
+    rules: &[FieldRule],
+    ctx: &ValidationContext,
+) -> Result<ChangeSet> {
+    validate_changes_compiled(value, rules, None, ctx)
+}
+
+pub(crate) fn validate_changes_compiled(
+    value: &Value,
+    rules: &[FieldRule],
+    compiled: Option<&[CompiledRule]>,
+    ctx: &ValidationContext,
+) -> Result<ChangeSet> {
+    let mut copy = value.clone();
+    validate_compiled(&mut copy, rules, compiled, ctx).map(|report| ChangeSet::new(report.changes))
+}
+
+/// 逐个字段给出结论（通过、失败、填入默认值、跳过的可选字段），不在第一个失败处停止，
+/// 见 [`OutcomeReport`]。载荷不会被修改，`coerce` 在副本上进行
+pub fn validate_outcomes(
+    value: &Value,
+    rules: &[FieldRule],
+    ctx: &ValidationContext,
+) -> OutcomeReport {
+    validate_outcomes_compiled(value, rules, None, ctx)
+}
+
+pub(crate) fn validate_outcomes_compiled(
+    value: &Value,
+    rules: &[FieldRule],
+    compiled: Option<&[CompiledRule]>,
+    ctx: &ValidationContext,
+) -> OutcomeReport {
+    // coerce 与转换在副本上进行
+    let mut copy = value.clone();
+    if ctx.options.coerce {
+        coerce_object(&mut copy, rules, Path::Root, &mut Vec::new());
+    }
+    transform_object(&mut copy, rules, Path::Root, ctx, &mut Vec::new());
+    let value = &copy;
+    let indexed;
+    let ctx = match ref_index(value, rules, false, ctx) {
+        Some(index) => {
+            indexed = ctx.clone().with_refs(index);
+            &indexed
+        }
+        None => ctx,
+    };
+    // 预算由全部字段共用
+    let _budget = budget::start(&ctx.options.budget);
+    OutcomeReport {
+        fields: field_outcomes(value, rules, compiled, ctx, ""),
+    }
+}
+
+fn field_outcomes(
+    value: &Value,
+    rules: &[FieldRule],
+    compiled: Option<&[CompiledRule]>,
+    ctx: &ValidationContext,
+    prefix: &str,
+) -> Vec<FieldOutcome> {
+    let Value::Object(obj) = value else {
+        return Vec::new();
+    };
+    let mut fields: Vec<FieldOutcome> = rules
+        .iter()
+        .enumerate()
+        .map(|(i, rule)| field_outcome(value, rule, compiled.and_then(|c| c.get(i)), ctx, prefix))
+        .collect();
+    if ctx.options.strict {
+        let mut unknown: Vec<&String> = obj
+            .keys()
+            .filter(|key| !rules.iter().any(|r| &r.field == *key))
+            .collect();
+        unknown.sort();
+        fields.extend(unknown.into_iter().map(|key| {
+            let path = field_path(prefix, key);
+            FieldOutcome {
+                outcome: Outcome::Failed(ValidationError::UnknownField(path.clone())),
+                path,
+                children: Vec::new(),
+            }
+        }));
+    }
+    fields
+}
+
+fn field_outcome(
+    parent: &Value,
+    rule: &FieldRule,
+    compiled: Option<&CompiledRule>,
+    ctx: &ValidationContext,
+    prefix: &str,
+) -> FieldOutcome {
+    let path = field_path(prefix, &rule.field);
+    let Some(val) = parent
+        .as_object()
+        .and_then(|obj| obj.get(rule.field.as_str()))
+    else {
+        let outcome = match default_value(rule, ctx) {
+            Some(d) => match validate_value(&d, rule, compiled, ctx, Some(parent)) {
+                Ok(()) => Outcome::Defaulted(d.into_owned()),
+                Err(e) => Outcome::Failed(e.within(prefix)),
+            },
+            None if rule.required || rule.must_be_present() => {
+                Outcome::Failed(ValidationError::MissingField(path.clone()))
+            }
+            None => Outcome::SkippedOptional,
+        };
+        return FieldOutcome {
+            path,
+            outcome,
+            children: Vec::new(),
+        };
+    };
+
+    let children = match (val, &rule.children, &rule.rule) {
+        (Value::Object(_), Some(children), _) => field_outcomes(
+            val,
+            children,
+            compiled.map(|c| c.children.as_slice()),
+            ctx,
+            &path,
+        ),
+        (Value::Array(items), None, Some(sub)) if sub.children.is_some() => {
+            let sub_compiled = compiled.and_then(|c| c.sub.as_deref());
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let element = format!("{}[{}]", path, i);
+                    FieldOutcome {
+                        outcome: match validate_value(item, sub, sub_compiled, ctx, None) {
+                            Ok(()) => Outcome::Passed,
+                            Err(e) => Outcome::Failed(e.within(&element)),
+                        },
+                        children: field_outcomes(
+                            item,
+                            sub.children.as_deref().unwrap_or_default(),
+                            sub_compiled.map(|c| c.children.as_slice()),
+                            ctx,
+                            &element,
+                        ),
+                        path: element,
+                    }
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+    let outcome = match validate_value(val, rule, compiled, ctx, Some(parent)) {
+        Ok(()) => Outcome::Passed,
+        Err(e) => Outcome::Failed(e.within(prefix)),
+    };
+    FieldOutcome {
+        path,
+        outcome,
+        children,
+    }
+}
 
-            let min_ok = if min_inc { n >= min_v } else { n > min_v };
-            let max_ok = if max_inc { n <= max_v } else { n < max_v };
+/// 只读校验：不写入载荷，缺失字段的默认值按填入后的样子校验。
+/// 适用于放在 `Arc<Value>` 中、被多个请求或缓存共享的数据，校验时不复制也不修改共享的子树；
+/// `coerce` 与 `slugify` 等转换作用在叶子值的副本上，结论与就地校验相同；
+/// `canonicalize` 只改写通过校验的值，不影响结论。
+/// 遍历只沿规则的嵌套进行（不超过 `MAX_NESTING_DEPTH` 层），与值的形状和大小无关
+pub fn validate_shared(value: &Value, rules: &[FieldRule], ctx: &ValidationContext) -> Result<()> {
+    validate_shared_compiled(value, rules, None, ctx)
+}
 
-            if !min_ok || !max_ok {
-                return Err(ValidationError::RangeError {
-                    field: field.to_string(),
-                    value: i.to_string(),
-                    min: min_v.to_string(),
-                    max: max_v.to_string(),
-                });
-            }
+pub(crate) fn validate_shared_compiled(
+    value: &Value,
+    rules: &[FieldRule],
+    compiled: Option<&[CompiledRule]>,
+    ctx: &ValidationContext,
+) -> Result<()> {
+    let payload = ctx.audit.as_ref().map(|_| audit::payload_hash(value));
+    let adjusting;
+    let ctx = if ctx.options.coerce || any_transform(rules) {
+        adjusting = ValidationContext {
+            adjust_leaves: true,
+            ..ctx.clone()
+        };
+        &adjusting
+    } else {
+        ctx
+    };
+    let result = if ctx.options.strict {
+        reject_unknown_fields(value, rules, Path::Root)
+    } else {
+        Ok(())
+    }
+    .and_then(|_| validate_object_in(value, rules, compiled, false, ctx, &mut Vec::new()));
+    record_audit(ctx, rules, payload, result.as_ref().copied());
+    result
+}
+
+/// 设置了审计记录时写入本次结论，`payload` 为校验前计算的载荷哈希
+pub(crate) fn record_audit(
+    ctx: &ValidationContext,
+    rules: &[FieldRule],
+    payload: Option<String>,
+    result: std::result::Result<(), &ValidationError>,
+) {
+    if let (Some(sink), Some(payload)) = (&ctx.audit, payload) {
+        audit::record(sink.as_ref(), rules, payload, result, ctx.clock.now());
+    }
+}
+
+/// 与 [`validate_with_context`] 相同，但 `exists(..)` 通过异步查找解析：
+/// 先收集文档中所有待查的 ID 并逐个等待，再同步完成校验
+pub async fn validate_async(
+    value: &mut Value,
+    rules: &[FieldRule],
+    ctx: &ValidationContext,
+    resolver: &impl AsyncRefResolver,
+) -> Result<()> {
+    let mut copy;
+    let value = if ctx.options.frozen {
+        copy = value.clone();
+        &mut copy
+    } else {
+        value
+    };
+    let payload = ctx.audit.as_ref().map(|_| audit::payload_hash(value));
+    let result = match prepare(value, rules, ctx, Vec::new()) {
+        Ok(changes) => {
+            let pending = collect_exists(value, rules);
+            let resolved = ResolvedRefs::resolve(pending, resolver).await;
+            let resolving = ctx.clone().resolver(resolved);
+            validate_object_in(value, rules, None, true, &resolving, &mut Vec::new())
+                .map(|_| finish(value, rules, ctx, changes))
+                .and_then(|report| check_frozen(ctx, report))
+                .map(|_| ())
         }
-        Value::Float(f) => {
-            let n = *f;
-            let min_v = match min {
-                Value::Int(mi) => *mi as f64,
-                Value::Float(mf) => *mf,
-                _ => {
-                    return Err(ValidationError::Custom(format!(
-                        "Invalid min value type in range for {}",
-                        field
-                    )));
-                }
-            };
-            let max_v = match max {
-                Value::Int(mi) => *mi as f64,
-                Value::Float(mf) => *mf,
-                _ => {
-                    return Err(ValidationError::Custom(format!(
-                        "Invalid max value type in range for {}",
-                        field
-                    )));
-                }
-            };
-            let min_ok = if min_inc { n >= min_v } else { n > min_v };
-            let max_ok = if max_inc { n <= max_v } else { n < max_v };
-            if !min_ok || !max_ok {
-                return Err(ValidationError::RangeError {
-                    field: field.to_string(),
-                    value: f.to_string(),
-                    min: min_v.to_string(),
-                    max: max_v.to_string(),
-                });
+        Err(e) => Err(e),
+    };
+    record_audit(ctx, rules, payload, result.as_ref().copied());
+    result
+}
+
+/// 校验通过后的收尾步骤；`changes` 为预处理中的修改，规范化的记录接在后面
+fn finish(
+    value: &mut Value,
+    rules: &[FieldRule],
+    ctx: &ValidationContext,
+    changes: Vec<Change>,
+) -> ValidationReport {
+    let mut report = ValidationReport {
+        changes,
+        ..Default::default()
+    };
+    if ctx.options.canonicalize {
+        canonicalize_object(value, rules, Path::Root, &mut report.normalized);
+        report
+            .changes
+            .extend(report.normalized.iter().map(|n| Change {
+                path: n.path.clone(),
+                kind: ChangeKind::Normalize,
+                before: Some(Value::String(n.before.clone())),
+                after: Value::String(n.after.clone()),
+            }));
+    }
+    hash_fields(value, rules, Path::Root, &mut report.hashes);
+    report
+}
+
+/// 预处理后校验，返回对载荷做的修改。校验结束后 `stack` 仍借用着 `value`，
+/// 收尾（规范化与哈希）由调用方在栈不再使用后交给 [`finish`]
+fn check_prepared<'a>(
+    value: &'a mut Value,
+    rules: &'a [FieldRule],
+    compiled: Option<&'a [CompiledRule]>,
+    ctx: &ValidationContext,
+    changes: Vec<Change>,
+    stack: &mut Vec<Task<'a>>,
+) -> Result<Vec<Change>> {
+    let changes = prepare(value, rules, ctx, changes)?;
+    validate_object_in(value, rules, compiled, true, ctx, stack)?;
+    Ok(changes)
+}
+
+/// 校验前的预处理：coerce、strict 与填充默认值，返回对载荷做的修改
+fn prepare(
+    value: &mut Value,
+    rules: &[FieldRule],
+    ctx: &ValidationContext,
+    mut changes: Vec<Change>,
+) -> Result<Vec<Change>> {
+    if ctx.options.coerce {
+        coerce_object(value, rules, Path::Root, &mut changes);
+    }
+    transform_object(value, rules, Path::Root, ctx, &mut changes);
+    if ctx.options.strict {
+        reject_unknown_fields(value, rules, Path::Root)?;
+    }
+    #[cfg(feature = "ordered")]
+    if ctx.options.ordered {
+        check_field_order(value, rules, Path::Root)?;
+    }
+    fill_defaults(value, rules, Path::Root, ctx, &mut changes);
+    Ok(changes)
+}
+
+/// 对象字段的路径，规则无名（数组元素）时就是 `prefix`
+fn field_path(prefix: &str, field: &str) -> String {
+    if prefix.is_empty() {
+        field.to_string()
+    } else {
+        join_path(prefix, field)
+    }
+}
+
+/// 按与校验相同的遍历方式，为缺失的字段填入默认值（默认值中的嵌套字段同样处理）。
+/// `ordered` 模式下填入的键放到声明的位置上，而不是追加在末尾
+fn fill_defaults(
+    value: &mut Value,
+    rules: &[FieldRule],
+    prefix: Path,
+    ctx: &ValidationContext,
+    changes: &mut Vec<Change>,
+) {
+    #[cfg(feature = "ordered")]
+    let len = value.as_object().map(ObjectMap::len);
+    for rule in rules {
+        fill_field(value, rule, prefix, ctx, changes);
+    }
+    #[cfg(feature = "ordered")]
+    if ctx.options.ordered
+        && let Value::Object(map) = value
+        && Some(map.len()) != len
+    {
+        *map = declared_order(std::mem::take(map).into_iter().collect(), rules)
+            .into_iter()
+            .collect();
+    }
+}
+
+/// 把键值对稳定地排成声明顺序，未声明的键跟在它前面的已声明键之后
+#[cfg(feature = "ordered")]
+pub(crate) fn declared_order<T>(
+    entries: Vec<(String, T)>,
+    rules: &[FieldRule],
+) -> Vec<(String, T)> {
+    let mut rank = 0;
+    let mut ranked: Vec<_> = entries
+        .into_iter()
+        .map(|(k, v)| {
+            if let Some(at) = rules.iter().position(|r| r.field == k) {
+                rank = at;
             }
-        }
-        Value::String(s) => {
-            let n = s.len();
-            let min_v = parse_usize(min, field, "min")?;
-            let max_v = parse_usize(max, field, "max")?;
-            let min_ok = if min_inc { n >= min_v } else { n > min_v };
-            let max_ok = if max_inc { n <= max_v } else { n < max_v };
-            if !min_ok || !max_ok {
-                return Err(ValidationError::RangeError {
-                    field: field.to_string(),
-                    value: n.to_string(),
-                    min: min_v.to_string(),
-                    max: max_v.to_string(),
+            (rank, k, v)
+        })
+        .collect();
+    ranked.sort_by_key(|(rank, _, _)| *rank);
+    ranked.into_iter().map(|(_, k, v)| (k, v)).collect()
+}
+
+fn fill_field(
+    value: &mut Value,
+    rule: &FieldRule,
+    prefix: Path,
+    ctx: &ValidationContext,
+    changes: &mut Vec<Change>,
+) {
+    let path = prefix.field(&rule.field);
+    let val = match value {
+        Value::Object(obj) => {
+            if !obj.contains_key(rule.field.as_str())
+                && let Some(d) = default_value(rule, ctx)
+            {
+                let d = d.into_owned();
+                obj.insert(rule.field.to_string(), d.clone());
+                changes.push(Change {
+                    path: path.to_string(),
+                    kind: ChangeKind::Default,
+                    before: None,
+                    after: d,
                 });
             }
+            // 取可写引用会复制与其他克隆共享的对象，嵌套规则没有默认值时不进入
+            if !fills_nested(rule) {
+                return;
+            }
+            match obj.get_mut(rule.field.as_str()) {
+                Some(v) => v,
+                None => return,
+            }
         }
-        _ => {
-            return Err(ValidationError::Custom(format!(
-                "{} cannot apply range constraint to {:?}",
-                field, val
-            )));
+        _ => value,
+    };
+    fill_value(val, rule, path, ctx, changes);
+}
+
+/// 嵌套的字段或元素规则中有需要填入的默认值
+fn fills_nested(rule: &FieldRule) -> bool {
+    let fills = |r: &FieldRule| r.has_default() || fills_nested(r);
+    rule.rule.as_deref().is_some_and(fills) || rule.children.iter().flatten().any(fills)
+}
+
+pub(crate) fn fill_value(
+    val: &mut Value,
+    rule: &FieldRule,
+    path: Path,
+    ctx: &ValidationContext,
+    changes: &mut Vec<Change>,
+) {
+    // 密文中的默认值无从填入
+    if rule.encryption().is_some() {
+        return;
+    }
+    if let Some(sub) = &rule.rule {
+        match val {
+            Value::Object(_) => fill_field(val, sub, path, ctx, changes),
+            Value::Array(items) => {
+                for (i, item) in items.iter_mut().enumerate() {
+                    fill_value(item, sub, path.index(i), ctx, changes);
+                }
+            }
+            _ => {}
         }
     }
-    Ok(())
+    if let (Some(children), Value::Object(_)) = (&rule.children, &val) {
+        fill_defaults(val, children, path, ctx, changes);
+    }
 }
 
-fn parse_usize(val: &Value, field: &str, label: &str) -> Result<usize> {
-    match val {
-        Value::Int(i) => Ok(*i as usize),
-        Value::String(s) => s.parse::<usize>().map_err(|_| {
-            ValidationError::Custom(format!(
-                "Failed to parse '{}' as usize for {} {}",
-                s, field, label
-            ))
-        }),
-        _ => Err(ValidationError::Custom(format!(
-            "Invalid {} value type in range for {}",
-            label, field
-        ))),
+/// 字段缺失时使用的默认值：固定值，或在本次校验时对默认值表达式求值。
+/// `=env(...)` / `=ctx(...)` 得到的字符串按字段类型转换（同 `coerce`），
+/// `=now()` / `=today()` 读取 `ctx.clock`，`=uuid()` 使用 `ctx.ids`
+pub(crate) fn default_value<'a>(
+    rule: &'a FieldRule,
+    ctx: &ValidationContext,
+) -> Option<Cow<'a, Value>> {
+    if let Some(d) = &rule.default {
+        return Some(Cow::Borrowed(d));
     }
+    let value = match rule.default_expr()? {
+        DefaultExpr::Env(name) => Value::String(std::env::var(name).ok()?),
+        DefaultExpr::Ctx(name) => ctx.vars.get(name)?.clone(),
+        DefaultExpr::Now => return Some(Cow::Owned(time_default(ctx.clock.now(), rule))),
+        DefaultExpr::Today => {
+            let midnight = ctx.clock.now().div_euclid(86400) * 86400;
+            return Some(Cow::Owned(time_default(midnight, rule)));
+        }
+        DefaultExpr::Uuid => return Some(Cow::Owned(Value::String(ctx.ids.uuid()))),
+    };
+    Some(Cow::Owned(match value {
+        Value::String(s) => coerce_str(&s, rule),
+        v => v,
+    }))
 }
 
-fn validate_string_type(value: &Value, re: &Regex, type_name: &str) -> Result<()> {
-    let s = value.as_str().ok_or(ValidationError::Custom(format!(
-        "Not string for {}",
-        type_name
-    )))?;
-    if re.is_match(s) {
-        Ok(())
-    } else {
-        Err(ValidationError::Custom(format!(
-            "Invalid {}: {}",
-            type_name, s
-        )))
+/// 按字段声明的第一个时间类型输出：timestamp 为秒数，datetime / date 为文本
+fn time_default(secs: i64, rule: &FieldRule) -> Value {
+    let types = match &rule.union_types {
+        Some(types) => types.as_slice(),
+        None => std::slice::from_ref(&rule.field_type),
+    };
+    match types.iter().find(|t| {
+        matches!(
+            t,
+            FieldType::Timestamp | FieldType::DateTime | FieldType::Date
+        )
+    }) {
+        Some(FieldType::Timestamp) => Value::Int(secs),
+        Some(FieldType::Date) => Value::String(format_instant(secs, true)),
+        _ => Value::String(format_instant(secs, false)),
     }
 }
 
-pub fn validate_type(value: &Value, t: &FieldType) -> Result<()> {
-    match t {
-        FieldType::String => value
-            .as_str()
-            .map(|_| ())
-            .ok_or(ValidationError::Custom("Not string".into())),
-        FieldType::Int => value
-            .as_int()
-            .map(|_| ())
-            .ok_or(ValidationError::Custom("Not int".into())),
-        FieldType::Float => value
-            .as_float()
-            .map(|_| ())
-            .ok_or(ValidationError::Custom("Not float".into())),
-        FieldType::Bool => value
-            .as_bool()
-            .map(|_| ())
-            .ok_or(ValidationError::Custom("Not bool".into())),
-        FieldType::Object => value
-            .as_object()
-            .map(|_| ())
-            .ok_or(ValidationError::Custom("Not object".into())),
-        FieldType::Array => value
-            .as_array()
-            .map(|_| ())
-            .ok_or(ValidationError::Custom("Not array".into())),
-        FieldType::Email => {
-            let s = value
-                .as_str()
-                .ok_or(ValidationError::Custom("Not string for email".into()))?;
-            if !EMAIL_RE.is_match(s) {
-                return Err(ValidationError::Custom(format!("Invalid email: {}", s)));
+fn coerce_object(value: &mut Value, rules: &[FieldRule], prefix: Path, changes: &mut Vec<Change>) {
+    if let Value::Object(map) = value {
+        for rule in rules {
+            if let Some(v) = map.get_mut(rule.field.as_str()) {
+                coerce_value(v, rule, prefix.field(&rule.field), changes);
             }
-            Ok(())
         }
-        FieldType::Uri => {
-            let s = value
-                .as_str()
-                .ok_or(ValidationError::Custom("Not string for uri".into()))?;
-            url::Url::parse(s)
-                .map(|_| ())
-                .map_err(|_| ValidationError::Custom(format!("{} is not a valid URI", s)))
+    }
+}
+
+fn coerce_value(value: &mut Value, rule: &FieldRule, path: Path, changes: &mut Vec<Change>) {
+    // 密文原样保留
+    if rule.encryption().is_some() {
+        return;
+    }
+    let types = match &rule.union_types {
+        Some(types) => types.as_slice(),
+        None => std::slice::from_ref(&rule.field_type),
+    };
+    let coerced = match value {
+        Value::String(s) => coerce_str(s, rule),
+        Value::Int(i) if types.contains(&FieldType::Float) && !types.contains(&FieldType::Int) => {
+            Value::Float(*i as f64)
         }
-        FieldType::Uuid => {
-            let s = value
-                .as_str()
-                .ok_or(ValidationError::Custom("Not string for uuid".into()))?;
-            if !UUID_RE.is_match(s) {
-                return Err(ValidationError::Custom(format!("Invalid uuid: {}", s)));
+        Value::Object(_) => {
+            if let Some(children) = &rule.children {
+                coerce_object(value, children, path, changes);
             }
-            Ok(())
+            return;
         }
-        FieldType::Ip => {
-            let s = value
-                .as_str()
-                .ok_or(ValidationError::Custom("Not string for ip".into()))?;
-            if IP_V4_RE.is_match(s) || IP_V6_RE.is_match(s) {
-                Ok(())
-            } else {
-                Err(ValidationError::Custom(format!("Invalid ip: {}", s)))
+        Value::Array(items) => {
+            if let Some(sub) = &rule.rule {
+                for (i, item) in items.iter_mut().enumerate() {
+                    coerce_value(item, sub, path.index(i), changes);
+                }
             }
+            return;
         }
-        FieldType::Mac => validate_string_type(value, &MAC_RE, "mac"),
-        FieldType::Date => validate_string_type(value, &DATE_RE, "date"),
-        FieldType::DateTime => validate_string_type(value, &DATETIME_RE, "datetime"),
-        FieldType::Time => validate_string_type(value, &TIME_RE, "time"),
-        FieldType::Timestamp => value
-            .as_int()
-            .map(|_| ())
-            .ok_or(ValidationError::Custom("Not number for timestamp".into())),
-        FieldType::Color => validate_string_type(value, &COLOR_RE, "color"),
-        FieldType::Hostname => {
-            let s = value
-                .as_str()
-                .ok_or(ValidationError::Custom("Not string for hostname".into()))?;
-            if s.is_empty() || s.len() > 253 {
-                return Err(ValidationError::Custom(format!(
-                    "Hostname length out of range: {}",
-                    s.len()
-                )));
-            }
-            if HOSTNAME_RE.is_match(s) {
-                Ok(())
-            } else {
-                Err(ValidationError::Custom(format!("Invalid hostname: {}", s)))
+        _ => return,
+    };
+    if coerced != *value {
+        let before = std::mem::replace(value, coerced);
+        changes.push(Change {
+            path: path.to_string(),
+            kind: ChangeKind::Coerce,
+            before: Some(before),
+            after: value.clone(),
+        });
+    }
+}
+
+/// 按字段的 `slugify` 等转换改写调用方提供的字符串（默认值不改写）
+fn transform_object(
+    value: &mut Value,
+    rules: &[FieldRule],
+    prefix: Path,
+    ctx: &ValidationContext,
+    changes: &mut Vec<Change>,
+) {
+    if let Value::Object(map) = value {
+        // 取可写引用会复制与其他克隆共享的对象，没有转换的字段不进入
+        for rule in rules.iter().filter(|r| has_transform(r)) {
+            if let Some(v) = map.get_mut(rule.field.as_str()) {
+                transform_value(v, rule, prefix.field(&rule.field), ctx, changes);
             }
         }
-        FieldType::Slug => validate_string_type(value, &SLUG_RE, "slug"),
-        FieldType::Hex => validate_string_type(value, &HEX_RE, "hex"),
-        FieldType::Base64 => validate_string_type(value, &BASE64_RE, "base64"),
-        FieldType::Password | FieldType::Token => value
-            .as_str()
-            .map(|_| ())
-            .ok_or(ValidationError::Custom(format!("Not string for {:?}", t))),
-        FieldType::Phone => validate_string_type(value, &PHONE_RE, "phone"),
-        FieldType::CreditCard => {
-            let s = value
-                .as_str()
-                .ok_or(ValidationError::Custom("Not string for creditcard".into()))?;
-            if !CREDITCARD_RE.is_match(s) {
-                return Err(ValidationError::Custom(format!(
-                    "Invalid creditcard: {}",
-                    s
-                )));
-            }
-            let digits: Vec<u32> = s.chars().filter_map(|c| c.to_digit(10)).collect();
-            if digits.len() < 13 || digits.len() > 19 {
-                return Err(ValidationError::Custom(format!(
-                    "Invalid creditcard length: {}",
-                    digits.len()
-                )));
+    }
+}
+
+/// 任一层规则带有转换。与 [`has_transform`] 不同，逐层展开而不递归，不随嵌套层数消耗调用栈
+fn any_transform(rules: &[FieldRule]) -> bool {
+    let mut pending: Vec<&FieldRule> = rules.iter().collect();
+    while let Some(rule) = pending.pop() {
+        if rule.has_constraint(|c| matches!(c, Constraint::Transform(_))) {
+            return true;
+        }
+        pending.extend(rule.rule.as_deref());
+        pending.extend(rule.children.iter().flatten());
+    }
+    false
+}
+
+/// 规则或其嵌套规则带有转换；密文不改写
+fn has_transform(rule: &FieldRule) -> bool {
+    if rule.encryption().is_some() {
+        return false;
+    }
+    rule.has_constraint(|c| matches!(c, Constraint::Transform(_)))
+        || rule.rule.as_deref().is_some_and(has_transform)
+        || rule.children.iter().flatten().any(has_transform)
+}
+
+fn transform_value(
+    value: &mut Value,
+    rule: &FieldRule,
+    path: Path,
+    ctx: &ValidationContext,
+    changes: &mut Vec<Change>,
+) {
+    match value {
+        Value::Object(_) => {
+            if let Some(children) = &rule.children {
+                transform_object(value, children, path, ctx, changes);
             }
-            let mut sum = 0;
-            let mut double = false;
-            for &d in digits.iter().rev() {
-                let mut val = d;
-                if double {
-                    val = if d > 4 { d * 2 - 9 } else { d * 2 };
+        }
+        Value::Array(items) => {
+            if let Some(sub) = rule.rule.as_deref().filter(|sub| has_transform(sub)) {
+                for (i, item) in items.iter_mut().enumerate() {
+                    transform_value(item, sub, path.index(i), ctx, changes);
                 }
-                sum += val;
-                double = !double;
             }
-            if sum % 10 != 0 {
-                return Err(ValidationError::Custom(
-                    "Invalid creditcard checksum".to_string(),
-                ));
-            }
-            Ok(())
         }
-        FieldType::ISBN => validate_string_type(value, &ISBN_RE, "isbn"),
-        FieldType::Port => validate_string_type(value, &PORT_RE, "port"),
-        FieldType::Json => validate_string_type(value, &JSON_RE, "json"),
-        FieldType::UrlEncoded => validate_string_type(value, &URLENCODED_RE, "urlencoded"),
-        FieldType::Lat => {
-            let s = value
-                .as_str()
-                .ok_or(ValidationError::Custom("Not string for lat".into()))?;
-            if let Ok(f) = s.parse::<f64>() {
-                if (-90.0..=90.0).contains(&f) {
-                    Ok(())
-                } else {
-                    Err(ValidationError::Custom(format!(
-                        "Latitude out of range: {}",
-                        f
-                    )))
-                }
-            } else {
-                Err(ValidationError::Custom(format!("Invalid latitude: {}", s)))
+        Value::String(s) => {
+            let transforms = rule.constraints.iter().flat_map(|c| &c.items);
+            let mut after = s.clone();
+            for transform in transforms.filter_map(|c| match c {
+                Constraint::Transform(t) => Some(t),
+                _ => None,
+            }) {
+                after = match transform {
+                    Transform::Slugify => slugify(&after, ctx.transliterator.as_ref()),
+                    Transform::NormalizePhone { region } => match normalize_phone(&after, region) {
+                        Ok(normalized) => normalized,
+                        Err(_) => after,
+                    },
+                };
+            }
+            if after != *s {
+                let before = std::mem::replace(s, after.clone());
+                changes.push(Change {
+                    path: path.to_string(),
+                    kind: ChangeKind::Transform,
+                    before: Some(Value::String(before)),
+                    after: Value::String(after),
+                });
             }
         }
-        FieldType::Lng => {
-            let s = value
-                .as_str()
-                .ok_or(ValidationError::Custom("Not string for lng".into()))?;
-            if let Ok(f) = s.parse::<f64>() {
-                if (-180.0..=180.0).contains(&f) {
-                    Ok(())
-                } else {
-                    Err(ValidationError::Custom(format!(
-                        "Longitude out of range: {}",
-                        f
-                    )))
-                }
-            } else {
-                Err(ValidationError::Custom(format!("Invalid longitude: {}", s)))
+        _ => {}
+    }
+}
+
+/// 按规则声明的类型（联合类型依次尝试）转换字符串，无法转换时保持原样
+pub(crate) fn coerce_str(raw: &str, rule: &FieldRule) -> Value {
+    let types = match &rule.union_types {
+        Some(types) => types.as_slice(),
+        None => std::slice::from_ref(&rule.field_type),
+    };
+    for t in types {
+        let t = if *t == FieldType::Timestamp {
+            &FieldType::Int
+        } else {
+            t
+        };
+        if let Ok(v) = convert_input_to_value(raw, t) {
+            return v;
+        }
+    }
+    Value::String(raw.to_string())
+}
+
+fn reject_unknown_fields(value: &Value, rules: &[FieldRule], prefix: Path) -> Result<()> {
+    let Value::Object(map) = value else {
+        return Ok(());
+    };
+    for (key, v) in map {
+        let path = prefix.field(key);
+        let Some(rule) = rules.iter().find(|r| &r.field == key) else {
+            return Err(ValidationError::UnknownField(path.to_string()));
+        };
+        if let Some(children) = &rule.children {
+            reject_unknown_fields(v, children, path)?;
+        }
+        if let (Some(sub), Value::Array(items)) = (&rule.rule, v)
+            && let Some(children) = &sub.children
+        {
+            for (i, item) in items.iter().enumerate() {
+                reject_unknown_fields(item, children, path.index(i))?;
             }
         }
-        FieldType::SemVer => validate_string_type(value, &SEMVER_RE, "semver"),
-        FieldType::Username => validate_string_type(value, &USERNAME_RE, "username"),
-        FieldType::CountryCode => validate_string_type(value, &COUNTRYCODE_RE, "countrycode"),
-        FieldType::PostalCode => validate_string_type(value, &POSTALCODE_RE, "postalcode"),
-        FieldType::FilePath => validate_string_type(value, &FILEPATH_RE, "filepath"),
-        FieldType::Alpha => validate_string_type(value, &ALPHA_RE, "alpha"),
-        FieldType::Alphanumeric => validate_string_type(value, &ALPHANUMERIC_RE, "alphanumeric"),
-        FieldType::Custom(pattern) => {
-            let s = value.as_str().ok_or(ValidationError::Custom(
-                "Not string for custom regex".into(),
-            ))?;
-            let re = {
-                let mut cache = match REGEX_CACHE.lock() {
-                    Ok(c) => c,
-                    Err(e) => {
-                        tracing::error!("REGEX_CACHE lock poisoned: {}", e);
-                        return Err(ValidationError::Custom("regex cache lock poisoned".into()));
-                    }
-                };
-                if let Some(r) = cache.get(pattern) {
-                    r.clone()
-                } else {
-                    match Regex::new(pattern) {
-                        Ok(regex) => {
-                            cache.insert(pattern.clone(), regex.clone());
-                            regex
-                        }
-                        Err(e) => return Err(ValidationError::InvalidRegex(e.to_string())),
-                    }
-                }
-            };
-            if re.is_match(s) {
-                Ok(())
-            } else {
-                Err(ValidationError::Custom(format!("Pattern mismatch: {}", s)))
+    }
+    Ok(())
+}
+
+/// 与 `reject_unknown_fields` 相同的遍历方式，逐层检查对象中键的顺序
+#[cfg(feature = "ordered")]
+fn check_field_order(value: &Value, rules: &[FieldRule], prefix: Path) -> Result<()> {
+    let Value::Object(map) = value else {
+        return Ok(());
+    };
+    if let Some((key, after)) = out_of_order(map.keys(), rules) {
+        return Err(ValidationError::OutOfOrder {
+            field: prefix.field(key).to_string(),
+            after: after.to_string(),
+        });
+    }
+    for (key, v) in map {
+        let Some(rule) = rules.iter().find(|r| &r.field == key) else {
+            continue;
+        };
+        let path = prefix.field(key);
+        if let Some(children) = &rule.children {
+            check_field_order(v, children, path)?;
+        }
+        if let (Some(sub), Value::Array(items)) = (&rule.rule, v)
+            && let Some(children) = &sub.children
+        {
+            for (i, item) in items.iter().enumerate() {
+                check_field_order(item, children, path.index(i))?;
             }
         }
     }
+    Ok(())
 }
 
-pub fn validate_object(value: &mut Value, rules: &[FieldRule]) -> Result<()> {
-    if let Value::Object(_) = value {
-        for rule in rules {
-            validate_field(value, rule)?;
+/// 按 `keys` 的顺序找出第一个在规则中声明得比前面的键更早的键，返回它和排在它前面的那个键
+#[cfg(feature = "ordered")]
+pub(crate) fn out_of_order<'a>(
+    keys: impl IntoIterator<Item = &'a String>,
+    rules: &[FieldRule],
+) -> Option<(&'a str, &'a str)> {
+    let mut last: Option<(usize, &str)> = None;
+    for key in keys {
+        let Some(at) = rules.iter().position(|r| &r.field == key) else {
+            continue;
+        };
+        match last {
+            Some((before, prev)) if at < before => return Some((key, prev)),
+            _ => last = Some((at, key)),
         }
-        Ok(())
-    } else {
-        Err(ValidationError::Custom("Value is not object".into()))
     }
+    None
 }
 
 pub fn validate(value: &Value, rules: &[FieldRule]) -> Option<Value> {
@@ -697,22 +3829,21 @@ pub fn validate_rule(rule_str: &str, value_str: &str) -> bool {
         Err(_) => return false,
     };
 
-    let mut map = HashMap::new();
-    map.insert(rule_ast.field.clone(), val_enum);
+    let mut map = ObjectMap::new();
+    map.insert(rule_ast.field.to_string(), val_enum);
     let mut wrapped_value = Value::Object(map);
 
     validate_field(&mut wrapped_value, &rule_ast).is_ok()
 }
 
-fn convert_input_to_value(
+pub(crate) fn convert_input_to_value(
     input: &str,
     target_type: &FieldType,
 ) -> std::result::Result<Value, String> {
     match target_type {
-        FieldType::Int => input
-            .parse::<i64>()
-            .map(Value::Int)
-            .map_err(|e| e.to_string()),
+        FieldType::Int => {
+            Value::integer(input).ok_or_else(|| format!("invalid integer '{}'", input))
+        }
         FieldType::Float => input
             .parse::<f64>()
             .map(Value::Float)
@@ -722,6 +3853,9 @@ fn convert_input_to_value(
             "false" | "0" => Ok(Value::Bool(false)),
             _ => Err("Invalid boolean value".into()),
         },
+        FieldType::Money => {
+            money::parse_str(input).ok_or_else(|| format!("invalid money '{}'", input))
+        }
         _ => Ok(Value::String(input.to_string())),
     }
 }
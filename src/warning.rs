@@ -0,0 +1,75 @@
+//! `warn(...)` 与 `deprecated` 产生的警告记录在线程局部变量里，只在一次校验期间有效，
+//! 由 `validate_with_report` 放进报告的 `warnings`；不在记录期间时直接丢弃。
+//! 路径的补全方式与 `ValidationError::within` 相同
+
+use std::cell::RefCell;
+
+use crate::validator::ValidationError;
+
+/// 一次校验的记录，`limit` 为 0 时不限制条数
+#[derive(Default)]
+pub(crate) struct Recording {
+    pub(crate) warnings: Vec<ValidationError>,
+    pub(crate) truncated: bool,
+    limit: usize,
+}
+
+thread_local! {
+    static WARNINGS: RefCell<Option<Recording>> = const { RefCell::new(None) };
+}
+
+/// 开始记录，最多保留 `limit` 条；返回外层（嵌套校验时）已有的记录，交给 `finish` 恢复
+pub(crate) fn start(limit: usize) -> Option<Recording> {
+    WARNINGS.with(|w| {
+        w.borrow_mut().replace(Recording {
+            limit,
+            ..Default::default()
+        })
+    })
+}
+
+pub(crate) fn finish(outer: Option<Recording>) -> Recording {
+    WARNINGS
+        .with(|w| std::mem::replace(&mut *w.borrow_mut(), outer))
+        .unwrap_or_default()
+}
+
+pub(crate) fn record(warning: ValidationError) {
+    WARNINGS.with(|w| {
+        if let Some(recording) = w.borrow_mut().as_mut() {
+            if recording.limit > 0 && recording.warnings.len() >= recording.limit {
+                recording.truncated = true;
+            } else {
+                recording.warnings.push(warning);
+            }
+        }
+    });
+}
+
+/// 已保留的警告条数，超出 `limit` 被丢弃的不计入；与 `within`、`map_since`
+/// 配合，只处理在此之后产生的警告
+pub(crate) fn mark() -> usize {
+    WARNINGS.with(|w| w.borrow().as_ref().map_or(0, |r| r.warnings.len()))
+}
+
+pub(crate) fn within(mark: usize, parent: &str) {
+    if parent.is_empty() {
+        return;
+    }
+    WARNINGS.with(|w| {
+        if let Some(recording) = w.borrow_mut().as_mut() {
+            let warnings = &mut recording.warnings;
+            let tail = warnings.split_off(mark.min(warnings.len()));
+            warnings.extend(tail.into_iter().map(|e| e.within(parent)));
+        }
+    });
+}
+
+/// 就地修改 `mark` 之后的记录，如去掉加密字段明文中的值
+pub(crate) fn map_since(mark: usize, f: impl Fn(&mut ValidationError)) {
+    WARNINGS.with(|w| {
+        if let Some(recording) = w.borrow_mut().as_mut() {
+            recording.warnings.iter_mut().skip(mark).for_each(f);
+        }
+    });
+}
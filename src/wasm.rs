@@ -0,0 +1,134 @@
+//! 以 WASM 模块形式提供的自定义校验器（`wasm` feature）。
+//!
+//! 模块在沙箱中运行：不提供任何宿主导入（依赖 WASI 或其它导入的模块无法实例化），
+//! 每次校验使用新的实例，执行步数（fuel）、线性内存与表的大小均受限，超限的增长直接 trap。
+//!
+//! 模块需导出：
+//! - `memory`：线性内存
+//! - `alloc(len: i32) -> i32`：为输入分配 `len` 字节并返回偏移
+//! - `validate(ptr: i32, len: i32) -> i32`：输入为字段值的 JSON 文本，返回 0 表示通过
+//!
+//! 可选导出 `error_ptr() -> i32` 与 `error_len() -> i32`，校验失败时从中读取 UTF-8 错误信息。
+
+use wasmtime::{Config, Engine, Instance, Memory, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::{ast::Value, json::to_json_string, registry::CustomValidator};
+
+/// -----------------------------
+/// WasmValidator
+/// -----------------------------
+pub struct WasmValidator {
+    engine: Engine,
+    module: Module,
+    fuel: u64,
+    max_memory: usize,
+    max_table_elements: usize,
+}
+
+impl WasmValidator {
+    /// 单次校验默认可消耗的 fuel
+    pub const DEFAULT_FUEL: u64 = 10_000_000;
+    /// 默认线性内存上限（16 MiB）
+    pub const DEFAULT_MAX_MEMORY: usize = 16 << 20;
+    /// 默认单个表的元素上限
+    pub const DEFAULT_MAX_TABLE_ELEMENTS: usize = 10_000;
+
+    /// 编译模块（二进制或 WAT 文本），并检查必需的导出
+    pub fn new(module: impl AsRef<[u8]>) -> Result<Self, String> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(|e| e.to_string())?;
+        let module = Module::new(&engine, module).map_err(|e| e.to_string())?;
+        for name in ["memory", "alloc", "validate"] {
+            if module.get_export(name).is_none() {
+                return Err(format!("WASM validator must export '{}'", name));
+            }
+        }
+        Ok(Self {
+            engine,
+            module,
+            fuel: Self::DEFAULT_FUEL,
+            max_memory: Self::DEFAULT_MAX_MEMORY,
+            max_table_elements: Self::DEFAULT_MAX_TABLE_ELEMENTS,
+        })
+    }
+
+    pub fn with_fuel(mut self, fuel: u64) -> Self {
+        self.fuel = fuel;
+        self
+    }
+
+    pub fn with_max_memory(mut self, bytes: usize) -> Self {
+        self.max_memory = bytes;
+        self
+    }
+
+    pub fn with_max_table_elements(mut self, elements: usize) -> Self {
+        self.max_table_elements = elements;
+        self
+    }
+
+    fn run(&self, input: &[u8]) -> wasmtime::Result<Result<(), String>> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(self.max_memory)
+            .table_elements(self.max_table_elements)
+            .instances(1)
+            .memories(1)
+            .tables(1)
+            .trap_on_grow_failure(true)
+            .build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits: &mut StoreLimits| limits);
+        store.set_fuel(self.fuel)?;
+
+        let instance = Instance::new(&mut store, &self.module, &[])?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| wasmtime::Error::msg("WASM validator must export memory 'memory'"))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let validate = instance.get_typed_func::<(i32, i32), i32>(&mut store, "validate")?;
+
+        let len = i32::try_from(input.len())
+            .map_err(|_| wasmtime::Error::msg("input too large for WASM validator"))?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory.write(&mut store, ptr as u32 as usize, input)?;
+        if validate.call(&mut store, (ptr, len))? == 0 {
+            return Ok(Ok(()));
+        }
+
+        let message = match (
+            instance.get_typed_func::<(), i32>(&mut store, "error_ptr"),
+            instance.get_typed_func::<(), i32>(&mut store, "error_len"),
+        ) {
+            (Ok(ptr), Ok(len)) => {
+                let ptr = ptr.call(&mut store, ())?;
+                let len = len.call(&mut store, ())?;
+                read_string(&memory, &store, ptr, len)?
+            }
+            _ => "rejected by WASM validator".to_string(),
+        };
+        Ok(Err(message))
+    }
+}
+
+fn read_string(
+    memory: &Memory,
+    store: &Store<StoreLimits>,
+    ptr: i32,
+    len: i32,
+) -> wasmtime::Result<String> {
+    let start = ptr as u32 as usize;
+    let end = start.saturating_add(len.max(0) as usize);
+    let bytes = memory
+        .data(store)
+        .get(start..end)
+        .ok_or_else(|| wasmtime::Error::msg("error message out of bounds"))?;
+    Ok(String::from_utf8_lossy(bytes).into_owned())
+}
+
+impl CustomValidator for WasmValidator {
+    fn check(&self, value: &Value) -> Result<(), String> {
+        self.run(to_json_string(value).as_bytes())
+            .unwrap_or_else(|e| Err(format!("WASM validator trapped: {}", e)))
+    }
+}
@@ -0,0 +1,140 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// 只统计当前线程的分配次数，其他并行运行的测试不影响结果
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+#[cfg(test)]
+mod allocation_tests {
+    use std::cell::Cell;
+
+    use super::ALLOCATIONS;
+    use zz_validator::{ast::Value, json::from_json_str, schema::Schema};
+
+    fn schema() -> Schema {
+        Schema::parse(
+            "(id:int[1, 1000000], name:string[1, 50], email:email, tags:array<string[1, 20]>, \
+             items:array<object(sku:string[1, 20], qty:int[1, 100], price:float[0, 10000])>, \
+             address:object(city:string[1, 100], zip:string[1, 10]))",
+        )
+        .unwrap()
+    }
+
+    fn payload(n: usize) -> Value {
+        let tags: Vec<String> = (0..n).map(|i| format!("\"t{}\"", i)).collect();
+        let items: Vec<String> = (0..n)
+            .map(|i| format!(r#"{{"sku": "s{}", "qty": 2, "price": 9.5}}"#, i))
+            .collect();
+        from_json_str(&format!(
+            r#"{{"id": 7, "name": "a", "email": "a@b.co", "tags": [{}], "items": [{}],
+                "address": {{"city": "x", "zip": "1"}}}}"#,
+            tags.join(","),
+            items.join(",")
+        ))
+        .unwrap()
+    }
+
+    /// 一次通过的校验的分配次数；载荷是共享存储的克隆，没有修改时不应被复制
+    fn allocations(schema: &Schema, value: &Value) -> usize {
+        let mut value = value.clone();
+        let before = ALLOCATIONS.with(Cell::get);
+        schema.validate(&mut value).unwrap();
+        ALLOCATIONS.with(Cell::get) - before
+    }
+
+    // `timing` 为每个约束记录一条耗时
+    #[cfg_attr(feature = "timing", ignore)]
+    #[test]
+    fn test_passing_validation_allocations_do_not_grow_with_payload() {
+        let schema = schema();
+        let (small, large) = (payload(2), payload(500));
+        // 预热线程局部变量与正则缓存
+        allocations(&schema, &small);
+        let n = allocations(&schema, &small);
+        assert!(n <= 8, "{} allocations", n);
+        assert_eq!(allocations(&schema, &large), n);
+    }
+
+    #[test]
+    fn test_defaults_and_errors_still_allocate_their_paths() {
+        let schema = Schema::parse("(items:array<object(sku:string, qty:int=1)>)").unwrap();
+        let mut value =
+            from_json_str(r#"{"items": [{"sku": "a"}, {"sku": "b", "qty": 2}]}"#).unwrap();
+        let report = zz_validator::validator::validate_with_report(
+            &mut value,
+            schema.rules(),
+            &Default::default(),
+        )
+        .unwrap();
+        assert_eq!(report.changes.len(), 1);
+        assert_eq!(report.changes[0].path, "items[0].qty");
+
+        let mut value = from_json_str(r#"{"items": [{"sku": "a"}, {"sku": 3}]}"#).unwrap();
+        let err = schema.validate(&mut value).unwrap_err();
+        assert_eq!(err.field(), Some("items[1].sku"));
+    }
+
+    /// 批量校验中同一段的记录共用工作栈，每条记录的分配少于单独校验
+    #[test]
+    fn test_batch_reuses_buffers_across_records() {
+        let schema = Schema::parse("(name:string[1, 32], role:string=\"guest\")").unwrap();
+        let record = from_json_str(r#"{"name": "a"}"#).unwrap();
+        let batch = |n: usize| {
+            let mut values = vec![record.clone(); n];
+            let before = ALLOCATIONS.with(Cell::get);
+            assert!(schema.validate_batch(&mut values).iter().all(Result::is_ok));
+            ALLOCATIONS.with(Cell::get) - before
+        };
+        batch(1);
+        let single = {
+            let mut value = record.clone();
+            let before = ALLOCATIONS.with(Cell::get);
+            schema.validate(&mut value).unwrap();
+            ALLOCATIONS.with(Cell::get) - before
+        };
+        let per_record = (batch(101) - batch(1)) / 100;
+        assert!(per_record < single, "{} vs {}", per_record, single);
+    }
+
+    #[test]
+    fn test_field_names_are_interned_when_compiled() {
+        let schema = Schema::parse(
+            "address = object(city: string, zip: string)
+             (home: address, work?: address, city: string)",
+        )
+        .unwrap();
+        let children = |name: &str| {
+            zz_validator::ast::find_rule(schema.rules(), name)
+                .and_then(|r| r.children.clone())
+                .unwrap()
+        };
+        let (home, work) = (children("home"), children("work"));
+        assert!(home[0].field.ptr_eq(&work[0].field));
+        assert!(home[0].field.ptr_eq(&schema.rules()[2].field));
+        assert_eq!(home[0].field, "city");
+
+        // 派生的 schema 共用原来的字段名
+        let picked = schema.pick(["city"]).unwrap();
+        assert!(picked.rules()[0].field.ptr_eq(&schema.rules()[2].field));
+    }
+}
@@ -0,0 +1,115 @@
+mod common;
+
+#[cfg(test)]
+mod array_aggregate_tests {
+    use crate::common::check_array;
+    use zz_validator::{
+        ast::{Aggregate, AggregateBound, CompareOp, Constraint, Value},
+        export::to_dsl,
+        parser::Parser,
+        validator::ValidationError,
+    };
+
+    const DSL: &str = r#"(
+        split?:array<float[0,100]> sum==100,
+        ratings?:array<int[1,5]> avg[2, 5] count>=1,
+        deltas?:array<int> count_where(>0) >= 1 count_where(!=0)<3,
+        scores?:array<float> min>0 max(0,10)
+    )"#;
+
+    fn check(field: &str, items: Vec<Value>) -> Result<(), ValidationError> {
+        check_array(DSL, field, items)
+    }
+
+    fn ints(items: &[i64]) -> Vec<Value> {
+        items.iter().map(|i| Value::Int(*i)).collect()
+    }
+
+    fn floats(items: &[f64]) -> Vec<Value> {
+        items.iter().map(|f| Value::Float(*f)).collect()
+    }
+
+    #[test]
+    fn test_parse_aggregates() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let items = |i: usize| rules[i].constraints.as_ref().unwrap().items.clone();
+
+        assert!(matches!(
+            &items(0)[0],
+            Constraint::ArrayAggregate {
+                aggregate: Aggregate::Sum,
+                bound: AggregateBound::Compare(CompareOp::Eq, Value::Int(100)),
+            }
+        ));
+        assert!(matches!(
+            &items(1)[0],
+            Constraint::ArrayAggregate {
+                aggregate: Aggregate::Avg,
+                bound: AggregateBound::Range {
+                    min: Value::Int(2),
+                    max: Value::Int(5),
+                    min_inclusive: true,
+                    max_inclusive: true,
+                },
+            }
+        ));
+        assert!(matches!(
+            &items(2)[0],
+            Constraint::ArrayAggregate {
+                aggregate: Aggregate::CountWhere(CompareOp::Gt, Value::Int(0)),
+                bound: AggregateBound::Compare(CompareOp::Ge, Value::Int(1)),
+            }
+        ));
+
+        let dsl = to_dsl(&rules);
+        assert!(dsl.contains("split?:array<float[0.0, 100.0]> sum==100"));
+        assert!(dsl.contains("deltas?:array<int> count_where(>0)>=1 count_where(!=0)<3"));
+        assert!(dsl.contains("scores?:array<float> min>0 max(0, 10)"));
+        assert_eq!(to_dsl(&Parser::parse_rules(&dsl).unwrap()), dsl);
+
+        for bad in ["sum", "sum=100", "sum<=x", "count_where>0", "avg[1 5]"] {
+            let dsl = format!("(a:array<int> {})", bad);
+            assert!(Parser::parse_rules(&dsl).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_sum_and_avg() {
+        assert!(check("split", floats(&[25.0, 25.0, 50.0])).is_ok());
+        let err = check("split", floats(&[25.0, 25.0])).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::AggregateError {
+                field: "split".into(),
+                constraint: "sum==100".into(),
+                actual: "50".into(),
+            }
+        );
+        assert_eq!(err.to_string(), "split fails sum==100 (actual 50)");
+
+        assert!(check("ratings", ints(&[2, 3, 4])).is_ok());
+        assert!(check("ratings", ints(&[1, 1, 3])).is_err());
+        // 空数组没有平均值，但 count>=1 失败
+        assert!(matches!(
+            check("ratings", vec![]),
+            Err(ValidationError::AggregateError { constraint, .. }) if constraint == "count>=1"
+        ));
+    }
+
+    #[test]
+    fn test_count_where_min_max() {
+        assert!(check("deltas", ints(&[0, 3, 0, -1])).is_ok());
+        assert!(check("deltas", ints(&[0, -3])).is_err());
+        assert!(check("deltas", ints(&[1, 2, 3])).is_err());
+
+        assert!(check("scores", floats(&[0.5, 9.5])).is_ok());
+        assert!(check("scores", vec![]).is_ok());
+        assert!(check("scores", floats(&[0.0, 5.0])).is_err());
+        assert!(check("scores", floats(&[1.0, 10.0])).is_err());
+
+        assert!(matches!(
+            check("scores", vec![Value::String("1".into())]),
+            Err(ValidationError::Custom(msg)) if msg.contains("not a number")
+        ));
+    }
+}
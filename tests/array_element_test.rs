@@ -0,0 +1,144 @@
+mod common;
+
+#[cfg(test)]
+mod array_element_tests {
+    use crate::common::check_array;
+    use zz_validator::{
+        ast::{Collation, CompareOp, Constraint, FieldCompare, Operand, Value},
+        export::to_dsl,
+        parser::Parser,
+        validator::ValidationError,
+    };
+
+    const DSL: &str = r#"(
+        slots?:array<object(start:int, end:int)>
+            each(start < end) adjacent(prev.end <= next.start),
+        bookings?:array<object(from:date, to?:date, guests:int)>
+            each(from <= to) each(guests >= 1) adjacent(prev.from < next.from)
+    )"#;
+
+    fn slot(start: i64, end: i64) -> Value {
+        Value::Object(
+            [
+                ("start".to_string(), Value::Int(start)),
+                ("end".to_string(), Value::Int(end)),
+            ]
+            .into(),
+        )
+    }
+
+    fn booking(from: &str, to: Option<&str>, guests: i64) -> Value {
+        let mut map = zz_validator::ast::ObjectMap::new();
+        map.insert("from".to_string(), Value::String(from.into()));
+        if let Some(to) = to {
+            map.insert("to".to_string(), Value::String(to.into()));
+        }
+        map.insert("guests".to_string(), Value::Int(guests));
+        Value::Object(map)
+    }
+
+    fn check(field: &str, items: Vec<Value>) -> Result<(), ValidationError> {
+        check_array(DSL, field, items)
+    }
+
+    #[test]
+    fn test_parse_element_constraints() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let items = |i: usize| rules[i].constraints.as_ref().unwrap().items.clone();
+        match &items(0)[1] {
+            Constraint::Adjacent(cmp) => assert_eq!(
+                cmp,
+                &FieldCompare {
+                    left: Operand::Field("prev.end".into()),
+                    op: CompareOp::Le,
+                    right: Operand::Field("next.start".into()),
+                    collation: Collation::Binary,
+                }
+            ),
+            other => panic!("unexpected {:?}", other),
+        }
+        match &items(1)[1] {
+            Constraint::Each(cmp) => assert_eq!(
+                cmp,
+                &FieldCompare {
+                    left: Operand::Field("guests".into()),
+                    op: CompareOp::Ge,
+                    right: Operand::Literal(Value::Int(1)),
+                    collation: Collation::Binary,
+                }
+            ),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let dsl = to_dsl(&rules);
+        assert!(dsl.contains("each(start < end) adjacent(prev.end <= next.start)"));
+        assert!(dsl.contains("each(guests >= 1)"));
+        assert_eq!(to_dsl(&Parser::parse_rules(&dsl).unwrap()), dsl);
+
+        for bad in [
+            "each(start)",
+            "each(start < )",
+            "each(1 < 2)",
+            "adjacent(end <= next.start)",
+            "adjacent(prev. <= next.start)",
+        ] {
+            let dsl = format!("(a:array<object(start:int, end:int)> {})", bad);
+            assert!(Parser::parse_rules(&dsl).is_err(), "{}", bad);
+        }
+        // 小数点开头的数字仍按数字解析
+        assert!(Parser::parse_rules("(a:float[.5, 1])").is_ok());
+    }
+
+    #[test]
+    fn test_each_and_adjacent() {
+        assert!(check("slots", vec![]).is_ok());
+        assert!(check("slots", vec![slot(1, 3), slot(3, 5), slot(8, 9)]).is_ok());
+
+        let err = check("slots", vec![slot(1, 3), slot(4, 4)]).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::ElementConstraint {
+                field: "slots".into(),
+                index: 1,
+                constraint: "each(start < end)".into(),
+            }
+        );
+        assert_eq!(err.to_string(), "slots[1] fails each(start < end)");
+
+        // 区间重叠
+        assert!(matches!(
+            check("slots", vec![slot(1, 3), slot(5, 7), slot(6, 8)]),
+            Err(ValidationError::ElementConstraint { index: 2, constraint, .. })
+                if constraint == "adjacent(prev.end <= next.start)"
+        ));
+    }
+
+    #[test]
+    fn test_element_dates_and_missing_fields() {
+        assert!(
+            check(
+                "bookings",
+                vec![
+                    booking("2024-01-01", Some("2024-01-03"), 2),
+                    booking("2024-01-02", None, 1),
+                ]
+            )
+            .is_ok()
+        );
+        assert!(
+            check(
+                "bookings",
+                vec![booking("2024-01-05", Some("2024-01-03"), 2)]
+            )
+            .is_err()
+        );
+        assert!(matches!(
+            check(
+                "bookings",
+                vec![booking("2024-01-01", None, 1), booking("2024-01-01", None, 0)]
+            ),
+            Err(ValidationError::ElementConstraint { constraint, .. })
+                if constraint == "each(guests >= 1)"
+        ));
+    }
+}
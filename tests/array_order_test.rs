@@ -0,0 +1,108 @@
+mod common;
+
+#[cfg(test)]
+mod array_order_tests {
+    use crate::common::{check_array, strings};
+    use zz_validator::{
+        ast::{Constraint, Value},
+        export::to_dsl,
+        parser::Parser,
+        validator::ValidationError,
+    };
+
+    const DSL: &str = r#"(
+        points?:array<float> sorted,
+        ranks?:array<int> sorted(desc),
+        cursors?:array<int> strictly_increasing,
+        days?:array<date> strictly_decreasing,
+        events?:array<datetime> sorted(asc)
+    )"#;
+
+    fn check(field: &str, items: Vec<Value>) -> Result<(), ValidationError> {
+        check_array(DSL, field, items)
+    }
+
+    #[test]
+    fn test_parse_sorted() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let first = |i: usize| rules[i].constraints.as_ref().unwrap().items[0].clone();
+        assert!(matches!(
+            first(0),
+            Constraint::Sorted {
+                descending: false,
+                strict: false,
+                ..
+            }
+        ));
+        assert!(matches!(
+            first(3),
+            Constraint::Sorted {
+                descending: true,
+                strict: true,
+                ..
+            }
+        ));
+
+        let dsl = to_dsl(&rules);
+        assert!(dsl.contains("points?:array<float> sorted(asc)"));
+        assert!(dsl.contains("cursors?:array<int> strictly_increasing"));
+        assert_eq!(to_dsl(&Parser::parse_rules(&dsl).unwrap()), dsl);
+
+        assert!(Parser::parse_rules("(a:array<int> sorted(up))").is_err());
+    }
+
+    #[test]
+    fn test_sorted_numbers() {
+        assert!(check("points", vec![]).is_ok());
+        assert!(check("points", vec![Value::Float(1.0)]).is_ok());
+        assert!(
+            check(
+                "points",
+                vec![Value::Float(-1.5), Value::Float(-1.5), Value::Float(2.0)]
+            )
+            .is_ok()
+        );
+        assert!(check("ranks", vec![Value::Int(3), Value::Int(3), Value::Int(1)]).is_ok());
+
+        let err = check("ranks", vec![Value::Int(3), Value::Int(1), Value::Int(2)]).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::NotSorted {
+                field: "ranks".into(),
+                index: 2,
+                order: "sorted(desc)".into(),
+            }
+        );
+        assert_eq!(err.to_string(), "ranks[2] breaks sorted(desc) order");
+
+        assert!(check("cursors", vec![Value::Int(1), Value::Int(2), Value::Int(5)]).is_ok());
+        assert!(matches!(
+            check("cursors", vec![Value::Int(1), Value::Int(1)]),
+            Err(ValidationError::NotSorted { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_sorted_dates() {
+        assert!(check("days", strings(&["2024-03-01", "2024-02-29", "2023-12-31"])).is_ok());
+        assert!(check("days", strings(&["2024-03-01", "2024-03-01"])).is_err());
+        assert!(
+            check(
+                "events",
+                strings(&[
+                    "2024-01-01T09:00:00Z",
+                    "2024-01-01T09:00:00Z",
+                    "2024-01-01T10:00:00Z"
+                ])
+            )
+            .is_ok()
+        );
+        assert!(
+            check(
+                "events",
+                strings(&["2024-01-02T00:00:00Z", "2024-01-01T23:59:59Z"])
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,103 @@
+mod common;
+
+#[cfg(test)]
+mod array_unique_tests {
+    use crate::common::check_array;
+    use zz_validator::{
+        ast::{Constraint, Value},
+        export::to_dsl,
+        parser::Parser,
+        validator::ValidationError,
+    };
+
+    const DSL: &str = r#"(
+        users?:array<object(id:int, email:string, profile?:object(handle:string))>
+            unique_by(id) unique_by(email),
+        members?:array<object(profile:object(handle:string))> unique_by("profile.handle")
+    )"#;
+
+    fn user(id: i64, email: &str) -> Value {
+        Value::Object(
+            [
+                ("id".to_string(), Value::Int(id)),
+                ("email".to_string(), Value::String(email.into())),
+            ]
+            .into(),
+        )
+    }
+
+    fn member(handle: &str) -> Value {
+        let profile = Value::Object([("handle".to_string(), Value::String(handle.into()))].into());
+        Value::Object([("profile".to_string(), profile)].into())
+    }
+
+    fn check(field: &str, items: Vec<Value>) -> Result<(), ValidationError> {
+        check_array(DSL, field, items)
+    }
+
+    #[test]
+    fn test_parse_unique_by() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let items = |i: usize| rules[i].constraints.as_ref().unwrap().items.clone();
+        assert!(matches!(
+            &items(0)[..],
+            [Constraint::UniqueBy(a), Constraint::UniqueBy(b)] if a == "id" && b == "email"
+        ));
+        assert!(matches!(
+            &items(1)[..],
+            [Constraint::UniqueBy(p)] if p == "profile.handle"
+        ));
+
+        let dsl = to_dsl(&rules);
+        assert!(dsl.contains("unique_by(id) unique_by(email)"));
+        assert!(dsl.contains(r#"unique_by("profile.handle")"#));
+        assert_eq!(to_dsl(&Parser::parse_rules(&dsl).unwrap()), dsl);
+
+        assert!(Parser::parse_rules("(a:array<object(id:int)> unique_by)").is_err());
+    }
+
+    #[test]
+    fn test_unique_by_reports_indices() {
+        assert!(check("users", vec![]).is_ok());
+        assert!(check("users", vec![user(1, "a@x.io"), user(2, "b@x.io")]).is_ok());
+
+        let err = check(
+            "users",
+            vec![
+                user(1, "a@x.io"),
+                user(2, "b@x.io"),
+                user(1, "c@x.io"),
+                user(1, "d@x.io"),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::DuplicateKey {
+                field: "users".into(),
+                key: "id".into(),
+                value: "1".into(),
+                indices: vec![0, 2, 3],
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "users has duplicate id = 1 at indices [0, 2, 3]"
+        );
+
+        assert!(matches!(
+            check("users", vec![user(1, "a@x.io"), user(2, "a@x.io")]),
+            Err(ValidationError::DuplicateKey { key, indices, .. })
+                if key == "email" && indices == vec![0, 1]
+        ));
+    }
+
+    #[test]
+    fn test_unique_by_nested_path() {
+        assert!(check("members", vec![member("ann"), member("bob")]).is_ok());
+        assert!(matches!(
+            check("members", vec![member("ann"), member("bob"), member("ann")]),
+            Err(ValidationError::DuplicateKey { indices, .. }) if indices == vec![0, 2]
+        ));
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+#[cfg(feature = "artifact")]
+mod artifact_tests {
+    use zz_validator::{json::from_json_str, parser::Parser, schema::Schema};
+
+    const DSL: &str = r#"meta(name="Order", version=2)
+    (
+        id:uuid(v4),
+        email:email,
+        status:string enum("open", "closed") = "open",
+        amount:float[0, 1000) multiple_of(0.01),
+        code?:string[4,4] regex("^[0-9]+$") luhn,
+        owner?:uuid|int,
+        placed?:date past,
+        items:array<object(sku:string[1,20], qty:int[1,99])>[1,10] unique_by(sku),
+        scores?:array<int> sorted(asc) sum<=100,
+        color?:color(hex, alpha)
+    )"#;
+
+    #[test]
+    fn test_round_trip() {
+        let schema = Schema::parse(DSL).unwrap();
+        let bytes = schema.serialize_compiled().unwrap();
+        assert!(bytes.starts_with(b"VDSL"));
+
+        let loaded = Schema::deserialize_compiled(&bytes).unwrap();
+        assert_eq!(loaded.metadata(), schema.metadata());
+        assert_eq!(loaded.to_dsl(), schema.to_dsl());
+
+        for json in [
+            r#"{"id": "3f2b8c1e-4d5a-4b6c-8d7e-9f0a1b2c3d4e", "email": "a@b.co", "amount": 12.5,
+                "items": [{"sku": "a", "qty": 1}], "scores": [1, 2, 3]}"#,
+            r#"{"id": "3f2b8c1e-4d5a-4b6c-8d7e-9f0a1b2c3d4e", "email": "a@b.co", "amount": 12.505,
+                "items": [{"sku": "a", "qty": 1}]}"#,
+            r#"{"id": "3f2b8c1e-4d5a-4b6c-8d7e-9f0a1b2c3d4e", "email": "a@b.co", "amount": 1,
+                "items": [{"sku": "a", "qty": 1}, {"sku": "a", "qty": 2}]}"#,
+            r#"{"id": "x", "email": "a@b.co", "amount": 1, "items": []}"#,
+            r#"{"id": "3f2b8c1e-4d5a-4b6c-8d7e-9f0a1b2c3d4e", "email": "a@b.co", "amount": 1,
+                "items": [{"sku": "a", "qty": 1}], "status": "void"}"#,
+        ] {
+            let mut a = from_json_str(json).unwrap();
+            let mut b = a.clone();
+            assert_eq!(schema.validate(&mut a), loaded.validate(&mut b), "{}", json);
+            assert_eq!(a, b);
+        }
+    }
+
+    #[test]
+    fn test_rejects_foreign_bytes() {
+        let bytes = Schema::parse("(a:int)")
+            .unwrap()
+            .serialize_compiled()
+            .unwrap();
+
+        let err = Schema::deserialize_compiled(b"(a:int)").unwrap_err();
+        assert_eq!(err, "Not a compiled schema artifact");
+
+        let mut other = b"VDSL".to_vec();
+        other.push(5);
+        other.extend_from_slice(b"0.0.1");
+        other.extend_from_slice(&bytes[4 + 1 + env!("CARGO_PKG_VERSION").len()..]);
+        let err = Schema::deserialize_compiled(&other).unwrap_err();
+        assert!(err.contains("built by version 0.0.1"), "{}", err);
+
+        let err = Schema::deserialize_compiled(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert!(
+            err.starts_with("Invalid compiled schema artifact"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_rejects_mismatched_compiled_data() {
+        // 联合类型的尝试顺序 [0, 1, 2] 位于产物末尾的预计算数据中，改为越界的下标
+        let mut bytes = Schema::parse("(a:int|string|bool, b?:int)")
+            .unwrap()
+            .serialize_compiled()
+            .unwrap();
+        let at = bytes.windows(4).rposition(|w| w == [3, 0, 1, 2]).unwrap();
+        bytes[at + 3] = 7;
+        let err = Schema::deserialize_compiled(&bytes).unwrap_err();
+        assert_eq!(
+            err,
+            "Invalid compiled schema artifact: compiled data does not match rules"
+        );
+
+        // 未经检查的规则在加载时同样被拒绝
+        let rules = Parser::parse_rules("(a:int[10,1])").unwrap();
+        let bytes = Schema::new(rules).serialize_compiled().unwrap();
+        let err = Schema::deserialize_compiled(&bytes).unwrap_err();
+        assert!(
+            err.starts_with("Invalid compiled schema artifact: "),
+            "{}",
+            err
+        );
+    }
+}
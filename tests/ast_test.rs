@@ -2,7 +2,7 @@
 mod tests {
     use zz_validator::ast::{Constraint, Constraints, FieldRule, FieldType, Value};
 
-    use std::collections::HashMap;
+    use zz_validator::ast::ObjectMap;
 
     #[test]
     fn test_field_type_variants() {
@@ -53,8 +53,8 @@ mod tests {
         assert_eq!(v_int.as_str(), None);
 
         // 3. Float
-        let v_float = Value::Float(3.14);
-        assert_eq!(v_float.as_float(), Some(3.14));
+        let v_float = Value::Float(2.5);
+        assert_eq!(v_float.as_float(), Some(2.5));
         assert_eq!(v_float.as_bool(), None);
 
         // 4. Bool
@@ -63,7 +63,7 @@ mod tests {
         assert_eq!(v_bool.as_float(), None);
 
         // 5. Object & Mut
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("key".to_string(), Value::Int(1));
         let mut v_obj = Value::Object(map);
         assert!(v_obj.as_object().is_some());
@@ -104,7 +104,7 @@ mod tests {
     fn test_field_rule_structure() {
         // 测试复杂的递归结构以覆盖所有字段
         let rule = FieldRule {
-            field: "username".to_string(),
+            field: "username".into(),
             field_type: FieldType::String,
             required: true,
             default: Some(Value::String("guest".to_string())),
@@ -114,7 +114,7 @@ mod tests {
                 items: vec![Constraint::Regex("^[a-z]+$".to_string())],
             }),
             rule: Some(Box::new(FieldRule {
-                field: "sub".to_string(),
+                field: "sub".into(),
                 field_type: FieldType::Int,
                 required: false,
                 default: None,
@@ -133,4 +133,103 @@ mod tests {
         assert_eq!(cloned_rule.field, "username");
         assert!(format!("{:?}", rule).contains("username"));
     }
+
+    #[test]
+    fn test_value_eq_and_hash() {
+        use std::collections::HashSet;
+
+        // 浮点约定：NaN 与自身相等，0.0 与 -0.0 相等
+        assert_eq!(Value::Float(f64::NAN), Value::Float(f64::NAN));
+        assert_eq!(Value::Float(0.0), Value::Float(-0.0));
+        assert_ne!(Value::Int(1), Value::Float(1.0));
+
+        let object = |entries: &[(&str, Value)]| {
+            Value::Object(
+                entries
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.clone()))
+                    .collect::<ObjectMap>(),
+            )
+        };
+        let set: HashSet<Value> = [
+            Value::Float(f64::NAN),
+            Value::Float(-0.0),
+            Value::Int(1),
+            Value::String("a".into()),
+            object(&[("x", Value::Int(1)), ("y", Value::Bool(true))]),
+            Value::Array(vec![Value::Int(1), Value::Int(2)]),
+        ]
+        .into_iter()
+        .collect();
+
+        assert!(set.contains(&Value::Float(-f64::NAN)));
+        assert!(set.contains(&Value::Float(0.0)));
+        assert!(set.contains(&Value::Int(1)));
+        assert!(!set.contains(&Value::Float(1.0)));
+        // 对象的哈希与键的顺序无关
+        assert!(set.contains(&object(&[("y", Value::Bool(true)), ("x", Value::Int(1))])));
+        assert!(!set.contains(&object(&[("x", Value::Int(1))])));
+        assert!(!set.contains(&Value::Array(vec![Value::Int(2), Value::Int(1)])));
+    }
+
+    #[test]
+    fn test_value_total_order() {
+        use std::cmp::Ordering;
+
+        let mut values = vec![
+            Value::Object([("a".to_string(), Value::Int(1))].into()),
+            Value::Array(vec![Value::Int(1)]),
+            Value::String("b".into()),
+            Value::Float(f64::NAN),
+            Value::Float(1.5),
+            Value::Float(1.0),
+            Value::Int(1),
+            Value::Int(i64::MAX),
+            Value::Float(f64::NEG_INFINITY),
+            Value::String("a".into()),
+            Value::Bool(true),
+            Value::Bool(false),
+        ];
+        values.sort();
+        assert_eq!(
+            values,
+            vec![
+                Value::Bool(false),
+                Value::Bool(true),
+                Value::Float(f64::NEG_INFINITY),
+                // 数值相等时 int 在前
+                Value::Int(1),
+                Value::Float(1.0),
+                Value::Float(1.5),
+                Value::Int(i64::MAX),
+                // NaN 大于所有数字
+                Value::Float(f64::NAN),
+                Value::String("a".into()),
+                Value::String("b".into()),
+                Value::Array(vec![Value::Int(1)]),
+                Value::Object([("a".to_string(), Value::Int(1))].into()),
+            ]
+        );
+
+        // int 与 float 精确比较：2^53 + 1 转为 f64 会丢失精度
+        let big = (1i64 << 53) + 1;
+        assert!(Value::Int(big) > Value::Float((1i64 << 53) as f64));
+        assert!(Value::Int(-1) > Value::Float(-1.5));
+        assert_eq!(Value::Float(0.0).cmp(&Value::Float(-0.0)), Ordering::Equal);
+
+        // 对象按排序后的键值对比较，与插入顺序无关
+        let obj = |entries: [(&str, i64); 2]| {
+            Value::Object(
+                entries
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), Value::Int(*v)))
+                    .collect(),
+            )
+        };
+        assert_eq!(
+            obj([("a", 1), ("b", 2)]).cmp(&obj([("b", 2), ("a", 1)])),
+            Ordering::Equal
+        );
+        assert!(obj([("a", 1), ("b", 2)]) < obj([("a", 1), ("b", 3)]));
+    }
 }
@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod audit_tests {
+    use std::sync::Arc;
+
+    use zz_validator::{
+        audit::{AuditOutcome, FileAuditSink, MemoryAuditSink, fingerprint, payload_hash},
+        clock::FixedClock,
+        json::from_json_str,
+        parser::Parser,
+        schema::Schema,
+        validator::{ValidationContext, ValidationError, ValidationOptions},
+    };
+
+    const DSL: &str = "(name:string[1,10], age:int[0,150]=18)";
+
+    fn ctx(sink: Arc<MemoryAuditSink>) -> ValidationContext {
+        ValidationContext::new()
+            .clock(FixedClock(1_700_000_000))
+            .audit(sink)
+    }
+
+    #[test]
+    fn test_memory_sink_records_every_validation() {
+        let schema = Schema::parse(DSL).unwrap();
+        let sink = Arc::new(MemoryAuditSink::new());
+        let ctx = ctx(sink.clone());
+
+        let mut ok = from_json_str(r#"{"name": "alice"}"#).unwrap();
+        let original = payload_hash(&ok);
+        assert!(schema.validate_with_context(&mut ok, &ctx).is_ok());
+        let mut bad = from_json_str(r#"{"name": "", "age": 200}"#).unwrap();
+        assert!(schema.validate_with_context(&mut bad, &ctx).is_err());
+
+        let records = sink.take();
+        assert_eq!(records.len(), 2);
+        assert!(sink.records().is_empty());
+
+        let passed = &records[0];
+        assert_eq!(passed.outcome, AuditOutcome::Passed);
+        assert!(passed.errors.is_empty());
+        assert_eq!(passed.timestamp, 1_700_000_000);
+        assert_eq!(passed.schema_fingerprint, fingerprint(schema.rules()));
+        assert_eq!(passed.schema_fingerprint.len(), 64);
+        // 哈希的是校验前的载荷，不含填充的默认值
+        assert_eq!(passed.payload_hash, original);
+        assert_ne!(passed.payload_hash, payload_hash(&ok));
+
+        let failed = &records[1];
+        assert_eq!(failed.outcome, AuditOutcome::Failed);
+        assert!(matches!(
+            failed.errors.as_slice(),
+            [ValidationError::RangeError { field, .. }] if field == "name"
+        ));
+    }
+
+    #[test]
+    fn test_hashes_are_stable() {
+        // 写法不同但规则相同，指纹相同
+        let a = Parser::parse_rules("(name:string[1,10],age:int[0,150]=18)").unwrap();
+        let b = Parser::parse_rules(DSL).unwrap();
+        assert_eq!(fingerprint(&a), fingerprint(&b));
+        assert_ne!(
+            fingerprint(&a),
+            fingerprint(&Parser::parse_rules("(name:string[1,11])").unwrap())
+        );
+
+        // 与键的顺序无关
+        let x = from_json_str(r#"{"a": 1, "b": [true, "x"]}"#).unwrap();
+        let y = from_json_str(r#"{"b": [true, "x"], "a": 1}"#).unwrap();
+        assert_eq!(payload_hash(&x), payload_hash(&y));
+        assert_ne!(
+            payload_hash(&x),
+            payload_hash(&from_json_str(r#"{"a": 2}"#).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_exhaustive_errors_are_expanded() {
+        let schema = Schema::parse(r#"(code:string[5,10] regex("^[0-9]+$"))"#).unwrap();
+        let sink = Arc::new(MemoryAuditSink::new());
+        let ctx = ctx(sink.clone()).options(ValidationOptions {
+            exhaustive: true,
+            ..Default::default()
+        });
+        let mut value = from_json_str(r#"{"code": "ab"}"#).unwrap();
+        assert!(schema.validate_with_context(&mut value, &ctx).is_err());
+        assert_eq!(sink.records()[0].errors.len(), 2);
+    }
+
+    #[test]
+    fn test_file_sink_appends_json_lines() {
+        let dir = std::env::temp_dir().join("zz_validator_audit_tests");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("audit.ndjson");
+        let _ = std::fs::remove_file(&path);
+
+        let schema = Schema::parse(DSL).unwrap();
+        for body in [r#"{"name": "alice"}"#, r#"{"age": 3}"#] {
+            // 每次重新打开，确认是追加写入
+            let ctx = ValidationContext::new()
+                .clock(FixedClock(42))
+                .audit(FileAuditSink::open(&path).unwrap());
+            let mut value = from_json_str(body).unwrap();
+            let _ = schema.validate_with_context(&mut value, &ctx);
+        }
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["outcome"], "passed");
+        assert_eq!(lines[0]["timestamp"], 42);
+        assert_eq!(lines[0]["schema"], fingerprint(schema.rules()));
+        assert_eq!(lines[1]["outcome"], "failed");
+        assert_eq!(lines[1]["errors"][0], "Missing required field name");
+    }
+}
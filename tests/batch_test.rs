@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod batch_tests {
+    use zz_validator::{
+        ast::Value,
+        json::from_json_str,
+        report::{Change, ValidationReport},
+        schema::Schema,
+        validator::{ValidationContext, ValidationError, ValidationOptions, validate_with_report},
+    };
+
+    /// 报告中除耗时（`timing` feature）外的内容
+    fn outline(report: &ValidationReport) -> (&[Change], &[ValidationError]) {
+        (&report.changes, &report.warnings)
+    }
+
+    fn records(n: usize) -> Vec<Value> {
+        (0..n)
+            .map(|i| {
+                let age = if i % 7 == 3 { 200 } else { i % 100 };
+                from_json_str(&format!(r#"{{"name": "user{}", "age": {}}}"#, i, age)).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_batch() {
+        let schema =
+            Schema::parse("(name:string[1,32], age:int[0,150], role:string=\"guest\")").unwrap();
+        let mut values = records(20);
+        let results = schema.validate_batch(&mut values);
+        assert_eq!(results.len(), 20);
+        for (i, result) in results.iter().enumerate() {
+            if i % 7 == 3 {
+                let report = result.as_ref().unwrap_err();
+                assert_eq!(report.fields[0].path, "age");
+            } else {
+                assert!(result.is_ok(), "{}", i);
+                // 默认值写回每条记录
+                assert_eq!(
+                    values[i].as_object().unwrap().get("role"),
+                    Some(&Value::String("guest".into()))
+                );
+            }
+        }
+        assert!(schema.validate_batch(&mut []).is_empty());
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let schema =
+            Schema::parse("(name:string[1,32], age:int[0,150], role:string=\"guest\")").unwrap();
+        let mut expected_values = records(1000);
+        let expected = schema.validate_batch(&mut expected_values);
+        for threads in [0, 1, 3, 8, 5000] {
+            let mut values = records(1000);
+            let results =
+                schema.validate_batch_parallel(&mut values, &ValidationContext::new(), threads);
+            assert!(
+                results
+                    .iter()
+                    .map(|r| r.as_ref().map(outline))
+                    .eq(expected.iter().map(|r| r.as_ref().map(outline))),
+                "threads={}",
+                threads
+            );
+            assert_eq!(values, expected_values);
+        }
+    }
+
+    /// 收尾的规范化只作用于通过的记录；frozen 时收尾产生的修改同样报错
+    #[test]
+    fn test_batch_finishes_passing_records() {
+        let schema = Schema::parse("(color:color, age:int[0,150])").unwrap();
+        let batch = |frozen: bool| {
+            let mut values = vec![
+                from_json_str(r##"{"color": "#ABC", "age": 1}"##).unwrap(),
+                from_json_str(r##"{"color": "#ABC", "age": 200}"##).unwrap(),
+            ];
+            let ctx = ValidationContext::new().options(ValidationOptions {
+                canonicalize: true,
+                frozen,
+                ..Default::default()
+            });
+            let results = schema.validate_batch_parallel(&mut values, &ctx, 1);
+            let colors: Vec<_> = values
+                .iter()
+                .map(|v| v.as_object().unwrap().get("color").cloned().unwrap())
+                .collect();
+            (results, colors)
+        };
+
+        let (results, colors) = batch(false);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert_eq!(
+            colors,
+            [
+                Value::String("#aabbcc".into()),
+                Value::String("#ABC".into())
+            ]
+        );
+
+        let (results, colors) = batch(true);
+        assert!(results.iter().all(Result::is_err));
+        assert!(colors.iter().all(|c| *c == Value::String("#ABC".into())));
+    }
+
+    /// 通过的记录与单独校验得到相同的报告，警告不会丢失
+    #[test]
+    fn test_batch_reports_match_single_validation() {
+        let schema =
+            Schema::parse("(name:string warn([1,3]), age:int[0,150], role:string=\"guest\")")
+                .unwrap();
+        let mut values = records(10);
+        let mut expected_values = values.clone();
+        let results = schema.validate_batch(&mut values);
+        for (result, value) in results.iter().zip(&mut expected_values) {
+            assert_eq!(
+                result
+                    .as_ref()
+                    .map(outline)
+                    .map_err(|e| e.fields[0].path.as_str()),
+                validate_with_report(value, schema.rules(), &ValidationContext::new())
+                    .as_ref()
+                    .map(outline)
+                    .map_err(|e| e.field().unwrap_or_default())
+            );
+        }
+        assert_eq!(values, expected_values);
+        let report = results[0].as_ref().unwrap();
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.changes.len(), 1);
+    }
+}
@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod bigint_tests {
+    use zz_validator::{ast::Value, json::from_json_str};
+
+    const SNOWFLAKE: &str = "123456789012345678901234567890";
+
+    #[cfg(not(feature = "bigint"))]
+    #[test]
+    fn test_large_integers_without_feature() {
+        assert_eq!(Value::integer(SNOWFLAKE), None);
+        let value = from_json_str(&format!("{{\"n\": {}}}", u64::MAX)).unwrap();
+        assert_eq!(
+            value.as_object().unwrap()["n"],
+            Value::Float(u64::MAX as f64)
+        );
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_large_integers_parse_as_bigint() {
+        use num_bigint::BigInt;
+        use zz_validator::json::to_json_string;
+
+        let value = from_json_str(&format!(
+            "{{\"id\": {}, \"max\": {}, \"small\": 7, \"ratio\": 0.5}}",
+            SNOWFLAKE,
+            u64::MAX
+        ))
+        .unwrap();
+        let obj = value.as_object().unwrap();
+        assert_eq!(obj["id"], Value::BigInt(SNOWFLAKE.parse().unwrap()));
+        assert_eq!(obj["max"], Value::BigInt(BigInt::from(u64::MAX)));
+        assert_eq!(obj["small"], Value::Int(7));
+        assert_eq!(obj["ratio"], Value::Float(0.5));
+
+        // 输出时保留原文，不经过 f64
+        assert!(to_json_string(&value).contains(SNOWFLAKE));
+
+        // i64 范围内的字面量仍为 Int
+        assert_eq!(Value::integer("-42"), Some(Value::Int(-42)));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_bigint_type_and_range() {
+        use zz_validator::{schema::Schema, validator::ValidationError};
+
+        let schema = Schema::parse(
+            "(id:int, amount:int[0, 1000000000000000000000000], small?:int[0, 10], ratio?:float[0, 1])",
+        )
+        .unwrap();
+        let check = |json: &str| {
+            let streamed = schema.validate_json_bytes(json.as_bytes());
+            let mut value = from_json_str(json).unwrap();
+            let parsed = schema.validate(&mut value);
+            assert_eq!(streamed, parsed, "{}", json);
+            parsed
+        };
+
+        assert!(
+            check(&format!(
+                r#"{{"id": {}, "amount": 5, "ratio": 0.25}}"#,
+                SNOWFLAKE
+            ))
+            .is_ok()
+        );
+        assert!(check(r#"{"id": 1, "amount": 999999999999999999999999}"#).is_ok());
+        assert!(matches!(
+            check(r#"{"id": 1, "amount": 1000000000000000000000001}"#),
+            Err(ValidationError::RangeError { field, .. }) if field == "amount"
+        ));
+        assert!(matches!(
+            check(&format!(r#"{{"id": 1, "amount": 1, "small": {}}}"#, SNOWFLAKE)),
+            Err(ValidationError::RangeError { field, .. }) if field == "small"
+        ));
+        assert!(check(r#"{"id": 1, "amount": -1}"#).is_err());
+        assert!(check(r#"{"id": 1, "amount": 1, "ratio": 1.5}"#).is_err());
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_bigint_comparison_and_conversion() {
+        use zz_validator::{ast::compare_numbers, convert::FromValue};
+
+        let big = Value::integer(SNOWFLAKE).unwrap();
+        let above_i64 = Value::integer("9223372036854775808").unwrap();
+        assert!(above_i64 > Value::Int(i64::MAX));
+        assert!(Value::Float(1e30) > big);
+        assert!(Value::Float(1e29) < big);
+        assert!(big < Value::Float(f64::INFINITY));
+        assert_eq!(compare_numbers(&big, &Value::Float(f64::NAN)), None);
+
+        let mut values = vec![big.clone(), Value::Int(1), above_i64.clone()];
+        values.sort();
+        assert_eq!(values, vec![Value::Int(1), above_i64.clone(), big.clone()]);
+
+        assert_eq!(u64::from_value(&above_i64, "n"), Ok(1u64 << 63));
+        assert!(i64::from_value(&above_i64, "n").is_err());
+        assert_eq!(
+            num_bigint::BigInt::from_value(&big, "n")
+                .unwrap()
+                .to_string(),
+            SNOWFLAKE
+        );
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_typed_view_reads_big_ints() {
+        use num_bigint::BigInt;
+        use zz_validator::schema::Schema;
+
+        let schema = Schema::parse("(n:int, small:int, m?:int)").unwrap();
+        let mut value = from_json_str(&format!("{{\"n\": {}, \"small\": 7}}", SNOWFLAKE)).unwrap();
+        let view = schema.typed_view(&mut value).unwrap();
+        assert_eq!(view.get_big_int("n"), SNOWFLAKE.parse::<BigInt>().unwrap());
+        assert_eq!(view.get_big_int("small"), BigInt::from(7));
+        assert_eq!(view.get_int("small"), 7);
+        assert_eq!(view.get_opt_big_int("m"), None);
+
+        let result = std::panic::catch_unwind(|| view.get_int("n"));
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("use get_big_int"), "{}", message);
+    }
+}
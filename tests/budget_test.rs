@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod budget_tests {
+    use std::time::Duration;
+
+    use zz_validator::{
+        ast::Value,
+        json::from_json_str,
+        schema::Schema,
+        validator::{Budget, ValidationContext, ValidationError, ValidationOptions},
+    };
+
+    fn ctx(max_nodes: usize, max_time: Option<Duration>) -> ValidationContext {
+        ValidationContext::new().options(ValidationOptions {
+            budget: Budget {
+                max_nodes,
+                max_time,
+            },
+            ..Default::default()
+        })
+    }
+
+    fn items(n: usize) -> Value {
+        let items: Vec<String> = (0..n).map(|i| i.to_string()).collect();
+        from_json_str(&format!(r#"{{"items": [{}]}}"#, items.join(","))).unwrap()
+    }
+
+    #[test]
+    fn test_max_nodes() {
+        let schema = Schema::parse("(items:array<int>)").unwrap();
+        let err = schema
+            .validate_with_context(&mut items(1000), &ctx(100, None))
+            .unwrap_err();
+        // 字段本身是第 1 个节点，第 101 个节点是下标 99 的元素
+        assert_eq!(
+            err,
+            ValidationError::BudgetExceeded {
+                field: "items[99]".into(),
+                limit: "100 nodes".into()
+            }
+        );
+        assert_eq!(err.code(), "budget_exceeded");
+        assert_eq!(
+            err.to_string(),
+            "Validation budget of 100 nodes exceeded at items[99]"
+        );
+        assert!(
+            schema
+                .validate_with_context(&mut items(99), &ctx(100, None))
+                .is_ok()
+        );
+        // 默认不限制
+        assert!(schema.validate(&mut items(10_000)).is_ok());
+    }
+
+    #[test]
+    fn test_max_time() {
+        let schema = Schema::parse("(name:string, items:array<int>)").unwrap();
+        let mut value = from_json_str(r#"{"name": "a", "items": [1, 2]}"#).unwrap();
+        let err = schema
+            .validate_with_context(&mut value, &ctx(0, Some(Duration::ZERO)))
+            .unwrap_err();
+        assert!(
+            matches!(err, ValidationError::BudgetExceeded { ref field, .. } if field == "name")
+        );
+        assert!(
+            schema
+                .validate_with_context(&mut value, &ctx(0, Some(Duration::from_secs(60))))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_budget_per_validation() {
+        let schema = Schema::parse("(items:array<int>)").unwrap();
+        let ctx = ctx(20, None);
+        // 每条记录各自计量
+        let mut records: Vec<Value> = (0..5).map(|_| items(15)).collect();
+        assert!(
+            schema
+                .validate_batch_with_context(&mut records, &ctx)
+                .iter()
+                .all(Result::is_ok)
+        );
+
+        // 逐个字段的结论共用一份预算，用完之后的字段都报告超出
+        let schema = Schema::parse("(a:array<int>, b:array<int>)").unwrap();
+        let value = from_json_str(r#"{"a": [1, 2, 3], "b": [4, 5, 6]}"#).unwrap();
+        let outcomes = schema.validate_outcomes_with_context(
+            &value,
+            &ValidationContext::new().options(ValidationOptions {
+                budget: Budget {
+                    max_nodes: 6,
+                    max_time: None,
+                },
+                ..Default::default()
+            }),
+        );
+        assert!(!outcomes.is_valid());
+        assert!(
+            schema
+                .validate_outcomes_with_context(&value, &ValidationContext::new())
+                .is_valid()
+        );
+    }
+}
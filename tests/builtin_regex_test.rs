@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod builtin_regex_tests {
+    use regex::Regex;
+    use zz_validator::{
+        ast::Value,
+        parser::Parser,
+        validator::{ValidationError, builtin_patterns, validate_object},
+    };
+
+    #[test]
+    fn test_every_builtin_pattern_compiles() {
+        let patterns = builtin_patterns();
+        assert!(patterns.len() >= 20);
+        for (name, pattern) in patterns {
+            assert!(name.ends_with("_RE"), "{}", name);
+            assert!(
+                pattern.starts_with('^') && pattern.ends_with('$'),
+                "{}",
+                name
+            );
+            if let Err(e) = Regex::new(pattern) {
+                panic!("{} does not compile: {}", name, e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_user_pattern_errors_instead_of_panicking() {
+        // regex crate 不支持环视与反向引用
+        for pattern in ["(?=a)b", r"(a)\1", "[unclosed", "a{99999}{99999}"] {
+            let dsl = format!("(code:string regex(\"{}\"))", pattern.replace('\\', "\\\\"));
+            let rules = Parser::parse_rules(&dsl).unwrap();
+            let mut value =
+                Value::Object([("code".to_string(), Value::String("ab".into()))].into());
+            assert!(
+                matches!(
+                    validate_object(&mut value, &rules),
+                    Err(ValidationError::InvalidRegex(_))
+                ),
+                "{}",
+                pattern
+            );
+        }
+    }
+}
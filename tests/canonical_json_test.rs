@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod canonical_json_tests {
+    use zz_validator::{ast::Value, json::from_json_str, schema::Schema};
+
+    fn canonical(json: &str) -> String {
+        from_json_str(json).unwrap().to_canonical_json().unwrap()
+    }
+
+    #[test]
+    fn test_numbers() {
+        // RFC 8785 附录 B 中的样例
+        for (f, expected) in [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (5e-324, "5e-324"),
+            (-5e-324, "-5e-324"),
+            (1.7976931348623157e308, "1.7976931348623157e+308"),
+            (-1.7976931348623157e308, "-1.7976931348623157e+308"),
+            (9007199254740992.0, "9007199254740992"),
+            (-9007199254740992.0, "-9007199254740992"),
+            (295147905179352830000.0, "295147905179352830000"),
+            (9.999999999999997e22, "9.999999999999997e+22"),
+            (1e23, "1e+23"),
+            (1e21, "1e+21"),
+            (999999999999999900000.0, "999999999999999900000"),
+            (0.000001, "0.000001"),
+            (1e-7, "1e-7"),
+            (333333333.3333333, "333333333.3333333"),
+            (4.5, "4.5"),
+            (0.002, "0.002"),
+            (1.5e-9, "1.5e-9"),
+        ] {
+            assert_eq!(
+                Value::Float(f).to_canonical_json().unwrap(),
+                expected,
+                "{:e}",
+                f
+            );
+        }
+        assert_eq!(Value::Int(-42).to_canonical_json().unwrap(), "-42");
+        assert_eq!(
+            Value::Int(i64::MAX).to_canonical_json().unwrap(),
+            "9223372036854776000"
+        );
+        for f in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(Value::Float(f).to_canonical_json().is_err());
+        }
+    }
+
+    #[test]
+    fn test_keys_and_strings() {
+        // 键按 UTF-16 码元排序：代理对 U+D83D 排在 U+FB33 之前
+        assert_eq!(
+            canonical(
+                r#"{"\u20ac": "Euro Sign", "\r": "Carriage Return", "\ufb33": "Dalet",
+                    "1": "One", "\ud83d\ude00": "Emoji", "\u0080": "Control", "\u00f6": "o"}"#
+            ),
+            "{\"\\r\":\"Carriage Return\",\"1\":\"One\",\"\u{80}\":\"Control\",\"ö\":\"o\",\
+             \"€\":\"Euro Sign\",\"😀\":\"Emoji\",\"\u{fb33}\":\"Dalet\"}"
+        );
+        assert_eq!(
+            canonical(r#"{"s": "\u0001\b\f\n\r\t\"\\/\u007f\u2028é"}"#),
+            "{\"s\":\"\\u0001\\b\\f\\n\\r\\t\\\"\\\\/\u{7f}\u{2028}é\"}"
+        );
+    }
+
+    #[test]
+    fn test_nested_and_deterministic() {
+        let a = canonical(r#"{"b": [3, {"z": true, "a": null}], "a": {"y": 1.0, "x": 1e30}}"#);
+        // 对象中的 null 视为字段缺失
+        assert_eq!(a, r#"{"a":{"x":1e+30,"y":1},"b":[3,{"z":true}]}"#);
+        let b = canonical(r#"{ "a": {"x": 1E30, "y": 1}, "b": [3.0, {"z": true, "a": null}] }"#);
+        assert_eq!(a, b);
+
+        // 校验并填充默认值后的载荷
+        let schema = Schema::parse("(name:string, tags:array<string>, n:int=7)").unwrap();
+        let mut value = from_json_str(r#"{"tags": ["x"], "name": "a"}"#).unwrap();
+        schema.validate(&mut value).unwrap();
+        assert_eq!(
+            value.to_canonical_json().unwrap(),
+            r#"{"n":7,"name":"a","tags":["x"]}"#
+        );
+    }
+}
@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod canonical_tests {
+    use zz_validator::{
+        ast::{FieldType, Value},
+        canonical::canonical_form,
+        json::from_json_str,
+        parser::Parser,
+        report::Normalization,
+        validator::{
+            ValidationContext, ValidationOptions, validate_with_context, validate_with_report,
+        },
+    };
+
+    const DSL: &str = r#"(
+        id:uuid(compact),
+        devices?:array<object(mac:mac, color?:color)>,
+        owner?:uuid|int
+    )"#;
+
+    fn ctx(canonicalize: bool) -> ValidationContext {
+        ValidationContext::new().options(ValidationOptions {
+            canonicalize,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_canonical_forms() {
+        let s = |v: &str| Value::String(v.into());
+        assert_eq!(
+            canonical_form(&s("550E8400E29B41D4A716446655440000"), &FieldType::Uuid).as_deref(),
+            Some("550e8400-e29b-41d4-a716-446655440000")
+        );
+        assert_eq!(
+            canonical_form(&s("AA-BB-CC-DD-EE-0F"), &FieldType::Mac).as_deref(),
+            Some("aa:bb:cc:dd:ee:0f")
+        );
+        assert_eq!(
+            canonical_form(&s("#F0a"), &FieldType::Color).as_deref(),
+            Some("#ff00aa")
+        );
+        assert_eq!(canonical_form(&s("abc"), &FieldType::String), None);
+        assert_eq!(canonical_form(&Value::Int(1), &FieldType::Color), None);
+    }
+
+    #[test]
+    fn test_report_records_normalizations() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut value = from_json_str(
+            r##"{
+                "id": "550E8400E29B41D4A716446655440000",
+                "devices": [
+                    {"mac": "aa:bb:cc:dd:ee:ff", "color": "#ABC"},
+                    {"mac": "AA-BB-CC-DD-EE-00"}
+                ],
+                "owner": 7
+            }"##,
+        )
+        .unwrap();
+        let report = validate_with_report(&mut value, &rules, &ctx(true)).unwrap();
+        assert_eq!(
+            report.normalized,
+            vec![
+                Normalization {
+                    path: "id".into(),
+                    before: "550E8400E29B41D4A716446655440000".into(),
+                    after: "550e8400-e29b-41d4-a716-446655440000".into(),
+                },
+                Normalization {
+                    path: "devices[0].color".into(),
+                    before: "#ABC".into(),
+                    after: "#aabbcc".into(),
+                },
+                Normalization {
+                    path: "devices[1].mac".into(),
+                    before: "AA-BB-CC-DD-EE-00".into(),
+                    after: "aa:bb:cc:dd:ee:00".into(),
+                },
+            ]
+        );
+        let expected = from_json_str(
+            r##"{
+                "id": "550e8400-e29b-41d4-a716-446655440000",
+                "devices": [
+                    {"mac": "aa:bb:cc:dd:ee:ff", "color": "#aabbcc"},
+                    {"mac": "aa:bb:cc:dd:ee:00"}
+                ],
+                "owner": 7
+            }"##,
+        )
+        .unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn test_canonicalize_is_opt_in() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let raw = r#"{"id": "550E8400-E29B-41D4-A716-446655440000", "owner": "550E8400-E29B-41D4-A716-446655440000"}"#;
+
+        let mut value = from_json_str(raw).unwrap();
+        let report = validate_with_report(&mut value, &rules, &ctx(false)).unwrap();
+        assert!(report.normalized.is_empty());
+        assert_eq!(value, from_json_str(raw).unwrap());
+
+        // 联合类型按匹配到的 uuid 规范化
+        let mut value = from_json_str(raw).unwrap();
+        assert!(validate_with_context(&mut value, &rules, &ctx(true)).is_ok());
+        assert_eq!(
+            value.as_object().unwrap()["owner"],
+            Value::String("550e8400-e29b-41d4-a716-446655440000".into())
+        );
+
+        // 校验失败时不修改
+        let mut value = from_json_str(
+            r#"{"id": "550E8400E29B41D4A716446655440000", "devices": [{"mac": "x"}]}"#,
+        )
+        .unwrap();
+        assert!(validate_with_report(&mut value, &rules, &ctx(true)).is_err());
+        assert_eq!(
+            value.as_object().unwrap()["id"],
+            Value::String("550E8400E29B41D4A716446655440000".into())
+        );
+    }
+}
@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod change_set_tests {
+    use std::sync::Arc;
+
+    use zz_validator::{
+        ast::Value,
+        json::from_json_str,
+        report::{Change, ChangeKind, ChangeSet},
+        schema::Schema,
+        validator::{ValidationContext, ValidationOptions, validate_changes, validate_with_report},
+    };
+
+    const DSL: &str = r#"(
+        age:int,
+        role:string = user,
+        id?:uuid,
+        profile:object(lang:string = en, score:float),
+        items?:array<object(qty:int = 1, price:float)>
+    )"#;
+
+    const INPUT: &str = r#"{
+        "age": "42",
+        "id": "550E8400-E29B-41D4-A716-446655440000",
+        "profile": {"score": 1},
+        "items": [{"price": 2.0}, {"qty": "3", "price": 1.5}]
+    }"#;
+
+    fn ctx() -> ValidationContext {
+        ValidationContext::new().options(ValidationOptions {
+            coerce: true,
+            canonicalize: true,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_changes_are_returned_not_applied() {
+        let schema = Schema::parse(DSL).unwrap();
+        let value = from_json_str(INPUT).unwrap();
+        let changes = schema
+            .validate_changes_with_context(&value, &ctx())
+            .unwrap();
+        // 载荷没有被修改
+        assert_eq!(value, from_json_str(INPUT).unwrap());
+
+        let listed: Vec<(&str, ChangeKind)> =
+            changes.iter().map(|c| (c.path.as_str(), c.kind)).collect();
+        assert_eq!(
+            listed,
+            [
+                ("age", ChangeKind::Coerce),
+                ("profile.score", ChangeKind::Coerce),
+                ("items[1].qty", ChangeKind::Coerce),
+                ("role", ChangeKind::Default),
+                ("profile.lang", ChangeKind::Default),
+                ("items[0].qty", ChangeKind::Default),
+                ("id", ChangeKind::Normalize),
+            ]
+        );
+
+        // 写入后与就地校验的结果相同
+        let mut applied = value.clone();
+        changes.apply(&mut applied).unwrap();
+        let mut in_place = value.clone();
+        let report = validate_with_report(&mut in_place, schema.rules(), &ctx()).unwrap();
+        assert_eq!(applied, in_place);
+        assert_eq!(changes, ChangeSet::new(report.changes));
+        assert!(schema.validate_changes(&applied).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_apply_rejects_stale_changes() {
+        let schema = Schema::parse(DSL).unwrap();
+        let value = from_json_str(INPUT).unwrap();
+        let changes = schema
+            .validate_changes_with_context(&value, &ctx())
+            .unwrap();
+
+        // 校验之后字段被改动过，全部修改都不写入
+        let mut edited = value.clone();
+        edited
+            .as_object_mut()
+            .unwrap()
+            .insert("role".into(), Value::String("admin".into()));
+        let err = changes.apply(&mut edited).unwrap_err();
+        assert_eq!(err, "Cannot apply change at role: field is already present");
+        assert_eq!(
+            edited.as_object().unwrap().get("age"),
+            Some(&Value::String("42".into()))
+        );
+
+        let stale = ChangeSet::new(vec![Change {
+            path: "items[5].qty".into(),
+            kind: ChangeKind::Coerce,
+            before: Some(Value::String("3".into())),
+            after: Value::Int(3),
+        }]);
+        assert_eq!(
+            stale.apply(&mut value.clone()).unwrap_err(),
+            "Cannot apply change at items[5].qty: path not found"
+        );
+    }
+
+    #[test]
+    fn test_shared_value_across_threads() {
+        let rules = Arc::new(Schema::parse(DSL).unwrap().rules().to_vec());
+        let value = Arc::new(from_json_str(INPUT).unwrap());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let (rules, value) = (rules.clone(), value.clone());
+                std::thread::spawn(move || validate_changes(&value, &rules, &ctx()).unwrap().len())
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 7);
+        }
+
+        // 校验失败时返回错误，没有修改
+        let bad = from_json_str(r#"{"age":"x","profile":{"score":1.0}}"#).unwrap();
+        assert_eq!(
+            validate_changes(&bad, &rules, &ctx()).unwrap_err().field(),
+            Some("age")
+        );
+    }
+}
@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod changes_tests {
+    use zz_validator::{
+        ast::Value,
+        json::from_json_str,
+        parser::Parser,
+        report::{Change, ChangeKind},
+        validator::{ValidationContext, ValidationOptions, validate_with_report},
+    };
+
+    const DSL: &str = r#"(
+        age:int,
+        role:string=user,
+        id?:uuid,
+        profile:object(lang:string=en, score:float),
+        items?:array<object(qty:int=1, price:float)>
+    )"#;
+
+    fn ctx(coerce: bool, canonicalize: bool) -> ValidationContext {
+        ValidationContext::new().options(ValidationOptions {
+            coerce,
+            canonicalize,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_defaults_are_recorded_with_paths() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut value = from_json_str(
+            r#"{"age": 1, "profile": {"score": 1.5}, "items": [{"price": 2.0}, {"qty": 3, "price": 1.0}]}"#,
+        )
+        .unwrap();
+        let report = validate_with_report(&mut value, &rules, &ctx(false, false)).unwrap();
+        let paths: Vec<(&str, ChangeKind)> = report
+            .changes
+            .iter()
+            .map(|c| (c.path.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                ("role", ChangeKind::Default),
+                ("profile.lang", ChangeKind::Default),
+                ("items[0].qty", ChangeKind::Default),
+            ]
+        );
+        assert_eq!(report.changes[0].before, None);
+        assert_eq!(report.changes[0].after, Value::String("user".into()));
+        assert_eq!(report.changes[2].to_string(), "items[0].qty: default 1");
+    }
+
+    #[test]
+    fn test_coercions_and_normalizations() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut value = from_json_str(
+            r#"{
+                "age": "42",
+                "role": "admin",
+                "id": "550E8400-E29B-41D4-A716-446655440000",
+                "profile": {"lang": "de", "score": 3},
+                "items": [{"qty": "2", "price": "9.5"}]
+            }"#,
+        )
+        .unwrap();
+        let report = validate_with_report(&mut value, &rules, &ctx(true, true)).unwrap();
+        assert_eq!(
+            report.changes[0],
+            Change {
+                path: "age".into(),
+                kind: ChangeKind::Coerce,
+                before: Some(Value::String("42".into())),
+                after: Value::Int(42),
+            }
+        );
+        let lines: Vec<String> = report.changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            lines,
+            [
+                r#"age: coerce "42" -> 42"#,
+                "profile.score: coerce 3 -> 3.0",
+                r#"items[0].qty: coerce "2" -> 2"#,
+                r#"items[0].price: coerce "9.5" -> 9.5"#,
+                r#"id: normalize "550E8400-E29B-41D4-A716-446655440000" -> "550e8400-e29b-41d4-a716-446655440000""#,
+            ]
+        );
+        assert_eq!(report.normalized.len(), 1);
+    }
+
+    #[test]
+    fn test_unchanged_payload_has_no_changes() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut value =
+            from_json_str(r#"{"age": 1, "role": "a", "profile": {"lang": "en", "score": 1.0}}"#)
+                .unwrap();
+        let report = validate_with_report(&mut value, &rules, &ctx(true, true)).unwrap();
+        assert!(report.changes.is_empty());
+    }
+
+    #[test]
+    fn test_failed_validation_does_not_leak_changes() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut value = from_json_str(r#"{"age": "x", "profile": {"score": 1.0}}"#).unwrap();
+        assert!(validate_with_report(&mut value, &rules, &ctx(true, false)).is_err());
+
+        let mut value =
+            from_json_str(r#"{"age": 1, "role": "a", "profile": {"lang": "en", "score": 1.0}}"#)
+                .unwrap();
+        let report = validate_with_report(&mut value, &rules, &ctx(false, false)).unwrap();
+        assert!(report.changes.is_empty());
+    }
+}
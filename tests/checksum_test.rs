@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod checksum_tests {
+    use zz_validator::{
+        ast::{Constraint, Value},
+        checksum::{Checksum, Crc32, Luhn, Mod97, crc32},
+        export::to_dsl,
+        parser::Parser,
+        registry::ValidatorRegistry,
+        validator::{ValidationContext, ValidationError, validate_object, validate_with_context},
+    };
+
+    fn obj(field: &str, value: Value) -> Value {
+        Value::Object([(field.to_string(), value)].into())
+    }
+
+    #[test]
+    fn test_builtin_algorithms() {
+        assert!(Luhn.verify("79927398713", None));
+        assert!(Luhn.verify("4111 1111-1111 1111", None));
+        assert!(!Luhn.verify("79927398710", None));
+        assert!(!Luhn.verify("7992739871x", None));
+        assert!(!Luhn.verify("0", None));
+
+        // IBAN GB82 WEST 1234 5698 7654 32，国家码与校验位移到末尾
+        assert!(Mod97.verify("WEST12345698765432GB82", None));
+        assert!(!Mod97.verify("WEST12345698765432GB83", None));
+
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert!(Crc32.verify("123456789", Some("CBF43926")));
+        assert!(!Crc32.verify("123456789", Some("00000000")));
+        assert!(!Crc32.verify("123456789", None));
+    }
+
+    #[test]
+    fn test_parse_checksum_constraints() {
+        let rules = Parser::parse_rules(
+            r#"(
+                card:string luhn,
+                imei:int checksum(luhn),
+                lei:string checksum(mod97),
+                payload:string checksum(crc32="cbf43926")
+            )"#,
+        )
+        .unwrap();
+        let first = |i: usize| rules[i].constraints.as_ref().unwrap().items[0].clone();
+        assert!(matches!(
+            first(0),
+            Constraint::Checksum { algorithm, expected: None } if algorithm == "luhn"
+        ));
+        assert!(matches!(
+            first(3),
+            Constraint::Checksum { algorithm, expected: Some(e) }
+                if algorithm == "crc32" && e == "cbf43926"
+        ));
+
+        let dsl = to_dsl(&rules);
+        assert!(dsl.contains("card:string luhn"));
+        assert!(dsl.contains("imei:int luhn"));
+        assert!(dsl.contains(r#"payload:string checksum(crc32="cbf43926")"#));
+        assert_eq!(to_dsl(&Parser::parse_rules(&dsl).unwrap()), dsl);
+    }
+
+    #[test]
+    fn test_validate_checksums() {
+        let rules = Parser::parse_rules(
+            r#"(
+                card?:string luhn,
+                imei?:int luhn,
+                payload?:string checksum(crc32="cbf43926")
+            )"#,
+        )
+        .unwrap();
+
+        assert!(
+            validate_object(
+                &mut obj("card", Value::String("4111111111111111".into())),
+                &rules
+            )
+            .is_ok()
+        );
+        assert!(validate_object(&mut obj("imei", Value::Int(490154203237518)), &rules).is_ok());
+        assert!(
+            validate_object(
+                &mut obj("payload", Value::String("123456789".into())),
+                &rules
+            )
+            .is_ok()
+        );
+
+        let err =
+            validate_object(&mut obj("imei", Value::Int(490154203237519)), &rules).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::ChecksumMismatch {
+                field: "imei".into(),
+                algorithm: "luhn".into(),
+            }
+        );
+        assert_eq!(err.to_string(), "imei failed luhn checksum");
+    }
+
+    #[test]
+    fn test_custom_checksum() {
+        let rules = Parser::parse_rules(
+            r#"(code:string checksum(digit_sum="7"), other?:string checksum(unknown))"#,
+        )
+        .unwrap();
+        let mut registry = ValidatorRegistry::new();
+        registry.register_checksum("digit_sum", |input: &str, expected: Option<&str>| {
+            let sum: u32 = input.chars().filter_map(|c| c.to_digit(10)).sum();
+            expected.and_then(|e| e.parse().ok()) == Some(sum % 10)
+        });
+        let ctx = ValidationContext::new().registry(registry);
+
+        assert!(
+            validate_with_context(&mut obj("code", Value::String("124".into())), &rules, &ctx)
+                .is_ok()
+        );
+        assert!(matches!(
+            validate_with_context(&mut obj("code", Value::String("125".into())), &rules, &ctx),
+            Err(ValidationError::ChecksumMismatch { .. })
+        ));
+
+        // 未注册的算法
+        let mut value = Value::Object(
+            [
+                ("code".to_string(), Value::String("124".into())),
+                ("other".to_string(), Value::String("x".into())),
+            ]
+            .into(),
+        );
+        assert!(matches!(
+            validate_with_context(&mut value, &rules, &ctx),
+            Err(ValidationError::Custom(msg)) if msg.contains("unknown")
+        ));
+    }
+}
@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod clock_tests {
+    use zz_validator::{
+        ast::{Constraint, Value},
+        clock::FixedClock,
+        export::{to_dsl, to_json_schema},
+        import::from_json_schema,
+        parser::Parser,
+        validator::{ValidationContext, ValidationError, validate_with_context},
+    };
+
+    /// 2024-01-01T12:00:00Z
+    const NOW: i64 = 1_704_067_200 + 12 * 3600;
+
+    const DSL: &str = r#"(
+        birthday?:date past,
+        starts_at?:datetime future,
+        expires_at?:timestamp within(30d),
+        token_at?:timestamp past within(2h)
+    )"#;
+
+    fn check(field: &str, value: Value) -> Result<(), ValidationError> {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let ctx = ValidationContext::new().clock(FixedClock(NOW));
+        let mut obj = Value::Object([(field.to_string(), value)].into_iter().collect());
+        validate_with_context(&mut obj, &rules, &ctx)
+    }
+
+    #[test]
+    fn test_parse_time_constraints() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let items = |i: usize| rules[i].constraints.as_ref().unwrap().items.clone();
+        assert!(matches!(items(0)[0], Constraint::Past));
+        assert!(matches!(items(1)[0], Constraint::Future));
+        assert!(matches!(items(2)[0], Constraint::Within(2_592_000)));
+        assert!(matches!(items(3)[1], Constraint::Within(7200)));
+
+        let dsl = to_dsl(&rules);
+        assert!(dsl.contains("token_at?:timestamp past within(2h)"));
+        assert!(dsl.contains("within(30d)"));
+
+        let schema = to_json_schema(&rules);
+        assert_eq!(
+            schema["properties"]["token_at"]["x-vdsl-constraints"],
+            serde_json::json!(["past", "within(2h)"])
+        );
+        let imported = from_json_schema(&schema).unwrap();
+        let token_at = imported.iter().find(|r| r.field == "token_at").unwrap();
+        assert!(matches!(
+            token_at.constraints.as_ref().unwrap().items[..],
+            [Constraint::Past, Constraint::Within(7200)]
+        ));
+
+        for bad in ["within(30)", "within(30y)", "within(-1d)", "within(d)"] {
+            let dsl = format!("(a:timestamp {})", bad);
+            assert!(Parser::parse_rules(&dsl).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_past_and_future() {
+        assert!(check("birthday", Value::String("2000-02-29".into())).is_ok());
+        assert!(check("birthday", Value::String("2023-12-31".into())).is_ok());
+        // 当天既不是过去也不是将来
+        assert!(check("birthday", Value::String("2024-01-01".into())).is_err());
+        assert!(check("birthday", Value::String("2030-01-01".into())).is_err());
+
+        assert!(check("starts_at", Value::String("2024-01-01T12:00:01Z".into())).is_ok());
+        let err = check("starts_at", Value::String("2024-01-01T12:00:00".into())).unwrap_err();
+        assert!(matches!(
+            &err,
+            ValidationError::TimeError { field, constraint, .. }
+                if field == "starts_at" && constraint == "future"
+        ));
+    }
+
+    #[test]
+    fn test_within() {
+        assert!(check("expires_at", Value::Int(NOW + 29 * 86400)).is_ok());
+        assert!(check("expires_at", Value::Int(NOW - 30 * 86400)).is_ok());
+        assert!(check("expires_at", Value::Int(NOW + 31 * 86400)).is_err());
+
+        assert!(check("token_at", Value::Int(NOW - 3600)).is_ok());
+        assert!(check("token_at", Value::Int(NOW + 60)).is_err());
+        assert!(check("token_at", Value::Int(NOW - 3 * 3600)).is_err());
+    }
+
+    #[test]
+    fn test_system_clock_is_default() {
+        let rules = Parser::parse_rules("(at:date past)").unwrap();
+        let mut obj =
+            Value::Object([("at".to_string(), Value::String("1999-01-01".into()))].into());
+        assert!(validate_with_context(&mut obj, &rules, &ValidationContext::new()).is_ok());
+
+        let mut obj =
+            Value::Object([("at".to_string(), Value::String("1999-02-30".into()))].into());
+        assert!(validate_with_context(&mut obj, &rules, &ValidationContext::new()).is_err());
+    }
+
+    fn adult_at(now: i64, birthday: &str) -> bool {
+        let rules = Parser::parse_rules("(birthday:date min_age(18))").unwrap();
+        let ctx = ValidationContext::new().clock(FixedClock(now));
+        let mut obj =
+            Value::Object([("birthday".to_string(), Value::String(birthday.into()))].into());
+        validate_with_context(&mut obj, &rules, &ctx).is_ok()
+    }
+
+    #[test]
+    fn test_min_age() {
+        let rules = Parser::parse_rules("(birthday:date min_age(18))").unwrap();
+        assert!(matches!(
+            rules[0].constraints.as_ref().unwrap().items[0],
+            Constraint::MinAge(18)
+        ));
+        assert!(to_dsl(&rules).contains("birthday:date min_age(18)"));
+        assert!(Parser::parse_rules("(birthday:date min_age(-1))").is_err());
+
+        // NOW 为 2024-01-01
+        assert!(adult_at(NOW, "2006-01-01"));
+        assert!(adult_at(NOW, "2005-12-31"));
+        assert!(!adult_at(NOW, "2006-01-02"));
+        assert!(!adult_at(NOW, "2020-06-15"));
+
+        // 2 月 29 日出生者在平年的 3 月 1 日满岁
+        let mar_1_2022 = 1_646_092_800;
+        let feb_28_2022 = mar_1_2022 - 86400;
+        assert!(adult_at(mar_1_2022, "2004-02-29"));
+        assert!(!adult_at(feb_28_2022 + 86399, "2004-02-29"));
+        // 闰年 2 月 29 日当天：2 月 28 日出生者已满岁，3 月 1 日出生者未满
+        let feb_29_2024 = 1_709_164_800;
+        assert!(adult_at(feb_29_2024, "2006-02-28"));
+        assert!(!adult_at(feb_29_2024, "2006-03-01"));
+
+        let err = {
+            let ctx = ValidationContext::new().clock(FixedClock(NOW));
+            let mut obj = Value::Object(
+                [("birthday".to_string(), Value::String("2010-05-05".into()))].into(),
+            );
+            validate_with_context(&mut obj, &rules, &ctx).unwrap_err()
+        };
+        assert!(matches!(
+            err,
+            ValidationError::TimeError { constraint, .. } if constraint == "min_age(18)"
+        ));
+    }
+}
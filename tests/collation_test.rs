@@ -0,0 +1,123 @@
+mod common;
+
+#[cfg(test)]
+mod collation_tests {
+    use crate::common::{check_array, strings};
+    use zz_validator::{
+        ast::{Collation, Constraint, Value},
+        export::to_dsl,
+        parser::Parser,
+        validator::ValidationError,
+    };
+
+    const DSL: &str = r#"(
+        names?:array<string> sorted,
+        tags?:array<string> strictly_increasing(nocase),
+        words?:array<string> sorted(asc, unicode),
+        swedish?:array<string> sorted(asc, unicode("sv")),
+        people?:array<object(first:string, last:string)> each(first < last, nocase)
+    )"#;
+
+    fn check(field: &str, items: Vec<Value>) -> Result<(), ValidationError> {
+        check_array(DSL, field, items)
+    }
+
+    fn person(first: &str, last: &str) -> Value {
+        Value::Object(
+            [
+                ("first".to_string(), Value::String(first.into())),
+                ("last".to_string(), Value::String(last.into())),
+            ]
+            .into(),
+        )
+    }
+
+    #[test]
+    fn test_parse_collation() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let first = |i: usize| rules[i].constraints.as_ref().unwrap().items[0].clone();
+        assert!(matches!(
+            first(1),
+            Constraint::Sorted {
+                strict: true,
+                collation: Collation::CaseInsensitive,
+                ..
+            }
+        ));
+        assert!(matches!(
+            first(3),
+            Constraint::Sorted { collation: Collation::Unicode(Some(locale)), .. } if locale == "sv"
+        ));
+
+        let dsl = to_dsl(&rules);
+        assert!(dsl.contains("names?:array<string> sorted(asc)"));
+        assert!(dsl.contains("strictly_increasing(nocase)"));
+        assert!(dsl.contains(r#"sorted(asc, unicode("sv"))"#));
+        assert!(dsl.contains("each(first < last, nocase)"));
+        assert_eq!(to_dsl(&Parser::parse_rules(&dsl).unwrap()), dsl);
+
+        for bad in [
+            "sorted(asc, upper)",
+            "strictly_increasing()",
+            "sorted(asc, unicode(sv))",
+        ] {
+            let dsl = format!("(a:array<string> {})", bad);
+            assert!(Parser::parse_rules(&dsl).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_binary_and_nocase() {
+        // 默认按码点比较：大写字母排在小写字母前
+        assert!(check("names", strings(&["Bob", "alice"])).is_ok());
+        assert!(check("names", strings(&["alice", "Bob"])).is_err());
+
+        assert!(check("tags", strings(&["alice", "Bob", "carol"])).is_ok());
+        // 忽略大小写后相等，不满足严格递增
+        assert!(check("tags", strings(&["bob", "BOB"])).is_err());
+
+        assert!(check("people", vec![person("Ada", "lovelace")]).is_ok());
+        assert_eq!(
+            check("people", vec![person("ada", "ADA")]).unwrap_err(),
+            ValidationError::ElementConstraint {
+                field: "people".into(),
+                index: 0,
+                constraint: "each(first < last, nocase)".into(),
+            }
+        );
+
+        // 日期字符串仍按时刻比较：两者是同一时刻，按文本比较则前者更小
+        assert!(check("names", strings(&["2024-01-01", "2024-01-01T00:00:00Z"])).is_ok());
+        assert!(check("tags", strings(&["2024-01-01", "2024-01-01T00:00:00Z"])).is_err());
+    }
+
+    #[cfg(not(feature = "icu"))]
+    #[test]
+    fn test_unicode_requires_feature() {
+        assert!(matches!(
+            check("words", strings(&["a", "b"])),
+            Err(ValidationError::Custom(msg)) if msg.contains("icu")
+        ));
+        // 只有一个元素时不需要比较
+        assert!(check("words", strings(&["a"])).is_ok());
+    }
+
+    #[cfg(feature = "icu")]
+    #[test]
+    fn test_unicode_collation() {
+        // 按码点比较时 "é" 排在 "z" 之后，Unicode 排序规则下排在 "f" 之前
+        assert!(
+            check(
+                "words",
+                strings(&["apple", "Banana", "éclair", "fig", "zebra"])
+            )
+            .is_ok()
+        );
+        assert!(check("names", strings(&["apple", "éclair", "fig"])).is_err());
+        assert!(check("words", strings(&["zebra", "apple"])).is_err());
+
+        // 瑞典语中 "ö" 排在 "z" 之后
+        assert!(check("swedish", strings(&["zon", "öl"])).is_ok());
+        assert!(check("words", strings(&["zon", "öl"])).is_err());
+    }
+}
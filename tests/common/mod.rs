@@ -0,0 +1,19 @@
+//! 多个测试文件共用的辅助函数，各文件只用到其中一部分
+#![allow(dead_code)]
+
+use zz_validator::{
+    ast::Value,
+    parser::Parser,
+    validator::{ValidationError, validate_object},
+};
+
+/// 按 `dsl` 校验只有一个数组字段 `field` 的对象
+pub fn check_array(dsl: &str, field: &str, items: Vec<Value>) -> Result<(), ValidationError> {
+    let rules = Parser::parse_rules(dsl).unwrap();
+    let mut value = Value::Object([(field.to_string(), Value::Array(items))].into());
+    validate_object(&mut value, &rules)
+}
+
+pub fn strings(items: &[&str]) -> Vec<Value> {
+    items.iter().map(|s| Value::String(s.to_string())).collect()
+}
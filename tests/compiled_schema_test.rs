@@ -0,0 +1,159 @@
+#[cfg(test)]
+mod compiled_schema_tests {
+    use std::sync::Arc;
+
+    use zz_validator::{
+        ast::Value,
+        json::from_json_str,
+        parser::Parser,
+        schema::{CompiledSchema, Schema, validate_ref, validate_ref_with_context},
+        validator::{ValidationContext, ValidationError, ValidationOptions, validate_object},
+    };
+
+    const DSL: &str = r#"(
+        code:string regex("^[A-Z]{3}$"),
+        sku:regex("^[a-z]+-[0-9]+$"),
+        tags?:array<string regex("^#")>,
+        owner?:object(handle:string regex("^@"))
+    )"#;
+
+    #[test]
+    fn test_same_results_as_uncompiled() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let schema: CompiledSchema = Schema::compile(rules.clone()).unwrap();
+        for json in [
+            r##"{"code": "ABC", "sku": "a-1", "tags": ["#x"], "owner": {"handle": "@me"}}"##,
+            r#"{"code": "abc", "sku": "a-1"}"#,
+            r#"{"code": "ABC", "sku": "A-1"}"#,
+            r#"{"code": "ABC", "sku": 1}"#,
+            r##"{"code": "ABC", "sku": "a-1", "tags": ["#x", "y"]}"##,
+            r#"{"code": "ABC", "sku": "a-1", "owner": {"handle": "me"}}"#,
+        ] {
+            let mut compiled = from_json_str(json).unwrap();
+            let mut plain = compiled.clone();
+            assert_eq!(
+                schema.validate(&mut compiled),
+                validate_object(&mut plain, &rules),
+                "{}",
+                json
+            );
+        }
+
+        let mut value = from_json_str(r#"{"code": "ABC", "sku": "a-1", "tags": ["y"]}"#).unwrap();
+        assert_eq!(
+            schema.validate(&mut value),
+            Err(ValidationError::RegexMismatch {
+                field: "tags[0]".into(),
+                pattern: "^#".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_reused_across_threads() {
+        let schema = Arc::new(Schema::parse(DSL).unwrap());
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let schema = Arc::clone(&schema);
+                std::thread::spawn(move || {
+                    // 奇数线程的 sku 为大写，不符合正则
+                    let prefix = if i % 2 == 0 { "a" } else { "A" };
+                    (0..100).all(|n| {
+                        let json = format!(r#"{{"code": "ABC", "sku": "{}-{}"}}"#, prefix, n);
+                        let mut value = from_json_str(&json).unwrap();
+                        schema.validate(&mut value).is_ok() == (i % 2 == 0)
+                    })
+                })
+            })
+            .collect();
+        assert!(handles.into_iter().all(|h| h.join().unwrap()));
+    }
+
+    #[test]
+    fn test_validate_ref_never_writes() {
+        let schema: CompiledSchema =
+            Schema::parse(r#"(name:string, role:string="user" enum(user, admin), n?:int)"#)
+                .unwrap();
+        let value = from_json_str(r#"{"name": "a"}"#).unwrap();
+        let before = value.clone();
+        assert!(validate_ref(&value, &schema).is_ok());
+        // 默认值只校验不填入
+        assert_eq!(value, before);
+
+        let shared = Arc::new(from_json_str(r#"{"name": "a", "role": "root"}"#).unwrap());
+        assert!(matches!(
+            validate_ref(&shared, &schema),
+            Err(ValidationError::EnumMismatch { .. })
+        ));
+
+        // coerce 作用在副本上，载荷保持原样
+        let ctx = ValidationContext::new().options(ValidationOptions {
+            coerce: true,
+            strict: true,
+            ..Default::default()
+        });
+        let value = from_json_str(r#"{"name": "a", "n": "1"}"#).unwrap();
+        let before = value.clone();
+        assert!(validate_ref_with_context(&value, &schema, &ctx).is_ok());
+        assert_eq!(value, before);
+        let value = from_json_str(r#"{"name": "a", "n": "x"}"#).unwrap();
+        assert!(validate_ref_with_context(&value, &schema, &ctx).is_err());
+        let value = from_json_str(r#"{"name": "a", "x": 1}"#).unwrap();
+        assert!(matches!(
+            validate_ref_with_context(&value, &schema, &ctx),
+            Err(ValidationError::UnknownField(_))
+        ));
+    }
+
+    /// 只读校验与就地校验的结论相同，包括转换与 coerce 之后才满足或违反的约束
+    #[test]
+    fn test_validate_ref_matches_validate() {
+        let coerce = ValidationContext::new().options(ValidationOptions {
+            coerce: true,
+            ..Default::default()
+        });
+        let cases = [
+            (
+                "(h:slug slugify)",
+                r#"{"h": "Hello World"}"#,
+                ValidationContext::new(),
+            ),
+            (
+                "(h:string[1,5] slugify)",
+                r#"{"h": "a  b   c  d"}"#,
+                ValidationContext::new(),
+            ),
+            (
+                "(h:array<slug slugify>)",
+                r#"{"h": ["Hello World"]}"#,
+                ValidationContext::new(),
+            ),
+            ("(n:int)", r#"{"n": "42"}"#, coerce.clone()),
+            ("(n:int[0,10])", r#"{"n": "42"}"#, coerce.clone()),
+            ("(o:object(f:float[0,1]))", r#"{"o": {"f": 1}}"#, coerce),
+        ];
+        for (dsl, json, ctx) in cases {
+            let schema = Schema::parse(dsl).unwrap();
+            let value = from_json_str(json).unwrap();
+            let shared = schema.validate_shared_with_context(&value, &ctx);
+            assert_eq!(
+                shared,
+                schema.validate_with_context(&mut value.clone(), &ctx),
+                "{}",
+                dsl
+            );
+            assert_eq!(value, from_json_str(json).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_unchecked_invalid_regex() {
+        // Schema::new 不检查规则，无法编译的正则在校验时报错
+        let schema = Schema::new(Parser::parse_rules(r#"(a:string regex("["))"#).unwrap());
+        let mut value = Value::Object([("a".to_string(), Value::String("x".into()))].into());
+        assert!(matches!(
+            schema.validate(&mut value),
+            Err(ValidationError::InvalidRegex(_))
+        ));
+    }
+}
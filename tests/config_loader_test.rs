@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod config_loader_tests {
+    use std::path::PathBuf;
+
+    use zz_validator::{
+        ast::Value,
+        config::{ConfigError, ConfigLoader},
+        parser::Parser,
+        validator::ValidationError,
+    };
+
+    fn write_config(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join("zz_validator_config_tests");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn rules() -> Vec<zz_validator::ast::FieldRule> {
+        Parser::parse_rules(
+            r#"(
+                name:string,
+                port:int[1,65535]=8080,
+                debug:bool=false,
+                db:object(
+                    host:string="localhost",
+                    pool:int[1,100]=10
+                )
+            )"#,
+        )
+        .unwrap()
+    }
+
+    fn get<'a>(v: &'a Value, path: &[&str]) -> &'a Value {
+        path.iter()
+            .fold(v, |cur, key| cur.as_object().unwrap().get(*key).unwrap())
+    }
+
+    #[test]
+    fn test_file_layer_with_defaults() {
+        let path = write_config(
+            "file_layer.toml",
+            "name = \"svc\"\n[db]\nhost = \"db.internal\"\n",
+        );
+        let config = ConfigLoader::new(rules())
+            .file(&path)
+            .env_vars(Vec::<(String, String)>::new())
+            .load()
+            .unwrap();
+
+        assert_eq!(get(&config, &["name"]), &Value::String("svc".into()));
+        assert_eq!(get(&config, &["port"]), &Value::Int(8080));
+        assert_eq!(
+            get(&config, &["db", "host"]),
+            &Value::String("db.internal".into())
+        );
+        assert_eq!(get(&config, &["db", "pool"]), &Value::Int(10));
+    }
+
+    #[test]
+    fn test_layers_override_in_order() {
+        let base = write_config("base.toml", "name = \"base\"\nport = 1000\n");
+        let local = write_config("local.toml", "port = 2000\n[db]\npool = 5\n");
+
+        let config = ConfigLoader::new(rules())
+            .file(&base)
+            .file(&local)
+            .env_prefix("APP_")
+            .env_vars([("APP_PORT", "3000"), ("APP_DB__POOL", "20"), ("OTHER", "x")])
+            .args(["prog", "--port=4000", "--debug", "--db.host", "cli-host"])
+            .load()
+            .unwrap();
+
+        assert_eq!(get(&config, &["name"]), &Value::String("base".into()));
+        assert_eq!(get(&config, &["port"]), &Value::Int(4000));
+        assert_eq!(get(&config, &["debug"]), &Value::Bool(true));
+        assert_eq!(get(&config, &["db", "pool"]), &Value::Int(20));
+        assert_eq!(
+            get(&config, &["db", "host"]),
+            &Value::String("cli-host".into())
+        );
+        assert!(config.as_object().unwrap().get("other").is_none());
+    }
+
+    #[test]
+    fn test_validation_failure() {
+        let res = ConfigLoader::new(rules())
+            .env_prefix("APP_")
+            .env_vars([("APP_NAME", "svc"), ("APP_PORT", "70000")])
+            .load();
+        assert!(matches!(
+            res,
+            Err(ConfigError::Validation(ValidationError::RangeError { .. }))
+        ));
+
+        let res = ConfigLoader::new(rules()).args(["--port", "80"]).load();
+        assert!(matches!(
+            res,
+            Err(ConfigError::Validation(ValidationError::MissingField(_)))
+        ));
+
+        // 无法转换的值保持为字符串，由校验报告类型错误
+        let res = ConfigLoader::new(rules())
+            .args(["--name=svc", "--port=abc"])
+            .load();
+        assert!(matches!(
+            res,
+            Err(ConfigError::Validation(
+                ValidationError::TypeMismatch { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_file_errors() {
+        let missing = std::env::temp_dir().join("zz_validator_missing.toml");
+        let res = ConfigLoader::new(rules()).file(&missing).load();
+        assert!(matches!(res, Err(ConfigError::Io { .. })));
+
+        let bad = write_config("bad.toml", "name = \n");
+        let res = ConfigLoader::new(rules()).file(&bad).load();
+        assert!(matches!(res, Err(ConfigError::Parse { .. })));
+
+        let yaml = write_config("app.yaml", "name: svc\n");
+        let err = ConfigLoader::new(rules()).file(&yaml).load().unwrap_err();
+        assert!(matches!(err, ConfigError::UnsupportedFormat(_)));
+        assert!(err.to_string().contains("app.yaml"));
+    }
+}
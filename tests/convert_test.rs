@@ -0,0 +1,218 @@
+#[cfg(test)]
+mod convert_tests {
+    use std::process::Command;
+
+    use serde_json::json;
+    use zz_validator::{
+        ast::{FieldType, Value},
+        export::{to_dsl, to_json_schema, to_openapi, to_typescript},
+        import::from_json_schema,
+        parser::Parser,
+        validator::validate,
+    };
+
+    const DSL: &str = r#"(
+        id:uuid,
+        username:string[3,20] regex("^[a-z_\\d]+$"),
+        age:int[0,150)=18,
+        score?:float(0,100],
+        role:string enum("admin", "user")="user",
+        active:bool=true,
+        code:regex("^[A-Z]{3}$"),
+        mac:mac,
+        seen:timestamp,
+        id_or_name:int|string,
+        profile:object(
+            contact:object(
+                email:email,
+                phone?:phone
+            )
+        ),
+        tags:array<string[1,10]>,
+        users:array<object(name:string, id:int)>
+    )"#;
+
+    #[test]
+    fn test_dsl_round_trip() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let printed = to_dsl(&rules);
+        let reparsed = Parser::parse_rules(&printed).unwrap();
+        assert_eq!(to_dsl(&reparsed), printed);
+        assert!(printed.contains(r#"username:string[3, 20] regex("^[a-z_\\d]+$")"#));
+        assert!(printed.contains("age:int[0, 150) = 18"));
+        assert!(printed.contains("score?:float(0.0, 100.0]"));
+        assert!(printed.contains(r#"role:string enum("admin", "user") = "user""#));
+        assert!(printed.contains("tags:array<string[1, 10]>"));
+    }
+
+    #[test]
+    fn test_json_schema_export() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let schema = to_json_schema(&rules);
+        let props = &schema["properties"];
+
+        assert_eq!(schema["type"], "object");
+        assert_eq!(props["id"], json!({"type": "string", "format": "uuid"}));
+        assert_eq!(props["username"]["minLength"], 3);
+        assert_eq!(props["username"]["maxLength"], 20);
+        assert_eq!(props["age"]["minimum"], 0);
+        assert_eq!(props["age"]["exclusiveMaximum"], 150);
+        assert_eq!(props["age"]["default"], 18);
+        assert_eq!(props["score"]["exclusiveMinimum"], 0.0);
+        assert_eq!(props["role"]["enum"], json!(["admin", "user"]));
+        assert_eq!(props["mac"]["x-vdsl-type"], "mac");
+        assert_eq!(props["seen"]["type"], "integer");
+        assert_eq!(props["id_or_name"]["anyOf"][1]["type"], "string");
+        assert_eq!(
+            props["profile"]["properties"]["contact"]["required"],
+            json!(["email"])
+        );
+        assert_eq!(
+            props["users"]["items"]["properties"]["id"]["type"],
+            "integer"
+        );
+
+        let required = schema["required"].as_array().unwrap();
+        assert!(required.contains(&json!("username")));
+        assert!(!required.contains(&json!("age")));
+        assert!(!required.contains(&json!("score")));
+    }
+
+    #[test]
+    fn test_json_schema_round_trip_validates_same() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let imported = from_json_schema(&to_json_schema(&rules)).unwrap();
+        // 导入结果可以重新输出为可解析的 DSL
+        Parser::parse_rules(&to_dsl(&imported)).unwrap();
+
+        let payload = zz_validator::json::from_json_str(
+            r#"{
+                "id": "123e4567-e89b-12d3-a456-426614174000",
+                "username": "john_1",
+                "code": "ABC",
+                "mac": "00:1A:2B:3C:4D:5E",
+                "seen": 1700000000,
+                "id_or_name": "x",
+                "profile": {"contact": {"email": "a@b.co"}},
+                "tags": ["a"],
+                "users": [{"name": "n", "id": 1}]
+            }"#,
+        )
+        .unwrap();
+        assert!(validate(&payload, &rules).is_some());
+        let validated = validate(&payload, &imported).unwrap();
+        assert_eq!(
+            validated.as_object().unwrap().get("age"),
+            Some(&Value::Int(18))
+        );
+
+        let mut bad = payload.clone();
+        bad.as_object_mut()
+            .unwrap()
+            .insert("username".into(), Value::String("x".into()));
+        assert!(validate(&bad, &imported).is_none());
+    }
+
+    #[test]
+    fn test_json_schema_import() {
+        let schema = json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": {"type": "string", "minLength": 1},
+                "count": {"type": ["integer", "null"], "maximum": 10},
+                "ratio": {"type": "number", "exclusiveMinimum": 0},
+                "host": {"type": "string", "format": "ipv4"},
+                "any": {"oneOf": [{"type": "integer"}, {"type": "boolean"}]}
+            }
+        });
+        let rules = from_json_schema(&schema).unwrap();
+        let find = |name: &str| rules.iter().find(|r| r.field == name).unwrap();
+        assert!(find("name").required);
+        assert!(!find("count").required);
+        assert_eq!(find("count").field_type, FieldType::Int);
+        assert_eq!(find("host").field_type, FieldType::Ip);
+        assert_eq!(
+            find("any").union_types,
+            Some(vec![FieldType::Int, FieldType::Bool])
+        );
+
+        assert!(from_json_schema(&json!({"type": "string"})).is_err());
+        assert!(
+            from_json_schema(&json!({"properties": {"a": {"$ref": "#/defs/a"}}}))
+                .unwrap_err()
+                .contains("$ref")
+        );
+        assert!(from_json_schema(&json!({"properties": {"a": {"type": "null"}}})).is_err());
+        assert!(
+            from_json_schema(
+                &json!({"properties": {"a": {"type": "string", "format": "email", "maxLength": 3}}})
+            )
+            .is_err()
+        );
+        assert!(
+            from_json_schema(&json!({"properties": {"a": {"type": "string", "minimum": 3}}}))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_openapi_and_typescript() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let doc = to_openapi(&rules, "User");
+        assert_eq!(doc["openapi"], "3.1.0");
+        assert_eq!(
+            doc["components"]["schemas"]["User"]["properties"]["id"]["format"],
+            "uuid"
+        );
+
+        let ts = to_typescript(&rules, "User");
+        assert!(ts.starts_with("export interface User {"));
+        assert!(ts.contains("  username: string;"));
+        assert!(ts.contains("  age?: number;"));
+        assert!(ts.contains(r#"  role?: "admin" | "user";"#));
+        assert!(ts.contains("  id_or_name: number | string;"));
+        assert!(ts.contains("      phone?: string;"));
+        assert!(ts.contains("  tags: Array<string>;"));
+    }
+
+    #[test]
+    fn test_cli_convert() {
+        let dir = std::env::temp_dir().join("zz_validator_convert_tests");
+        std::fs::create_dir_all(&dir).unwrap();
+        let dsl_path = dir.join("create_user.vdsl");
+        std::fs::write(&dsl_path, DSL).unwrap();
+
+        let run = |args: &[&str], file: &std::path::Path| {
+            Command::new(env!("CARGO_BIN_EXE_validator"))
+                .arg("convert")
+                .args(args)
+                .arg(file)
+                .output()
+                .unwrap()
+        };
+
+        let out = run(&["--from", "vdsl", "--to", "jsonschema"], &dsl_path);
+        assert!(out.status.success());
+        let json_path = dir.join("create_user.json");
+        std::fs::write(&json_path, &out.stdout).unwrap();
+
+        let out = run(&["--from", "jsonschema", "--to", "vdsl"], &json_path);
+        assert!(out.status.success());
+        Parser::parse_rules(&String::from_utf8(out.stdout).unwrap()).unwrap();
+
+        let out = run(&["--from", "vdsl", "--to", "typescript"], &dsl_path);
+        let ts = String::from_utf8(out.stdout).unwrap();
+        assert!(ts.starts_with("export interface CreateUser {"));
+
+        let out = run(
+            &["--from", "vdsl", "--to", "openapi", "--name", "Account"],
+            &dsl_path,
+        );
+        let doc: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+        assert!(doc["components"]["schemas"]["Account"].is_object());
+
+        let out = run(&["--from", "yaml", "--to", "vdsl"], &dsl_path);
+        assert!(!out.status.success());
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod coverage_boost_tests {
-    use std::collections::HashMap;
+    use zz_validator::ast::ObjectMap;
     use zz_validator::{
         ast::{Constraint, Constraints, FieldRule, FieldType, Value},
         parser::Parser,
@@ -83,7 +83,7 @@ mod coverage_boost_tests {
     // -------------------------------------------------------------------------
     #[test]
     fn test_ast_conversions_and_clone() {
-        let mut v = Value::Object(HashMap::new());
+        let mut v = Value::Object(ObjectMap::new());
         assert!(v.as_object_mut().is_some());
         assert!(v.as_object().is_some());
         assert!(v.as_str().is_none());
@@ -147,7 +147,7 @@ mod coverage_boost_tests {
             children: None,
             is_array: false,
         };
-        let mut obj = Value::Object(HashMap::new());
+        let mut obj = Value::Object(ObjectMap::new());
         validate_field(&mut obj, &rule).unwrap();
         assert_eq!(
             obj.as_object()
@@ -190,7 +190,7 @@ mod coverage_boost_tests {
             children: None,
             is_array: false,
         };
-        let mut data = Value::Object(HashMap::from([("re".into(), Value::String("a".into()))]));
+        let mut data = Value::Object(ObjectMap::from([("re".into(), Value::String("a".into()))]));
         assert!(validate_field(&mut data, &rule_re).is_err());
 
         // validate_object 非对象覆盖
@@ -210,7 +210,7 @@ mod coverage_boost_tests {
             is_array: false,
         };
         let mut data_union =
-            Value::Object(HashMap::from([("u".into(), Value::String("s".into()))]));
+            Value::Object(ObjectMap::from([("u".into(), Value::String("s".into()))]));
         assert!(validate_field(&mut data_union, &rule_union).is_err());
 
         // 递归 array 覆盖
@@ -237,7 +237,7 @@ mod coverage_boost_tests {
             children: None,
             is_array: true,
         };
-        let mut data_arr = Value::Object(HashMap::from([(
+        let mut data_arr = Value::Object(ObjectMap::from([(
             "tags".into(),
             Value::Array(vec![Value::Int(1), Value::String("2".into())]),
         )]));
@@ -264,8 +264,9 @@ mod coverage_boost_tests {
             ValidationError::TypeMismatch {
                 field: "f".into(),
                 value: "v".into(),
-                expected: "Int".into(),
-                actual: "err".into(),
+                expected: FieldType::Int,
+                actual: "string",
+                reason: None,
             },
             ValidationError::UnionTypeMismatch {
                 field: "f".into(),
@@ -282,6 +283,9 @@ mod coverage_boost_tests {
                 value: "v".into(),
                 min: "1".into(),
                 max: "10".into(),
+                min_inclusive: true,
+                max_inclusive: true,
+                length: false,
             },
             ValidationError::RegexMismatch {
                 field: "f".into(),
@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod default_expr_tests {
+    use zz_validator::{
+        ast::{DefaultExpr, Value},
+        explain::explain,
+        export::to_json_schema,
+        import::from_json_schema,
+        json::from_json_str,
+        parser::Parser,
+        report::ChangeKind,
+        schema::Schema,
+        validator::{ValidationContext, ValidationError, validate_with_report},
+    };
+
+    const DSL: &str = r#"(
+        region:string = env(ZZ_VALIDATOR_TEST_REGION),
+        retries:int[0,5] = env(ZZ_VALIDATOR_TEST_RETRIES),
+        currency:string[3,3] = ctx(tenant_currency),
+        note?:string = ctx("missing")
+    )"#;
+
+    fn set_env() {
+        // 只在测试中设置本测试专用的变量
+        unsafe {
+            std::env::set_var("ZZ_VALIDATOR_TEST_REGION", "eu-west");
+            std::env::set_var("ZZ_VALIDATOR_TEST_RETRIES", "3");
+        }
+    }
+
+    #[test]
+    fn test_parse_and_export() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        assert!(matches!(
+            rules[0].default_expr(),
+            Some(DefaultExpr::Env(name)) if name == "ZZ_VALIDATOR_TEST_REGION"
+        ));
+        assert!(rules[0].default.is_none());
+        assert!(rules[0].has_default());
+        assert_eq!(
+            rules[2].default_expr(),
+            Some(&DefaultExpr::Ctx("tenant_currency".into()))
+        );
+
+        let dsl = Schema::new(rules.clone()).to_dsl();
+        assert!(
+            dsl.contains("retries:int[0, 5] = env(ZZ_VALIDATOR_TEST_RETRIES)"),
+            "{}",
+            dsl
+        );
+        assert_eq!(Schema::parse(&dsl).unwrap().to_dsl(), dsl);
+
+        // JSON Schema 中不算必填，表达式以文本保留
+        let schema = to_json_schema(&rules);
+        assert!(schema["required"].as_array().is_none_or(|r| r.is_empty()));
+        let imported = from_json_schema(&schema).unwrap();
+        let retries = imported.iter().find(|r| r.field == "retries").unwrap();
+        assert_eq!(
+            retries.default_expr(),
+            Some(&DefaultExpr::Env("ZZ_VALIDATOR_TEST_RETRIES".into()))
+        );
+
+        let text = explain(&rules, "currency").unwrap();
+        assert!(
+            text.contains("default:     ctx(tenant_currency)"),
+            "{}",
+            text
+        );
+        assert!(text.contains("constraints: [3, 3]"), "{}", text);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (dsl, message) in [
+            (
+                "(a:string = env(X) = ctx(y))",
+                "Field 'a' has more than one default",
+            ),
+            (
+                "(a:string = \"x\" = env(X))",
+                "Field 'a' has more than one default",
+            ),
+            (
+                "(a?:string present = env(X))",
+                "Field 'a' is marked present and cannot have a default",
+            ),
+            (
+                "(a:string = env())",
+                "Expected name in env(...), got Some(RParen)",
+            ),
+        ] {
+            let err = Parser::parse_rules(dsl).unwrap_err();
+            assert!(err.starts_with(message), "{}: {}", dsl, err);
+        }
+        assert!(Parser::parse_rules("(a:string warn(= env(X)))").is_err());
+
+        // 没有括号时仍是普通的字符串默认值
+        let rules = Parser::parse_rules("(a:string = env)").unwrap();
+        assert_eq!(rules[0].default, Some(Value::String("env".into())));
+        assert!(matches!(
+            rules[0].constraints.as_ref().map(|c| c.items.as_slice()),
+            None | Some([])
+        ));
+    }
+
+    #[test]
+    fn test_resolved_per_validation() {
+        set_env();
+        let schema = Schema::parse(DSL).unwrap();
+
+        let ctx = ValidationContext::new().var("tenant_currency", Value::String("EUR".into()));
+        let mut value = from_json_str("{}").unwrap();
+        schema.validate_with_context(&mut value, &ctx).unwrap();
+        let obj = value.as_object().unwrap();
+        assert_eq!(obj.get("region"), Some(&Value::String("eu-west".into())));
+        // 按字段类型转换
+        assert_eq!(obj.get("retries"), Some(&Value::Int(3)));
+        assert_eq!(obj.get("currency"), Some(&Value::String("EUR".into())));
+        assert!(obj.get("note").is_none());
+
+        // 不同请求使用不同的值，默认值同样要通过约束
+        let ctx = ValidationContext::new().var("tenant_currency", Value::String("YUAN".into()));
+        let mut value = from_json_str("{}").unwrap();
+        let err = schema.validate_with_context(&mut value, &ctx).unwrap_err();
+        assert_eq!(err.field(), Some("currency"));
+
+        // 取不到值时按字段缺失处理
+        let mut value = from_json_str("{}").unwrap();
+        assert!(matches!(
+            schema.validate(&mut value),
+            Err(ValidationError::MissingField(f)) if f == "currency"
+        ));
+
+        // 只读校验同样求值
+        let ctx = ValidationContext::new().var("tenant_currency", Value::String("USD".into()));
+        let value = from_json_str("{}").unwrap();
+        assert!(schema.validate_shared_with_context(&value, &ctx).is_ok());
+    }
+
+    #[test]
+    fn test_recorded_as_change() {
+        set_env();
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let ctx = ValidationContext::new().var("tenant_currency", Value::String("EUR".into()));
+        let mut value = from_json_str(r#"{"region": "us"}"#).unwrap();
+        let report = validate_with_report(&mut value, &rules, &ctx).unwrap();
+        let changes: Vec<String> = report.changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            changes,
+            ["retries: default 3", r#"currency: default "EUR""#]
+        );
+        assert!(report.changes.iter().all(|c| c.kind == ChangeKind::Default));
+    }
+}
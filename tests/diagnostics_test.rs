@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod diagnostics_tests {
+    use zz_validator::{
+        diagnostics::{render, render_all},
+        parser::Parser,
+    };
+
+    #[test]
+    fn test_render_caret_under_token() {
+        let dsl = "(\n  a:int,\n  b:strin,\n  c:bool\n)";
+        let errors = Parser::parse_rules_recovering(dsl).unwrap_err();
+        assert_eq!(
+            render(dsl, "user.vdsl", &errors[0]),
+            "error: Unknown type strin\n --> user.vdsl:3:5\n  |\n3 |   b:strin,\n  |     ^^^^^\n"
+        );
+    }
+
+    #[test]
+    fn test_render_compile_errors_and_end_of_input() {
+        // 约束与类型不符这类检查同样指向出错的位置
+        let dsl = "(name:string past)";
+        let errors = Parser::parse_rules_recovering(dsl).unwrap_err();
+        assert_eq!(
+            render(dsl, "", &errors[0]),
+            "error: past cannot be applied to field 'name' of type string\n --> 1:14\n  |\n1 | (name:string past)\n  |              ^^^^\n"
+        );
+
+        let dsl = "(\n  a:int,\n  b:string regex(\"x\"\n\n";
+        let errors = Parser::parse_rules_recovering(dsl).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            render(dsl, "", &errors[0]),
+            "error: Unexpected EOF\n --> 3:21\n  |\n3 |   b:string regex(\"x\"\n  |                     ^\n"
+        );
+    }
+
+    #[test]
+    fn test_render_all_and_tabs() {
+        let dsl = "(\n\ta:strin,\n\tb:int[0,10,20]\n)";
+        let errors = Parser::parse_rules_recovering(dsl).unwrap_err();
+        let text = render_all(dsl, "t.vdsl", &errors);
+        assert_eq!(errors.len(), 2);
+        assert!(text.contains("2 | \ta:strin,\n  | \t  ^^^^^\n"), "{}", text);
+        assert!(
+            text.contains("\n\nerror: Expected closing bracket or paren"),
+            "{}",
+            text
+        );
+        assert!(text.contains(" --> t.vdsl:3:12\n"), "{}", text);
+    }
+}
@@ -0,0 +1,209 @@
+#[cfg(test)]
+mod dsl_macro_tests {
+    use zz_validator::{ast::Value, json::from_json_str, parser::Parser, schema::Schema};
+
+    #[test]
+    fn test_pagination_defaults() {
+        let schema = Schema::parse("(pagination, q?:string)").unwrap();
+        let names: Vec<&str> = schema.rules().iter().map(|r| r.field.as_str()).collect();
+        assert_eq!(names, ["page", "limit", "sort", "q"]);
+
+        let mut value = from_json_str("{}").unwrap();
+        schema.validate(&mut value).unwrap();
+        let obj = value.as_object().unwrap();
+        assert_eq!(obj.get("page"), Some(&Value::Int(1)));
+        assert_eq!(obj.get("limit"), Some(&Value::Int(20)));
+
+        for ok in [
+            r#"{"page": 3, "limit": 100}"#,
+            r#"{"sort": "-created_at,name"}"#,
+            r#"{"sort": "user.name"}"#,
+        ] {
+            assert!(
+                schema.validate(&mut from_json_str(ok).unwrap()).is_ok(),
+                "{}",
+                ok
+            );
+        }
+        for bad in [
+            r#"{"page": 0}"#,
+            r#"{"limit": 101}"#,
+            r#"{"sort": "name;drop"}"#,
+            r#"{"sort": "--name"}"#,
+        ] {
+            assert!(
+                schema.validate(&mut from_json_str(bad).unwrap()).is_err(),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_pagination_parameters() {
+        let rules =
+            Parser::parse_rules("(filter:object(pagination(max_limit=50, default_limit=10)))")
+                .unwrap();
+        let schema = Schema::new(rules);
+        let mut value = from_json_str(r#"{"filter": {}}"#).unwrap();
+        schema.validate(&mut value).unwrap();
+        assert_eq!(
+            value
+                .as_object()
+                .unwrap()
+                .get("filter")
+                .map(|f| f.to_string()),
+            Some(r#"{"limit": 10, "page": 1}"#.to_string())
+        );
+        assert!(
+            schema
+                .validate(&mut from_json_str(r#"{"filter": {"limit": 51}}"#).unwrap())
+                .is_err()
+        );
+        // 展开后的字段按普通字段输出
+        assert!(schema.to_dsl().contains("limit:int[1, 50] = 10,"));
+
+        // 同名字段不受影响
+        assert!(Parser::parse_rules("(pagination:object(page:int))").is_ok());
+        assert!(
+            Parser::parse_rules("(pagination(max=5))")
+                .unwrap_err()
+                .starts_with("Unknown parameter 'max' for macro pagination")
+        );
+        assert!(
+            Parser::parse_rules("(pagination(max_limit=abc))")
+                .unwrap_err()
+                .starts_with("In macro pagination: ")
+        );
+        assert!(Parser::parse_rules("(pagination(max_limit=5, max_limit=6))").is_err());
+    }
+
+    #[test]
+    fn test_user_macros() {
+        let schema = Schema::parse(
+            r#"macro timestamps() = (created_at:datetime, updated_at?:datetime)
+macro owned(by=string) = (owner: {by}, ...timestamps())
+(
+    id:uuid,
+    ...timestamps(),
+    doc:object(name:string, ...owned(by=int))
+)"#,
+        )
+        .unwrap();
+        let names: Vec<&str> = schema.rules().iter().map(|r| r.field.as_str()).collect();
+        assert_eq!(names, ["id", "created_at", "updated_at", "doc"]);
+        let doc = &schema.rules()[3];
+        let children: Vec<String> = doc
+            .children
+            .iter()
+            .flatten()
+            .map(|r| format!("{}:{}", r.field, r.field_type))
+            .collect();
+        assert_eq!(
+            children,
+            [
+                "name:string",
+                "owner:int",
+                "created_at:datetime",
+                "updated_at:datetime"
+            ]
+        );
+
+        let mut value = from_json_str(
+            r#"{"id": "550e8400-e29b-41d4-a716-446655440000", "created_at": "2024-01-01T00:00:00Z",
+                "doc": {"name": "a", "owner": 7, "created_at": "2024-01-01T00:00:00Z"}}"#,
+        )
+        .unwrap();
+        assert!(schema.validate(&mut value).is_ok());
+
+        // 必填参数与内置宏的 `...` 形式
+        let rules = Parser::parse_rules(
+            "macro named(kind) = (name:{kind}[1, 10]) (...named(kind=string), ...pagination())",
+        )
+        .unwrap();
+        assert_eq!(rules.len(), 4);
+        assert!(
+            Parser::parse_rules("macro named(kind) = (name:{kind}) (...named())")
+                .unwrap_err()
+                .starts_with("Missing parameter 'kind' for macro named")
+        );
+    }
+
+    #[test]
+    fn test_macro_errors() {
+        let err = |dsl: &str| Parser::parse_rules(dsl).unwrap_err();
+        let ts = "macro ts() = (created_at:datetime) ";
+        assert!(
+            err(&format!("{}(created_at:string, ...ts())", ts))
+                .starts_with("Field 'created_at' from macro ts is already defined")
+        );
+        assert!(
+            err(&format!("{}(...ts(), created_at:string)", ts))
+                .starts_with("Field 'created_at' is already defined by macro ts")
+        );
+        assert!(
+            err(&format!("{}(o:object(...ts(), ...ts()))", ts))
+                .starts_with("Field 'created_at' from macro ts is already defined")
+        );
+        assert!(err("(...nope())").starts_with("Unknown macro 'nope'"));
+        assert!(
+            err("macro a() = (...a()) (...a())").starts_with("In macro a: Macro a expands itself")
+        );
+        assert!(
+            err("macro a() = (...b()) macro b() = (...a()) (...a())")
+                .starts_with("In macro a: In macro b: Macro a expands itself")
+        );
+        assert!(
+            err("macro a() = (x:{y}) (...a())").starts_with("Unknown parameter 'y' in macro a")
+        );
+        assert!(
+            err("macro a() = (x:int) macro a() = (y:int) (z:int)")
+                .starts_with("Macro a is already defined")
+        );
+        assert!(
+            err("macro pagination() = (x:int) (z:int)")
+                .starts_with("Macro pagination is already defined")
+        );
+        assert!(err("macro a(p, p) = (x:int) (z:int)").starts_with("Duplicate parameter 'p'"));
+        // 宏只在用到时解析，出错时指出所在的宏
+        assert!(err("macro a() = (x:nope) (...a())").starts_with("In macro a: "));
+        assert!(Parser::parse_rules("macro a() = (x:nope) (z:int)").is_ok());
+    }
+
+    #[test]
+    fn test_expansion_limit() {
+        // 每个宏调用上一个宏两次：放进两个对象中，或者上一个宏不产生任何字段
+        let nested = |levels: usize| {
+            let mut dsl = String::from("macro m0() = (x: int)\n");
+            for i in 1..=levels {
+                dsl += &format!(
+                    "macro m{}() = (a: object(...m{}()), b: object(...m{}()))\n",
+                    i,
+                    i - 1,
+                    i - 1
+                );
+            }
+            format!("{}(...m{}())", dsl, levels)
+        };
+        let empty = |levels: usize| {
+            let mut dsl = String::from("macro m0() = ()\n");
+            for i in 1..=levels {
+                dsl += &format!("macro m{}() = (...m{}(), ...m{}())\n", i, i - 1, i - 1);
+            }
+            format!("{}(...m{}(), x: int)", dsl, levels)
+        };
+        assert!(Parser::parse_rules(&nested(6)).is_ok());
+        assert!(Parser::parse_rules(&empty(6)).is_ok());
+
+        for dsl in [nested(40), empty(40)] {
+            let started = std::time::Instant::now();
+            let err = Parser::parse_rules(&dsl).unwrap_err();
+            assert!(
+                err.contains("Schema expands to more than 100000 rules"),
+                "{}",
+                err
+            );
+            assert!(started.elapsed() < std::time::Duration::from_secs(2));
+        }
+    }
+}
@@ -0,0 +1,243 @@
+#[cfg(test)]
+mod encrypted_tests {
+    use zz_validator::{
+        ast::Value,
+        json::from_json_str,
+        registry::ValidatorRegistry,
+        report::ErrorReport,
+        schema::Schema,
+        validator::{ValidationContext, ValidationOptions, validate_with_report},
+    };
+
+    const DSL: &str = r#"(
+        id: int,
+        card: object(number: string[12, 19], cvv: string[3, 4]) encrypted(kms),
+        ssn?: regex("^[0-9]{3}-[0-9]{2}-[0-9]{4}$") encrypted(kms),
+        pin?: int[1000, 9999] encrypted(kms)
+    )"#;
+
+    /// 测试用的“信封”：`enc:` 加上倒序的明文
+    fn seal(plain: &str) -> String {
+        format!("enc:{}", plain.chars().rev().collect::<String>())
+    }
+
+    fn ctx() -> ValidationContext {
+        let mut registry = ValidatorRegistry::new();
+        registry.register_decryptor("kms", |ciphertext: &str| {
+            ciphertext
+                .strip_prefix("enc:")
+                .map(|s| s.chars().rev().collect())
+                .ok_or_else(|| "bad envelope".to_string())
+        });
+        ValidationContext::new().registry(registry)
+    }
+
+    fn payload(card: &str) -> Value {
+        let mut value = from_json_str(r#"{"id": 1}"#).unwrap();
+        if let Value::Object(map) = &mut value {
+            map.insert("card".into(), Value::String(seal(card)));
+        }
+        value
+    }
+
+    fn check(value: &mut Value) -> Result<(), String> {
+        Schema::parse(DSL)
+            .unwrap()
+            .validate_with_context(value, &ctx())
+            .map_err(|e| format!("{} {}", e.code(), e))
+    }
+
+    #[test]
+    fn test_plaintext_is_validated() {
+        let mut value = payload(r#"{"number": "4111111111111111", "cvv": "123"}"#);
+        let before = value.clone();
+        assert_eq!(check(&mut value), Ok(()));
+        // 载荷中仍是密文
+        assert_eq!(value, before);
+
+        let mut value = payload(r#"{"number": "4111111111111111", "cvv": "12345"}"#);
+        let err = check(&mut value).unwrap_err();
+        assert!(err.starts_with("out_of_range card.cvv"), "{}", err);
+
+        let mut value = payload(r#"{"number": "4111111111111111"}"#);
+        assert_eq!(
+            check(&mut value).unwrap_err(),
+            "missing_field Missing required field card.cvv"
+        );
+    }
+
+    #[test]
+    fn test_scalar_plaintext() {
+        let mut value = payload(r#"{"number": "4111111111111111", "cvv": "123"}"#);
+        if let Value::Object(map) = &mut value {
+            map.insert("ssn".into(), Value::String(seal("123-45-6789")));
+            map.insert("pin".into(), Value::String(seal("4321")));
+        }
+        assert_eq!(check(&mut value.clone()), Ok(()));
+
+        if let Value::Object(map) = &mut value {
+            map.insert("ssn".into(), Value::String(seal("123456789")));
+        }
+        assert!(
+            check(&mut value.clone())
+                .unwrap_err()
+                .starts_with("type_mismatch ssn")
+        );
+
+        if let Value::Object(map) = &mut value {
+            map.insert("ssn".into(), Value::String(String::new()));
+            map.insert("pin".into(), Value::String(seal("12")));
+        }
+        assert!(
+            check(&mut value)
+                .unwrap_err()
+                .starts_with("out_of_range pin")
+        );
+    }
+
+    #[test]
+    fn test_errors_do_not_leak_plaintext() {
+        let schema = Schema::parse(
+            r#"(
+                ssn: regex("^[0-9]{3}-[0-9]{2}-[0-9]{4}$") encrypted(kms),
+                card: creditcard encrypted(kms),
+                pin?: int[1000, 9999] enum(1234, 4321) encrypted(kms),
+                note?: string warn([0, 3]) encrypted(kms)
+            )"#,
+        )
+        .unwrap();
+        let secrets = ["123-45-678X", "4111111111111112", "77", "top secret"];
+        let sealed = |values: [&str; 4]| {
+            let mut value = from_json_str("{}").unwrap();
+            if let Value::Object(map) = &mut value {
+                for (field, plain) in ["ssn", "card", "pin", "note"].into_iter().zip(values) {
+                    map.insert(field.into(), Value::String(seal(plain)));
+                }
+            }
+            value
+        };
+        let assert_redacted = |text: &str| {
+            for secret in secrets {
+                assert!(!text.contains(secret), "{} leaks {}", text, secret);
+            }
+        };
+
+        let exhaustive = ctx().options(ValidationOptions {
+            exhaustive: true,
+            ..Default::default()
+        });
+        for (i, field) in ["ssn", "card", "pin"].into_iter().enumerate() {
+            let mut values = ["123-45-6789", "4111111111111111", "1234", "ok"];
+            values[i] = secrets[i];
+            for ctx in [&ctx(), &exhaustive] {
+                let err = schema
+                    .validate_with_context(&mut sealed(values), ctx)
+                    .unwrap_err();
+                assert_eq!(err.field(), Some(field));
+                assert_redacted(&err.to_string());
+                assert_redacted(&format!("{:?}", ErrorReport::from_error(&err)));
+                assert!(err.value().is_none_or(|v| v == "<redacted>"), "{:?}", err);
+            }
+        }
+
+        // 警告同样不带明文
+        let mut value = sealed(["123-45-6789", "4111111111111111", "1234", secrets[3]]);
+        let report = validate_with_report(&mut value, schema.rules(), &ctx()).unwrap();
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].field(), Some("note"));
+        assert_redacted(&format!("{:?}", report.warnings));
+    }
+
+    #[test]
+    fn test_decryption_errors() {
+        let mut value = payload("{}");
+        if let Value::Object(map) = &mut value {
+            map.insert("card".into(), Value::String("plain".into()));
+        }
+        assert_eq!(
+            check(&mut value).unwrap_err(),
+            "decryption_failed card could not be decrypted with kms: bad envelope"
+        );
+
+        let mut value = payload("not json");
+        let err = check(&mut value).unwrap_err();
+        assert!(err.contains("plaintext is not valid JSON"), "{}", err);
+
+        let mut value =
+            from_json_str(r#"{"id": 1, "card": {"number": "4111111111111111", "cvv": "123"}}"#)
+                .unwrap();
+        assert!(
+            check(&mut value)
+                .unwrap_err()
+                .starts_with("type_mismatch card")
+        );
+
+        let mut value = payload(r#"{"number": "4111111111111111", "cvv": "123"}"#);
+        let err = Schema::parse(DSL)
+            .unwrap()
+            .validate(&mut value)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Unknown decryptor kms");
+    }
+
+    #[test]
+    fn test_options_leave_ciphertext() {
+        let options = ValidationOptions {
+            strict: true,
+            coerce: true,
+            ..Default::default()
+        };
+        let ctx = ctx().options(options);
+        let schema = Schema::parse(DSL).unwrap();
+
+        let mut value = payload(r#"{"number": "4111111111111111", "cvv": "123"}"#);
+        if let Value::Object(map) = &mut value {
+            map.insert("pin".into(), Value::String(seal("4321")));
+        }
+        let before = value.clone();
+        assert_eq!(schema.validate_with_context(&mut value, &ctx), Ok(()));
+        assert_eq!(value, before);
+
+        // strict 同样检查明文中的字段
+        let mut value = payload(r#"{"number": "4111111111111111", "cvv": "123", "pan": "x"}"#);
+        let err = schema.validate_with_context(&mut value, &ctx).unwrap_err();
+        assert_eq!(err.code(), "unknown_field");
+        assert_eq!(err.field(), Some("card.pan"));
+    }
+
+    #[cfg(feature = "json-value")]
+    #[test]
+    fn test_serde_json_value() {
+        let schema = Schema::parse(DSL).unwrap();
+        let card = seal(r#"{"number": "4111111111111111", "cvv": "1"}"#);
+        let mut json = serde_json::json!({"id": 1, "card": card});
+        let err = schema
+            .validate_json_with_context(&mut json, &ctx())
+            .unwrap_err();
+        assert_eq!(err.field(), Some("card.cvv"));
+        assert_eq!(json["card"], serde_json::json!(card));
+    }
+
+    #[test]
+    fn test_parse() {
+        let schema = Schema::parse(DSL).unwrap();
+        let dsl = schema.to_dsl();
+        assert!(dsl.contains(") encrypted(kms)"), "{}", dsl);
+        assert_eq!(Schema::parse(&dsl).unwrap().to_dsl(), dsl);
+
+        for (dsl, error) in [
+            (
+                r#"(pin?: int encrypted(kms) = 1234)"#,
+                "is encrypted and cannot have a default",
+            ),
+            (
+                r#"(pin: int warn(encrypted(kms)))"#,
+                "warn(...) cannot wrap",
+            ),
+            (r#"(pin: int encrypted())"#, "Expected decryptor name"),
+        ] {
+            let err = Schema::parse(dsl).unwrap_err();
+            assert!(err.contains(error), "{}: {}", dsl, err);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod error_report_tests {
+    use zz_validator::{
+        i18n::Catalog,
+        json::from_json_str,
+        report::ErrorReport,
+        schema::Schema,
+        validator::{ValidationContext, ValidationError, ValidationOptions},
+    };
+
+    const DSL: &str = r#"(
+        code:string[5,10] regex("^[0-9]+$"),
+        age:int[0,150]
+    )"#;
+
+    fn error(json: &str, max_errors: usize) -> ValidationError {
+        let ctx = ValidationContext::new().options(ValidationOptions {
+            exhaustive: true,
+            max_errors,
+            ..Default::default()
+        });
+        let mut value = from_json_str(json).unwrap();
+        Schema::parse(DSL)
+            .unwrap()
+            .validate_with_context(&mut value, &ctx)
+            .unwrap_err()
+    }
+
+    #[test]
+    fn test_groups_by_path() {
+        let e = error(r#"{"code": "ab", "age": 1}"#, 0);
+        let report = ErrorReport::from(&e);
+        assert_eq!(report.len(), 2);
+        assert_eq!(report.fields.len(), 1);
+        let failures = report.get("code").unwrap();
+        assert_eq!(failures[0].code, "out_of_range");
+        assert_eq!(failures[1].code, "regex_mismatch");
+        assert_eq!(failures[0].message, "code length 2 out of range [5, 10]");
+        assert!(report.get("age").is_none());
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(
+            json,
+            r#"{"errors":{"code":[{"code":"out_of_range","message":"code length 2 out of range [5, 10]","params":{"value":"2","min":"5","max":"10"}},{"code":"regex_mismatch","message":"code regex mismatch: ^[0-9]+$","params":{"pattern":"^[0-9]+$"}}]},"truncated":false}"#
+        );
+    }
+
+    #[test]
+    fn test_single_error_and_truncation() {
+        let e = error(r#"{"code": "12345", "age": 200}"#, 0);
+        let report = ErrorReport::from_error(&e);
+        assert_eq!(report.fields[0].path, "age");
+        assert!(!report.truncated);
+        assert_eq!(report.to_json()["errors"]["age"][0]["params"]["max"], "150");
+
+        let report = ErrorReport::from_error(&error(r#"{"code": "ab", "age": 1}"#, 1));
+        assert_eq!(report.len(), 1);
+        assert!(report.truncated);
+
+        let report = ErrorReport::from_error(&ValidationError::Custom("boom".into()));
+        assert_eq!(report.get("").unwrap()[0].message, "boom");
+    }
+
+    #[test]
+    fn test_localized() {
+        let catalog = Catalog::new().with("out_of_range", "{value} 不在 [{min}, {max}] 范围内");
+        let e = error(r#"{"code": "12345", "age": 200}"#, 0);
+        let report = ErrorReport::localized(&e, &catalog);
+        assert_eq!(
+            report.get("age").unwrap()[0].message,
+            "200 不在 [0, 150] 范围内"
+        );
+    }
+}
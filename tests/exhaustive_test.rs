@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod exhaustive_tests {
+    use zz_validator::{
+        ast::Value,
+        parser::Parser,
+        validator::{
+            ValidationContext, ValidationError, ValidationOptions, validate_object,
+            validate_with_context,
+        },
+    };
+
+    const DSL: &str = r#"(
+        username:string[3,20] regex("^[a-z]+$") enum("admin","root"),
+        profile:object(code:string[4,4] regex("^[0-9]+$"))
+    )"#;
+
+    fn check(username: Value, code: &str, exhaustive: bool) -> Result<(), ValidationError> {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut value = Value::Object(
+            [
+                ("username".to_string(), username),
+                (
+                    "profile".to_string(),
+                    Value::Object([("code".to_string(), Value::String(code.into()))].into()),
+                ),
+            ]
+            .into(),
+        );
+        let ctx = ValidationContext::new().options(ValidationOptions {
+            exhaustive,
+            ..Default::default()
+        });
+        validate_with_context(&mut value, &rules, &ctx)
+    }
+
+    #[test]
+    fn test_fail_fast_by_default() {
+        let err = check(Value::String("A1".into()), "1234", false).unwrap_err();
+        assert!(
+            matches!(err, ValidationError::EnumMismatch { .. }),
+            "{:?}",
+            err
+        );
+
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut value =
+            Value::Object([("username".to_string(), Value::String("A1".into()))].into());
+        assert!(matches!(
+            validate_object(&mut value, &rules),
+            Err(ValidationError::EnumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_reports_every_failing_constraint() {
+        let err = check(Value::String("A1".into()), "1234", true).unwrap_err();
+        let ValidationError::FieldErrors { field, errors, .. } = &err else {
+            panic!("expected FieldErrors, got {:?}", err);
+        };
+        assert_eq!(field, "username");
+        assert_eq!(errors.len(), 3);
+        assert!(matches!(errors[0], ValidationError::EnumMismatch { .. }));
+        assert!(matches!(errors[1], ValidationError::RangeError { .. }));
+        assert!(matches!(errors[2], ValidationError::RegexMismatch { .. }));
+        assert!(err.to_string().starts_with("username has 3 errors: "));
+    }
+
+    #[test]
+    fn test_single_failure_is_not_wrapped() {
+        let err = check(Value::String("nobody".into()), "1234", true).unwrap_err();
+        assert!(
+            matches!(err, ValidationError::EnumMismatch { .. }),
+            "{:?}",
+            err
+        );
+        assert!(check(Value::String("admin".into()), "1234", true).is_ok());
+
+        // 嵌套字段同样收集，内外都带完整路径
+        let err = check(Value::String("admin".into()), "x", true).unwrap_err();
+        assert!(
+            matches!(&err, ValidationError::FieldErrors { field, errors, .. } if field == "profile.code" && errors.len() == 2),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_type_mismatch_short_circuits() {
+        let err = check(Value::Int(7), "1234", true).unwrap_err();
+        assert!(
+            matches!(err, ValidationError::TypeMismatch { .. }),
+            "{:?}",
+            err
+        );
+    }
+}
@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod explain_tests {
+    use std::process::Command;
+
+    use zz_validator::{ast::find_rule, explain::explain, parser::Parser};
+
+    const DSL: &str = r#"(
+        username:string[3,20] regex("^[a-z_]+$"),
+        age?:int[0,150)=18,
+        role:string enum("admin","user")="user",
+        profile:object(
+            contact:object(
+                email:email,
+                phone?:phone
+            )
+        ),
+        tags:array<string[1,10]>,
+        users:array<object(name:string, id:int|string)>
+    )"#;
+
+    #[test]
+    fn test_find_rule_paths() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        assert_eq!(find_rule(&rules, "age").unwrap().field, "age");
+        assert_eq!(
+            find_rule(&rules, "profile.contact.email").unwrap().field,
+            "email"
+        );
+        assert_eq!(find_rule(&rules, "users[].name").unwrap().field, "name");
+        assert_eq!(find_rule(&rules, "users.id").unwrap().field, "id");
+        assert!(find_rule(&rules, "profile.missing").is_none());
+        assert!(find_rule(&rules, "age.inner").is_none());
+    }
+
+    #[test]
+    fn test_explain_output() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+
+        let out = explain(&rules, "username").unwrap();
+        assert!(out.contains("type:        string"));
+        assert!(out.contains("required:    true"));
+        assert!(out.contains(r#"constraints: [3, 20] regex("^[a-z_]+$")"#));
+
+        let out = explain(&rules, "age").unwrap();
+        assert!(out.contains("required:    false"));
+        assert!(out.contains("default:     18"));
+        assert!(out.contains("constraints: [0, 150)"));
+
+        let out = explain(&rules, "role").unwrap();
+        assert!(out.contains(r#"enum:        "admin", "user""#));
+        assert!(out.contains(r#"default:     "user""#));
+
+        let out = explain(&rules, "profile.contact").unwrap();
+        assert!(out.contains("fields:      email, phone"));
+
+        let out = explain(&rules, "tags").unwrap();
+        assert!(out.contains("type:        array<string [1, 10]>"));
+
+        let out = explain(&rules, "users.id").unwrap();
+        assert!(out.contains("type:        int|string"));
+
+        assert!(explain(&rules, "nope").is_err());
+    }
+
+    #[test]
+    fn test_cli_explain() {
+        let dir = std::env::temp_dir().join("zz_validator_explain_tests");
+        std::fs::create_dir_all(&dir).unwrap();
+        let schema = dir.join("user.vdsl");
+        std::fs::write(&schema, DSL).unwrap();
+
+        let out = Command::new(env!("CARGO_BIN_EXE_validator"))
+            .args(["explain", "--schema"])
+            .arg(&schema)
+            .arg("profile.contact.email")
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+        let stdout = String::from_utf8(out.stdout).unwrap();
+        assert!(stdout.contains("field:       profile.contact.email"));
+        assert!(stdout.contains("type:        email"));
+
+        let out = Command::new(env!("CARGO_BIN_EXE_validator"))
+            .args(["explain", "--schema"])
+            .arg(&schema)
+            .arg("profile.nope")
+            .output()
+            .unwrap();
+        assert!(!out.status.success());
+
+        let out = Command::new(env!("CARGO_BIN_EXE_validator"))
+            .output()
+            .unwrap();
+        assert_eq!(out.status.code(), Some(2));
+    }
+}
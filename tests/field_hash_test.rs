@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod field_hash_tests {
+    use zz_validator::{
+        json::from_json_str,
+        parser::Parser,
+        report::FieldHash,
+        validator::{ValidationContext, validate_with_report},
+    };
+
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    fn hashes(dsl: &str, json: &str) -> Result<Vec<(String, String)>, String> {
+        let rules = Parser::parse_rules(dsl)?;
+        let mut value = from_json_str(json)?;
+        let report = validate_with_report(&mut value, &rules, &ValidationContext::new())
+            .map_err(|e| e.to_string())?;
+        Ok(report
+            .hashes
+            .into_iter()
+            .map(|FieldHash { path, hash, .. }| (path, hash))
+            .collect())
+    }
+
+    #[test]
+    fn test_hashes_selected_fields() {
+        let dsl = r#"(
+            email: string hash_into_report(sha256),
+            name: string,
+            contacts?: array<object(phone: string hash_into_report(sha256))>,
+            prefs?: object hash_into_report(sha256)
+        )"#;
+        let got = hashes(
+            dsl,
+            r#"{"email": "abc", "name": "abc", "contacts": [{"phone": "abc"}, {"phone": "abc"}],
+                "prefs": {"b": [true], "a": 1}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            got,
+            vec![
+                ("email".to_string(), ABC_SHA256.to_string()),
+                ("contacts[0].phone".to_string(), ABC_SHA256.to_string()),
+                ("contacts[1].phone".to_string(), ABC_SHA256.to_string()),
+                // 对象按规范化 JSON 计算，与键的顺序无关
+                (
+                    "prefs".to_string(),
+                    "90eddf64b875cb5fa184bb12503cc7309b6ce21b175521a80bd8d83082bae604".to_string()
+                ),
+            ]
+        );
+
+        // 缺失的可选字段没有记录，校验失败时没有报告
+        let got = hashes(dsl, r#"{"email": "abc", "name": "x"}"#).unwrap();
+        assert_eq!(got.len(), 1);
+        assert!(hashes(dsl, r#"{"email": 1, "name": "x"}"#).is_err());
+    }
+
+    #[test]
+    fn test_hash_after_transform() {
+        let dsl = r#"(handle: string slugify hash_into_report(sha256))"#;
+        let got = hashes(dsl, r#"{"handle": "ABC"}"#).unwrap();
+        assert_eq!(got, vec![("handle".to_string(), ABC_SHA256.to_string())]);
+
+        let rules = Parser::parse_rules(dsl).unwrap();
+        let mut value = from_json_str(r#"{"handle": "ABC"}"#).unwrap();
+        let report = validate_with_report(&mut value, &rules, &ValidationContext::new()).unwrap();
+        assert_eq!(report.hashes[0].algorithm, "sha256");
+    }
+
+    #[test]
+    fn test_parse() {
+        let rules = Parser::parse_rules("(email: email hash_into_report(sha256))").unwrap();
+        let dsl = zz_validator::export::to_dsl(&rules);
+        assert!(dsl.contains("email hash_into_report(sha256)"), "{}", dsl);
+
+        let err = Parser::parse_rules("(email: email hash_into_report(md5))").unwrap_err();
+        assert!(err.contains("Unknown hash algorithm 'md5'"), "{}", err);
+        let err = Parser::parse_rules("(email: email warn(hash_into_report(sha256)))").unwrap_err();
+        assert!(err.contains("cannot wrap"), "{}", err);
+    }
+}
@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod float_epsilon_tests {
+    use zz_validator::{
+        ast::{Constraint, Value},
+        export::{to_dsl, to_json_schema},
+        import::from_json_schema,
+        parser::Parser,
+        schema::Schema,
+        validator::{ValidationContext, ValidationError, ValidationOptions, validate_with_context},
+    };
+
+    const DSL: &str = r#"(
+        price?:float multiple_of(0.1),
+        qty?:int multiple_of(5),
+        rate?:float enum(0.3, 0.5),
+        split?:array<float> sum==1 avg[0.3, 0.5],
+        lines?:array<object(total:float, sum:float)> each(total == sum)
+    )"#;
+
+    fn check(field: &str, value: Value, epsilon: f64) -> Result<(), ValidationError> {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let ctx = ValidationContext::new().options(ValidationOptions {
+            float_epsilon: epsilon,
+            ..Default::default()
+        });
+        let mut value = Value::Object([(field.to_string(), value)].into());
+        validate_with_context(&mut value, &rules, &ctx).map(|_| ())
+    }
+
+    fn floats(items: &[f64]) -> Value {
+        Value::Array(items.iter().map(|f| Value::Float(*f)).collect())
+    }
+
+    #[test]
+    fn test_parse_multiple_of() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        assert!(matches!(
+            &rules[0].constraints.as_ref().unwrap().items[0],
+            Constraint::MultipleOf(Value::Float(f)) if *f == 0.1
+        ));
+        let dsl = to_dsl(&rules);
+        assert!(dsl.contains("price?:float multiple_of(0.1)"));
+        assert!(dsl.contains("qty?:int multiple_of(5)"));
+        assert_eq!(to_dsl(&Parser::parse_rules(&dsl).unwrap()), dsl);
+
+        let schema = to_json_schema(&rules);
+        assert_eq!(schema["properties"]["qty"]["multipleOf"], 5);
+        let imported = to_dsl(&from_json_schema(&schema).unwrap());
+        assert!(imported.contains("qty?:int multiple_of(5)"));
+        assert!(imported.contains("price?:float multiple_of(0.1)"));
+
+        for bad in ["(a:int multiple_of(0))", "(a:string multiple_of(2))"] {
+            assert!(Parser::parse_rules(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_exact_by_default() {
+        let sum = 0.1 + 0.2;
+        assert!(check("qty", Value::Int(15), 0.0).is_ok());
+        assert_eq!(
+            check("qty", Value::Int(12), 0.0).unwrap_err().to_string(),
+            "qty = 12 is not a multiple of 5"
+        );
+        // 0.7 / 0.1 = 6.999999999999999，7 * 0.1 = 0.7000000000000001
+        assert!(check("price", Value::Float(0.7), 0.0).is_err());
+        assert!(check("rate", Value::Float(sum), 0.0).is_err());
+        assert!(check("split", floats(&[0.7, 0.2, 0.1]), 0.0).is_err());
+    }
+
+    #[test]
+    fn test_epsilon_tolerance() {
+        let eps = 1e-9;
+        let sum = 0.1 + 0.2;
+        assert!(check("price", Value::Float(0.7), eps).is_ok());
+        assert!(check("price", Value::Float(-1.2), eps).is_ok());
+        assert!(check("price", Value::Float(0.35), eps).is_err());
+        // int 之间仍精确取余
+        assert!(check("qty", Value::Int(12), eps).is_err());
+
+        assert!(check("rate", Value::Float(sum), eps).is_ok());
+        assert!(check("rate", Value::Float(0.31), eps).is_err());
+
+        // 0.7 + 0.2 + 0.1 = 0.9999999999999999
+        assert!(check("split", floats(&[0.7, 0.2, 0.1]), eps).is_ok());
+        assert!(check("split", floats(&[0.1, 0.2, 0.6]), eps).is_err());
+
+        let line = |total: f64, sum: f64| {
+            Value::Array(vec![Value::Object(
+                [
+                    ("total".to_string(), Value::Float(total)),
+                    ("sum".to_string(), Value::Float(sum)),
+                ]
+                .into(),
+            )])
+        };
+        assert!(check("lines", line(0.3, sum), 0.0).is_err());
+        assert!(check("lines", line(0.3, sum), eps).is_ok());
+        assert!(check("lines", line(0.3, 0.4), eps).is_err());
+    }
+
+    #[test]
+    fn test_epsilon_declared_with_schema() {
+        let schema = Schema::parse(&format!("meta(float_epsilon=1e-9) {}", DSL)).unwrap();
+        assert_eq!(schema.metadata().float_epsilon(), Some(1e-9));
+        assert!(schema.to_dsl().starts_with("meta(float_epsilon=1e-9)"));
+        let rate = |f: f64| Value::Object([("rate".to_string(), Value::Float(f))].into());
+
+        // 不论调用方如何设置，同一 schema 的比较结果一致
+        let exact = ValidationContext::new();
+        let loose = ValidationContext::new().options(ValidationOptions {
+            float_epsilon: 0.1,
+            ..Default::default()
+        });
+        for ctx in [&exact, &loose] {
+            assert!(
+                schema
+                    .validate_with_context(&mut rate(0.1 + 0.2), ctx)
+                    .is_ok()
+            );
+            assert!(schema.validate_with_context(&mut rate(0.31), ctx).is_err());
+            assert!(
+                schema
+                    .validate_shared_with_context(&rate(0.1 + 0.2), ctx)
+                    .is_ok()
+            );
+        }
+        assert!(schema.validate(&mut rate(0.1 + 0.2)).is_ok());
+        assert!(
+            schema
+                .validate_json_bytes(format!(r#"{{"rate": {}}}"#, 0.1 + 0.2).as_bytes())
+                .is_ok()
+        );
+        let mut values = vec![rate(0.1 + 0.2), rate(0.31)];
+        let results = schema.validate_batch(&mut values);
+        assert!(results[0].is_ok() && results[1].is_err());
+
+        // 未声明时仍使用调用方的设置
+        let plain = Schema::parse(DSL).unwrap();
+        assert_eq!(plain.metadata().float_epsilon(), None);
+        assert!(plain.validate(&mut rate(0.1 + 0.2)).is_err());
+        assert!(
+            plain
+                .validate_with_context(&mut rate(0.1 + 0.2), &loose)
+                .is_ok()
+        );
+
+        for bad in ["-1", "\"tiny\"", "true"] {
+            let dsl = format!("meta(float_epsilon={}) (a:float)", bad);
+            assert!(Schema::parse(&dsl).is_err(), "{}", bad);
+        }
+        assert!(Schema::parse("meta(float_epsilon=0) (a:float)").is_ok());
+    }
+}
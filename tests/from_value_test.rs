@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod from_value_tests {
+    use std::collections::HashMap;
+
+    use zz_validator::{
+        ast::Value, convert::ConvertError, impl_from_value, json::from_json_str, schema::Schema,
+        validator::ValidationError,
+    };
+
+    #[derive(Debug, PartialEq)]
+    struct Contact {
+        email: String,
+        phone: Option<String>,
+    }
+    impl_from_value!(Contact { email, phone });
+
+    #[derive(Debug, PartialEq)]
+    struct User {
+        name: String,
+        age: u8,
+        score: Option<f64>,
+        active: bool,
+        tags: Vec<String>,
+        contact: Contact,
+        labels: HashMap<String, i64>,
+        display_name: Option<String>,
+    }
+    impl_from_value!(User {
+        name,
+        age,
+        score,
+        active,
+        tags,
+        contact,
+        labels,
+        display_name = "displayName",
+    });
+
+    const DSL: &str = r#"(
+        name:string,
+        age:int[0,150]=18,
+        score?:float,
+        active:bool=true,
+        tags:array<string>,
+        contact:object(email:email, phone?:phone),
+        labels:object,
+        displayName?:string
+    )"#;
+
+    #[test]
+    fn test_decode_honors_defaults_and_optionals() {
+        let schema = Schema::parse(DSL).unwrap();
+        let value = from_json_str(
+            r#"{"name":"alice","tags":["a"],"contact":{"email":"a@example.com","phone":""},"labels":{"x":1}}"#,
+        )
+        .unwrap();
+        let user: User = schema.decode(value).unwrap();
+        assert_eq!(
+            user,
+            User {
+                name: "alice".into(),
+                age: 18,
+                score: None,
+                active: true,
+                tags: vec!["a".into()],
+                contact: Contact {
+                    email: "a@example.com".into(),
+                    phone: Some(String::new()),
+                },
+                labels: [("x".to_string(), 1)].into(),
+                display_name: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_reports_validation_errors() {
+        let schema = Schema::parse(DSL).unwrap();
+        let value = from_json_str(r#"{"tags":[],"contact":{"email":"a@example.com"},"labels":{}}"#)
+            .unwrap();
+        let err = schema.decode::<User>(value).unwrap_err();
+        assert_eq!(
+            err,
+            ConvertError::Invalid(ValidationError::MissingField("name".into()))
+        );
+    }
+
+    #[test]
+    fn test_try_from_errors_carry_paths() {
+        let value = from_json_str(r#"{"email":"a@example.com","phone":5}"#).unwrap();
+        assert_eq!(
+            Contact::try_from(value).unwrap_err().to_string(),
+            "phone expected string, found int"
+        );
+
+        let value = from_json_str(
+            r#"{"name":"a","age":300,"active":true,"tags":[],"contact":{"email":"x"},"labels":{}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            User::try_from(value).unwrap_err(),
+            ConvertError::OutOfRange {
+                path: "age".into(),
+                value: "300".into(),
+                target: "u8",
+            }
+        );
+
+        let value = from_json_str(
+            r#"{"name":"a","age":3,"active":true,"tags":["x",1],"contact":{},"labels":{}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            User::try_from(value).unwrap_err().to_string(),
+            "tags[1] expected string, found int"
+        );
+
+        let value = from_json_str(
+            r#"{"name":"a","age":3,"active":true,"tags":[],"contact":{},"labels":{}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            User::try_from(value).unwrap_err(),
+            ConvertError::Missing("contact.email".into())
+        );
+
+        assert!(Contact::try_from(Value::Int(1)).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod generated_default_tests {
+    use zz_validator::{
+        ast::{DefaultExpr, Value},
+        clock::FixedClock,
+        export::to_json_schema,
+        ids::{IdGenerator, RandomIds, SequentialIds},
+        import::from_json_schema,
+        json::from_json_str,
+        parser::Parser,
+        schema::Schema,
+        validator::ValidationContext,
+    };
+
+    const DSL: &str = r#"(
+        id:uuid = uuid(),
+        created_at:datetime = now(),
+        created:timestamp = now(),
+        day:date = today(),
+        trace?:string = uuid()
+    )"#;
+
+    // 2023-11-14T22:13:20Z
+    const NOW: i64 = 1_700_000_000;
+
+    fn ctx() -> ValidationContext {
+        ValidationContext::new()
+            .clock(FixedClock(NOW))
+            .ids(SequentialIds::new())
+    }
+
+    #[test]
+    fn test_stamped_during_validation() {
+        let schema = Schema::parse(DSL).unwrap();
+        let ctx = ctx();
+
+        let mut value = from_json_str("{}").unwrap();
+        schema.validate_with_context(&mut value, &ctx).unwrap();
+        let obj = value.as_object().unwrap();
+        assert_eq!(
+            obj.get("id"),
+            Some(&Value::String(
+                "00000000-0000-4000-8000-000000000001".into()
+            ))
+        );
+        assert_eq!(
+            obj.get("created_at"),
+            Some(&Value::String("2023-11-14T22:13:20Z".into()))
+        );
+        assert_eq!(obj.get("created"), Some(&Value::Int(NOW)));
+        assert_eq!(obj.get("day"), Some(&Value::String("2023-11-14".into())));
+        assert_eq!(
+            obj.get("trace"),
+            Some(&Value::String(
+                "00000000-0000-4000-8000-000000000002".into()
+            ))
+        );
+
+        // 调用方提供的值不会被覆盖，每次校验重新生成
+        let mut value =
+            from_json_str(r#"{"id":"6f1c2a4e-9b7d-4c3e-8a5f-2d1e0b9c8a7f","day":"2020-01-01"}"#)
+                .unwrap();
+        schema.validate_with_context(&mut value, &ctx).unwrap();
+        let obj = value.as_object().unwrap();
+        assert_eq!(
+            obj.get("id"),
+            Some(&Value::String(
+                "6f1c2a4e-9b7d-4c3e-8a5f-2d1e0b9c8a7f".into()
+            ))
+        );
+        assert_eq!(obj.get("day"), Some(&Value::String("2020-01-01".into())));
+        assert_eq!(
+            obj.get("trace"),
+            Some(&Value::String(
+                "00000000-0000-4000-8000-000000000003".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_today_is_midnight_and_union_types() {
+        let rules = Parser::parse_rules("(at:timestamp|string = today())").unwrap();
+        let mut value = from_json_str("{}").unwrap();
+        Schema::new(rules)
+            .validate_with_context(&mut value, &ctx())
+            .unwrap();
+        assert_eq!(
+            value.as_object().unwrap().get("at"),
+            Some(&Value::Int(NOW - NOW % 86400))
+        );
+    }
+
+    #[test]
+    fn test_parse_and_export() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        assert_eq!(rules[0].default_expr(), Some(&DefaultExpr::Uuid));
+        assert_eq!(rules[1].default_expr(), Some(&DefaultExpr::Now));
+        assert_eq!(rules[3].default_expr(), Some(&DefaultExpr::Today));
+
+        let dsl = Schema::new(rules.clone()).to_dsl();
+        assert!(dsl.contains("created_at:datetime = now()"), "{}", dsl);
+        assert_eq!(Schema::parse(&dsl).unwrap().to_dsl(), dsl);
+
+        let imported = from_json_schema(&to_json_schema(&rules)).unwrap();
+        let day = imported.iter().find(|r| r.field == "day").unwrap();
+        assert_eq!(day.default_expr(), Some(&DefaultExpr::Today));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (dsl, message) in [
+            (
+                "(a:int = now())",
+                "= now() cannot be applied to field 'a' of type int",
+            ),
+            (
+                "(a:bool = uuid())",
+                "= uuid() cannot be applied to field 'a' of type bool",
+            ),
+            ("(a:date = today(x))", "Expected RParen, got Ident(\"x\")"),
+        ] {
+            let err = Parser::parse_rules(dsl).unwrap_err();
+            assert!(err.starts_with(message), "{}: {}", dsl, err);
+        }
+        // 没有括号时仍是普通的字符串默认值
+        let rules = Parser::parse_rules("(a:string = uuid)").unwrap();
+        assert_eq!(rules[0].default, Some(Value::String("uuid".into())));
+    }
+
+    #[test]
+    fn test_random_ids_are_v4() {
+        let a = RandomIds.uuid();
+        let b = RandomIds.uuid();
+        assert_ne!(a, b);
+        let mut value = from_json_str(&format!(r#"{{"id":"{}"}}"#, a)).unwrap();
+        Schema::parse("(id:uuid(v4))")
+            .unwrap()
+            .validate(&mut value)
+            .unwrap();
+    }
+}
@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod generator_tests {
+    use std::process::Command;
+
+    use zz_validator::{
+        generator::Generator,
+        json::{from_json_str, to_json_string},
+        parser::Parser,
+        validator::validate,
+    };
+
+    const DSL: &str = r#"(
+        id:uuid,
+        username:string[3,20] regex("^[a-zA-Z0-9_]+$"),
+        email:email,
+        age:int[0,150]=18,
+        score:float(0,100),
+        role:string enum("admin","user","guest")="user",
+        active:bool=true,
+        nickname?:string[0,20],
+        code:regex("^[0-9]{4}$"),
+        profile:object(
+            first_name:string[1,50],
+            contact:object(
+                phone?:phone,
+                site?:uri
+            )
+        ),
+        tags:array<string[1,10]>,
+        users:array<object(name:alpha, card:creditcard)>,
+        version:semver,
+        location:object(lat:lat, lng:lng),
+        id_or_name:int|hostname,
+        misc:object(
+            ip:ip, mac:mac, date:date, datetime:datetime, time:time, ts:timestamp,
+            color:color, slug:slug, hex:hex, b64:base64, pwd:password, tok:token,
+            isbn:isbn, port:port, json:json, enc:urlencoded, user:username,
+            country:countrycode, postal:postalcode, path:filepath, alnum:alphanumeric,
+            ulid:ulid, ksuid:ksuid, nano:nanoid, short_nano:nanoid(10), v7:uuid(v7, compact),
+            rgba:color(hex, alpha), css:color(rgb), hue:color(hsl),
+            addr:socketaddr, ip_addr:ip_port
+        )
+    )"#;
+
+    #[test]
+    fn test_valid_payloads_pass_validation() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut generator = Generator::new(7);
+        for _ in 0..50 {
+            let value = generator.valid(&rules).unwrap();
+            assert!(validate(&value, &rules).is_some(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_invalid_payloads_fail_validation() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut generator = Generator::new(11);
+        for _ in 0..50 {
+            let value = generator.invalid(&rules).unwrap();
+            assert!(validate(&value, &rules).is_none(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_data() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let a = Generator::new(42).valid(&rules).unwrap();
+        let b = Generator::new(42).valid(&rules).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_unsatisfiable_rules() {
+        // 正则无法由内置字符集满足
+        let rules = Parser::parse_rules(r#"(a:string regex("^\\$\\$$"))"#).unwrap();
+        assert!(Generator::new(1).valid(&rules).is_err());
+        assert!(Generator::new(1).invalid(&[]).is_err());
+    }
+
+    #[test]
+    fn test_cli_generate_ndjson() {
+        let dir = std::env::temp_dir().join("zz_validator_generate_tests");
+        std::fs::create_dir_all(&dir).unwrap();
+        let schema = dir.join("user.vdsl");
+        std::fs::write(&schema, DSL).unwrap();
+        let rules = Parser::parse_rules(DSL).unwrap();
+
+        let out = Command::new(env!("CARGO_BIN_EXE_validator"))
+            .args(["generate", "--schema"])
+            .arg(&schema)
+            .args(["--count", "20", "--invalid-rate", "0.25", "--seed", "3"])
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+        let stdout = String::from_utf8(out.stdout).unwrap();
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), 20);
+
+        let invalid = lines
+            .iter()
+            .map(|line| from_json_str(line).unwrap())
+            .filter(|v| validate(v, &rules).is_none())
+            .count();
+        assert_eq!(invalid, 5);
+
+        let out = Command::new(env!("CARGO_BIN_EXE_validator"))
+            .args(["generate", "--schema"])
+            .arg(&schema)
+            .args(["--invalid-rate", "2"])
+            .output()
+            .unwrap();
+        assert!(!out.status.success());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let value = from_json_str(r#"{"b":[1,2.5,"x",true],"a":{"c":null,"d":"e"}}"#).unwrap();
+        assert_eq!(
+            to_json_string(&value),
+            r#"{"a":{"d":"e"},"b":[1,2.5,"x",true]}"#
+        );
+        assert!(from_json_str("{").is_err());
+    }
+}
@@ -0,0 +1,233 @@
+#[cfg(test)]
+mod i18n_tests {
+    use std::sync::Arc;
+
+    use zz_validator::{
+        i18n::{Catalog, Labels, Locales, MessageResolver, render},
+        json::from_json_str,
+        report::ErrorReport,
+        schema::Schema,
+        validator::{ValidationContext, ValidationError, ValidationOptions},
+    };
+
+    fn error(dsl: &str, json: &str) -> ValidationError {
+        let mut value = from_json_str(json).unwrap();
+        Schema::parse(dsl)
+            .unwrap()
+            .validate(&mut value)
+            .unwrap_err()
+    }
+
+    fn zh() -> Catalog {
+        Catalog::new()
+            .with("missing_field", "缺少必填字段 {field}")
+            .with(
+                "out_of_range",
+                "{field} 的值 {value} 不在 [{min}, {max}] 范围内",
+            )
+            .with("enum_mismatch", "{field} 只能是 {expected}")
+            .with("field_errors", "{field}：{errors}")
+    }
+
+    #[test]
+    fn test_params() {
+        let e = error("(age:int[0,150])", r#"{"age": 200}"#);
+        assert_eq!(
+            e.params(),
+            [
+                ("field", "age".to_string()),
+                ("value", "200".to_string()),
+                ("min", "0".to_string()),
+                ("max", "150".to_string()),
+            ]
+        );
+
+        let e = error(r#"(role:string enum("a", "b"))"#, r#"{"role": "c"}"#);
+        assert_eq!(e.params()[2], ("expected", r#""a", "b""#.to_string()));
+    }
+
+    #[test]
+    fn test_localized() {
+        let catalog = zh();
+        let e = error("(age:int[0,150])", r#"{"age": 200}"#);
+        assert_eq!(e.localized(&catalog), "age 的值 200 不在 [0, 150] 范围内");
+
+        let e = error("(profile:object(name:string))", r#"{"profile": {}}"#);
+        assert_eq!(e.localized(&catalog), "缺少必填字段 profile.name");
+
+        // 没有模板时使用英文文本
+        let e = error(r#"(code:string regex("^[0-9]+$"))"#, r#"{"code": "x"}"#);
+        assert_eq!(e.localized(&catalog), e.to_string());
+    }
+
+    #[test]
+    fn test_localized_field_errors() {
+        let schema = Schema::parse(r#"(code:string[5,10] regex("^[0-9]+$"))"#).unwrap();
+        let ctx = ValidationContext::new().options(ValidationOptions {
+            exhaustive: true,
+            ..Default::default()
+        });
+        let mut value = from_json_str(r#"{"code": "ab"}"#).unwrap();
+        let e = schema.validate_with_context(&mut value, &ctx).unwrap_err();
+
+        let message = e.localized(&zh());
+        assert_eq!(
+            message,
+            "code：code 的值 2 不在 [5, 10] 范围内; code regex mismatch: ^[0-9]+$"
+        );
+
+        let partial = Catalog::new().with("out_of_range", "{field} 超出范围");
+        let message = e.localized(&partial);
+        assert!(message.starts_with("code has 2 errors: code 超出范围; code regex"));
+    }
+
+    #[test]
+    fn test_render() {
+        let params = [("field", "age".to_string())];
+        assert_eq!(render("{field} 无效", &params), "age 无效");
+        assert_eq!(render("{other} {field}", &params), "{other} age");
+        assert_eq!(render("{field", &params), "{field");
+        assert_eq!(render("{{field}}", &params), "{age}");
+    }
+
+    #[test]
+    fn test_from_toml() {
+        let catalog = Catalog::from_toml(
+            r#"
+            missing_field = "Pflichtfeld {field} fehlt"
+            custom = "{message}"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(catalog.len(), 2);
+        assert_eq!(
+            catalog.get("missing_field"),
+            Some("Pflichtfeld {field} fehlt")
+        );
+
+        let e = error("(name:string)", "{}");
+        assert_eq!(e.localized(&catalog), "Pflichtfeld name fehlt");
+
+        let err = Catalog::from_toml("missing_field = 1").unwrap_err();
+        assert!(err.contains("missing_field"));
+        assert!(Catalog::from_toml("missing_field = ").is_err());
+    }
+
+    struct Upper;
+
+    impl MessageResolver for Upper {
+        fn resolve(&self, code: &str, _params: &[(&'static str, String)]) -> Option<String> {
+            Some(code.to_uppercase())
+        }
+    }
+
+    #[test]
+    fn test_custom_resolver() {
+        let resolver: Arc<dyn MessageResolver> = Arc::new(Upper);
+        let e = error("(name:string)", "{}");
+        assert_eq!(e.localized(&resolver), "MISSING_FIELD");
+    }
+
+    #[test]
+    fn test_label_placeholder() {
+        let dsl = r#"(
+            profile:object(contact:object(email:string regex("@") label("Email address"))),
+            users:array<object(name:string[2,10] label("Name"))>,
+            age:int[0,150]
+        )"#;
+        let schema = Schema::parse(dsl).unwrap();
+        let labels = schema.labels();
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels.get("users[3].name"), Some("Name"));
+        assert_eq!(labels.label_for("age"), "age");
+
+        let catalog = Catalog::new()
+            .with("regex_mismatch", "{label} is invalid")
+            .with("out_of_range", "{label} is out of range");
+        let mut value =
+            from_json_str(r#"{"profile":{"contact":{"email":"x"}},"users":[],"age":1}"#).unwrap();
+        let e = schema.validate(&mut value).unwrap_err();
+        assert_eq!(
+            e.localized_with_labels(&catalog, &labels),
+            "Email address is invalid"
+        );
+        // 没有 labels 时 {label} 为字段路径
+        assert_eq!(e.localized(&catalog), "profile.contact.email is invalid");
+
+        let mut value = from_json_str(
+            r#"{"profile":{"contact":{"email":"a@b"}},"users":[{"name":"ok"},{"name":"x"}],"age":1}"#,
+        )
+        .unwrap();
+        let e = schema.validate(&mut value).unwrap_err();
+        let report = ErrorReport::localized_with_labels(&e, &catalog, &labels);
+        assert_eq!(
+            report.get("users[1].name").unwrap()[0].message,
+            "Name is out of range"
+        );
+    }
+
+    #[test]
+    fn test_label_annotation() {
+        let schema = Schema::parse(r#"(tags:array<string label("Tag")>)"#).unwrap();
+        assert_eq!(schema.labels().get("tags[0]"), Some("Tag"));
+        assert!(schema.to_dsl().contains(r#"label("Tag")"#));
+        assert!(Schema::parse("(a:int label(1))").is_err());
+        assert!(Schema::parse(r#"(a:int warn(label("A")))"#).is_err());
+
+        let mut labels = Labels::new();
+        labels.insert("a[].b", "B");
+        assert_eq!(labels.label_for("a[12].b"), "B");
+    }
+
+    fn locales() -> Locales {
+        Locales::new().with("zh-CN", zh()).with(
+            "de",
+            Catalog::new().with("missing_field", "Pflichtfeld {field} fehlt"),
+        )
+    }
+
+    #[test]
+    fn test_negotiate_accept_language() {
+        let locales = locales();
+        assert_eq!(locales.negotiate("de"), Some("de"));
+        assert_eq!(locales.negotiate("de-CH, zh;q=0.9"), Some("de"));
+        assert_eq!(locales.negotiate("fr, zh;q=0.5, de;q=0.3"), Some("zh-CN"));
+        assert_eq!(locales.negotiate("de;q=0.2, ZH-cn;q=0.7"), Some("zh-CN"));
+        assert_eq!(locales.negotiate("de;q=0, zh"), Some("zh-CN"));
+        assert_eq!(locales.negotiate("fr, en;q=0.8"), None);
+        assert_eq!(locales.negotiate(""), None);
+        assert_eq!(locales.negotiate("*"), Some("zh-CN"));
+        assert_eq!(locales.negotiate("de;q=abc"), None);
+
+        let locales = locales.fallback("de");
+        assert_eq!(locales.negotiate("fr"), Some("de"));
+        assert_eq!(locales.negotiate("fr, *;q=0.1"), Some("de"));
+        assert_eq!(locales.tags().collect::<Vec<_>>(), ["zh-CN", "de"]);
+    }
+
+    #[test]
+    fn test_negotiated_report() {
+        let locales = locales();
+        let e = error("(name:string)", "{}");
+        let report = ErrorReport::negotiated(&e, &locales, "zh, de;q=0.5");
+        assert_eq!(report.get("name").unwrap()[0].message, "缺少必填字段 name");
+        // zh-TW 不会使用 zh-CN 的消息包
+        let report = ErrorReport::negotiated(&e, &locales, "zh-TW, de;q=0.5");
+        assert_eq!(
+            report.get("name").unwrap()[0].message,
+            "Pflichtfeld name fehlt"
+        );
+        let report = ErrorReport::negotiated(&e, &locales, "de-AT");
+        assert_eq!(
+            report.get("name").unwrap()[0].message,
+            "Pflichtfeld name fehlt"
+        );
+        let report = ErrorReport::negotiated(&e, &locales, "fr");
+        assert_eq!(report.get("name").unwrap()[0].message, e.to_string());
+
+        // 选中的语言没有对应模板时使用英文
+        let e = error("(age:int[0,150])", r#"{"age":200}"#);
+        let report = ErrorReport::negotiated(&e, &locales, "de");
+        assert_eq!(report.get("age").unwrap()[0].message, e.to_string());
+    }
+}
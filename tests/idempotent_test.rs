@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod idempotent_tests {
+    use zz_validator::{
+        clock::FixedClock,
+        generator::Generator,
+        ids::SequentialIds,
+        json::from_json_str,
+        parser::Parser,
+        validator::{ValidationContext, ValidationError, ValidationOptions, validate_with_report},
+    };
+
+    const DSL: &str = r#"(
+        id:uuid = uuid(),
+        created:datetime = now(),
+        age:int[0,150],
+        score:float,
+        active:bool = true,
+        role:string enum("user","admin") = "user",
+        mac?:mac,
+        color?:color,
+        profile:object(lang:string = en, tags?:array<string[1,10]>),
+        items?:array<object(qty:int[1,99] = 1, price:float)>
+    )"#;
+
+    fn ctx(frozen: bool) -> ValidationContext {
+        ValidationContext::new()
+            .options(ValidationOptions {
+                coerce: true,
+                canonicalize: true,
+                frozen,
+                ..Default::default()
+            })
+            .clock(FixedClock(1_700_000_000))
+            .ids(SequentialIds::new())
+    }
+
+    #[test]
+    fn test_second_pass_changes_nothing() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut value = from_json_str(
+            r##"{
+                "age": "42",
+                "score": 1,
+                "mac": "aa-bb-cc-dd-ee-ff",
+                "color": "#ABC",
+                "profile": {},
+                "items": [{"price": "2.5"}]
+            }"##,
+        )
+        .unwrap();
+        let first = validate_with_report(&mut value, &rules, &ctx(false)).unwrap();
+        assert!(!first.changes.is_empty());
+
+        let validated = value.clone();
+        let second = validate_with_report(&mut value, &rules, &ctx(false)).unwrap();
+        assert_eq!(value, validated);
+        assert!(second.changes.is_empty(), "{:?}", second.changes);
+        assert!(validate_with_report(&mut value, &rules, &ctx(true)).is_ok());
+    }
+
+    #[test]
+    fn test_generated_payloads_are_stable() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut generator = Generator::new(7);
+        for _ in 0..200 {
+            let mut value = generator.valid(&rules).unwrap();
+            validate_with_report(&mut value, &rules, &ctx(false)).unwrap();
+            let validated = value.clone();
+            let report = validate_with_report(&mut value, &rules, &ctx(true)).unwrap();
+            assert!(report.changes.is_empty());
+            assert_eq!(value, validated);
+        }
+    }
+
+    #[test]
+    fn test_frozen_rejects_mutations_without_applying_them() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let input = r#"{"id":"550E8400-E29B-41D4-A716-446655440000","created":"2024-01-01T00:00:00Z","age":1,"score":1.0,"active":true,"role":"user","profile":{"lang":"en"}}"#;
+        let mut value = from_json_str(input).unwrap();
+        let err = validate_with_report(&mut value, &rules, &ctx(true)).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::WouldMutate {
+                field: "id".into(),
+                change: "normalize".into(),
+            }
+        );
+        assert_eq!(err.code(), "would_mutate");
+        assert_eq!(
+            err.to_string(),
+            "id would be changed by validation (normalize)"
+        );
+        // 载荷保持原样
+        assert_eq!(value, from_json_str(input).unwrap());
+
+        let mut value = from_json_str(r#"{"age":1,"score":1.0,"profile":{}}"#).unwrap();
+        let err = validate_with_report(&mut value, &rules, &ctx(true)).unwrap_err();
+        assert_eq!(err.field(), Some("id"));
+        assert_eq!(err.params()[1], ("change", "default".to_string()));
+
+        // 载荷本身不合法时照常报告校验错误
+        let mut value = from_json_str(r#"{"age":"x","score":1.0,"profile":{}}"#).unwrap();
+        let err = validate_with_report(&mut value, &rules, &ctx(true)).unwrap_err();
+        assert_eq!(err.field(), Some("age"));
+    }
+}
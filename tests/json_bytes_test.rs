@@ -0,0 +1,211 @@
+#[cfg(test)]
+mod json_bytes_tests {
+    use std::sync::Arc;
+
+    use zz_validator::{
+        ast::Value,
+        audit::MemoryAuditSink,
+        json::from_json_str,
+        schema::Schema,
+        validator::{ValidationContext, ValidationError, ValidationOptions, validate_object},
+    };
+
+    const SCHEMA: &str = r#"(
+        id:uuid, name:string[1,20], age:int[0,150]=18, role:string enum("admin","user")="user",
+        nickname?:string[0,5], id_or_name:int|hostname,
+        profile:object(first:string[1,50], contact:object(phone?:phone, site?:uri)),
+        tags:array<string[1,10]>, scores?:array<int[0,100]> sorted count>=1,
+        users?:array<object(name:alpha, email:email)>, meta?:object
+    )"#;
+
+    const VALID: &str = r#"{
+        "id": "550e8400-e29b-41d4-a716-446655440000", "name": "alice", "id_or_name": 7,
+        "profile": {"first": "Alice", "contact": {"site": "https://example.com"}},
+        "tags": ["a", "b"], "users": [{"name": "bob", "email": "bob@example.com"}],
+        "unknown": {"deeply": [1, 2, {"ignored": true}]}, "meta": {"any": 1}
+    }"#;
+
+    fn both(
+        schema: &Schema,
+        json: &str,
+    ) -> (Result<(), ValidationError>, Result<(), ValidationError>) {
+        let streamed = schema.validate_json_bytes(json.as_bytes());
+        let mut value = from_json_str(json).unwrap();
+        (streamed, validate_object(&mut value, schema.rules()))
+    }
+
+    #[test]
+    fn test_matches_validate_object() {
+        let schema = Schema::parse(SCHEMA).unwrap();
+        let cases = [
+            (VALID, true),
+            (&VALID.replace(r#""alice""#, r#""""#), false),
+            (
+                &VALID.replace(
+                    r#""tags": ["a", "b"]"#,
+                    r#""tags": ["a", "this is too long"]"#,
+                ),
+                false,
+            ),
+            (
+                &VALID.replace(r#""tags": ["a", "b"]"#, r#""tags": "a""#),
+                false,
+            ),
+            (
+                &VALID.replace(r#""tags": ["a", "b"]"#, r#""tags": null"#),
+                false,
+            ),
+            (
+                &VALID.replace(r#""first": "Alice""#, r#""first": 1"#),
+                false,
+            ),
+            (
+                &VALID.replace(r#""site": "https://example.com""#, r#""phone": null"#),
+                true,
+            ),
+            (&VALID.replace(r#""bob@example.com""#, r#""bob""#), false),
+            (
+                &VALID.replace(r#""id_or_name": 7"#, r#""id_or_name": "example.com""#),
+                true,
+            ),
+            (
+                &VALID.replace(r#""id_or_name": 7"#, r#""id_or_name": true"#),
+                false,
+            ),
+            (
+                &VALID.replace(r#""name": "alice""#, r#""name": "alice", "age": 200"#),
+                false,
+            ),
+            (
+                &VALID.replace(r#""name": "alice""#, r#""name": "alice", "role": "root""#),
+                false,
+            ),
+            (
+                &VALID.replace(r#""name": "alice""#, r#""name": "alice", "nickname": """#),
+                true,
+            ),
+            (
+                &VALID.replace(r#""name": "alice""#, r#""name": "alice", "scores": [3, 1]"#),
+                false,
+            ),
+            (
+                &VALID.replace(r#""name": "alice""#, r#""name": "alice", "scores": [1, 3]"#),
+                true,
+            ),
+            (
+                &VALID.replace(r#""meta": {"any": 1}"#, r#""meta": [1]"#),
+                false,
+            ),
+        ];
+        for (json, ok) in cases {
+            let (streamed, materialized) = both(&schema, json);
+            assert_eq!(streamed.is_ok(), ok, "{}: {:?}", json, streamed);
+            assert_eq!(materialized.is_ok(), ok, "{}: {:?}", json, materialized);
+        }
+    }
+
+    #[test]
+    fn test_reports_same_error() {
+        let schema = Schema::parse("(name:string[1,5], age:int=18, tags:array<int[0,9]>)").unwrap();
+        for json in [
+            r#"{"tags": []}"#,
+            r#"{"name": "toolong", "tags": []}"#,
+            r#"{"name": "bob", "tags": [1, 10]}"#,
+            r#"{"name": "bob", "tags": [1, "x"]}"#,
+            r#"{"name": "bob", "age": 1.5, "tags": []}"#,
+            r#"[1]"#,
+            r#""text""#,
+        ] {
+            let (streamed, materialized) = both(&schema, json);
+            assert_eq!(streamed, materialized, "{}", json);
+        }
+
+        // 默认值本身不合规时同样报错
+        let schema = Schema::new(
+            zz_validator::parser::Parser::new(zz_validator::token::tokenize("(a:int=x)").unwrap())
+                .lossy_defaults(zz_validator::parser::LossyDefaults::Allow)
+                .parse_program()
+                .unwrap(),
+        );
+        let (streamed, materialized) = both(&schema, "{}");
+        assert!(streamed.is_err());
+        assert_eq!(streamed, materialized);
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let schema = Schema::parse("(a:int)").unwrap();
+        for json in [r#"{"a": 1"#, r#"{"a": 1} x"#, "", "{\"a\": 01}"] {
+            let err = schema.validate_json_bytes(json.as_bytes()).unwrap_err();
+            assert!(
+                err.to_string().contains("Invalid JSON"),
+                "{}: {}",
+                json,
+                err
+            );
+        }
+        // 嵌套过深的输入报错而不是栈溢出；serde_json 跳过未声明的字段时不递归，不受深度限制
+        let deep = format!("{}1{}", "[".repeat(10_000), "]".repeat(10_000));
+        let skipped = format!("{{\"b\": {}, \"a\": 1}}", deep);
+        assert_eq!(
+            schema.validate_json_bytes(skipped.as_bytes()).is_ok(),
+            cfg!(any(not(feature = "simd-json"), feature = "bigint"))
+        );
+        let declared = format!("{{\"a\": {}}}", deep);
+        assert!(schema.validate_json_bytes(declared.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_whole_document_rules() {
+        let schema = Schema::parse(
+            "(users:array<object(id:int)>, orders:array<object(user_id:int ref(users[].id))>)",
+        )
+        .unwrap();
+        let ok = r#"{"orders": [{"user_id": 1}], "users": [{"id": 1}]}"#;
+        assert!(schema.validate_json_bytes(ok.as_bytes()).is_ok());
+        let bad = r#"{"orders": [{"user_id": 2}], "users": [{"id": 1}]}"#;
+        assert!(schema.validate_json_bytes(bad.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_with_context() {
+        // 缺失字段的默认值表达式读取调用方上下文中的变量
+        let schema = Schema::parse("(a:int, currency:string[3,3] = ctx(currency))").unwrap();
+        let ctx = ValidationContext::new().var("currency", Value::String("EUR".into()));
+        assert!(schema.validate_json_bytes(br#"{"a": 1}"#).is_err());
+        assert!(
+            schema
+                .validate_json_bytes_with_context(br#"{"a": 1}"#, &ctx)
+                .is_ok()
+        );
+
+        // 流式校验无法满足的选项退回先解析再校验，结论与 validate 相同
+        let strict = ctx.clone().options(ValidationOptions {
+            strict: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            schema.validate_json_bytes_with_context(br#"{"a": 1, "b": 2}"#, &strict),
+            Err(ValidationError::UnknownField("b".into()))
+        );
+        let coerce = ctx.clone().options(ValidationOptions {
+            coerce: true,
+            ..Default::default()
+        });
+        assert!(schema.validate_json_bytes(br#"{"a": "1"}"#).is_err());
+        assert!(
+            schema
+                .validate_json_bytes_with_context(br#"{"a": "1"}"#, &coerce)
+                .is_ok()
+        );
+
+        let sink = Arc::new(MemoryAuditSink::new());
+        let audited = ctx.audit(sink.clone());
+        assert!(
+            schema
+                .validate_json_bytes_with_context(br#"{"a": "x"}"#, &audited)
+                .is_err()
+        );
+        assert_eq!(sink.take().len(), 1);
+    }
+}
@@ -0,0 +1,292 @@
+#[cfg(test)]
+#[cfg(feature = "json-value")]
+mod json_value_tests {
+    use std::sync::Arc;
+
+    use serde_json::json;
+    use zz_validator::{
+        ast::Value,
+        audit::MemoryAuditSink,
+        schema::Schema,
+        validator::{ValidationContext, ValidationError, ValidationOptions},
+    };
+
+    const SCHEMA: &str = r#"(
+        id:uuid, name:string[1,20] slugify, age:int[0,150]=18, role:string enum("admin","user")="user",
+        profile:object(first:string[1,50], contact:object(phone?:phone, site?:uri, lang:string="en")),
+        tags:array<string[1,10]>, scores?:array<int[0,100]> sorted count>=1,
+        users?:array<object(name:alpha, email:email, active:bool=true)>, meta?:object
+    )"#;
+
+    /// 与先转换为 `Value` 再校验的结果比较，通过时写回的内容也必须一致
+    fn both(
+        schema: &Schema,
+        json: serde_json::Value,
+        ctx: &ValidationContext,
+    ) -> Result<serde_json::Value, ValidationError> {
+        let mut direct = json.clone();
+        let direct_result = schema.validate_json_with_context(&mut direct, ctx);
+        let mut value = Value::from(json);
+        let result = schema.validate_with_context(&mut value, ctx);
+        assert_eq!(direct_result, result, "{}", direct);
+        if result.is_ok() {
+            assert_eq!(direct, serde_json::Value::from(&value));
+        }
+        direct_result.map(|_| direct)
+    }
+
+    fn valid() -> serde_json::Value {
+        json!({
+            "id": "550e8400-e29b-41d4-a716-446655440000", "name": "Alice Smith",
+            "profile": {"first": "Alice", "contact": {"site": "https://example.com"}},
+            "tags": ["a", "b"], "users": [{"name": "bob", "email": "bob@example.com"}],
+            "unknown": {"deeply": [1, 2, {"ignored": true}]}, "meta": {"any": 1}
+        })
+    }
+
+    #[test]
+    fn test_writes_back_defaults_and_transforms() {
+        let schema = Schema::parse(SCHEMA).unwrap();
+        let json = both(&schema, valid(), &ValidationContext::new()).unwrap();
+        assert_eq!(json["name"], "alice-smith");
+        assert_eq!(json["age"], 18);
+        assert_eq!(json["role"], "user");
+        assert_eq!(json["profile"]["contact"]["lang"], "en");
+        assert_eq!(json["users"][0]["active"], true);
+        assert_eq!(json["unknown"]["deeply"][2]["ignored"], true);
+
+        let mut json = json!({"a": null, "b": "x"});
+        let schema = Schema::parse("(a?:int, b:string)").unwrap();
+        schema.validate_json(&mut json).unwrap();
+        // 缺失的可选字段保持原样
+        assert_eq!(json, json!({"a": null, "b": "x"}));
+
+        // 数组中的 null 按空字符串校验，但通过后同样保持原样，与整体转换的退回路径一致
+        let schema = Schema::parse("(t:array<string>)").unwrap();
+        let mut json = json!({"t": ["a", null]});
+        schema.validate_json(&mut json).unwrap();
+        assert_eq!(json, json!({"t": ["a", null]}));
+        let schema = Schema::parse(
+            "(t:array<string>, users?:array<object(id:int)>, owner?:int ref(users[].id))",
+        )
+        .unwrap();
+        let mut json = json!({"t": ["a", null]});
+        schema.validate_json(&mut json).unwrap();
+        assert_eq!(json, json!({"t": ["a", null]}));
+    }
+
+    #[test]
+    fn test_reports_same_error() {
+        let schema = Schema::parse(SCHEMA).unwrap();
+        let ctx = ValidationContext::new();
+        let cases: [(&str, serde_json::Value); 9] = [
+            ("/name", json!("")),
+            ("/tags", json!(["a", "this is too long"])),
+            ("/tags", json!("a")),
+            ("/tags", json!(null)),
+            ("/profile/first", json!(1)),
+            ("/users/0/email", json!("bob")),
+            ("/scores", json!([3, 1])),
+            ("/age", json!(200)),
+            ("/meta", json!([1])),
+        ];
+        for (pointer, bad) in cases {
+            let mut json = valid();
+            match json.pointer_mut(pointer) {
+                Some(slot) => *slot = bad,
+                None => {
+                    let (parent, key) = pointer.rsplit_once('/').unwrap();
+                    let parent = if parent.is_empty() {
+                        &mut json
+                    } else {
+                        json.pointer_mut(parent).unwrap()
+                    };
+                    parent[key] = bad;
+                }
+            }
+            assert!(both(&schema, json, &ctx).is_err(), "{}", pointer);
+        }
+
+        let err = both(
+            &schema,
+            json!({"id": "550e8400-e29b-41d4-a716-446655440000", "name": "a",
+                   "profile": {"first": "A", "contact": {}}, "tags": ["a", null, 1]}),
+            &ctx,
+        )
+        .unwrap_err();
+        assert_eq!(err.field(), Some("tags[1]"));
+
+        let schema = Schema::parse("(a:int)").unwrap();
+        for root in [json!([1]), json!("text"), json!(null)] {
+            assert_eq!(
+                both(&schema, root, &ctx).unwrap_err().to_string(),
+                "Value is not object"
+            );
+        }
+    }
+
+    #[test]
+    fn test_options() {
+        let schema = Schema::parse("(n:int, o:object(a:array<object(b:int)>))").unwrap();
+        let strict = ValidationContext::new().options(ValidationOptions {
+            strict: true,
+            coerce: true,
+            ..Default::default()
+        });
+        let json = json!({"n": "3", "o": {"a": [{"b": "4"}]}});
+        assert_eq!(
+            both(&schema, json, &strict).unwrap(),
+            json!({"n": 3, "o": {"a": [{"b": 4}]}})
+        );
+        let err = both(
+            &schema,
+            json!({"n": 1, "o": {"a": [{"b": 1, "c": 2}]}}),
+            &strict,
+        )
+        .unwrap_err();
+        assert_eq!(err, ValidationError::UnknownField("o.a[0].c".into()));
+
+        let shallow = ValidationContext::new().options(ValidationOptions {
+            max_depth: 3,
+            ..Default::default()
+        });
+        let err = both(&schema, json!({"n": 1, "o": {"a": [{"b": 1}]}}), &shallow).unwrap_err();
+        assert_eq!(err.field(), Some("o.a[0].b"));
+        let schema = Schema::parse("(o:object(a:object(b:object(c:int))))").unwrap();
+        let err = both(&schema, json!({"o": {"a": {"b": {"c": 1}}}}), &shallow).unwrap_err();
+        assert!(
+            matches!(err, ValidationError::MaxDepthExceeded { ref field, limit: 3 } if field == "o.a.b.c"),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_whole_document_rules() {
+        let schema = Schema::parse(
+            "(users:array<object(id:int)>, orders:array<object(user_id:int ref(users[].id))>)",
+        )
+        .unwrap();
+        let mut ok = json!({"orders": [{"user_id": 1}], "users": [{"id": 1}]});
+        assert!(schema.validate_json(&mut ok).is_ok());
+        let mut bad = json!({"orders": [{"user_id": 2}], "users": [{"id": 1}]});
+        assert!(schema.validate_json(&mut bad).is_err());
+
+        // 带 ref 时同样就地校验，null 与默认值、转换的结果都保留下来
+        let schema = Schema::parse(
+            "(users:array<object(id:int, name?:string)>, \
+              orders:array<object(user_id:int ref(users[].id), title?:slug slugify, qty:int = 1)>, \
+              extra?:string)",
+        )
+        .unwrap();
+        let mut json = json!({
+            "orders": [{"user_id": 1, "title": "Hi There", "coupon": null}],
+            "users": [{"id": 1, "name": null}],
+            "extra": null
+        });
+        schema.validate_json(&mut json).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "orders": [{"user_id": 1, "title": "hi-there", "coupon": null, "qty": 1}],
+                "users": [{"id": 1, "name": null}],
+                "extra": null
+            })
+        );
+    }
+
+    #[test]
+    fn test_audit_frozen_and_canonicalize() {
+        // 载荷哈希直接在文档上计算，与转换后的结果相同
+        let schema = Schema::parse("(a:int, b?:array<string>, c?:object, d?:float)").unwrap();
+        let json = json!({"d": 1.5, "c": {"z": null, "y": [1, null]}, "b": ["x", null], "a": 1, "n": null});
+        let (direct, converted) = (
+            Arc::new(MemoryAuditSink::new()),
+            Arc::new(MemoryAuditSink::new()),
+        );
+        schema
+            .validate_json_with_context(
+                &mut json.clone(),
+                &ValidationContext::new().audit(direct.clone()),
+            )
+            .unwrap();
+        schema
+            .validate_with_context(
+                &mut Value::from(json),
+                &ValidationContext::new().audit(converted.clone()),
+            )
+            .unwrap();
+        let (direct, converted) = (direct.take(), converted.take());
+        assert_eq!(direct.len(), 1);
+        assert_eq!(direct[0].payload_hash, converted[0].payload_hash);
+
+        // frozen 报告第一处修改，文档不被改写
+        let schema = Schema::parse("(n:int, s:string slugify, d:int=1)").unwrap();
+        for (coerce, json, change) in [
+            (true, json!({"n": "1", "s": "A B", "d": 2}), "coerce"),
+            (false, json!({"n": 1, "s": "A B", "d": 2}), "transform"),
+        ] {
+            let ctx = ValidationContext::new().options(ValidationOptions {
+                frozen: true,
+                coerce,
+                ..Default::default()
+            });
+            let err = both(&schema, json.clone(), &ctx).unwrap_err();
+            assert!(err.to_string().contains(change), "{}", err);
+            let mut untouched = json.clone();
+            let _ = schema.validate_json_with_context(&mut untouched, &ctx);
+            assert_eq!(untouched, json);
+        }
+        let frozen = ValidationContext::new().options(ValidationOptions {
+            frozen: true,
+            coerce: true,
+            ..Default::default()
+        });
+        let err = both(&schema, json!({"n": "x", "s": "a"}), &frozen).unwrap_err();
+        assert_eq!(err.field(), Some("n"));
+        let err = both(&schema, json!({"n": 1, "s": "a"}), &frozen).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::WouldMutate {
+                field: "d".into(),
+                change: "default".into()
+            }
+        );
+
+        let canonical = ValidationContext::new().options(ValidationOptions {
+            canonicalize: true,
+            ..Default::default()
+        });
+        let schema = Schema::parse("(id:uuid, ids:array<uuid>)").unwrap();
+        let json = both(
+            &schema,
+            json!({"id": "550E8400-E29B-41D4-A716-446655440000", "ids": ["550E8400-E29B-41D4-A716-446655440000"]}),
+            &canonical,
+        )
+        .unwrap();
+        assert_eq!(json["id"], "550e8400-e29b-41d4-a716-446655440000");
+        assert_eq!(json["ids"][0], "550e8400-e29b-41d4-a716-446655440000");
+    }
+
+    #[cfg(feature = "script")]
+    #[test]
+    fn test_script_reads_siblings() {
+        let schema = Schema::parse(
+            "(range:object(start:int, end:int script(\"value > parent.start\"), label?:string slugify))",
+        )
+        .unwrap();
+        let ctx = ValidationContext::new();
+        let json = both(
+            &schema,
+            json!({"range": {"start": 1, "end": 2, "label": "A B"}}),
+            &ctx,
+        )
+        .unwrap();
+        assert_eq!(
+            json,
+            json!({"range": {"start": 1, "end": 2, "label": "a-b"}})
+        );
+        let err = both(&schema, json!({"range": {"start": 3, "end": 2}}), &ctx).unwrap_err();
+        assert_eq!(err.field(), Some("range.end"));
+    }
+}
@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod max_depth_tests {
+    use zz_validator::{
+        ast::{FieldRule, FieldType, ObjectMap, Value},
+        json::from_json_str,
+        schema::Schema,
+        validator::{
+            DEFAULT_MAX_DEPTH, ValidationContext, ValidationError, ValidationOptions,
+            validate_object_with,
+        },
+    };
+
+    fn rule(name: &str, field_type: FieldType, children: Option<Vec<FieldRule>>) -> FieldRule {
+        FieldRule {
+            field: name.into(),
+            field_type,
+            required: true,
+            default: None,
+            enum_values: None,
+            union_types: None,
+            constraints: None,
+            rule: None,
+            children,
+            is_array: false,
+        }
+    }
+
+    /// `levels` 层嵌套的 `a` 对象，最内层为 int 字段 `v`
+    fn nested(levels: usize) -> (Vec<FieldRule>, Value) {
+        let mut r = rule("v", FieldType::Int, None);
+        let mut obj = ObjectMap::new();
+        obj.insert("v".into(), Value::Int(1));
+        let mut value = Value::Object(obj);
+        for _ in 0..levels {
+            r = rule("a", FieldType::Object, Some(vec![r]));
+            let mut obj = ObjectMap::new();
+            obj.insert("a".into(), value);
+            value = Value::Object(obj);
+        }
+        (vec![r], value)
+    }
+
+    fn options(max_depth: usize) -> ValidationOptions {
+        ValidationOptions {
+            max_depth,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_max_depth_exceeded() {
+        assert_eq!(ValidationOptions::default().max_depth, DEFAULT_MAX_DEPTH);
+        let (rules, mut value) = nested(5);
+        assert!(validate_object_with(&mut value, &rules, &options(6)).is_ok());
+        let err = validate_object_with(&mut value, &rules, &options(3)).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::MaxDepthExceeded {
+                field: "a.a.a.a".into(),
+                limit: 3
+            }
+        );
+        assert_eq!(err.code(), "max_depth_exceeded");
+        assert_eq!(err.to_string(), "a.a.a.a is nested deeper than 3 levels");
+    }
+
+    #[test]
+    fn test_arrays_count_as_levels() {
+        let schema = Schema::parse("(m:array<array<array<int>>>)").unwrap();
+        let ctx = ValidationContext::new().options(options(3));
+        let mut value = from_json_str(r#"{"m": [[[1, 2]], [[3]]]}"#).unwrap();
+        let err = schema.validate_with_context(&mut value, &ctx).unwrap_err();
+        assert_eq!(err.field(), Some("m[0][0][0]"));
+        assert!(matches!(
+            err,
+            ValidationError::MaxDepthExceeded { limit: 3, .. }
+        ));
+
+        let ctx = ValidationContext::new().options(options(4));
+        assert!(schema.validate_with_context(&mut value, &ctx).is_ok());
+        // 层数以实际深入的值计算，空数组不会触发
+        let ctx = ValidationContext::new().options(options(2));
+        let mut value = from_json_str(r#"{"m": [[]]}"#).unwrap();
+        assert!(schema.validate_with_context(&mut value, &ctx).is_ok());
+    }
+
+    #[test]
+    fn test_deep_nesting_does_not_overflow() {
+        let (rules, value) = nested(500);
+        let schema = Schema::new(rules);
+        // 校验不随嵌套层数消耗调用栈，在很小的线程栈上也能完成
+        std::thread::scope(|scope| {
+            std::thread::Builder::new()
+                .stack_size(128 * 1024)
+                .spawn_scoped(scope, || {
+                    let err = schema.validate_shared(&value).unwrap_err();
+                    assert!(matches!(
+                        err,
+                        ValidationError::MaxDepthExceeded {
+                            limit: DEFAULT_MAX_DEPTH,
+                            ..
+                        }
+                    ));
+                    let ctx = ValidationContext::new().options(options(0));
+                    assert!(schema.validate_shared_with_context(&value, &ctx).is_ok());
+                })
+                .unwrap()
+                .join()
+                .unwrap();
+        });
+    }
+}
@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod max_errors_tests {
+    use zz_validator::{
+        json::from_json_str,
+        parser::Parser,
+        schema::Schema,
+        validator::{ValidationContext, ValidationError, ValidationOptions, validate_with_report},
+    };
+
+    fn ctx(max_errors: usize) -> ValidationContext {
+        ValidationContext::new().options(ValidationOptions {
+            exhaustive: true,
+            max_errors,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_field_errors_truncated() {
+        let schema =
+            Schema::parse(r#"(code:string[5,10] regex("^[0-9]+$") regex("^C") enum("C12345"))"#)
+                .unwrap();
+        let run = |max_errors| {
+            let mut value = from_json_str(r#"{"code": "ab"}"#).unwrap();
+            schema
+                .validate_with_context(&mut value, &ctx(max_errors))
+                .unwrap_err()
+        };
+
+        // 0 表示不限制
+        let ValidationError::FieldErrors {
+            errors, truncated, ..
+        } = run(0)
+        else {
+            panic!("expected FieldErrors");
+        };
+        assert_eq!(errors.len(), 4);
+        assert!(!truncated);
+
+        let err = run(2);
+        let ValidationError::FieldErrors {
+            field,
+            errors,
+            truncated,
+        } = &err
+        else {
+            panic!("expected FieldErrors, got {:?}", err);
+        };
+        assert_eq!(field, "code");
+        assert_eq!(errors.len(), 2);
+        assert!(truncated);
+        assert!(matches!(errors[0], ValidationError::EnumMismatch { .. }));
+        assert!(
+            err.to_string()
+                .starts_with("code has 2 errors (truncated): "),
+            "{}",
+            err
+        );
+
+        // 只保留一个时仍然带上截断标记
+        assert!(matches!(
+            run(1),
+            ValidationError::FieldErrors { ref errors, truncated: true, .. } if errors.len() == 1
+        ));
+
+        // 未达到上限时不截断
+        assert!(matches!(
+            run(4),
+            ValidationError::FieldErrors {
+                truncated: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_warnings_truncated() {
+        let rules = Parser::parse_rules("(tags:array<string warn([1,3])>)").unwrap();
+        let json = r#"{"tags": ["long1", "long2", "long3", "long4", "long5"]}"#;
+
+        let mut value = from_json_str(json).unwrap();
+        let report = validate_with_report(&mut value, &rules, &ctx(0)).unwrap();
+        assert_eq!(report.warnings.len(), 5);
+        assert!(!report.warnings_truncated);
+
+        let mut value = from_json_str(json).unwrap();
+        let report = validate_with_report(&mut value, &rules, &ctx(3)).unwrap();
+        assert_eq!(report.warnings.len(), 3);
+        assert!(report.warnings_truncated);
+        assert_eq!(report.warnings[2].field(), Some("tags[2]"));
+    }
+}
@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod metadata_tests {
+    use zz_validator::{
+        ast::{Metadata, Value},
+        export::to_dsl,
+        parser::Parser,
+        schema::Schema,
+    };
+
+    const DSL: &str = r#"meta(name="CreateUser", version=3, owner="payments", internal=false)
+    (
+        name:string[1,20],
+        age?:int
+    )"#;
+
+    #[test]
+    fn test_parse_metadata() {
+        let schema = Schema::parse(DSL).unwrap();
+        let meta = schema.metadata();
+        assert_eq!(meta.name(), Some("CreateUser"));
+        assert_eq!(meta.version(), Some(&Value::Int(3)));
+        assert_eq!(meta.get("owner"), Some(&Value::String("payments".into())));
+        assert_eq!(meta.get("internal"), Some(&Value::Bool(false)));
+        let keys: Vec<&str> = meta.iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, ["name", "version", "owner", "internal"]);
+        assert_eq!(schema.rules().len(), 2);
+
+        // parse_rules 接受头部但只返回规则；没有头部时 metadata 为空
+        assert_eq!(Parser::parse_rules(DSL).unwrap().len(), 2);
+        assert!(Schema::parse("(a:int)").unwrap().metadata().is_empty());
+        assert!(
+            Schema::parse("meta() (a:int)")
+                .unwrap()
+                .metadata()
+                .is_empty()
+        );
+
+        for bad in [
+            r#"meta(name="a", name="b") (a:int)"#,
+            "meta(name) (a:int)",
+            "meta(name=) (a:int)",
+            r#"meta("name"="a") (a:int)"#,
+            r#"meta(name="a" (a:int)"#,
+            "meta(tags=[1]) (a:int)",
+        ] {
+            assert!(Parser::parse_rules(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_metadata_in_exports() {
+        let schema = Schema::parse(DSL).unwrap();
+
+        let dsl = schema.to_dsl();
+        assert!(dsl.starts_with(
+            "meta(name=\"CreateUser\", version=3, owner=\"payments\", internal=false)\n(\n"
+        ));
+        let reparsed = Schema::parse(&dsl).unwrap();
+        assert_eq!(reparsed.metadata(), schema.metadata());
+        assert_eq!(reparsed.to_dsl(), dsl);
+        // 只有规则时不输出头部
+        assert!(to_dsl(schema.rules()).starts_with("(\n"));
+
+        let json = schema.to_json_schema();
+        assert_eq!(json["title"], "CreateUser");
+        assert_eq!(json["x-vdsl-meta"]["version"], 3);
+        assert_eq!(json["x-vdsl-meta"]["owner"], "payments");
+        let imported = Schema::from_json_schema(&json).unwrap();
+        assert_eq!(imported.metadata().name(), Some("CreateUser"));
+        assert_eq!(
+            imported.metadata().get("internal"),
+            Some(&Value::Bool(false))
+        );
+
+        let openapi = schema.to_openapi("CreateUser");
+        assert_eq!(openapi["info"]["version"], "3");
+        assert_eq!(
+            openapi["components"]["schemas"]["CreateUser"]["x-vdsl-meta"]["owner"],
+            "payments"
+        );
+
+        let ts = schema.to_typescript("CreateUser");
+        assert!(ts.starts_with("/**\n * name: CreateUser\n * version: 3\n * owner: payments\n"));
+        assert!(ts.contains("export interface CreateUser {"));
+
+        let bad = serde_json::json!({ "type": "object", "x-vdsl-meta": { "tags": [1] } });
+        assert!(Schema::from_json_schema(&bad).is_err());
+    }
+
+    #[test]
+    fn test_metadata_builder() {
+        let mut meta = Metadata::new();
+        assert_eq!(meta.insert("name", Value::String("A".into())), None);
+        meta.insert("version", Value::Int(1));
+        assert_eq!(
+            meta.insert("name", Value::String("B".into())),
+            Some(Value::String("A".into()))
+        );
+        assert_eq!(meta.to_string(), r#"meta(name="B", version=1)"#);
+
+        let schema = Schema::parse("(a:int)")
+            .unwrap()
+            .with_metadata(meta.clone());
+        assert_eq!(schema.metadata(), &meta);
+        assert!(schema.to_dsl().starts_with(r#"meta(name="B", version=1)"#));
+    }
+}
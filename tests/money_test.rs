@@ -0,0 +1,188 @@
+#[cfg(test)]
+mod money_tests {
+    use zz_validator::{
+        ast::Value,
+        export::{to_json_schema, to_typescript},
+        generator::Generator,
+        json::from_json_str,
+        parser::Parser,
+        schema::Schema,
+        validator::{ValidationError, ValidationOptions, validate_object_with},
+    };
+
+    fn check(dsl: &str, json: &str) -> Result<Value, ValidationError> {
+        let rules = Parser::parse_rules(dsl).unwrap();
+        let mut value = from_json_str(json).unwrap();
+        validate_object_with(&mut value, &rules, &ValidationOptions::default())?;
+        Ok(value)
+    }
+
+    #[test]
+    fn test_money_objects() {
+        let dsl = "(price:money)";
+        assert!(check(dsl, r#"{"price": {"amount": 12.5, "currency": "USD"}}"#).is_ok());
+        assert!(check(dsl, r#"{"price": {"amount": "12.50", "currency": "EUR"}}"#).is_ok());
+        assert!(check(dsl, r#"{"price": {"amount": -3, "currency": "JPY"}}"#).is_ok());
+        assert!(check(dsl, r#"{"price": {"amount": "1.125", "currency": "KWD"}}"#).is_ok());
+
+        let err = check(dsl, r#"{"price": {"amount": "12.505", "currency": "USD"}}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.ends_with("USD amounts allow at most 2 decimal places"),
+            "{}",
+            err
+        );
+        let err = check(dsl, r#"{"price": {"amount": 1.5, "currency": "JPY"}}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.ends_with("JPY amounts allow at most 0 decimal places"),
+            "{}",
+            err
+        );
+        let err = check(dsl, r#"{"price": {"amount": 1, "currency": "XYZ"}}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.ends_with(r#"unknown currency "XYZ""#), "{}", err);
+        for bad in [
+            r#"{"price": {"amount": "12,50", "currency": "USD"}}"#,
+            r#"{"price": {"amount": true, "currency": "USD"}}"#,
+            r#"{"price": {"amount": 1}}"#,
+            r#"{"price": {"amount": 1, "currency": "USD", "note": "x"}}"#,
+            r#"{"price": "12.50 USD"}"#,
+            r#"{"price": 12.5}"#,
+        ] {
+            assert!(
+                matches!(
+                    check(dsl, bad),
+                    Err(ValidationError::TypeMismatch { ref field, .. }) if field == "price"
+                ),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_range_and_currency() {
+        let dsl = "(price:money[0,10000](currency=USD|EUR))";
+        assert!(
+            check(
+                dsl,
+                r#"{"price": {"amount": "9999.99", "currency": "EUR"}}"#
+            )
+            .is_ok()
+        );
+        assert_eq!(
+            check(
+                dsl,
+                r#"{"price": {"amount": "10000.01", "currency": "USD"}}"#
+            )
+            .unwrap_err()
+            .to_string(),
+            "price value 10000.01 out of range [0, 10000]"
+        );
+        let err = check(dsl, r#"{"price": {"amount": 5, "currency": "GBP"}}"#).unwrap_err();
+        assert!(
+            matches!(&err, ValidationError::EnumMismatch { field, .. } if field == "price.currency"),
+            "{:?}",
+            err
+        );
+
+        // 币种可以单独限制，区间也可以是开区间
+        assert!(
+            check(
+                "(p:money(currency=JPY))",
+                r#"{"p": {"amount": 1, "currency": "USD"}}"#
+            )
+            .is_err()
+        );
+        assert!(
+            check(
+                "(p:money(0,100])",
+                r#"{"p": {"amount": 0, "currency": "USD"}}"#
+            )
+            .is_err()
+        );
+        // 数组元素同样适用
+        let err = check(
+            "(items:array<money[1,10]>)",
+            r#"{"items": [{"amount": 2, "currency": "USD"}, {"amount": 20, "currency": "USD"}]}"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.field(), Some("items[1]"));
+    }
+
+    #[test]
+    fn test_coerce_strings() {
+        let rules = Parser::parse_rules("(price:money[0,100](currency=USD|EUR))").unwrap();
+        let options = ValidationOptions {
+            coerce: true,
+            ..Default::default()
+        };
+        let mut value = from_json_str(r#"{"price": "12.50 usd"}"#).unwrap();
+        validate_object_with(&mut value, &rules, &options).unwrap();
+        assert_eq!(
+            value
+                .as_object()
+                .unwrap()
+                .get("price")
+                .map(|p| p.to_string()),
+            Some(r#"{"amount": "12.50", "currency": "USD"}"#.to_string())
+        );
+
+        for bad in ["12.50", "USD 12.50", "12.50 GBP", "abc USD", "200 USD"] {
+            let mut value = from_json_str(&format!(r#"{{"price": "{}"}}"#, bad)).unwrap();
+            assert!(
+                validate_object_with(&mut value, &rules, &options).is_err(),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_dsl_and_export() {
+        let schema = Schema::parse("(price:money[0,10000](currency=USD|EUR))").unwrap();
+        assert_eq!(
+            schema.to_dsl().trim(),
+            "(\n    price:money[0, 10000](currency=USD|EUR)\n)"
+        );
+        assert!(Schema::parse(&schema.to_dsl()).is_ok());
+        assert!(
+            Parser::parse_rules("(p:money(currency=ABC))")
+                .unwrap_err()
+                .starts_with("Unknown currency 'ABC'")
+        );
+        assert!(Parser::parse_rules("(p:int(currency=USD))").is_err());
+        assert!(Parser::parse_rules("(p:string(currency=USD))").is_err());
+
+        let json = to_json_schema(schema.rules());
+        let price = &json["properties"]["price"];
+        assert_eq!(price["type"], "object");
+        assert_eq!(price["x-vdsl-type"], "money");
+        assert_eq!(
+            price["x-vdsl-constraints"],
+            serde_json::json!(["[0, 10000]", "(currency=USD|EUR)"])
+        );
+        let back = Schema::from_json_schema(&json).unwrap();
+        assert_eq!(back.to_dsl(), schema.to_dsl());
+
+        assert!(
+            to_typescript(schema.rules(), "Order")
+                .contains("price: { amount: number | string; currency: string };")
+        );
+    }
+
+    #[test]
+    fn test_generator() {
+        let rules = Parser::parse_rules("(price:money[5,50](currency=EUR))").unwrap();
+        let mut generator = Generator::new(7);
+        for _ in 0..10 {
+            let mut value = generator.valid(&rules).unwrap();
+            validate_object_with(&mut value, &rules, &ValidationOptions::default()).unwrap();
+        }
+        assert!(generator.invalid(&rules).is_ok());
+    }
+}
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod mutation_tests {
+    use zz_validator::{
+        json::from_json_str,
+        mutation::{MutationKind, mutants},
+        parser::Parser,
+        replay::Corpus,
+        schema::Schema,
+    };
+
+    const DSL: &str = r#"(
+        name:string[1,10] regex("^[a-z]+$"),
+        role:string enum("admin", "user"),
+        age?:int[0,150],
+        users?:array<object(id:int[1,100])>
+    )"#;
+
+    fn samples(items: &[&str]) -> Vec<zz_validator::ast::Value> {
+        items.iter().map(|s| from_json_str(s).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_mutants() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let names: Vec<String> = mutants(&rules).iter().map(|(m, _)| m.to_string()).collect();
+        assert_eq!(
+            names,
+            [
+                "name: drop required",
+                "name: widen range [1, 10]",
+                r#"name: remove regex("^[a-z]+$")"#,
+                "role: drop required",
+                "role: remove enum",
+                "age: widen range [0, 150]",
+                "users[].id: drop required",
+                "users[].id: widen range [1, 100]",
+            ]
+        );
+
+        // 每个变体只改动一处
+        let (mutation, mutant) = &mutants(&rules)[7];
+        assert!(matches!(mutation.kind, MutationKind::WidenRange(_)));
+        let id = &mutant[3].rule.as_ref().unwrap().children.as_ref().unwrap()[0];
+        assert!(id.constraints.as_ref().unwrap().items.is_empty());
+        assert!(
+            rules[3].rule.as_ref().unwrap().children.as_ref().unwrap()[0]
+                .constraints
+                .as_ref()
+                .is_some_and(|c| !c.items.is_empty())
+        );
+    }
+
+    #[test]
+    fn test_load_bearing_rules() {
+        let schema = Schema::parse(DSL).unwrap();
+        let report = schema.mutation_test(&samples(&[
+            r#"{"role": "user"}"#,
+            r#"{"name": "Alice", "role": "user"}"#,
+            r#"{"name": "alice", "role": "root"}"#,
+            r#"{"name": "alice", "role": "user", "users": [{"id": 0}]}"#,
+            r#"{"name": "alice", "role": "user"}"#,
+        ]));
+        assert_eq!(report.samples, 4);
+        assert_eq!(report.ignored, [4]);
+
+        let load_bearing: Vec<String> = report.load_bearing().map(|m| m.to_string()).collect();
+        assert_eq!(
+            load_bearing,
+            [
+                "name: drop required",
+                r#"name: remove regex("^[a-z]+$")"#,
+                "role: remove enum",
+                "users[].id: widen range [1, 100]",
+            ]
+        );
+        let dead: Vec<String> = report.dead().map(|m| m.path.clone()).collect();
+        assert_eq!(dead, ["name", "role", "age", "users[].id"]);
+
+        let text = report.to_string();
+        assert!(text.starts_with("4 invalid samples, 8 mutants\n"));
+        assert!(text.contains("  dead          age: widen range [0, 150]\n"));
+        assert!(text.contains("  load-bearing  role: remove enum (1 passed)\n"));
+        assert!(text.ends_with("1 samples passed the original schema\n"));
+    }
+
+    #[test]
+    fn test_samples_from_replay_corpus() {
+        let dir = std::env::temp_dir().join("zz_validator_mutation_tests");
+        let _ = std::fs::remove_dir_all(&dir);
+        let corpus = Corpus::open(&dir).unwrap();
+        let schema = Schema::parse("(age:int[0,150], code?:string[2,4])").unwrap();
+        corpus
+            .record(&schema, &from_json_str(r#"{"age": 200}"#).unwrap())
+            .unwrap();
+
+        let report = schema.mutation_test(&corpus.payloads().unwrap());
+        assert_eq!(report.samples, 1);
+        let load_bearing: Vec<String> = report.load_bearing().map(|m| m.to_string()).collect();
+        assert_eq!(load_bearing, ["age: widen range [0, 150]"]);
+    }
+}
@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod object_map_tests {
+    use std::collections::HashMap;
+
+    use zz_validator::{
+        ast::{ObjectMap, Value},
+        map::SMALL_MAP_MAX,
+        parser::Parser,
+        validator::validate_object,
+    };
+
+    fn entries(n: usize) -> Vec<(String, Value)> {
+        (0..n)
+            .map(|i| (format!("k{}", i), Value::Int(i as i64)))
+            .collect()
+    }
+
+    #[test]
+    fn test_behaves_like_hashmap_across_promotion() {
+        for n in [0, 1, SMALL_MAP_MAX, SMALL_MAP_MAX + 1, 50] {
+            let mut map: ObjectMap = entries(n).into_iter().collect();
+            let mut expected: HashMap<String, Value> = entries(n).into_iter().collect();
+            assert_eq!(map.len(), n);
+
+            // 覆盖已有键返回旧值，不改变长度
+            if n > 0 {
+                assert_eq!(
+                    map.insert("k0".into(), Value::Bool(true)),
+                    Some(Value::Int(0))
+                );
+                expected.insert("k0".into(), Value::Bool(true));
+            }
+            assert_eq!(map.insert("extra".into(), Value::Int(-1)), None);
+            expected.insert("extra".into(), Value::Int(-1));
+            if n > 1 {
+                assert_eq!(map.remove("k1"), Some(Value::Int(1)));
+                expected.remove("k1");
+            }
+            assert_eq!(map.remove("missing"), None);
+
+            assert_eq!(map.len(), expected.len());
+            for (k, v) in &expected {
+                assert_eq!(map.get(k.as_str()), Some(v), "{} of {}", k, n);
+            }
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let mut expected_keys: Vec<&String> = expected.keys().collect();
+            expected_keys.sort();
+            assert_eq!(keys, expected_keys);
+
+            let back: HashMap<String, Value> = map.clone().into();
+            assert_eq!(back, expected);
+            assert_eq!(ObjectMap::from(expected), map);
+        }
+    }
+
+    #[test]
+    fn test_equality_ignores_order() {
+        let a = ObjectMap::from([
+            ("a".to_string(), Value::Int(1)),
+            ("b".to_string(), Value::Int(2)),
+        ]);
+        let b = ObjectMap::from([
+            ("b".to_string(), Value::Int(2)),
+            ("a".to_string(), Value::Int(1)),
+        ]);
+        assert_eq!(a, b);
+        assert_ne!(a, ObjectMap::from([("a".to_string(), Value::Int(1))]));
+    }
+
+    #[test]
+    fn test_defaults_fill_past_small_limit() {
+        let fields: Vec<String> = (0..SMALL_MAP_MAX + 4)
+            .map(|i| format!("f{}:int={}", i, i))
+            .collect();
+        let rules = Parser::parse_rules(&format!("({})", fields.join(", "))).unwrap();
+        let mut value = Value::Object(ObjectMap::new());
+        validate_object(&mut value, &rules).unwrap();
+        let obj = value.as_object().unwrap();
+        assert_eq!(obj.len(), SMALL_MAP_MAX + 4);
+        assert_eq!(obj["f11"], Value::Int(11));
+    }
+
+    #[test]
+    fn test_clone_shares_until_written() {
+        let nested = |n: usize| Value::Object(entries(n).into_iter().collect());
+        let original = ObjectMap::from([
+            ("a".to_string(), nested(3)),
+            ("b".to_string(), nested(SMALL_MAP_MAX + 2)),
+        ]);
+        let mut copy = original.clone();
+        assert!(ObjectMap::ptr_eq(&original, &copy));
+
+        // 只读访问与不存在的键不会触发复制
+        assert!(copy.get_mut("missing").is_none());
+        assert_eq!(copy.remove("missing"), None);
+        assert!(ObjectMap::ptr_eq(&original, &copy));
+
+        // 修改 a 只复制顶层与 a，b 仍然共享
+        if let Some(Value::Object(a)) = copy.get_mut("a") {
+            a.insert("k0".into(), Value::Bool(true));
+        }
+        assert!(!ObjectMap::ptr_eq(&original, &copy));
+        let b = |m: &ObjectMap| m["b"].as_object().unwrap().clone();
+        assert!(ObjectMap::ptr_eq(&b(&original), &b(&copy)));
+        assert_eq!(original["a"].as_object().unwrap()["k0"], Value::Int(0));
+        assert_eq!(copy["a"].as_object().unwrap()["k0"], Value::Bool(true));
+
+        let items: Vec<(String, Value)> = original.clone().into_iter().collect();
+        assert_eq!(items.len(), 2);
+        assert_eq!(original.len(), 2);
+    }
+}
@@ -0,0 +1,126 @@
+#[cfg(test)]
+#[cfg(feature = "ordered")]
+mod ordered_tests {
+    use zz_validator::{
+        ast::{ObjectMap, Value},
+        json::{from_json_str, to_json_string},
+        schema::Schema,
+        validator::{ValidationContext, ValidationError, ValidationOptions},
+    };
+
+    fn ordered() -> ValidationContext {
+        ValidationContext::new().options(ValidationOptions {
+            ordered: true,
+            ..Default::default()
+        })
+    }
+
+    fn check(schema: &Schema, json: &str) -> Result<Value, ValidationError> {
+        let mut value = from_json_str(json).unwrap();
+        schema.validate_with_context(&mut value, &ordered())?;
+        Ok(value)
+    }
+
+    #[test]
+    fn test_object_map_keeps_insertion_order() {
+        let keys: Vec<String> = (0..20).rev().map(|i| format!("k{}", i)).collect();
+        let mut map: ObjectMap = keys.iter().map(|k| (k.clone(), Value::Int(1))).collect();
+        assert!(map.keys().eq(keys.iter()));
+        map.remove("k10");
+        map.remove("k19");
+        assert!(
+            map.keys()
+                .eq(keys.iter().filter(|k| *k != "k10" && *k != "k19"))
+        );
+
+        let value = from_json_str(r#"{"z": 1, "a": 2, "m": {"y": 1, "b": 2}}"#).unwrap();
+        let obj = value.as_object().unwrap();
+        assert!(obj.keys().eq(["z", "a", "m"]));
+        // 输出的 JSON 仍按字典序排列键
+        assert_eq!(to_json_string(&value), r#"{"a":2,"m":{"b":2,"y":1},"z":1}"#);
+    }
+
+    #[test]
+    fn test_declared_order() {
+        let schema = Schema::parse(
+            "(id:int, name:string, profile:object(first:string, last:string), \
+             items:array<object(sku:string, qty:int)>)",
+        )
+        .unwrap();
+        assert!(
+            check(
+                &schema,
+                r#"{"id": 1, "name": "a", "extra": true, "profile": {"first": "A", "last": "B"},
+                    "items": [{"sku": "x", "qty": 1}]}"#
+            )
+            .is_ok()
+        );
+
+        let err = check(
+            &schema,
+            r#"{"name": "a", "id": 1, "profile": {"first": "A", "last": "B"}, "items": []}"#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::OutOfOrder {
+                field: "id".into(),
+                after: "name".into()
+            }
+        );
+        assert_eq!(err.to_string(), "id must come before 'name'");
+        assert_eq!(err.code(), "out_of_order");
+
+        let err = check(
+            &schema,
+            r#"{"id": 1, "name": "a", "profile": {"last": "B", "first": "A"}, "items": []}"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.field(), Some("profile.first"));
+        let err = check(
+            &schema,
+            r#"{"id": 1, "name": "a", "profile": {"first": "A", "last": "B"},
+                "items": [{"sku": "x", "qty": 1}, {"qty": 1, "sku": "y"}]}"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.field(), Some("items[1].sku"));
+
+        // 未开启时不检查
+        let mut value = from_json_str(
+            r#"{"name": "a", "id": 1, "profile": {"first": "A", "last": "B"}, "items": []}"#,
+        )
+        .unwrap();
+        assert!(schema.validate(&mut value).is_ok());
+    }
+
+    #[test]
+    fn test_defaults_take_declared_position() {
+        let schema = Schema::parse("(a:int, b:int=2, c:int, d?:object(x:int=1, y:int))").unwrap();
+        let value = check(&schema, r#"{"a": 1, "extra": 0, "c": 3, "d": {"y": 2}}"#).unwrap();
+        let obj = value.as_object().unwrap();
+        assert!(obj.keys().eq(["a", "extra", "b", "c", "d"]));
+        let d = obj.get("d").unwrap().as_object().unwrap();
+        assert!(d.keys().eq(["x", "y"]));
+
+        // 填充后的载荷再次校验仍然通过
+        let mut again = value.clone();
+        assert!(schema.validate_with_context(&mut again, &ordered()).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "json-value")]
+    fn test_json_value() {
+        let schema = Schema::parse("(a:int, b:int=2, c:object(x:int, y:int))").unwrap();
+        let mut json = serde_json::json!({"a": 1, "c": {"x": 1, "y": 2}});
+        schema
+            .validate_json_with_context(&mut json, &ordered())
+            .unwrap();
+        assert_eq!(json.to_string(), r#"{"a":1,"b":2,"c":{"x":1,"y":2}}"#);
+
+        let mut json = serde_json::json!({"a": 1, "c": {"y": 2, "x": 1}});
+        let err = schema
+            .validate_json_with_context(&mut json, &ordered())
+            .unwrap_err();
+        assert_eq!(err.field(), Some("c.x"));
+    }
+}
@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod outcome_tests {
+    use zz_validator::{
+        ast::Value,
+        json::from_json_str,
+        report::Outcome,
+        schema::Schema,
+        validator::{ValidationContext, ValidationError, ValidationOptions, validate_outcomes},
+    };
+
+    const DSL: &str = r#"(
+        username:string[3,20],
+        email:email,
+        age?:int[0,150],
+        nickname?:string,
+        role:string = user,
+        profile:object(lang:string = en, bio:string[0,10]),
+        items?:array<object(qty:int[1,9] = 1, price:float)>
+    )"#;
+
+    fn names(schema: &Schema, json: &str) -> Vec<(String, &'static str)> {
+        let value = from_json_str(json).unwrap();
+        schema
+            .validate_outcomes(&value)
+            .iter()
+            .map(|f| (f.path.clone(), f.outcome.name()))
+            .collect()
+    }
+
+    #[test]
+    fn test_every_field_has_an_outcome() {
+        let schema = Schema::parse(DSL).unwrap();
+        let outcomes = names(
+            &schema,
+            r#"{
+                "username": "al",
+                "email": "a@example.com",
+                "profile": {"bio": "far too long for this"},
+                "items": [{"price": 1.0}, {"qty": 10, "price": 2.0}]
+            }"#,
+        );
+        let expected = [
+            ("username", "failed"),
+            ("email", "passed"),
+            ("age", "skipped_optional"),
+            ("nickname", "skipped_optional"),
+            ("role", "defaulted"),
+            ("profile", "failed"),
+            ("profile.lang", "defaulted"),
+            ("profile.bio", "failed"),
+            ("items", "failed"),
+            ("items[0]", "passed"),
+            ("items[0].qty", "defaulted"),
+            ("items[0].price", "passed"),
+            ("items[1]", "failed"),
+            ("items[1].qty", "failed"),
+            ("items[1].price", "passed"),
+        ];
+        assert_eq!(
+            outcomes,
+            expected
+                .iter()
+                .map(|(p, o)| (p.to_string(), *o))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_failed_and_defaulted_fields() {
+        let schema = Schema::parse(DSL).unwrap();
+        let value = from_json_str(
+            r#"{"email": "nope", "profile": {"bio": "far too long for this"}, "items": [{"qty": 0, "price": 1.0}]}"#,
+        )
+        .unwrap();
+        let report = schema.validate_outcomes(&value);
+        assert!(!report.is_valid());
+
+        // 只列出最深的失败字段，错误带有完整路径
+        let failed: Vec<(&str, Option<&str>)> =
+            report.failed().map(|(p, e)| (p, e.field())).collect();
+        assert_eq!(
+            failed,
+            [
+                ("username", Some("username")),
+                ("email", Some("email")),
+                ("profile.bio", Some("profile.bio")),
+                ("items[0].qty", Some("items[0].qty")),
+            ]
+        );
+        assert_eq!(
+            report.get("username").unwrap().outcome,
+            Outcome::Failed(ValidationError::MissingField("username".into()))
+        );
+
+        let defaulted: Vec<(&str, &Value)> = report.defaulted().collect();
+        assert_eq!(
+            defaulted,
+            [
+                ("role", &Value::String("user".into())),
+                ("profile.lang", &Value::String("en".into())),
+            ]
+        );
+        // 载荷没有被修改
+        assert!(value.as_object().unwrap().get("role").is_none());
+    }
+
+    #[test]
+    fn test_agrees_with_validate() {
+        let schema = Schema::parse(DSL).unwrap();
+        for json in [
+            r#"{"username": "alice", "email": "a@example.com", "profile": {"bio": "hi"}}"#,
+            r#"{"username": "alice", "email": "a@example.com", "profile": {"bio": 1}}"#,
+            r#"{"username": "alice", "email": "a@example.com", "profile": {"bio": "hi"}, "items": [{"price": "x"}]}"#,
+        ] {
+            let value = from_json_str(json).unwrap();
+            let report = schema.validate_outcomes(&value);
+            let result = schema.validate(&mut value.clone());
+            assert_eq!(report.is_valid(), result.is_ok(), "{}", json);
+            if let Err(e) = result {
+                assert_eq!(report.failed().next().map(|(_, e)| e), Some(&e));
+            }
+        }
+    }
+
+    #[test]
+    fn test_strict_and_coerce() {
+        let rules = Schema::parse("(age:int, name?:string)").unwrap();
+        let value = from_json_str(r#"{"age": "42", "extra": true}"#).unwrap();
+        let ctx = ValidationContext::new().options(ValidationOptions {
+            strict: true,
+            coerce: true,
+            ..Default::default()
+        });
+        let report = validate_outcomes(&value, rules.rules(), &ctx);
+        let outcomes: Vec<(&str, &str)> = report
+            .iter()
+            .map(|f| (f.path.as_str(), f.outcome.name()))
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                ("age", "passed"),
+                ("name", "skipped_optional"),
+                ("extra", "failed")
+            ]
+        );
+    }
+}
@@ -0,0 +1,411 @@
+//! `Parser::parse_rules` 与 `validate_*` 对任意输入都不会 panic：
+//! 以下测试用确定性的伪随机变异代替 cargo-fuzz，普通 `cargo test` 即可在 CI 中运行。
+//! 需要更长时间时设置 `VDSL_FUZZ_ITERATIONS` 加大迭代次数。
+
+#[cfg(test)]
+mod panic_free_tests {
+    use zz_validator::ast::ObjectMap;
+
+    use zz_validator::{
+        ast::{FieldRule, Value},
+        clock::FixedClock,
+        generator::Generator,
+        parser::{MAX_NESTING_DEPTH, Parser},
+        validator::{
+            Budget, ValidationContext, ValidationOptions, validate, validate_object, validate_rule,
+            validate_with_report,
+        },
+    };
+
+    /// 覆盖大部分类型与约束写法的种子语料
+    const CORPUS: &[&str] = &[
+        r#"(
+            id:uuid, username:string[3,20] regex("^[a-zA-Z0-9_]+$"), email:email,
+            age:int[0,150]=18, score:float(0,100), role:string enum("admin","user")="user",
+            nickname?:string[0,20], id_or_name:int|hostname,
+            profile:object(first:string[1,50], contact:object(phone?:phone, site?:uri)),
+            tags:array<string[1,10]>, users:array<object(name:alpha, card:creditcard)>
+        )"#,
+        r#"(
+            ip:ip, mac:mac oui("AC:DE:48"), date:date past, datetime:datetime future,
+            ts:timestamp within(30d), birthday:date min_age(18), color:color(rgb, alpha),
+            port:port, addr:socketaddr, host:hostname(allow_single_label, allow_trailing_dot),
+            v7:uuid(v7, compact), nano:nanoid(10), ulid:ulid, ksuid:ksuid, semver:semver,
+            card:string luhn, lei:string checksum(mod97), sum:string checksum(crc32="cbf43926")
+        )"#,
+        r#"(
+            split?:array<float[0,100]> sum==100, ratings?:array<int[1,5]> avg[2, 5] count>=1,
+            deltas?:array<int> count_where(>0) >= 1, ranks:array<int> sorted(desc),
+            times:array<timestamp> strictly_increasing,
+            users:array<object(id:int, email:email)> unique_by(id) unique_by("profile.email"),
+            slots:array<object(start:datetime, end:datetime)>
+                each(start < end) adjacent(prev.end <= next.start),
+            orders:array<object(id:int, user_id:int ref(users[].id))>,
+            owner:int exists("user"), n:int[-9223372036854775808, 9223372036854775807],
+            when?:string|datetime past within(1d) min_age(1)
+        )"#,
+    ];
+
+    /// 变异时插入的片段：DSL 的各种记号与容易触发边界情况的字面量
+    const FRAGMENTS: &[&str] = &[
+        "(",
+        ")",
+        "<",
+        ">",
+        "[",
+        "]",
+        ",",
+        ":",
+        "?",
+        "|",
+        "=",
+        "==",
+        "!=",
+        "<=",
+        ">=",
+        ".",
+        "\"",
+        "\\",
+        "-",
+        "0",
+        "-1",
+        "1e308",
+        "9223372036854775807",
+        "-9223372036854775808",
+        "99999999999999999999",
+        ".5",
+        "int",
+        "string",
+        "array",
+        "object",
+        "uuid(",
+        "color(",
+        "hostname(",
+        "nanoid(0)",
+        "regex(\"",
+        "enum(",
+        "sum",
+        "avg",
+        "count_where(",
+        "each(",
+        "adjacent(",
+        "prev.",
+        "next.",
+        "ref(",
+        "exists(",
+        "unique_by(",
+        "within(",
+        "0d",
+        "w)",
+        "min_age(",
+        "é",
+        "数",
+        "\u{0}",
+        " ",
+    ];
+
+    const STRINGS: &[&str] = &[
+        "",
+        " ",
+        "a",
+        "é",
+        "日本語",
+        "\u{0}",
+        "-",
+        ".",
+        ":",
+        "::",
+        "[::1]:0",
+        "#",
+        "#ABCDEFG",
+        "rgb(",
+        "hsl(1,2,3)",
+        "00:00:00",
+        "9999-99-99",
+        "2024-02-30",
+        "1970-01-01T00:00:00Z",
+        "0000-00-00",
+        "-1",
+        "1e999",
+        "NaN",
+        "9223372036854775808",
+        "AC:DE:48:00:00:00",
+        "01ARZ3NDEKTSV4RRFFQ69G5FAV",
+        "zzzzzzzzzzzzzzzzzzzzzzzzzzz",
+        "ffffffff-ffff-ffff-ffff",
+        "localhost.",
+        "a..b",
+        "x:65536",
+        "\u{10FFFF}\u{10FFFF}",
+        "99999999999999-12-31",
+        "-9223372036854775808-01-01T23:59:59Z",
+        "292277026596-12-04T15:30:08",
+    ];
+
+    /// xorshift64*，保证每次运行结果一致
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n.max(1) as u64) as usize
+        }
+
+        fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+            &items[self.below(items.len())]
+        }
+    }
+
+    fn iterations(default: usize) -> usize {
+        std::env::var("VDSL_FUZZ_ITERATIONS")
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(default)
+    }
+
+    /// 在字符边界上随机插入、删除或截断
+    fn mutate(rng: &mut Rng, input: &str) -> String {
+        let mut chars: Vec<char> = input.chars().collect();
+        for _ in 0..=rng.below(4) {
+            let at = rng.below(chars.len() + 1);
+            match rng.below(4) {
+                0 => {
+                    let fragment = rng.pick(FRAGMENTS);
+                    chars.splice(at..at, fragment.chars());
+                }
+                1 if at < chars.len() => {
+                    let end = (at + rng.below(8) + 1).min(chars.len());
+                    chars.drain(at..end);
+                }
+                2 => chars.truncate(at),
+                _ => {
+                    let copy: Vec<char> = chars[at..].iter().take(rng.below(24)).copied().collect();
+                    let to = rng.below(chars.len() + 1);
+                    chars.splice(to..to, copy);
+                }
+            }
+        }
+        chars.into_iter().collect()
+    }
+
+    fn random_scalar(rng: &mut Rng) -> Value {
+        match rng.below(6) {
+            0 => Value::Int(*rng.pick(&[0, 1, -1, i64::MIN, i64::MAX, 65536, 1 << 53])),
+            1 => Value::Float(*rng.pick(&[
+                0.0,
+                -0.0,
+                f64::NAN,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::MAX,
+                f64::MIN_POSITIVE,
+                1e19,
+            ])),
+            2 => Value::Bool(rng.below(2) == 0),
+            _ => Value::String(rng.pick(STRINGS).to_string()),
+        }
+    }
+
+    fn random_value(rng: &mut Rng, depth: usize) -> Value {
+        match rng.below(if depth == 0 { 1 } else { 8 }) {
+            6 => Value::Array(
+                (0..rng.below(4))
+                    .map(|_| random_value(rng, depth - 1))
+                    .collect(),
+            ),
+            7 => Value::Object(
+                (0..rng.below(4))
+                    .map(|i| (format!("k{}", i), random_value(rng, depth - 1)))
+                    .collect(),
+            ),
+            _ => random_scalar(rng),
+        }
+    }
+
+    /// 按规则的形状填充随机值，使校验能深入到约束而不是在类型检查处提前返回
+    fn shaped_value(rng: &mut Rng, rules: &[FieldRule], depth: usize) -> Value {
+        let mut obj = ObjectMap::new();
+        for rule in rules {
+            if rng.below(8) == 0 {
+                continue;
+            }
+            obj.insert(rule.field.to_string(), shaped_field(rng, rule, depth));
+        }
+        Value::Object(obj)
+    }
+
+    fn shaped_field(rng: &mut Rng, rule: &FieldRule, depth: usize) -> Value {
+        if depth == 0 || rng.below(6) == 0 {
+            return random_value(rng, 2);
+        }
+        if let Some(sub) = &rule.rule {
+            return Value::Array(
+                (0..rng.below(5))
+                    .map(|_| match &sub.children {
+                        Some(children) => shaped_value(rng, children, depth - 1),
+                        None => shaped_field(rng, sub, depth - 1),
+                    })
+                    .collect(),
+            );
+        }
+        match &rule.children {
+            Some(children) => shaped_value(rng, children, depth - 1),
+            None => random_scalar(rng),
+        }
+    }
+
+    /// 依次运行所有同步入口
+    fn validate_everything(value: &Value, rules: &[FieldRule]) {
+        let _ = validate(value, rules);
+        let _ = validate_object(&mut value.clone(), rules);
+        for options in [
+            ValidationOptions {
+                strict: true,
+                coerce: true,
+                canonicalize: true,
+                exhaustive: true,
+                float_epsilon: 1e-9,
+                max_errors: 1,
+                frozen: false,
+                max_depth: 2,
+                budget: Budget {
+                    max_nodes: 50,
+                    max_time: None,
+                },
+                #[cfg(feature = "ordered")]
+                ordered: true,
+            },
+            ValidationOptions {
+                strict: false,
+                coerce: true,
+                canonicalize: false,
+                exhaustive: false,
+                float_epsilon: 0.0,
+                max_errors: 0,
+                frozen: false,
+                max_depth: 0,
+                budget: Budget::default(),
+                #[cfg(feature = "ordered")]
+                ordered: false,
+            },
+        ] {
+            let ctx = ValidationContext::new()
+                .options(options.clone())
+                .clock(FixedClock(i64::MAX))
+                .resolver(|_: &str, _: &Value| true);
+            let mut once = value.clone();
+            if validate_with_report(&mut once, rules, &ctx).is_ok() {
+                // 校验过的载荷再次校验仍然通过，且不再有任何修改
+                let frozen = ctx.clone().options(ValidationOptions {
+                    frozen: true,
+                    ..options
+                });
+                let report = validate_with_report(&mut once.clone(), rules, &frozen);
+                assert!(
+                    matches!(&report, Ok(r) if r.changes.is_empty()),
+                    "{:?}: {:?}",
+                    once,
+                    report
+                );
+            }
+        }
+        for rule in rules {
+            if let Value::Object(obj) = value
+                && let Some(field) = obj.get(rule.field.as_str())
+            {
+                let _ = validate(field, std::slice::from_ref(rule));
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_rules_never_panics() {
+        let mut rng = Rng(0x5EED);
+        for _ in 0..iterations(3000) {
+            let mut dsl = rng.pick(CORPUS).to_string();
+            for _ in 0..=rng.below(3) {
+                dsl = mutate(&mut rng, &dsl);
+            }
+            let strict = Parser::parse_rules(&dsl);
+            // 恢复模式不会卡住，结论与 parse_rules 一致
+            let recovering = Parser::parse_rules_recovering(&dsl);
+            assert_eq!(strict.is_ok(), recovering.is_ok(), "{}", dsl);
+            if let (Err(first), Err(all)) = (&strict, &recovering) {
+                assert!(first.starts_with(&all[0].message), "{}", dsl);
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_never_panics() {
+        let mut rng = Rng(0xC0FFEE);
+        let mut checked = 0;
+        for i in 0..iterations(1500) {
+            let seed = *rng.pick(CORPUS);
+            let dsl = if i % 3 == 0 {
+                seed.to_string()
+            } else {
+                mutate(&mut rng, seed)
+            };
+            let Ok(rules) = Parser::parse_rules(&dsl) else {
+                continue;
+            };
+            checked += 1;
+            validate_everything(&shaped_value(&mut rng, &rules, 4), &rules);
+            validate_everything(&random_value(&mut rng, 3), &rules);
+            if let Ok(value) = Generator::new(rng.next()).invalid(&rules) {
+                validate_everything(&value, &rules);
+            }
+        }
+        assert!(checked > 100, "too few mutated schemas parsed: {}", checked);
+    }
+
+    #[test]
+    fn test_validate_rule_never_panics() {
+        let mut rng = Rng(0xFACE);
+        for _ in 0..iterations(1000) {
+            let rule = mutate(&mut rng, "(a:int[0,10])");
+            let value = mutate(&mut rng, r#"{"a": 5}"#);
+            let _ = validate_rule(&rule, &value);
+        }
+    }
+
+    #[test]
+    fn test_out_of_range_dates_are_rejected() {
+        let rules =
+            Parser::parse_rules("(when:string|datetime past within(1d) min_age(1))").unwrap();
+        for when in [
+            "99999999999999-12-31",
+            "-9223372036854775808-01-01T23:59:59Z",
+            "292277026596-12-04T15:30:08",
+        ] {
+            let mut value =
+                Value::Object([("when".to_string(), Value::String(when.into()))].into());
+            assert!(validate_object(&mut value, &rules).is_err(), "{}", when);
+        }
+    }
+
+    #[test]
+    fn test_nesting_depth_is_limited() {
+        let nested = |n: usize| format!("(a:{}int{})", "array<".repeat(n), ">".repeat(n));
+        assert!(Parser::parse_rules(&nested(MAX_NESTING_DEPTH - 1)).is_ok());
+        let err = Parser::parse_rules(&nested(100_000)).unwrap_err();
+        assert!(err.contains("Nesting deeper than"), "{}", err);
+
+        let objects = |n: usize| format!("({}b:int{})", "a:object(".repeat(n), ")".repeat(n));
+        assert!(Parser::parse_rules(&objects(MAX_NESTING_DEPTH - 1)).is_ok());
+        assert!(Parser::parse_rules(&objects(MAX_NESTING_DEPTH)).is_err());
+
+        let deep = format!("{}1{}", "[".repeat(100_000), "]".repeat(100_000));
+        assert!(!validate_rule(
+            "(a:array<int>)",
+            &format!("{{\"a\": {}}}", deep)
+        ));
+    }
+}
@@ -2,7 +2,8 @@
 mod parser_tests {
     use zz_validator::{
         ast::{Constraint, Constraints, FieldType, Value},
-        parser::Parser,
+        parser::{DuplicateFields, LossyDefaults, Parser},
+        token::{tokenize, tokenize_with_spans},
     };
 
     #[test]
@@ -36,7 +37,18 @@ mod parser_tests {
         )
         "#;
 
-        let rules = Parser::parse_rules(dsl).expect("Failed to parse DSL");
+        // 重复的 age 默认是错误，兼容旧 schema 时保留两条规则并记录 warning
+        assert_eq!(
+            Parser::parse_rules(dsl).unwrap_err(),
+            "Duplicate field 'age' (first defined at line 4, column 13) at line 5, column 13"
+        );
+        let mut parser = Parser::with_spans(tokenize_with_spans(dsl).unwrap())
+            .duplicate_fields(DuplicateFields::Warn);
+        let rules = parser.parse_program().expect("Failed to parse DSL");
+        assert_eq!(
+            parser.warnings(),
+            ["Duplicate field 'age' (first defined at line 4, column 13) at line 5, column 13"]
+        );
 
         // 检查总字段数量
         assert_eq!(rules.len(), 17);
@@ -63,4 +75,214 @@ mod parser_tests {
         assert!(escaped_field_constraint.contains("\n"));
         // assert!(escaped_field_constraint.contains(r"quote"));
     }
+
+    fn parse_with(dsl: &str, policy: LossyDefaults) -> (Result<usize, String>, Vec<String>) {
+        let mut parser = Parser::new(tokenize(dsl).unwrap()).lossy_defaults(policy);
+        let rules = parser.parse_program().map(|rules| rules.len());
+        (rules, parser.warnings().to_vec())
+    }
+
+    #[test]
+    fn test_lossy_defaults() {
+        for dsl in [
+            "(a:int=1.5)",
+            r#"(a:int|float="5")"#,
+            r#"(a:uuid="not-a-uuid")"#,
+        ] {
+            let (rules, warnings) = parse_with(dsl, LossyDefaults::Warn);
+            assert_eq!(rules, Ok(1), "{}", dsl);
+            assert_eq!(warnings.len(), 1, "{}", dsl);
+
+            let (rules, _) = parse_with(dsl, LossyDefaults::Deny);
+            assert!(rules.is_err(), "{}", dsl);
+
+            let (rules, warnings) = parse_with(dsl, LossyDefaults::Allow);
+            assert!(rules.is_ok() && warnings.is_empty(), "{}", dsl);
+        }
+
+        let (_, warnings) = parse_with("(age:int=1.5)", LossyDefaults::Warn);
+        assert_eq!(
+            warnings[0],
+            "Default 1.5 of field 'age' does not match type int"
+        );
+
+        // 联合类型满足其一即可；float 字段的整数默认值按 float 解析
+        for dsl in [
+            "(a:int|float=1.5)",
+            r#"(a:int|string="x")"#,
+            "(a:float=1)",
+            "(a:int[0,10]=3, b:bool=true, c:string=5)",
+        ] {
+            let (rules, warnings) = parse_with(dsl, LossyDefaults::Deny);
+            assert!(rules.is_ok() && warnings.is_empty(), "{}", dsl);
+        }
+
+        // parse_rules 默认只警告，保持兼容
+        let rules = Parser::parse_rules("(a:int=1.5)").unwrap();
+        assert_eq!(rules[0].default, Some(Value::Float(1.5)));
+    }
+
+    #[test]
+    fn test_constraint_type_compatibility() {
+        for (dsl, message) in [
+            (
+                "(a:bool[1,10])",
+                "[1, 10] cannot be applied to field 'a' of type bool",
+            ),
+            (
+                "(a:uuid[1,10])",
+                "[1, 10] cannot be applied to field 'a' of type uuid",
+            ),
+            (
+                r#"(a:int regex("x"))"#,
+                r#"regex("x") cannot be applied to field 'a' of type int"#,
+            ),
+            (
+                "(a:string past)",
+                "past cannot be applied to field 'a' of type string",
+            ),
+            (
+                "(a:int sorted)",
+                "sorted(asc) cannot be applied to field 'a' of type int",
+            ),
+            (
+                "(a:uuid oui(\"AC:DE:48\"))",
+                "cannot be applied to field 'a' of type uuid",
+            ),
+            (
+                "(a:array<bool[0,1]>)",
+                "cannot be applied to array elements of type bool",
+            ),
+            (
+                "(a:bool|uuid[1,2])",
+                "cannot be applied to field 'a' of type bool|uuid",
+            ),
+        ] {
+            let err = Parser::parse_rules(dsl).unwrap_err();
+            assert!(err.contains(message), "{}: {}", dsl, err);
+        }
+
+        for dsl in [
+            "(a:int[1,10], b:float(0,1), c:string[1,5], d:timestamp[0,100])",
+            "(a:array<int>[1,3], b:array<string> sorted count>=1)",
+            r#"(a:email regex("@example\\.com$"), b:port regex("^80"))"#,
+            "(a:bool|int[1,2], b:date past, c:int luhn, d:mac oui(\"AC:DE:48\"))",
+        ] {
+            assert!(Parser::parse_rules(dsl).is_ok(), "{}", dsl);
+        }
+    }
+
+    #[test]
+    fn test_array_length_range() {
+        let rules = Parser::parse_rules("(tags:array<string>[1,3])").unwrap();
+        let check = |n: usize| {
+            let items = vec![Value::String("x".into()); n];
+            let mut value = Value::Object([("tags".to_string(), Value::Array(items))].into());
+            zz_validator::validator::validate_object(&mut value, &rules)
+        };
+        assert!(check(0).is_err());
+        assert!(check(1).is_ok());
+        assert!(check(3).is_ok());
+        assert!(check(4).is_err());
+    }
+
+    #[test]
+    fn test_syntax_errors_have_location() {
+        for (dsl, message) in [
+            (
+                "(name:string[1,20, age:int)",
+                "Expected closing bracket or paren, got Some(Comma) at line 1, column 18",
+            ),
+            (
+                "(\n  a:int\n  b:int\n)",
+                "Expected ',' or ')', got Some(Ident(\"b\")) at line 3, column 3",
+            ),
+            (
+                "(a:int, b:string regex(1))",
+                "Expected pattern, got Some(Number(\"1\")) at line 1, column 24",
+            ),
+            ("(a:int", "Expected ',' or ')', got None at end of input"),
+        ] {
+            assert_eq!(Parser::parse_rules(dsl).unwrap_err(), message);
+        }
+
+        let err = Parser::parse_constraints("regex(\"a\") foo", &FieldType::String).unwrap_err();
+        assert_eq!(
+            err,
+            "Unexpected Ident(\"foo\") in constraints at line 1, column 12"
+        );
+
+        // 直接用 tokenize 的结果创建时没有位置信息
+        let mut parser = Parser::new(tokenize("(a:int b:int)").unwrap());
+        assert_eq!(
+            parser.parse_program().unwrap_err(),
+            "Expected ',' or ')', got Some(Ident(\"b\"))"
+        );
+        assert_eq!(parser.span(), None);
+    }
+
+    #[test]
+    fn test_recovering_reports_every_error() {
+        let dsl = "(\n  a:int[0,10,20],\n  b:strin,\n  c:object(d:int e:int, f:bool),\n  g:string regex(1),\n  h:int\n)";
+        let errors = Parser::parse_rules_recovering(dsl).unwrap_err();
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Expected closing bracket or paren, got Some(Comma) at line 2, column 13",
+                "Unknown type strin at line 3, column 5",
+                "Expected ',' or ')' in object at line 4, column 14",
+                "Expected pattern, got Some(Number(\"1\")) at line 5, column 18",
+            ]
+        );
+        assert_eq!(errors[1].message, "Unknown type strin");
+        assert_eq!(errors[1].span.unwrap().line, 3);
+
+        // 与 parse_rules 的第一个错误一致
+        assert_eq!(Parser::parse_rules(dsl).unwrap_err(), messages[0]);
+
+        // 没有错误时与 parse_rules 相同
+        let rules = Parser::parse_rules_recovering("(a:int, b:object(c:string))").unwrap();
+        assert_eq!(rules.len(), 2);
+
+        // 未闭合的括号只报告一次
+        let errors = Parser::parse_rules_recovering("(a:int, b:string regex(\"x\"").unwrap_err();
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert_eq!(errors[0].to_string(), "Unexpected EOF at end of input");
+
+        // 词法错误无法继续解析
+        let errors = Parser::parse_rules_recovering("(a:int, b:int $)").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "Unexpected char '$' at line 1, column 15"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_fields() {
+        let dsl = "(user:object(name:string,\n  name:string[1, 5]), other:object(name:string))";
+        assert_eq!(
+            Parser::parse_rules(dsl).unwrap_err(),
+            "Duplicate field 'name' (first defined at line 1, column 14) at line 2, column 3"
+        );
+        let errors =
+            Parser::parse_rules_recovering("(a:int, b:int, a:string, b:bool)").unwrap_err();
+        let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Duplicate field 'a' (first defined at line 1, column 2) at line 1, column 16",
+                "Duplicate field 'b' (first defined at line 1, column 9) at line 1, column 26"
+            ]
+        );
+
+        // 没有位置信息时只给出字段名
+        let mut parser = Parser::new(tokenize("(a:int, a:int)").unwrap());
+        assert_eq!(parser.parse_program().unwrap_err(), "Duplicate field 'a'");
+        let mut parser = Parser::new(tokenize("(a:int, a:int)").unwrap())
+            .duplicate_fields(DuplicateFields::Warn);
+        assert_eq!(parser.parse_program().unwrap().len(), 2);
+        assert_eq!(parser.warnings(), ["Duplicate field 'a'"]);
+    }
 }
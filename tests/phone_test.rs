@@ -0,0 +1,145 @@
+#[cfg(test)]
+mod phone_tests {
+    use zz_validator::{
+        ast::Value,
+        json::from_json_str,
+        phone::normalize_phone,
+        report::ChangeKind,
+        schema::Schema,
+        validator::{ValidationContext, ValidationError, validate_with_report},
+    };
+
+    #[test]
+    fn test_normalize_phone() {
+        assert_eq!(
+            normalize_phone("(415) 555-0123", "US").unwrap(),
+            "+14155550123"
+        );
+        assert_eq!(
+            normalize_phone("1-415-555-0123", "US").unwrap(),
+            "+14155550123"
+        );
+        assert_eq!(
+            normalize_phone("011 44 20 7946 0958", "us").unwrap(),
+            "+442079460958"
+        );
+        assert_eq!(
+            normalize_phone("+44 20 7946 0958", "US").unwrap(),
+            "+442079460958"
+        );
+        assert_eq!(
+            normalize_phone("020 7946 0958", "GB").unwrap(),
+            "+442079460958"
+        );
+        assert_eq!(
+            normalize_phone("0049 30 123456", "DE").unwrap(),
+            "+4930123456"
+        );
+        assert_eq!(
+            normalize_phone("138 1234 5678", "CN").unwrap(),
+            "+8613812345678"
+        );
+        assert_eq!(
+            normalize_phone("010-1234-5678", "CN").unwrap(),
+            "+861012345678"
+        );
+        // 已经是 E.164 时不变
+        assert_eq!(
+            normalize_phone("+14155550123", "GB").unwrap(),
+            "+14155550123"
+        );
+
+        assert!(normalize_phone("555-0123", "US").is_err());
+        assert!(normalize_phone("415 555 O123", "US").is_err());
+        assert!(normalize_phone("20 7946 0958", "GB").is_err());
+        assert!(normalize_phone("+0123456789", "US").is_err());
+        assert_eq!(
+            normalize_phone("123", "XX").unwrap_err(),
+            "Unknown phone region 'XX'"
+        );
+        // 010 既是国际冠码，也是长途前缀 0 加上以 10 开头的号码
+        assert_eq!(
+            normalize_phone("010 1234 5678", "JP").unwrap_err(),
+            "'010 1234 5678' is ambiguous for region JP: +12345678 or +811012345678"
+        );
+    }
+
+    #[test]
+    fn test_normalize_before_validation() {
+        let schema = Schema::parse(
+            r#"(phone:phone normalize_phone, alt?:array<phone normalize_phone(region="GB")>)"#,
+        )
+        .unwrap();
+        let mut value = from_json_str(
+            r#"{"phone": "(415) 555-0123", "alt": ["020 7946 0958", "+14155550123"]}"#,
+        )
+        .unwrap();
+        let report =
+            validate_with_report(&mut value, schema.rules(), &ValidationContext::new()).unwrap();
+        let obj = value.as_object().unwrap();
+        assert_eq!(
+            obj.get("phone"),
+            Some(&Value::String("+14155550123".into()))
+        );
+        assert_eq!(
+            obj.get("alt"),
+            Some(&Value::Array(vec![
+                Value::String("+442079460958".into()),
+                Value::String("+14155550123".into())
+            ]))
+        );
+        let paths: Vec<(&str, ChangeKind)> = report
+            .changes
+            .iter()
+            .map(|c| (c.path.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                ("phone", ChangeKind::Transform),
+                ("alt[0]", ChangeKind::Transform)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unnormalizable_numbers_fail() {
+        let schema = Schema::parse(r#"(tel:string normalize_phone(region="JP"))"#).unwrap();
+        let mut value = from_json_str(r#"{"tel": "010 1234 5678"}"#).unwrap();
+        let err = schema.validate(&mut value).unwrap_err();
+        assert!(matches!(err, ValidationError::TypeMismatch { .. }));
+        assert_eq!(
+            err.to_string(),
+            "tel value 010 1234 5678: '010 1234 5678' is ambiguous for region JP: +12345678 or +811012345678"
+        );
+        // 失败时载荷保持原样
+        assert_eq!(
+            value.as_object().unwrap().get("tel"),
+            Some(&Value::String("010 1234 5678".into()))
+        );
+
+        let outcomes = schema.validate_outcomes(&from_json_str(r#"{"tel": "12"}"#).unwrap());
+        assert!(!outcomes.is_valid());
+        let outcomes =
+            schema.validate_outcomes(&from_json_str(r#"{"tel": "03-1234-5678"}"#).unwrap());
+        assert!(outcomes.is_valid());
+    }
+
+    #[test]
+    fn test_dsl() {
+        let schema = Schema::parse("(p:phone normalize_phone)").unwrap();
+        assert_eq!(
+            schema.to_dsl().trim(),
+            "(\n    p:phone normalize_phone(region=\"US\")\n)"
+        );
+        assert!(Schema::parse(&schema.to_dsl()).is_ok());
+        assert!(Schema::parse("(p:phone normalize_phone(region=gb))").is_ok());
+        assert!(
+            Schema::parse(r#"(p:phone normalize_phone(region="XX"))"#)
+                .unwrap_err()
+                .starts_with("Unknown phone region 'XX'")
+        );
+        assert!(Schema::parse("(n:int normalize_phone)").is_err());
+        assert!(Schema::parse("(p:phone warn(normalize_phone))").is_err());
+    }
+}
@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod prelude_tests {
+    use zz_validator::{
+        ast::FieldType,
+        json::from_json_str,
+        parser::Parser,
+        prelude::{envelope, named, std_type, std_type_names, with_envelope},
+        schema::Schema,
+        validator::{ValidationError, validate_object},
+    };
+
+    fn check(json: &str) -> Result<(), ValidationError> {
+        let rules = with_envelope(Parser::parse_rules("(amount:int[1, 100])").unwrap());
+        let mut value = from_json_str(json).unwrap();
+        validate_object(&mut value, &rules)
+    }
+
+    #[test]
+    fn test_envelope_rules() {
+        let rules = envelope();
+        assert_eq!(rules.len(), 4);
+        assert!(rules.iter().all(|r| !r.required));
+        assert_eq!(
+            named("client_version").unwrap().field_type,
+            FieldType::SemVer
+        );
+        assert!(named("missing").is_none());
+
+        // 业务 schema 中的同名字段优先
+        let rules = with_envelope(Parser::parse_rules("(request_id:int)").unwrap());
+        assert_eq!(rules.len(), 4);
+        assert_eq!(rules[0].field_type, FieldType::Int);
+        assert!(rules[0].required);
+    }
+
+    #[test]
+    fn test_validate_envelope() {
+        assert!(
+            check(
+                r#"{
+                    "amount": 5,
+                    "idempotency_key": "01ARZ3NDEKTSV4RRFFQ69G5FAV",
+                    "request_id": "req-123:a",
+                    "client_version": "2.1.0-beta.1",
+                    "rate_limit": {"limit": 100, "remaining": 0, "reset": 1700000000}
+                }"#
+            )
+            .is_ok()
+        );
+        assert!(
+            check(r#"{"amount": 5, "idempotency_key": "550e8400-e29b-41d4-a716-446655440000"}"#)
+                .is_ok()
+        );
+
+        assert!(matches!(
+            check(r#"{"amount": 5, "idempotency_key": "not-a-key"}"#),
+            Err(ValidationError::UnionTypeMismatch { field, .. }) if field == "idempotency_key"
+        ));
+        assert!(check(r#"{"amount": 5, "request_id": "has space"}"#).is_err());
+        assert!(check(r#"{"amount": 5, "client_version": "v2"}"#).is_err());
+        assert!(
+            check(r#"{"amount": 5, "rate_limit": {"limit": 0, "remaining": 0, "reset": 1}}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_std_types() {
+        assert_eq!(
+            std_type_names(),
+            ["Address", "GeoPoint", "Pagination", "DateRange", "Money"]
+        );
+        assert_eq!(std_type("Money").unwrap().field_type, FieldType::Money);
+
+        let schema = Schema::parse(
+            "import std
+            (
+                shipping: Address,
+                billing?: Address label(\"Billing address\"),
+                stops?: array<GeoPoint>[1, 10],
+                page?: Pagination,
+                stay: DateRange,
+                total: Money[0, 500]
+            )",
+        )
+        .unwrap();
+        let mut value = from_json_str(
+            r#"{
+                "shipping": {"line1": "1 Main St", "city": "Springfield", "postal_code": "12345", "country": "US"},
+                "stops": [{"lat": 51.5, "lng": -0.12}],
+                "page": {},
+                "stay": {"start": "2024-05-01", "end": "2024-05-03"},
+                "total": {"amount": "120.00", "currency": "EUR"}
+            }"#,
+        )
+        .unwrap();
+        schema.validate(&mut value).unwrap();
+        // 具名类型中的默认值同样填入
+        assert_eq!(
+            value
+                .as_object()
+                .unwrap()
+                .get("page")
+                .map(|p| p.to_string()),
+            Some(r#"{"page": 1, "per_page": 20}"#.to_string())
+        );
+
+        let mut value = from_json_str(
+            r#"{
+                "shipping": {"line1": "1 Main St", "city": "Springfield", "postal_code": "12345", "country": "US"},
+                "stops": [{"lat": 91, "lng": 0}],
+                "stay": {"start": "2024-05-01", "end": "2024-05-03"},
+                "total": {"amount": 1, "currency": "EUR"}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            schema.validate(&mut value).unwrap_err().field(),
+            Some("stops[0].lat")
+        );
+
+        // 展开后的 DSL 不再依赖 import std
+        assert!(Schema::parse(&schema.to_dsl()).is_ok());
+    }
+
+    #[test]
+    fn test_std_requires_import() {
+        let err = Parser::parse_rules("(a: Address)").unwrap_err();
+        assert!(
+            err.starts_with("Unknown type Address (add 'import std'"),
+            "{}",
+            err
+        );
+        assert!(
+            Parser::parse_rules("import std (a: Adress)")
+                .unwrap_err()
+                .starts_with("Unknown type Adress at")
+        );
+        assert!(
+            Parser::parse_rules("import extra (a: int)")
+                .unwrap_err()
+                .starts_with("Unknown import 'extra'")
+        );
+        assert!(
+            Parser::parse_rules("import std (a: Address | string)")
+                .unwrap_err()
+                .starts_with("Named type Address cannot be used in a union")
+        );
+        // import 与 meta 的顺序任意
+        let (rules, metadata) =
+            Parser::parse_with_metadata("meta(name=\"Trip\") import std (at: GeoPoint)").unwrap();
+        assert_eq!(metadata.name(), Some("Trip"));
+        assert_eq!(rules[0].field_type, FieldType::Object);
+        assert_eq!(rules[0].children.as_ref().map(Vec::len), Some(2));
+    }
+}
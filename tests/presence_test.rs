@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod presence_tests {
+    use zz_validator::{
+        export::{to_dsl, to_json_schema, to_typescript},
+        generator::Generator,
+        json::from_json_str,
+        parser::Parser,
+        schema::Schema,
+        validator::{ValidationError, validate_object},
+    };
+
+    /// 创建时不能带 id，且必须显式给出 role（即使更新时它是可选的）
+    const CREATE: &str = r#"(
+        id?:int absent,
+        name:string[1,20],
+        role?:string enum("admin", "user") present,
+        note?:string
+    )"#;
+
+    fn check(json: &str) -> Result<(), ValidationError> {
+        let schema = Schema::parse(CREATE).unwrap();
+        let streamed = schema.validate_json_bytes(json.as_bytes());
+        let mut value = from_json_str(json).unwrap();
+        let parsed = schema.validate(&mut value);
+        assert_eq!(streamed, parsed, "{}", json);
+        parsed
+    }
+
+    #[test]
+    fn test_present_and_absent() {
+        assert!(check(r#"{"name": "alice", "role": "user"}"#).is_ok());
+        assert_eq!(
+            check(r#"{"id": 1, "name": "alice", "role": "user"}"#),
+            Err(ValidationError::ForbiddenField("id".into()))
+        );
+        // 类型错误时也报告不得提供，而不是类型不匹配
+        assert_eq!(
+            check(r#"{"id": "x", "name": "alice", "role": "user"}"#),
+            Err(ValidationError::ForbiddenField("id".into()))
+        );
+        // 可选字段的空串也算提供了值
+        assert_eq!(
+            check(r#"{"id": "", "name": "alice", "role": "user"}"#),
+            Err(ValidationError::ForbiddenField("id".into()))
+        );
+        assert_eq!(
+            check(r#"{"name": "alice"}"#),
+            Err(ValidationError::MissingField("role".into()))
+        );
+        assert_eq!(
+            ValidationError::ForbiddenField("id".into()).to_string(),
+            "Field id must not be provided"
+        );
+    }
+
+    #[test]
+    fn test_presence_conflicts() {
+        for bad in [
+            "(id:int absent)",
+            "(id?:int absent = 1)",
+            "(role:string present = \"user\")",
+            "(id?:int present absent)",
+            "(ids:array<int absent>)",
+        ] {
+            assert!(Parser::parse_rules(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_presence_export() {
+        let rules = Parser::parse_rules(CREATE).unwrap();
+        let dsl = to_dsl(&rules);
+        assert!(dsl.contains("id?:int absent"));
+        assert!(dsl.contains("role?:string present enum"));
+        assert_eq!(to_dsl(&Parser::parse_rules(&dsl).unwrap()), dsl);
+
+        let schema = to_json_schema(&rules);
+        let required = schema["required"].as_array().unwrap();
+        assert!(required.contains(&"role".into()));
+        assert!(!required.contains(&"id".into()));
+
+        let ts = to_typescript(&rules, "CreateUser");
+        assert!(ts.contains("id?: never;"));
+        assert!(ts.contains("role: "));
+
+        let mut generator = Generator::new(3);
+        for _ in 0..20 {
+            let mut value = generator.valid(&rules).unwrap();
+            let obj = value.as_object().unwrap();
+            assert!(!obj.contains_key("id") && obj.contains_key("role"));
+            assert!(validate_object(&mut value, &rules).is_ok());
+            let mut bad = generator.invalid(&rules).unwrap();
+            assert!(validate_object(&mut bad, &rules).is_err());
+        }
+    }
+}
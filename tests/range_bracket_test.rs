@@ -0,0 +1,156 @@
+#[cfg(test)]
+mod range_bracket_tests {
+    use zz_validator::{
+        ast::{Constraint, Value},
+        export::{to_dsl, to_json_schema},
+        generator::Generator,
+        import::from_json_schema,
+        json::from_json_str,
+        parser::Parser,
+        validator::{ValidationError, validate_object},
+    };
+
+    /// 依次为 `[]`、`[)`、`(]`、`()`
+    const BRACKETS: [(char, char); 4] = [('[', ']'), ('[', ')'), ('(', ']'), ('(', ')')];
+
+    fn check(rule: &str, value: Value) -> Result<(), ValidationError> {
+        let rules = Parser::parse_rules(&format!("({})", rule)).unwrap();
+        let mut value = Value::Object([("v".to_string(), value)].into());
+        validate_object(&mut value, &rules)
+    }
+
+    /// 在四种括号组合下检查 min、区间内的值与 max 是否通过
+    fn assert_brackets(ty: &str, min: &str, max: &str, at: impl Fn(usize) -> Value) {
+        for (open, close) in BRACKETS {
+            let rule = format!("v:{}{}{}, {}{}", ty, open, min, max, close);
+            assert_eq!(check(&rule, at(0)).is_ok(), open == '[', "{} at min", rule);
+            assert!(check(&rule, at(1)).is_ok(), "{} inside", rule);
+            assert_eq!(check(&rule, at(2)).is_ok(), close == ']', "{} at max", rule);
+        }
+    }
+
+    #[test]
+    fn test_mixed_brackets_all_types() {
+        assert_brackets("int", "0", "100", |i| Value::Int([0, 50, 100][i]));
+        assert_brackets("float", "0.5", "1.5", |i| Value::Float([0.5, 1.0, 1.5][i]));
+        assert_brackets("string", "2", "4", |i| {
+            Value::String("abcd"[..i + 2].into())
+        });
+        assert_brackets("array<int>", "1", "3", |i| {
+            Value::Array(vec![Value::Int(0); i + 1])
+        });
+        assert_brackets("date", r#""2024-01-01""#, r#""2024-12-31""#, |i| {
+            Value::String(["2024-01-01", "2024-06-15", "2024-12-31"][i].into())
+        });
+        assert_brackets(
+            "datetime",
+            r#""2024-01-01T08:00:00Z""#,
+            r#""2024-01-01T18:00:00Z""#,
+            |i| {
+                let hour = [8, 12, 18][i];
+                Value::String(format!("2024-01-01T{:02}:00:00Z", hour))
+            },
+        );
+    }
+
+    #[test]
+    fn test_error_message_brackets() {
+        // 报错中的括号与规则中的写法相同
+        for (open, close) in BRACKETS {
+            let rule = format!("v:int{}0, 10{}", open, close);
+            let outside = if open == '(' { 0 } else { 11 };
+            assert_eq!(
+                check(&rule, Value::Int(outside)).unwrap_err().to_string(),
+                format!("v value {} out of range {}0, 10{}", outside, open, close)
+            );
+            let rule = format!("v:string{}1, 3{}", open, close);
+            let text = if close == ')' { "abc" } else { "abcd" };
+            assert_eq!(
+                check(&rule, Value::String(text.into()))
+                    .unwrap_err()
+                    .to_string(),
+                format!("v length {} out of range {}1, 3{}", text.len(), open, close)
+            );
+        }
+        let rule = r#"v:date("2024-01-01", "2025-01-01"]"#;
+        assert_eq!(
+            check(rule, Value::String("2024-01-01".into()))
+                .unwrap_err()
+                .to_string(),
+            "v value 2024-01-01 out of range (2024-01-01, 2025-01-01]"
+        );
+    }
+
+    #[test]
+    fn test_date_range() {
+        let rule = r#"v:date["2024-01-01", "2025-01-01")"#;
+        assert!(check(rule, Value::String("2023-12-31".into())).is_err());
+        assert_eq!(
+            check(rule, Value::String("2025-01-01".into())).unwrap_err(),
+            ValidationError::RangeError {
+                field: "v".into(),
+                value: "2025-01-01".into(),
+                min: "2024-01-01".into(),
+                max: "2025-01-01".into(),
+                min_inclusive: true,
+                max_inclusive: false,
+                length: false,
+            }
+        );
+        // 只精确到天的边界按当天 00:00 比较
+        let rule = r#"v:datetime["2024-01-01", "2024-01-02"]"#;
+        assert!(check(rule, Value::String("2024-01-01T23:59:59Z".into())).is_ok());
+        assert!(check(rule, Value::String("2024-01-02T00:00:01Z".into())).is_err());
+        assert!(check(rule, Value::String("not a date".into())).is_err());
+    }
+
+    #[test]
+    fn test_invalid_ranges() {
+        for bad in [
+            r#"(v:date["2024-02-30", "2025-01-01"])"#,
+            "(v:date[1, 2])",
+            r#"(v:int["1", "2"])"#,
+            "(v:int[1, 2}",
+            "(v:int[1 2])",
+        ] {
+            assert!(Parser::parse_rules(bad).is_err(), "{}", bad);
+        }
+        // 相等的边界是合法的：闭区间只允许该值，其余组合为空区间
+        assert!(check("v:int[5, 5]", Value::Int(5)).is_ok());
+        assert!(check("v:int[5, 5)", Value::Int(5)).is_err());
+    }
+
+    #[test]
+    fn test_date_range_round_trip() {
+        let rules = Parser::parse_rules(
+            r#"(from:date["2024-01-01", "2025-01-01"), at:datetime("2024-01-01T00:00:00Z", "2024-02-01T00:00:00Z"])"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            &rules[0].constraints.as_ref().unwrap().items[0],
+            Constraint::Range { min: Value::String(min), min_inclusive: true, max_inclusive: false, .. }
+                if min == "2024-01-01"
+        ));
+        let dsl = to_dsl(&rules);
+        assert!(dsl.contains(r#"from:date["2024-01-01", "2025-01-01")"#));
+        assert_eq!(to_dsl(&Parser::parse_rules(&dsl).unwrap()), dsl);
+
+        // JSON Schema 中保留为扩展约束，不会变成 minLength
+        let schema = to_json_schema(&rules);
+        assert!(schema["properties"]["from"].get("minLength").is_none());
+        let imported = to_dsl(&from_json_schema(&schema).unwrap());
+        assert!(imported.contains(r#"from:date["2024-01-01", "2025-01-01")"#));
+        assert!(
+            imported.contains(r#"at:datetime("2024-01-01T00:00:00Z", "2024-02-01T00:00:00Z"]"#)
+        );
+
+        let mut generator = Generator::new(7);
+        for _ in 0..20 {
+            let mut value = generator.valid(&rules).unwrap();
+            assert!(validate_object(&mut value, &rules).is_ok());
+        }
+        let mut value =
+            from_json_str(r#"{"from": "2024-05-05", "at": "2024-01-01T00:00:00Z"}"#).unwrap();
+        assert!(validate_object(&mut value, &rules).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+#[cfg(test)]
+mod ref_tests {
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Mutex;
+    use std::task::{Context, Poll, Waker};
+
+    use zz_validator::{
+        ast::{Constraint, Value},
+        export::to_dsl,
+        json::from_json_str,
+        parser::Parser,
+        refs::AsyncRefResolver,
+        report::Outcome,
+        validator::{
+            ValidationContext, ValidationError, validate_async, validate_object, validate_outcomes,
+            validate_shared, validate_with_context,
+        },
+    };
+
+    const DSL: &str = r#"(
+        orders:array<object(id:int, user_id:int ref(users[].id), tags?:array<string ref(tags[])>)>,
+        users:array<object(id:int, name:string)>,
+        tags?:array<string>,
+        owner?:int ref(users[].id)
+    )"#;
+
+    fn check(json: &str) -> Result<(), ValidationError> {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut value = from_json_str(json).unwrap();
+        validate_object(&mut value, &rules)
+    }
+
+    #[test]
+    fn test_parse_ref() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        assert!(matches!(
+            &rules[3].constraints.as_ref().unwrap().items[0],
+            Constraint::Ref(path) if path == "users[].id"
+        ));
+
+        let dsl = to_dsl(&rules);
+        assert!(dsl.contains("user_id:int ref(users[].id)"));
+        assert!(dsl.contains("array<string ref(tags[])>"));
+        assert_eq!(to_dsl(&Parser::parse_rules(&dsl).unwrap()), dsl);
+
+        for bad in ["ref()", "ref(users[)", "ref(users.)", "ref(\"users\")"] {
+            let dsl = format!("(a:int {})", bad);
+            assert!(Parser::parse_rules(&dsl).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_ref_resolves_within_payload() {
+        // 引用目标出现在被引用字段之后，也能找到
+        assert!(
+            check(
+                r#"{
+                    "orders": [{"id": 1, "user_id": 7, "tags": ["vip"]}, {"id": 2, "user_id": 8}],
+                    "users": [{"id": 7, "name": "a"}, {"id": 8, "name": "b"}],
+                    "tags": ["vip", "new"],
+                    "owner": 8
+                }"#
+            )
+            .is_ok()
+        );
+
+        let err = check(
+            r#"{
+                "orders": [{"id": 1, "user_id": 9}],
+                "users": [{"id": 7, "name": "a"}]
+            }"#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::DanglingRef {
+                field: "orders[0].user_id".into(),
+                value: "9".into(),
+                target: "users[].id".into(),
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "orders[0].user_id = 9 does not match any users[].id"
+        );
+
+        assert!(matches!(
+            check(
+                r#"{
+                    "orders": [{"id": 1, "user_id": 7, "tags": ["gold"]}],
+                    "users": [{"id": 7, "name": "a"}],
+                    "tags": ["vip"]
+                }"#
+            ),
+            Err(ValidationError::DanglingRef { target, .. }) if target == "tags[]"
+        ));
+        assert!(check(r#"{"orders": [], "users": [], "owner": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_ref_target_filled_by_default() {
+        // 引用目标只由默认值提供，只读校验与逐字段结论也不报悬空引用
+        let rules = Parser::parse_rules(
+            "(owner:int ref(admin_id), admin_id:int = 5, team:object(lead:int ref(team.id), id?:int = 9))",
+        )
+        .unwrap();
+        let ctx = ValidationContext::new();
+        let value = from_json_str(r#"{"owner": 5, "team": {"lead": 9}}"#).unwrap();
+        assert!(validate_shared(&value, &rules, &ctx).is_ok());
+        let report = validate_outcomes(&value, &rules, &ctx);
+        assert!(
+            !report
+                .fields
+                .iter()
+                .any(|f| matches!(f.outcome, Outcome::Failed(_))),
+            "{:?}",
+            report
+        );
+        let mut filled = value.clone();
+        assert!(validate_object(&mut filled, &rules).is_ok());
+
+        let value = from_json_str(r#"{"owner": 6, "team": {"lead": 9}}"#).unwrap();
+        assert!(matches!(
+            validate_shared(&value, &rules, &ctx),
+            Err(ValidationError::DanglingRef { field, .. }) if field == "owner"
+        ));
+    }
+
+    const EXISTS_DSL: &str = r#"(
+        owner_id:int exists("user"),
+        members?:array<object(user_id:int exists(user), team:string exists("team"))>
+    )"#;
+
+    fn known(kind: &str, id: &Value) -> bool {
+        match (kind, id) {
+            ("user", Value::Int(id)) => [1, 2, 3].contains(id),
+            ("team", Value::String(name)) => name == "core",
+            _ => false,
+        }
+    }
+
+    /// 测试用的异步查找，记录被查询的 ID
+    struct Lookup(Mutex<Vec<String>>);
+
+    impl AsyncRefResolver for Lookup {
+        async fn exists(&self, kind: &str, id: &Value) -> bool {
+            self.0.lock().unwrap().push(format!("{}:{}", kind, id));
+            known(kind, id)
+        }
+    }
+
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+                return out;
+            }
+        }
+    }
+
+    #[test]
+    fn test_exists_with_resolver() {
+        let rules = Parser::parse_rules(EXISTS_DSL).unwrap();
+        assert!(to_dsl(&rules).contains(r#"owner_id:int exists("user")"#));
+        let ctx = ValidationContext::new().resolver(known);
+
+        let mut value =
+            from_json_str(r#"{"owner_id": 1, "members": [{"user_id": 2, "team": "core"}]}"#)
+                .unwrap();
+        assert!(validate_with_context(&mut value, &rules, &ctx).is_ok());
+
+        let mut value = from_json_str(r#"{"owner_id": 4}"#).unwrap();
+        let err = validate_with_context(&mut value, &rules, &ctx).unwrap_err();
+        assert_eq!(err.to_string(), "owner_id = 4 does not match any user");
+
+        // 未配置 resolver
+        let mut value = from_json_str(r#"{"owner_id": 1}"#).unwrap();
+        assert!(matches!(
+            validate_object(&mut value, &rules),
+            Err(ValidationError::Custom(msg)) if msg.contains("RefResolver")
+        ));
+    }
+
+    #[test]
+    fn test_exists_async() {
+        let rules = Parser::parse_rules(EXISTS_DSL).unwrap();
+        let ctx = ValidationContext::new();
+        let lookup = Lookup(Mutex::new(Vec::new()));
+
+        let mut value = from_json_str(
+            r#"{
+                "owner_id": 1,
+                "members": [{"user_id": 1, "team": "core"}, {"user_id": 3, "team": "core"}]
+            }"#,
+        )
+        .unwrap();
+        assert!(block_on(validate_async(&mut value, &rules, &ctx, &lookup)).is_ok());
+        // 相同的 ID 只查询一次
+        assert_eq!(
+            *lookup.0.lock().unwrap(),
+            vec!["user:1", "team:\"core\"", "user:3"]
+        );
+
+        let mut value =
+            from_json_str(r#"{"owner_id": 2, "members": [{"user_id": 2, "team": "ops"}]}"#)
+                .unwrap();
+        assert!(matches!(
+            block_on(validate_async(&mut value, &rules, &ctx, &lookup)),
+            Err(ValidationError::DanglingRef { target, .. }) if target == "team"
+        ));
+    }
+}
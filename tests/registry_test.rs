@@ -0,0 +1,213 @@
+#[cfg(test)]
+mod registry_tests {
+    use zz_validator::{
+        ast::Value,
+        export::{to_dsl, to_json_schema},
+        import::from_json_schema,
+        parser::Parser,
+        registry::ValidatorRegistry,
+        validator::{ValidationContext, ValidationError, validate_object, validate_with_context},
+    };
+
+    const DSL: &str = r#"(
+        code:string check("even_length"),
+        tags?:array<string check("lowercase")>
+    )"#;
+
+    fn registry() -> ValidatorRegistry {
+        let mut registry = ValidatorRegistry::new();
+        registry
+            .register("even_length", |v: &Value| match v.as_str() {
+                Some(s) if s.len() % 2 == 0 => Ok(()),
+                _ => Err("length must be even".to_string()),
+            })
+            .register("lowercase", |v: &Value| {
+                if v.as_str().is_some_and(|s| s == s.to_lowercase()) {
+                    Ok(())
+                } else {
+                    Err("must be lowercase".to_string())
+                }
+            });
+        registry
+    }
+
+    fn object(pairs: Vec<(&str, Value)>) -> Value {
+        Value::Object(pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+    }
+
+    #[test]
+    fn test_check_uses_registered_validators() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let ctx = ValidationContext::new().registry(registry());
+
+        let mut ok = object(vec![
+            ("code", Value::String("ab".into())),
+            ("tags", Value::Array(vec![Value::String("x".into())])),
+        ]);
+        assert!(validate_with_context(&mut ok, &rules, &ctx).is_ok());
+
+        let mut odd = object(vec![("code", Value::String("abc".into()))]);
+        let err = validate_with_context(&mut odd, &rules, &ctx).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::CheckFailed {
+                field: "code".into(),
+                check: "even_length".into(),
+                message: "length must be even".into(),
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "code failed check 'even_length': length must be even"
+        );
+
+        let mut upper = object(vec![
+            ("code", Value::String("ab".into())),
+            ("tags", Value::Array(vec![Value::String("X".into())])),
+        ]);
+        assert!(matches!(
+            validate_with_context(&mut upper, &rules, &ctx),
+            Err(ValidationError::CheckFailed { check, .. }) if check == "lowercase"
+        ));
+    }
+
+    #[test]
+    fn test_unregistered_check_fails() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut value = object(vec![("code", Value::String("ab".into()))]);
+        assert!(matches!(
+            validate_object(&mut value, &rules),
+            Err(ValidationError::CheckFailed { message, .. }) if message == "validator not registered"
+        ));
+    }
+
+    #[test]
+    fn test_check_round_trips_through_exports() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let dsl = to_dsl(&rules);
+        assert!(dsl.contains(r#"code:string check("even_length")"#));
+        assert_eq!(to_dsl(&Parser::parse_rules(&dsl).unwrap()), dsl);
+
+        let schema = to_json_schema(&rules);
+        assert_eq!(
+            schema["properties"]["code"]["x-vdsl-constraints"],
+            serde_json::json!([r#"check("even_length")"#])
+        );
+        assert_eq!(to_dsl(&from_json_schema(&schema).unwrap()), dsl);
+    }
+
+    #[cfg(feature = "wasm")]
+    mod wasm {
+        use super::*;
+        use zz_validator::wasm::WasmValidator;
+
+        /// 输入 JSON 以 '-' 或 '0' 开头时拒绝，即只接受正数
+        const POSITIVE: &str = r#"(module
+            (memory (export "memory") 1)
+            (data (i32.const 0) "must be positive")
+            (func (export "alloc") (param i32) (result i32) i32.const 1024)
+            (func (export "error_ptr") (result i32) i32.const 0)
+            (func (export "error_len") (result i32) i32.const 16)
+            (func (export "validate") (param $ptr i32) (param $len i32) (result i32)
+                (local $c i32)
+                (local.set $c (i32.load8_u (local.get $ptr)))
+                (i32.or
+                    (i32.eq (local.get $c) (i32.const 45))
+                    (i32.eq (local.get $c) (i32.const 48)))))"#;
+
+        const SPIN: &str = r#"(module
+            (memory (export "memory") 1)
+            (func (export "alloc") (param i32) (result i32) i32.const 0)
+            (func (export "validate") (param i32 i32) (result i32)
+                (loop $l (br $l))
+                i32.const 0))"#;
+
+        #[test]
+        fn test_wasm_validator() {
+            let rules = Parser::parse_rules(r#"(amount:int check("positive"))"#).unwrap();
+            let mut registry = ValidatorRegistry::new();
+            registry.register_wasm("positive", POSITIVE).unwrap();
+            let ctx = ValidationContext::new().registry(registry);
+
+            let mut ok = object(vec![("amount", Value::Int(12))]);
+            assert!(validate_with_context(&mut ok, &rules, &ctx).is_ok());
+
+            let mut bad = object(vec![("amount", Value::Int(-3))]);
+            assert!(matches!(
+                validate_with_context(&mut bad, &rules, &ctx),
+                Err(ValidationError::CheckFailed { message, .. }) if message == "must be positive"
+            ));
+        }
+
+        #[test]
+        fn test_wasm_validator_is_sandboxed() {
+            let mut registry = ValidatorRegistry::new();
+            registry.register("spin", WasmValidator::new(SPIN).unwrap().with_fuel(10_000));
+            let rules = Parser::parse_rules(r#"(n:int check("spin"))"#).unwrap();
+            let ctx = ValidationContext::new().registry(registry);
+            let mut value = object(vec![("n", Value::Int(1))]);
+            assert!(matches!(
+                validate_with_context(&mut value, &rules, &ctx),
+                Err(ValidationError::CheckFailed { message, .. }) if message.contains("trapped")
+            ));
+
+            // 需要宿主导入的模块无法实例化，缺少约定导出的模块无法注册
+            let importing = r#"(module
+                (import "env" "exfiltrate" (func))
+                (memory (export "memory") 1)
+                (func (export "alloc") (param i32) (result i32) i32.const 0)
+                (func (export "validate") (param i32 i32) (result i32) i32.const 0))"#;
+            let mut registry = ValidatorRegistry::new();
+            registry.register_wasm("importing", importing).unwrap();
+            let ctx = ValidationContext::new().registry(registry);
+            let rules = Parser::parse_rules(r#"(n:int check("importing"))"#).unwrap();
+            assert!(validate_with_context(&mut value, &rules, &ctx).is_err());
+
+            assert!(WasmValidator::new("(module)").is_err());
+        }
+
+        #[test]
+        fn test_wasm_validator_limits_tables() {
+            // validate 尝试把表扩展到上限之外
+            let growing = r#"(module
+                (memory (export "memory") 1)
+                (table 1 funcref)
+                (func (export "alloc") (param i32) (result i32) i32.const 0)
+                (func (export "validate") (param i32 i32) (result i32)
+                    (drop (table.grow (ref.null func) (i32.const 100000)))
+                    i32.const 0))"#;
+            let rules = Parser::parse_rules(r#"(n:int check("grow"))"#).unwrap();
+            let mut value = object(vec![("n", Value::Int(1))]);
+
+            let mut registry = ValidatorRegistry::new();
+            registry.register_wasm("grow", growing).unwrap();
+            let ctx = ValidationContext::new().registry(registry);
+            assert!(matches!(
+                validate_with_context(&mut value, &rules, &ctx),
+                Err(ValidationError::CheckFailed { message, .. }) if message.contains("trapped")
+            ));
+
+            let mut registry = ValidatorRegistry::new();
+            registry.register(
+                "grow",
+                WasmValidator::new(growing)
+                    .unwrap()
+                    .with_max_table_elements(200_000),
+            );
+            let ctx = ValidationContext::new().registry(registry);
+            assert!(validate_with_context(&mut value, &rules, &ctx).is_ok());
+
+            // 多于一个表的模块无法实例化
+            let tables = r#"(module
+                (memory (export "memory") 1)
+                (table 1 funcref)
+                (table 1 funcref)
+                (func (export "alloc") (param i32) (result i32) i32.const 0)
+                (func (export "validate") (param i32 i32) (result i32) i32.const 0))"#;
+            let mut registry = ValidatorRegistry::new();
+            registry.register_wasm("grow", tables).unwrap();
+            let ctx = ValidationContext::new().registry(registry);
+            assert!(validate_with_context(&mut value, &rules, &ctx).is_err());
+        }
+    }
+}
@@ -0,0 +1,180 @@
+#[cfg(test)]
+mod repl_tests {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    use zz_validator::{
+        ast::Value,
+        json::from_json_str,
+        parser::Parser,
+        validator::{ValidationError, ValidationOptions, validate_object_with},
+    };
+
+    const DSL: &str = r#"(
+        name:string[1,20],
+        age:int[0,150],
+        score?:float,
+        active:bool=true,
+        profile:object(city:string),
+        items:array<object(id:int)>
+    )"#;
+
+    #[test]
+    fn test_strict_rejects_unknown_fields() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let strict = ValidationOptions {
+            strict: true,
+            ..Default::default()
+        };
+
+        let mut value =
+            from_json_str(r#"{"name":"a","age":1,"profile":{"city":"x","zip":"1"},"items":[]}"#)
+                .unwrap();
+        assert_eq!(
+            validate_object_with(&mut value, &rules, &strict),
+            Err(ValidationError::UnknownField("profile.zip".into()))
+        );
+
+        let mut value = from_json_str(
+            r#"{"name":"a","age":1,"profile":{"city":"x"},"items":[{"id":1},{"id":2,"x":0}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            validate_object_with(&mut value, &rules, &strict),
+            Err(ValidationError::UnknownField("items[1].x".into()))
+        );
+
+        // 非 strict 模式忽略未声明字段
+        let mut value =
+            from_json_str(r#"{"name":"a","age":1,"profile":{"city":"x"},"items":[],"extra":1}"#)
+                .unwrap();
+        assert!(validate_object_with(&mut value, &rules, &ValidationOptions::default()).is_ok());
+    }
+
+    #[test]
+    fn test_coerce_converts_declared_types() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let coerce = ValidationOptions {
+            coerce: true,
+            ..Default::default()
+        };
+
+        let raw = r#"{"name":"a","age":"42","score":3,"active":"false","profile":{"city":"x"},"items":[{"id":"7"}]}"#;
+        let mut value = from_json_str(raw).unwrap();
+        validate_object_with(&mut value, &rules, &coerce).unwrap();
+        let obj = value.as_object().unwrap();
+        assert_eq!(obj["age"], Value::Int(42));
+        assert_eq!(obj["score"], Value::Float(3.0));
+        assert_eq!(obj["active"], Value::Bool(false));
+        assert_eq!(
+            obj["items"].as_array().unwrap()[0].as_object().unwrap()["id"],
+            Value::Int(7)
+        );
+
+        let mut value = from_json_str(raw).unwrap();
+        assert!(matches!(
+            validate_object_with(&mut value, &rules, &ValidationOptions::default()),
+            Err(ValidationError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_repl_session() {
+        let dir = std::env::temp_dir().join("zz_validator_repl_tests");
+        std::fs::create_dir_all(&dir).unwrap();
+        let user = dir.join("user.vdsl");
+        let other = dir.join("other.vdsl");
+        std::fs::write(&user, DSL).unwrap();
+        std::fs::write(&other, "(id:int)").unwrap();
+
+        let input = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n",
+            r#"{"name":"a","age":"5","profile":{"city":"x"},"items":[]}"#,
+            ":coerce on",
+            r#"{"name":"a","#,
+            r#" "age":"5","profile":{"city":"x"},"items":[]}"#,
+            ":schema missing.vdsl",
+            format_args!(":schema {}", other.display()),
+            ":strict",
+            r#"{"id":1,"name":"a"}"#,
+        );
+
+        let mut child = Command::new(env!("CARGO_BIN_EXE_validator"))
+            .args(["repl", "--schema", user.to_str().unwrap()])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), 7, "{}", stdout);
+
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["valid"], false);
+        assert_eq!(lines[1], "coerce: on");
+        let second: serde_json::Value = serde_json::from_str(lines[2]).unwrap();
+        assert_eq!(second["valid"], true);
+        assert_eq!(second["value"]["age"], 5);
+        assert_eq!(second["value"]["active"], true);
+        assert!(lines[3].starts_with("error: missing.vdsl"));
+        assert!(lines[4].starts_with("schema: "));
+        assert_eq!(lines[5], "strict: on");
+        let last: serde_json::Value = serde_json::from_str(lines[6]).unwrap();
+        assert_eq!(last["valid"], false);
+        assert_eq!(last["error"], "Unknown field name");
+    }
+
+    #[test]
+    fn test_repl_uses_schema_checks_and_metadata() {
+        let dir = std::env::temp_dir().join("zz_validator_repl_tests");
+        std::fs::create_dir_all(&dir).unwrap();
+        let empty = dir.join("empty_range.vdsl");
+        let meta = dir.join("meta.vdsl");
+        std::fs::write(&empty, "(a:int[10,1])").unwrap();
+        std::fs::write(&meta, "meta(float_epsilon=0.01) (x:float enum(0.3))").unwrap();
+
+        // 语法正确但规则冲突的 schema 同样被拒绝
+        for command in ["repl", "explain", "generate"] {
+            let output = Command::new(env!("CARGO_BIN_EXE_validator"))
+                .args([command, "--schema", empty.to_str().unwrap(), "a"])
+                .stdin(Stdio::null())
+                .output()
+                .unwrap();
+            assert!(!output.status.success(), "{}", command);
+            let stderr = String::from_utf8(output.stderr).unwrap();
+            assert!(stderr.contains("is empty"), "{}", stderr);
+        }
+
+        // meta(float_epsilon=...) 在交互模式中生效
+        let mut child = Command::new(env!("CARGO_BIN_EXE_validator"))
+            .args(["repl", "--schema", meta.to_str().unwrap()])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"{\"x\":0.305}\n{\"x\":0.35}\n")
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<serde_json::Value> = stdout
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2, "{}", stdout);
+        assert_eq!(lines[0]["valid"], true);
+        assert_eq!(lines[1]["valid"], false);
+    }
+}
@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod replay_tests {
+    use std::path::PathBuf;
+
+    use zz_validator::{json::from_json_str, replay::Corpus, schema::Schema};
+
+    const V1: &str = "(user:string[3,20], secret:password, card?:string[4,4], age?:int[0,150])";
+
+    fn corpus(name: &str) -> Corpus {
+        let dir: PathBuf = std::env::temp_dir()
+            .join("zz_validator_replay_tests")
+            .join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        Corpus::open(&dir).unwrap()
+    }
+
+    fn record(corpus: &Corpus, schema: &Schema, json: &str) -> Option<PathBuf> {
+        corpus
+            .record(schema, &from_json_str(json).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_record_failures_redacted() {
+        let corpus = corpus("record").redact(["card"]);
+        let schema = Schema::parse(V1).unwrap();
+
+        // 通过的载荷不记录
+        assert!(
+            record(
+                &corpus,
+                &schema,
+                r#"{"user": "alice", "secret": "hunter2hunter2"}"#
+            )
+            .is_none()
+        );
+
+        let path = record(
+            &corpus,
+            &schema,
+            r#"{"user": "al", "secret": "hunter2!", "card": "1234"}"#,
+        )
+        .unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(!text.contains("hunter2") && !text.contains("1234"));
+        let case: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(case["payload"]["secret"], "********");
+        assert_eq!(case["payload"]["card"], "****");
+        assert_eq!(case["payload"]["user"], "al");
+        assert_eq!(case["passed"], false);
+        assert_eq!(case["errors"].as_array().unwrap().len(), 1);
+
+        // 相同载荷只保存一份
+        let again = record(
+            &corpus,
+            &schema,
+            r#"{"user": "al", "secret": "hunter2!", "card": "1234"}"#,
+        );
+        assert_eq!(again, Some(path));
+        assert_eq!(std::fs::read_dir(corpus.dir()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_redact_keeps_byte_length() {
+        // 长度约束按字节计："€1" 占 4 字节，脱敏后仍满足 card:string[4,4]
+        let corpus = corpus("multibyte").redact(["card"]);
+        let schema = Schema::parse(V1).unwrap();
+        let path = record(
+            &corpus,
+            &schema,
+            r#"{"user": "al", "secret": "hunter2!", "card": "€1"}"#,
+        )
+        .unwrap();
+        let case: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(case["payload"]["card"], "****");
+        assert_eq!(case["errors"].as_array().unwrap().len(), 1);
+        schema.replay(&corpus).unwrap().assert_unchanged();
+    }
+
+    #[test]
+    fn test_redact_non_string_values() {
+        let corpus = corpus("non_string").redact(["pin", "profile.salary", "answers"]);
+        let schema = Schema::parse(
+            "(user:string[3,20], pin:int[0,9999], key?:token|int, \
+              profile?:object(salary:float, vip:bool), answers?:array<object(q:int, a:string)>)",
+        )
+        .unwrap();
+        let path = record(
+            &corpus,
+            &schema,
+            r#"{"user": "al", "pin": 4821, "key": 987654321,
+                "profile": {"salary": 123456.5, "vip": true},
+                "answers": [{"q": 7, "a": "my first pet"}]}"#,
+        )
+        .unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        for secret in ["4821", "987654321", "123456", "pet"] {
+            assert!(!text.contains(secret), "{} in {}", secret, text);
+        }
+        let case: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(case["payload"]["pin"], 0);
+        assert_eq!(case["payload"]["key"], 0);
+        assert_eq!(case["payload"]["profile"]["salary"], 0.0);
+        // 未指定的字段保持原样，数组逐项替换并保留键名
+        assert_eq!(case["payload"]["profile"]["vip"], true);
+        assert_eq!(
+            case["payload"]["answers"],
+            serde_json::json!([{"q": 0, "a": "************"}])
+        );
+        schema.replay(&corpus).unwrap().assert_unchanged();
+    }
+
+    #[test]
+    fn test_replay_detects_changes() {
+        let corpus = corpus("changes");
+        let v1 = Schema::parse(V1).unwrap();
+        record(&corpus, &v1, r#"{"user": "al", "secret": "12345678"}"#).unwrap();
+        record(
+            &corpus,
+            &v1,
+            r#"{"user": "alice", "secret": "12345678", "age": 200}"#,
+        )
+        .unwrap();
+
+        let report = v1.replay(&corpus).unwrap();
+        assert_eq!(report.cases, 2);
+        report.assert_unchanged();
+
+        // 放宽 user 的长度：第一条用例从失败变为通过
+        let v2 = Schema::parse(&V1.replace("user:string[3,20]", "user:string[2,20]")).unwrap();
+        let report = v2.replay(&corpus).unwrap();
+        assert_eq!(report.changes.len(), 1);
+        let change = &report.changes[0];
+        assert!(!change.expected.passed);
+        assert!(change.actual.passed);
+
+        // 确认是有意的变化后更新用例
+        corpus.accept(change, &v2).unwrap();
+        v2.replay(&corpus).unwrap().assert_unchanged();
+    }
+
+    #[test]
+    #[should_panic(expected = "1 of 1 replayed payloads changed outcome")]
+    fn test_assert_unchanged_panics() {
+        let corpus = corpus("panics");
+        let v1 = Schema::parse(V1).unwrap();
+        record(
+            &corpus,
+            &v1,
+            r#"{"user": "alice", "secret": "12345678", "age": 200}"#,
+        )
+        .unwrap();
+        let v2 = Schema::parse(&V1.replace("int[0,150]", "int[0,250]")).unwrap();
+        v2.replay(&corpus).unwrap().assert_unchanged();
+    }
+}
@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod schema_registry_tests {
+    use std::sync::Arc;
+
+    use zz_validator::{json::from_json_str, schema::SchemaRegistry};
+
+    const TENANT_A: &str = "(name: string[1, 32], age?: int[0, 150])";
+    const TENANT_B: &str = "(sku: string, qty: int[1, 100])";
+
+    #[test]
+    fn test_caches_by_dsl_text() {
+        let registry = SchemaRegistry::new();
+        assert!(registry.is_empty());
+        assert!(registry.get(TENANT_A).is_none());
+
+        let first = registry.get_or_compile(TENANT_A).unwrap();
+        let second = registry.get_or_compile(TENANT_A).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(Arc::ptr_eq(&first, &registry.get(TENANT_A).unwrap()));
+
+        // 文本不同即为不同的 schema
+        let other = registry.get_or_compile(TENANT_B).unwrap();
+        assert!(!Arc::ptr_eq(&first, &other));
+        assert_eq!(registry.len(), 2);
+
+        let mut value = from_json_str(r#"{"name": "ada", "age": 36}"#).unwrap();
+        assert!(first.validate(&mut value).is_ok());
+        assert!(other.validate(&mut value).is_err());
+
+        assert!(registry.remove(TENANT_A));
+        assert!(!registry.remove(TENANT_A));
+        let recompiled = registry.get_or_compile(TENANT_A).unwrap();
+        assert!(!Arc::ptr_eq(&first, &recompiled));
+
+        registry.clear();
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_errors_are_not_cached() {
+        let registry = SchemaRegistry::new();
+        let err = registry.get_or_compile("(name: string[5, 1])").unwrap_err();
+        assert!(!err.is_empty());
+        assert!(registry.is_empty());
+        assert!(registry.get_or_compile("(name: nope)").is_err());
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_capacity_evicts_least_recently_used() {
+        let registry = SchemaRegistry::with_capacity(2);
+        let a = registry.get_or_compile(TENANT_A).unwrap();
+        registry.get_or_compile(TENANT_B).unwrap();
+        // 使用 A 之后，B 成为最久未用的
+        registry.get(TENANT_A).unwrap();
+        registry.get_or_compile("(id: uuid)").unwrap();
+
+        assert_eq!(registry.len(), 2);
+        assert!(registry.get(TENANT_B).is_none());
+        assert!(Arc::ptr_eq(&a, &registry.get(TENANT_A).unwrap()));
+        assert!(registry.get("(id: uuid)").is_some());
+    }
+
+    #[test]
+    fn test_shared_across_threads() {
+        let registry = Arc::new(SchemaRegistry::new());
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let registry = Arc::clone(&registry);
+                std::thread::spawn(move || {
+                    let dsl = if i % 2 == 0 { TENANT_A } else { TENANT_B };
+                    registry.get_or_compile(dsl).unwrap()
+                })
+            })
+            .collect();
+        let schemas: Vec<_> = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+        assert_eq!(registry.len(), 2);
+        let cached = registry.get(TENANT_A).unwrap();
+        assert!(
+            schemas
+                .iter()
+                .step_by(2)
+                .all(|s| s.to_dsl() == cached.to_dsl())
+        );
+    }
+}
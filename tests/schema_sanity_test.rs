@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod schema_sanity_tests {
+    use zz_validator::{
+        ast::Value,
+        parser::{DuplicateFields, Parser},
+        schema::Schema,
+        token::tokenize,
+    };
+
+    fn error(dsl: &str) -> String {
+        Schema::parse(dsl)
+            .err()
+            .unwrap_or_else(|| panic!("{} compiled", dsl))
+    }
+
+    #[test]
+    fn test_empty_ranges() {
+        assert_eq!(
+            error("(a:int[10,1])"),
+            "Range [10, 1] of field 'a' is empty"
+        );
+        assert!(error("(a:float(1.5,1.5])").contains("is empty"));
+        assert!(error("(a:string[5,2])").contains("is empty"));
+        assert!(error(r#"(d:date["2024-02-01", "2024-01-01"])"#).contains("field 'd'"));
+        assert!(error("(a:array<int>[5,1])").contains("is empty"));
+        // 嵌套字段报告完整路径
+        assert!(
+            error("(p:object(q:array<object(n:int[3,2])>))").contains("field 'p.q[].n'"),
+            "{}",
+            error("(p:object(q:array<object(n:int[3,2])>))")
+        );
+
+        assert!(Schema::parse("(a:int[1,1], b:float[0,1), c:string[0,0])").is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_fields() {
+        let legacy = |dsl: &str| {
+            let mut parser =
+                Parser::new(tokenize(dsl).unwrap()).duplicate_fields(DuplicateFields::Warn);
+            parser.parse_program().unwrap()
+        };
+        assert_eq!(
+            Schema::compile(legacy("(a:int, a:string)")).unwrap_err(),
+            "Duplicate field 'a'"
+        );
+        assert_eq!(
+            Schema::compile(legacy("(u:array<object(n:int, n:int)>)")).unwrap_err(),
+            "Duplicate field 'u[].n'"
+        );
+        // 不做检查的 Schema::new 仍然接受
+        assert_eq!(Schema::new(legacy("(a:int, a:string)")).rules().len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_regex() {
+        assert!(error(r#"(a:string regex("["))"#).starts_with("Invalid regex for field 'a'"));
+        assert!(error(r#"(a:regex("("))"#).starts_with("Invalid regex for field 'a'"));
+        assert!(error(r#"(a:string warn(regex("[")))"#).starts_with("Invalid regex"));
+    }
+
+    #[test]
+    fn test_enum_values_match_type() {
+        assert_eq!(
+            error(r#"(a:int enum("x"))"#),
+            "Enum value \"x\" of field 'a' does not match type int"
+        );
+        assert!(Schema::parse(r#"(a:int|string enum(1, "x"))"#).is_ok());
+        assert!(Schema::parse(r#"(a:string enum("x", "y"))"#).is_ok());
+    }
+
+    #[test]
+    fn test_defaults_satisfy_constraints() {
+        assert!(
+            error("(a:int[0,10] = 20)")
+                .starts_with("Default 20 of field 'a' violates its own constraints")
+        );
+        assert!(error(r#"(a:string[3,5] = "ab")"#).contains("field 'a'"));
+        assert!(error(r#"(a:string enum("x") = "y")"#).ends_with("not in enum"));
+        assert!(Schema::parse(r#"(a:int[0,10] = 5, b:string enum("x") = "x")"#).is_ok());
+        // 依赖校验时刻的约束不在编译时判断
+        assert!(Schema::parse(r#"(d:date past = "2999-01-01")"#).is_ok());
+    }
+
+    #[test]
+    fn test_new_skips_checks() {
+        let rules = Parser::parse_rules("(a:int[10,1])").unwrap();
+        assert!(Schema::compile(rules.clone()).is_err());
+        let schema = Schema::new(rules);
+        let mut value = Value::Object([("a".to_string(), Value::Int(5))].into());
+        assert!(schema.validate(&mut value).is_err());
+    }
+}
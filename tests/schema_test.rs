@@ -0,0 +1,333 @@
+#[cfg(test)]
+mod schema_tests {
+    use zz_validator::{ast::Value, json::from_json_str, schema::Schema};
+
+    const DSL: &str = r#"(
+        name:string[1,20],
+        age:int=18,
+        score?:float,
+        active:bool=true,
+        id:uuid|ulid,
+        port:port,
+        profile:object(
+            contact:object(email:email, phone?:phone),
+            nickname?:string
+        ),
+        extra?:object(note:string),
+        tags:array<string>,
+        users?:array<object(name:string, admin?:bool)>
+    )"#;
+
+    fn payload() -> Value {
+        from_json_str(
+            r#"{
+                "name": "alice",
+                "id": "01ARZ3NDEKTSV4RRFFQ69G5FAV",
+                "port": 8080,
+                "profile": {"contact": {"email": "a@example.com"}, "nickname": ""},
+                "tags": ["x", "y"],
+                "users": [{"name": "bob", "admin": true}, {"name": "carol"}]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_typed_getters() {
+        let schema = Schema::parse(DSL).unwrap();
+        let mut value = payload();
+        let view = schema.typed_view(&mut value).unwrap();
+
+        assert_eq!(view.get_str("name"), "alice");
+        assert_eq!(view.get_int("age"), 18);
+        assert!(view.get_bool("active"));
+        assert_eq!(view.get_opt_float("score"), None);
+        assert_eq!(view.get_str("id"), "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        assert_eq!(view.get_str("profile.contact.email"), "a@example.com");
+        assert_eq!(view.get_opt_str("profile.contact.phone"), None);
+        // 可选字段允许留空字符串
+        assert_eq!(view.get_opt_str("profile.nickname"), Some(""));
+        assert_eq!(view.get_array("tags").len(), 2);
+        assert_eq!(view.get("port"), Some(&Value::Int(8080)));
+
+        let contact = view.get_object("profile").get_object("contact");
+        assert_eq!(contact.get_str("email"), "a@example.com");
+        assert!(view.get_opt_object("extra").is_none());
+        assert_eq!(view.get_opt_str("extra.note"), None);
+
+        let users = view.items("users");
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0].get_str("name"), "bob");
+        assert_eq!(users[0].get_opt_bool("admin"), Some(true));
+        assert_eq!(users[1].get_opt_bool("admin"), None);
+    }
+
+    #[test]
+    fn test_typed_view_requires_valid_value() {
+        let schema = Schema::parse(DSL).unwrap();
+        let mut value = payload();
+        if let Value::Object(obj) = &mut value {
+            obj.remove("name");
+        }
+        assert!(schema.typed_view(&mut value).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "score may be absent, use get_opt_float")]
+    fn test_required_getter_on_optional_field() {
+        let schema = Schema::parse(DSL).unwrap();
+        let mut value = payload();
+        schema.typed_view(&mut value).unwrap().get_float("score");
+    }
+
+    #[test]
+    #[should_panic(expected = "age is declared as int, not str")]
+    fn test_getter_type_mismatch() {
+        let schema = Schema::parse(DSL).unwrap();
+        let mut value = payload();
+        schema.typed_view(&mut value).unwrap().get_str("age");
+    }
+
+    #[test]
+    #[should_panic(expected = "port is declared as port, not str")]
+    fn test_port_may_be_int() {
+        let schema = Schema::parse(DSL).unwrap();
+        let mut value = payload();
+        schema.typed_view(&mut value).unwrap().get_str("port");
+    }
+
+    #[test]
+    #[should_panic(expected = "profile.missing is not declared in the schema")]
+    fn test_unknown_path() {
+        let schema = Schema::parse(DSL).unwrap();
+        let mut value = payload();
+        schema
+            .typed_view(&mut value)
+            .unwrap()
+            .get_opt_str("profile.missing");
+    }
+
+    #[test]
+    fn test_compiled_matches_validate_object() {
+        let enum_values: Vec<String> = (0..300).map(|i| format!("\"v{}\"", i)).collect();
+        let dsl = format!(
+            r#"(
+                code:string enum({}), level?:int enum(1, 2, 3),
+                name:string[2,4], n:int[1.5, 9.5], f?:float(0, 1],
+                id:uuid|int|bool, items?:array<object(tag:string[1,3])>[1,2]
+            )"#,
+            enum_values.join(", ")
+        );
+        let schema = Schema::parse(&dsl).unwrap();
+        for json in [
+            r#"{"code": "v299", "name": "abc", "n": 2, "id": true}"#,
+            r#"{"code": "v300", "name": "abc", "n": 2, "id": true}"#,
+            r#"{"code": "V1", "name": "abc", "n": 2, "id": true}"#,
+            r#"{"code": "v1", "level": 4, "name": "abc", "n": 2, "id": 1}"#,
+            r#"{"code": "v1", "name": "a", "n": 2, "id": 1}"#,
+            r#"{"code": "v1", "name": "abcde", "n": 2, "id": 1}"#,
+            r#"{"code": "v1", "name": "abc", "n": 1, "id": 1}"#,
+            r#"{"code": "v1", "name": "abc", "n": 10, "id": 1}"#,
+            r#"{"code": "v1", "name": "abc", "n": 2, "f": 0, "id": 1}"#,
+            r#"{"code": "v1", "name": "abc", "n": 2, "f": 1.0, "id": 1}"#,
+            r#"{"code": "v1", "name": "abc", "n": 2, "id": "x"}"#,
+            r#"{"code": "v1", "name": "abc", "n": 2, "id": 1, "items": []}"#,
+            r#"{"code": "v1", "name": "abc", "n": 2, "id": 1, "items": [{"tag": "abcd"}]}"#,
+            r#"{"code": "v1", "name": "abc", "n": 2, "id": 1, "items": [{"tag": "ab"}]}"#,
+        ] {
+            let mut compiled = from_json_str(json).unwrap();
+            let mut plain = compiled.clone();
+            assert_eq!(
+                schema.validate(&mut compiled),
+                zz_validator::validator::validate_object(&mut plain, schema.rules()),
+                "{}",
+                json
+            );
+        }
+
+        // 联合类型按成本重排尝试顺序，但报错仍按声明顺序列出
+        let mut value =
+            from_json_str(r#"{"code": "v1", "name": "abc", "n": 2, "id": "x"}"#).unwrap();
+        let err = schema.validate(&mut value).unwrap_err().to_string();
+        assert!(
+            err.contains("Uuid") && err.find("Uuid") < err.find("Int"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_compiled_string_bounds() {
+        use zz_validator::ast::{Constraint, Constraints};
+
+        // 字符串形式的长度边界在编译时解析，无法解析时仍在校验时报同样的错
+        let mut rules = zz_validator::parser::Parser::parse_rules("(a:string)").unwrap();
+        for (min, max) in [("1", "3"), ("x", "3")] {
+            rules[0].constraints = Some(Constraints {
+                items: vec![Constraint::Range {
+                    min: Value::String(min.into()),
+                    max: Value::String(max.into()),
+                    min_inclusive: true,
+                    max_inclusive: true,
+                }],
+            });
+            let schema = Schema::compile(rules.clone()).unwrap();
+            for a in ["ab", "abcd"] {
+                let mut compiled =
+                    Value::Object([("a".to_string(), Value::String(a.into()))].into());
+                let mut plain = compiled.clone();
+                assert_eq!(
+                    schema.validate(&mut compiled),
+                    zz_validator::validator::validate_object(&mut plain, &rules),
+                    "{} {} {}",
+                    min,
+                    max,
+                    a
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_subschema() {
+        let schema = Schema::parse(&format!("meta(name=\"User\") {}", DSL)).unwrap();
+
+        let contact = schema.subschema("profile.contact").unwrap();
+        let fields: Vec<&str> = contact.rules().iter().map(|r| r.field.as_str()).collect();
+        assert_eq!(fields, ["email", "phone"]);
+        assert_eq!(contact.metadata().name(), Some("User"));
+        let mut value = from_json_str(r#"{"email": "a@example.com"}"#).unwrap();
+        assert!(contact.validate(&mut value).is_ok());
+        let mut value = from_json_str(r#"{"email": "nope"}"#).unwrap();
+        assert_eq!(
+            contact.validate(&mut value).unwrap_err().field(),
+            Some("email")
+        );
+
+        let user = schema.subschema("users[]").unwrap();
+        let mut value = from_json_str(r#"{"name": "ada", "admin": true}"#).unwrap();
+        assert!(user.validate(&mut value).is_ok());
+        assert_eq!(
+            user.to_dsl(),
+            "meta(name=\"User\")\n(\n    name:string,\n    admin?:bool\n)"
+        );
+
+        for (path, message) in [
+            (
+                "profile.missing",
+                "'profile.missing' is not declared in the schema",
+            ),
+            ("name", "'name' is not declared as object(...)"),
+            (
+                "name.first",
+                "'name.first' does not go through object fields",
+            ),
+            ("profile[]", "'profile' is not an array in 'profile[]'"),
+            ("users", "'users' is not declared as object(...)"),
+        ] {
+            assert_eq!(schema.subschema(path).unwrap_err(), message);
+        }
+    }
+
+    #[test]
+    fn test_pick_and_omit() {
+        let schema = Schema::parse(
+            r#"meta(name="User") (
+                id:uuid,
+                username:string[3,20],
+                email:email,
+                password:string[8,64]
+            )"#,
+        )
+        .unwrap();
+        let fields =
+            |s: &Schema| -> Vec<String> { s.rules().iter().map(|r| r.field.to_string()).collect() };
+
+        // 保持声明顺序，而不是列出的顺序
+        let public = schema.pick(["email", "username"]).unwrap();
+        assert_eq!(fields(&public), ["username", "email"]);
+        assert_eq!(public.metadata().name(), Some("User"));
+        let mut value = from_json_str(r#"{"username": "ada", "email": "a@example.com"}"#).unwrap();
+        assert!(public.validate(&mut value).is_ok());
+
+        let create = schema.omit(["id"]).unwrap();
+        assert_eq!(fields(&create), ["username", "email", "password"]);
+
+        let update = schema.project("omit(password) pick(id, email)").unwrap();
+        assert_eq!(fields(&update), ["id", "email"]);
+
+        assert_eq!(
+            schema.pick(["nickname"]).unwrap_err(),
+            "'nickname' is not declared in the schema"
+        );
+        assert!(schema.project("pick(id").is_err());
+        assert!(schema.project("extend(id)").is_err());
+
+        // DSL 末尾的简写
+        let dsl =
+            Schema::parse("(id:uuid, username:string, password:string) omit(password)").unwrap();
+        assert_eq!(fields(&dsl), ["id", "username"]);
+        assert_eq!(dsl.to_dsl(), "(\n    id:uuid,\n    username:string\n)");
+        let err = Schema::parse("(id:uuid) pick(name)").unwrap_err();
+        assert!(
+            err.starts_with("'name' is not declared in the schema"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_as_partial_and_as_required() {
+        let schema = Schema::parse(
+            r#"(
+                name:string[1,20],
+                age:int=18,
+                nick?:string present,
+                legacy?:string absent,
+                profile:object(email:email, phone?:phone),
+                users:array<object(name:string, admin?:bool)>
+            )"#,
+        )
+        .unwrap();
+
+        let partial = schema.as_partial();
+        let mut value = from_json_str("{}").unwrap();
+        assert!(partial.validate(&mut value).is_ok());
+        // 不填入默认值
+        assert!(value.as_object().unwrap().get("age").is_none());
+        let mut value = from_json_str(r#"{"profile": {}, "users": [{}]}"#).unwrap();
+        assert!(partial.validate(&mut value).is_ok());
+        // 出现的字段仍按原约束校验
+        let mut value = from_json_str(r#"{"profile": {"email": "nope"}}"#).unwrap();
+        assert_eq!(
+            partial.validate(&mut value).unwrap_err().field(),
+            Some("profile.email")
+        );
+        let mut value = from_json_str(r#"{"legacy": "x"}"#).unwrap();
+        assert!(partial.validate(&mut value).is_err());
+
+        let required = schema.as_required();
+        let mut value = from_json_str(
+            r#"{"name": "ada", "nick": "a", "profile": {"email": "a@example.com"}, "users": []}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            required.validate(&mut value).unwrap_err().field(),
+            Some("profile.phone")
+        );
+        let mut value = from_json_str(
+            r#"{"name": "ada", "nick": "a", "profile": {"email": "a@example.com", "phone": "+8613800000000"}, "users": [{"name": "b"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            required.validate(&mut value).unwrap_err().field(),
+            Some("users[0].admin")
+        );
+        // absent 字段保持可选，默认值保留
+        let rules = required.rules();
+        assert!(!rules[3].required);
+        assert!(rules[1].default.is_some());
+
+        assert_eq!(required.as_partial().to_dsl(), partial.to_dsl(),);
+    }
+}
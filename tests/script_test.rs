@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod script_tests {
+    use zz_validator::{
+        ast::{Constraint, Value},
+        export::{to_dsl, to_json_schema},
+        import::from_json_schema,
+        parser::Parser,
+        validator::{ValidationError, validate_object},
+    };
+
+    const DSL: &str = r#"(
+        code:string script("value.len() % 2 == 0"),
+        start:int,
+        end:int script("value > parent.start"),
+        scores?:array<int script("value >= 0")>
+    )"#;
+
+    fn object(pairs: Vec<(&str, Value)>) -> Value {
+        Value::Object(pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+    }
+
+    #[test]
+    fn test_parse_and_export_script() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let items = &rules[0].constraints.as_ref().unwrap().items;
+        assert!(matches!(&items[0], Constraint::Script(s) if s == "value.len() % 2 == 0"));
+
+        let dsl = to_dsl(&rules);
+        assert!(dsl.contains(r#"end:int script("value > parent.start")"#));
+        assert_eq!(to_dsl(&Parser::parse_rules(&dsl).unwrap()), dsl);
+
+        let schema = to_json_schema(&rules);
+        assert_eq!(
+            schema["properties"]["end"]["x-vdsl-constraints"],
+            serde_json::json!([r#"script("value > parent.start")"#])
+        );
+        let imported = from_json_schema(&schema).unwrap();
+        let end = imported.iter().find(|r| r.field == "end").unwrap();
+        assert!(matches!(
+            &end.constraints.as_ref().unwrap().items[0],
+            Constraint::Script(s) if s == "value > parent.start"
+        ));
+
+        assert!(Parser::parse_rules("(a:string script(value))").is_err());
+    }
+
+    #[cfg(not(feature = "script"))]
+    #[test]
+    fn test_script_requires_feature() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut value = object(vec![
+            ("code", Value::String("ab".into())),
+            ("start", Value::Int(1)),
+            ("end", Value::Int(2)),
+        ]);
+        assert!(matches!(
+            validate_object(&mut value, &rules),
+            Err(ValidationError::ScriptFailed { message, .. }) if message.contains("feature")
+        ));
+    }
+
+    #[cfg(feature = "script")]
+    #[test]
+    fn test_script_constraints() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let valid = || {
+            object(vec![
+                ("code", Value::String("ab".into())),
+                ("start", Value::Int(1)),
+                ("end", Value::Int(2)),
+                ("scores", Value::Array(vec![Value::Int(0), Value::Int(3)])),
+            ])
+        };
+        assert!(validate_object(&mut valid(), &rules).is_ok());
+
+        let mut odd = valid();
+        odd.as_object_mut()
+            .unwrap()
+            .insert("code".into(), Value::String("abc".into()));
+        let err = validate_object(&mut odd, &rules).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::ScriptFailed {
+                field: "code".into(),
+                script: "value.len() % 2 == 0".into(),
+                message: "returned false".into(),
+            }
+        );
+
+        let mut backwards = valid();
+        backwards
+            .as_object_mut()
+            .unwrap()
+            .insert("end".into(), Value::Int(0));
+        assert!(matches!(
+            validate_object(&mut backwards, &rules),
+            Err(ValidationError::ScriptFailed { field, .. }) if field == "end"
+        ));
+
+        let mut negative = valid();
+        negative
+            .as_object_mut()
+            .unwrap()
+            .insert("scores".into(), Value::Array(vec![Value::Int(-1)]));
+        assert!(validate_object(&mut negative, &rules).is_err());
+    }
+
+    #[cfg(feature = "script")]
+    #[test]
+    fn test_script_errors() {
+        let mut value = object(vec![("n", Value::Int(1))]);
+
+        // 非 bool 结果与语法错误都视为失败
+        for source in ["value + 1", "value >", "let x = 1; x == 1"] {
+            let rules = Parser::parse_rules(&format!("(n:int script({:?}))", source)).unwrap();
+            assert!(matches!(
+                validate_object(&mut value, &rules),
+                Err(ValidationError::ScriptFailed { message, .. }) if message != "returned false"
+            ));
+        }
+
+        // 资源受限：超长字符串与数组会被拒绝
+        for source in [
+            r#""x".pad(2000000, "y").len() > 0"#,
+            "[].pad(100000, 0).len() > 0",
+        ] {
+            let rules = Parser::parse_rules(&format!("(n:int script({:?}))", source)).unwrap();
+            let err = validate_object(&mut value, &rules).unwrap_err();
+            assert!(
+                matches!(&err, ValidationError::ScriptFailed { message, .. } if message.contains("too large")),
+                "{}",
+                err
+            );
+        }
+    }
+}
@@ -0,0 +1,259 @@
+#[cfg(test)]
+mod sequence_tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use zz_validator::{
+        ast::{SequenceRule, Value},
+        json::from_json_str,
+        parser::Parser,
+        schema::Schema,
+        sequence::{MAX_RETRIES, MemoryStore, SequenceStore, SequenceValidator},
+        validator::ValidationError,
+    };
+
+    const ACCOUNT: &str = "(account: string, version: int, balance: int, at?: datetime)";
+
+    fn submit(validator: &SequenceValidator, json: &str) -> Result<(), ValidationError> {
+        validator.validate(&mut from_json_str(json).unwrap())
+    }
+
+    #[test]
+    fn test_parse_sequence_rules() {
+        let rules = Parser::parse_sequence_rules(
+            "monotonic(version) strictly_monotonic(meta.seq), delta(balance) <= 1000, abs_delta(x) < 5",
+        )
+        .unwrap();
+        assert_eq!(rules.len(), 4);
+        assert_eq!(rules[1].path(), "meta.seq");
+        let dsl: Vec<String> = rules.iter().map(SequenceRule::to_string).collect();
+        assert_eq!(
+            dsl,
+            [
+                "monotonic(version)",
+                "strictly_monotonic(meta.seq)",
+                "delta(balance) <= 1000",
+                "abs_delta(x) < 5",
+            ]
+        );
+
+        assert!(Parser::parse_sequence_rules("delta(balance)").is_err());
+        assert!(Parser::parse_sequence_rules("monotonic(items[].id)").is_err());
+        assert!(Parser::parse_sequence_rules("monotonic(version) pick(a)").is_err());
+    }
+
+    #[test]
+    fn test_monotonic_version() {
+        let validator = SequenceValidator::new(
+            Schema::parse(ACCOUNT).unwrap(),
+            "account",
+            "monotonic(version)",
+        )
+        .unwrap();
+        assert!(submit(&validator, r#"{"account":"a","version":1,"balance":0}"#).is_ok());
+        assert!(submit(&validator, r#"{"account":"a","version":1,"balance":5}"#).is_ok());
+        assert!(submit(&validator, r#"{"account":"a","version":3,"balance":5}"#).is_ok());
+
+        let err = submit(&validator, r#"{"account":"a","version":2,"balance":5}"#).unwrap_err();
+        assert_eq!(err.code(), "sequence_violation");
+        assert_eq!(err.field(), Some("version"));
+        assert_eq!(
+            err.to_string(),
+            "version changed from 3 to 2, violating monotonic(version)"
+        );
+
+        // 不同的键各自独立
+        assert!(submit(&validator, r#"{"account":"b","version":1,"balance":0}"#).is_ok());
+    }
+
+    #[test]
+    fn test_strict_and_delta() {
+        let validator = SequenceValidator::new(
+            Schema::parse(ACCOUNT).unwrap(),
+            "account",
+            "strictly_monotonic(version), delta(balance) <= 1000",
+        )
+        .unwrap();
+        assert!(submit(&validator, r#"{"account":"a","version":1,"balance":100}"#).is_ok());
+        // 版本号重复
+        assert!(submit(&validator, r#"{"account":"a","version":1,"balance":100}"#).is_err());
+        // 增量超过上限
+        let err = submit(&validator, r#"{"account":"a","version":2,"balance":1101}"#).unwrap_err();
+        assert!(matches!(err, ValidationError::SequenceViolation { .. }));
+        // 失败的提交不会记录，仍以 100 为基准
+        assert!(submit(&validator, r#"{"account":"a","version":2,"balance":1100}"#).is_ok());
+        // delta 允许减少
+        assert!(submit(&validator, r#"{"account":"a","version":3,"balance":-5000}"#).is_ok());
+    }
+
+    #[test]
+    fn test_abs_delta_and_datetime() {
+        let validator = SequenceValidator::new(
+            Schema::parse(ACCOUNT).unwrap(),
+            "account",
+            "abs_delta(balance) <= 10 monotonic(at) delta(at) <= 3600",
+        )
+        .unwrap();
+        assert!(
+            submit(
+                &validator,
+                r#"{"account":"a","version":1,"balance":0,"at":"2024-01-01T10:00:00Z"}"#
+            )
+            .is_ok()
+        );
+        assert!(
+            submit(
+                &validator,
+                r#"{"account":"a","version":1,"balance":-20,"at":"2024-01-01T10:30:00Z"}"#
+            )
+            .is_err()
+        );
+        assert!(
+            submit(
+                &validator,
+                r#"{"account":"a","version":1,"balance":-10,"at":"2024-01-01T10:30:00Z"}"#
+            )
+            .is_ok()
+        );
+        assert!(
+            submit(
+                &validator,
+                r#"{"account":"a","version":1,"balance":-10,"at":"2024-01-01T09:00:00Z"}"#
+            )
+            .is_err()
+        );
+        assert!(
+            submit(
+                &validator,
+                r#"{"account":"a","version":1,"balance":-10,"at":"2024-01-01T12:00:01Z"}"#
+            )
+            .is_err()
+        );
+        // 上一次或本次缺少字段时跳过该规则
+        assert!(submit(&validator, r#"{"account":"a","version":1,"balance":-10}"#).is_ok());
+    }
+
+    #[test]
+    fn test_schema_errors_come_first() {
+        let validator = SequenceValidator::new(
+            Schema::parse(ACCOUNT).unwrap(),
+            "account",
+            "monotonic(version)",
+        )
+        .unwrap();
+        let err = submit(&validator, r#"{"account":"a","version":"x","balance":0}"#).unwrap_err();
+        assert_ne!(err.code(), "sequence_violation");
+    }
+
+    #[test]
+    fn test_rejects_unknown_paths() {
+        let schema = Schema::parse(ACCOUNT).unwrap();
+        assert!(SequenceValidator::new(schema.clone(), "tenant", "monotonic(version)").is_err());
+        assert!(SequenceValidator::new(schema.clone(), "account", "monotonic(seq)").is_err());
+        let err = SequenceValidator::new(schema, "account", "delta(account) < 3").unwrap_err();
+        assert!(err.contains("numeric"), "{}", err);
+    }
+
+    /// 统计写入次数的存储
+    #[derive(Default)]
+    struct CountingStore {
+        inner: MemoryStore,
+        writes: Arc<AtomicUsize>,
+    }
+
+    impl SequenceStore for CountingStore {
+        fn get(&self, key: &str) -> Option<Value> {
+            self.inner.get(key)
+        }
+
+        fn compare_and_set(&self, key: &str, previous: Option<&Value>, value: Value) -> bool {
+            self.writes.fetch_add(1, Ordering::SeqCst);
+            self.inner.compare_and_set(key, previous, value)
+        }
+    }
+
+    #[test]
+    fn test_custom_store() {
+        let store = CountingStore::default();
+        let writes = store.writes.clone();
+        let validator = SequenceValidator::new(
+            Schema::parse(ACCOUNT).unwrap(),
+            "account",
+            "monotonic(version)",
+        )
+        .unwrap()
+        .store(store);
+        assert!(submit(&validator, r#"{"account":"a","version":1,"balance":0}"#).is_ok());
+        assert!(submit(&validator, r#"{"account":"a","version":0,"balance":0}"#).is_err());
+        assert_eq!(writes.load(Ordering::SeqCst), 1);
+    }
+
+    /// 每次写入前都有其他提交抢先的存储
+    #[derive(Default)]
+    struct ContendedStore {
+        attempts: Arc<AtomicUsize>,
+    }
+
+    impl SequenceStore for ContendedStore {
+        fn get(&self, _key: &str) -> Option<Value> {
+            None
+        }
+
+        fn compare_and_set(&self, _key: &str, _previous: Option<&Value>, _value: Value) -> bool {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            false
+        }
+    }
+
+    #[test]
+    fn test_gives_up_after_max_retries() {
+        let store = ContendedStore::default();
+        let attempts = store.attempts.clone();
+        let validator = SequenceValidator::new(
+            Schema::parse(ACCOUNT).unwrap(),
+            "account",
+            "monotonic(version)",
+        )
+        .unwrap()
+        .store(store);
+        let err = submit(&validator, r#"{"account":"a","version":1,"balance":0}"#).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::SequenceConflict {
+                field: "account".into(),
+                attempts: MAX_RETRIES,
+            }
+        );
+        assert_eq!(err.code(), "sequence_conflict");
+        assert_eq!(attempts.load(Ordering::SeqCst), MAX_RETRIES + 1);
+    }
+
+    #[test]
+    fn test_concurrent_submissions_stay_ordered() {
+        let validator = SequenceValidator::new(
+            Schema::parse(ACCOUNT).unwrap(),
+            "account",
+            "strictly_monotonic(version)",
+        )
+        .unwrap();
+        let accepted = AtomicUsize::new(0);
+        std::thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| {
+                    for version in 1..=50 {
+                        let json =
+                            format!(r#"{{"account":"a","version":{},"balance":0}}"#, version);
+                        if submit(&validator, &json).is_ok() {
+                            accepted.fetch_add(1, Ordering::SeqCst);
+                        }
+                    }
+                });
+            }
+        });
+        // 每个版本号最多被接受一次
+        assert!(accepted.load(Ordering::SeqCst) <= 50);
+        assert!(submit(&validator, r#"{"account":"a","version":50,"balance":0}"#).is_err());
+    }
+}
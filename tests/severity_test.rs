@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod severity_tests {
+    use zz_validator::{
+        ast::{Constraint, Severity},
+        json::from_json_str,
+        parser::Parser,
+        schema::Schema,
+        validator::{ValidationContext, ValidationError, validate_object, validate_with_report},
+    };
+
+    const DSL: &str = r#"(
+        name:string[1,20],
+        bio?:string warn([0,10]),
+        nick?:string deprecated("use handle"),
+        legacy_id?:int deprecated,
+        profile?:object(site?:string warn(regex("^https://"))),
+        tags?:array<string warn([1,3])>
+    )"#;
+
+    #[test]
+    fn test_parse_and_display() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let items = |i: usize| &rules[i].constraints.as_ref().unwrap().items;
+        assert_eq!(items(1)[0].to_string(), "warn([0, 10])");
+        assert_eq!(items(1)[0].severity(), Severity::Warning);
+        assert!(matches!(
+            &items(2)[0],
+            Constraint::Deprecated(Some(m)) if m == "use handle"
+        ));
+        assert_eq!(items(3)[0].to_string(), "deprecated");
+        assert_eq!(
+            rules[0].constraints.as_ref().unwrap().items[0].severity(),
+            Severity::Error
+        );
+
+        let exported = Schema::new(rules).to_dsl();
+        assert!(exported.contains(r#"nick?:string deprecated("use handle")"#));
+        assert_eq!(Schema::parse(&exported).unwrap().to_dsl(), exported);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (dsl, message) in [
+            ("(a:int warn(present))", "warn(...) cannot wrap present"),
+            (
+                "(a:int warn(warn([0,1])))",
+                "warn(...) cannot wrap warn([0, 1])",
+            ),
+            ("(a:int warn())", "Expected constraint in warn(...)"),
+            (
+                "(a:bool warn([0,1]))",
+                "cannot be applied to field 'a' of type bool",
+            ),
+            ("(a:int deprecated(1))", "Expected deprecation message"),
+        ] {
+            let err = Parser::parse_rules(dsl).unwrap_err();
+            assert!(err.contains(message), "{}: {}", dsl, err);
+        }
+    }
+
+    #[test]
+    fn test_warnings_are_collected_separately() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut value = from_json_str(
+            r#"{
+                "name": "ada",
+                "bio": "a very long biography",
+                "nick": "a",
+                "legacy_id": 7,
+                "profile": {"site": "http://example.com"},
+                "tags": ["ok", "toolong"]
+            }"#,
+        )
+        .unwrap();
+        let report = validate_with_report(&mut value, &rules, &ValidationContext::new()).unwrap();
+        let warnings: Vec<(&str, Option<&str>)> = report
+            .warnings
+            .iter()
+            .map(|w| (w.code(), w.field()))
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                ("out_of_range", Some("bio")),
+                ("deprecated", Some("nick")),
+                ("deprecated", Some("legacy_id")),
+                ("regex_mismatch", Some("profile.site")),
+                ("out_of_range", Some("tags[1]")),
+            ]
+        );
+        assert_eq!(
+            report.warnings[1].to_string(),
+            "nick is deprecated: use handle"
+        );
+
+        // 警告不影响校验结论
+        assert!(validate_object(&mut value, &rules).is_ok());
+    }
+
+    #[test]
+    fn test_errors_still_fail() {
+        let rules = Parser::parse_rules(DSL).unwrap();
+        let mut value = from_json_str(r#"{"name": "", "nick": "a"}"#).unwrap();
+        let err = validate_with_report(&mut value, &rules, &ValidationContext::new()).unwrap_err();
+        assert_eq!(err.code(), "out_of_range");
+
+        let mut value = from_json_str(r#"{"name": "ada"}"#).unwrap();
+        let report = validate_with_report(&mut value, &rules, &ValidationContext::new()).unwrap();
+        assert!(report.warnings.is_empty());
+        let deprecated = ValidationError::Deprecated {
+            field: "x".into(),
+            message: None,
+        };
+        assert_eq!(deprecated.to_string(), "x is deprecated");
+    }
+}
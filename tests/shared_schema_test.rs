@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod shared_schema_tests {
+    use std::sync::Arc;
+
+    use zz_validator::{
+        ast::Value,
+        json::from_json_str,
+        schema::{CompiledSchema, Schema},
+        validator::ValidationContext,
+    };
+
+    const DSL: &str = r#"(
+        email: email,
+        handle: regex("^[a-z][a-z0-9_]{2,15}$"),
+        age?: int[0, 150],
+        tags?: array<string[1, 16]>
+    )"#;
+
+    fn assert_shareable<T: Send + Sync + Clone + 'static>() {}
+
+    fn record(i: usize) -> Value {
+        // 每 3 条中有一条 handle 不合法
+        let handle = if i.is_multiple_of(3) {
+            format!("{}x", i)
+        } else {
+            format!("user_{}", i)
+        };
+        from_json_str(&format!(
+            r#"{{"email": "u{}@example.com", "handle": "{}", "age": {}, "tags": ["a", "b"]}}"#,
+            i,
+            handle,
+            i % 100
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_send_sync_and_cheap_clone() {
+        assert_shareable::<Schema>();
+        assert_shareable::<CompiledSchema>();
+        assert_shareable::<ValidationContext>();
+
+        let schema = Schema::parse(DSL).unwrap();
+        let clone = schema.clone();
+        // 克隆与原实例共享同一份规则
+        assert!(std::ptr::eq(schema.rules(), clone.rules()));
+        assert_eq!(schema.to_dsl(), clone.to_dsl());
+    }
+
+    #[test]
+    fn test_concurrent_validation() {
+        let schema = Schema::parse(DSL).unwrap();
+        let ctx = ValidationContext::new();
+        let failures: Vec<usize> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|t| {
+                    let (schema, ctx) = (&schema, &ctx);
+                    scope.spawn(move || {
+                        (0..300)
+                            .map(|i| record(t * 300 + i))
+                            .filter_map(|mut value| {
+                                schema.validate_with_context(&mut value, ctx).err()
+                            })
+                            .count()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(failures.iter().sum::<usize>(), 2400 / 3);
+    }
+
+    #[test]
+    fn test_app_state() {
+        // 模拟 web 框架的应用状态：每个处理线程持有一份克隆
+        #[derive(Clone)]
+        struct AppState {
+            schema: CompiledSchema,
+            shared: Arc<Schema>,
+        }
+
+        let state = AppState {
+            schema: Schema::parse(DSL).unwrap(),
+            shared: Arc::new(Schema::parse("(email: email)").unwrap()),
+        };
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let state = state.clone();
+                std::thread::spawn(move || {
+                    let mut value = record(t + 1);
+                    state.schema.validate(&mut value).is_ok()
+                        && state.shared.validate_shared(&value).is_ok()
+                })
+            })
+            .collect();
+        let results: Vec<bool> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(results, vec![true, true, false, true]);
+    }
+}
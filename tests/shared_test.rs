@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod shared_tests {
+    use std::{sync::Arc, thread};
+
+    use zz_validator::{
+        ast::Value,
+        json::from_json_str,
+        parser::Parser,
+        schema::Schema,
+        validator::{ValidationContext, ValidationOptions, validate_shared},
+    };
+
+    const DSL: &str = r#"(
+        name:string[1,10],
+        role:string enum("admin", "user")=user,
+        profile:object(lang:string=en, age?:int[0,150]),
+        items?:array<object(qty:int[1,9]=1, sku:string)>
+    )"#;
+
+    #[test]
+    fn test_validate_shared_does_not_write() {
+        let schema = Schema::parse(DSL).unwrap();
+        let shared = Arc::new(
+            from_json_str(r#"{"name": "ada", "profile": {}, "items": [{"sku": "a"}]}"#).unwrap(),
+        );
+        let before = (*shared).clone();
+        assert!(schema.validate_shared(&shared).is_ok());
+        assert_eq!(*shared, before);
+        assert!(shared.as_object().unwrap().get("role").is_none());
+
+        // 就地校验填入同样的默认值
+        let mut owned = (*shared).clone();
+        assert!(schema.validate(&mut owned).is_ok());
+        assert_eq!(
+            owned.as_object().unwrap().get("role"),
+            Some(&Value::String("user".into()))
+        );
+    }
+
+    #[test]
+    fn test_validate_shared_errors_match_in_place() {
+        let schema = Schema::parse(DSL).unwrap();
+        for json in [
+            r#"{"profile": {}}"#,
+            r#"{"name": "ada", "role": "root", "profile": {}}"#,
+            r#"{"name": "ada", "profile": {"age": 200}}"#,
+            r#"{"name": "ada", "profile": {}, "items": [{"sku": "a"}, {"qty": 0, "sku": "b"}]}"#,
+            r#"{"name": "ada", "profile": {}, "items": [{"qty": 2}]}"#,
+        ] {
+            let shared = from_json_str(json).unwrap();
+            let mut owned = shared.clone();
+            assert_eq!(
+                schema.validate_shared(&shared).unwrap_err(),
+                schema.validate(&mut owned).unwrap_err(),
+                "{}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_shared_options() {
+        let rules = Parser::parse_rules("(a:int)").unwrap();
+        let value = from_json_str(r#"{"a": 1, "b": 2}"#).unwrap();
+        let strict = ValidationContext::new().options(ValidationOptions {
+            strict: true,
+            ..Default::default()
+        });
+        assert!(validate_shared(&value, &rules, &ValidationContext::new()).is_ok());
+        assert_eq!(
+            validate_shared(&value, &rules, &strict).unwrap_err().code(),
+            "unknown_field"
+        );
+    }
+
+    #[test]
+    fn test_shared_subtrees_across_threads() {
+        let schema = Arc::new(Schema::parse(DSL).unwrap());
+        let profile = from_json_str(r#"{"lang": "de", "age": 30}"#).unwrap();
+        let payloads: Vec<Arc<Value>> = (0..8)
+            .map(|i| {
+                let mut map = zz_validator::ast::ObjectMap::new();
+                map.insert("name".into(), Value::String(format!("user{}", i)));
+                map.insert("profile".into(), profile.clone());
+                Arc::new(Value::Object(map))
+            })
+            .collect();
+        let handles: Vec<_> = payloads
+            .iter()
+            .map(|payload| {
+                let schema = Arc::clone(&schema);
+                let payload = Arc::clone(payload);
+                thread::spawn(move || schema.validate_shared(&payload).is_ok())
+            })
+            .collect();
+        assert!(handles.into_iter().all(|h| h.join().unwrap()));
+        assert!(payloads.iter().all(|p| Arc::strong_count(p) == 1));
+    }
+}
@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod signature_tests {
+    use zz_validator::{
+        ast::Value, json::from_json_str, schema::Schema, signature::hmac_sha256,
+        validator::ValidationContext,
+    };
+
+    const DSL: &str = r#"(
+        payload: object(id: int, amount: float, tags?: array<string>),
+        signature: string signed_with(hmac_sha256, key="ctx:webhook_secret", over="$.payload")
+    )"#;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn sign(payload: &str) -> String {
+        let canonical = from_json_str(payload).unwrap().to_canonical_json().unwrap();
+        hex(&hmac_sha256(b"s3cret", canonical.as_bytes()))
+    }
+
+    fn ctx() -> ValidationContext {
+        ValidationContext::new().var("webhook_secret", Value::String("s3cret".into()))
+    }
+
+    fn check(payload: &str, signature: &str) -> Result<(), String> {
+        let schema = Schema::parse(DSL).unwrap();
+        let mut value = from_json_str(&format!(
+            r#"{{"payload": {}, "signature": "{}"}}"#,
+            payload, signature
+        ))
+        .unwrap();
+        schema
+            .validate_with_context(&mut value, &ctx())
+            .map_err(|e| format!("{} {}", e.code(), e))
+    }
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 测试用例 1、2、6
+        assert_eq!(
+            hex(&hmac_sha256(&[0x0b; 20], b"Hi There")),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            hex(&hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn test_signed_payload() {
+        let signature = sign(r#"{"id": 1, "amount": 9.5}"#);
+        assert_eq!(check(r#"{"id": 1, "amount": 9.5}"#, &signature), Ok(()));
+        // 签名按规范化 JSON 计算，与键的顺序、空白和数字写法无关
+        assert_eq!(check(r#"{ "amount": 9.50, "id": 1 }"#, &signature), Ok(()));
+        assert_eq!(
+            check(
+                r#"{"id": 1, "amount": 9.5}"#,
+                &format!("sha256={}", signature.to_uppercase())
+            ),
+            Ok(())
+        );
+
+        let err = check(r#"{"id": 2, "amount": 9.5}"#, &signature).unwrap_err();
+        assert_eq!(
+            err,
+            "signature_mismatch signature is not a valid hmac_sha256 signature"
+        );
+        assert!(check(r#"{"id": 1, "amount": 9.5}"#, &signature[2..]).is_err());
+        assert!(check(r#"{"id": 1, "amount": 9.5}"#, "not hex").is_err());
+    }
+
+    #[test]
+    fn test_missing_subtree_and_key() {
+        let schema = Schema::parse(
+            r#"(payload?: object(id: int),
+                signature: string signed_with(hmac_sha256, key="ctx:webhook_secret", over="$.payload"))"#,
+        )
+        .unwrap();
+        let mut value = from_json_str(r#"{"signature": "00"}"#).unwrap();
+        let err = schema
+            .validate_with_context(&mut value, &ctx())
+            .unwrap_err();
+        assert_eq!(err.code(), "signature_mismatch");
+
+        let mut value = from_json_str(&format!(
+            r#"{{"payload": {{"id": 1, "amount": 1.0}}, "signature": "{}"}}"#,
+            sign(r#"{"id": 1, "amount": 1.0}"#)
+        ))
+        .unwrap();
+        let err = Schema::parse(DSL)
+            .unwrap()
+            .validate(&mut value)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Signing key ctx:webhook_secret is not set");
+    }
+
+    #[test]
+    fn test_env_key_and_json_bytes() {
+        // SAFETY: 只有这个测试读写该环境变量
+        unsafe { std::env::set_var("ZZ_SIGNATURE_TEST_SECRET", "s3cret") };
+        let schema = Schema::parse(
+            r#"(data: object(id: int),
+                sig: string signed_with(hmac_sha256, key="env:ZZ_SIGNATURE_TEST_SECRET", over="$.data"))"#,
+        )
+        .unwrap();
+        let signature = sign(r#"{"id": 7}"#);
+        let body = format!(r#"{{"data": {{"id": 7}}, "sig": "{}"}}"#, signature);
+        assert_eq!(schema.validate_json_bytes(body.as_bytes()), Ok(()));
+        let tampered = format!(r#"{{"data": {{"id": 8}}, "sig": "{}"}}"#, signature);
+        assert!(schema.validate_json_bytes(tampered.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        let schema = Schema::parse(DSL).unwrap();
+        let dsl = schema.to_dsl();
+        assert!(
+            dsl.contains(r#"signed_with(hmac_sha256, key="ctx:webhook_secret", over="$.payload")"#)
+        );
+        assert_eq!(Schema::parse(&dsl).unwrap().to_dsl(), dsl);
+
+        for (dsl, error) in [
+            (
+                r#"(s: string signed_with(md5, key="ctx:k", over="$.p"))"#,
+                "Expected signature algorithm",
+            ),
+            (
+                r#"(s: string signed_with(hmac_sha256, key="k", over="$.p"))"#,
+                "Invalid signing key",
+            ),
+            (
+                r#"(s: string signed_with(hmac_sha256, key="ctx:k", over="p"))"#,
+                "Invalid signed path",
+            ),
+            (
+                r#"(s: string signed_with(hmac_sha256, over="$.p"))"#,
+                "requires key",
+            ),
+            (
+                r#"(s: int signed_with(hmac_sha256, key="ctx:k", over="$.p"))"#,
+                "signed_with",
+            ),
+        ] {
+            let err = Schema::parse(dsl).unwrap_err();
+            assert!(err.contains(error), "{}: {}", dsl, err);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod slugify_tests {
+    use zz_validator::{
+        ast::Value,
+        json::from_json_str,
+        parser::Parser,
+        report::ChangeKind,
+        schema::Schema,
+        slug::{BasicTransliterator, Transliterator, slugify},
+        validator::{ValidationContext, ValidationError, ValidationOptions, validate_with_report},
+    };
+
+    #[test]
+    fn test_slugify() {
+        let t = BasicTransliterator;
+        assert_eq!(slugify("Crème Brûlée: 2 Ways!", &t), "creme-brulee-2-ways");
+        assert_eq!(slugify("  --Hello,   World--  ", &t), "hello-world");
+        assert_eq!(slugify("Straße & Œuvre", &t), "strasse-oeuvre");
+        assert_eq!(slugify("Привет мир", &t), "privet-mir");
+        assert_eq!(slugify("Αθήνα 2004", &t), "athina-2004");
+        assert_eq!(slugify("already-a-slug", &t), "already-a-slug");
+        // 无法音译的字符当作分隔符
+        assert_eq!(slugify("Rust 编程 Guide", &t), "rust-guide");
+        assert_eq!(slugify("！？", &t), "");
+    }
+
+    #[test]
+    fn test_slugify_before_validation() {
+        let rules =
+            Parser::parse_rules("(title:string, slug:slug slugify, tags?:array<slug slugify>)")
+                .unwrap();
+        let mut value = from_json_str(
+            r#"{"title": "Crème Brûlée", "slug": "Crème Brûlée", "tags": ["Déjà Vu", "ok"]}"#,
+        )
+        .unwrap();
+        let report = validate_with_report(&mut value, &rules, &ValidationContext::new()).unwrap();
+        assert_eq!(
+            value.as_object().unwrap().get("slug"),
+            Some(&Value::String("creme-brulee".into()))
+        );
+        assert_eq!(
+            value.as_object().unwrap().get("title"),
+            Some(&Value::String("Crème Brûlée".into()))
+        );
+        let paths: Vec<(&str, ChangeKind)> = report
+            .changes
+            .iter()
+            .map(|c| (c.path.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                ("slug", ChangeKind::Transform),
+                ("tags[0]", ChangeKind::Transform)
+            ]
+        );
+        assert_eq!(
+            report.changes[0].to_string(),
+            r#"slug: transform "Crème Brûlée" -> "creme-brulee""#
+        );
+
+        // 再次校验不会再有修改
+        let report = validate_with_report(&mut value, &rules, &ValidationContext::new()).unwrap();
+        assert!(report.changes.is_empty());
+
+        // 转换后仍要满足约束
+        let mut value = from_json_str(r#"{"title": "x", "slug": "？？"}"#).unwrap();
+        assert!(validate_with_report(&mut value, &rules, &ValidationContext::new()).is_err());
+    }
+
+    #[test]
+    fn test_frozen_and_dsl() {
+        let schema = Schema::parse("(slug:slug slugify)").unwrap();
+        assert_eq!(schema.to_dsl().trim(), "(\n    slug:slug slugify\n)");
+        assert!(Schema::parse("(n:int slugify)").is_err());
+        assert!(Schema::parse("(s:string warn(slugify))").is_err());
+
+        let ctx = ValidationContext::new().options(ValidationOptions {
+            frozen: true,
+            ..Default::default()
+        });
+        let mut value = from_json_str(r#"{"slug": "Hello World"}"#).unwrap();
+        assert!(matches!(
+            schema.validate_with_context(&mut value, &ctx),
+            Err(ValidationError::WouldMutate { .. })
+        ));
+        let outcomes = schema.validate_outcomes(&value);
+        assert!(outcomes.is_valid());
+    }
+
+    #[derive(Debug)]
+    struct Pinyin;
+
+    impl Transliterator for Pinyin {
+        fn transliterate(&self, input: &str) -> String {
+            input.replace("编程", " bian cheng ")
+        }
+    }
+
+    #[test]
+    fn test_custom_transliterator() {
+        let schema = Schema::parse("(slug:slug slugify)").unwrap();
+        let ctx = ValidationContext::new().transliterator(Pinyin);
+        let mut value = from_json_str(r#"{"slug": "Rust编程"}"#).unwrap();
+        schema.validate_with_context(&mut value, &ctx).unwrap();
+        assert_eq!(
+            value.as_object().unwrap().get("slug"),
+            Some(&Value::String("rust-bian-cheng".into()))
+        );
+    }
+}
@@ -0,0 +1,135 @@
+#[cfg(test)]
+mod stream_tests {
+    use std::io::{self, Read};
+
+    use zz_validator::{
+        ast::Value,
+        json::to_json_string,
+        schema::Schema,
+        stream::{StreamItem, StreamReport, StreamValidator},
+    };
+
+    fn schema() -> Schema {
+        Schema::parse(
+            "(id:int[1, 1000000], name:string[1,20], tags?:array<string>, status:string=\"new\")",
+        )
+        .unwrap()
+    }
+
+    /// 每次最多读出 3 个字节，模拟分块到达的大文件
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.0.len().min(buf.len()).min(3);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    fn failed_paths(item: &StreamItem) -> Vec<String> {
+        match &item.result {
+            Ok(()) => Vec::new(),
+            Err(report) => report.fields.iter().map(|f| f.path.clone()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_array() {
+        let schema = schema();
+        let input = br#" [
+            {"id": 1, "name": "a"},
+            {"id": 0, "name": "b"},
+            {"id": 3, "name": "", "tags": ["x"]},
+            {"id": 4, "name": "d", "tags": ["y"]}
+        ] "#;
+        let mut items = Vec::new();
+        let report = StreamValidator::new(&schema)
+            .validate_array(Trickle(input), |item| items.push(item))
+            .unwrap();
+        assert_eq!(
+            report,
+            StreamReport {
+                items: 4,
+                failed: 2
+            }
+        );
+        assert!(!report.is_valid());
+        assert_eq!(
+            items.iter().map(|i| i.index).collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+        assert_eq!(
+            items.iter().map(failed_paths).collect::<Vec<_>>(),
+            [
+                vec![],
+                vec!["id".to_string()],
+                vec!["name".to_string()],
+                vec![]
+            ]
+        );
+        // 默认值写回回调收到的记录
+        assert_eq!(
+            to_json_string(&items[0].value),
+            r#"{"id":1,"name":"a","status":"new"}"#
+        );
+    }
+
+    #[test]
+    fn test_array_errors_are_emitted_before_syntax_error() {
+        let schema = schema();
+        let mut seen = 0;
+        let err = StreamValidator::new(&schema)
+            .validate_array(&br#"[{"id": 1, "name": "a"}, {"id": 2,"#[..], |_| seen += 1)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        assert_eq!(seen, 1);
+
+        for input in [&br#"{"id": 1}"#[..], b"[1] [2]", b"[1,]"] {
+            let err = StreamValidator::new(&schema)
+                .validate_array(input, |_| {})
+                .unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+
+        let report = StreamValidator::new(&schema)
+            .validate_array(&b" [] "[..], |_| panic!("no items"))
+            .unwrap();
+        assert_eq!(report, StreamReport::default());
+        assert!(report.is_valid());
+    }
+
+    #[test]
+    fn test_ndjson() {
+        let schema = schema();
+        let input = "{\"id\": 1, \"name\": \"a\"}\n\n{\"id\": 2, \"name\": \"b\"\n  \r\n[1]\n{\"id\": 5, \"name\": \"e\"}";
+        let mut items = Vec::new();
+        let report = StreamValidator::new(&schema)
+            .validate_ndjson(input.as_bytes(), |item| items.push(item))
+            .unwrap();
+        assert_eq!(
+            report,
+            StreamReport {
+                items: 4,
+                failed: 2
+            }
+        );
+        assert_eq!(
+            items.iter().map(|i| i.index).collect::<Vec<_>>(),
+            [0, 2, 4, 5]
+        );
+        assert!(items[0].result.is_ok() && items[3].result.is_ok());
+        // 无法解析的行记为失败并保留原文，后续行照常校验
+        assert_eq!(
+            items[1].value,
+            Value::String("{\"id\": 2, \"name\": \"b\"".into())
+        );
+        assert!(
+            items[1].result.as_ref().unwrap_err().fields[0].failures[0]
+                .message
+                .starts_with("Invalid JSON")
+        );
+        assert!(items[2].result.is_err());
+    }
+}
@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod testing_tests {
+    use zz_validator::{
+        assert_errors_snapshot, assert_valid,
+        json::from_json_str,
+        schema::Schema,
+        testing::{check_snapshot, render},
+    };
+
+    const DSL: &str = r#"(name:string[1,10], age:int[0,150]=18, tags?:array<string>[0,3])"#;
+
+    fn schema() -> Schema {
+        Schema::parse(DSL).unwrap()
+    }
+
+    #[test]
+    fn test_assert_valid() {
+        let schema = schema();
+        assert_valid!(schema, r#"{"name": "alice"}"#);
+        assert_valid!(&schema, String::from(r#"{"name": "bob", "tags": ["a"]}"#));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected payload to be valid")]
+    fn test_assert_valid_panics() {
+        assert_valid!(schema(), r#"{"name": "", "age": 200}"#);
+    }
+
+    #[test]
+    fn test_errors_snapshot() {
+        assert_errors_snapshot!(schema(), r#"{"age": 200}"#, "missing_name");
+    }
+
+    #[test]
+    #[should_panic(expected = "expected payload to be invalid")]
+    fn test_errors_snapshot_requires_failure() {
+        assert_errors_snapshot!(schema(), r#"{"name": "alice"}"#, "never_written");
+    }
+
+    #[test]
+    fn test_render() {
+        let schema = schema();
+        let mut value = from_json_str(r#"{"name": "alice"}"#).unwrap();
+        let result = schema.validate(&mut value);
+        assert_eq!(
+            render(&result, &value),
+            "passed\n{\n  \"age\": 18,\n  \"name\": \"alice\"\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_snapshot_mismatch() {
+        let dir = std::env::temp_dir().join("zz_validator_testing_tests");
+        let path = dir.join("mismatch.snap");
+        let _ = std::fs::remove_file(&path);
+
+        // 不存在时写入
+        check_snapshot(&path, "failed\n- a\n");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "failed\n- a\n");
+        check_snapshot(&path, "failed\n- a\n");
+
+        let err = std::panic::catch_unwind(|| check_snapshot(&path, "failed\n- b\n")).unwrap_err();
+        let message = err.downcast_ref::<String>().unwrap();
+        assert!(message.contains("does not match"));
+        assert!(message.contains("--- expected\nfailed\n- a\n--- actual\nfailed\n- b\n"));
+    }
+}
@@ -0,0 +1,63 @@
+#[cfg(test)]
+#[cfg(feature = "timing")]
+mod timing_tests {
+    use zz_validator::{
+        json::from_json_str,
+        parser::Parser,
+        validator::{ValidationContext, validate_with_report},
+    };
+
+    #[test]
+    fn test_timings_per_constraint() {
+        let rules = Parser::parse_rules(
+            r#"(
+                name:string[1,20] regex("^[a-z]+$"),
+                role:string enum("admin", "user"),
+                users:array<object(id:int[1,100])>
+            )"#,
+        )
+        .unwrap();
+        let mut value =
+            from_json_str(r#"{"name": "alice", "role": "user", "users": [{"id": 1}, {"id": 2}]}"#)
+                .unwrap();
+        let report = validate_with_report(&mut value, &rules, &ValidationContext::new()).unwrap();
+
+        let entries: Vec<(&str, &str)> = report
+            .timings
+            .iter()
+            .map(|t| (t.path.as_str(), t.constraint.as_str()))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("name", "[1, 20]"),
+                ("name", r#"regex("^[a-z]+$")"#),
+                ("role", "enum"),
+                ("users[0].id", "[1, 100]"),
+                ("users[1].id", "[1, 100]"),
+            ]
+        );
+
+        let slowest = report.slowest(2);
+        assert_eq!(slowest.len(), 2);
+        assert!(slowest[0].elapsed >= slowest[1].elapsed);
+        assert!(report.slowest(10).len() == 5);
+    }
+
+    #[test]
+    fn test_each_validation_starts_fresh() {
+        let rules = Parser::parse_rules("(a:int[0,9])").unwrap();
+        for _ in 0..2 {
+            let mut value = from_json_str(r#"{"a": 1}"#).unwrap();
+            let report =
+                validate_with_report(&mut value, &rules, &ValidationContext::new()).unwrap();
+            assert_eq!(report.timings.len(), 1);
+        }
+        // 失败时没有报告，也不会留下记录影响下一次
+        let mut bad = from_json_str(r#"{"a": 10}"#).unwrap();
+        assert!(validate_with_report(&mut bad, &rules, &ValidationContext::new()).is_err());
+        let mut value = from_json_str(r#"{"a": 2}"#).unwrap();
+        let report = validate_with_report(&mut value, &rules, &ValidationContext::new()).unwrap();
+        assert_eq!(report.timings.len(), 1);
+    }
+}
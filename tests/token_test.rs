@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use zz_validator::token::{Token, tokenize};
+    use zz_validator::token::{Span, Token, tokenize, tokenize_with_spans};
     #[test]
     fn test_tokenize_full_dsl_with_scientific_range() {
         let dsl = r#"
@@ -255,4 +255,48 @@ mod tests {
             "Tokens did not match expected sequence"
         );
     }
+
+    #[test]
+    fn test_tokenize_with_spans() {
+        let spanned = tokenize_with_spans("(\n  名字:string,\n  age:int[0,1.5e2]\n)").unwrap();
+        let span_of = |token: Token| spanned.iter().find(|(t, _)| *t == token).unwrap().1;
+        assert_eq!(
+            span_of(Token::Ident("名字".into())),
+            Span {
+                start: 4,
+                end: 10,
+                line: 2,
+                column: 3
+            }
+        );
+        assert_eq!(
+            span_of(Token::Ident("age".into())),
+            Span {
+                start: 21,
+                end: 24,
+                line: 3,
+                column: 3
+            }
+        );
+        assert_eq!(span_of(Token::Number("1.5e2".into())).column, 13);
+        assert_eq!(spanned.last().unwrap().1.line, 4);
+
+        let tokens: Vec<Token> = spanned.into_iter().map(|(t, _)| t).collect();
+        assert_eq!(
+            tokens,
+            tokenize("(\n  名字:string,\n  age:int[0,1.5e2]\n)").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_tokenize_errors_have_location() {
+        assert_eq!(
+            tokenize("(a:int,\n b:@int)").unwrap_err(),
+            "Unexpected char '@' at line 2, column 4"
+        );
+        assert_eq!(
+            tokenize("(a:int[1.2.3])").unwrap_err(),
+            "Invalid number '1.2.3' at line 1, column 8"
+        );
+    }
 }
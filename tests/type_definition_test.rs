@@ -0,0 +1,173 @@
+#[cfg(test)]
+mod type_definition_tests {
+    use zz_validator::{
+        ast::{FieldType, find_rule},
+        json::from_json_str,
+        parser::Parser,
+        schema::Schema,
+    };
+
+    const ORDER: &str = "
+        address = object(street: string[1, 64], city: string, zip?: string)
+        line = object(sku: string, qty: int[1, 99], ship_to?: address)
+        (
+            shipping: address,
+            billing?: address,
+            lines: array<line>[1, 10]
+        )";
+
+    #[test]
+    fn test_references_expand_to_definition() {
+        let schema = Schema::parse(ORDER).unwrap();
+        let shipping = find_rule(schema.rules(), "shipping").unwrap();
+        assert_eq!(shipping.field_type, FieldType::Object);
+        assert_eq!(shipping.children.as_ref().unwrap().len(), 3);
+        assert!(!find_rule(schema.rules(), "billing").unwrap().required);
+
+        let mut value = from_json_str(
+            r#"{
+                "shipping": {"street": "1 Main St", "city": "Springfield"},
+                "lines": [
+                    {"sku": "A-1", "qty": 2},
+                    {"sku": "B-2", "qty": 1, "ship_to": {"street": "9 Elm", "city": "Shelbyville"}}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert!(schema.validate(&mut value).is_ok());
+
+        // 每处引用按定义各自校验，错误路径指向引用处
+        let mut value = from_json_str(
+            r#"{
+                "shipping": {"street": "1 Main St", "city": "Springfield"},
+                "billing": {"street": "", "city": "Springfield"},
+                "lines": [{"sku": "A-1", "qty": 1}]
+            }"#,
+        )
+        .unwrap();
+        let err = schema.validate(&mut value).unwrap_err();
+        assert_eq!(err.field(), Some("billing.street"));
+
+        let mut value = from_json_str(
+            r#"{
+                "shipping": {"street": "1 Main St", "city": "Springfield"},
+                "lines": [{"sku": "A-1", "qty": 1, "ship_to": {"street": "9 Elm"}}]
+            }"#,
+        )
+        .unwrap();
+        let err = schema.validate(&mut value).unwrap_err();
+        assert_eq!(err.field(), Some("lines[0].ship_to.city"));
+
+        // 展开后的 DSL 不再依赖定义
+        assert!(Schema::parse(&schema.to_dsl()).is_ok());
+    }
+
+    #[test]
+    fn test_definitions_with_constraints_and_unions() {
+        let schema = Schema::parse(
+            "code = string[2, 4] regex(\"^[A-Z]+$\")
+            id = string | int
+            (from: code, to: code[3, 3], ref: id)",
+        )
+        .unwrap();
+        let mut value = from_json_str(r#"{"from": "AB", "to": "XYZ", "ref": 7}"#).unwrap();
+        assert!(schema.validate(&mut value).is_ok());
+        let mut value = from_json_str(r#"{"from": "ab", "to": "XYZ", "ref": "r"}"#).unwrap();
+        assert_eq!(
+            schema.validate(&mut value).unwrap_err().field(),
+            Some("from")
+        );
+        // 引用处追加的约束同样生效
+        let mut value = from_json_str(r#"{"from": "AB", "to": "XY", "ref": "r"}"#).unwrap();
+        assert_eq!(schema.validate(&mut value).unwrap_err().field(), Some("to"));
+        let mut value = from_json_str(r#"{"from": "AB", "to": "XYZ", "ref": true}"#).unwrap();
+        assert!(schema.validate(&mut value).is_err());
+
+        // 定义末尾的开区间不会被当作字段列表
+        let schema = Schema::parse("ratio = float(0, 1) (share: ratio)").unwrap();
+        let mut value = from_json_str(r#"{"share": 0.5}"#).unwrap();
+        assert!(schema.validate(&mut value).is_ok());
+        let mut value = from_json_str(r#"{"share": 1.0}"#).unwrap();
+        assert!(schema.validate(&mut value).is_err());
+    }
+
+    #[test]
+    fn test_field_list_starting_with_currency() {
+        // 字段列表按 `名称:` / `名称?` 识别，第一个字段叫 currency 也不会被当作约束
+        for dsl in [
+            "code = string\n(currency: string, b: code)",
+            "code = string\n(currency?: string, b: code)",
+            "price = money\n(currency: string, total: price)",
+        ] {
+            let rules = Parser::parse_rules(dsl).unwrap_or_else(|e| panic!("{}: {}", dsl, e));
+            assert_eq!(rules[0].field, "currency", "{}", dsl);
+            assert_eq!(rules.len(), 2, "{}", dsl);
+        }
+
+        // 定义末尾的 `(currency ...)` 仍是 money 的约束
+        let schema = Schema::parse("price = money(currency=USD|EUR)\n(total: price)").unwrap();
+        let mut value =
+            from_json_str(r#"{"total": {"amount": "1.00", "currency": "USD"}}"#).unwrap();
+        assert!(schema.validate(&mut value).is_ok());
+        let mut value =
+            from_json_str(r#"{"total": {"amount": "1.00", "currency": "JPY"}}"#).unwrap();
+        assert!(schema.validate(&mut value).is_err());
+    }
+
+    #[test]
+    fn test_definitions_shadow_std_and_reach_macros() {
+        let rules = Parser::parse_rules(
+            "import std
+            Address = object(line: string)
+            macro located() = (home: Address)
+            (...located())",
+        )
+        .unwrap();
+        let home = &rules[0];
+        assert_eq!(home.field, "home");
+        assert_eq!(home.children.as_ref().unwrap()[0].field, "line");
+    }
+
+    #[test]
+    fn test_definition_errors() {
+        let err = |dsl: &str| Parser::parse_rules(dsl).unwrap_err();
+        assert!(
+            err("node = object(next?: node) (n: node)")
+                .starts_with("Type node cannot refer to itself")
+        );
+        assert!(err("a = object(x: b) b = int (v: a)").starts_with("Unknown type b"));
+        assert!(err("a = int a = string (v: a)").starts_with("Type a is already defined"));
+        assert!(
+            err("string = int (v: string)")
+                .starts_with("string is a built-in type and cannot be redefined")
+        );
+        assert!(
+            err("a = object(x: int) (v: a | string)")
+                .starts_with("Named type a cannot be used in a union")
+        );
+        assert!(err("(v: address)").starts_with("Unknown type address"));
+    }
+
+    /// 每层引用上一层两次，展开后的规则数按层数翻倍
+    fn doubling(levels: usize) -> String {
+        let mut dsl = String::from("t0 = object(x: int)\n");
+        for i in 1..=levels {
+            dsl += &format!("t{} = object(a: t{}, b: t{})\n", i, i - 1, i - 1);
+        }
+        format!("{}(v: t{})", dsl, levels)
+    }
+
+    #[test]
+    fn test_expansion_limit() {
+        assert!(Parser::parse_rules(&doubling(8)).is_ok());
+
+        let started = std::time::Instant::now();
+        let err = Parser::parse_rules(&doubling(40)).unwrap_err();
+        assert!(
+            err.starts_with("Schema expands to more than 100000 rules"),
+            "{}",
+            err
+        );
+        assert!(started.elapsed() < std::time::Duration::from_secs(2));
+    }
+}
@@ -1,13 +1,13 @@
-use std::collections::HashMap;
-use zz_validator::ast::FieldType;
+use zz_validator::ast::ObjectMap;
 use zz_validator::ast::Value;
+use zz_validator::ast::{ColorSpec, FieldType, HostnameSpec, UuidSpec};
 use zz_validator::parser::Parser;
 use zz_validator::validator::{ValidationError, validate, validate_type};
 
 #[test]
 fn test_validate_function_returns_option_value() {
     let rules = Parser::parse_rules("(name?:string)").unwrap();
-    let data = Value::Object(HashMap::new());
+    let data = Value::Object(ObjectMap::new());
     let result = validate(&data, &rules);
     assert!(result.is_some());
 }
@@ -16,7 +16,7 @@ fn test_validate_function_returns_option_value() {
 fn test_validate_function_returns_none_on_failure() {
     let rules = Parser::parse_rules("(name:string[3,5])").unwrap();
     let data = Value::Object({
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("name".to_string(), Value::String("toolongname".to_string()));
         map
     });
@@ -36,6 +36,15 @@ fn test_validate_type_phone_invalid() {
     assert!(validate_type(&val, &FieldType::Phone).is_err());
 }
 
+#[test]
+fn test_validate_type_urlencoded() {
+    // 连字符放在字符类末尾按字面匹配，`~-%` 会被当作起止颠倒的区间
+    let check = |s: &str| validate_type(&Value::String(s.to_string()), &FieldType::UrlEncoded);
+    assert!(check("a%20b-c_d.e~f").is_ok());
+    assert!(check("a b").is_err());
+    assert!(check("a&b").is_err());
+}
+
 #[test]
 fn test_validate_type_creditcard_valid() {
     let val = Value::String("4532015112830366".to_string());
@@ -317,7 +326,7 @@ fn test_validate_complex_object_with_validate_function() {
     "#;
     let rules = Parser::parse_rules(dsl).unwrap();
     let data = Value::Object({
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("name".to_string(), Value::String("john".to_string()));
         map.insert(
             "tags".to_string(),
@@ -333,9 +342,9 @@ fn test_validate_complex_object_with_validate_function() {
 fn test_validate_array_with_defaults() {
     let dsl = r#"(items:array<int[0,100]>)"#;
     let rules = Parser::parse_rules(dsl).unwrap();
-    let mut items = vec![Value::Int(1), Value::Int(2)];
+    let items = vec![Value::Int(1), Value::Int(2)];
     let data = Value::Object({
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("items".to_string(), Value::Array(items));
         map
     });
@@ -347,10 +356,10 @@ fn test_validate_array_with_defaults() {
 fn test_validate_nested_object_with_validate() {
     let dsl = r#"(user:object(name:string, age:int=0))"#;
     let rules = Parser::parse_rules(dsl).unwrap();
-    let mut user = HashMap::new();
+    let mut user = ObjectMap::new();
     user.insert("name".to_string(), Value::String("john".to_string()));
     let data = Value::Object({
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("user".to_string(), Value::Object(user));
         map
     });
@@ -370,7 +379,7 @@ fn test_validate_object_not_object() {
 fn test_validate_optional_field_with_validate() {
     let dsl = r#"(name?:string)"#;
     let rules = Parser::parse_rules(dsl).unwrap();
-    let data = Value::Object(HashMap::new());
+    let data = Value::Object(ObjectMap::new());
     let result = validate(&data, &rules);
     assert!(result.is_some());
 }
@@ -380,7 +389,7 @@ fn test_validate_enum_with_validate() {
     let dsl = r#"(status:string enum("active","inactive"))"#;
     let rules = Parser::parse_rules(dsl).unwrap();
     let data = Value::Object({
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("status".to_string(), Value::String("active".to_string()));
         map
     });
@@ -393,7 +402,7 @@ fn test_validate_union_type_with_validate() {
     let dsl = r#"(value:int|string)"#;
     let rules = Parser::parse_rules(dsl).unwrap();
     let data = Value::Object({
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("value".to_string(), Value::Int(42));
         map
     });
@@ -406,7 +415,7 @@ fn test_validate_regex_constraint_with_validate() {
     let dsl = r#"(code:string regex("^[A-Z]{3}$"))"#;
     let rules = Parser::parse_rules(dsl).unwrap();
     let data = Value::Object({
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("code".to_string(), Value::String("ABC".to_string()));
         map
     });
@@ -418,12 +427,7 @@ fn test_validate_regex_constraint_with_validate() {
 fn test_validate_type_custom_invalid_regex() {
     let val = Value::String("test".to_string());
     let result = validate_type(&val, &FieldType::Custom(r"[invalid".to_string()));
-    assert!(result.is_err());
-    if let Err(ValidationError::InvalidRegex(_)) = result {
-        assert!(true);
-    } else {
-        assert!(false);
-    }
+    assert!(matches!(result, Err(ValidationError::InvalidRegex(_))));
 }
 
 #[test]
@@ -440,7 +444,7 @@ fn test_validate_full_dsl_with_validate_function() {
     "#;
     let rules = Parser::parse_rules(dsl).unwrap();
     let data = Value::Object({
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert(
             "id".to_string(),
             Value::String("550e8400-e29b-41d4-a716-446655440000".to_string()),
@@ -466,7 +470,7 @@ fn test_validate_fills_defaults_with_validate() {
     let dsl = r#"(name:string, age:int=25, active:bool=false)"#;
     let rules = Parser::parse_rules(dsl).unwrap();
     let data = Value::Object({
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("name".to_string(), Value::String("john".to_string()));
         map
     });
@@ -475,7 +479,7 @@ fn test_validate_fills_defaults_with_validate() {
         assert_eq!(obj.get("age"), Some(&Value::Int(25)));
         assert_eq!(obj.get("active"), Some(&Value::Bool(false)));
     } else {
-        assert!(false);
+        panic!("Expected object");
     }
 }
 
@@ -496,3 +500,420 @@ fn test_validate_type_mac_invalid() {
     let val = Value::String("invalid-mac".to_string());
     assert!(validate_type(&val, &FieldType::Mac).is_err());
 }
+
+#[test]
+fn test_validate_type_ulid() {
+    let ok = [
+        "01ARZ3NDEKTSV4RRFFQ69G5FAV",
+        "7ZZZZZZZZZZZZZZZZZZZZZZZZZ",
+        "01arz3ndektsv4rrffq69g5fav",
+    ];
+    for s in ok {
+        assert!(
+            validate_type(&Value::String(s.into()), &FieldType::Ulid).is_ok(),
+            "{}",
+            s
+        );
+    }
+    // 溢出、非法字符 (I/L/O/U)、长度错误
+    let bad = [
+        "8ZZZZZZZZZZZZZZZZZZZZZZZZZ",
+        "01ARZ3NDEKTSV4RRFFQ69G5FAI",
+        "01ARZ3NDEKTSV4RRFFQ69G5FA",
+    ];
+    for s in bad {
+        assert!(
+            validate_type(&Value::String(s.into()), &FieldType::Ulid).is_err(),
+            "{}",
+            s
+        );
+    }
+}
+
+#[test]
+fn test_validate_type_ksuid() {
+    let ok = [
+        "0ujtsYcgvSTl8PAuAdqWYSMnLOv",
+        "aWgEPTl1tmebfsQzFP4bxwgy80V",
+        "000000000000000000000000000",
+    ];
+    for s in ok {
+        assert!(
+            validate_type(&Value::String(s.into()), &FieldType::Ksuid).is_ok(),
+            "{}",
+            s
+        );
+    }
+    let bad = [
+        "aWgEPTl1tmebfsQzFP4bxwgy80W",
+        "zzzzzzzzzzzzzzzzzzzzzzzzzzz",
+        "0ujtsYcgvSTl8PAuAdqWYSMnLO",
+        "0ujtsYcgvSTl8PAuAdqWYSMnLO-",
+    ];
+    for s in bad {
+        assert!(
+            validate_type(&Value::String(s.into()), &FieldType::Ksuid).is_err(),
+            "{}",
+            s
+        );
+    }
+}
+
+#[test]
+fn test_validate_type_nanoid() {
+    let default = Parser::parse_rules("(id:nanoid)").unwrap();
+    assert_eq!(default[0].field_type, FieldType::NanoId(21));
+    let short = Parser::parse_rules("(id:nanoid(8))").unwrap();
+    assert_eq!(short[0].field_type, FieldType::NanoId(8));
+    assert_eq!(short[0].field_type.to_string(), "nanoid(8)");
+    assert!(Parser::parse_rules("(id:nanoid(0))").is_err());
+    assert!(Parser::parse_rules("(id:nanoid(x))").is_err());
+
+    let check = |s: &str, len| validate_type(&Value::String(s.into()), &FieldType::NanoId(len));
+    assert!(check("V1StGXR8_Z5jdHi6B-myT", 21).is_ok());
+    assert!(check("V1StGXR8", 8).is_ok());
+    assert!(check("V1StGXR8_Z5jdHi6B-my", 21).is_err());
+    assert!(check("V1StGXR8_Z5jdHi6B-my+", 21).is_err());
+    assert!(validate_type(&Value::Int(1), &FieldType::NanoId(1)).is_err());
+}
+
+#[test]
+fn test_validate_type_uuid_forms() {
+    let check = |s: &str, t: &FieldType| validate_type(&Value::String(s.into()), t);
+    let v4 = "550e8400-e29b-41d4-a716-446655440000";
+    assert!(check(v4, &FieldType::Uuid).is_ok());
+    // 默认不再接受无连字符或混合形式
+    assert!(check("550e8400e29b41d4a716446655440000", &FieldType::Uuid).is_err());
+    assert!(check("550e8400-e29b41d4-a716-446655440000", &FieldType::Uuid).is_err());
+
+    let compact = FieldType::UuidOf(UuidSpec {
+        version: None,
+        compact: true,
+    });
+    assert!(check("550e8400e29b41d4a716446655440000", &compact).is_ok());
+    assert!(check(v4, &compact).is_ok());
+    assert!(check("550e8400-e29b41d4-a716-446655440000", &compact).is_err());
+}
+
+#[test]
+fn test_validate_type_uuid_version() {
+    let rules = Parser::parse_rules("(a:uuid(v4), b:uuid(v7, compact), c:uuid(compact))").unwrap();
+    let spec = |i: usize| match &rules[i].field_type {
+        FieldType::UuidOf(spec) => *spec,
+        other => panic!("unexpected {:?}", other),
+    };
+    assert_eq!(
+        spec(1),
+        UuidSpec {
+            version: Some(7),
+            compact: true
+        }
+    );
+    assert_eq!(rules[1].field_type.to_string(), "uuid(v7, compact)");
+    assert_eq!(spec(2).version, None);
+    for bad in [
+        "uuid()",
+        "uuid(v9)",
+        "uuid(v4, v7)",
+        "uuid(compact, compact)",
+        "uuid(v4 compact)",
+    ] {
+        assert!(
+            Parser::parse_rules(&format!("(a:{})", bad)).is_err(),
+            "{}",
+            bad
+        );
+    }
+
+    let check = |s: &str, i: usize| validate_type(&Value::String(s.into()), &rules[i].field_type);
+    assert!(check("550e8400-e29b-41d4-a716-446655440000", 0).is_ok());
+    // 版本号不符
+    assert!(check("018f3c2e-7b1a-7cde-8f00-1234567890ab", 0).is_err());
+    // 变体位不是 10xx
+    assert!(check("550e8400-e29b-41d4-c716-446655440000", 0).is_err());
+    assert!(check("018f3c2e-7b1a-7cde-8f00-1234567890ab", 1).is_ok());
+    assert!(check("018f3c2e7b1a7cde8f001234567890ab", 1).is_ok());
+    assert!(check("018f3c2e7b1a4cde8f001234567890ab", 1).is_err());
+}
+
+#[test]
+fn test_parse_color_spec() {
+    let rules =
+        Parser::parse_rules("(a:color(alpha), b:color(rgb, hsl, alpha), c:color(hex))").unwrap();
+    let spec = |i: usize| match &rules[i].field_type {
+        FieldType::ColorOf(spec) => *spec,
+        other => panic!("unexpected {:?}", other),
+    };
+    assert_eq!(
+        spec(0),
+        ColorSpec {
+            hex: true,
+            rgb: false,
+            hsl: false,
+            alpha: true
+        }
+    );
+    assert!(!spec(1).hex && spec(1).rgb && spec(1).hsl);
+    assert_eq!(rules[0].field_type.to_string(), "color(hex, alpha)");
+    assert_eq!(rules[1].field_type.to_string(), "color(rgb, hsl, alpha)");
+    for bad in [
+        "color()",
+        "color(cmyk)",
+        "color(hex, hex)",
+        "color(hex rgb)",
+    ] {
+        assert!(
+            Parser::parse_rules(&format!("(a:{})", bad)).is_err(),
+            "{}",
+            bad
+        );
+    }
+}
+
+#[test]
+fn test_validate_type_color_spec() {
+    let rules =
+        Parser::parse_rules("(a:color(hex, alpha), b:color(rgb, hsl), c:color(rgb, alpha))")
+            .unwrap();
+    let check = |s: &str, i: usize| validate_type(&Value::String(s.into()), &rules[i].field_type);
+
+    for ok in ["#fff", "#ffff", "#a0b1c2", "#a0b1c2ff"] {
+        assert!(check(ok, 0).is_ok(), "{}", ok);
+    }
+    assert!(check("#a0b1c", 0).is_err());
+    assert!(check("rgb(0, 0, 0)", 0).is_err());
+    // 原有 color 仍只接受 3/6 位
+    assert!(validate_type(&Value::String("#ffff".into()), &FieldType::Color).is_err());
+
+    for ok in [
+        "rgb(255, 0, 10)",
+        "RGB(0 128 255)",
+        "rgb(10%, 20%, 30%)",
+        "hsl(120, 50%, 25%)",
+        "hsl(90deg 10% 10%)",
+    ] {
+        assert!(check(ok, 1).is_ok(), "{}", ok);
+    }
+    for bad in [
+        "rgb(256, 0, 0)",
+        "rgb(10%, 20, 30)",
+        "rgb(0, 0)",
+        "rgba(0, 0, 0, 0.5)",
+        "rgb(0 0 0 / 50%)",
+        "hsl(120, 50, 25%)",
+        "#fff",
+        "rgb(0, 0, 0",
+    ] {
+        assert!(check(bad, 1).is_err(), "{}", bad);
+    }
+
+    for ok in [
+        "rgba(0, 0, 0, 0.5)",
+        "rgb(0 0 0 / 50%)",
+        "rgb(1, 2, 3, 1)",
+        "rgb(1, 2, 3)",
+    ] {
+        assert!(check(ok, 2).is_ok(), "{}", ok);
+    }
+    assert!(check("rgba(0, 0, 0)", 2).is_err());
+    assert!(check("rgba(0, 0, 0, 1.5)", 2).is_err());
+}
+
+#[test]
+fn test_validate_type_mac_formats() {
+    let ok = [
+        "aa:bb:cc:dd:ee:ff",
+        "AA-BB-CC-DD-EE-FF",
+        "00:1a:2b:ff:fe:3c:4d:5e",
+        "001a.2b3c.4d5e",
+        "001a.2bff.fe3c.4d5e",
+    ];
+    for s in ok {
+        assert!(
+            validate_type(&Value::String(s.into()), &FieldType::Mac).is_ok(),
+            "{}",
+            s
+        );
+    }
+    let bad = [
+        "aa:bb:cc:dd:ee",
+        "aa:bb:cc:dd:ee:ff:00",
+        "001a.2b3c",
+        "001a.2b3c.4d5",
+        "001a:2b3c:4d5e",
+    ];
+    for s in bad {
+        assert!(
+            validate_type(&Value::String(s.into()), &FieldType::Mac).is_err(),
+            "{}",
+            s
+        );
+    }
+}
+
+#[test]
+fn test_oui_constraint() {
+    let rules = Parser::parse_rules(r#"(nic:mac oui("AC:DE:48", "00-1A-2B"))"#).unwrap();
+    assert!(matches!(
+        &rules[0].constraints.as_ref().unwrap().items[0],
+        zz_validator::ast::Constraint::Oui(p) if p.len() == 2
+    ));
+    assert!(
+        zz_validator::export::to_dsl(&rules).contains(r#"nic:mac oui("AC:DE:48", "00-1A-2B")"#)
+    );
+    for bad in [
+        r#"oui()"#,
+        r#"oui("AC:DE")"#,
+        r#"oui(ACDE48)"#,
+        r#"oui("ZZ:DE:48")"#,
+    ] {
+        assert!(
+            Parser::parse_rules(&format!("(nic:mac {})", bad)).is_err(),
+            "{}",
+            bad
+        );
+    }
+
+    let check = |mac: &str| {
+        let mut value = Value::Object(ObjectMap::from([(
+            "nic".to_string(),
+            Value::String(mac.into()),
+        )]));
+        zz_validator::validator::validate_object(&mut value, &rules)
+    };
+    assert!(check("ac:de:48:00:11:22").is_ok());
+    assert!(check("001a.2b3c.4d5e").is_ok());
+    assert!(check("00:1a:2b:ff:fe:3c:4d:5e").is_ok());
+    let err = check("ac:de:49:00:11:22").unwrap_err();
+    assert_eq!(
+        err,
+        ValidationError::OuiMismatch {
+            field: "nic".into(),
+            oui: "ACDE49".into(),
+        }
+    );
+    assert_eq!(err.to_string(), "nic vendor prefix ACDE49 is not allowed");
+}
+
+#[test]
+fn test_validate_type_port_int_and_bounds() {
+    assert!(validate_type(&Value::Int(443), &FieldType::Port).is_ok());
+    assert!(validate_type(&Value::Int(65535), &FieldType::Port).is_ok());
+    for bad in [
+        Value::Int(0),
+        Value::Int(65536),
+        Value::Int(-1),
+        Value::Float(80.0),
+    ] {
+        assert!(validate_type(&bad, &FieldType::Port).is_err(), "{:?}", bad);
+    }
+    for bad in ["0", "", "+80", "8080 ", "123456"] {
+        assert!(
+            validate_type(&Value::String(bad.into()), &FieldType::Port).is_err(),
+            "{}",
+            bad
+        );
+    }
+}
+
+#[test]
+fn test_validate_type_socketaddr() {
+    let rules = Parser::parse_rules("(a:socketaddr, b:ip_port)").unwrap();
+    assert_eq!(rules[0].field_type, FieldType::SocketAddr);
+    assert_eq!(rules[1].field_type.to_string(), "ip_port");
+
+    let check = |s: &str, t: &FieldType| validate_type(&Value::String(s.into()), t);
+    for ok in [
+        "localhost:8080",
+        "db.example.com:5432",
+        "127.0.0.1:80",
+        "[::1]:443",
+        "[2001:db8::1]:65535",
+    ] {
+        assert!(check(ok, &FieldType::SocketAddr).is_ok(), "{}", ok);
+    }
+    for bad in [
+        "localhost",
+        "localhost:0",
+        "::1:443",
+        "[::1]443",
+        "[localhost]:80",
+        "host name:80",
+        "300:80",
+        ":80",
+    ] {
+        assert!(check(bad, &FieldType::SocketAddr).is_err(), "{}", bad);
+    }
+
+    assert!(check("10.0.0.1:8080", &FieldType::IpPort).is_ok());
+    assert!(check("[fe80::1]:22", &FieldType::IpPort).is_ok());
+    assert!(check("localhost:8080", &FieldType::IpPort).is_err());
+    assert!(check("10.0.0.256:8080", &FieldType::IpPort).is_err());
+}
+
+#[test]
+fn test_validate_type_hostname_rfc1123() {
+    let check = |s: &str, t: &FieldType| validate_type(&Value::String(s.into()), t);
+    for ok in [
+        "example.com",
+        "a.b.c.example.org",
+        "xn--bcher-kva.example",
+        "3com.com",
+        "host-1.internal.io",
+    ] {
+        assert!(check(ok, &FieldType::Hostname).is_ok(), "{}", ok);
+    }
+    let long_label = format!("{}.com", "a".repeat(64));
+    for bad in [
+        "localhost",
+        "example.com.",
+        "-bad.com",
+        "bad-.com",
+        "a..com",
+        "under_score.com",
+        "1.2.3.4",
+        long_label.as_str(),
+    ] {
+        assert!(check(bad, &FieldType::Hostname).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn test_hostname_options() {
+    let rules = Parser::parse_rules(
+        "(a:hostname(allow_single_label), b:hostname(allow_trailing_dot, allow_single_label))",
+    )
+    .unwrap();
+    assert_eq!(
+        rules[0].field_type,
+        FieldType::HostnameOf(HostnameSpec {
+            allow_single_label: true,
+            allow_trailing_dot: false,
+        })
+    );
+    assert_eq!(
+        rules[1].field_type.to_string(),
+        "hostname(allow_single_label, allow_trailing_dot)"
+    );
+    for bad in [
+        "hostname()",
+        "hostname(fqdn)",
+        "hostname(allow_single_label, allow_single_label)",
+    ] {
+        assert!(
+            Parser::parse_rules(&format!("(a:{})", bad)).is_err(),
+            "{}",
+            bad
+        );
+    }
+
+    let check = |s: &str, t: &FieldType| validate_type(&Value::String(s.into()), t);
+    let single = &rules[0].field_type;
+    assert!(check("localhost", single).is_ok());
+    assert!(check("example.com.", single).is_err());
+    let both = &rules[1].field_type;
+    assert!(check("example.com.", both).is_ok());
+    assert!(check("localhost.", both).is_ok());
+    assert!(check(".", both).is_err());
+    assert!(check("1234", both).is_err());
+}
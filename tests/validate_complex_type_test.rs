@@ -38,6 +38,6 @@ mod validator_complex_type_tests {
 
         // 4. 验证可选字段 phone?
         assert_eq!(contact_children[1].field, "phone");
-        assert_eq!(contact_children[1].required, false);
+        assert!(!contact_children[1].required);
     }
 }
@@ -0,0 +1,135 @@
+#[cfg(test)]
+mod validation_error_tests {
+    use zz_validator::{
+        json::from_json_str,
+        schema::Schema,
+        validator::{ValidationContext, ValidationError, ValidationOptions},
+    };
+
+    fn error(dsl: &str, json: &str) -> ValidationError {
+        let mut value = from_json_str(json).unwrap();
+        Schema::parse(dsl)
+            .unwrap()
+            .validate(&mut value)
+            .unwrap_err()
+    }
+
+    #[test]
+    fn test_code_field_value() {
+        let e = error("(age:int[0,150])", r#"{"age": 200}"#);
+        assert_eq!(e.code(), "out_of_range");
+        assert_eq!(e.field(), Some("age"));
+        assert_eq!(e.value(), Some("200"));
+
+        let e = error("(name:string)", "{}");
+        assert_eq!(e.code(), "missing_field");
+        assert_eq!(e.field(), Some("name"));
+        assert_eq!(e.value(), None);
+
+        let e = error(r#"(role:string enum("a", "b"))"#, r#"{"role": "c"}"#);
+        assert_eq!((e.code(), e.field()), ("enum_mismatch", Some("role")));
+
+        let e = error(r#"(code:string regex("^[0-9]+$"))"#, r#"{"code": "x"}"#);
+        assert_eq!((e.code(), e.value()), ("regex_mismatch", None));
+
+        let e = error("(id?:int absent)", r#"{"id": 1}"#);
+        assert_eq!((e.code(), e.field()), ("forbidden_field", Some("id")));
+
+        let e = ValidationError::Custom("boom".into());
+        assert_eq!((e.code(), e.field()), ("custom", None));
+    }
+
+    #[test]
+    fn test_field_errors() {
+        let schema = Schema::parse(r#"(code:string[5,10] regex("^[0-9]+$"))"#).unwrap();
+        let ctx = ValidationContext::new().options(ValidationOptions {
+            exhaustive: true,
+            ..Default::default()
+        });
+        let mut value = from_json_str(r#"{"code": "ab"}"#).unwrap();
+        let e = schema.validate_with_context(&mut value, &ctx).unwrap_err();
+        assert_eq!(e.code(), "field_errors");
+        assert_eq!(e.field(), Some("code"));
+        let ValidationError::FieldErrors { errors, .. } = e else {
+            unreachable!()
+        };
+        let codes: Vec<&str> = errors.iter().map(|e| e.code()).collect();
+        assert_eq!(codes, ["out_of_range", "regex_mismatch"]);
+    }
+
+    const NESTED: &str = r#"(
+        profile:object(contact:object(email:email, phones?:array<string[3,10]>)),
+        tags?:array<int[0,9]>,
+        users?:array<object(name:string[1,5])>
+    )"#;
+
+    fn nested(json: &str) -> ValidationError {
+        let schema = Schema::parse(NESTED).unwrap();
+        let streamed = schema.validate_json_bytes(json.as_bytes()).unwrap_err();
+        let mut value = from_json_str(json).unwrap();
+        let parsed = schema.validate(&mut value).unwrap_err();
+        assert_eq!(streamed, parsed, "{}", json);
+        parsed
+    }
+
+    #[test]
+    fn test_nested_paths() {
+        let e = nested(r#"{"profile": {"contact": {"email": "x"}}}"#);
+        assert_eq!(e.field(), Some("profile.contact.email"));
+        assert_eq!(e.pointer().as_deref(), Some("/profile/contact/email"));
+
+        let e = nested(r#"{"profile": {"contact": {}}}"#);
+        assert_eq!(
+            e,
+            ValidationError::MissingField("profile.contact.email".into())
+        );
+
+        let e = nested(r#"{"profile": {"contact": {"email": "a@b.co"}}, "tags": [1, 2, 3, 10]}"#);
+        assert_eq!(e.field(), Some("tags[3]"));
+        assert_eq!(e.pointer().as_deref(), Some("/tags/3"));
+        assert_eq!(e.to_string(), "tags[3] value 10 out of range [0, 9]");
+
+        let e = nested(r#"{"profile": {"contact": {"email": "a@b.co", "phones": ["123", "1"]}}}"#);
+        assert_eq!(e.field(), Some("profile.contact.phones[1]"));
+        assert_eq!(e.pointer().as_deref(), Some("/profile/contact/phones/1"));
+        assert_eq!(
+            e.to_string(),
+            "profile.contact.phones[1] length 1 out of range [3, 10]"
+        );
+
+        let e = nested(
+            r#"{"profile": {"contact": {"email": "a@b.co"}}, "users": [{"name": "a"}, {}]}"#,
+        );
+        assert_eq!(e, ValidationError::MissingField("users[1].name".into()));
+        assert_eq!(e.pointer().as_deref(), Some("/users/1/name"));
+    }
+
+    #[test]
+    fn test_element_length_errors() {
+        let check = |dsl: &str, json: &str| {
+            let mut value = from_json_str(json).unwrap();
+            Schema::parse(dsl)
+                .unwrap()
+                .validate(&mut value)
+                .unwrap_err()
+        };
+        let e = check("(tags:array<string[1,10]>)", r#"{"tags": ["a", "b", ""]}"#);
+        assert_eq!(e.to_string(), "tags[2] length 0 out of range [1, 10]");
+        assert!(matches!(
+            e,
+            ValidationError::RangeError { length: true, .. }
+        ));
+
+        let e = check("(m:array<array<int>[1,2]>)", r#"{"m": [[1], [1, 2, 3]]}"#);
+        assert_eq!(e.to_string(), "m[1] length 3 out of range [1, 2]");
+        let e = check("(m:array<array<int[0,5]>>)", r#"{"m": [[1], [2, 9]]}"#);
+        assert_eq!(e.to_string(), "m[1][1] value 9 out of range [0, 5]");
+    }
+
+    #[test]
+    fn test_pointer_escaping() {
+        let e = ValidationError::MissingField("a/b.c~d".into());
+        assert_eq!(e.pointer().as_deref(), Some("/a~1b/c~0d"));
+        assert_eq!(ValidationError::Custom("x".into()).pointer(), None);
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod range_error_tests {
-    use std::collections::HashMap;
+    use zz_validator::ast::ObjectMap;
 
     use zz_validator::{
         ast::{Constraint, Constraints, FieldRule, Value},
@@ -19,7 +19,7 @@ mod range_error_tests {
     #[test]
     fn test_err_range_int_out_of_bounds() {
         let rule = parse_rule("count:int[1, 10]");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("count".to_string(), Value::Int(11)); // 越过最大值
         let mut data = Value::Object(map);
 
@@ -32,7 +32,7 @@ mod range_error_tests {
     fn test_err_int_min_rounding() {
         // 规则：int[1.2, 10] -> 实际有效最小值是 2
         let rule = parse_rule("count:int[1.2, 10]");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
 
         // 错误案例：输入 1。虽然 1 > 1.2 是假的，
         // 但根据逻辑，min_v 变成了 2.0，1 < 2.0 触发错误。
@@ -47,7 +47,7 @@ mod range_error_tests {
     fn test_err_int_max_rounding() {
         // 规则：int[0, 5.8] -> 实际有效最大值是 5
         let rule = parse_rule("count:int[0, 5.8]");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
 
         // 错误案例：输入 6。
         // 根据逻辑，max_v 变成了 5.0，6 > 5.0 触发错误。
@@ -64,11 +64,11 @@ mod range_error_tests {
         let rule = parse_rule("count:int[1.2, 5.8]");
 
         // 2 应该通过 (ceil(1.2) = 2)
-        let mut data2 = Value::Object(HashMap::from([("count".into(), Value::Int(2))]));
+        let mut data2 = Value::Object(ObjectMap::from([("count".into(), Value::Int(2))]));
         assert!(validate_field(&mut data2, &rule).is_ok());
 
         // 5 应该通过 (floor(5.8) = 5)
-        let mut data5 = Value::Object(HashMap::from([("count".into(), Value::Int(5))]));
+        let mut data5 = Value::Object(ObjectMap::from([("count".into(), Value::Int(5))]));
         assert!(validate_field(&mut data5, &rule).is_ok());
     }
 
@@ -76,7 +76,7 @@ mod range_error_tests {
     #[test]
     fn test_err_range_string_length_out_of_bounds() {
         let rule = parse_rule("name:string[2, 4]");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("name".to_string(), Value::String("a".into())); // 太短
         let mut data = Value::Object(map);
 
@@ -99,7 +99,7 @@ mod range_error_tests {
             }],
         });
 
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("count".to_string(), Value::Int(5));
         let mut data = Value::Object(map);
 
@@ -131,7 +131,7 @@ mod range_error_tests {
             }],
         });
 
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("active".to_string(), Value::Bool(true));
         let mut data = Value::Object(map);
 
@@ -159,7 +159,7 @@ mod range_error_tests {
             }],
         });
 
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("name".to_string(), Value::String("test".into()));
         let mut data = Value::Object(map);
 
@@ -176,7 +176,7 @@ mod range_error_tests {
     fn test_err_int_max_inclusive() {
         // 规则：count 必须在 [1, 10] 之间
         let rule = parse_rule("count:int[1, 10]");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
 
         // 错误案例：超过最大值 10
         map.insert("count".to_string(), Value::Int(11));
@@ -195,7 +195,7 @@ mod range_error_tests {
     fn test_err_int_max_exclusive() {
         // 规则：count 必须在 [1, 10) 之间 (即最大只能是 9)
         let rule = parse_rule("count:int[1, 10)");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
 
         // 错误案例：等于 10，但在开区间下是不允许的
         map.insert("count".to_string(), Value::Int(10));
@@ -220,7 +220,7 @@ mod range_error_tests {
             }],
         });
 
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("count".to_string(), Value::Int(5));
         let mut data = Value::Object(map);
 
@@ -233,18 +233,21 @@ mod range_error_tests {
         );
     }
 
-    // --- 1. 类型不匹配 (Not float) ---
+    // --- 1. 类型不匹配 (expected float) ---
     #[test]
     fn test_err_float_type_mismatch() {
         let rule = parse_rule("price:float");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         // 传入的是布尔值，不是浮点数
         map.insert("price".to_string(), Value::Bool(true));
         let mut data = Value::Object(map);
 
         let res = validate_field(&mut data, &rule);
         assert!(res.is_err());
-        assert!(res.unwrap_err().to_string().contains("Not float"));
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "price: expected float, got bool true"
+        );
     }
 
     // --- 2. 最小值越界 (Min boundary) ---
@@ -252,7 +255,7 @@ mod range_error_tests {
     fn test_err_float_min_out_of_bounds() {
         // 规则：(0.5, 10.0]
         let rule = parse_rule("weight:float(0.5, 10.0]");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
 
         // 错误：等于 0.5，但由于是 '(' 开区间，要求必须 > 0.5
         map.insert("weight".to_string(), Value::Float(0.5));
@@ -267,7 +270,7 @@ mod range_error_tests {
     #[test]
     fn test_err_float_max_out_of_bounds() {
         let rule = parse_rule("weight:float[0.0, 5.5]");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
 
         // 错误：5.51 超过了 5.5
         map.insert("weight".to_string(), Value::Float(5.51));
@@ -292,7 +295,7 @@ mod range_error_tests {
             }],
         });
 
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("val".to_string(), Value::Float(5.0));
         let mut data = Value::Object(map);
 
@@ -319,7 +322,7 @@ mod range_error_tests {
             }],
         });
 
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("val".to_string(), Value::Float(5.0));
         let mut data = Value::Object(map);
 
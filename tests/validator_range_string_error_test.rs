@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod string_range_error_tests {
-    use std::collections::HashMap;
+    use zz_validator::ast::ObjectMap;
 
     use zz_validator::{
         ast::{Constraint, Constraints, FieldRule, Value},
@@ -20,7 +20,7 @@ mod string_range_error_tests {
     fn test_err_string_length_min() {
         // 规则：长度必须在 [3, 10]
         let rule = parse_rule("username:string[3, 10]");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("username".to_string(), Value::String("ab".into())); // 长度为 2
         let mut data = Value::Object(map);
 
@@ -34,7 +34,7 @@ mod string_range_error_tests {
     fn test_err_string_length_max() {
         // 规则：长度必须在 [1, 5]
         let rule = parse_rule("tag:string[1, 5]");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("tag".to_string(), Value::String("abcdef".into())); // 长度为 6
         let mut data = Value::Object(map);
 
@@ -48,7 +48,7 @@ mod string_range_error_tests {
     fn test_err_string_length_exclusive() {
         // 规则：长度在 (3, 5] 之间，即长度必须 > 3
         let rule = parse_rule("code:string(3, 5]");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("code".to_string(), Value::String("abc".into())); // 长度刚好为 3，本应报错
         let mut data = Value::Object(map);
 
@@ -72,7 +72,7 @@ mod string_range_error_tests {
             }],
         });
 
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("name".to_string(), Value::String("test".into()));
         let mut data = Value::Object(map);
 
@@ -100,7 +100,7 @@ mod string_range_error_tests {
             }],
         });
 
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("note".to_string(), Value::String("hello".into()));
         let mut data = Value::Object(map);
 
@@ -118,7 +118,7 @@ mod string_range_error_tests {
     fn test_err_string_length_max_inclusive() {
         // 规则：长度必须在 [1, 3] 之间
         let rule = parse_rule("code:string[1, 3]");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         // 错误案例：长度为 4，超过 3
         map.insert("code".to_string(), Value::String("abcd".into()));
         let mut data = Value::Object(map);
@@ -133,7 +133,7 @@ mod string_range_error_tests {
     fn test_err_string_length_max_exclusive() {
         // 规则：长度必须在 [1, 3) 之间，即长度必须 < 3（实际只能是 1 或 2）
         let rule = parse_rule("code:string[1, 3)");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         // 错误案例：长度为 3，但在开区间上限处是不允许的
         map.insert("code".to_string(), Value::String("abc".into()));
         let mut data = Value::Object(map);
@@ -158,7 +158,7 @@ mod string_range_error_tests {
             }],
         });
 
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("note".to_string(), Value::String("hello!".into()));
         let mut data = Value::Object(map);
 
@@ -186,7 +186,7 @@ mod string_range_error_tests {
             }],
         });
 
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert(
             "comment".to_string(),
             Value::String("This is a test".into()),
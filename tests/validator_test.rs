@@ -102,7 +102,7 @@ mod tests {
         let err = validate_object(&mut bad_obj, &rules).unwrap_err();
         println!("err = {:?}", err);
         assert!(
-            err.to_string().contains("age value"),
+            err.to_string().contains("age: expected int"),
             "Expected age type error, got {}",
             err
         );
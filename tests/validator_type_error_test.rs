@@ -1,10 +1,12 @@
 #[cfg(test)]
 mod validate_type_error_tests {
-    use std::collections::HashMap;
+    use zz_validator::ast::ObjectMap;
 
     use zz_validator::{
         ast::{FieldType, Value},
-        validator::validate_type,
+        json::from_json_str,
+        parser::Parser,
+        validator::{ValidationError, validate_object, validate_type},
     };
 
     // 辅助工具：快速构造 Value 进行测试
@@ -18,26 +20,32 @@ mod validate_type_error_tests {
         // String 错误
         assert_eq!(
             check_type_err(Value::Int(1), FieldType::String),
-            "Not string"
+            "expected string, got int 1"
         );
         // Int 错误
         assert_eq!(
             check_type_err(Value::String("1".into()), FieldType::Int),
-            "Not int"
+            "expected int, got string \"1\""
         );
         // Float 错误
-        assert_eq!(check_type_err(Value::Int(1), FieldType::Float), "Not float");
+        assert_eq!(
+            check_type_err(Value::Int(1), FieldType::Float),
+            "expected float, got int 1"
+        );
         // Bool 错误
-        assert_eq!(check_type_err(Value::Int(1), FieldType::Bool), "Not bool");
+        assert_eq!(
+            check_type_err(Value::Int(1), FieldType::Bool),
+            "expected bool, got int 1"
+        );
         // Object 错误
         assert_eq!(
             check_type_err(Value::Array(vec![]), FieldType::Object),
-            "Not object"
+            "expected object, got array []"
         );
         // Array 错误
         assert_eq!(
-            check_type_err(Value::Object(HashMap::new()), FieldType::Array),
-            "Not array"
+            check_type_err(Value::Object(ObjectMap::new()), FieldType::Array),
+            "expected array, got object {}"
         );
     }
 
@@ -47,17 +55,17 @@ mod validate_type_error_tests {
         // Email 必须是字符串
         assert_eq!(
             check_type_err(Value::Int(123), FieldType::Email),
-            "Not string for email"
+            "expected email, got int 123"
         );
         // Timestamp 必须是整数
         assert_eq!(
             check_type_err(Value::String("2024".into()), FieldType::Timestamp),
-            "Not number for timestamp"
+            "expected timestamp, got string \"2024\""
         );
         // Password/Token 必须是字符串
         assert_eq!(
             check_type_err(Value::Bool(true), FieldType::Password),
-            "Not string for Password"
+            "expected password, got bool true"
         );
     }
 
@@ -120,4 +128,36 @@ mod validate_type_error_tests {
                 .contains("Invalid base64")
         );
     }
+    // --- 4. 结构化的类型错误：声明的类型与值的实际种类 ---
+    #[test]
+    fn test_structured_type_mismatch() {
+        let err = validate_type(&Value::String("abc".into()), &FieldType::Int).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::TypeMismatch {
+                field: String::new(),
+                value: "\"abc\"".into(),
+                expected: FieldType::Int,
+                actual: "string",
+                reason: None,
+            }
+        );
+        assert_eq!(err.params()[3], ("actual", "string".to_string()));
+
+        // 种类相符、格式不对时带上原因
+        let err = validate_type(&Value::String("x".into()), &FieldType::Email).unwrap_err();
+        assert!(matches!(
+            &err,
+            ValidationError::TypeMismatch { actual: "string", reason: Some(r), .. }
+                if r == "Invalid email: x"
+        ));
+
+        // 字段中的错误带有完整路径
+        let rules = Parser::parse_rules("(user:object(age:int))").unwrap();
+        let mut value = from_json_str(r#"{"user":{"age":[1]}}"#).unwrap();
+        let err = validate_object(&mut value, &rules).unwrap_err();
+        assert_eq!(err.to_string(), "user.age: expected int, got array [1]");
+        assert_eq!(err.field(), Some("user.age"));
+        assert_eq!(err.value(), Some("[1]"));
+    }
 }
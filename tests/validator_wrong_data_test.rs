@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod negative_tests {
-    use std::collections::HashMap;
+    use zz_validator::ast::ObjectMap;
 
     use zz_validator::{
         ast::{FieldRule, Value},
@@ -20,7 +20,7 @@ mod negative_tests {
     #[test]
     fn test_err_missing_required() {
         let rule = parse_rule("name:string");
-        let mut data = Value::Object(HashMap::new()); // 数据中没有 "name"
+        let mut data = Value::Object(ObjectMap::new()); // 数据中没有 "name"
         let res = validate_field(&mut data, &rule);
         assert!(res.is_err());
         assert!(
@@ -34,7 +34,7 @@ mod negative_tests {
     #[test]
     fn test_err_union_mismatch() {
         let rule = parse_rule("id:int|float");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("id".to_string(), Value::String("abc".into())); // 既不是 int 也不是 float
         let mut data = Value::Object(map);
         let res = validate_field(&mut data, &rule);
@@ -50,7 +50,7 @@ mod negative_tests {
     #[test]
     fn test_err_type_mismatch() {
         let rule = parse_rule("age:int");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         // 模拟传入了字符串类型的 "25"，但规则要求是 int
         map.insert("age".to_string(), Value::String("25".into()));
         let mut data = Value::Object(map);
@@ -60,14 +60,14 @@ mod negative_tests {
         assert!(res.is_err());
         let err_msg = res.unwrap_err().to_string();
 
-        // 真实输出应匹配 Display 实现: "age value String("25"): expected Int, found Not int"
-        assert!(err_msg.contains("age value String(\"25\"): expected Int, found Not int"));
+        // 同时给出声明的类型与值的实际种类
+        assert_eq!(err_msg, "age: expected int, got string \"25\"");
     }
     // --- 4. 枚举值不存在 (Enum mismatch) ---
     #[test]
     fn test_err_enum_out_of_range() {
         let rule = parse_rule("color:string enum(red, blue)");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("color".to_string(), Value::String("green".into()));
         let mut data = Value::Object(map);
         let res = validate_field(&mut data, &rule);
@@ -79,7 +79,7 @@ mod negative_tests {
     #[test]
     fn test_err_int_range() {
         let rule = parse_rule("score:int[0, 100]");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("score".to_string(), Value::Int(101));
         let mut data = Value::Object(map);
         let res = validate_field(&mut data, &rule);
@@ -91,7 +91,7 @@ mod negative_tests {
     #[test]
     fn test_err_string_length() {
         let rule = parse_rule("pwd:string[6, 12]");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("pwd".to_string(), Value::String("123".into())); // 太短
         let mut data = Value::Object(map);
         let res = validate_field(&mut data, &rule);
@@ -103,7 +103,7 @@ mod negative_tests {
     #[test]
     fn test_err_regex_mismatch() {
         let rule = parse_rule(r#"code:string regex("^\d{3}$")"#);
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("code".to_string(), Value::String("12a".into()));
         let mut data = Value::Object(map);
         let res = validate_field(&mut data, &rule);
@@ -116,7 +116,7 @@ mod negative_tests {
     fn test_err_array_sub_rule() {
         // 规则：tags 是一个字符串数组，每个字符串长度必须 [2, 5]
         let rule = parse_rule("tags:array<string[2, 5]>");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert(
             "tags".to_string(),
             Value::Array(vec![
@@ -134,14 +134,14 @@ mod negative_tests {
     fn test_err_semantic_types() {
         // 测试 Email 格式
         let rule_email = parse_rule("email:email");
-        let mut map = HashMap::new();
+        let mut map = ObjectMap::new();
         map.insert("email".to_string(), Value::String("invalid-email".into()));
         let mut data = Value::Object(map);
         assert!(validate_field(&mut data, &rule_email).is_err());
 
         // 测试 IP 格式
         let rule_ip = parse_rule("ip:ip");
-        let mut map_ip = HashMap::new();
+        let mut map_ip = ObjectMap::new();
         map_ip.insert("ip".to_string(), Value::String("256.256.256.256".into()));
         let mut data_ip = Value::Object(map_ip);
         assert!(validate_field(&mut data_ip, &rule_ip).is_err());